@@ -1,18 +1,989 @@
-// src/config.rs v2
+// src/config.rs v4
 //! Configuration management with platform-specific storage
 
 use crate::error::{Result, GpsError};
+use crate::gps::{AltitudeSource, CoordinateFormat, GpsData, NorthReference, SnrThresholds, SpeedUnit, COORDINATE_DECIMALS_RANGE, DEFAULT_COORDINATE_DECIMALS, DEFAULT_MIN_SATELLITES_USED, METERS_PER_FOOT};
+use crate::waypoint::WaypointFormat;
+use chrono::{DateTime, DurationRound, FixedOffset, Local, Offset, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Serial baud rates accepted by `GpsConfig::validate`
+pub const STANDARD_BAUD_RATES: [u32; 11] = [1200, 2400, 4800, 9600, 19200, 38400, 57600, 115200, 230400, 460800, 921600];
+
+/// Seconds between redraws while the window is unfocused, unless configured otherwise
+pub const DEFAULT_UNFOCUSED_REPAINT_SECS: u32 = 5;
+
+/// Milliseconds between stopping and starting again on Restart, unless configured otherwise
+pub const DEFAULT_RESTART_DELAY_MS: u32 = 500;
+
+/// Seconds a satellite stays listed after its last GSV/SKY report, unless configured otherwise
+pub const DEFAULT_SATELLITE_MAX_AGE_SECS: u64 = 10;
+
+/// Source types this build can connect to
+#[cfg(windows)]
+pub const SOURCE_TYPES: [&str; 3] = ["serial", "gpsd", "windows"];
+#[cfg(all(target_os = "linux", feature = "geoclue"))]
+pub const SOURCE_TYPES: [&str; 3] = ["serial", "gpsd", "geoclue"];
+#[cfg(not(any(windows, all(target_os = "linux", feature = "geoclue"))))]
+pub const SOURCE_TYPES: [&str; 2] = ["serial", "gpsd"];
+
+/// Appearance of the markers drawn on the map
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MarkerStyle {
+    pub position_color: [u8; 3],  // RGB
+    pub position_size: f32,       // radius in pixels
+    pub waypoint_color: [u8; 3],  // RGB, used when a waypoint has no color of its own
+    pub waypoint_size: f32,       // radius in pixels
+    pub track_color: [u8; 3],     // RGB
+    pub active_track_color: [u8; 3],  // RGB, the track being recorded
+    pub track_width: f32,         // line width in pixels
+    pub track_opacity: f32,       // 0.0 (invisible) to 1.0 (opaque)
+}
+
+impl Default for MarkerStyle {
+    fn default() -> Self {
+        Self {
+            position_color: [0, 122, 255],
+            position_size: 8.0,
+            waypoint_color: [255, 0, 0],
+            waypoint_size: 6.0,
+            track_color: [255, 0, 0],
+            active_track_color: [0, 220, 0],
+            track_width: 3.0,
+            track_opacity: 1.0,
+        }
+    }
+}
+
+/// Map window behaviour
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MapOptions {
+    pub auto_follow: bool,       // Re-enable follow mode after panning
+    pub auto_follow_secs: u64,   // Idle time before follow mode comes back
+    pub show_breadcrumb: bool,   // Draw a fading trail of recent positions
+    pub breadcrumb_length: usize,  // Positions kept in the trail
+    pub prefetch_ahead: bool,    // Download tiles where the vehicle is heading while following
+    pub prefetch_secs: u64,      // How far ahead to look, in seconds at the current speed
+    pub label_min_zoom: u8,      // Waypoint names are hidden below this zoom level
+    pub warm_cache_on_open: bool,  // Load the opening view's cached tiles into memory
+    pub split_antimeridian: bool,  // Break tracks at ±180° instead of drawing across the map
+    pub heading_smoothing_secs: f64,  // Time constant of the heading low-pass filter (0 = raw course)
+    pub heading_min_speed: f64,       // km/h; below this the heading is ignored
+    pub follow_offset: f32,      // While following, the marker sits this fraction of the map height below center
+    pub follow_dead_zone_px: f32,  // While following, smaller moves of the position don't recenter (0 = every fix)
+    pub speed_zoom: bool,        // While following, zoom out as speed rises
+    pub speed_zoom_steps: Vec<SpeedZoomStep>,  // Ascending by speed
+    pub memory_tiles: usize,          // Tiles kept in memory; lower it on constrained devices
+    pub concurrent_downloads: usize,  // Tiles downloaded at once
+}
+
+/// Zoom level used by speed-adaptive zoom from `min_speed` upwards
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SpeedZoomStep {
+    pub min_speed: f64,  // km/h
+    pub zoom: u8,
+}
+
+impl SpeedZoomStep {
+    /// Town, road and highway speeds
+    pub fn defaults() -> Vec<SpeedZoomStep> {
+        vec![
+            SpeedZoomStep { min_speed: 0.0, zoom: 16 },
+            SpeedZoomStep { min_speed: 20.0, zoom: 14 },
+            SpeedZoomStep { min_speed: 60.0, zoom: 12 },
+        ]
+    }
+}
+
+impl Default for MapOptions {
+    fn default() -> Self {
+        Self {
+            auto_follow: false,
+            auto_follow_secs: 10,
+            show_breadcrumb: true,
+            breadcrumb_length: 500,
+            prefetch_ahead: true,
+            prefetch_secs: 60,
+            label_min_zoom: 1,
+            warm_cache_on_open: true,
+            split_antimeridian: true,
+            heading_smoothing_secs: 1.5,
+            heading_min_speed: 3.0,
+            follow_offset: 0.15,
+            follow_dead_zone_px: 8.0,
+            speed_zoom: false,
+            speed_zoom_steps: SpeedZoomStep::defaults(),
+            memory_tiles: crate::map::DEFAULT_MEMORY_TILES,
+            concurrent_downloads: crate::map::DEFAULT_CONCURRENT_DOWNLOADS,
+        }
+    }
+}
+
+/// Parity bit of a serial line
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SerialParity {
+    #[default]
+    None,
+    Odd,
+    Even,
+}
+
+impl SerialParity {
+    pub const ALL: [SerialParity; 3] = [SerialParity::None, SerialParity::Odd, SerialParity::Even];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SerialParity::None => "None",
+            SerialParity::Odd => "Odd",
+            SerialParity::Even => "Even",
+        }
+    }
+}
+
+/// Flow control of a serial line
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SerialFlowControl {
+    #[default]
+    None,
+    Software,  // XON/XOFF
+    Hardware,  // RTS/CTS
+}
+
+impl SerialFlowControl {
+    pub const ALL: [SerialFlowControl; 3] = [SerialFlowControl::None, SerialFlowControl::Software, SerialFlowControl::Hardware];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SerialFlowControl::None => "None",
+            SerialFlowControl::Software => "Software (XON/XOFF)",
+            SerialFlowControl::Hardware => "Hardware (RTS/CTS)",
+        }
+    }
+}
+
+/// Framing, flow control and control lines for the serial port. The default
+/// 8N1 without flow control suits NMEA receivers; some modules need DTR or
+/// RTS set to power up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SerialLine {
+    pub data_bits: u8,  // 5 to 8
+    pub parity: SerialParity,
+    pub stop_bits: u8,  // 1 or 2
+    pub flow_control: SerialFlowControl,
+    pub dtr: Option<bool>,  // Set when the port opens; None leaves the OS default
+    pub rts: Option<bool>,
+}
+
+impl Default for SerialLine {
+    fn default() -> Self {
+        Self {
+            data_bits: 8,
+            parity: SerialParity::None,
+            stop_bits: 1,
+            flow_control: SerialFlowControl::None,
+            dtr: None,
+            rts: None,
+        }
+    }
+}
+
+impl SerialLine {
+    /// Framing in the usual short form, e.g. "8N1"
+    pub fn framing(&self) -> String {
+        let parity = match self.parity {
+            SerialParity::None => 'N',
+            SerialParity::Odd => 'O',
+            SerialParity::Even => 'E',
+        };
+        format!("{}{}{}", self.data_bits, parity, self.stop_bits)
+    }
+
+    pub fn is_valid(&self) -> bool {
+        (5..=8).contains(&self.data_bits) && (1..=2).contains(&self.stop_bits)
+    }
+}
+
+/// OBD-II adapter connection, used when built with the "obd" feature
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ObdConfig {
+    pub enabled: bool,
+    pub port: String,
+    pub baudrate: u32,
+    pub poll_interval_ms: u64,
+}
+
+impl Default for ObdConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: String::new(),
+            baudrate: 38400,  // Common ELM327 default
+            poll_interval_ms: 500,
+        }
+    }
+}
+
+/// HTTP endpoint serving the latest data as JSON, used when built with the "status-server" feature
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StatusEndpoint {
+    pub enabled: bool,
+    pub address: String,  // "ip:port"; 0.0.0.0 to allow other machines
+}
+
+impl Default for StatusEndpoint {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            address: "127.0.0.1:8947".to_string(),
+        }
+    }
+}
+
+/// Warning shown when no valid fix has been seen for a while
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NoFixWarning {
+    pub enabled: bool,
+    pub threshold_secs: u64,
+    pub beep: bool,
+}
+
+impl Default for NoFixWarning {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            threshold_secs: 30,
+            beep: false,
+        }
+    }
+}
+
+/// Simplifying tracks as they are imported, for dense files from other devices
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ImportThinning {
+    pub enabled: bool,
+    pub tolerance_m: f64,  // Points closer than this to the simplified line are dropped
+}
+
+impl Default for ImportThinning {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            tolerance_m: 5.0,
+        }
+    }
+}
+
+impl ImportThinning {
+    /// Tolerance to pass to the importer, None when thinning is off
+    pub fn tolerance(&self) -> Option<f64> {
+        self.enabled.then_some(self.tolerance_m)
+    }
+}
+
+/// Restarting the connection when data stops arriving, e.g. because the
+/// read task ended while the machine was asleep
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StallReconnect {
+    pub enabled: bool,
+    pub threshold_secs: u64,
+}
+
+impl Default for StallReconnect {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            threshold_secs: 60,
+        }
+    }
+}
+
+impl StallReconnect {
+    /// Whether a connection that has been silent for `quiet` should be restarted
+    pub fn is_stalled(&self, quiet: std::time::Duration) -> bool {
+        self.enabled && quiet.as_secs() >= self.threshold_secs.max(1)
+    }
+}
+
+/// Analog speedometer panel
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Speedometer {
+    pub enabled: bool,
+    pub max_speed: f64,  // Top of the scale, in the display speed unit
+}
+
+impl Default for Speedometer {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_speed: 160.0,
+        }
+    }
+}
+
+/// Keep showing the last good fix in the waypoint dialog through brief
+/// dropouts, so a waypoint can still be saved from it while it's recent
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WaypointFixHold {
+    pub enabled: bool,
+    pub max_age_secs: u32,  // Older held fixes are shown but can't be saved
+}
+
+impl Default for WaypointFixHold {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_age_secs: 10,
+        }
+    }
+}
+
+impl WaypointFixHold {
+    /// Whether a waypoint may be saved from a fix held for `age`
+    pub fn allows(&self, age: std::time::Duration) -> bool {
+        age.as_secs_f64() <= f64::from(self.max_age_secs)
+    }
+}
+
+/// Reference grid drawn on the sky plot, and how its satellites are sized
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SkyPlotGrid {
+    pub ring_interval_deg: u32,  // Elevation between rings, one of RING_INTERVALS
+    pub azimuth_labels: bool,    // Spokes and bearing labels every 30°
+    pub size_by_snr: bool,       // Stronger satellites drawn bigger, rather than used ones
+    pub tint_unused: bool,       // Unused satellites in a dimmed constellation color instead of gray
+}
+
+impl Default for SkyPlotGrid {
+    fn default() -> Self {
+        Self {
+            ring_interval_deg: 30,
+            azimuth_labels: false,
+            size_by_snr: false,
+            tint_unused: false,
+        }
+    }
+}
+
+impl SkyPlotGrid {
+    /// Intervals that divide the sky evenly from horizon to zenith
+    pub const RING_INTERVALS: [u32; 4] = [10, 15, 30, 45];
+
+    /// Elevations of the rings between the horizon and the zenith, ascending
+    pub fn rings(&self) -> Vec<u32> {
+        let interval = self.ring_interval_deg.max(1);
+        (1..).map(|i| i * interval).take_while(|&elevation| elevation < 90).collect()
+    }
+}
+
+/// One corner of a horizon mask: obstructions toward `azimuth_deg` reach up to `min_elevation_deg`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct HorizonPoint {
+    pub azimuth_deg: f32,
+    pub min_elevation_deg: f32,
+}
+
+/// Azimuth-dependent horizon (buildings, terrain) shaded on the sky plot.
+/// Between points the elevation is interpolated linearly, wrapping through north.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HorizonMask {
+    pub enabled: bool,
+    pub points: Vec<HorizonPoint>,  // Sorted by azimuth once normalized
+}
+
+impl HorizonMask {
+    /// Obstruction height toward `azimuth`, 0 when disabled or without points
+    pub fn min_elevation_at(&self, azimuth: f32) -> f32 {
+        if !self.enabled || self.points.is_empty() {
+            return 0.0;
+        }
+        let azimuth = azimuth.rem_euclid(360.0);
+        let after = self.points.iter().position(|p| p.azimuth_deg >= azimuth).unwrap_or(0);
+        let before = if after == 0 { self.points.len() - 1 } else { after - 1 };
+        let (from, to) = (self.points[before], self.points[after]);
+
+        let span = (to.azimuth_deg - from.azimuth_deg).rem_euclid(360.0);
+        if span == 0.0 {
+            return to.min_elevation_deg;
+        }
+        let t = (azimuth - from.azimuth_deg).rem_euclid(360.0) / span;
+        from.min_elevation_deg + (to.min_elevation_deg - from.min_elevation_deg) * t
+    }
+
+    /// Whether a satellite at this position is behind the mask
+    pub fn hides(&self, elevation: f32, azimuth: f32) -> bool {
+        elevation < self.min_elevation_at(azimuth)
+    }
+}
+
+/// Text a position can be copied as, e.g. a map link; `{lat}` and `{lon}`
+/// are replaced by the coordinates in decimal degrees
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CopyTemplate {
+    pub name: String,
+    pub template: String,
+}
+
+impl CopyTemplate {
+    pub fn new(name: &str, template: &str) -> Self {
+        Self { name: name.to_string(), template: template.to_string() }
+    }
+
+    /// Plain pair, Google Maps, OpenStreetMap and a geo: URI
+    pub fn defaults() -> Vec<CopyTemplate> {
+        vec![
+            CopyTemplate::new("Latitude, longitude", "{lat}, {lon}"),
+            CopyTemplate::new("Google Maps", "https://www.google.com/maps?q={lat},{lon}"),
+            CopyTemplate::new("OpenStreetMap", "https://www.openstreetmap.org/?mlat={lat}&mlon={lon}#map=17/{lat}/{lon}"),
+            CopyTemplate::new("geo: URI", "geo:{lat},{lon}"),
+        ]
+    }
+
+    /// The template with the position filled in to `decimals` places
+    pub fn apply(&self, lat: f64, lon: f64, decimals: usize) -> String {
+        let decimals = GpsData::coordinate_decimals(decimals);
+        self.template
+            .replace("{lat}", &format!("{:.*}", decimals, lat))
+            .replace("{lon}", &format!("{:.*}", decimals, lon))
+    }
+}
+
+/// Minimum fix quality required before a waypoint can be saved
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WaypointQualityGate {
+    pub enabled: bool,
+    pub max_hdop: f64,
+    pub require_3d: bool,
+    pub min_satellites: usize,
+}
+
+impl Default for WaypointQualityGate {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_hdop: 2.0,
+            require_3d: true,
+            min_satellites: 5,
+        }
+    }
+}
+
+/// How often a rotating track log starts a new file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RotationInterval {
+    Hourly,
+    #[default]
+    Daily,
+}
+
+impl RotationInterval {
+    pub const ALL: [RotationInterval; 2] = [RotationInterval::Hourly, RotationInterval::Daily];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            RotationInterval::Hourly => "Hourly",
+            RotationInterval::Daily => "Daily",
+        }
+    }
+
+    fn length(&self) -> chrono::Duration {
+        match self {
+            RotationInterval::Hourly => chrono::Duration::hours(1),
+            RotationInterval::Daily => chrono::Duration::days(1),
+        }
+    }
+
+    /// Start (UTC) of the period containing `time`
+    pub fn period_start(&self, time: &DateTime<Utc>) -> DateTime<Utc> {
+        time.duration_trunc(self.length()).unwrap_or(*time)
+    }
+
+    /// GPX file name for the period starting at `start`, e.g. "track_2024-05-01_13.gpx"
+    pub fn file_name(&self, start: &DateTime<Utc>) -> String {
+        match self {
+            RotationInterval::Hourly => format!("track_{}.gpx", start.format("%Y-%m-%d_%H")),
+            RotationInterval::Daily => format!("track_{}.gpx", start.format("%Y-%m-%d")),
+        }
+    }
+}
+
+/// Periodic export of the recording in progress to dated GPX files
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TrackRotation {
+    pub enabled: bool,
+    pub interval: RotationInterval,
+    pub output_dir: String,  // Empty for the default tracks directory
+}
+
+impl TrackRotation {
+    /// Directory rotated files are written to
+    pub fn output_path(&self) -> PathBuf {
+        if !self.output_dir.trim().is_empty() {
+            return PathBuf::from(self.output_dir.trim());
+        }
+        let mut path = dirs::data_local_dir().unwrap_or_else(|| PathBuf::from("."));
+        path.push("gps-monitor");
+        path.push("tracks");
+        path
+    }
+}
+
+/// Continuous CSV log of the decoded fix, one row per position update
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DataLog {
+    pub enabled: bool,
+    pub output_dir: String,  // Empty for the default logs directory
+    pub max_size_mb: u32,    // Start a new file past this size, 0 for no limit
+    pub interval: Option<RotationInterval>,  // Also start a new file each period
+}
+
+impl Default for DataLog {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            output_dir: String::new(),
+            max_size_mb: 10,
+            interval: None,
+        }
+    }
+}
+
+impl DataLog {
+    /// Directory log files are written to
+    pub fn output_path(&self) -> PathBuf {
+        if !self.output_dir.trim().is_empty() {
+            return PathBuf::from(self.output_dir.trim());
+        }
+        let mut path = dirs::data_local_dir().unwrap_or_else(|| PathBuf::from("."));
+        path.push("gps-monitor");
+        path.push("logs");
+        path
+    }
+
+    /// Size limit in bytes, if any
+    pub fn max_size_bytes(&self) -> Option<u64> {
+        (self.max_size_mb > 0).then(|| u64::from(self.max_size_mb) * 1024 * 1024)
+    }
+}
+
+/// Periodic saving of the waypoints, tracks and routes, read back on the next start
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SessionAutosave {
+    pub enabled: bool,
+    pub interval_secs: u64,
+}
+
+impl Default for SessionAutosave {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_secs: 120,
+        }
+    }
+}
+
+impl SessionAutosave {
+    /// Whether a save is due `elapsed` after the last one
+    pub fn is_due(&self, elapsed: std::time::Duration) -> bool {
+        self.enabled && elapsed.as_secs() >= self.interval_secs.max(10)
+    }
+}
+
+/// Units used when displaying distances
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DistanceUnits {
+    #[default]
+    Metric,
+    Imperial,
+    Nautical,
+}
+
+impl DistanceUnits {
+    pub const ALL: [DistanceUnits; 3] = [DistanceUnits::Metric, DistanceUnits::Imperial, DistanceUnits::Nautical];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            DistanceUnits::Metric => "Metric (km)",
+            DistanceUnits::Imperial => "Imperial (mi)",
+            DistanceUnits::Nautical => "Nautical (nm)",
+        }
+    }
+
+    /// Format a distance in meters, switching to the small unit below one large unit
+    pub fn format_distance(&self, meters: f64) -> String {
+        match self {
+            DistanceUnits::Metric if meters < 1000.0 => format!("{:.0} m", meters),
+            DistanceUnits::Metric => format!("{:.2} km", meters / 1000.0),
+            DistanceUnits::Imperial if meters < 1609.344 => format!("{:.0} ft", self.altitude_from_meters(meters)),
+            DistanceUnits::Imperial => format!("{:.2} mi", meters / 1609.344),
+            DistanceUnits::Nautical => format!("{:.2} nm", meters / 1852.0),
+        }
+    }
+
+    /// Altitude unit matching the distance unit. Altitudes are stored, and
+    /// written to GPX/KML/GeoJSON/FIT, in meters as those formats require;
+    /// only displays, reports and CSV use this unit.
+    pub fn altitude_label(&self) -> &'static str {
+        match self {
+            DistanceUnits::Imperial => "ft",
+            DistanceUnits::Metric | DistanceUnits::Nautical => "m",
+        }
+    }
+
+    /// Convert an altitude in meters to `altitude_label` units
+    pub fn altitude_from_meters(&self, meters: f64) -> f64 {
+        match self {
+            DistanceUnits::Imperial => meters / METERS_PER_FOOT,
+            DistanceUnits::Metric | DistanceUnits::Nautical => meters,
+        }
+    }
+
+    /// Format an altitude (or climb) given in meters, with its unit
+    pub fn format_altitude(&self, meters: f64, decimals: usize) -> String {
+        format!("{:.*} {}", decimals, self.altitude_from_meters(meters), self.altitude_label())
+    }
+
+    /// Speed unit matching the distance unit
+    pub fn speed_unit(&self) -> DisplaySpeedUnit {
+        match self {
+            DistanceUnits::Metric => DisplaySpeedUnit::KilometersPerHour,
+            DistanceUnits::Imperial => DisplaySpeedUnit::MilesPerHour,
+            DistanceUnits::Nautical => DisplaySpeedUnit::Knots,
+        }
+    }
+
+    /// Label of `speed_unit`
+    pub fn speed_label(&self) -> &'static str {
+        self.speed_unit().label()
+    }
+
+    /// Convert a speed in km/h to `speed_label` units
+    pub fn speed_from_kmh(&self, kmh: f64) -> f64 {
+        self.speed_unit().from_kmh(kmh)
+    }
+}
+
+/// Unit used when displaying speeds. Speeds are stored, recorded and written
+/// to GPX/KML/reports in km/h; CSV exports follow the distance units.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DisplaySpeedUnit {
+    KilometersPerHour,
+    MilesPerHour,
+    Knots,
+    MetersPerSecond,
+}
+
+impl DisplaySpeedUnit {
+    pub const ALL: [DisplaySpeedUnit; 4] = [
+        DisplaySpeedUnit::KilometersPerHour,
+        DisplaySpeedUnit::MilesPerHour,
+        DisplaySpeedUnit::Knots,
+        DisplaySpeedUnit::MetersPerSecond,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            DisplaySpeedUnit::KilometersPerHour => "km/h",
+            DisplaySpeedUnit::MilesPerHour => "mph",
+            DisplaySpeedUnit::Knots => "kn",
+            DisplaySpeedUnit::MetersPerSecond => "m/s",
+        }
+    }
+
+    /// Convert a speed in km/h to this unit
+    pub fn from_kmh(&self, kmh: f64) -> f64 {
+        match self {
+            DisplaySpeedUnit::KilometersPerHour => kmh,
+            DisplaySpeedUnit::MilesPerHour => kmh / 1.609344,
+            DisplaySpeedUnit::Knots => kmh / 1.852,
+            DisplaySpeedUnit::MetersPerSecond => kmh / 3.6,
+        }
+    }
+
+    /// Format a speed given in km/h, with the unit
+    pub fn format_speed(&self, kmh: f64, decimals: usize) -> String {
+        format!("{:.*} {}", decimals, self.from_kmh(kmh), self.label())
+    }
+}
+
+/// Colors used for constellations and signal quality
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorPalette {
+    #[default]
+    Standard,
+    ColorblindSafe,     // Okabe-Ito colors, distinguishable with red-green color blindness
+    HighContrast,
+}
+
+impl ColorPalette {
+    pub const ALL: [ColorPalette; 3] = [ColorPalette::Standard, ColorPalette::ColorblindSafe, ColorPalette::HighContrast];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ColorPalette::Standard => "Standard",
+            ColorPalette::ColorblindSafe => "Colorblind-safe",
+            ColorPalette::HighContrast => "High contrast",
+        }
+    }
+}
+
+/// Timestamp style for CSV exports
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CsvTimestamp {
+    #[default]
+    Rfc3339,
+    Epoch,  // Whole seconds since 1970-01-01 UTC
+    Local,  // "YYYY-MM-DD HH:MM:SS" in the system time zone
+}
+
+impl CsvTimestamp {
+    pub const ALL: [CsvTimestamp; 3] = [CsvTimestamp::Rfc3339, CsvTimestamp::Epoch, CsvTimestamp::Local];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CsvTimestamp::Rfc3339 => "RFC 3339 (UTC)",
+            CsvTimestamp::Epoch => "Epoch seconds",
+            CsvTimestamp::Local => "Local date and time",
+        }
+    }
+
+    pub fn format(&self, time: &DateTime<Utc>) -> String {
+        match self {
+            CsvTimestamp::Rfc3339 => time.to_rfc3339(),
+            CsvTimestamp::Epoch => time.timestamp().to_string(),
+            CsvTimestamp::Local => time.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S").to_string(),
+        }
+    }
+}
+
+/// Most export files remembered for quick re-export
+pub const MAX_RECENT_EXPORTS: usize = 5;
+
+/// A file written from the waypoint dialog, offered for one-click re-export
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecentExport {
+    pub path: PathBuf,  // Including the extension
+    pub format: WaypointFormat,
+}
+
+impl RecentExport {
+    /// Put `export` first in `recent`, dropping an older entry for the same
+    /// file and the oldest entries beyond `MAX_RECENT_EXPORTS`
+    pub fn remember(recent: &mut Vec<RecentExport>, export: RecentExport) {
+        recent.retain(|r| r.path != export.path);
+        recent.insert(0, export);
+        recent.truncate(MAX_RECENT_EXPORTS);
+    }
+}
+
+/// Column formatting for CSV exports, for spreadsheets that expect other
+/// conventions. The defaults keep the original RFC 3339, metric, comma format.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CsvExport {
+    pub timestamp: CsvTimestamp,
+    pub units: DistanceUnits,  // Imperial: feet and mph; Nautical: meters and knots
+    pub decimal_comma: bool,   // "," as the decimal separator, with ";" between fields
+    pub segment_column: bool,  // Number each track point's segment, so pauses show
+}
+
+/// Time zone used when displaying timestamps. Stored and exported times stay in UTC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DisplayTimeZone {
+    #[default]
+    Utc,
+    Local,              // System time zone, including daylight saving changes
+    Fixed(i32),         // Minutes east of UTC
+}
+
+impl DisplayTimeZone {
+    pub fn name(&self) -> &'static str {
+        match self {
+            DisplayTimeZone::Utc => "UTC",
+            DisplayTimeZone::Local => "System local",
+            DisplayTimeZone::Fixed(_) => "Fixed offset",
+        }
+    }
+
+    /// Offset from UTC in effect at `time`
+    pub fn offset_at(&self, time: &DateTime<Utc>) -> FixedOffset {
+        match self {
+            DisplayTimeZone::Utc => Utc.fix(),
+            DisplayTimeZone::Local => time.with_timezone(&Local).offset().fix(),
+            DisplayTimeZone::Fixed(minutes) => FixedOffset::east_opt(minutes * 60).unwrap_or(Utc.fix()),
+        }
+    }
+
+    /// Format `time` in this zone with a chrono format string such as "%H:%M:%S"
+    pub fn format(&self, time: &DateTime<Utc>, fmt: &str) -> String {
+        time.with_timezone(&self.offset_at(time)).format(fmt).to_string()
+    }
+
+    /// Like `format`, followed by the zone ("UTC" or "UTC+02:00")
+    pub fn format_with_zone(&self, time: &DateTime<Utc>, fmt: &str) -> String {
+        let local = time.with_timezone(&self.offset_at(time));
+        if local.offset().local_minus_utc() == 0 {
+            format!("{} UTC", local.format(fmt))
+        } else {
+            format!("{} {}", local.format(fmt), local.format("UTC%:z"))
+        }
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GpsConfig {
     pub source_type: String,  // "serial", "gpsd", "windows"
     pub serial_port: Option<String>,
     pub serial_baudrate: Option<u32>,
+    #[serde(default)]
+    pub serial_read_timeout_ms: Option<u32>,
+    #[serde(default)]
+    pub serial_max_line_length: Option<u32>,
+    #[serde(default)]
+    pub rmc_speed_unit: SpeedUnit,  // Unit the serial receiver uses for RMC speed
+    #[serde(default)]
+    pub serial_line: SerialLine,
     pub gpsd_host: Option<String>,
     pub gpsd_port: Option<u16>,
     pub windows_accuracy: Option<u32>,
     pub windows_interval: Option<u64>,
+    #[serde(default = "default_auto_connect")]
+    pub auto_connect: bool,  // Connect to the source as soon as the app starts
+    #[serde(default = "default_restart_delay_ms")]
+    pub restart_delay_ms: u32,  // Time for the old connection to release the device on Restart
+    #[serde(default)]
+    pub stall_reconnect: StallReconnect,
+    #[serde(default)]
+    pub marker_style: MarkerStyle,
+    #[serde(default)]
+    pub map_options: MapOptions,
+    #[serde(default)]
+    pub no_fix_warning: NoFixWarning,
+    #[serde(default)]
+    pub import_thinning: ImportThinning,
+    #[serde(default)]
+    pub speedometer: Speedometer,
+    #[serde(default)]
+    pub waypoint_quality_gate: WaypointQualityGate,
+    #[serde(default)]
+    pub waypoint_fix_hold: WaypointFixHold,
+    #[serde(default)]
+    pub track_rotation: TrackRotation,
+    #[serde(default)]
+    pub data_log: DataLog,
+    #[serde(default)]
+    pub snr_thresholds: SnrThresholds,
+    #[serde(default)]
+    pub hidden_constellations: Vec<String>,  // Hidden from sky plot and satellite table
+    #[serde(default)]
+    pub used_satellites_only: bool,  // Sky plot and satellite table show only satellites used in the fix
+    #[serde(default)]
+    pub elevation_mask_deg: f32,  // Display-only: used satellites below this count as unused
+    #[serde(default)]
+    pub sky_plot_grid: SkyPlotGrid,
+    #[serde(default)]
+    pub horizon_mask: HorizonMask,
+    #[serde(default = "default_satellite_timeout_secs")]
+    pub satellite_timeout_secs: u64,  // Drop satellites not reported for this long, 0 to keep them
+    #[serde(default)]
+    pub expected_update_hz: u32,  // Receiver output rate; warn when fixes arrive much slower, 0 to skip
+    #[serde(default = "default_min_satellites_used")]
+    pub min_satellites_used: usize,  // Caution on fixes from fewer satellites, 0 to skip
+    #[serde(default = "default_unfocused_repaint_secs")]
+    pub unfocused_repaint_secs: u32,  // Redraw interval while the window is in the background
+    #[serde(default)]
+    pub distance_units: DistanceUnits,
+    #[serde(default)]
+    pub speed_unit: Option<DisplaySpeedUnit>,  // None follows the distance units
+    #[serde(default)]
+    pub color_palette: ColorPalette,
+    #[serde(default)]
+    pub altitude_source: AltitudeSource,  // Preferred altitude when both GPS and baro are present
+    #[serde(default)]
+    pub north_reference: NorthReference,  // Headings shown relative to true or magnetic north
+    #[serde(default)]
+    pub exclude_2d_altitude: bool,  // Record no altitude for track points taken during a 2D fix
+    #[serde(default)]
+    pub record_satellite_snapshots: bool,  // Keep satellite counts and average SNR with track points
+    #[serde(default = "default_recording_indicator")]
+    pub recording_indicator: bool,  // Blinking REC in the top bar and on the map while recording
+    #[serde(default)]
+    pub session_autosave: SessionAutosave,
+    #[serde(default)]
+    pub time_zone: DisplayTimeZone,
+    #[serde(default)]
+    pub csv_export: CsvExport,
+    #[serde(default = "default_coordinate_decimals")]
+    pub coordinate_decimals: usize,  // Places shown and exported to CSV/GeoJSON, 3 to 9
+    #[serde(default)]
+    pub coordinate_format: CoordinateFormat,  // Display only; exports stay in latitude/longitude
+    #[serde(default = "CopyTemplate::defaults")]
+    pub copy_templates: Vec<CopyTemplate>,  // Offered when copying the position
+    #[serde(default)]
+    pub tile_cache_dir: Option<String>,  // Map tile cache, default under the user cache dir
+    #[serde(default)]
+    pub tile_user_agent: Option<String>,  // Identifies tile downloads, default DEFAULT_USER_AGENT
+    #[serde(default)]
+    pub tile_contact: Option<String>,     // Email or URL added to the User-Agent, as OSM asks
+    #[serde(default)]
+    pub obd: ObdConfig,
+    #[serde(default)]
+    pub status_endpoint: StatusEndpoint,
+    #[serde(default)]
+    pub recent_exports: Vec<RecentExport>,  // Newest first
+}
+
+fn default_auto_connect() -> bool {
+    true
+}
+
+fn default_recording_indicator() -> bool {
+    true
+}
+
+fn default_coordinate_decimals() -> usize {
+    DEFAULT_COORDINATE_DECIMALS
+}
+
+fn default_satellite_timeout_secs() -> u64 {
+    DEFAULT_SATELLITE_MAX_AGE_SECS
+}
+
+fn default_min_satellites_used() -> usize {
+    DEFAULT_MIN_SATELLITES_USED
+}
+
+fn default_restart_delay_ms() -> u32 {
+    DEFAULT_RESTART_DELAY_MS
+}
+
+fn default_unfocused_repaint_secs() -> u32 {
+    DEFAULT_UNFOCUSED_REPAINT_SECS
 }
 
 impl Default for GpsConfig {
@@ -30,10 +1001,56 @@ impl GpsConfig {
                 source_type: "windows".to_string(),
                 serial_port: None,
                 serial_baudrate: Some(9600),
+                serial_read_timeout_ms: Some(1000),
+                serial_max_line_length: Some(1024),
+                rmc_speed_unit: SpeedUnit::Knots,
+                serial_line: SerialLine::default(),
                 gpsd_host: Some("localhost".to_string()),
                 gpsd_port: Some(2947),
                 windows_accuracy: Some(10),
                 windows_interval: Some(1),
+                auto_connect: true,
+                restart_delay_ms: DEFAULT_RESTART_DELAY_MS,
+                stall_reconnect: StallReconnect::default(),
+                marker_style: MarkerStyle::default(),
+                map_options: MapOptions::default(),
+                no_fix_warning: NoFixWarning::default(),
+                import_thinning: ImportThinning::default(),
+                speedometer: Speedometer::default(),
+                waypoint_quality_gate: WaypointQualityGate::default(),
+                waypoint_fix_hold: WaypointFixHold::default(),
+                track_rotation: TrackRotation::default(),
+                data_log: DataLog::default(),
+                snr_thresholds: SnrThresholds::default(),
+                hidden_constellations: Vec::new(),
+                used_satellites_only: false,
+                elevation_mask_deg: 0.0,
+                sky_plot_grid: SkyPlotGrid::default(),
+                horizon_mask: HorizonMask::default(),
+                satellite_timeout_secs: DEFAULT_SATELLITE_MAX_AGE_SECS,
+                expected_update_hz: 0,
+                min_satellites_used: DEFAULT_MIN_SATELLITES_USED,
+                unfocused_repaint_secs: DEFAULT_UNFOCUSED_REPAINT_SECS,
+                distance_units: DistanceUnits::default(),
+                speed_unit: None,
+                color_palette: ColorPalette::default(),
+                altitude_source: AltitudeSource::default(),
+                north_reference: NorthReference::default(),
+                exclude_2d_altitude: false,
+                record_satellite_snapshots: false,
+                recording_indicator: true,
+                session_autosave: SessionAutosave::default(),
+                time_zone: DisplayTimeZone::default(),
+                csv_export: CsvExport::default(),
+                coordinate_decimals: DEFAULT_COORDINATE_DECIMALS,
+                coordinate_format: CoordinateFormat::default(),
+                copy_templates: CopyTemplate::defaults(),
+                tile_cache_dir: None,
+                tile_user_agent: None,
+                tile_contact: None,
+                obd: ObdConfig::default(),
+                status_endpoint: StatusEndpoint::default(),
+                recent_exports: Vec::new(),
             }
         }
 
@@ -43,24 +1060,248 @@ impl GpsConfig {
                 source_type: "gpsd".to_string(),
                 serial_port: None,
                 serial_baudrate: Some(9600),
+                serial_read_timeout_ms: Some(1000),
+                serial_max_line_length: Some(1024),
+                rmc_speed_unit: SpeedUnit::Knots,
+                serial_line: SerialLine::default(),
                 gpsd_host: Some("localhost".to_string()),
                 gpsd_port: Some(2947),
                 windows_accuracy: Some(10),
                 windows_interval: Some(1),
+                auto_connect: true,
+                restart_delay_ms: DEFAULT_RESTART_DELAY_MS,
+                stall_reconnect: StallReconnect::default(),
+                marker_style: MarkerStyle::default(),
+                map_options: MapOptions::default(),
+                no_fix_warning: NoFixWarning::default(),
+                import_thinning: ImportThinning::default(),
+                speedometer: Speedometer::default(),
+                waypoint_quality_gate: WaypointQualityGate::default(),
+                waypoint_fix_hold: WaypointFixHold::default(),
+                track_rotation: TrackRotation::default(),
+                data_log: DataLog::default(),
+                snr_thresholds: SnrThresholds::default(),
+                hidden_constellations: Vec::new(),
+                used_satellites_only: false,
+                elevation_mask_deg: 0.0,
+                sky_plot_grid: SkyPlotGrid::default(),
+                horizon_mask: HorizonMask::default(),
+                satellite_timeout_secs: DEFAULT_SATELLITE_MAX_AGE_SECS,
+                expected_update_hz: 0,
+                min_satellites_used: DEFAULT_MIN_SATELLITES_USED,
+                unfocused_repaint_secs: DEFAULT_UNFOCUSED_REPAINT_SECS,
+                distance_units: DistanceUnits::default(),
+                speed_unit: None,
+                color_palette: ColorPalette::default(),
+                altitude_source: AltitudeSource::default(),
+                north_reference: NorthReference::default(),
+                exclude_2d_altitude: false,
+                record_satellite_snapshots: false,
+                recording_indicator: true,
+                session_autosave: SessionAutosave::default(),
+                time_zone: DisplayTimeZone::default(),
+                csv_export: CsvExport::default(),
+                coordinate_decimals: DEFAULT_COORDINATE_DECIMALS,
+                coordinate_format: CoordinateFormat::default(),
+                copy_templates: CopyTemplate::defaults(),
+                tile_cache_dir: None,
+                tile_user_agent: None,
+                tile_contact: None,
+                obd: ObdConfig::default(),
+                status_endpoint: StatusEndpoint::default(),
+                recent_exports: Vec::new(),
             }
         }
     }
 
-    /// Load configuration from storage
+    /// Load configuration from storage, normalized. Call `validate` before
+    /// connecting; an invalid config still loads so it can be fixed in settings.
     pub fn load() -> Result<Self> {
         #[cfg(windows)]
-        {
-            Self::load_from_registry()
+        let mut config = Self::load_from_registry()?;
+        #[cfg(not(windows))]
+        let mut config = Self::load_from_file()?;
+
+        for fix in config.normalize() {
+            eprintln!("Config: {}", fix);
         }
+        Ok(config)
+    }
 
-        #[cfg(not(windows))]
+    /// Fix trivially fixable problems, such as stray whitespace or zero values
+    /// that mean "use the default". Returns a description of each change.
+    pub fn normalize(&mut self) -> Vec<String> {
+        let mut fixes = Vec::new();
+
+        let source_type = self.source_type.trim().to_lowercase();
+        if source_type != self.source_type {
+            fixes.push(format!("source type \"{}\" read as \"{}\"", self.source_type, source_type));
+            self.source_type = source_type;
+        }
+
+        for (name, value) in [
+            ("serial port", &mut self.serial_port),
+            ("gpsd host", &mut self.gpsd_host),
+            ("tile cache directory", &mut self.tile_cache_dir),
+            ("tile User-Agent", &mut self.tile_user_agent),
+            ("tile contact", &mut self.tile_contact),
+        ] {
+            if let Some(text) = value.as_mut() {
+                if text.trim().is_empty() {
+                    fixes.push(format!("empty {} cleared", name));
+                    *value = None;
+                } else if text.trim() != text {
+                    fixes.push(format!("whitespace trimmed from {}", name));
+                    *text = text.trim().to_string();
+                }
+            }
+        }
+
+        // Sent as a header, which can't carry control or non-ASCII characters
+        for (name, value) in [("tile User-Agent", &mut self.tile_user_agent), ("tile contact", &mut self.tile_contact)] {
+            if let Some(text) = value.as_mut().filter(|text| !text.chars().all(crate::map::is_header_char)) {
+                fixes.push(format!("characters a header can't carry removed from {}", name));
+                text.retain(crate::map::is_header_char);
+                *text = text.trim().to_string();
+                if text.is_empty() {
+                    *value = None;
+                }
+            }
+        }
+
+        // Zero never works for these; unset falls back to the default
+        fn clear_zero<T: Default + PartialEq>(value: &mut Option<T>, name: &str, fixes: &mut Vec<String>) {
+            if value.as_ref().is_some_and(|v| *v == T::default()) {
+                fixes.push(format!("zero {} reset to default", name));
+                *value = None;
+            }
+        }
+        clear_zero(&mut self.serial_baudrate, "baud rate", &mut fixes);
+        clear_zero(&mut self.serial_read_timeout_ms, "serial read timeout", &mut fixes);
+        clear_zero(&mut self.serial_max_line_length, "serial line length", &mut fixes);
+        clear_zero(&mut self.gpsd_port, "gpsd port", &mut fixes);
+        clear_zero(&mut self.windows_interval, "Windows location interval", &mut fixes);
+
+        if !COORDINATE_DECIMALS_RANGE.contains(&self.coordinate_decimals) {
+            let clamped = GpsData::coordinate_decimals(self.coordinate_decimals);
+            fixes.push(format!("coordinate decimals {} clamped to {}", self.coordinate_decimals, clamped));
+            self.coordinate_decimals = clamped;
+        }
+
+        let steps = &mut self.map_options.speed_zoom_steps;
+        if steps.is_empty() {
+            fixes.push("empty speed zoom steps reset to defaults".to_string());
+            *steps = SpeedZoomStep::defaults();
+        } else if steps.windows(2).any(|pair| pair[0].min_speed > pair[1].min_speed) {
+            fixes.push("speed zoom steps sorted by speed".to_string());
+            steps.sort_by(|a, b| a.min_speed.total_cmp(&b.min_speed));
+        }
+
+        if self.map_options.memory_tiles == 0 {
+            fixes.push("map tiles in memory reset from 0".to_string());
+            self.map_options.memory_tiles = crate::map::DEFAULT_MEMORY_TILES;
+        }
+        if self.map_options.concurrent_downloads == 0 {
+            fixes.push("concurrent tile downloads reset from 0".to_string());
+            self.map_options.concurrent_downloads = crate::map::DEFAULT_CONCURRENT_DOWNLOADS;
+        }
+
+        let tolerance = self.import_thinning.tolerance_m;
+        if !(tolerance.is_finite() && tolerance > 0.0) {
+            fixes.push(format!("import thinning tolerance {} m reset to default", tolerance));
+            self.import_thinning.tolerance_m = ImportThinning::default().tolerance_m;
+        }
+
+        if !SkyPlotGrid::RING_INTERVALS.contains(&self.sky_plot_grid.ring_interval_deg) {
+            fixes.push(format!("sky plot ring interval {}° reset to 30°", self.sky_plot_grid.ring_interval_deg));
+            self.sky_plot_grid.ring_interval_deg = SkyPlotGrid::default().ring_interval_deg;
+        }
+
+        let before = self.copy_templates.len();
+        self.copy_templates.retain(|t| !t.name.trim().is_empty() && !t.template.trim().is_empty());
+        if self.copy_templates.len() < before {
+            fixes.push(format!("{} copy template(s) without a name or text removed", before - self.copy_templates.len()));
+        }
+
+        let horizon = &mut self.horizon_mask.points;
+        let unsorted = horizon.windows(2).any(|pair| pair[0].azimuth_deg > pair[1].azimuth_deg);
+        let out_of_range = horizon.iter().any(|p| !(0.0..360.0).contains(&p.azimuth_deg) || !(0.0..=90.0).contains(&p.min_elevation_deg));
+        if unsorted || out_of_range {
+            fixes.push("horizon mask points wrapped to 0-360° and sorted by azimuth".to_string());
+            for point in horizon.iter_mut() {
+                point.azimuth_deg = point.azimuth_deg.rem_euclid(360.0);
+                point.min_elevation_deg = point.min_elevation_deg.clamp(0.0, 90.0);
+            }
+            horizon.sort_by(|a, b| a.azimuth_deg.total_cmp(&b.azimuth_deg));
+        }
+
+        fixes
+    }
+
+    /// Check that the selected source can be connected with these settings
+    pub fn validate(&self) -> Result<()> {
+        let mut problems = Vec::new();
+
+        match self.source_type.as_str() {
+            "serial" => {
+                if self.serial_port.as_deref().is_none_or(|p| p.trim().is_empty()) {
+                    problems.push("no serial port set".to_string());
+                }
+                if let Some(baudrate) = self.serial_baudrate {
+                    if !STANDARD_BAUD_RATES.contains(&baudrate) {
+                        problems.push(format!("{} is not a standard baud rate", baudrate));
+                    }
+                }
+                if self.serial_read_timeout_ms == Some(0) {
+                    problems.push("serial read timeout must be positive".to_string());
+                }
+                if self.serial_max_line_length == Some(0) {
+                    problems.push("serial line length must be positive".to_string());
+                }
+                if !self.serial_line.is_valid() {
+                    problems.push(format!("serial framing {} needs 5-8 data bits and 1-2 stop bits", self.serial_line.framing()));
+                }
+            }
+            "gpsd" => {
+                if self.gpsd_host.as_deref().is_some_and(|h| h.trim().is_empty()) {
+                    problems.push("gpsd host is empty".to_string());
+                }
+                if self.gpsd_port == Some(0) {
+                    problems.push("gpsd port must be nonzero".to_string());
+                }
+            }
+            "windows" if SOURCE_TYPES.contains(&"windows") => {
+                if self.windows_interval == Some(0) {
+                    problems.push("Windows location interval must be positive".to_string());
+                }
+            }
+            "geoclue" if SOURCE_TYPES.contains(&"geoclue") => {}
+            other => problems.push(format!(
+                "unknown source type \"{}\" (expected one of: {})",
+                other,
+                SOURCE_TYPES.join(", ")
+            )),
+        }
+
+        if cfg!(feature = "obd") && self.obd.enabled {
+            if self.obd.port.trim().is_empty() {
+                problems.push("OBD-II is enabled but no port is set".to_string());
+            }
+            if self.obd.baudrate == 0 || self.obd.poll_interval_ms == 0 {
+                problems.push("OBD-II baud rate and poll interval must be positive".to_string());
+            }
+        }
+
+        if cfg!(feature = "status-server") && self.status_endpoint.enabled
+            && self.status_endpoint.address.trim().parse::<std::net::SocketAddr>().is_err()
         {
-            Self::load_from_file()
+            problems.push(format!("status endpoint address \"{}\" is not ip:port", self.status_endpoint.address));
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(GpsError::Other(format!("Invalid configuration: {}", problems.join("; "))))
         }
     }
 
@@ -103,10 +1344,61 @@ impl GpsConfig {
                     source_type,
                     serial_port: key.get_value("SerialPort").ok(),
                     serial_baudrate: key.get_value("SerialBaudrate").ok(),
+                    serial_read_timeout_ms: key.get_value("SerialReadTimeout").ok(),
+                    serial_max_line_length: key.get_value("SerialMaxLineLength").ok(),
+                    rmc_speed_unit: Self::load_registry_json(&key, "RmcSpeedUnit"),
+                    serial_line: Self::load_registry_json(&key, "SerialLine"),
                     gpsd_host: key.get_value("GpsdHost").ok(),
                     gpsd_port,
                     windows_accuracy: key.get_value("WindowsAccuracy").ok(),
                     windows_interval,
+                    auto_connect: key.get_value::<u32, _>("AutoConnect").map_or(true, |v| v != 0),
+                    marker_style: Self::load_registry_json(&key, "MarkerStyle"),
+                    map_options: Self::load_registry_json(&key, "MapOptions"),
+                    no_fix_warning: Self::load_registry_json(&key, "NoFixWarning"),
+                    stall_reconnect: Self::load_registry_json(&key, "StallReconnect"),
+                    import_thinning: Self::load_registry_json(&key, "ImportThinning"),
+                    speedometer: Self::load_registry_json(&key, "Speedometer"),
+                    waypoint_quality_gate: Self::load_registry_json(&key, "WaypointQualityGate"),
+                    waypoint_fix_hold: Self::load_registry_json(&key, "WaypointFixHold"),
+                    track_rotation: Self::load_registry_json(&key, "TrackRotation"),
+                    data_log: Self::load_registry_json(&key, "DataLog"),
+                    snr_thresholds: Self::load_registry_json(&key, "SnrThresholds"),
+                    hidden_constellations: Self::load_registry_json(&key, "HiddenConstellations"),
+                    used_satellites_only: key.get_value::<u32, _>("UsedSatellitesOnly").is_ok_and(|v| v != 0),
+                    elevation_mask_deg: Self::load_registry_json(&key, "ElevationMask"),
+                    sky_plot_grid: Self::load_registry_json(&key, "SkyPlotGrid"),
+                    horizon_mask: Self::load_registry_json(&key, "HorizonMask"),
+                    satellite_timeout_secs: key.get_value::<u32, _>("SatelliteTimeout")
+                        .map_or(DEFAULT_SATELLITE_MAX_AGE_SECS, u64::from),
+                    expected_update_hz: key.get_value("ExpectedUpdateRate").unwrap_or(0),
+                    min_satellites_used: key.get_value::<u32, _>("MinSatellitesUsed")
+                        .map_or(DEFAULT_MIN_SATELLITES_USED, |v| v as usize),
+                    unfocused_repaint_secs: key.get_value("UnfocusedRepaintSecs").unwrap_or(DEFAULT_UNFOCUSED_REPAINT_SECS),
+                    restart_delay_ms: key.get_value("RestartDelayMs").unwrap_or(DEFAULT_RESTART_DELAY_MS),
+                    distance_units: Self::load_registry_json(&key, "DistanceUnits"),
+                    speed_unit: Self::load_registry_json(&key, "SpeedUnit"),
+                    color_palette: Self::load_registry_json(&key, "ColorPalette"),
+                    altitude_source: Self::load_registry_json(&key, "AltitudeSource"),
+                    north_reference: Self::load_registry_json(&key, "NorthReference"),
+                    exclude_2d_altitude: key.get_value::<u32, _>("Exclude2dAltitude").is_ok_and(|v| v != 0),
+                    record_satellite_snapshots: key.get_value::<u32, _>("RecordSatelliteSnapshots").is_ok_and(|v| v != 0),
+                    recording_indicator: key.get_value::<u32, _>("RecordingIndicator").map_or(true, |v| v != 0),
+                    session_autosave: Self::load_registry_json(&key, "SessionAutosave"),
+                    time_zone: Self::load_registry_json(&key, "TimeZone"),
+                    csv_export: Self::load_registry_json(&key, "CsvExport"),
+                    coordinate_decimals: key.get_value::<u32, _>("CoordinateDecimals")
+                        .map_or(DEFAULT_COORDINATE_DECIMALS, |v| v as usize),
+                    coordinate_format: Self::load_registry_json(&key, "CoordinateFormat"),
+                    copy_templates: key.get_value::<String, _>("CopyTemplates").ok()
+                        .and_then(|s| serde_json::from_str(&s).ok())
+                        .unwrap_or_else(CopyTemplate::defaults),
+                    tile_cache_dir: key.get_value("TileCacheDir").ok(),
+                    tile_user_agent: key.get_value("TileUserAgent").ok(),
+                    tile_contact: key.get_value("TileContact").ok(),
+                    obd: Self::load_registry_json(&key, "Obd"),
+                    status_endpoint: Self::load_registry_json(&key, "StatusEndpoint"),
+                    recent_exports: Self::load_registry_json(&key, "RecentExports"),
                 };
                 
                 Ok(config)
@@ -143,6 +1435,16 @@ impl GpsConfig {
                 .map_err(|e| GpsError::Other(format!("Failed to save SerialBaudrate: {}", e)))?;
         }
         
+        if let Some(timeout) = self.serial_read_timeout_ms {
+            key.set_value("SerialReadTimeout", &timeout)
+                .map_err(|e| GpsError::Other(format!("Failed to save SerialReadTimeout: {}", e)))?;
+        }
+        
+        if let Some(max_length) = self.serial_max_line_length {
+            key.set_value("SerialMaxLineLength", &max_length)
+                .map_err(|e| GpsError::Other(format!("Failed to save SerialMaxLineLength: {}", e)))?;
+        }
+        
         if let Some(ref host) = self.gpsd_host {
             key.set_value("GpsdHost", host)
                 .map_err(|e| GpsError::Other(format!("Failed to save GpsdHost: {}", e)))?;
@@ -167,9 +1469,94 @@ impl GpsConfig {
                 .map_err(|e| GpsError::Other(format!("Failed to save WindowsInterval: {}", e)))?;
         }
         
+        key.set_value("AutoConnect", &(self.auto_connect as u32))
+            .map_err(|e| GpsError::Other(format!("Failed to save AutoConnect: {}", e)))?;
+
+        Self::save_registry_json(&key, "RmcSpeedUnit", &self.rmc_speed_unit)?;
+        Self::save_registry_json(&key, "SerialLine", &self.serial_line)?;
+        Self::save_registry_json(&key, "MarkerStyle", &self.marker_style)?;
+        Self::save_registry_json(&key, "MapOptions", &self.map_options)?;
+        Self::save_registry_json(&key, "NoFixWarning", &self.no_fix_warning)?;
+        Self::save_registry_json(&key, "StallReconnect", &self.stall_reconnect)?;
+        Self::save_registry_json(&key, "ImportThinning", &self.import_thinning)?;
+        Self::save_registry_json(&key, "Speedometer", &self.speedometer)?;
+        Self::save_registry_json(&key, "WaypointQualityGate", &self.waypoint_quality_gate)?;
+        Self::save_registry_json(&key, "WaypointFixHold", &self.waypoint_fix_hold)?;
+        Self::save_registry_json(&key, "TrackRotation", &self.track_rotation)?;
+        Self::save_registry_json(&key, "DataLog", &self.data_log)?;
+        Self::save_registry_json(&key, "SnrThresholds", &self.snr_thresholds)?;
+        Self::save_registry_json(&key, "HiddenConstellations", &self.hidden_constellations)?;
+        key.set_value("UsedSatellitesOnly", &(self.used_satellites_only as u32))
+            .map_err(|e| GpsError::Other(format!("Failed to save UsedSatellitesOnly: {}", e)))?;
+        Self::save_registry_json(&key, "ElevationMask", &self.elevation_mask_deg)?;
+        Self::save_registry_json(&key, "SkyPlotGrid", &self.sky_plot_grid)?;
+        Self::save_registry_json(&key, "HorizonMask", &self.horizon_mask)?;
+        key.set_value("SatelliteTimeout", &(self.satellite_timeout_secs as u32))
+            .map_err(|e| GpsError::Other(format!("Failed to save SatelliteTimeout: {}", e)))?;
+        key.set_value("ExpectedUpdateRate", &self.expected_update_hz)
+            .map_err(|e| GpsError::Other(format!("Failed to save ExpectedUpdateRate: {}", e)))?;
+        key.set_value("MinSatellitesUsed", &(self.min_satellites_used as u32))
+            .map_err(|e| GpsError::Other(format!("Failed to save MinSatellitesUsed: {}", e)))?;
+        key.set_value("UnfocusedRepaintSecs", &self.unfocused_repaint_secs)
+            .map_err(|e| GpsError::Other(format!("Failed to save UnfocusedRepaintSecs: {}", e)))?;
+        key.set_value("RestartDelayMs", &self.restart_delay_ms)
+            .map_err(|e| GpsError::Other(format!("Failed to save RestartDelayMs: {}", e)))?;
+        Self::save_registry_json(&key, "DistanceUnits", &self.distance_units)?;
+        Self::save_registry_json(&key, "SpeedUnit", &self.speed_unit)?;
+        Self::save_registry_json(&key, "ColorPalette", &self.color_palette)?;
+        Self::save_registry_json(&key, "AltitudeSource", &self.altitude_source)?;
+        Self::save_registry_json(&key, "NorthReference", &self.north_reference)?;
+        key.set_value("Exclude2dAltitude", &(self.exclude_2d_altitude as u32))
+            .map_err(|e| GpsError::Other(format!("Failed to save Exclude2dAltitude: {}", e)))?;
+        key.set_value("RecordSatelliteSnapshots", &(self.record_satellite_snapshots as u32))
+            .map_err(|e| GpsError::Other(format!("Failed to save RecordSatelliteSnapshots: {}", e)))?;
+        key.set_value("RecordingIndicator", &(self.recording_indicator as u32))
+            .map_err(|e| GpsError::Other(format!("Failed to save RecordingIndicator: {}", e)))?;
+        Self::save_registry_json(&key, "SessionAutosave", &self.session_autosave)?;
+        Self::save_registry_json(&key, "TimeZone", &self.time_zone)?;
+        Self::save_registry_json(&key, "CsvExport", &self.csv_export)?;
+        key.set_value("CoordinateDecimals", &(self.coordinate_decimals as u32))
+            .map_err(|e| GpsError::Other(format!("Failed to save CoordinateDecimals: {}", e)))?;
+        Self::save_registry_json(&key, "CoordinateFormat", &self.coordinate_format)?;
+        Self::save_registry_json(&key, "CopyTemplates", &self.copy_templates)?;
+
+        match self.tile_cache_dir {
+            Some(ref dir) => key.set_value("TileCacheDir", dir)
+                .map_err(|e| GpsError::Other(format!("Failed to save TileCacheDir: {}", e)))?,
+            // Missing value means the default location
+            None => { let _ = key.delete_value("TileCacheDir"); }
+        }
+        for (name, value) in [("TileUserAgent", &self.tile_user_agent), ("TileContact", &self.tile_contact)] {
+            match value {
+                Some(text) => key.set_value(name, text)
+                    .map_err(|e| GpsError::Other(format!("Failed to save {}: {}", name, e)))?,
+                None => { let _ = key.delete_value(name); }
+            }
+        }
+        Self::save_registry_json(&key, "Obd", &self.obd)?;
+        Self::save_registry_json(&key, "StatusEndpoint", &self.status_endpoint)?;
+        Self::save_registry_json(&key, "RecentExports", &self.recent_exports)?;
+        
         Ok(())
     }
 
+    /// Load a settings group stored as a JSON string value, falling back to defaults
+    #[cfg(windows)]
+    fn load_registry_json<T: serde::de::DeserializeOwned + Default>(key: &winreg::RegKey, name: &str) -> T {
+        key.get_value::<String, _>(name).ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    /// Save a settings group as a JSON string value
+    #[cfg(windows)]
+    fn save_registry_json<T: Serialize>(key: &winreg::RegKey, name: &str, value: &T) -> Result<()> {
+        let json = serde_json::to_string(value)
+            .map_err(|e| GpsError::Other(format!("Failed to serialize {}: {}", name, e)))?;
+        key.set_value(name, &json)
+            .map_err(|e| GpsError::Other(format!("Failed to save {}: {}", name, e)))
+    }
+
     /// Load from config file on Unix systems
     #[cfg(not(windows))]
     fn load_from_file() -> Result<Self> {
@@ -219,6 +1606,62 @@ impl GpsConfig {
         Ok(PathBuf::from(home).join(".config").join("gps-monitor").join("config.json"))
     }
 
+    /// Directory map tiles are cached in
+    pub fn tile_cache_path(&self) -> PathBuf {
+        match self.tile_cache_dir.as_deref().map(str::trim) {
+            Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+            _ => Self::default_tile_cache_path(),
+        }
+    }
+
+    /// Unit for displayed speeds
+    pub fn display_speed_unit(&self) -> DisplaySpeedUnit {
+        self.speed_unit.unwrap_or_else(|| self.distance_units.speed_unit())
+    }
+
+    /// User-Agent for map tile downloads
+    pub fn tile_user_agent_header(&self) -> String {
+        crate::map::user_agent(self.tile_user_agent.as_deref(), self.tile_contact.as_deref())
+    }
+
+    /// Tile cache location when none is configured
+    pub fn default_tile_cache_path() -> PathBuf {
+        let mut path = dirs::cache_dir().unwrap_or_else(|| PathBuf::from("."));
+        path.push("gps-monitor");
+        path.push("tiles");
+        path
+    }
+
+    /// Export configuration to a JSON file
+    pub fn export_to_file(&self, path: &Path) -> Result<()> {
+        let contents = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, contents)?;
+        Ok(())
+    }
+
+    /// Import configuration from a JSON file written by `export_to_file`
+    pub fn import_from_file(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        let mut config: Self = serde_json::from_str(&contents)
+            .map_err(|e| GpsError::Parse(format!("Invalid settings file: {}", e)))?;
+        config.normalize();
+        Ok(config)
+    }
+
+    /// Take the export history from `current`. It belongs to this install
+    /// rather than to a set of settings, so a profile switch or an import
+    /// must not roll it back.
+    pub fn keep_state_from(&mut self, current: &GpsConfig) {
+        self.recent_exports = current.recent_exports.clone();
+    }
+
+    /// Copy with the export history left empty
+    fn without_state(&self) -> Self {
+        let mut settings = self.clone();
+        settings.keep_state_from(&Self::default());
+        settings
+    }
+
     /// Update configuration with new source settings
     pub fn update_source(&mut self, source_type: &str) {
         self.source_type = source_type.to_string();
@@ -231,6 +1674,12 @@ impl GpsConfig {
         self.serial_baudrate = Some(baudrate);
     }
 
+    /// Update serial read timeout and maximum accepted line length
+    pub fn update_serial_limits(&mut self, read_timeout_ms: u32, max_line_length: u32) {
+        self.serial_read_timeout_ms = Some(read_timeout_ms);
+        self.serial_max_line_length = Some(max_line_length);
+    }
+
     /// Update gpsd settings
     pub fn update_gpsd(&mut self, host: String, port: u16) {
         self.source_type = "gpsd".to_string();
@@ -246,6 +1695,143 @@ impl GpsConfig {
     }
 }
 
+/// Named configurations the user can switch between
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConfigProfiles {
+    pub active: Option<String>,
+    pub profiles: BTreeMap<String, GpsConfig>,
+}
+
+impl ConfigProfiles {
+    /// Load profiles from storage
+    pub fn load() -> Result<Self> {
+        #[cfg(windows)]
+        {
+            Self::load_from_registry()
+        }
+
+        #[cfg(not(windows))]
+        {
+            Self::load_from_file()
+        }
+    }
+
+    /// Save profiles to storage
+    pub fn save(&self) -> Result<()> {
+        #[cfg(windows)]
+        {
+            self.save_to_registry()
+        }
+
+        #[cfg(not(windows))]
+        {
+            self.save_to_file()
+        }
+    }
+
+    /// Profile names in sorted order
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.profiles.keys().map(|name| name.as_str())
+    }
+
+    /// Store `config` under `name`, replacing any existing profile, and make it
+    /// active. Only the settings are stored, see `GpsConfig::keep_state_from`.
+    pub fn save_profile(&mut self, name: &str, config: &GpsConfig) -> Result<()> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(GpsError::Other("Profile name cannot be empty".to_string()));
+        }
+
+        self.profiles.insert(name.to_string(), config.without_state());
+        self.active = Some(name.to_string());
+        Ok(())
+    }
+
+    /// Make `name` the active profile and return its configuration
+    pub fn switch_to(&mut self, name: &str) -> Result<GpsConfig> {
+        let config = self.profiles.get(name)
+            .cloned()
+            .ok_or_else(|| GpsError::Other(format!("No profile named '{}'", name)))?;
+
+        self.active = Some(name.to_string());
+        Ok(config)
+    }
+
+    /// Delete a profile, returning whether it existed
+    pub fn remove(&mut self, name: &str) -> bool {
+        if self.active.as_deref() == Some(name) {
+            self.active = None;
+        }
+        self.profiles.remove(name).is_some()
+    }
+
+    /// Load from Windows Registry
+    #[cfg(windows)]
+    fn load_from_registry() -> Result<Self> {
+        use winreg::enums::*;
+        use winreg::RegKey;
+
+        let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+        match hkcu.open_subkey(r"Software\GpsMonitor") {
+            Ok(key) => Ok(GpsConfig::load_registry_json(&key, "Profiles")),
+            Err(_) => Ok(Self::default()),
+        }
+    }
+
+    /// Save to Windows Registry
+    #[cfg(windows)]
+    fn save_to_registry(&self) -> Result<()> {
+        use winreg::enums::*;
+        use winreg::RegKey;
+
+        let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+        let (key, _) = hkcu.create_subkey(r"Software\GpsMonitor")
+            .map_err(|e| GpsError::Other(format!("Failed to create registry key: {}", e)))?;
+
+        GpsConfig::save_registry_json(&key, "Profiles", self)
+    }
+
+    /// Load from profiles file on Unix systems
+    #[cfg(not(windows))]
+    fn load_from_file() -> Result<Self> {
+        let path = Self::get_profiles_path()?;
+
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let contents = std::fs::read_to_string(&path)
+            .map_err(|e| GpsError::Other(format!("Failed to read profiles file: {}", e)))?;
+
+        serde_json::from_str(&contents)
+            .map_err(|e| GpsError::Other(format!("Failed to parse profiles file: {}", e)))
+    }
+
+    /// Save to profiles file on Unix systems
+    #[cfg(not(windows))]
+    fn save_to_file(&self) -> Result<()> {
+        let path = Self::get_profiles_path()?;
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| GpsError::Other(format!("Failed to create config directory: {}", e)))?;
+        }
+
+        let contents = serde_json::to_string_pretty(self)
+            .map_err(|e| GpsError::Other(format!("Failed to serialize profiles: {}", e)))?;
+
+        std::fs::write(&path, contents)
+            .map_err(|e| GpsError::Other(format!("Failed to write profiles file: {}", e)))
+    }
+
+    /// Profiles live next to config.json
+    #[cfg(not(windows))]
+    fn get_profiles_path() -> Result<std::path::PathBuf> {
+        Ok(GpsConfig::get_config_path()?.with_file_name("profiles.json"))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -276,4 +1862,313 @@ mod tests {
         assert_eq!(config.serial_port, Some("/dev/ttyUSB0".to_string()));
         assert_eq!(config.serial_baudrate, Some(115200));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_config_without_optional_groups() {
+        let json = r#"{"source_type":"gpsd","serial_port":null,"serial_baudrate":9600,"gpsd_host":"localhost","gpsd_port":2947,"windows_accuracy":10,"windows_interval":1}"#;
+        let config: GpsConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(config.rmc_speed_unit, SpeedUnit::Knots);
+        assert_eq!(config.serial_line, SerialLine::default());
+        assert_eq!(config.serial_line.framing(), "8N1");
+        assert!(config.auto_connect);
+        assert_eq!(config.marker_style, MarkerStyle::default());
+        assert_eq!(config.map_options, MapOptions::default());
+        assert_eq!(config.no_fix_warning, NoFixWarning::default());
+        assert_eq!(config.stall_reconnect, StallReconnect::default());
+        assert_eq!(config.import_thinning, ImportThinning::default());
+        assert!(!config.speedometer.enabled);
+        assert_eq!(config.waypoint_quality_gate, WaypointQualityGate::default());
+        assert_eq!(config.waypoint_fix_hold, WaypointFixHold::default());
+        assert_eq!(config.track_rotation, TrackRotation::default());
+        assert_eq!(config.data_log, DataLog::default());
+        assert_eq!(config.snr_thresholds, SnrThresholds::default());
+        assert!(config.hidden_constellations.is_empty());
+        assert_eq!(config.distance_units, DistanceUnits::Metric);
+        assert_eq!(config.color_palette, ColorPalette::Standard);
+        assert_eq!(config.altitude_source, AltitudeSource::Gps);
+        assert_eq!(config.north_reference, NorthReference::True);
+        assert_eq!(config.coordinate_format, CoordinateFormat::Decimal);
+        assert!(!config.exclude_2d_altitude);
+        assert!(!config.record_satellite_snapshots);
+        assert!(config.recording_indicator);
+        assert!(!config.used_satellites_only);
+        assert_eq!(config.session_autosave, SessionAutosave::default());
+        assert_eq!(config.time_zone, DisplayTimeZone::Utc);
+        assert_eq!(config.csv_export, CsvExport::default());
+        assert_eq!(config.coordinate_decimals, DEFAULT_COORDINATE_DECIMALS);
+        assert_eq!(config.tile_cache_path(), GpsConfig::default_tile_cache_path());
+        assert_eq!(config.tile_user_agent_header(), crate::map::DEFAULT_USER_AGENT);
+        assert_eq!(config.display_speed_unit(), DisplaySpeedUnit::KilometersPerHour);
+        assert_eq!(config.elevation_mask_deg, 0.0);
+        assert_eq!(config.sky_plot_grid, SkyPlotGrid::default());
+        assert_eq!(config.horizon_mask, HorizonMask::default());
+        assert_eq!(config.copy_templates, CopyTemplate::defaults());
+        assert_eq!(config.satellite_timeout_secs, DEFAULT_SATELLITE_MAX_AGE_SECS);
+        assert_eq!(config.expected_update_hz, 0);
+        assert_eq!(config.min_satellites_used, DEFAULT_MIN_SATELLITES_USED);
+        assert_eq!(config.unfocused_repaint_secs, DEFAULT_UNFOCUSED_REPAINT_SECS);
+        assert_eq!(config.restart_delay_ms, DEFAULT_RESTART_DELAY_MS);
+        assert_eq!(config.obd, ObdConfig::default());
+        assert_eq!(config.status_endpoint, StatusEndpoint::default());
+        assert!(config.recent_exports.is_empty());
+    }
+
+    #[test]
+    fn test_format_distance() {
+        assert_eq!(DistanceUnits::Metric.format_distance(250.0), "250 m");
+        assert_eq!(DistanceUnits::Metric.format_distance(12_345.0), "12.35 km");
+        assert_eq!(DistanceUnits::Imperial.format_distance(100.0), "328 ft");
+        assert_eq!(DistanceUnits::Imperial.format_distance(16_093.44), "10.00 mi");
+        assert_eq!(DistanceUnits::Nautical.format_distance(3704.0), "2.00 nm");
+    }
+
+    #[test]
+    fn test_recent_exports_capped_and_deduplicated() {
+        let export = |name: &str, format| RecentExport { path: PathBuf::from(name), format };
+        let mut recent = Vec::new();
+        for i in 0..MAX_RECENT_EXPORTS + 2 {
+            RecentExport::remember(&mut recent, export(&format!("track{}.gpx", i), WaypointFormat::GPX));
+        }
+        assert_eq!(recent.len(), MAX_RECENT_EXPORTS);
+        assert_eq!(recent[0].path, PathBuf::from(format!("track{}.gpx", MAX_RECENT_EXPORTS + 1)));
+
+        // Exporting to a listed file again moves it to the front with its new format
+        RecentExport::remember(&mut recent, export("track3.gpx", WaypointFormat::KML));
+        assert_eq!(recent.len(), MAX_RECENT_EXPORTS);
+        assert_eq!(recent[0], export("track3.gpx", WaypointFormat::KML));
+        assert_eq!(recent.iter().filter(|r| r.path == Path::new("track3.gpx")).count(), 1);
+    }
+
+    #[test]
+    fn test_speed_units() {
+        assert_eq!(DistanceUnits::Metric.speed_from_kmh(100.0), 100.0);
+        assert!((DistanceUnits::Imperial.speed_from_kmh(160.9344) - 100.0).abs() < 1e-9);
+        assert!((DistanceUnits::Nautical.speed_from_kmh(18.52) - 10.0).abs() < 1e-9);
+        assert_eq!(DistanceUnits::Imperial.speed_label(), "mph");
+    }
+
+    #[test]
+    fn test_copy_templates() {
+        let templates = CopyTemplate::defaults();
+        let copied: Vec<_> = templates.iter().map(|t| t.apply(48.1173, -11.5166667, 6)).collect();
+        assert_eq!(copied, [
+            "48.117300, -11.516667",
+            "https://www.google.com/maps?q=48.117300,-11.516667",
+            "https://www.openstreetmap.org/?mlat=48.117300&mlon=-11.516667#map=17/48.117300/-11.516667",
+            "geo:48.117300,-11.516667",
+        ]);
+
+        // Places follow the coordinate decimals setting, clamped the same way
+        let custom = CopyTemplate::new("Swapped", "lon={lon} lat={lat}");
+        assert_eq!(custom.apply(48.1173, -11.5166667, 3), "lon=-11.517 lat=48.117");
+        assert_eq!(custom.apply(48.1173, -11.5166667, 0), custom.apply(48.1173, -11.5166667, 3));
+        assert_eq!(CopyTemplate::new("Fixed", "Home").apply(1.0, 2.0, 6), "Home");
+
+        let mut config = GpsConfig::default();
+        config.copy_templates.push(CopyTemplate::new(" ", "{lat}"));
+        assert_eq!(config.normalize().len(), 1);
+        assert_eq!(config.copy_templates, CopyTemplate::defaults());
+    }
+
+    #[test]
+    fn test_horizon_mask_interpolation() {
+        let point = |azimuth_deg, min_elevation_deg| HorizonPoint { azimuth_deg, min_elevation_deg };
+        let mut mask = HorizonMask { enabled: true, points: vec![point(90.0, 30.0), point(180.0, 10.0), point(300.0, 0.0)] };
+
+        assert_eq!(mask.min_elevation_at(90.0), 30.0);
+        assert_eq!(mask.min_elevation_at(135.0), 20.0);
+        // Wrapping through north from 300° to 90°
+        assert_eq!(mask.min_elevation_at(15.0), 15.0);
+        assert_eq!(mask.min_elevation_at(375.0), 15.0);
+
+        assert!(mask.hides(25.0, 90.0));
+        assert!(!mask.hides(35.0, 90.0));
+        assert!(!mask.hides(5.0, 300.0));
+
+        mask.enabled = false;
+        assert!(!mask.hides(25.0, 90.0));
+
+        // Normalizing wraps and sorts the points
+        let mut config = GpsConfig::default();
+        config.horizon_mask.points = vec![point(370.0, 20.0), point(-30.0, 95.0)];
+        assert!(!config.normalize().is_empty());
+        assert_eq!(config.horizon_mask.points, vec![point(10.0, 20.0), point(330.0, 90.0)]);
+    }
+
+    #[test]
+    fn test_display_speed_units() {
+        assert_eq!(DisplaySpeedUnit::KilometersPerHour.from_kmh(100.0), 100.0);
+        assert!((DisplaySpeedUnit::MilesPerHour.from_kmh(160.9344) - 100.0).abs() < 1e-9);
+        assert!((DisplaySpeedUnit::Knots.from_kmh(18.52) - 10.0).abs() < 1e-9);
+        assert!((DisplaySpeedUnit::MetersPerSecond.from_kmh(36.0) - 10.0).abs() < 1e-9);
+        assert_eq!(DisplaySpeedUnit::Knots.format_speed(18.52, 1), "10.0 kn");
+
+        // Unset, the speed unit follows the distance units; set, it's independent
+        let mut config = GpsConfig { distance_units: DistanceUnits::Metric, ..GpsConfig::default() };
+        assert_eq!(config.display_speed_unit(), DisplaySpeedUnit::KilometersPerHour);
+        config.distance_units = DistanceUnits::Imperial;
+        assert_eq!(config.display_speed_unit(), DisplaySpeedUnit::MilesPerHour);
+        config.speed_unit = Some(DisplaySpeedUnit::Knots);
+        assert_eq!(config.display_speed_unit(), DisplaySpeedUnit::Knots);
+    }
+
+    #[test]
+    fn test_display_time_zone_fixed_offset() {
+        let time = DateTime::parse_from_rfc3339("2024-05-01T23:30:00Z").unwrap().with_timezone(&Utc);
+
+        assert_eq!(DisplayTimeZone::Utc.format_with_zone(&time, "%Y-%m-%d %H:%M"), "2024-05-01 23:30 UTC");
+
+        let tokyo = DisplayTimeZone::Fixed(9 * 60);
+        assert_eq!(tokyo.format(&time, "%Y-%m-%d %H:%M"), "2024-05-02 08:30");
+        assert_eq!(tokyo.format_with_zone(&time, "%H:%M"), "08:30 UTC+09:00");
+
+        let newfoundland = DisplayTimeZone::Fixed(-(3 * 60 + 30));
+        assert_eq!(newfoundland.format_with_zone(&time, "%H:%M"), "20:00 UTC-03:30");
+
+        let json = serde_json::to_string(&tokyo).unwrap();
+        assert_eq!(serde_json::from_str::<DisplayTimeZone>(&json).unwrap(), tokyo);
+    }
+
+    #[test]
+    fn test_profiles_round_trip() {
+        let mut profiles = ConfigProfiles::default();
+
+        let mut desk = GpsConfig::default();
+        desk.update_serial("/dev/ttyUSB0".to_string(), 4800);
+        profiles.save_profile("Desk", &desk).unwrap();
+
+        let mut field = GpsConfig::default();
+        field.update_gpsd("laptop.local".to_string(), 2948);
+        profiles.save_profile("Field", &field).unwrap();
+
+        let json = serde_json::to_string(&profiles).unwrap();
+        let loaded: ConfigProfiles = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(loaded.names().collect::<Vec<_>>(), vec!["Desk", "Field"]);
+        assert_eq!(loaded.active.as_deref(), Some("Field"));
+        assert_eq!(loaded.profiles["Desk"].serial_baudrate, Some(4800));
+        assert_eq!(loaded.profiles["Field"].gpsd_host.as_deref(), Some("laptop.local"));
+    }
+
+    #[test]
+    fn test_profile_switching() {
+        let mut profiles = ConfigProfiles::default();
+        let mut desk = GpsConfig::default();
+        desk.update_serial("COM3".to_string(), 9600);
+        profiles.save_profile("Desk", &desk).unwrap();
+        profiles.save_profile("Field", &GpsConfig::default()).unwrap();
+
+        let config = profiles.switch_to("Desk").unwrap();
+        assert_eq!(config.source_type, "serial");
+        assert_eq!(profiles.active.as_deref(), Some("Desk"));
+
+        assert!(profiles.switch_to("Missing").is_err());
+        assert_eq!(profiles.active.as_deref(), Some("Desk"));
+
+        assert!(profiles.save_profile("  ", &desk).is_err());
+
+        assert!(profiles.remove("Desk"));
+        assert!(profiles.active.is_none());
+        assert!(!profiles.remove("Desk"));
+
+        // The export history stays with the running config
+        let mut current = GpsConfig::default();
+        RecentExport::remember(&mut current.recent_exports, RecentExport { path: "/tmp/a.gpx".into(), format: WaypointFormat::GPX });
+        profiles.save_profile("Desk", &current).unwrap();
+        assert!(profiles.profiles["Desk"].recent_exports.is_empty());
+        let mut config = profiles.switch_to("Field").unwrap();
+        config.keep_state_from(&current);
+        assert_eq!(config.recent_exports, current.recent_exports);
+    }
+
+    #[test]
+    fn test_export_import_file() {
+        let path = std::env::temp_dir().join(format!("gps-monitor-export-{}.json", std::process::id()));
+        let mut config = GpsConfig::default();
+        config.update_gpsd("example.org".to_string(), 3000);
+        config.hidden_constellations.push("GLONASS".to_string());
+
+        config.export_to_file(&path).unwrap();
+        let imported = GpsConfig::import_from_file(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(imported.gpsd_host.as_deref(), Some("example.org"));
+        assert_eq!(imported.gpsd_port, Some(3000));
+        assert_eq!(imported.hidden_constellations, vec!["GLONASS".to_string()]);
+    }
+
+    #[test]
+    fn test_rotation_period_and_file_name() {
+        use chrono::TimeZone;
+        let time = Utc.with_ymd_and_hms(2024, 5, 1, 13, 47, 12).unwrap();
+
+        let hour = RotationInterval::Hourly.period_start(&time);
+        assert_eq!(hour, Utc.with_ymd_and_hms(2024, 5, 1, 13, 0, 0).unwrap());
+        assert_eq!(RotationInterval::Hourly.file_name(&hour), "track_2024-05-01_13.gpx");
+
+        let day = RotationInterval::Daily.period_start(&time);
+        assert_eq!(day, Utc.with_ymd_and_hms(2024, 5, 1, 0, 0, 0).unwrap());
+        assert_eq!(RotationInterval::Daily.file_name(&day), "track_2024-05-01.gpx");
+
+        // The next period starts exactly on the boundary
+        let boundary = Utc.with_ymd_and_hms(2024, 5, 2, 0, 0, 0).unwrap();
+        assert_eq!(RotationInterval::Daily.period_start(&boundary), boundary);
+        assert_ne!(RotationInterval::Hourly.period_start(&(boundary - chrono::Duration::seconds(1))), boundary);
+    }
+
+    #[test]
+    fn test_validate_rejects_invalid_configs() {
+        let mut serial = GpsConfig::default();
+        serial.update_serial("/dev/ttyUSB0".to_string(), 9600);
+        assert!(serial.validate().is_ok());
+
+        let mut bad = serial.clone();
+        bad.serial_baudrate = Some(12345);
+        assert!(bad.validate().is_err());
+
+        let mut bad = serial.clone();
+        bad.serial_port = None;
+        assert!(bad.validate().is_err());
+
+        let mut bad = GpsConfig::default();
+        bad.update_gpsd("localhost".to_string(), 0);
+        assert!(bad.validate().is_err());
+
+        let bad = GpsConfig {
+            source_type: "bluetooth".to_string(),
+            ..GpsConfig::default()
+        };
+        let err = bad.validate().unwrap_err().to_string();
+        assert!(err.contains("bluetooth"));
+    }
+
+    #[test]
+    fn test_normalize_fixes_trivial_issues() {
+        let mut config = GpsConfig {
+            source_type: " Serial ".to_string(),
+            serial_port: Some(" COM3 ".to_string()),
+            serial_baudrate: Some(0),
+            coordinate_decimals: 20,
+            map_options: MapOptions {
+                speed_zoom_steps: SpeedZoomStep::defaults().into_iter().rev().collect(),
+                concurrent_downloads: 0,
+                ..MapOptions::default()
+            },
+            sky_plot_grid: SkyPlotGrid { ring_interval_deg: 20, ..SkyPlotGrid::default() },
+            tile_contact: Some("ops@exämple.com\n".to_string()),
+            ..GpsConfig::default()
+        };
+
+        assert_eq!(config.normalize().len(), 9);
+        assert_eq!(config.source_type, "serial");
+        assert_eq!(config.serial_port.as_deref(), Some("COM3"));
+        assert_eq!(config.serial_baudrate, None);
+        assert_eq!(config.coordinate_decimals, *COORDINATE_DECIMALS_RANGE.end());
+        assert_eq!(config.map_options.speed_zoom_steps, SpeedZoomStep::defaults());
+        assert_eq!(config.map_options.concurrent_downloads, crate::map::DEFAULT_CONCURRENT_DOWNLOADS);
+        assert_eq!(config.sky_plot_grid.rings(), vec![30, 60]);
+        assert_eq!(config.tile_contact.as_deref(), Some("ops@exmple.com"));
+        assert!(config.validate().is_ok());
+        assert!(config.normalize().is_empty());
+    }
+}
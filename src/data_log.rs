@@ -0,0 +1,276 @@
+// src/data_log.rs v3
+//! Continuous CSV log of the decoded fix for later analysis. Unlike track
+//! recording there is no distance or time gating: every new fix becomes a
+//! row. Files rotate once they pass a size limit or a new period starts.
+
+use crate::{
+    config::DataLog,
+    error::Result,
+    gps::{DataField, GpsData},
+    monitor::GpsMonitor,
+};
+use chrono::{DateTime, SecondsFormat, Utc};
+use std::{
+    fs::{self, File, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+    sync::{mpsc, Mutex},
+    thread::JoinHandle,
+};
+
+/// First line of every log file
+pub const CSV_HEADER: &str = "timestamp,latitude,longitude,altitude_m,speed_kmh,course_deg,satellites,hdop,fix_quality";
+
+/// Appends one CSV row per fix to the current log file
+pub struct DataLogger {
+    options: DataLog,
+    file: Option<File>,
+    path: Option<PathBuf>,
+    bytes_written: u64,
+    period_start: Option<DateTime<Utc>>,
+    last_fix: Option<DateTime<Utc>>,
+}
+
+impl DataLogger {
+    pub fn new(options: DataLog) -> Self {
+        Self {
+            options,
+            file: None,
+            path: None,
+            bytes_written: 0,
+            period_start: None,
+            last_fix: None,
+        }
+    }
+
+    /// Log every fix `monitor` decodes, giving up on the first write error.
+    ///
+    /// Rows are written on a thread of their own so the reader tasks never
+    /// wait for the disk. The thread ends once the monitor and its clones are
+    /// dropped; join the returned handle to wait for the last rows.
+    pub fn attach(mut self, monitor: &mut GpsMonitor) -> JoinHandle<()> {
+        let (sender, fixes) = mpsc::channel::<(GpsData, DateTime<Utc>)>();
+        let last_sent = Mutex::new(None);
+        monitor.add_update_observer(move |data| {
+            // Only new fixes are worth a copy, see `record`
+            let fix_time = data.field_updated(DataField::Position);
+            let mut last_sent = last_sent.lock().unwrap();
+            if fix_time.is_some() && fix_time != *last_sent {
+                *last_sent = fix_time;
+                // Fails only once the writer gave up
+                let _ = sender.send((data.clone(), Utc::now()));
+            }
+        });
+
+        std::thread::spawn(move || {
+            for (data, now) in fixes {
+                if let Err(e) = self.record(&data, now) {
+                    eprintln!("Data log stopped: {}", e);
+                    return;
+                }
+            }
+        })
+    }
+
+    /// File currently being written, once the first row is logged
+    pub fn current_path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// CSV row (with trailing newline) for the fix in `data`, None without a position
+    pub fn csv_row(data: &GpsData) -> Option<String> {
+        let (latitude, longitude) = (data.latitude?, data.longitude?);
+        let time = data.timestamp.or(data.field_updated(DataField::Position))?;
+        let number = |value: Option<f64>, decimals: usize| value.map_or(String::new(), |v| format!("{:.*}", decimals, v));
+        let integer = |value: Option<u8>| value.map_or(String::new(), |v| v.to_string());
+
+        Some(format!(
+            "{},{:.7},{:.7},{},{},{},{},{},{}\n",
+            time.to_rfc3339_opts(SecondsFormat::Millis, true),
+            latitude,
+            longitude,
+            number(data.altitude, 1),
+            number(data.speed, 2),
+            number(data.course, 1),
+            integer(data.satellites),
+            number(data.hdop, 2),
+            integer(data.fix_quality),
+        ))
+    }
+
+    /// Whether writing `row_len` more bytes at `now` should start a new file.
+    /// A file always takes at least one row, so a tiny limit can't loop.
+    fn should_rotate(&self, row_len: usize, now: &DateTime<Utc>) -> bool {
+        if self.file.is_none() {
+            return true;
+        }
+        if let Some(interval) = self.options.interval {
+            if self.period_start != Some(interval.period_start(now)) {
+                return true;
+            }
+        }
+        self.options.max_size_bytes().is_some_and(|max| {
+            self.bytes_written > CSV_HEADER.len() as u64 + 1 && self.bytes_written + row_len as u64 > max
+        })
+    }
+
+    /// Log the fix in `data` if it is new. Returns whether a row was written.
+    ///
+    /// A fix is new when the position was updated, not on every sentence:
+    /// `timestamp` changes with each one, and an epoch of GGA, RMC, GSA and
+    /// GSV should be one row.
+    pub fn record(&mut self, data: &GpsData, now: DateTime<Utc>) -> Result<bool> {
+        let fix_time = data.field_updated(DataField::Position);
+        if fix_time.is_none() || fix_time == self.last_fix {
+            return Ok(false);
+        }
+        let Some(row) = Self::csv_row(data) else {
+            return Ok(false);
+        };
+
+        if self.should_rotate(row.len(), &now) {
+            self.open(&now)?;
+        }
+        if let Some(file) = self.file.as_mut() {
+            file.write_all(row.as_bytes())?;
+            self.bytes_written += row.len() as u64;
+        }
+        self.last_fix = fix_time;
+        Ok(true)
+    }
+
+    /// Start a new file named after `now`, e.g. "gps_2024-05-01_130501.csv"
+    fn open(&mut self, now: &DateTime<Utc>) -> Result<()> {
+        let dir = self.options.output_path();
+        fs::create_dir_all(&dir)?;
+
+        let stem = format!("gps_{}", now.format("%Y-%m-%d_%H%M%S"));
+        let mut path = dir.join(format!("{}.csv", stem));
+        let mut suffix = 1;
+        while path.exists() {
+            path = dir.join(format!("{}_{}.csv", stem, suffix));
+            suffix += 1;
+        }
+
+        let mut file = OpenOptions::new().create_new(true).write(true).open(&path)?;
+        writeln!(file, "{}", CSV_HEADER)?;
+
+        self.file = Some(file);
+        self.path = Some(path);
+        self.bytes_written = CSV_HEADER.len() as u64 + 1;
+        self.period_start = self.options.interval.map(|interval| interval.period_start(now));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RotationInterval;
+    use chrono::TimeZone;
+
+    fn fix(time: DateTime<Utc>) -> GpsData {
+        GpsData {
+            latitude: Some(48.1173),
+            longitude: Some(11.5166667),
+            altitude: Some(545.4),
+            speed: Some(41.5),
+            satellites: Some(8),
+            hdop: Some(0.9),
+            fix_quality: Some(1),
+            timestamp: Some(time),
+            position_updated: Some(time),
+            ..GpsData::default()
+        }
+    }
+
+    #[test]
+    fn test_csv_row() {
+        let time = Utc.with_ymd_and_hms(2024, 5, 1, 12, 35, 19).unwrap();
+        assert_eq!(
+            DataLogger::csv_row(&fix(time)).as_deref(),
+            Some("2024-05-01T12:35:19.000Z,48.1173000,11.5166667,545.4,41.50,,8,0.90,1\n")
+        );
+        assert_eq!(DataLogger::csv_row(&GpsData::default()), None);
+    }
+
+    #[test]
+    fn test_rotation() {
+        let dir = std::env::temp_dir().join(format!("gps-monitor-data-log-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let mut logger = DataLogger::new(DataLog {
+            enabled: true,
+            output_dir: dir.to_string_lossy().into_owned(),
+            max_size_mb: 0,
+            interval: Some(RotationInterval::Hourly),
+        });
+
+        let start = Utc.with_ymd_and_hms(2024, 5, 1, 12, 59, 58).unwrap();
+        let second = chrono::Duration::seconds(1);
+        assert!(logger.record(&fix(start), start).unwrap());
+        let first_path = logger.current_path().unwrap().to_path_buf();
+
+        // The same fix seen again after another sentence isn't repeated
+        assert!(!logger.record(&fix(start), start).unwrap());
+        assert!(logger.record(&fix(start + second), start + second).unwrap());
+        assert_eq!(logger.current_path(), Some(first_path.as_path()));
+
+        // A new hour starts a new file
+        let next_hour = start + second * 2;
+        assert!(logger.record(&fix(next_hour), next_hour).unwrap());
+        assert_ne!(logger.current_path(), Some(first_path.as_path()));
+
+        let text = fs::read_to_string(&first_path).unwrap();
+        assert_eq!(text.lines().count(), 3);
+        assert_eq!(text.lines().next(), Some(CSV_HEADER));
+
+        // Past the size limit the next row goes to a new file
+        logger.options.max_size_mb = 1;
+        let path = logger.current_path().unwrap().to_path_buf();
+        let row_len = DataLogger::csv_row(&fix(next_hour)).unwrap().len();
+        assert!(!logger.should_rotate(row_len, &next_hour));
+        logger.bytes_written = 1024 * 1024;
+        assert!(logger.should_rotate(row_len, &next_hour));
+        assert!(logger.record(&fix(next_hour + second), next_hour + second).unwrap());
+        assert_ne!(logger.current_path(), Some(path.as_path()));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_one_row_per_epoch() {
+        let dir = std::env::temp_dir().join(format!("gps-monitor-data-log-epoch-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let mut monitor = GpsMonitor::new();
+        let writer = DataLogger::new(DataLog {
+            enabled: true,
+            output_dir: dir.to_string_lossy().into_owned(),
+            max_size_mb: 0,
+            interval: None,
+        }).attach(&mut monitor);
+
+        for line in [
+            "$GPRMC,123519,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W*6A",
+            "$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47",
+            "$GPGSA,A,3,05,12,,,,,,,,,,,2.5,1.3,2.1*3F",
+            "$GPGSV,1,1,02,05,45,090,40,12,30,270,35*71",
+            "$GPRMC,123520,A,4807.040,N,01131.000,E,022.4,084.4,230394,003.1,W*6F",
+            "$GPGGA,123520,4807.040,N,01131.000,E,1,08,0.9,545.6,M,46.9,M,,*40",
+        ] {
+            monitor.ingest_sentence(line);
+        }
+        // The last observer goes with the monitor, which ends the writer
+        drop(monitor);
+        writer.join().unwrap();
+
+        let files: Vec<_> = fs::read_dir(&dir).unwrap().map(|entry| entry.unwrap().path()).collect();
+        assert_eq!(files.len(), 1);
+        let text = fs::read_to_string(&files[0]).unwrap();
+        let rows: Vec<_> = text.lines().skip(1).collect();
+        assert_eq!(rows.len(), 2, "{}", text);
+        assert!(rows[0].contains(",48.1173000,11.5166667,545.4,"), "{}", rows[0]);
+        assert!(rows[1].contains(",48.1173333,11.5166667,545.6,"), "{}", rows[1]);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,137 @@
+// src/display/gui/antenna_assistant.rs v1
+//! Antenna placement view: large live signal figures and a sky plot for
+//! watching reception change while moving an antenna
+
+use crate::gps::GpsData;
+use eframe::egui;
+
+use super::skyplot::SkyPlot;
+
+/// Font size of the main figures
+const FIGURE_SIZE: f32 = 56.0;
+
+/// Best readings seen since the view was opened or reset
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AntennaAssistant {
+    best_used: usize,
+    best_average_snr: Option<f64>,
+}
+
+impl AntennaAssistant {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Raise the high-water marks from the latest data
+    pub fn update(&mut self, data: &GpsData) {
+        self.best_used = self.best_used.max(data.satellites_used());
+        if let Some(snr) = data.average_used_snr() {
+            self.best_average_snr = Some(self.best_average_snr.map_or(snr, |best| best.max(snr)));
+        }
+    }
+
+    /// Forget the best readings, e.g. after moving to a new mounting spot
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn render(&mut self, ui: &mut egui::Ui, data: &GpsData, sky_plot: &SkyPlot) {
+        self.update(data);
+
+        ui.horizontal(|ui| {
+            ui.heading("📡 Antenna Placement");
+            if ui.button("Reset best").on_hover_text("Start the best-so-far marks again, e.g. at a new spot").clicked() {
+                self.reset();
+            }
+        });
+        ui.separator();
+
+        let available = ui.available_size();
+        ui.horizontal(|ui| {
+            ui.allocate_ui_with_layout(
+                [available.x * 0.45, available.y].into(),
+                egui::Layout::top_down(egui::Align::Min),
+                |ui| self.render_figures(ui, data),
+            );
+
+            ui.separator();
+
+            ui.vertical(|ui| sky_plot.render(ui, data));
+        });
+    }
+
+    fn render_figures(&self, ui: &mut egui::Ui, data: &GpsData) {
+        let used = data.satellites_used();
+        let average_snr = data.average_used_snr();
+
+        egui::Grid::new("antenna_figures")
+            .num_columns(2)
+            .spacing([30.0, 20.0])
+            .show(ui, |ui| {
+                figure(ui, "Used", used.to_string(), Some(format!("best {}", self.best_used)));
+                figure(ui, "Visible", data.satellites_info.len().to_string(), None);
+                ui.end_row();
+
+                figure(
+                    ui,
+                    "Avg SNR",
+                    average_snr.map_or("--".to_string(), |snr| format!("{:.1}", snr)),
+                    Some(self.best_average_snr.map_or("best --".to_string(), |snr| format!("best {:.1} dB", snr))),
+                );
+                figure(ui, "HDOP", data.hdop.map_or("--".to_string(), |hdop| format!("{:.1}", hdop)), None);
+                ui.end_row();
+            });
+
+        ui.add_space(10.0);
+        let (score, label) = data.signal_health();
+        ui.add(
+            egui::ProgressBar::new(score as f32 / 100.0)
+                .text(egui::RichText::new(format!("Health {} {}", score, label)).size(18.0)),
+        );
+    }
+}
+
+/// One large labelled figure with an optional note beneath it
+fn figure(ui: &mut egui::Ui, label: &str, value: String, note: Option<String>) {
+    ui.vertical(|ui| {
+        ui.label(egui::RichText::new(label).size(18.0).weak());
+        ui.label(egui::RichText::new(value).size(FIGURE_SIZE).strong().monospace());
+        if let Some(note) = note {
+            ui.label(egui::RichText::new(note).size(16.0).color(egui::Color32::from_rgb(0, 160, 0)));
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gps::data::SatelliteInfo;
+
+    fn satellite(prn: u8, snr: f32, used: bool) -> SatelliteInfo {
+        SatelliteInfo { snr: Some(snr), used, ..SatelliteInfo::new(prn) }
+    }
+
+    #[test]
+    fn test_best_so_far_only_rises() {
+        let mut assistant = AntennaAssistant::new();
+        let mut data = GpsData::new();
+        data.satellites_info = vec![satellite(1, 30.0, true), satellite(2, 40.0, true), satellite(3, 20.0, false)];
+        assistant.update(&data);
+        assert_eq!(assistant.best_used, 2);
+        assert_eq!(assistant.best_average_snr, Some(35.0));
+
+        // A worse position keeps the marks
+        data.satellites_info = vec![satellite(1, 25.0, true)];
+        assistant.update(&data);
+        assert_eq!(assistant.best_used, 2);
+        assert_eq!(assistant.best_average_snr, Some(35.0));
+
+        data.satellites_info = (1..=5).map(|prn| satellite(prn, 42.0, true)).collect();
+        assistant.update(&data);
+        assert_eq!(assistant.best_used, 5);
+        assert_eq!(assistant.best_average_snr, Some(42.0));
+
+        assistant.reset();
+        assert_eq!(assistant, AntennaAssistant::new());
+    }
+}
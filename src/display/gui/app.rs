@@ -1,20 +1,26 @@
-// src/display/gui/app.rs v10
+// src/display/gui/app.rs v21
 //! Main GUI application structure - Pure egui implementation
 
-use crate::{gps::GpsData, config::GpsConfig, monitor::{GpsMonitor, GpsSource}, map::TileCache};
+use crate::{gps::{ConnectionStatus, FixValidity, GpsData, LOW_UPDATE_RATE_FRACTION}, config::GpsConfig, data_log::DataLogger, headless::wait_for_shutdown_signal, lock::RecoverPoison, monitor::{GpsMonitor, GpsSource}, map::TileCache, odometer::{Odometer, ReferenceDistance}, track_recorder::{RecordingCheckpoint, RecordingStatus}, waypoint::Session};
 use chrono::{DateTime, Utc};
 use eframe::egui;
 use std::{
+    ops::Deref,
     sync::{
         atomic::{AtomicBool, Ordering},
-        Arc, RwLock,
+        Arc, RwLock, RwLockReadGuard,
     },
-    time::Duration,
+    time::{Duration, Instant},
     path::PathBuf,
+    io::Write,
 };
 use tokio::runtime::Runtime;
+#[cfg(feature = "obd")]
+use crate::obd::{ObdData, ObdReader};
+#[cfg(feature = "status-server")]
+use crate::status_server::StatusServer;
 
-use super::{panels, satellites::SatellitePanel, skyplot, settings::SettingsWindow, waypoint_dialog::WaypointDialog, map_window::MapWindow};
+use super::{panels::{self, DataPanel}, recording_indicator, satellites::{self, SatelliteHighs, SatellitePanel}, skyplot::SkyPlot, settings::SettingsWindow, waypoint_dialog::WaypointDialog, map_window::MapWindow, nmea_inspector::NmeaInspector, fix_events::FixEventsWindow, speedometer, antenna_assistant::AntennaAssistant};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum SatelliteSortColumn {
@@ -32,6 +38,74 @@ enum ConnectionState {
     Disconnected,
     Connecting,
     Connected,
+    Restarting(Instant),  // Stopped; connects again at this time
+}
+
+impl ConnectionState {
+    /// A connection is running or being set up
+    fn is_active(&self) -> bool {
+        matches!(self, ConnectionState::Connecting | ConnectionState::Connected)
+    }
+
+    /// Time left before a pending restart connects again, zero once it is due
+    fn restart_remaining(&self, now: Instant) -> Option<Duration> {
+        match self {
+            ConnectionState::Restarting(due) => Some(due.saturating_duration_since(now)),
+            _ => None,
+        }
+    }
+}
+
+/// Notices when data stops arriving while connected. Times come from the wall
+/// clock, since `Instant` may not advance while the machine is asleep.
+#[derive(Debug, Clone, Default)]
+struct DataWatchdog {
+    last_seen: Option<(u64, Option<DateTime<Utc>>)>,  // Sentence count and fix time
+    changed_at: Option<DateTime<Utc>>,
+}
+
+impl DataWatchdog {
+    /// Note the latest sentence count and fix time. Returns how long they
+    /// have gone unchanged.
+    fn observe(&mut self, sentences: u64, fix_time: Option<DateTime<Utc>>, now: DateTime<Utc>) -> Duration {
+        if self.last_seen != Some((sentences, fix_time)) {
+            self.last_seen = Some((sentences, fix_time));
+            self.changed_at = Some(now);
+        }
+        let changed_at = *self.changed_at.get_or_insert(now);
+        (now - changed_at).to_std().unwrap_or(Duration::ZERO)
+    }
+}
+
+/// What fills the main window
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum MainLayout {
+    Standard,
+    AntennaPlacement,
+}
+
+/// Data the panels draw from: the live shared data, or a snapshot while the display is frozen
+enum DisplayData<'a> {
+    Live(RwLockReadGuard<'a, GpsData>),
+    Frozen(&'a GpsData),
+}
+
+impl Deref for DisplayData<'_> {
+    type Target = GpsData;
+
+    fn deref(&self) -> &GpsData {
+        match self {
+            DisplayData::Live(data) => data,
+            DisplayData::Frozen(data) => data,
+        }
+    }
+}
+
+fn display_data<'a>(live: &'a RwLock<GpsData>, frozen: &'a Option<GpsData>) -> DisplayData<'a> {
+    match frozen {
+        Some(snapshot) => DisplayData::Frozen(snapshot),
+        None => DisplayData::Live(live.read_recover()),
+    }
 }
 
 pub struct GpsGuiApp {
@@ -43,13 +117,51 @@ pub struct GpsGuiApp {
     settings_window: SettingsWindow,
     waypoint_dialog: WaypointDialog,
     map_window: MapWindow,
+    nmea_inspector: NmeaInspector,
+    fix_events: FixEventsWindow,
+    layout: MainLayout,
+    antenna_assistant: AntennaAssistant,
+    satellite_highs: SatelliteHighs,  // Since connecting or the last reset
+    frozen: Option<GpsData>,  // Shown instead of the live data while the display is frozen
     monitor: Option<GpsMonitor>,
     connection_state: ConnectionState,
     error_message: Option<String>,
     config: GpsConfig,
     runtime: Arc<Runtime>,
+    shutdown_requested: Arc<AtomicBool>,
+    last_fix_time: Option<Instant>,
+    no_fix_warning_active: bool,
+    low_rate_since: Option<Instant>,  // When the measured update rate fell below expected
+    low_rate_warned: bool,
+    data_watchdog: DataWatchdog,
+    focused: bool,  // Window has focus; redraws slow down in the background
+    session_path: PathBuf,  // Waypoints, tracks and routes auto-saved here
+    last_autosave: Instant,
+    odometer: Arc<RwLock<Odometer>>,  // Counted by the monitor, saved from here
+    odometer_path: PathBuf,
+    reference_distance: Option<ReferenceDistance>,  // Path distance since a marked waypoint
+    #[cfg(feature = "obd")]
+    obd_data: Arc<RwLock<ObdData>>,
+    #[cfg(feature = "obd")]
+    obd_reader: Option<tokio::task::JoinHandle<()>>,
+    #[cfg(feature = "status-server")]
+    status_server: Option<tokio::task::JoinHandle<()>>,
 }
 
+/// Save a track style edited on the map once it has been left alone this long
+const MAP_STYLE_SAVE_DELAY: Duration = Duration::from_secs(1);
+
+/// Track points are taken as frames are drawn, so while recording the
+/// window is redrawn at least this often even in the background
+const RECORDING_REPAINT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How often background work shown in a window (an offline download, baud
+/// rate detection) is checked on while it runs
+const BACKGROUND_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Warn when the measured update rate stays low for this long
+const LOW_RATE_WARNING_DELAY: Duration = Duration::from_secs(10);
+
 impl GpsGuiApp {
     pub fn new_from_config(config: GpsConfig) -> Self {
         let data = Arc::new(RwLock::new(GpsData::new()));
@@ -61,9 +173,41 @@ impl GpsGuiApp {
         );
         
         // Create tile cache directory
-        let cache_dir = Self::get_cache_directory();
-        let tile_cache = TileCache::new(cache_dir)
+        let (cache_dir, cache_warning) = Self::tile_cache_directory(&config);
+        let mut tile_cache = TileCache::with_user_agent(cache_dir.clone(), runtime.handle().clone(), &config.tile_user_agent_header())
+            .or_else(|_| TileCache::new(cache_dir, runtime.handle().clone()))
             .expect("Failed to create tile cache");
+        tile_cache.set_limits(config.map_options.memory_tiles, config.map_options.concurrent_downloads);
+        let mut map_window = MapWindow::new(tile_cache);
+        map_window.set_marker_style(config.marker_style.clone());
+        map_window.set_options(config.map_options.clone());
+        map_window.set_time_zone(config.time_zone);
+        map_window.set_coordinate_decimals(config.coordinate_decimals);
+        map_window.set_coordinate_format(config.coordinate_format);
+        map_window.set_distance_units(config.distance_units);
+        map_window.set_north_reference(config.north_reference);
+        let mut waypoint_dialog = WaypointDialog::new();
+        waypoint_dialog.set_checkpoint_path(RecordingCheckpoint::default_path());
+        waypoint_dialog.set_time_zone(config.time_zone);
+        waypoint_dialog.set_coordinate_decimals(config.coordinate_decimals);
+        waypoint_dialog.set_coordinate_format(config.coordinate_format);
+        waypoint_dialog.set_distance_units(config.distance_units);
+        waypoint_dialog.set_speed_unit(config.display_speed_unit());
+        waypoint_dialog.set_csv_export(config.csv_export.clone());
+        waypoint_dialog.set_quality_gate(config.waypoint_quality_gate.clone());
+        waypoint_dialog.set_fix_hold(config.waypoint_fix_hold);
+        waypoint_dialog.set_track_rotation(&config.track_rotation);
+        waypoint_dialog.set_exclude_2d_altitude(config.exclude_2d_altitude);
+        waypoint_dialog.set_satellite_snapshots(config.record_satellite_snapshots);
+        waypoint_dialog.set_recent_exports(config.recent_exports.clone());
+        waypoint_dialog.set_import_thinning(config.import_thinning.clone());
+        let mut fix_events = FixEventsWindow::new();
+        fix_events.set_export_dir(config.data_log.output_path());
+        let odometer_path = Odometer::default_path();
+        let odometer = Odometer::load(&odometer_path).unwrap_or_else(|e| {
+            eprintln!("Warning: Failed to load odometer: {}", e);
+            Odometer::new()
+        });
         
         let mut app = Self {
             data,
@@ -72,39 +216,134 @@ impl GpsGuiApp {
             sat_sort_column: SatelliteSortColumn::Constellation,
             sat_sort_ascending: true,
             settings_window: SettingsWindow::new(config.clone()),
-            waypoint_dialog: WaypointDialog::new(),
-            map_window: MapWindow::new(tile_cache),
+            waypoint_dialog,
+            map_window,
+            nmea_inspector: NmeaInspector::new(),
+            fix_events,
+            layout: MainLayout::Standard,
+            antenna_assistant: AntennaAssistant::new(),
+            satellite_highs: SatelliteHighs::default(),
+            frozen: None,
             monitor: None,
             connection_state: ConnectionState::Disconnected,
             error_message: None,
             config,
             runtime,
+            shutdown_requested: Arc::new(AtomicBool::new(false)),
+            last_fix_time: None,
+            no_fix_warning_active: false,
+            low_rate_since: None,
+            low_rate_warned: false,
+            data_watchdog: DataWatchdog::default(),
+            focused: true,
+            session_path: Session::default_path(),
+            last_autosave: Instant::now(),
+            odometer: Arc::new(RwLock::new(odometer)),
+            odometer_path,
+            reference_distance: None,
+            #[cfg(feature = "obd")]
+            obd_data: Arc::new(RwLock::new(ObdData::new())),
+            #[cfg(feature = "obd")]
+            obd_reader: None,
+            #[cfg(feature = "status-server")]
+            status_server: None,
         };
+
+        #[cfg(feature = "status-server")]
+        app.restart_status_server();
+
+        #[cfg(feature = "obd")]
+        app.waypoint_dialog.set_obd_source(Arc::clone(&app.obd_data));
         
-        // Auto-connect on startup
-        app.start_connection();
+        // Bring back the waypoints, tracks and routes of the last run
+        if app.config.session_autosave.enabled {
+            app.waypoint_dialog.restore_session(&app.session_path);
+        }
+
+        // Offer to resume or save a recording interrupted by a crash
+        if app.waypoint_dialog.has_pending_checkpoint() {
+            app.waypoint_dialog.open = true;
+        }
+
+        // Auto-connect on startup unless the user only wants to review tracks offline
+        if app.config.auto_connect {
+            app.start_connection();
+        }
+
+        if app.error_message.is_none() {
+            app.error_message = cache_warning;
+        }
         
         app
     }
 
-    fn get_cache_directory() -> PathBuf {
-        let mut path = dirs::cache_dir().unwrap_or_else(|| PathBuf::from("."));
-        path.push("gps-monitor");
-        path.push("tiles");
-        path
+    /// Correct the elevation of waypoints saved from the GUI, e.g. from a DEM
+    pub fn set_elevation_corrector(&mut self, corrector: Box<dyn crate::waypoint::ElevationCorrector>) {
+        self.waypoint_dialog.set_elevation_corrector(corrector);
+    }
+
+    /// Offer "snap to road" for saved tracks, using e.g. a map-matching service
+    pub fn set_map_matcher(&mut self, matcher: Box<dyn crate::waypoint::MapMatcher>) {
+        self.waypoint_dialog.set_map_matcher(matcher, self.runtime.handle().clone());
+    }
+
+    /// Install Ctrl+C / SIGTERM handlers that stop the connection and close the window
+    pub fn install_signal_handler(&self, ctx: egui::Context) {
+        let running = Arc::clone(&self.running);
+        let shutdown_requested = Arc::clone(&self.shutdown_requested);
+
+        self.runtime.spawn(async move {
+            wait_for_shutdown_signal().await;
+            println!("Shutdown signal received, closing...");
+            running.store(false, Ordering::Relaxed);
+            shutdown_requested.store(true, Ordering::Relaxed);
+            ctx.request_repaint();
+        });
+    }
+
+    /// Configured tile cache directory, or the default plus a warning when it can't be written
+    fn tile_cache_directory(config: &GpsConfig) -> (PathBuf, Option<String>) {
+        let path = config.tile_cache_path();
+        match TileCache::check_writable(&path) {
+            Ok(()) => (path, None),
+            Err(e) if config.tile_cache_dir.is_some() => {
+                let warning = format!(
+                    "Tile cache {} is not writable ({}); using the default location",
+                    path.display(),
+                    e
+                );
+                eprintln!("Warning: {}", warning);
+                (GpsConfig::default_tile_cache_path(), Some(warning))
+            }
+            // Nothing to fall back to; TileCache::new reports the problem
+            Err(_) => (path, None),
+        }
     }
 
     fn start_connection(&mut self) {
+        if let Err(e) = self.config.validate() {
+            self.error_message = Some(format!("{} - fix it in Settings", e));
+            self.connection_state = ConnectionState::Disconnected;
+            return;
+        }
+
         self.connection_state = ConnectionState::Connecting;
         self.error_message = None;
+        self.satellite_highs.reset();
         self.running.store(true, Ordering::Relaxed);
         
-        let monitor = GpsMonitor::new_with_shared(
+        let mut monitor = GpsMonitor::new_with_shared(
             Arc::clone(&self.data),
             Arc::clone(&self.running)
         );
+        let satellite_timeout = self.config.satellite_timeout_secs;
+        monitor.set_satellite_max_age((satellite_timeout > 0).then(|| Duration::from_secs(satellite_timeout)));
+        if self.config.data_log.enabled {
+            DataLogger::new(self.config.data_log.clone()).attach(&mut monitor);
+        }
+        Odometer::attach(&self.odometer, &mut monitor);
         
-        let source = self.create_gps_source();
+        let source = GpsSource::from_config(&self.config);
         
         // Start connection in background using our runtime
         let monitor_clone = monitor.clone();
@@ -117,55 +356,97 @@ impl GpsGuiApp {
             });
         });
         
+        #[cfg(feature = "obd")]
+        self.start_obd_reader();
+
         self.monitor = Some(monitor);
         self.connection_state = ConnectionState::Connected;
+        // Start the no-fix clock from the moment we expect data
+        self.last_fix_time = Some(Instant::now());
+    }
+
+    /// Poll the OBD-II adapter alongside the GPS while `running` is set,
+    /// replacing any reader already running
+    #[cfg(feature = "obd")]
+    fn start_obd_reader(&mut self) {
+        self.stop_obd_reader();
+        let obd = &self.config.obd;
+        if !obd.enabled || obd.port.is_empty() {
+            return;
+        }
+
+        let reader = ObdReader::new(obd.port.clone(), obd.baudrate, Duration::from_millis(obd.poll_interval_ms));
+        let obd_data = Arc::clone(&self.obd_data);
+        let running = Arc::clone(&self.running);
+        self.obd_reader = Some(self.runtime.spawn(async move { reader.run(obd_data, running).await }));
+    }
+
+    /// Stop the OBD-II reader now, releasing the port, rather than when it
+    /// next notices `running` was cleared
+    #[cfg(feature = "obd")]
+    fn stop_obd_reader(&mut self) {
+        if let Some(task) = self.obd_reader.take() {
+            task.abort();
+        }
+    }
+
+    /// Show why the OBD-II adapter was lost; the reader keeps reconnecting
+    #[cfg(feature = "obd")]
+    fn check_obd_error(&mut self) {
+        if let Some(e) = self.obd_data.write_recover().error.take() {
+            self.error_message = Some(format!("⚠ OBD-II: {}, reconnecting", e));
+        }
+    }
+
+    /// Serve GET /status if enabled, replacing any server already running
+    #[cfg(feature = "status-server")]
+    fn restart_status_server(&mut self) {
+        if let Some(task) = self.status_server.take() {
+            task.abort();
+        }
+        let endpoint = &self.config.status_endpoint;
+        if !endpoint.enabled {
+            return;
+        }
+
+        match StatusServer::bind(&endpoint.address, Arc::clone(&self.data)) {
+            Ok(server) => {
+                if let Ok(addr) = server.local_addr() {
+                    println!("Status endpoint at http://{}/status", addr);
+                }
+                self.status_server = Some(self.runtime.spawn(server.run()));
+            }
+            Err(e) => self.error_message = Some(e.to_string()),
+        }
     }
 
     fn stop_connection(&mut self) {
         self.running.store(false, Ordering::Relaxed);
+        #[cfg(feature = "obd")]
+        self.stop_obd_reader();
         self.monitor = None;
         self.connection_state = ConnectionState::Disconnected;
+        self.last_fix_time = None;
+        self.no_fix_warning_active = false;
+        self.low_rate_since = None;
+        self.low_rate_warned = false;
+        self.odometer.write_recover().reset_position();
     }
 
+    /// Stop now and connect again after the configured delay, giving the old
+    /// read loop time to notice and release the device. The UI keeps running
+    /// meanwhile; `poll_restart` makes the new connection once it is due.
     fn restart_connection(&mut self) {
         self.stop_connection();
-        // Small delay to ensure cleanup
-        std::thread::sleep(Duration::from_millis(500));
-        self.start_connection();
+        let delay = Duration::from_millis(u64::from(self.config.restart_delay_ms));
+        self.connection_state = ConnectionState::Restarting(Instant::now() + delay);
     }
 
-    fn create_gps_source(&self) -> GpsSource {
-        match self.config.source_type.as_str() {
-            "serial" => {
-                let port = self.config.serial_port.clone().unwrap_or_default();
-                let baudrate = self.config.serial_baudrate.unwrap_or(9600);
-                GpsSource::Serial { port, baudrate }
-            }
-            "gpsd" => {
-                let host = self.config.gpsd_host.clone().unwrap_or_else(|| "localhost".to_string());
-                let port = self.config.gpsd_port.unwrap_or(2947);
-                GpsSource::Gpsd { host, port }
-            }
-            #[cfg(windows)]
-            "windows" => {
-                let accuracy = self.config.windows_accuracy.unwrap_or(10);
-                let interval = self.config.windows_interval.unwrap_or(1);
-                GpsSource::Windows { accuracy, interval }
-            }
-            _ => {
-                // Default to platform-specific source
-                #[cfg(windows)]
-                {
-                    GpsSource::Windows { accuracy: 10, interval: 1 }
-                }
-                #[cfg(not(windows))]
-                {
-                    GpsSource::Gpsd {
-                        host: "localhost".to_string(),
-                        port: 2947,
-                    }
-                }
-            }
+    fn poll_restart(&mut self, ctx: &egui::Context) {
+        match self.connection_state.restart_remaining(Instant::now()) {
+            Some(remaining) if remaining.is_zero() => self.start_connection(),
+            Some(remaining) => ctx.request_repaint_after(remaining),
+            None => {}
         }
     }
 
@@ -177,15 +458,9 @@ impl GpsGuiApp {
                 
                 // Connection state indicator
                 let (status_color, status_text) = match self.connection_state {
-                    ConnectionState::Connected => {
-                        let data = self.data.read().unwrap();
-                        if data.timestamp.is_some() && data.is_recent() {
-                            (egui::Color32::GREEN, "Connected")
-                        } else {
-                            (egui::Color32::YELLOW, "Waiting for data")
-                        }
-                    }
+                    ConnectionState::Connected => connected_status(&self.data.read_recover(), self.config.min_satellites_used),
                     ConnectionState::Connecting => (egui::Color32::YELLOW, "Connecting..."),
+                    ConnectionState::Restarting(_) => (egui::Color32::YELLOW, "Restarting..."),
                     ConnectionState::Disconnected => (egui::Color32::RED, "Disconnected"),
                 };
                 
@@ -193,9 +468,12 @@ impl GpsGuiApp {
                 ui.label(status_text);
                 
                 // Last update timestamp
-                let data = self.data.read().unwrap();
+                let data = display_data(&self.data, &self.frozen);
                 let timestamp_str = match data.timestamp {
-                    Some(ts) => ts.format("%H:%M:%S UTC").to_string(),
+                    Some(ts) if !self.connection_state.is_active() => {
+                        format!("{} (disconnected)", self.config.time_zone.format_with_zone(&ts, "%H:%M:%S"))
+                    }
+                    Some(ts) => self.config.time_zone.format_with_zone(&ts, "%H:%M:%S"),
                     None => "No data".to_string(),
                 };
                 ui.label(format!("Last Update: {}", timestamp_str));
@@ -205,6 +483,7 @@ impl GpsGuiApp {
                     ui.label(format!("Source: {}", source));
                 }
                 drop(data);
+                recording_indicator::render(ui, self.recording_status());
 
                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                     if ui.button("❌ Exit").clicked() {
@@ -223,6 +502,30 @@ impl GpsGuiApp {
                     if ui.button("🗺 Map").clicked() {
                         self.map_window.open = true;
                     }
+
+                    if ui.button("📉 Fix Events")
+                        .on_hover_text("When the fix was acquired, lost or changed mode")
+                        .clicked()
+                    {
+                        self.fix_events.open = true;
+                    }
+
+                    let mut antenna = self.layout == MainLayout::AntennaPlacement;
+                    if ui.toggle_value(&mut antenna, "📡 Antenna")
+                        .on_hover_text("Large signal figures for positioning an antenna")
+                        .changed()
+                    {
+                        self.layout = if antenna { MainLayout::AntennaPlacement } else { MainLayout::Standard };
+                        self.antenna_assistant.reset();
+                    }
+
+                    let mut frozen = self.frozen.is_some();
+                    if ui.toggle_value(&mut frozen, "❄ Freeze")
+                        .on_hover_text("Hold the display on the current data; the connection keeps running")
+                        .changed()
+                    {
+                        self.set_frozen(frozen);
+                    }
                     
                     if ui.button("🔄 Restart").clicked() {
                         self.restart_connection();
@@ -230,7 +533,7 @@ impl GpsGuiApp {
                     
                     // Connection control
                     match self.connection_state {
-                        ConnectionState::Connected | ConnectionState::Connecting => {
+                        ConnectionState::Connected | ConnectionState::Connecting | ConnectionState::Restarting(_) => {
                             if ui.button("⏸ Disconnect").clicked() {
                                 self.stop_connection();
                             }
@@ -246,7 +549,153 @@ impl GpsGuiApp {
         });
     }
 
-    fn render_bottom_panel(&self, ctx: &egui::Context) {
+    /// Pause the panels on a snapshot of the current data, or go back to live data
+    fn set_frozen(&mut self, frozen: bool) {
+        self.frozen = frozen.then(|| self.data.read_recover().clone());
+    }
+
+    fn render_frozen_banner(&mut self, ctx: &egui::Context) {
+        let Some(ref snapshot) = self.frozen else {
+            return;
+        };
+        let taken = match snapshot.timestamp {
+            Some(ts) => self.config.time_zone.format_with_zone(&ts, "%H:%M:%S"),
+            None => "before any data".to_string(),
+        };
+
+        let mut unfreeze = false;
+        egui::TopBottomPanel::top("frozen_banner")
+            .frame(egui::Frame::none().fill(egui::Color32::from_rgb(30, 70, 120)).inner_margin(4.0))
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(egui::RichText::new("❄ Frozen").strong().color(egui::Color32::WHITE));
+                    ui.label(egui::RichText::new(format!(
+                        "Showing data from {}; live updates continue in the background",
+                        taken
+                    )).color(egui::Color32::WHITE));
+                    unfreeze = ui.button("Resume live").clicked();
+                });
+            });
+        if unfreeze {
+            self.set_frozen(false);
+        }
+    }
+
+    /// Warn once when fixes keep arriving well below the expected update rate,
+    /// e.g. a receiver set to 10 Hz behind a link too slow to carry it
+    fn check_update_rate(&mut self) {
+        let expected = self.config.expected_update_hz;
+        let measured = self.data.read_recover().update_rate();
+        let low = match measured {
+            // No fixes at all is the no-fix warning's job
+            Some(rate) if expected > 0 && rate > 0.0 => rate < expected as f64 * LOW_UPDATE_RATE_FRACTION,
+            _ => false,
+        };
+        if !low || self.connection_state != ConnectionState::Connected {
+            self.low_rate_since = None;
+            self.low_rate_warned = false;
+            return;
+        }
+
+        let since = *self.low_rate_since.get_or_insert_with(Instant::now);
+        if !self.low_rate_warned && since.elapsed() >= LOW_RATE_WARNING_DELAY {
+            self.low_rate_warned = true;
+            self.error_message = Some(format!(
+                "⚠ Receiving {:.1} fixes/s, expected {} Hz - check the port speed and receiver output rate",
+                measured.unwrap_or(0.0),
+                expected
+            ));
+        }
+    }
+
+    /// Restart a connection whose data stopped arriving, e.g. because its read
+    /// task ended while the machine was asleep and nothing reported it
+    fn check_data_stall(&mut self) {
+        let stall = &self.config.stall_reconnect;
+        let monitor = self.monitor.as_ref()
+            .filter(|_| stall.enabled && self.connection_state == ConnectionState::Connected);
+        let Some(monitor) = monitor else {
+            self.data_watchdog = DataWatchdog::default();
+            return;
+        };
+
+        let fix_time = self.data.read_recover().timestamp;
+        let quiet = self.data_watchdog.observe(monitor.sentence_counts().total(), fix_time, Utc::now());
+        if stall.is_stalled(quiet) {
+            self.restart_connection();
+            self.error_message = Some(format!("⚠ No data for {} seconds, reconnecting", quiet.as_secs()));
+        }
+    }
+
+    /// Track time since the last valid fix and warn once it exceeds the threshold
+    fn check_no_fix_warning(&mut self, ctx: &egui::Context) {
+        let warning = &self.config.no_fix_warning;
+        if !warning.enabled || self.connection_state != ConnectionState::Connected {
+            self.no_fix_warning_active = false;
+            return;
+        }
+
+        if self.data.read_recover().has_valid_fix() {
+            self.last_fix_time = Some(Instant::now());
+            self.no_fix_warning_active = false;
+            return;
+        }
+
+        let elapsed = self.last_fix_time.map_or(Duration::ZERO, |t| t.elapsed());
+        if elapsed < Duration::from_secs(warning.threshold_secs) {
+            return;
+        }
+
+        if !self.no_fix_warning_active {
+            self.no_fix_warning_active = true;
+            if warning.beep {
+                // Terminal bell
+                print!("\x07");
+                let _ = std::io::stdout().flush();
+            }
+            self.error_message = Some(format!(
+                "⚠ No valid GPS fix for {} seconds",
+                elapsed.as_secs()
+            ));
+        }
+
+        // Flashing banner
+        let flash_on = (elapsed.as_millis() / 500).is_multiple_of(2);
+        let fill = if flash_on {
+            egui::Color32::from_rgb(180, 0, 0)
+        } else {
+            egui::Color32::from_rgb(90, 0, 0)
+        };
+        egui::TopBottomPanel::top("no_fix_banner")
+            .frame(egui::Frame::default().fill(fill).inner_margin(4.0))
+            .show(ctx, |ui| {
+                ui.vertical_centered(|ui| {
+                    ui.colored_label(
+                        egui::Color32::WHITE,
+                        format!("⚠ NO GPS FIX for {}s", elapsed.as_secs()),
+                    );
+                });
+            });
+        self.request_repaint_after(ctx, Duration::from_millis(500));
+    }
+
+    /// Banner for a fault the receiver reported itself, e.g. an open antenna
+    fn render_receiver_fault_banner(&self, ctx: &egui::Context) {
+        let fault = self.data.read_recover().receiver_fault(Utc::now()).map(|message| message.text.clone());
+        let Some(text) = fault else {
+            return;
+        };
+
+        egui::TopBottomPanel::top("receiver_fault_banner")
+            .frame(egui::Frame::default().fill(egui::Color32::from_rgb(160, 90, 0)).inner_margin(4.0))
+            .show(ctx, |ui| {
+                ui.vertical_centered(|ui| {
+                    ui.colored_label(egui::Color32::WHITE, format!("⚠ Receiver: {}", text));
+                });
+            });
+    }
+
+    fn render_bottom_panel(&mut self, ctx: &egui::Context) {
         egui::TopBottomPanel::bottom("bottom_panel")
             .resizable(true)
             .default_height(80.0)
@@ -254,11 +703,16 @@ impl GpsGuiApp {
                 ui.label("📝 Latest NMEA Sentences / Raw Data");
                 ui.separator();
                 
+                let mut clicked = None;
                 egui::ScrollArea::vertical().max_height(60.0).show(ui, |ui| {
-                    let data = self.data.read().unwrap();
+                    let data = display_data(&self.data, &self.frozen);
                     if !data.raw_history.is_empty() {
                         for sentence in data.raw_history.iter().rev() {
-                            ui.monospace(sentence);
+                            let label = egui::Label::new(egui::RichText::new(sentence).monospace())
+                                .sense(egui::Sense::click());
+                            if ui.add(label).on_hover_text("Click to inspect").clicked() {
+                                clicked = Some(sentence.clone());
+                            }
                         }
                     } else if !data.raw_data.is_empty() {
                         ui.monospace(&data.raw_data);
@@ -266,9 +720,24 @@ impl GpsGuiApp {
                         ui.weak("No data received");
                     }
                 });
+
+                if let Some(sentence) = clicked {
+                    self.nmea_inspector.inspect(&sentence, self.config.rmc_speed_unit);
+                }
             });
     }
 
+    /// Focused view for adjusting an antenna, refreshed as data arrives
+    fn render_antenna_content(&mut self, ctx: &egui::Context) {
+        self.request_repaint_after(ctx, Duration::from_millis(100));
+        egui::CentralPanel::default().show(ctx, |ui| {
+            let data = display_data(&self.data, &self.frozen);
+            // The whole sky matters when placing an antenna
+            let sky_plot = SkyPlot::from_config(&self.config, false);
+            self.antenna_assistant.render(ui, &data, &sky_plot);
+        });
+    }
+
     fn render_main_content(&mut self, ctx: &egui::Context) {
         egui::CentralPanel::default().show(ctx, |ui| {
             let available_size = ui.available_size();
@@ -285,8 +754,46 @@ impl GpsGuiApp {
                             ui.set_height(available_size.y - 10.0);
                             
                             egui::ScrollArea::vertical().show(ui, |ui| {
-                                let data = self.data.read().unwrap();
-                                panels::render_main_data_panel(ui, &data);
+                                {
+                                    let data = display_data(&self.data, &self.frozen);
+                                    let connected = self.connection_state.is_active();
+                                    let data_panel = DataPanel {
+                                        units: self.config.distance_units,
+                                        speed_unit: self.config.display_speed_unit(),
+                                        altitude_source: self.config.altitude_source,
+                                        north_reference: self.config.north_reference,
+                                        coordinate_decimals: self.config.coordinate_decimals,
+                                        coordinate_format: self.config.coordinate_format,
+                                        expected_update_hz: self.config.expected_update_hz,
+                                        min_satellites_used: self.config.min_satellites_used,
+                                        copy_templates: &self.config.copy_templates,
+                                    };
+                                    data_panel.render(ui, &data, connected);
+                                    if self.config.speedometer.enabled {
+                                        speedometer::render_speedometer(ui, data.speed.filter(|_| connected), self.config.speedometer.max_speed, self.config.display_speed_unit());
+                                    }
+                                }
+
+                                let lifetime_m = self.odometer.read_recover().lifetime_m();
+                                let since_reference = self.reference_distance.as_ref()
+                                    .map(|reference| (reference.name.as_str(), reference.distance(lifetime_m)));
+                                match panels::render_odometer_panel(ui, lifetime_m, since_reference, self.config.distance_units) {
+                                    Some(panels::OdometerAction::ResetLifetime) => {
+                                        if let Some(reference) = self.reference_distance.as_mut() {
+                                            reference.odometer_reset(lifetime_m);
+                                        }
+                                        self.odometer.write_recover().reset_lifetime();
+                                        self.save_odometer();
+                                    }
+                                    Some(panels::OdometerAction::ClearReference) => self.reference_distance = None,
+                                    None => {}
+                                }
+
+                                if let Some(ref monitor) = self.monitor {
+                                    panels::render_sentence_panel(ui, &monitor.sentence_counts());
+                                }
+
+                                panels::render_receiver_messages_panel(ui, &display_data(&self.data, &self.frozen).text_messages);
                             });
                         });
                     }
@@ -300,28 +807,40 @@ impl GpsGuiApp {
                     [right_width, available_size.y].into(),
                     egui::Layout::top_down(egui::Align::Min),
                     |ui| {
-                        let sky_plot_height = (available_size.y * 0.5).max(200.0).min(400.0);
-                        let satellite_table_height = available_size.y - sky_plot_height - 20.0;
+                        let sky_plot_height = (available_size.y * 0.5).clamp(200.0, 400.0);
+                        let satellite_table_height = available_size.y - sky_plot_height - 45.0;
+
+                        // Without a used list (e.g. no GSA) "Used only" would hide every satellite
+                        let used_known = display_data(&self.data, &self.frozen).reports_used_satellites();
+                        let used_only = self.config.used_satellites_only && used_known;
                         
                         // Sky plot (top section)
                         ui.group(|ui| {
                             ui.set_width(right_width - 10.0);
                             ui.set_height(sky_plot_height);
-                            let data = self.data.read().unwrap();
-                            skyplot::render_sky_plot(ui, &data);
+                            let data = display_data(&self.data, &self.frozen);
+                            SkyPlot::from_config(&self.config, used_only).render(ui, &data);
                         });
 
-                        ui.add_space(5.0);
+                        if satellites::render_constellation_filter(ui, &mut self.config.hidden_constellations, &mut self.config.used_satellites_only, used_known) {
+                            self.save_config();
+                        }
 
                         // Satellite table (bottom section)
                         ui.group(|ui| {
                             ui.set_width(right_width - 10.0);
                             ui.set_height(satellite_table_height.max(150.0));
                             
-                            let data = self.data.read().unwrap();
+                            let data = display_data(&self.data, &self.frozen);
                             let mut sat_panel = SatellitePanel {
                                 sort_column: self.sat_sort_column,
                                 sort_ascending: self.sat_sort_ascending,
+                                hidden_constellations: &self.config.hidden_constellations,
+                                used_only,
+                                snr_thresholds: &self.config.snr_thresholds,
+                                elevation_mask: self.config.elevation_mask_deg,
+                                palette: self.config.color_palette,
+                                highs: &mut self.satellite_highs,
                             };
                             sat_panel.render(ui, &data);
                             
@@ -335,24 +854,127 @@ impl GpsGuiApp {
         });
     }
 
+    /// Persist a config change made outside the settings window
+    fn save_config(&mut self) {
+        // Keep the settings window's copy in sync so a later save doesn't revert it
+        self.settings_window.config = self.config.clone();
+        if let Err(e) = self.config.save() {
+            self.error_message = Some(format!("Failed to save settings: {}", e));
+        }
+    }
+
+    /// Persist a track style changed from the map toolbar
+    fn save_map_style(&mut self, settle: Duration) {
+        if let Some(style) = self.map_window.take_marker_style_change(settle) {
+            self.config.marker_style = style.clone();
+            self.settings_window.set_marker_style(style);
+            self.save_config();
+        }
+    }
+
+    /// Schedule the next redraw, stretched to the configured background
+    /// interval while the window is unfocused
+    fn request_repaint_after(&self, ctx: &egui::Context, wanted: Duration) {
+        let background = Duration::from_secs(u64::from(self.config.unfocused_repaint_secs));
+        ctx.request_repaint_after(repaint_delay(wanted, self.focused, background, self.waypoint_dialog.is_recording()));
+    }
+
+    fn save_odometer(&mut self) {
+        if let Err(e) = self.odometer.write_recover().save(&self.odometer_path) {
+            self.error_message = Some(format!("Failed to save odometer: {}", e));
+        }
+    }
+
     fn handle_settings_window(&mut self, ctx: &egui::Context) {
         if self.settings_window.show(ctx) {
             // Configuration was saved, reload it
+            #[cfg(feature = "status-server")]
+            let previous_endpoint = self.config.status_endpoint.clone();
             self.config = self.settings_window.get_config().clone();
+            self.map_window.set_marker_style(self.config.marker_style.clone());
+            self.map_window.set_options(self.config.map_options.clone());
+            self.map_window.set_time_zone(self.config.time_zone);
+            self.map_window.set_coordinate_decimals(self.config.coordinate_decimals);
+            self.map_window.set_coordinate_format(self.config.coordinate_format);
+            self.map_window.set_distance_units(self.config.distance_units);
+            self.map_window.set_north_reference(self.config.north_reference);
+            self.waypoint_dialog.set_time_zone(self.config.time_zone);
+            self.waypoint_dialog.set_coordinate_decimals(self.config.coordinate_decimals);
+            self.waypoint_dialog.set_coordinate_format(self.config.coordinate_format);
+            self.waypoint_dialog.set_distance_units(self.config.distance_units);
+            self.waypoint_dialog.set_speed_unit(self.config.display_speed_unit());
+            self.waypoint_dialog.set_csv_export(self.config.csv_export.clone());
+            self.waypoint_dialog.set_quality_gate(self.config.waypoint_quality_gate.clone());
+            self.waypoint_dialog.set_fix_hold(self.config.waypoint_fix_hold);
+            self.waypoint_dialog.set_track_rotation(&self.config.track_rotation);
+            self.fix_events.set_export_dir(self.config.data_log.output_path());
+            self.waypoint_dialog.set_exclude_2d_altitude(self.config.exclude_2d_altitude);
+            self.waypoint_dialog.set_satellite_snapshots(self.config.record_satellite_snapshots);
             
             // Ask user if they want to reconnect
             self.error_message = Some("Settings saved! Click 'Restart' to apply changes.".to_string());
+
+            #[cfg(feature = "status-server")]
+            if self.config.status_endpoint != previous_endpoint {
+                self.restart_status_server();
+            }
         }
     }
 
     fn handle_waypoint_dialog(&mut self, ctx: &egui::Context) {
-        let data = self.data.read().unwrap().clone();
+        let data = self.data.read_recover().clone();
+        self.waypoint_dialog.update_from_gps(&data);
+        // Wake up for the idle auto-stop even while background repaints are slowed
+        if let Some(deadline) = self.waypoint_dialog.idle_deadline() {
+            ctx.request_repaint_after(deadline.saturating_duration_since(Instant::now()));
+        }
         self.waypoint_dialog.show(ctx, &data);
+
+        if let Some(name) = self.waypoint_dialog.take_reference_request() {
+            self.reference_distance = Some(ReferenceDistance::new(name, self.odometer.read_recover().lifetime_m()));
+        }
+
+        if let Some(waypoint) = self.waypoint_dialog.take_navigate_request() {
+            self.map_window.navigate_to(waypoint);
+        }
+
+        if let Some(recent) = self.waypoint_dialog.take_recent_exports_change() {
+            self.config.recent_exports = recent;
+            self.save_config();
+        }
+
+        if let Some(thinning) = self.waypoint_dialog.take_import_thinning_change() {
+            self.config.import_thinning = thinning;
+            self.save_config();
+        }
+    }
+
+    /// Recording state for the REC indicator, `Stopped` when it is turned off
+    fn recording_status(&self) -> RecordingStatus {
+        if self.config.recording_indicator {
+            self.waypoint_dialog.recording_status()
+        } else {
+            RecordingStatus::Stopped
+        }
+    }
+
+    /// Save the session once the auto-save interval has passed
+    fn check_autosave(&mut self) {
+        if self.config.session_autosave.is_due(self.last_autosave.elapsed()) {
+            self.last_autosave = Instant::now();
+            self.waypoint_dialog.autosave(self.session_path.clone());
+        }
     }
 
     fn handle_map_window(&mut self, ctx: &egui::Context) {
-        let data = self.data.read().unwrap().clone();
-        self.map_window.show(ctx, &data, &self.waypoint_dialog.exporter);
+        let data = self.data.read_recover().clone();
+        self.map_window.update_breadcrumb(&data);
+        self.map_window.set_connected(self.connection_state.is_active());
+        self.map_window.set_recording_status(self.recording_status());
+        let shown = self.frozen.as_ref().unwrap_or(&data);
+        self.map_window.set_compared_tracks(self.waypoint_dialog.compared_tracks());
+        self.map_window.show(ctx, shown, &self.waypoint_dialog.exporter, self.waypoint_dialog.active_track());
+        self.save_map_style(MAP_STYLE_SAVE_DELAY);
         
         // Clean up when window closes
         if !self.map_window.open {
@@ -360,6 +982,16 @@ impl GpsGuiApp {
         }
     }
 
+    fn handle_fix_events_window(&mut self, ctx: &egui::Context) {
+        if !self.fix_events.open {
+            return;
+        }
+        let log = self.data.read_recover().fix_events.clone();
+        if self.fix_events.show(ctx, &log) {
+            self.data.write_recover().fix_events.clear();
+        }
+    }
+
     fn show_error_notification(&mut self, ctx: &egui::Context) {
         // Take ownership of error_message to avoid borrow issues
         if let Some(msg) = self.error_message.take() {
@@ -386,20 +1018,192 @@ impl GpsGuiApp {
 
 impl eframe::App for GpsGuiApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        // Request repaint every second
-        ctx.request_repaint_after(Duration::from_secs(1));
+        // Close cleanly if a shutdown signal arrived
+        if self.shutdown_requested.load(Ordering::Relaxed) {
+            self.stop_connection();
+            ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+        }
+
+        // Request repaint every second, less often in the background. The
+        // connection runs on its own tasks and isn't affected.
+        self.focused = ctx.input(|i| i.focused);
+        self.request_repaint_after(ctx, Duration::from_secs(1));
 
         // Render UI components
+        self.poll_restart(ctx);
+        self.satellite_highs.update(&self.data.read_recover());
         self.render_top_menu(ctx);
+        self.render_frozen_banner(ctx);
+        self.check_no_fix_warning(ctx);
+        self.render_receiver_fault_banner(ctx);
+        self.check_update_rate();
+        self.check_data_stall();
+        #[cfg(feature = "obd")]
+        self.check_obd_error();
         self.render_bottom_panel(ctx);
-        self.render_main_content(ctx);
+        match self.layout {
+            MainLayout::Standard => self.render_main_content(ctx),
+            MainLayout::AntennaPlacement => self.render_antenna_content(ctx),
+        }
         self.handle_settings_window(ctx);
         self.handle_waypoint_dialog(ctx);
         self.handle_map_window(ctx);
+        self.nmea_inspector.show(ctx);
+        self.handle_fix_events_window(ctx);
         self.show_error_notification(ctx);
+        if self.map_window.is_downloading() || self.settings_window.is_detecting_baudrate() {
+            self.request_repaint_after(ctx, BACKGROUND_POLL_INTERVAL);
+        }
+        self.check_autosave();
+        if self.odometer.read_recover().save_due() {
+            self.save_odometer();
+        }
     }
 
     fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
         self.stop_connection();
+        self.save_map_style(Duration::ZERO);
+        if self.odometer.read_recover().has_unsaved() {
+            self.save_odometer();
+        }
+        if self.config.session_autosave.enabled {
+            self.waypoint_dialog.save_session(&self.session_path);
+        }
+    }
+}
+
+/// Status dot and text while connected: green only with a usable fix (so a
+/// gpsd mode 1 or GGA quality 0 stream stays yellow), red once data stops.
+/// A fix from too few satellites gets a muted caution instead of green.
+fn connected_status(data: &GpsData, min_satellites_used: usize) -> (egui::Color32, &'static str) {
+    if matches!(data.connection, ConnectionStatus::Lost(_)) {
+        (egui::Color32::RED, "Connection lost")
+    } else if data.timestamp.is_none() {
+        (egui::Color32::YELLOW, "Waiting for data")
+    } else if !data.is_recent() {
+        (egui::Color32::RED, "No recent data")
+    } else if data.fix_validity() == FixValidity::Invalid {
+        (egui::Color32::from_rgb(255, 165, 0), "Connected (fix flagged invalid)")
+    } else if data.low_satellite_count(min_satellites_used) {
+        (egui::Color32::from_rgb(200, 180, 60), "Connected (low satellite count)")
+    } else if data.has_valid_fix() {
+        (egui::Color32::GREEN, "Connected")
+    } else {
+        (egui::Color32::YELLOW, "Connected (no fix)")
+    }
+}
+
+/// Delay before the next redraw: `wanted` while focused, otherwise at least
+/// `background`, but no longer than recording a track allows
+fn repaint_delay(wanted: Duration, focused: bool, background: Duration, recording: bool) -> Duration {
+    if focused {
+        return wanted;
+    }
+    let background = if recording {
+        background.min(RECORDING_REPAINT_INTERVAL)
+    } else {
+        background
+    };
+    wanted.max(background)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restart_waits_without_blocking() {
+        let now = Instant::now();
+        let state = ConnectionState::Restarting(now + Duration::from_millis(500));
+        assert!(!state.is_active());
+        assert_eq!(state.restart_remaining(now), Some(Duration::from_millis(500)));
+        assert_eq!(state.restart_remaining(now + Duration::from_millis(200)), Some(Duration::from_millis(300)));
+
+        // Due (or overdue) restarts connect on the next frame
+        assert_eq!(state.restart_remaining(now + Duration::from_secs(2)), Some(Duration::ZERO));
+
+        // Nothing pending once connecting or after a Disconnect cancelled it
+        for state in [ConnectionState::Connecting, ConnectionState::Connected, ConnectionState::Disconnected] {
+            assert_eq!(state.restart_remaining(now), None);
+        }
+        assert!(ConnectionState::Connecting.is_active());
+    }
+
+    #[test]
+    fn test_data_watchdog_triggers_reconnect() {
+        let start = DateTime::parse_from_rfc3339("2024-05-01T12:00:00Z").unwrap().with_timezone(&Utc);
+        let fix = Some(start);
+        let seconds = |s| start + chrono::Duration::seconds(s);
+        let stall = crate::config::StallReconnect { enabled: true, threshold_secs: 60 };
+
+        let mut watchdog = DataWatchdog::default();
+        assert_eq!(watchdog.observe(10, fix, start), Duration::ZERO);
+        assert!(!stall.is_stalled(watchdog.observe(10, fix, seconds(30))));
+
+        // New sentences restart the clock
+        assert_eq!(watchdog.observe(11, fix, seconds(45)), Duration::ZERO);
+        assert!(!stall.is_stalled(watchdog.observe(11, fix, seconds(100))));
+
+        // Waking from sleep an hour later with the same data
+        let quiet = watchdog.observe(11, fix, seconds(3645));
+        assert_eq!(quiet, Duration::from_secs(3600));
+        assert!(stall.is_stalled(quiet));
+        assert!(!crate::config::StallReconnect { enabled: false, ..stall }.is_stalled(quiet));
+    }
+
+    #[test]
+    fn test_repaint_delay_in_background() {
+        let second = Duration::from_secs(1);
+        let background = Duration::from_secs(5);
+
+        assert_eq!(repaint_delay(second, true, background, false), second);
+        assert_eq!(repaint_delay(Duration::from_millis(100), true, background, true), Duration::from_millis(100));
+
+        assert_eq!(repaint_delay(second, false, background, false), background);
+        assert_eq!(repaint_delay(Duration::from_secs(10), false, background, false), Duration::from_secs(10));
+        // Recording keeps sampling at least once a second
+        assert_eq!(repaint_delay(Duration::from_millis(100), false, background, true), RECORDING_REPAINT_INTERVAL);
+    }
+
+    #[test]
+    fn test_connected_status_needs_a_fix() {
+        let mut data = GpsData::new();
+        assert_eq!(connected_status(&data, 5).1, "Waiting for data");
+
+        // gpsd reporting mode 1: data is flowing but there is no fix
+        data.update_timestamp();
+        data.mode = Some(1);
+        assert_eq!(connected_status(&data, 5), (egui::Color32::YELLOW, "Connected (no fix)"));
+
+        // A held position doesn't count while the mode says no fix
+        data.latitude = Some(42.0);
+        data.longitude = Some(-71.0);
+        assert_eq!(connected_status(&data, 5).0, egui::Color32::YELLOW);
+
+        data.mode = Some(3);
+        assert_eq!(connected_status(&data, 5), (egui::Color32::GREEN, "Connected"));
+
+        // A fix from too few satellites is a caution, not a missing fix
+        data.satellites = Some(4);
+        assert_eq!(connected_status(&data, 5).1, "Connected (low satellite count)");
+        assert_eq!(connected_status(&data, 4), (egui::Color32::GREEN, "Connected"));
+        data.satellites = None;
+
+        data.fix_quality = Some(0);
+        assert_eq!(connected_status(&data, 5).0, egui::Color32::YELLOW);
+
+        data.timestamp = Some(Utc::now() - chrono::Duration::seconds(30));
+        assert_eq!(connected_status(&data, 5), (egui::Color32::RED, "No recent data"));
+    }
+
+    #[test]
+    fn test_frozen_display_ignores_live_updates() {
+        let live = RwLock::new(GpsData::new());
+        live.write_recover().latitude = Some(42.0);
+        let frozen = Some(live.read_recover().clone());
+
+        live.write_recover().latitude = Some(43.0);
+        assert_eq!(display_data(&live, &frozen).latitude, Some(42.0));
+        assert_eq!(display_data(&live, &None).latitude, Some(43.0));
     }
 }
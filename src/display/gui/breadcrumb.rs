@@ -0,0 +1,102 @@
+// src/display/gui/breadcrumb.rs v1
+//! Rolling trail of recent positions, independent of track recording
+
+use crate::gps::GpsData;
+use crate::waypoint::TrackPoint;
+use std::collections::VecDeque;
+
+/// Skip positions closer than this to the previous one so jitter doesn't fill the buffer
+const MIN_SPACING_METERS: f64 = 2.0;
+
+pub struct Breadcrumb {
+    points: VecDeque<TrackPoint>,
+    max_points: usize,
+}
+
+impl Breadcrumb {
+    pub fn new(max_points: usize) -> Self {
+        Self {
+            points: VecDeque::new(),
+            max_points: max_points.max(2),
+        }
+    }
+
+    /// Append the current position if it moved far enough, dropping the oldest points over the cap
+    pub fn update(&mut self, gps_data: &GpsData) {
+        if !gps_data.has_valid_fix() {
+            return;
+        }
+        let Some(point) = TrackPoint::from_gps_data(gps_data) else {
+            return;
+        };
+
+        if let Some(last) = self.points.back() {
+            if last.distance_to(&point) < MIN_SPACING_METERS {
+                return;
+            }
+            // Start over after a position jump rather than drawing a line across it
+            if !last.is_plausible_step(&point) {
+                self.points.clear();
+            }
+        }
+
+        self.points.push_back(point);
+        while self.points.len() > self.max_points {
+            self.points.pop_front();
+        }
+    }
+
+    pub fn set_max_points(&mut self, max_points: usize) {
+        self.max_points = max_points.max(2);
+        while self.points.len() > self.max_points {
+            self.points.pop_front();
+        }
+    }
+
+    /// Positions (lat, lon), oldest first
+    pub fn positions(&self) -> impl Iterator<Item = (f64, f64)> + '_ {
+        self.points.iter().map(|p| (p.latitude, p.longitude))
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn clear(&mut self) {
+        self.points.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+
+    fn gps_at(lat: f64, secs: i64) -> GpsData {
+        let mut data = GpsData::new();
+        data.latitude = Some(lat);
+        data.longitude = Some(-71.0);
+        data.fix_quality = Some(1);
+        data.timestamp = Some(Utc::now() + Duration::seconds(secs));
+        data
+    }
+
+    #[test]
+    fn test_breadcrumb_spacing_and_cap() {
+        let mut trail = Breadcrumb::new(3);
+        trail.update(&gps_at(42.0, 0));
+        trail.update(&gps_at(42.00001, 1)); // ~1 m, ignored
+        assert_eq!(trail.len(), 1);
+
+        for i in 1..=4 {
+            trail.update(&gps_at(42.0 + i as f64 * 0.0001, i + 1));
+        }
+        assert_eq!(trail.len(), 3);
+        let first = trail.positions().next().unwrap();
+        assert!((first.0 - 42.0002).abs() < 1e-9);
+
+        // A jump restarts the trail
+        trail.update(&gps_at(43.0, 6));
+        assert_eq!(trail.len(), 1);
+    }
+}
@@ -0,0 +1,157 @@
+// src/display/gui/fix_events.rs v2
+//! Table of recent fix acquisitions, losses and mode changes, with CSV export
+
+use crate::config::DataLog;
+use crate::gps::{FixEventKind, FixEventLog};
+use crate::waypoint::format_duration;
+use chrono::Utc;
+use eframe::egui;
+use std::path::PathBuf;
+
+/// Rows shown in the table; the export has all of them
+const SHOWN_EVENTS: usize = 100;
+
+pub struct FixEventsWindow {
+    pub open: bool,
+    export_dir: PathBuf,  // Where a relative export path is written
+    export_path: String,
+    status_message: Option<String>,
+}
+
+impl FixEventsWindow {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            export_dir: DataLog::default().output_path(),
+            export_path: "fix_events.csv".to_string(),
+            status_message: None,
+        }
+    }
+
+    /// Directory a relative export path is taken from, the data log's
+    pub fn set_export_dir(&mut self, dir: PathBuf) {
+        self.export_dir = dir;
+    }
+
+    /// File the export is written to
+    fn export_file(&self) -> PathBuf {
+        // An absolute path replaces the directory
+        self.export_dir.join(self.export_path.trim())
+    }
+
+    fn export(&self, log: &FixEventLog) -> std::io::Result<PathBuf> {
+        let path = self.export_file();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, log.to_csv())?;
+        Ok(path)
+    }
+
+    /// Show the events of `log`. Returns true when the user cleared it.
+    pub fn show(&mut self, ctx: &egui::Context, log: &FixEventLog) -> bool {
+        if !self.open {
+            return false;
+        }
+
+        let mut open = self.open;
+        let mut cleared = false;
+        egui::Window::new("📉 Fix Events")
+            .open(&mut open)
+            .resizable(true)
+            .default_width(560.0)
+            .show(ctx, |ui| {
+                let lost = log.events().filter(|e| e.kind == FixEventKind::Lost).count();
+                ui.horizontal(|ui| {
+                    ui.label(format!("{} events, {} fix losses", log.len(), lost));
+                    if let Some(outage) = log.current_outage(Utc::now()) {
+                        ui.colored_label(egui::Color32::RED, format!("No fix for {}", format_duration(outage)));
+                    }
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label("Export to:");
+                    ui.text_edit_singleline(&mut self.export_path)
+                        .on_hover_text(format!("Relative to {}", self.export_dir.display()));
+                    let can_export = !log.is_empty() && !self.export_path.trim().is_empty();
+                    if ui.add_enabled(can_export, egui::Button::new("💾 Export CSV")).clicked() {
+                        self.status_message = Some(match self.export(log) {
+                            Ok(path) => format!("✓ Exported {} events to {}", log.len(), path.display()),
+                            Err(e) => format!("✗ Export failed: {}", e),
+                        });
+                    }
+                    if ui.add_enabled(!log.is_empty(), egui::Button::new("🗑 Clear")).clicked() {
+                        cleared = true;
+                    }
+                });
+                if let Some(message) = &self.status_message {
+                    ui.label(message);
+                }
+                ui.separator();
+
+                if log.is_empty() {
+                    ui.weak("No fix acquired or lost yet");
+                    return;
+                }
+
+                egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                    egui::Grid::new("fix_events")
+                        .num_columns(5)
+                        .striped(true)
+                        .spacing([12.0, 4.0])
+                        .show(ui, |ui| {
+                            ui.strong("Time");
+                            ui.strong("GPS time");
+                            ui.strong("Event");
+                            ui.strong("Mode");
+                            ui.strong("Outage");
+                            ui.end_row();
+
+                            for event in log.events().rev().take(SHOWN_EVENTS) {
+                                ui.monospace(event.time.format("%Y-%m-%d %H:%M:%S").to_string());
+                                ui.monospace(event.gps_time.map_or("-".to_string(), |t| t.format("%H:%M:%S").to_string()));
+                                let color = match event.kind {
+                                    FixEventKind::Acquired => egui::Color32::from_rgb(0, 160, 0),
+                                    FixEventKind::Lost => egui::Color32::RED,
+                                    FixEventKind::ModeChanged => ui.visuals().text_color(),
+                                };
+                                ui.colored_label(color, event.kind.label());
+                                ui.label(match event.mode {
+                                    Some(3..) => "3D",
+                                    Some(2) => "2D",
+                                    Some(_) => "No fix",
+                                    None => "-",
+                                });
+                                ui.monospace(event.outage.map_or("-".to_string(), format_duration));
+                                ui.end_row();
+                            }
+                        });
+                });
+            });
+        self.open = open;
+        cleared
+    }
+}
+
+impl Default for FixEventsWindow {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_goes_to_the_log_directory() {
+        let mut window = FixEventsWindow::new();
+        let dir = std::env::temp_dir().join("gps-monitor-logs");
+        window.set_export_dir(dir.clone());
+        assert_eq!(window.export_file(), dir.join("fix_events.csv"));
+
+        let absolute = std::env::temp_dir().join("outages.csv");
+        window.export_path = absolute.display().to_string();
+        assert_eq!(window.export_file(), absolute);
+    }
+}
@@ -1,12 +1,153 @@
-// src/display/gui/map_window.rs v2
+// src/display/gui/map_window.rs v17
 //! Map window with live position, tracks, and waypoints
 
-use crate::{gps::GpsData, waypoint::WaypointExporter, map::TileCache};
+use crate::{gps::{coordinates::parse_lat_lon, CoordinateFormat, GpsData, NorthReference, DEFAULT_COORDINATE_DECIMALS}, waypoint::{destination_point, Track, TrackSegment, Waypoint, WaypointExporter}, map::{lat_lon_to_tile, lat_to_world_y, world_y_to_lat, BoundingBox, MapSnapshot, TileCache, MAX_ZOOM}, config::{DisplayTimeZone, DistanceUnits, MapOptions, MarkerStyle, SpeedZoomStep}, track_recorder::RecordingStatus};
+use super::{breadcrumb::Breadcrumb, offline_download::OfflineDownload, recording_indicator, track_compare::COMPARE_COLORS, track_playback::TrackPlayback};
 use eframe::egui;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
 
 const TILE_SIZE: f32 = 256.0;
 
+/// Textures kept beyond those on screen before unused ones are freed
+const MAX_LOADED_TILES: usize = 256;
+
+/// Below this speed (km/h) the projected position is too noisy to prefetch around
+const MIN_PREFETCH_SPEED_KMH: f64 = 5.0;
+
+/// How long a recenter takes to glide to its target
+const RECENTER_DURATION: Duration = Duration::from_millis(400);
+
+/// Recenters shorter than this (pixels) snap, so continuous following doesn't lag
+const MIN_ANIMATED_MOVE_PX: f32 = 40.0;
+
+/// Recenters longer than this many map diagonals snap rather than sweep
+/// across (and download) everything in between
+const MAX_ANIMATED_MOVE_SCREENS: f32 = 4.0;
+
+/// Extra space (pixels) kept around a waypoint label; labels that would come
+/// closer than this to one already drawn are skipped
+const LABEL_MARGIN: f32 = 2.0;
+
+/// Speed-adaptive zoom only changes step once the speed is this far (km/h)
+/// past a threshold, so cruising near one doesn't flip the zoom back and forth
+const SPEED_ZOOM_HYSTERESIS_KMH: f64 = 5.0;
+
+/// Fraction of the map size moved by an arrow key press
+const KEY_PAN_FRACTION: f32 = 0.25;
+
+/// Mouse wheel scroll, in points, that changes the zoom by one level
+const SCROLL_PER_ZOOM_LEVEL: f32 = 50.0;
+
+/// Space (pixels) kept between the map edge and the position or waypoint
+/// while navigating, so the markers and label stay clear of it
+const FIT_MARGIN_PX: f32 = 60.0;
+
+/// Pieces the great circle to a navigation target is drawn in
+const NAVIGATION_LINE_STEPS: usize = 64;
+
+/// Line and target ring color while navigating to a waypoint
+const NAVIGATION_COLOR: egui::Color32 = egui::Color32::from_rgb(200, 0, 200);
+
+/// Map control from the keyboard
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum MapKey {
+    Pan(egui::Vec2),  // As a drag delta, so the view moves the way the arrow points
+    ZoomIn,
+    ZoomOut,
+    Home,
+}
+
+/// Map controls pressed this frame, for a map of size `view`
+fn map_keys(input: &egui::InputState, view: egui::Vec2) -> Vec<MapKey> {
+    let step = view * KEY_PAN_FRACTION;
+    [
+        (egui::Key::ArrowLeft, MapKey::Pan(egui::vec2(step.x, 0.0))),
+        (egui::Key::ArrowRight, MapKey::Pan(egui::vec2(-step.x, 0.0))),
+        (egui::Key::ArrowUp, MapKey::Pan(egui::vec2(0.0, step.y))),
+        (egui::Key::ArrowDown, MapKey::Pan(egui::vec2(0.0, -step.y))),
+        (egui::Key::Plus, MapKey::ZoomIn),
+        (egui::Key::Equals, MapKey::ZoomIn),  // "+" without shift
+        (egui::Key::Minus, MapKey::ZoomOut),
+        (egui::Key::Home, MapKey::Home),
+    ]
+    .into_iter()
+    .filter(|(key, _)| input.key_pressed(*key))
+    .map(|(_, action)| action)
+    .collect()
+}
+
+/// Low-pass filtered course, so the heading drawn on the map doesn't jitter.
+/// Gives no heading below the minimum speed, where the course is noise.
+struct HeadingFilter {
+    heading: Option<f64>,
+    last_update: Option<Instant>,
+}
+
+impl HeadingFilter {
+    fn new() -> Self {
+        Self { heading: None, last_update: None }
+    }
+
+    /// Fold in the latest course, `dt` seconds after the previous update
+    fn update(&mut self, course: Option<f64>, speed: Option<f64>, dt: f64, options: &MapOptions) -> Option<f64> {
+        let (Some(course), true) = (course, speed.is_some_and(|s| s >= options.heading_min_speed)) else {
+            self.heading = None;
+            return None;
+        };
+        let heading = match self.heading {
+            Some(heading) if options.heading_smoothing_secs > 0.0 => {
+                let alpha = 1.0 - (-dt / options.heading_smoothing_secs).exp();
+                // Turn the short way round through north
+                let delta = (course - heading + 540.0) % 360.0 - 180.0;
+                (heading + delta * alpha).rem_euclid(360.0)
+            }
+            _ => course,
+        };
+        self.heading = Some(heading);
+        Some(heading)
+    }
+
+    /// Update from the latest GPS data using the time since the last call
+    fn update_now(&mut self, gps_data: &GpsData, options: &MapOptions) -> Option<f64> {
+        let now = Instant::now();
+        let dt = self.last_update.map_or(0.0, |t| now.duration_since(t).as_secs_f64());
+        self.last_update = Some(now);
+        self.update(gps_data.course, gps_data.speed, dt, options)
+    }
+}
+
+/// Eased glide of the map center from one position to another
+struct RecenterAnimation {
+    from: (f64, f64),
+    to: (f64, f64),
+    started: Instant,
+}
+
+impl RecenterAnimation {
+    /// Center at this point of the animation, and whether it has arrived
+    fn current(&self) -> ((f64, f64), bool) {
+        let t = (self.started.elapsed().as_secs_f64() / RECENTER_DURATION.as_secs_f64()).min(1.0);
+        let eased = 1.0 - (1.0 - t).powi(3);  // Ease out: fast start, gentle arrival
+
+        // Go the short way round across the antimeridian
+        let mut delta_lon = self.to.1 - self.from.1;
+        if delta_lon > 180.0 {
+            delta_lon -= 360.0;
+        } else if delta_lon < -180.0 {
+            delta_lon += 360.0;
+        }
+        let lat = self.from.0 + (self.to.0 - self.from.0) * eased;
+        let lon = ((self.from.1 + delta_lon * eased + 540.0) % 360.0) - 180.0;
+
+        if t >= 1.0 {
+            (self.to, true)
+        } else {
+            ((lat, lon), false)
+        }
+    }
+}
+
 pub struct MapWindow {
     pub open: bool,
     tile_cache: TileCache,
@@ -14,10 +155,40 @@ pub struct MapWindow {
     center_lat: f64,
     center_lon: f64,
     follow_position: bool,
+    speed_zoom_step: Option<usize>,  // Index into the speed zoom steps last applied while following
     loaded_tiles: HashMap<(u8, u32, u32), egui::TextureHandle>,
+    drawn_tiles: HashSet<(u8, u32, u32)>,  // Tiles drawn during the current frame
     show_tracks: bool,
     show_waypoints: bool,
+    show_grid: bool,
     preload_triggered: bool,
+    memory_warmed: bool,  // Cached tiles for the opening view loaded (once per opening)
+    last_prefetch_tile: Option<(u8, u32, u32)>,  // Tile around which tiles ahead were last requested
+    marker_style: MarkerStyle,
+    marker_style_edited: Option<Instant>,  // Unsaved track style change from the toolbar
+    options: MapOptions,
+    time_zone: DisplayTimeZone,
+    coordinate_decimals: usize,
+    coordinate_format: CoordinateFormat,
+    last_pan: Option<Instant>,  // Last manual pan or zoom, for auto-follow
+    zoom_input: f32,  // Scroll and pinch zoom not yet making up a whole level
+    recenter_animation: Option<RecenterAnimation>,
+    last_map_size: egui::Vec2,
+    snapshot_path: String,
+    snapshot_status: Option<String>,
+    goto_input: String,
+    goto_error: Option<String>,
+    playback: TrackPlayback,
+    compared_tracks: Option<[usize; 2]>,  // Saved tracks drawn in the comparison colors
+    breadcrumb: Breadcrumb,
+    offline_download: OfflineDownload,
+    connected: bool,  // Otherwise the position is drawn as last known, not live
+    heading: HeadingFilter,
+    navigation: Option<Waypoint>,  // Waypoint a line is drawn to from the position
+    fit_navigation: bool,          // Keep the position and navigation target in view
+    distance_units: DistanceUnits,
+    north_reference: NorthReference,  // For the bearing to the navigation target
+    recording: RecordingStatus,  // Shown as a REC overlay unless stopped
 }
 
 impl MapWindow {
@@ -29,31 +200,238 @@ impl MapWindow {
             center_lat: 42.438878,
             center_lon: -71.119277,
             follow_position: true,
+            speed_zoom_step: None,
             loaded_tiles: HashMap::new(),
+            drawn_tiles: HashSet::new(),
             show_tracks: true,
             show_waypoints: true,
+            show_grid: false,
             preload_triggered: false,
+            memory_warmed: false,
+            last_prefetch_tile: None,
+            marker_style: MarkerStyle::default(),
+            marker_style_edited: None,
+            options: MapOptions::default(),
+            time_zone: DisplayTimeZone::default(),
+            coordinate_decimals: DEFAULT_COORDINATE_DECIMALS,
+            coordinate_format: CoordinateFormat::default(),
+            last_pan: None,
+            zoom_input: 0.0,
+            recenter_animation: None,
+            last_map_size: egui::vec2(800.0, 600.0),
+            snapshot_path: "map.png".to_string(),
+            snapshot_status: None,
+            goto_input: String::new(),
+            goto_error: None,
+            playback: TrackPlayback::new(),
+            compared_tracks: None,
+            breadcrumb: Breadcrumb::new(MapOptions::default().breadcrumb_length),
+            offline_download: OfflineDownload::new(),
+            connected: true,
+            heading: HeadingFilter::new(),
+            navigation: None,
+            fit_navigation: true,
+            distance_units: DistanceUnits::default(),
+            north_reference: NorthReference::default(),
+            recording: RecordingStatus::Stopped,
+        }
+    }
+
+    /// Update the marker appearance (e.g. after settings are saved)
+    pub fn set_marker_style(&mut self, style: MarkerStyle) {
+        self.marker_style = style;
+        self.marker_style_edited = None;
+    }
+
+    /// Whether an offline area download is running, for the app to keep
+    /// redrawing its progress
+    pub fn is_downloading(&self) -> bool {
+        self.offline_download.is_downloading()
+    }
+
+    /// Marker style changed from the map toolbar, once no edit has been made
+    /// for `settle` (so dragging a slider doesn't save every frame)
+    pub fn take_marker_style_change(&mut self, settle: Duration) -> Option<MarkerStyle> {
+        let edited = self.marker_style_edited?;
+        if edited.elapsed() < settle {
+            return None;
+        }
+        self.marker_style_edited = None;
+        Some(self.marker_style.clone())
+    }
+
+    /// Update map behaviour options (e.g. after settings are saved)
+    pub fn set_options(&mut self, options: MapOptions) {
+        self.breadcrumb.set_max_points(options.breadcrumb_length);
+        self.options = options;
+    }
+
+    /// Saved tracks (indices into the exporter's) to draw as a comparison, with a legend
+    pub fn set_compared_tracks(&mut self, tracks: Option<[usize; 2]>) {
+        self.compared_tracks = tracks;
+    }
+
+    /// Time zone for the playback clock (e.g. after settings are saved)
+    pub fn set_time_zone(&mut self, time_zone: DisplayTimeZone) {
+        self.time_zone = time_zone;
+    }
+
+    pub fn set_coordinate_decimals(&mut self, decimals: usize) {
+        self.coordinate_decimals = decimals;
+    }
+
+    /// Show the center, GPS and cursor positions as lat/lon, UTM or MGRS
+    pub fn set_coordinate_format(&mut self, format: CoordinateFormat) {
+        self.coordinate_format = format;
+    }
+
+    /// Units for the distance to a navigation target
+    pub fn set_distance_units(&mut self, units: DistanceUnits) {
+        self.distance_units = units;
+    }
+
+    /// North the bearing to the navigation target is given from, when the
+    /// receiver reports the magnetic variation
+    pub fn set_north_reference(&mut self, reference: NorthReference) {
+        self.north_reference = reference;
+    }
+
+    /// Open the map on a line from the position to `waypoint`, zoomed to show
+    /// both as the position moves. Without a fix the map centers on the waypoint.
+    pub fn navigate_to(&mut self, waypoint: Waypoint) {
+        self.navigation = Some(waypoint);
+        self.fit_navigation = true;
+        self.follow_position = false;
+        self.last_pan = None;
+        self.open = true;
+    }
+
+    /// Whether the GPS source is connected, so a last known position isn't shown as live
+    pub fn set_connected(&mut self, connected: bool) {
+        self.connected = connected;
+    }
+
+    /// Track recording state for the REC overlay; `Stopped` hides it
+    pub fn set_recording_status(&mut self, status: RecordingStatus) {
+        self.recording = status;
+    }
+
+    /// Add the latest position to the breadcrumb trail. Called every frame,
+    /// even while the window is closed, so the trail covers the whole session.
+    pub fn update_breadcrumb(&mut self, gps_data: &GpsData) {
+        self.breadcrumb.update(gps_data);
+    }
+
+    /// Turn follow mode back on once the user has stopped panning for a while
+    fn check_auto_follow(&mut self) {
+        if self.follow_position {
+            self.last_pan = None;
+            return;
+        }
+
+        let idle = Duration::from_secs(self.options.auto_follow_secs);
+        if self.options.auto_follow && self.last_pan.is_some_and(|t| t.elapsed() >= idle) {
+            self.follow_position = true;
+            self.last_pan = None;
+        }
+    }
+
+    /// Zoom and center to show both the position and the navigation target,
+    /// or center on the target while there's no fix
+    fn fit_navigation_view(&mut self, gps_data: &GpsData) {
+        if self.follow_position {
+            self.fit_navigation = false;
+        }
+        let Some(target) = self.navigation.as_ref().filter(|_| self.fit_navigation) else {
+            return;
+        };
+        let target_position = (target.latitude, target.longitude);
+        let ((lat, lon), zoom) = match gps_data.latitude.zip(gps_data.longitude) {
+            Some(position) => fit_view(position, target_position, self.last_map_size),
+            None => (target_position, self.zoom),
+        };
+
+        if zoom != self.zoom {
+            self.set_zoom(zoom);
+        }
+        let moved = world_distance_px((self.center_lat, self.center_lon), (lat, lon), self.zoom);
+        if self.recenter_animation.is_some() || moved > f64::from(self.options.follow_dead_zone_px) {
+            self.recenter(lat, lon);
         }
     }
 
-    pub fn show(&mut self, ctx: &egui::Context, gps_data: &GpsData, exporter: &WaypointExporter) {
+    /// Preload tiles around where the current course and speed will be in
+    /// `prefetch_secs`. Only requests again once that point reaches a new tile,
+    /// and the downloads go through the cache's usual rate limiting.
+    fn prefetch_ahead(&mut self, gps_data: &GpsData) {
+        if !self.options.prefetch_ahead {
+            return;
+        }
+        let (Some(lat), Some(lon), Some(speed), Some(course)) =
+            (gps_data.latitude, gps_data.longitude, gps_data.speed, gps_data.course)
+        else {
+            return;
+        };
+        if speed < MIN_PREFETCH_SPEED_KMH {
+            return;
+        }
+
+        let distance = speed / 3.6 * self.options.prefetch_secs as f64;
+        let (ahead_lat, ahead_lon) = destination_point(lat, lon, course, distance);
+        let (x, y) = lat_lon_to_tile(ahead_lat, ahead_lon, self.zoom);
+        let tile = (self.zoom, x, y);
+
+        if self.last_prefetch_tile != Some(tile) {
+            self.tile_cache.preload_area(ahead_lat, ahead_lon, self.zoom, 1);
+            self.last_prefetch_tile = Some(tile);
+        }
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context, gps_data: &GpsData, exporter: &WaypointExporter, active_track: Option<&Track>) {
         if !self.open {
             return;
         }
 
-        // Update center to current position if following
+        self.check_auto_follow();
+
+        // Disk tiles for the view the map opens at, loaded in the background
+        // so they are ready in memory as the first frames are drawn
+        if self.options.warm_cache_on_open && !self.memory_warmed {
+            let view = egui::Rect::from_center_size(egui::Pos2::ZERO, self.last_map_size);
+            if let Some(area) = self.visible_area(view) {
+                drop(self.tile_cache.warm_memory(self.zoom, &area));
+            }
+            self.memory_warmed = true;
+        }
+
+        self.fit_navigation_view(gps_data);
+
+        // Update center to current position if following, leaving room ahead
         if self.follow_position {
             if let (Some(lat), Some(lon)) = (gps_data.latitude, gps_data.longitude) {
-                self.center_lat = lat;
-                self.center_lon = lon;
+                self.apply_speed_zoom(gps_data.speed);
+
+                let offset_px = (self.options.follow_offset * self.last_map_size.y) as f64;
+                let (center_lat, center_lon) = offset_follow_center(lat, lon, self.zoom, offset_px);
+                // Jitter inside the dead zone leaves a parked map still
+                let moved = world_distance_px((self.center_lat, self.center_lon), (center_lat, center_lon), self.zoom);
+                if self.recenter_animation.is_some() || moved > f64::from(self.options.follow_dead_zone_px) {
+                    self.recenter(center_lat, center_lon);
+                }
                 
                 // Preload tiles around current position (once per opening)
                 if !self.preload_triggered {
                     self.tile_cache.preload_area(lat, lon, self.zoom, 2);
                     self.preload_triggered = true;
                 }
+
+                self.prefetch_ahead(gps_data);
             }
+        } else {
+            // Apply the speed's zoom afresh once following resumes
+            self.speed_zoom_step = None;
         }
+        self.step_recenter_animation(ctx);
 
         let mut window_open = self.open;
         
@@ -72,7 +450,7 @@ impl MapWindow {
                 );
                 
                 egui::CentralPanel::default().show(ctx, |ui| {
-                    self.render_window_contents(ui, gps_data, exporter);
+                    self.render_window_contents(ui, gps_data, exporter, active_track);
                 });
 
                 // Check if window was closed
@@ -85,18 +463,16 @@ impl MapWindow {
         self.open = window_open;
     }
 
-    fn render_window_contents(&mut self, ui: &mut egui::Ui, gps_data: &GpsData, exporter: &WaypointExporter) {
+    fn render_window_contents(&mut self, ui: &mut egui::Ui, gps_data: &GpsData, exporter: &WaypointExporter, active_track: Option<&Track>) {
         // Top controls
         ui.horizontal(|ui| {
             ui.label("Zoom:");
             if ui.button("➖").clicked() && self.zoom > 1 {
-                self.zoom -= 1;
-                self.preload_triggered = false;
+                self.zoom_manually(self.zoom - 1);
             }
             ui.label(format!("{}", self.zoom));
             if ui.button("➕").clicked() && self.zoom < 18 {
-                self.zoom += 1;
-                self.preload_triggered = false;
+                self.zoom_manually(self.zoom + 1);
             }
 
             ui.separator();
@@ -107,44 +483,347 @@ impl MapWindow {
             
             ui.checkbox(&mut self.show_tracks, "Show Tracks");
             ui.checkbox(&mut self.show_waypoints, "Show Waypoints");
+            ui.checkbox(&mut self.show_grid, "Grid");
+            ui.checkbox(&mut self.options.show_breadcrumb, "Trail")
+                .on_hover_text("Recent positions, whether or not a track is recording");
+            if self.options.show_breadcrumb && ui.small_button("Clear").clicked() {
+                self.breadcrumb.clear();
+            }
+
+            ui.menu_button("Track Style", |ui| {
+                let style = &mut self.marker_style;
+                let changed = [
+                    ui.horizontal(|ui| {
+                        ui.label("Color:");
+                        ui.color_edit_button_srgb(&mut style.track_color)
+                    }).inner,
+                    ui.horizontal(|ui| {
+                        ui.label("Recording:");
+                        ui.color_edit_button_srgb(&mut style.active_track_color)
+                    }).inner,
+                    ui.add(egui::Slider::new(&mut style.track_width, 1.0..=10.0).text("Width").suffix(" px")),
+                    ui.add(egui::Slider::new(&mut style.track_opacity, 0.1..=1.0).text("Opacity")),
+                ].iter().any(|response| response.changed());
+                if changed {
+                    self.marker_style_edited = Some(Instant::now());
+                }
+            });
 
             ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                 let stats = self.tile_cache.get_stats();
                 ui.label(format!("Cache: {} tiles ({:.1} MB)", 
                     stats.disk_tiles, stats.disk_size_mb));
-                
-                if ui.button("🗑 Clear Cache").clicked() {
-                    let _ = self.tile_cache.clear_disk_cache();
-                    self.tile_cache.clear_memory_cache();
-                    self.loaded_tiles.clear();
+                if stats.corrupt_tiles > 0 {
+                    ui.colored_label(egui::Color32::from_rgb(255, 165, 0), format!("⚠ {} corrupt", stats.corrupt_tiles))
+                        .on_hover_text("Cached tiles that failed to decode were deleted and downloaded again");
                 }
+                
+                ui.add_enabled_ui(!self.offline_download.is_downloading(), |ui| {
+                    if ui.button("🗑 Clear Cache").clicked() {
+                        let _ = self.tile_cache.clear_disk_cache();
+                        self.tile_cache.clear_memory_cache();
+                        self.loaded_tiles.clear();
+                    }
+                });
+                ui.toggle_value(&mut self.offline_download.open, "⬇ Offline")
+                    .on_hover_text("Download the tiles for an area to use without a connection");
             });
         });
 
+        // Jump to typed coordinates
+        ui.horizontal(|ui| {
+            ui.label("Go to:");
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut self.goto_input)
+                    .hint_text("lat, lon  (e.g. 42.4389, -71.1193 or 42°26'20\"N 71°07'09\"W)")
+                    .desired_width(320.0),
+            );
+            let submitted = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            if ui.button("Go").clicked() || submitted {
+                self.go_to_input();
+            }
+            if let Some(ref error) = self.goto_error {
+                ui.colored_label(egui::Color32::RED, error);
+            }
+        });
+
+        if self.navigation.is_some() {
+            self.render_navigation_controls(ui, gps_data);
+        }
+
+        // Track playback controls
+        if exporter.track_count() > 0 {
+            self.playback.tick(exporter.get_tracks());
+            self.playback.render_controls(ui, exporter.get_tracks(), self.time_zone);
+            if self.playback.is_playing() {
+                ui.ctx().request_repaint();
+            }
+        }
+
         ui.separator();
 
         // Map display area
         let available_size = ui.available_size();
-        let (response, painter) = ui.allocate_painter(available_size, egui::Sense::drag());
+        let (response, painter) = ui.allocate_painter(available_size, egui::Sense::click_and_drag());
+        self.last_map_size = response.rect.size();
 
         // Handle dragging
         if response.dragged() && !self.follow_position {
+            self.fit_navigation = false;
             let delta = response.drag_delta();
             self.pan_map(delta);
         }
 
+        // Mouse wheel, ctrl+scroll and pinch zoom
+        if response.hovered() {
+            let (zoom_factor, scroll) = ui.input(|i| (i.zoom_delta(), i.smooth_scroll_delta.y));
+            self.zoom_from_input(zoom_factor, scroll);
+        }
+
+        // Keyboard control while the map is hovered or focused (click it, or
+        // tab to it), but not while typing into one of the text fields
+        if response.clicked() {
+            response.request_focus();
+        }
+        if response.has_focus() {
+            ui.memory_mut(|m| m.set_focus_lock_filter(response.id, egui::EventFilter {
+                horizontal_arrows: true,
+                vertical_arrows: true,
+                ..Default::default()
+            }));
+        }
+        let typing = ui.memory(|m| m.focused()).is_some_and(|id| id != response.id);
+        if (response.hovered() || response.has_focus()) && !typing {
+            let keys = ui.input(|i| map_keys(i, response.rect.size()));
+            for key in keys {
+                self.apply_map_key(key, gps_data);
+            }
+        }
+
         // Render map
-        self.render_map(ui.ctx(), &painter, response.rect, gps_data, exporter);
+        self.render_map(ui.ctx(), &painter, response.rect, gps_data, exporter, active_track);
+        recording_indicator::paint_overlay(ui.ctx(), &painter, response.rect, self.recording);
+
+        let visible_area = self.visible_area(response.rect);
+        self.offline_download.show(ui.ctx(), &self.tile_cache, visible_area, self.zoom);
+
+        // Crosshair and coordinates under the pointer
+        let cursor = response.hover_pos()
+            .filter(|pos| response.rect.contains(*pos))
+            .map(|pos| {
+                Self::render_crosshair(&painter, pos);
+                self.screen_to_lat_lon(pos, response.rect)
+            });
 
         // Show current coordinates
         ui.separator();
         ui.horizontal(|ui| {
-            ui.label(format!("Center: {:.6}, {:.6}", self.center_lat, self.center_lon));
+            let format = |lat, lon| self.coordinate_format.format_position(lat, lon, self.coordinate_decimals);
+            ui.label(format!("Center: {}", format(self.center_lat, self.center_lon)));
             if let (Some(lat), Some(lon)) = (gps_data.latitude, gps_data.longitude) {
                 ui.separator();
-                ui.label(format!("GPS: {:.6}, {:.6}", lat, lon));
+                ui.label(format!("GPS: {}", format(lat, lon)));
+            }
+            if let Some((lat, lon)) = cursor {
+                ui.separator();
+                ui.label(format!("Cursor: {}", format(lat, lon)));
+            }
+
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui.button("📷 Save PNG").clicked() {
+                    self.save_snapshot(gps_data, exporter);
+                }
+                ui.add(egui::TextEdit::singleline(&mut self.snapshot_path).desired_width(140.0));
+                if let Some(ref status) = self.snapshot_status {
+                    ui.label(status);
+                }
+            });
+        });
+    }
+
+    /// Pan and zoom as the mouse and zoom buttons do. Panning leaves follow
+    /// mode (auto-follow resumes as after a drag); Home goes back to the GPS.
+    fn apply_map_key(&mut self, key: MapKey, gps_data: &GpsData) {
+        match key {
+            MapKey::Pan(delta) => {
+                self.follow_position = false;
+                self.fit_navigation = false;
+                self.pan_map(delta);
+            }
+            MapKey::ZoomIn if self.zoom < 18 => self.zoom_manually(self.zoom + 1),
+            MapKey::ZoomOut if self.zoom > 1 => self.zoom_manually(self.zoom - 1),
+            MapKey::ZoomIn | MapKey::ZoomOut => {}
+            MapKey::Home => {
+                if let (Some(lat), Some(lon)) = (gps_data.latitude, gps_data.longitude) {
+                    self.follow_position = true;
+                    self.recenter(lat, lon);
+                }
+            }
+        }
+    }
+
+    /// Target name, distance and bearing, and controls to keep both in view or stop
+    fn render_navigation_controls(&mut self, ui: &mut egui::Ui, gps_data: &GpsData) {
+        let Some(target) = &self.navigation else {
+            return;
+        };
+        let mut stop = false;
+        ui.horizontal(|ui| {
+            ui.label(egui::RichText::new(format!("🧭 To {}", target.name)).strong().color(NAVIGATION_COLOR));
+            match gps_data.latitude.zip(gps_data.longitude) {
+                Some((lat, lon)) => {
+                    ui.monospace(navigation_label(target, gps_data, lat, lon, self.distance_units, self.north_reference));
+                }
+                None => {
+                    ui.weak("No fix — showing the waypoint");
+                }
+            }
+            ui.separator();
+            if ui.checkbox(&mut self.fit_navigation, "Keep both in view").changed() && self.fit_navigation {
+                self.follow_position = false;
             }
+            stop = ui.small_button("✖ Stop").clicked();
         });
+        if stop {
+            self.navigation = None;
+        }
+    }
+
+    /// Recenter on the coordinates typed in the "Go to" box, leaving follow mode
+    fn go_to_input(&mut self) {
+        match parse_lat_lon(&self.goto_input) {
+            Ok((lat, lon)) => {
+                // No auto-follow timer: stay here until the user turns follow back on
+                self.follow_position = false;
+                self.last_pan = None;
+                self.recenter(lat, lon);
+                self.goto_error = None;
+            }
+            Err(e) => self.goto_error = Some(e.to_string()),
+        }
+    }
+
+    /// Move the center to (lat, lon), gliding there for moves large enough to
+    /// be disorienting. A recenter during an animation just retargets it.
+    fn recenter(&mut self, lat: f64, lon: f64) {
+        if let Some(animation) = &mut self.recenter_animation {
+            animation.to = (lat, lon);
+            return;
+        }
+
+        let view = egui::Rect::from_min_size(egui::Pos2::ZERO, self.last_map_size);
+        let distance = (self.lat_lon_to_screen_unclipped(lat, lon, view) - view.center()).length();
+        let max_distance = self.last_map_size.length() * MAX_ANIMATED_MOVE_SCREENS;
+        if (MIN_ANIMATED_MOVE_PX..=max_distance).contains(&distance) {
+            self.recenter_animation = Some(RecenterAnimation {
+                from: (self.center_lat, self.center_lon),
+                to: (lat, lon),
+                started: Instant::now(),
+            });
+        } else {
+            self.center_lat = lat;
+            self.center_lon = lon;
+        }
+    }
+
+    /// Advance a running recenter animation, repainting until it arrives
+    fn step_recenter_animation(&mut self, ctx: &egui::Context) {
+        let Some(animation) = &self.recenter_animation else {
+            return;
+        };
+        let ((lat, lon), arrived) = animation.current();
+        self.center_lat = lat;
+        self.center_lon = lon;
+        if arrived {
+            self.recenter_animation = None;
+        } else {
+            ctx.request_repaint();
+        }
+    }
+
+    /// Write the current view, with visible overlays, to `snapshot_path`
+    fn save_snapshot(&mut self, gps_data: &GpsData, exporter: &WaypointExporter) {
+        let mut path = std::path::PathBuf::from(&self.snapshot_path);
+        if path.extension().is_none() {
+            path.set_extension("png");
+        }
+
+        let snapshot = MapSnapshot {
+            zoom: self.zoom,
+            center_lat: self.center_lat,
+            center_lon: self.center_lon,
+            width: self.last_map_size.x.max(1.0) as u32,
+            height: self.last_map_size.y.max(1.0) as u32,
+            tracks: if self.show_tracks { exporter.get_tracks() } else { &[] },
+            waypoints: if self.show_waypoints { exporter.get_waypoints() } else { &[] },
+            position: gps_data.latitude.zip(gps_data.longitude),
+            style: &self.marker_style,
+        };
+
+        self.snapshot_status = Some(match snapshot.save_png(&self.tile_cache, &path) {
+            Ok(_) => format!("✓ Saved {}", path.display()),
+            Err(e) => format!("✗ {}", e),
+        });
+    }
+
+    /// Zoom for the current speed while following. The zoom is only set when
+    /// the speed step changes, so manual zooming in between is kept.
+    fn apply_speed_zoom(&mut self, speed: Option<f64>) {
+        let steps = &self.options.speed_zoom_steps;
+        let step = match speed {
+            Some(speed) if self.options.speed_zoom && !steps.is_empty() => speed_zoom_step(steps, speed, self.speed_zoom_step),
+            _ => {
+                self.speed_zoom_step = None;
+                return;
+            }
+        };
+        if self.speed_zoom_step != Some(step) {
+            self.speed_zoom_step = Some(step);
+            let zoom = steps[step].zoom.clamp(1, MAX_ZOOM);
+            if zoom != self.zoom {
+                self.set_zoom(zoom);
+            }
+        }
+    }
+
+    /// Zoom at the user's request. Outside follow mode this restarts the
+    /// auto-follow idle timer, as a pan does.
+    fn zoom_manually(&mut self, zoom: u8) {
+        if !self.follow_position {
+            self.last_pan = Some(Instant::now());
+        }
+        if zoom != self.zoom {
+            self.set_zoom(zoom);
+        }
+    }
+
+    /// Zoom by a pinch factor (also ctrl+scroll) and plain wheel scroll,
+    /// a whole level at a time once enough has built up
+    fn zoom_from_input(&mut self, zoom_factor: f32, scroll: f32) {
+        self.zoom_input += zoom_factor.log2() + scroll / SCROLL_PER_ZOOM_LEVEL;
+        let levels = self.zoom_input.trunc();
+        if levels == 0.0 {
+            return;
+        }
+        self.zoom_input -= levels;
+        let zoom = (i32::from(self.zoom) + levels as i32).clamp(1, i32::from(MAX_ZOOM));
+        self.zoom_manually(zoom as u8);
+    }
+
+    /// Change zoom level, dropping the textures and queued downloads of the old level
+    fn set_zoom(&mut self, zoom: u8) {
+        self.zoom = zoom;
+        self.preload_triggered = false;
+        self.loaded_tiles.retain(|&(z, _, _), _| z == zoom);
+        self.tile_cache.drop_queued_downloads(|(z, _, _)| z == zoom);
+    }
+
+    /// Free textures not drawn this frame once more than `MAX_LOADED_TILES` are held
+    fn evict_unused_tiles(&mut self) {
+        if self.loaded_tiles.len() > MAX_LOADED_TILES {
+            let drawn = &self.drawn_tiles;
+            self.loaded_tiles.retain(|key, _| drawn.contains(key));
+        }
     }
 
     fn render_map(
@@ -154,6 +833,7 @@ impl MapWindow {
         rect: egui::Rect,
         gps_data: &GpsData,
         exporter: &WaypointExporter,
+        active_track: Option<&Track>,
     ) {
         let width = rect.width();
         let height = rect.height();
@@ -164,14 +844,14 @@ impl MapWindow {
         // Calculate pixel offset within center tile
         let n = 2_f64.powi(self.zoom as i32);
         let center_pixel_x = ((self.center_lon + 180.0) / 360.0 * n * TILE_SIZE as f64) % TILE_SIZE as f64;
-        let lat_rad = self.center_lat.to_radians();
-        let center_pixel_y = ((1.0 - (lat_rad.tan() + 1.0 / lat_rad.cos()).ln() / std::f64::consts::PI) / 2.0 * n * TILE_SIZE as f64) % TILE_SIZE as f64;
+        let center_pixel_y = (lat_to_world_y(self.center_lat) * n * TILE_SIZE as f64) % TILE_SIZE as f64;
 
         // Calculate how many tiles we need in each direction
         let tiles_x = (width / TILE_SIZE).ceil() as i32 + 1;
         let tiles_y = (height / TILE_SIZE).ceil() as i32 + 1;
 
         // Render tiles
+        self.drawn_tiles.clear();
         for dy in -tiles_y..=tiles_y {
             for dx in -tiles_x..=tiles_x {
                 let tile_x = (center_tile_x as i32 + dx) as u32;
@@ -181,21 +861,44 @@ impl MapWindow {
                 let screen_x = rect.left() + width / 2.0 + dx as f32 * TILE_SIZE - center_pixel_x as f32;
                 let screen_y = rect.top() + height / 2.0 + dy as f32 * TILE_SIZE - center_pixel_y as f32;
 
-                self.render_tile(ctx, painter, self.zoom, tile_x, tile_y, screen_x, screen_y);
+                self.render_tile(ctx, painter, (self.zoom, tile_x, tile_y), egui::pos2(screen_x, screen_y));
             }
         }
+        self.evict_unused_tiles();
+
+        if self.show_grid {
+            self.render_grid(painter, rect);
+        }
+
+        if self.options.show_breadcrumb {
+            self.render_breadcrumb(painter, rect);
+        }
 
         // Render GPS position
         if let (Some(lat), Some(lon)) = (gps_data.latitude, gps_data.longitude) {
-            if let Some(pos) = self.lat_lon_to_screen(lat, lon, rect) {
+            if !self.connected {
+                self.render_last_known_position(painter, rect, lat, lon);
+            } else if let Some(pos) = self.lat_lon_to_screen(lat, lon, rect) {
+                // Draw accuracy circle under the marker
+                if let Some(accuracy) = gps_data.accuracy {
+                    let radius = self.meters_to_pixels(accuracy, lat);
+                    if radius > self.marker_style.position_size {
+                        painter.circle_filled(pos, radius, egui::Color32::from_rgba_unmultiplied(0, 122, 255, 40));
+                        painter.circle_stroke(pos, radius, egui::Stroke::new(1.0, egui::Color32::from_rgba_unmultiplied(0, 122, 255, 120)));
+                    }
+                }
+
                 // Draw position circle
-                painter.circle_filled(pos, 8.0, egui::Color32::from_rgb(0, 122, 255));
-                painter.circle_stroke(pos, 8.0, egui::Stroke::new(2.0, egui::Color32::WHITE));
+                let [r, g, b] = self.marker_style.position_color;
+                let size = self.marker_style.position_size;
+                painter.circle_filled(pos, size, egui::Color32::from_rgb(r, g, b));
+                painter.circle_stroke(pos, size, egui::Stroke::new(2.0, egui::Color32::WHITE));
                 
-                // Draw heading indicator if course available
-                if let Some(course) = gps_data.course {
-                    let angle = course.to_radians();
-                    let end_pos = pos + egui::vec2(angle.sin() as f32 * 15.0, -angle.cos() as f32 * 15.0);
+                // Draw heading indicator once moving fast enough for the course to mean something
+                if let Some(heading) = self.heading.update_now(gps_data, &self.options) {
+                    let angle = heading.to_radians();
+                    let length = size + 7.0;
+                    let end_pos = pos + egui::vec2(angle.sin() as f32 * length, -angle.cos() as f32 * length);
                     painter.line_segment([pos, end_pos], egui::Stroke::new(3.0, egui::Color32::WHITE));
                 }
             }
@@ -203,51 +906,96 @@ impl MapWindow {
 
         // Render tracks
         if self.show_tracks {
-            for track in exporter.get_tracks() {
+            let stroke = track_stroke(&self.marker_style, false);
+            let compared = self.compared_tracks.unwrap_or_default();
+            for (index, track) in exporter.get_tracks().iter().enumerate() {
+                if self.compared_tracks.is_some() && compared.contains(&index) {
+                    continue;
+                }
+                let stroke = saved_track_stroke(stroke, track, self.marker_style.track_opacity);
+                for segment in &track.segments {
+                    self.render_track_segment(painter, segment, rect, stroke);
+                }
+            }
+
+            // Compared tracks over the others, in their legend colors
+            if let Some(indices) = self.compared_tracks {
+                let tracks = indices.map(|index| exporter.get_tracks().get(index));
+                if let [Some(first), Some(second)] = tracks {
+                    for (track, color) in [first, second].into_iter().zip(COMPARE_COLORS) {
+                        let stroke = egui::Stroke::new(stroke.width.max(3.0), color);
+                        for segment in &track.segments {
+                            self.render_track_segment(painter, segment, rect, stroke);
+                        }
+                    }
+                    Self::render_comparison_legend(painter, rect, [first, second]);
+                }
+            }
+
+            // The recording in progress, on top so the live line stays visible
+            if let Some(track) = active_track {
+                let stroke = track_stroke(&self.marker_style, true);
                 for segment in &track.segments {
-                    self.render_track_segment(painter, segment, rect);
+                    self.render_track_segment(painter, segment, rect, stroke);
                 }
             }
         }
 
+        // Line to the navigation target, over the tracks
+        if let Some(target) = &self.navigation {
+            self.render_navigation(painter, rect, gps_data, target);
+        }
+
+        // Render track playback marker
+        if let Some((lat, lon)) = self.playback.position(exporter.get_tracks()) {
+            if let Some(pos) = self.lat_lon_to_screen(lat, lon, rect) {
+                painter.circle_filled(pos, 7.0, egui::Color32::from_rgb(255, 165, 0));
+                painter.circle_stroke(pos, 7.0, egui::Stroke::new(2.0, egui::Color32::BLACK));
+            }
+        }
+
         // Render waypoints
         if self.show_waypoints {
+            let show_labels = self.zoom >= self.options.label_min_zoom;
+            let mut placed_labels: Vec<egui::Rect> = Vec::new();
             for waypoint in exporter.get_waypoints() {
                 if let Some(pos) = self.lat_lon_to_screen(waypoint.latitude, waypoint.longitude, rect) {
                     // Draw waypoint marker
-                    painter.circle_filled(pos, 6.0, egui::Color32::RED);
-                    painter.circle_stroke(pos, 6.0, egui::Stroke::new(2.0, egui::Color32::WHITE));
+                    let [r, g, b] = waypoint.color.unwrap_or(self.marker_style.waypoint_color);
+                    let size = self.marker_style.waypoint_size;
+                    painter.circle_filled(pos, size, egui::Color32::from_rgb(r, g, b));
+                    painter.circle_stroke(pos, size, egui::Stroke::new(2.0, egui::Color32::WHITE));
                     
-                    // Draw label
-                    let label_pos = pos + egui::vec2(10.0, -10.0);
-                    painter.text(
-                        label_pos,
-                        egui::Align2::LEFT_BOTTOM,
-                        &waypoint.name,
+                    // Draw label unless it would overlap one already drawn
+                    if !show_labels {
+                        continue;
+                    }
+                    let galley = painter.layout_no_wrap(
+                        waypoint.name.clone(),
                         egui::FontId::proportional(12.0),
-                        egui::Color32::WHITE
+                        egui::Color32::WHITE,
                     );
+                    let label_pos = pos + egui::vec2(size + 4.0, -(size + 4.0));
+                    let label_rect = egui::Align2::LEFT_BOTTOM.anchor_size(label_pos, galley.size());
+                    if placed_labels.iter().any(|placed| placed.expand(LABEL_MARGIN).intersects(label_rect)) {
+                        continue;
+                    }
+                    painter.galley(label_rect.min, galley, egui::Color32::WHITE);
+                    placed_labels.push(label_rect);
                 }
             }
         }
     }
 
-    fn render_tile(
-        &mut self,
-        ctx: &egui::Context,
-        painter: &egui::Painter,
-        zoom: u8,
-        x: u32,
-        y: u32,
-        screen_x: f32,
-        screen_y: f32,
-    ) {
-        let key = (zoom, x, y);
+    /// Draw tile `key` (zoom, x, y) with its top-left corner at `min`
+    fn render_tile(&mut self, ctx: &egui::Context, painter: &egui::Painter, key: (u8, u32, u32), min: egui::Pos2) {
+        let (zoom, x, y) = key;
+        self.drawn_tiles.insert(key);
 
         // Check if we already have this tile as a texture
         if let Some(texture) = self.loaded_tiles.get(&key) {
             let rect = egui::Rect::from_min_size(
-                egui::pos2(screen_x, screen_y),
+                min,
                 egui::vec2(TILE_SIZE, TILE_SIZE),
             );
             painter.image(texture.id(), rect, egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)), egui::Color32::WHITE);
@@ -255,41 +1003,41 @@ impl MapWindow {
         }
 
         // Try to get tile from cache
-        match self.tile_cache.get_tile(zoom, x, y) {
-            Ok(tile_data) => {
-                // Load image
-                if let Ok(image) = image::load_from_memory(&tile_data) {
-                    let size = [image.width() as usize, image.height() as usize];
-                    let rgba = image.to_rgba8();
-                    let pixels = rgba.as_flat_samples();
-                    
-                    let color_image = egui::ColorImage::from_rgba_unmultiplied(
-                        size,
-                        pixels.as_slice(),
-                    );
+        // A corrupt cached tile is discarded and comes back as an error too
+        match self.tile_cache.get_tile_image(zoom, x, y) {
+            Ok(image) => {
+                let size = [image.width() as usize, image.height() as usize];
+                let rgba = image.to_rgba8();
+                let pixels = rgba.as_flat_samples();
 
-                    let texture = ctx.load_texture(
-                        format!("tile_{}_{}_{}", zoom, x, y),
-                        color_image,
-                        egui::TextureOptions::LINEAR,
-                    );
+                let color_image = egui::ColorImage::from_rgba_unmultiplied(
+                    size,
+                    pixels.as_slice(),
+                );
 
-                    let rect = egui::Rect::from_min_size(
-                        egui::pos2(screen_x, screen_y),
-                        egui::vec2(TILE_SIZE, TILE_SIZE),
-                    );
-                    painter.image(texture.id(), rect, egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)), egui::Color32::WHITE);
+                let texture = ctx.load_texture(
+                    format!("tile_{}_{}_{}", zoom, x, y),
+                    color_image,
+                    egui::TextureOptions::LINEAR,
+                );
 
-                    self.loaded_tiles.insert(key, texture);
-                }
+                let rect = egui::Rect::from_min_size(
+                    min,
+                    egui::vec2(TILE_SIZE, TILE_SIZE),
+                );
+                painter.image(texture.id(), rect, egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)), egui::Color32::WHITE);
+
+                self.loaded_tiles.insert(key, texture);
             }
             Err(_) => {
-                // Tile not in cache, download it
-                self.tile_cache.download_tile_async(zoom, x, y);
+                // Tile not in cache, download it (but not for every tile swept past mid-glide)
+                if self.recenter_animation.is_none() {
+                    self.tile_cache.download_tile_async(zoom, x, y);
+                }
                 
                 // Draw placeholder
                 let rect = egui::Rect::from_min_size(
-                    egui::pos2(screen_x, screen_y),
+                    min,
                     egui::vec2(TILE_SIZE, TILE_SIZE),
                 );
                 painter.rect_filled(rect, 0.0, egui::Color32::from_gray(240));
@@ -304,54 +1052,262 @@ impl MapWindow {
         }
     }
 
-    fn render_track_segment(&self, painter: &egui::Painter, segment: &crate::waypoint::TrackSegment, rect: egui::Rect) {
-        let points: Vec<egui::Pos2> = segment.points.iter()
-            .filter_map(|pt| self.lat_lon_to_screen(pt.latitude, pt.longitude, rect))
+    /// Names of the compared tracks beside their line colors, in the top left corner
+    fn render_comparison_legend(painter: &egui::Painter, rect: egui::Rect, tracks: [&Track; 2]) {
+        let [first, second] = tracks;
+        let galleys = [(first, COMPARE_COLORS[0]), (second, COMPARE_COLORS[1])].map(|(track, color)| {
+            painter.layout_no_wrap(format!("━━ {}", track.name), egui::FontId::proportional(13.0), color)
+        });
+        let width = galleys.iter().map(|g| g.size().x).fold(0.0, f32::max);
+        let line_height = galleys[0].size().y + 2.0;
+        let origin = rect.left_top() + egui::vec2(8.0, 8.0);
+        painter.rect_filled(
+            egui::Rect::from_min_size(origin, egui::vec2(width, line_height * 2.0)).expand(4.0),
+            4.0,
+            egui::Color32::from_black_alpha(160),
+        );
+        for (row, galley) in galleys.into_iter().enumerate() {
+            painter.galley(origin + egui::vec2(0.0, row as f32 * line_height), galley, egui::Color32::WHITE);
+        }
+    }
+
+    /// Grey, hollow marker and a notice while disconnected, so the last fix isn't taken as live
+    fn render_last_known_position(&self, painter: &egui::Painter, rect: egui::Rect, lat: f64, lon: f64) {
+        if let Some(pos) = self.lat_lon_to_screen(lat, lon, rect) {
+            let size = self.marker_style.position_size;
+            painter.circle_filled(pos, size, egui::Color32::from_gray(150));
+            painter.circle_stroke(pos, size, egui::Stroke::new(2.0, egui::Color32::from_gray(80)));
+        }
+
+        let galley = painter.layout_no_wrap(
+            "⏸ Disconnected — last known position".to_string(),
+            egui::FontId::proportional(14.0),
+            egui::Color32::WHITE,
+        );
+        let text_pos = egui::pos2(rect.center().x - galley.size().x / 2.0, rect.top() + 8.0);
+        painter.rect_filled(
+            egui::Rect::from_min_size(text_pos, galley.size()).expand(4.0),
+            4.0,
+            egui::Color32::from_black_alpha(160),
+        );
+        painter.galley(text_pos, galley, egui::Color32::WHITE);
+    }
+
+    /// Great circle from the position to the navigation target, labelled with
+    /// the distance and bearing at its middle, and a ring around the target
+    fn render_navigation(&self, painter: &egui::Painter, rect: egui::Rect, gps_data: &GpsData, target: &Waypoint) {
+        let painter = painter.with_clip_rect(rect);
+        if let Some(pos) = self.lat_lon_to_screen(target.latitude, target.longitude, rect) {
+            painter.circle_stroke(pos, self.marker_style.waypoint_size + 5.0, egui::Stroke::new(3.0, NAVIGATION_COLOR));
+        }
+        let Some((lat, lon)) = gps_data.latitude.zip(gps_data.longitude) else {
+            return;
+        };
+
+        let distance = target.distance_from(lat, lon);
+        let bearing = target.bearing_from(lat, lon);
+        let line = (0..=NAVIGATION_LINE_STEPS)
+            .map(|i| destination_point(lat, lon, bearing, distance * i as f64 / NAVIGATION_LINE_STEPS as f64));
+        let runs = if self.options.split_antimeridian {
+            crate::map::split_at_antimeridian(line)
+        } else {
+            vec![line.collect()]
+        };
+        let stroke = egui::Stroke::new(3.0, NAVIGATION_COLOR);
+        for run in runs {
+            let points: Vec<egui::Pos2> = run.iter()
+                .map(|&(lat, lon)| self.lat_lon_to_screen_unclipped(lat, lon, rect))
+                .collect();
+            painter.extend(egui::Shape::dashed_line(&points, stroke, 10.0, 6.0));
+        }
+
+        let (mid_lat, mid_lon) = destination_point(lat, lon, bearing, distance / 2.0);
+        if let Some(mid) = self.lat_lon_to_screen(mid_lat, mid_lon, rect) {
+            let galley = painter.layout_no_wrap(
+                navigation_label(target, gps_data, lat, lon, self.distance_units, self.north_reference),
+                egui::FontId::proportional(13.0),
+                egui::Color32::WHITE,
+            );
+            let label_rect = egui::Align2::CENTER_CENTER.anchor_size(mid, galley.size());
+            painter.rect_filled(label_rect.expand(4.0), 4.0, egui::Color32::from_black_alpha(160));
+            painter.galley(label_rect.min, galley, egui::Color32::WHITE);
+        }
+    }
+
+    fn render_track_segment(&self, painter: &egui::Painter, segment: &TrackSegment, rect: egui::Rect, stroke: egui::Stroke) {
+        let coordinates = segment.points.iter().map(|pt| (pt.latitude, pt.longitude));
+        let runs = if self.options.split_antimeridian {
+            crate::map::split_at_antimeridian(coordinates)
+        } else {
+            vec![coordinates.collect()]
+        };
+
+        let painter = painter.with_clip_rect(rect);
+        for run in runs {
+            // Project every point so lines leaving the view are clipped at its edge, not cut short
+            let points: Vec<egui::Pos2> = run.iter()
+                .map(|&(lat, lon)| self.lat_lon_to_screen_unclipped(lat, lon, rect))
+                .collect();
+            if points.len() > 1 {
+                painter.add(egui::Shape::line(points, stroke));
+            }
+        }
+    }
+
+    /// Thin trail of recent positions, fading out towards the oldest
+    fn render_breadcrumb(&self, painter: &egui::Painter, rect: egui::Rect) {
+        let count = self.breadcrumb.len();
+        if count < 2 {
+            return;
+        }
+
+        let positions: Vec<(f64, f64)> = self.breadcrumb.positions().collect();
+        let points: Vec<egui::Pos2> = positions.iter()
+            .map(|&(lat, lon)| self.lat_lon_to_screen_unclipped(lat, lon, rect))
             .collect();
+        let painter = painter.with_clip_rect(rect);
+        for (i, pair) in points.windows(2).enumerate() {
+            // Leave a gap at the antimeridian rather than a line across the map
+            if self.options.split_antimeridian && (positions[i + 1].1 - positions[i].1).abs() > 180.0 {
+                continue;
+            }
+            let alpha = 30 + (200 * (i + 1) / (count - 1)) as u8;
+            painter.line_segment(
+                [pair[0], pair[1]],
+                egui::Stroke::new(2.0, egui::Color32::from_rgba_unmultiplied(0, 122, 255, alpha)),
+            );
+        }
+    }
 
-        if points.len() > 1 {
-            painter.add(egui::Shape::line(
-                points,
-                egui::Stroke::new(3.0, egui::Color32::from_rgb(255, 0, 0)),
-            ));
+    /// Draw a lat/lon graticule at an interval suited to the visible span
+    fn render_grid(&self, painter: &egui::Painter, rect: egui::Rect) {
+        let (north, west) = self.screen_to_lat_lon(rect.left_top(), rect);
+        let (south, east) = self.screen_to_lat_lon(rect.right_bottom(), rect);
+
+        let interval = grid_interval((east - west).max(north - south));
+        let decimals = (-interval.log10().floor()).max(0.0) as usize;
+
+        let stroke = egui::Stroke::new(1.0, egui::Color32::from_rgba_unmultiplied(60, 60, 60, 140));
+        let font = egui::FontId::monospace(10.0);
+        let label_color = egui::Color32::from_gray(40);
+
+        // Meridians, labeled along the top edge
+        let mut lon = (west / interval).ceil() * interval;
+        while lon <= east {
+            let x = self.lat_lon_to_screen_unclipped(self.center_lat, lon, rect).x;
+            painter.line_segment([egui::pos2(x, rect.top()), egui::pos2(x, rect.bottom())], stroke);
+            painter.text(
+                egui::pos2(x + 2.0, rect.top() + 2.0),
+                egui::Align2::LEFT_TOP,
+                format!("{:.*}°", decimals, lon),
+                font.clone(),
+                label_color,
+            );
+            lon += interval;
+        }
+
+        // Parallels, labeled along the left edge
+        let mut lat = (south / interval).ceil() * interval;
+        while lat <= north {
+            let y = self.lat_lon_to_screen_unclipped(lat, self.center_lon, rect).y;
+            painter.line_segment([egui::pos2(rect.left(), y), egui::pos2(rect.right(), y)], stroke);
+            painter.text(
+                egui::pos2(rect.left() + 2.0, y - 2.0),
+                egui::Align2::LEFT_BOTTOM,
+                format!("{:.*}°", decimals, lat),
+                font.clone(),
+                label_color,
+            );
+            lat += interval;
         }
     }
 
     fn lat_lon_to_screen(&self, lat: f64, lon: f64, rect: egui::Rect) -> Option<egui::Pos2> {
+        let pos = self.lat_lon_to_screen_unclipped(lat, lon, rect);
+
+        // Check if on screen
+        if rect.contains(pos) {
+            Some(pos)
+        } else {
+            None
+        }
+    }
+
+    /// Project a coordinate to screen space without checking it is visible
+    fn lat_lon_to_screen_unclipped(&self, lat: f64, lon: f64, rect: egui::Rect) -> egui::Pos2 {
         let n = 2_f64.powi(self.zoom as i32);
         
         // Convert to pixel coordinates
         let world_x = (lon + 180.0) / 360.0 * n * TILE_SIZE as f64;
-        let lat_rad = lat.to_radians();
-        let world_y = (1.0 - (lat_rad.tan() + 1.0 / lat_rad.cos()).ln() / std::f64::consts::PI) / 2.0 * n * TILE_SIZE as f64;
+        let world_y = lat_to_world_y(lat) * n * TILE_SIZE as f64;
 
         // Convert center to world coordinates
         let center_world_x = (self.center_lon + 180.0) / 360.0 * n * TILE_SIZE as f64;
-        let center_lat_rad = self.center_lat.to_radians();
-        let center_world_y = (1.0 - (center_lat_rad.tan() + 1.0 / center_lat_rad.cos()).ln() / std::f64::consts::PI) / 2.0 * n * TILE_SIZE as f64;
+        let center_world_y = lat_to_world_y(self.center_lat) * n * TILE_SIZE as f64;
 
         // Calculate screen position
         let screen_x = rect.left() + rect.width() / 2.0 + (world_x - center_world_x) as f32;
         let screen_y = rect.top() + rect.height() / 2.0 + (world_y - center_world_y) as f32;
 
-        // Check if on screen
-        if screen_x >= rect.left() && screen_x <= rect.right() &&
-           screen_y >= rect.top() && screen_y <= rect.bottom() {
-            Some(egui::pos2(screen_x, screen_y))
-        } else {
-            None
-        }
+        egui::pos2(screen_x, screen_y)
+    }
+
+    /// Convert a ground distance at `lat` to screen pixels at the current zoom
+    fn meters_to_pixels(&self, meters: f64, lat: f64) -> f32 {
+        const EARTH_CIRCUMFERENCE: f64 = 40_075_016.686;
+        let n = 2_f64.powi(self.zoom as i32);
+        let meters_per_pixel = EARTH_CIRCUMFERENCE * lat.to_radians().cos() / (n * TILE_SIZE as f64);
+        (meters / meters_per_pixel) as f32
+    }
+
+    /// Small crosshair marking the pointer position
+    fn render_crosshair(painter: &egui::Painter, pos: egui::Pos2) {
+        const ARM: f32 = 10.0;
+        let stroke = egui::Stroke::new(1.0, egui::Color32::from_black_alpha(180));
+        painter.line_segment([pos - egui::vec2(ARM, 0.0), pos + egui::vec2(ARM, 0.0)], stroke);
+        painter.line_segment([pos - egui::vec2(0.0, ARM), pos + egui::vec2(0.0, ARM)], stroke);
+        painter.circle_stroke(pos, 3.0, stroke);
+    }
+
+    /// Convert a screen position back to (lat, lon)
+    fn screen_to_lat_lon(&self, pos: egui::Pos2, rect: egui::Rect) -> (f64, f64) {
+        let n = 2_f64.powi(self.zoom as i32);
+        let world_size = n * TILE_SIZE as f64;
+
+        let center_world_x = (self.center_lon + 180.0) / 360.0 * world_size;
+        let center_world_y = lat_to_world_y(self.center_lat) * world_size;
+
+        let world_x = center_world_x + (pos.x - rect.center().x) as f64;
+        let world_y = center_world_y + (pos.y - rect.center().y) as f64;
+
+        let lon = world_x / world_size * 360.0 - 180.0;
+        let lat = world_y_to_lat(world_y / world_size);
+        (lat, lon)
     }
 
+    /// Area shown on the map, or None when it wraps across the antimeridian
+    fn visible_area(&self, rect: egui::Rect) -> Option<BoundingBox> {
+        let (north, west) = self.screen_to_lat_lon(rect.left_top(), rect);
+        let (south, east) = self.screen_to_lat_lon(rect.right_bottom(), rect);
+        BoundingBox::new(south.max(-90.0), west.max(-180.0), north.min(90.0), east.min(180.0)).ok()
+    }
+
+    /// Move the view by `delta` screen pixels. Every manual pan restarts the
+    /// auto-follow idle timer.
     fn pan_map(&mut self, delta: egui::Vec2) {
+        // Dragging takes over from any recenter in progress
+        self.recenter_animation = None;
+        self.last_pan = Some(Instant::now());
+
         let n = 2_f64.powi(self.zoom as i32);
         let pixels_per_degree_lon = n * TILE_SIZE as f64 / 360.0;
         
+        // Mercator stretches latitude by 1/cos(lat); screen y grows southward
         let lat_rad = self.center_lat.to_radians();
-        let pixels_per_degree_lat = n * TILE_SIZE as f64 * lat_rad.cos() / 360.0;
+        let pixels_per_degree_lat = n * TILE_SIZE as f64 / lat_rad.cos() / 360.0;
 
         self.center_lon -= (delta.x / pixels_per_degree_lon as f32) as f64;
-        self.center_lat -= (delta.y / pixels_per_degree_lat as f32) as f64;
+        self.center_lat += (delta.y / pixels_per_degree_lat as f32) as f64;
 
         // Clamp coordinates
         self.center_lat = self.center_lat.clamp(-85.0, 85.0);
@@ -360,5 +1316,371 @@ impl MapWindow {
 
     pub fn on_close(&mut self) {
         self.preload_triggered = false;
+        self.memory_warmed = false;
+        if let Some(animation) = self.recenter_animation.take() {
+            (self.center_lat, self.center_lon) = animation.to;
+        }
+        self.last_prefetch_tile = None;
+        // No need to hold GPU textures while the window is closed
+        self.loaded_tiles.clear();
+    }
+}
+
+/// Map center that puts (lat, lon) `offset_px` pixels below the middle of the view
+fn offset_follow_center(lat: f64, lon: f64, zoom: u8, offset_px: f64) -> (f64, f64) {
+    let world_size = 2_f64.powi(zoom as i32) * TILE_SIZE as f64;
+    let world_y = lat_to_world_y(lat) * world_size;
+    let center_y = (world_y - offset_px).max(0.0);
+    let center_lat = world_y_to_lat(center_y / world_size);
+    (center_lat.clamp(-85.0, 85.0), lon)
+}
+
+/// Line style for saved tracks, or for the track being recorded when `active`
+fn track_stroke(style: &MarkerStyle, active: bool) -> egui::Stroke {
+    let color = if active { style.active_track_color } else { style.track_color };
+    egui::Stroke::new(style.track_width, faded_track_color(color, style.track_opacity))
+}
+
+/// `stroke` in the track's own color, if it has one, at the style's opacity
+fn saved_track_stroke(stroke: egui::Stroke, track: &Track, opacity: f32) -> egui::Stroke {
+    match track.color {
+        Some(color) => egui::Stroke::new(stroke.width, faded_track_color(color, opacity)),
+        None => stroke,
+    }
+}
+
+/// `color` drawn at `opacity`, so the map shows through the track
+fn faded_track_color([r, g, b]: [u8; 3], opacity: f32) -> egui::Color32 {
+    egui::Color32::from_rgba_unmultiplied(r, g, b, (opacity.clamp(0.0, 1.0) * 255.0) as u8)
+}
+
+/// Distance in screen pixels at `zoom` between two positions
+fn world_distance_px(from: (f64, f64), to: (f64, f64), zoom: u8) -> f64 {
+    let world_size = 2_f64.powi(zoom as i32) * TILE_SIZE as f64;
+    let world = |(lat, lon): (f64, f64)| ((lon + 180.0) / 360.0 * world_size, lat_to_world_y(lat) * world_size);
+    let ((x1, y1), (x2, y2)) = (world(from), world(to));
+    (x2 - x1).hypot(y2 - y1)
+}
+
+/// Center and highest zoom that show both positions in a map of size
+/// `view`, `FIT_MARGIN_PX` in from its edges. Identical positions get the
+/// highest zoom.
+fn fit_view(a: (f64, f64), b: (f64, f64), view: egui::Vec2) -> ((f64, f64), u8) {
+    // Web Mercator position as a fraction of the world's width and height
+    let world = |(lat, lon): (f64, f64)| ((lon + 180.0) / 360.0, lat_to_world_y(lat.clamp(-85.0, 85.0)));
+    let ((x1, y1), (mut x2, y2)) = (world(a), world(b));
+    // The short way round across the antimeridian
+    if x2 - x1 > 0.5 {
+        x2 -= 1.0;
+    } else if x1 - x2 > 0.5 {
+        x2 += 1.0;
+    }
+
+    let (x, y) = ((x1 + x2) / 2.0, (y1 + y2) / 2.0);
+    let center = (world_y_to_lat(y), x.rem_euclid(1.0) * 360.0 - 180.0);
+
+    let usable = |size: f32| f64::from((size - 2.0 * FIT_MARGIN_PX).max(1.0));
+    let (width, height) = ((x2 - x1).abs(), (y2 - y1).abs());
+    let zoom = (1..=MAX_ZOOM)
+        .rev()
+        .find(|&zoom| {
+            let world_size = 2_f64.powi(zoom as i32) * TILE_SIZE as f64;
+            width * world_size <= usable(view.x) && height * world_size <= usable(view.y)
+        })
+        .unwrap_or(1);
+    (center, zoom)
+}
+
+/// Distance and bearing from (lat, lon) to the navigation target, e.g.
+/// "1.25 km · 047° T". The bearing is magnetic when that is preferred and
+/// `data` has the variation, like the course.
+fn navigation_label(target: &Waypoint, data: &GpsData, lat: f64, lon: f64, units: DistanceUnits, north: NorthReference) -> String {
+    let (bearing, reference) = data.relative_to_north(target.bearing_from(lat, lon), north);
+    format!("{} · {:03.0}° {}", units.format_distance(target.distance_from(lat, lon)), bearing, reference.suffix())
+}
+
+/// Index of the step in `steps` (ascending by speed) for `speed` km/h.
+/// Moving off the `current` step needs the speed to clear the boundary by
+/// `SPEED_ZOOM_HYSTERESIS_KMH`.
+fn speed_zoom_step(steps: &[SpeedZoomStep], speed: f64, current: Option<usize>) -> usize {
+    let Some(mut step) = current.filter(|&i| i < steps.len()) else {
+        return steps.iter().rposition(|s| s.min_speed <= speed).unwrap_or(0);
+    };
+    while step + 1 < steps.len() && speed >= steps[step + 1].min_speed + SPEED_ZOOM_HYSTERESIS_KMH {
+        step += 1;
+    }
+    while step > 0 && speed < steps[step].min_speed - SPEED_ZOOM_HYSTERESIS_KMH {
+        step -= 1;
+    }
+    step
+}
+
+/// Pick a "nice" grid spacing in degrees giving a handful of lines across `span`
+fn grid_interval(span: f64) -> f64 {
+    const STEPS: [f64; 16] = [
+        30.0, 10.0, 5.0, 2.0, 1.0, 0.5, 0.2, 0.1,
+        0.05, 0.02, 0.01, 0.005, 0.002, 0.001, 0.0005, 0.0002,
+    ];
+    let target = span / 6.0;
+    STEPS.iter()
+        .rev()
+        .copied()
+        .find(|&step| step >= target)
+        .unwrap_or(STEPS[0])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_heading_filter_response() {
+        let options = MapOptions { heading_smoothing_secs: 1.0, heading_min_speed: 3.0, ..Default::default() };
+        let mut filter = HeadingFilter::new();
+
+        // The first heading is taken as is, then a step change is approached gradually
+        assert_eq!(filter.update(Some(90.0), Some(30.0), 0.0, &options), Some(90.0));
+        let after_one = filter.update(Some(180.0), Some(30.0), 1.0, &options).unwrap();
+        assert!((after_one - (90.0 + 90.0 * (1.0 - (-1.0f64).exp()))).abs() < 1e-9);
+        let mut heading = after_one;
+        for _ in 0..10 {
+            heading = filter.update(Some(180.0), Some(30.0), 1.0, &options).unwrap();
+        }
+        assert!((heading - 180.0).abs() < 0.01);
+
+        // A wobble across north doesn't swing the heading through south
+        let mut filter = HeadingFilter::new();
+        filter.update(Some(350.0), Some(30.0), 0.0, &options);
+        let heading = filter.update(Some(10.0), Some(30.0), 0.5, &options).unwrap();
+        assert!(!(10.0..=350.0).contains(&heading), "{}", heading);
+
+        // Slow or stopped: no heading, and the filter starts over once moving
+        assert_eq!(filter.update(Some(200.0), Some(1.0), 1.0, &options), None);
+        assert_eq!(filter.update(Some(200.0), None, 1.0, &options), None);
+        assert_eq!(filter.update(Some(120.0), Some(10.0), 1.0, &options), Some(120.0));
+
+        // No smoothing passes the course straight through
+        let raw = MapOptions { heading_smoothing_secs: 0.0, ..options };
+        assert_eq!(filter.update(Some(45.0), Some(10.0), 1.0, &raw), Some(45.0));
+    }
+
+    #[tokio::test]
+    async fn test_map_keys() {
+        let pressed = |keys: &[egui::Key]| {
+            let ctx = egui::Context::default();
+            let events = keys.iter()
+                .map(|&key| egui::Event::Key {
+                    key,
+                    physical_key: None,
+                    pressed: true,
+                    repeat: false,
+                    modifiers: egui::Modifiers::NONE,
+                })
+                .collect();
+            let mut actions = Vec::new();
+            let _ = ctx.run(egui::RawInput { events, ..Default::default() }, |ctx| {
+                actions = ctx.input(|i| map_keys(i, egui::vec2(800.0, 600.0)));
+            });
+            actions
+        };
+
+        assert_eq!(pressed(&[egui::Key::ArrowRight]), vec![MapKey::Pan(egui::vec2(-200.0, 0.0))]);
+        assert_eq!(pressed(&[egui::Key::ArrowUp]), vec![MapKey::Pan(egui::vec2(0.0, 150.0))]);
+        assert_eq!(pressed(&[egui::Key::Equals, egui::Key::Minus]), vec![MapKey::ZoomIn, MapKey::ZoomOut]);
+        assert_eq!(pressed(&[egui::Key::Home]), vec![MapKey::Home]);
+        assert!(pressed(&[egui::Key::A]).is_empty());
+
+        // The right arrow shows what's to the east, the up arrow what's north
+        let cache_dir = std::env::temp_dir().join(format!("gps-monitor-map-keys-{}", std::process::id()));
+        let mut map = MapWindow::new(TileCache::new(cache_dir.clone(), tokio::runtime::Handle::current()).unwrap());
+        let (lat, lon) = (map.center_lat, map.center_lon);
+        map.follow_position = true;
+        map.apply_map_key(MapKey::Pan(egui::vec2(-200.0, 0.0)), &GpsData::new());
+        assert!(map.center_lon > lon && map.center_lat == lat);
+        assert!(!map.follow_position);
+        map.apply_map_key(MapKey::Pan(egui::vec2(0.0, 150.0)), &GpsData::new());
+        assert!(map.center_lat > lat);
+
+        // Like a drag, what was at the center moves by the pan delta
+        let view = egui::Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(800.0, 600.0));
+        let before = (map.center_lat, map.center_lon);
+        map.apply_map_key(MapKey::Pan(egui::vec2(0.0, -150.0)), &GpsData::new());
+        let moved = map.lat_lon_to_screen_unclipped(before.0, before.1, view) - view.center();
+        assert!((moved - egui::vec2(0.0, -150.0)).length() < 1.0, "{:?}", moved);
+
+        map.apply_map_key(MapKey::ZoomIn, &GpsData::new());
+        assert_eq!(map.zoom, 14);
+        let _ = std::fs::remove_dir_all(cache_dir);
+    }
+
+    #[tokio::test]
+    async fn test_pan_map_follows_drag() {
+        let cache_dir = std::env::temp_dir().join(format!("gps-monitor-pan-{}", std::process::id()));
+        let mut map = MapWindow::new(TileCache::new(cache_dir.clone(), tokio::runtime::Handle::current()).unwrap());
+        let view = egui::Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(800.0, 600.0));
+
+        // Dragging down brings what's to the north into view
+        let (lat, lon) = (map.center_lat, map.center_lon);
+        assert!(map.last_pan.is_none());
+        map.pan_map(egui::vec2(0.0, 150.0));
+        assert!(map.center_lat > lat && map.center_lon == lon);
+        // Any pan pauses auto-follow, not just a drag
+        assert!(map.last_pan.is_some());
+
+        // What was at the center moves with the pointer
+        let before = (map.center_lat, map.center_lon);
+        map.pan_map(egui::vec2(40.0, -150.0));
+        let moved = map.lat_lon_to_screen_unclipped(before.0, before.1, view) - view.center();
+        assert!((moved - egui::vec2(40.0, -150.0)).length() < 1.0, "{:?}", moved);
+        let _ = std::fs::remove_dir_all(cache_dir);
+    }
+
+    #[tokio::test]
+    async fn test_scroll_and_pinch_zoom_restart_auto_follow() {
+        let cache_dir = std::env::temp_dir().join(format!("gps-monitor-scroll-zoom-{}", std::process::id()));
+        let mut map = MapWindow::new(TileCache::new(cache_dir.clone(), tokio::runtime::Handle::current()).unwrap());
+        map.follow_position = false;
+        let zoom = map.zoom;
+
+        // Scroll builds up to a whole level before zooming
+        map.zoom_from_input(1.0, SCROLL_PER_ZOOM_LEVEL / 2.0);
+        assert_eq!(map.zoom, zoom);
+        assert!(map.last_pan.is_none());
+        map.zoom_from_input(1.0, SCROLL_PER_ZOOM_LEVEL / 2.0);
+        assert_eq!(map.zoom, zoom + 1);
+        let scrolled = map.last_pan.expect("scroll zoom should restart the timer");
+
+        // Pinching out by half zooms out a level
+        std::thread::sleep(Duration::from_millis(5));
+        map.zoom_from_input(0.5, 0.0);
+        assert_eq!(map.zoom, zoom);
+        assert!(map.last_pan.unwrap() > scrolled);
+        let _ = std::fs::remove_dir_all(cache_dir);
+    }
+
+    #[test]
+    fn test_speed_zoom_step_hysteresis() {
+        let steps = SpeedZoomStep::defaults();  // 0 -> z16, 20 -> z14, 60 -> z12
+        let zoom = |step: usize| steps[step].zoom;
+
+        // No current step: plain thresholds
+        assert_eq!(zoom(speed_zoom_step(&steps, 10.0, None)), 16);
+        assert_eq!(zoom(speed_zoom_step(&steps, 20.0, None)), 14);
+        assert_eq!(zoom(speed_zoom_step(&steps, 100.0, None)), 12);
+
+        // Speeding up from town needs 25 km/h to zoom out
+        assert_eq!(speed_zoom_step(&steps, 22.0, Some(0)), 0);
+        assert_eq!(speed_zoom_step(&steps, 25.0, Some(0)), 1);
+        // and slowing back down needs under 15 km/h to zoom in again
+        assert_eq!(speed_zoom_step(&steps, 18.0, Some(1)), 1);
+        assert_eq!(speed_zoom_step(&steps, 14.0, Some(1)), 0);
+
+        // Big changes cross several steps at once
+        assert_eq!(speed_zoom_step(&steps, 90.0, Some(0)), 2);
+        assert_eq!(speed_zoom_step(&steps, 0.0, Some(2)), 0);
+
+        // A stale index from a longer step list is ignored
+        assert_eq!(speed_zoom_step(&steps, 30.0, Some(7)), 1);
+    }
+
+    #[test]
+    fn test_follow_offset_puts_position_below_center() {
+        // An offset of one tile height centers the map on the top edge of
+        // the tile above the one the position is at the top of
+        for (lat, zoom) in [(42.44, 15), (-33.9, 10), (0.0, 3)] {
+            let (x, y) = lat_lon_to_tile(lat, -71.12, zoom);
+            let (tile_top, _) = crate::map::tile_to_lat_lon(x, y, zoom);
+            let (center_lat, center_lon) = offset_follow_center(tile_top, -71.12, zoom, TILE_SIZE as f64);
+            assert_eq!(center_lon, -71.12);
+            assert!((center_lat - crate::map::tile_to_lat_lon(x, y - 1, zoom).0).abs() < 1e-9);
+        }
+
+        assert_eq!(offset_follow_center(42.44, -71.12, 15, 0.0), (42.44, -71.12));
+    }
+
+    #[test]
+    fn test_navigation_label_north_reference() {
+        let mut data = GpsData::new();
+        data.latitude = Some(42.0);
+        data.longitude = Some(-71.0);
+        let target = Waypoint::from_gps_data(&GpsData { latitude: Some(42.0), longitude: Some(-70.99), ..data.clone() }, "East".to_string(), None).unwrap();
+        let label = |data: &GpsData, north| navigation_label(&target, data, 42.0, -71.0, DistanceUnits::Metric, north);
+
+        // Due east is about 090° true
+        assert!(label(&data, NorthReference::True).ends_with("090° T"));
+        // Magnetic needs the variation; without it the bearing stays true
+        assert!(label(&data, NorthReference::Magnetic).ends_with("090° T"));
+        data.magnetic_variation = Some(-14.5);
+        assert!(label(&data, NorthReference::Magnetic).ends_with("104° M"));
+        assert!(label(&data, NorthReference::True).ends_with("090° T"));
+    }
+
+    #[test]
+    fn test_fit_view_shows_both_points() {
+        let view = egui::vec2(800.0, 600.0);
+        let pixels = |a, b, zoom| world_distance_px(a, b, zoom);
+
+        // About 2.2 km apart north-south: the highest zoom where they fit
+        let (a, b) = ((42.43, -71.12), (42.45, -71.12));
+        let ((lat, lon), zoom) = fit_view(a, b, view);
+        assert!((lat - 42.44).abs() < 1e-3);
+        assert!((lon + 71.12).abs() < 1e-9);
+        assert!(pixels(a, b, zoom) <= f64::from(view.y - 2.0 * FIT_MARGIN_PX));
+        assert!(pixels(a, b, zoom + 1) > f64::from(view.y - 2.0 * FIT_MARGIN_PX));
+        assert_eq!(zoom, 14);
+
+        // A wider view fits an east-west pair one zoom closer than a narrow one
+        let (c, d) = ((42.44, -71.15), (42.44, -71.10));
+        let wide = fit_view(c, d, egui::vec2(1600.0, 600.0)).1;
+        assert_eq!(fit_view(c, d, egui::vec2(800.0, 600.0)).1 + 1, wide);
+
+        // Far apart, and the same point
+        assert_eq!(fit_view((51.5, -0.1), (-33.9, 151.2), view).1, 2);
+        let (center, zoom) = fit_view(a, a, view);
+        assert_eq!(zoom, MAX_ZOOM);
+        assert!(pixels(center, a, MAX_ZOOM) < 1e-6);
+
+        // Across the antimeridian the center is there, not on the far side of the world
+        let ((_, lon), zoom) = fit_view((-17.0, 179.5), (-17.0, -179.5), view);
+        assert!(lon.abs() > 179.9, "{}", lon);
+        assert!(zoom >= 7);
+    }
+
+    #[test]
+    fn test_follow_dead_zone_distance() {
+        let dead_zone = f64::from(MapOptions::default().follow_dead_zone_px);
+        let center = (42.438878, -71.119277);
+
+        // About a meter of jitter stays inside the dead zone at street zoom
+        let jitter = (center.0 + 0.00001, center.1 - 0.00001);
+        assert!(world_distance_px(center, jitter, 16) < dead_zone);
+
+        // Driving off the block leaves it, and so does the same move zoomed further in
+        let moved = (center.0 + 0.001, center.1);
+        assert!(world_distance_px(center, moved, 16) > dead_zone);
+        assert!(world_distance_px(center, jitter, 22) > dead_zone);
+
+        // One degree of longitude at zoom 0 is 256/360 pixels
+        assert!((world_distance_px((0.0, 0.0), (0.0, 1.0), 0) - 256.0 / 360.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_active_track_stroke_is_distinct() {
+        let style = MarkerStyle { track_opacity: 0.5, ..MarkerStyle::default() };
+        let saved = track_stroke(&style, false);
+        let active = track_stroke(&style, true);
+
+        // Half transparent
+        assert_eq!(saved.color, egui::Color32::from_rgba_unmultiplied(255, 0, 0, 127));
+        assert_eq!(active.color, egui::Color32::from_rgba_unmultiplied(0, 220, 0, 127));
+        assert_eq!(active.width, saved.width);
+
+        // Only the recording color follows the new setting
+        let style = MarkerStyle { active_track_color: [255, 255, 0], ..style };
+        assert_eq!(track_stroke(&style, false), saved);
+        assert_eq!(track_stroke(&style, true).color, egui::Color32::from_rgba_unmultiplied(255, 255, 0, 127));
+
+        let opaque = MarkerStyle { track_opacity: 1.0, ..style };
+        assert_eq!(track_stroke(&opaque, false).color, egui::Color32::from_rgb(255, 0, 0));
     }
 }
@@ -1,4 +1,4 @@
-// src/display/gui/mod.rs v10
+// src/display/gui/mod.rs v23
 //! GUI display module - Pure egui implementation
 
 pub mod app;
@@ -7,8 +7,17 @@ mod satellites;
 mod skyplot;
 mod settings;
 mod waypoint_dialog;
-mod track_recorder;
+mod track_playback;
+mod track_compare;
 mod map_window;
+mod nmea_inspector;
+mod fix_events;
+mod breadcrumb;
+mod offline_download;
+mod speedometer;
+mod antenna_assistant;
+mod palette;
+mod recording_indicator;
 
 pub use app::{GpsGuiApp, SatelliteSortColumn};
 pub use settings::SettingsWindow;
@@ -0,0 +1,99 @@
+// src/display/gui/nmea_inspector.rs v2
+//! Field-by-field view of a single NMEA sentence
+
+use crate::gps::{nmea_decoder::{decode_sentence_with_speed_unit, DecodedSentence}, SpeedUnit};
+use eframe::egui;
+
+pub struct NmeaInspector {
+    pub open: bool,
+    sentence: String,
+    decoded: Option<DecodedSentence>,
+}
+
+impl NmeaInspector {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            sentence: String::new(),
+            decoded: None,
+        }
+    }
+
+    /// Decode `sentence`, with RMC speed in `rmc_speed_unit`, and open the window on it
+    pub fn inspect(&mut self, sentence: &str, rmc_speed_unit: SpeedUnit) {
+        self.sentence = sentence.trim().to_string();
+        self.decoded = Some(decode_sentence_with_speed_unit(&self.sentence, rmc_speed_unit));
+        self.open = true;
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context) {
+        if !self.open {
+            return;
+        }
+
+        let mut open = self.open;
+        egui::Window::new("🔍 NMEA Inspector")
+            .open(&mut open)
+            .resizable(true)
+            .default_width(520.0)
+            .show(ctx, |ui| {
+                ui.monospace(&self.sentence);
+                ui.separator();
+
+                let Some(decoded) = &self.decoded else {
+                    ui.weak("Click a sentence in the bottom panel to inspect it");
+                    return;
+                };
+
+                egui::Grid::new("nmea_inspector_header")
+                    .num_columns(2)
+                    .spacing([10.0, 4.0])
+                    .show(ui, |ui| {
+                        if !decoded.talker.is_empty() {
+                            ui.label("Talker:");
+                            ui.monospace(&decoded.talker);
+                            ui.end_row();
+                        }
+                        ui.label("Type:");
+                        ui.monospace(&decoded.sentence_type);
+                        ui.end_row();
+                        ui.label("Description:");
+                        ui.label(decoded.description);
+                        ui.end_row();
+                    });
+
+                ui.add_space(6.0);
+
+                egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                    egui::Grid::new("nmea_inspector_fields")
+                        .num_columns(3)
+                        .striped(true)
+                        .spacing([12.0, 4.0])
+                        .show(ui, |ui| {
+                            ui.strong("Field");
+                            ui.strong("Raw");
+                            ui.strong("Value");
+                            ui.end_row();
+
+                            for field in &decoded.fields {
+                                ui.label(&field.name);
+                                ui.monospace(&field.raw);
+                                if field.value.starts_with("Mismatch") {
+                                    ui.colored_label(egui::Color32::RED, &field.value);
+                                } else {
+                                    ui.label(&field.value);
+                                }
+                                ui.end_row();
+                            }
+                        });
+                });
+            });
+        self.open = open;
+    }
+}
+
+impl Default for NmeaInspector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,195 @@
+// src/display/gui/offline_download.rs v2
+//! Batch download of map tiles for an area, for use without connectivity
+
+use crate::map::{BoundingBox, RegionDownload, TileCache, MAX_REGION_TILES, MAX_ZOOM};
+use eframe::egui;
+use std::sync::Arc;
+
+/// Ask for confirmation before queueing more tiles than this
+const LARGE_REGION_TILES: u64 = 5_000;
+
+/// Rough size of an OSM tile on disk, for the download estimate
+const AVERAGE_TILE_KB: f64 = 20.0;
+
+pub struct OfflineDownload {
+    pub open: bool,
+    south: String,
+    west: String,
+    north: String,
+    east: String,
+    zoom_min: u8,
+    zoom_max: u8,
+    confirm_large: bool,  // Large download acknowledged for the current tile count
+    download: Option<Arc<RegionDownload>>,
+    error: Option<String>,
+}
+
+impl OfflineDownload {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            south: String::new(),
+            west: String::new(),
+            north: String::new(),
+            east: String::new(),
+            zoom_min: 10,
+            zoom_max: 15,
+            confirm_large: false,
+            download: None,
+            error: None,
+        }
+    }
+
+    /// Whether a batch is still running (so the map keeps repainting)
+    pub fn is_downloading(&self) -> bool {
+        self.download.as_ref().is_some_and(|d| !d.is_finished())
+    }
+
+    fn set_area(&mut self, area: BoundingBox) {
+        self.south = format!("{:.5}", area.south);
+        self.west = format!("{:.5}", area.west);
+        self.north = format!("{:.5}", area.north);
+        self.east = format!("{:.5}", area.east);
+    }
+
+    fn bounding_box(&self) -> Option<BoundingBox> {
+        let value = |text: &str| text.trim().parse::<f64>().ok();
+        BoundingBox::new(value(&self.south)?, value(&self.west)?, value(&self.north)?, value(&self.east)?).ok()
+    }
+
+    /// Show the download window. `visible_area` is what the map currently
+    /// shows, offered as the area and used when none has been entered yet.
+    pub fn show(&mut self, ctx: &egui::Context, tile_cache: &TileCache, visible_area: Option<BoundingBox>, zoom: u8) {
+        if !self.open {
+            return;
+        }
+        if let Some(area) = visible_area.filter(|_| self.south.is_empty()) {
+            self.set_area(area);
+            self.zoom_min = zoom;
+            self.zoom_max = (zoom + 3).min(MAX_ZOOM);
+        }
+
+        let mut open = self.open;
+        egui::Window::new("⬇ Offline Area")
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| self.render_contents(ui, tile_cache, visible_area));
+        self.open = open;
+    }
+
+    fn render_contents(&mut self, ui: &mut egui::Ui, tile_cache: &TileCache, visible_area: Option<BoundingBox>) {
+        let busy = self.is_downloading();
+
+        ui.add_enabled_ui(!busy, |ui| {
+            egui::Grid::new("offline_area_grid")
+                .num_columns(4)
+                .spacing([8.0, 6.0])
+                .show(ui, |ui| {
+                    ui.label("North:");
+                    ui.add(egui::TextEdit::singleline(&mut self.north).desired_width(90.0));
+                    ui.label("South:");
+                    ui.add(egui::TextEdit::singleline(&mut self.south).desired_width(90.0));
+                    ui.end_row();
+
+                    ui.label("West:");
+                    ui.add(egui::TextEdit::singleline(&mut self.west).desired_width(90.0));
+                    ui.label("East:");
+                    ui.add(egui::TextEdit::singleline(&mut self.east).desired_width(90.0));
+                    ui.end_row();
+
+                    ui.label("Zoom:");
+                    ui.add(egui::DragValue::new(&mut self.zoom_min).range(0..=MAX_ZOOM));
+                    ui.label("to");
+                    ui.add(egui::DragValue::new(&mut self.zoom_max).range(self.zoom_min..=MAX_ZOOM));
+                    ui.end_row();
+                });
+
+            if let Some(area) = visible_area {
+                if ui.button("Use current map view").clicked() {
+                    self.set_area(area);
+                }
+            }
+        });
+
+        ui.separator();
+
+        let Some(area) = self.bounding_box() else {
+            ui.colored_label(egui::Color32::RED, "Enter an area in degrees with south below north and west left of east");
+            return;
+        };
+        self.zoom_max = self.zoom_max.max(self.zoom_min);
+        let count = area.tile_count(self.zoom_min, self.zoom_max);
+        ui.label(format!(
+            "{} tiles, about {:.0} MB",
+            count,
+            count as f64 * AVERAGE_TILE_KB / 1024.0
+        ));
+
+        if let Some(ref download) = self.download {
+            Self::render_progress(ui, download);
+            if busy {
+                return;
+            }
+        }
+
+        if count > MAX_REGION_TILES {
+            ui.colored_label(
+                egui::Color32::RED,
+                format!("Over the {} tile limit; choose a smaller area or zoom range", MAX_REGION_TILES),
+            );
+            return;
+        }
+        if count > LARGE_REGION_TILES {
+            ui.colored_label(
+                egui::Color32::YELLOW,
+                "Large download: this takes a while and puts load on the OpenStreetMap servers",
+            );
+            ui.checkbox(&mut self.confirm_large, "Download anyway");
+        }
+
+        let allowed = count <= LARGE_REGION_TILES || self.confirm_large;
+        if ui.add_enabled(allowed, egui::Button::new("⬇ Download")).clicked() {
+            match tile_cache.download_region(area, self.zoom_min, self.zoom_max) {
+                Ok(download) => {
+                    self.download = Some(download);
+                    self.error = None;
+                }
+                Err(e) => self.error = Some(e.to_string()),
+            }
+            self.confirm_large = false;
+        }
+        if let Some(ref error) = self.error {
+            ui.colored_label(egui::Color32::RED, error);
+        }
+    }
+
+    fn render_progress(ui: &mut egui::Ui, download: &RegionDownload) {
+        ui.add(
+            egui::ProgressBar::new(download.fraction())
+                .text(format!("{} / {}", download.completed() + download.failed(), download.total())),
+        );
+
+        let failed = download.failed();
+        if !download.is_finished() {
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.label(if download.is_cancelled() { "Finishing downloads in progress..." } else { "Downloading..." });
+                if !download.is_cancelled() && ui.button("Cancel").clicked() {
+                    download.cancel();
+                }
+            });
+        } else if download.is_cancelled() {
+            ui.label(format!("Cancelled after {} tiles", download.completed()));
+        } else if failed > 0 {
+            ui.colored_label(egui::Color32::YELLOW, format!("Done; {} tiles failed and can be retried", failed));
+        } else {
+            ui.colored_label(egui::Color32::GREEN, "Done; the area is available offline");
+        }
+    }
+}
+
+impl Default for OfflineDownload {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,168 @@
+// src/display/gui/palette.rs v2
+//! Constellation and signal quality colors for each selectable palette.
+//! Renderers take their colors from here so a palette change applies everywhere.
+
+use crate::config::ColorPalette;
+use crate::gps::SignalQuality;
+use eframe::egui::Color32;
+
+/// Constellations shown in the sky plot legend, with their short labels
+pub const LEGEND: [(&str, &str); 5] = [
+    ("GPS", "GPS"),
+    ("GLONASS", "GLO"),
+    ("GALILEO", "GAL"),
+    ("BEIDOU", "BDS"),
+    ("QZSS", "QZS"),
+];
+
+/// Color of a used satellite from the given constellation
+pub fn constellation_color(palette: ColorPalette, constellation: &str) -> Color32 {
+    match palette {
+        ColorPalette::Standard => match constellation {
+            "GPS" => Color32::from_rgb(0, 150, 255),
+            "GLONASS" => Color32::from_rgb(255, 100, 100),
+            "GALILEO" => Color32::from_rgb(100, 255, 100),
+            "BEIDOU" => Color32::from_rgb(255, 255, 100),
+            "QZSS" => Color32::from_rgb(255, 150, 0),
+            _ => Color32::WHITE,
+        },
+        ColorPalette::ColorblindSafe => match constellation {
+            "GPS" => Color32::from_rgb(0, 114, 178),
+            "GLONASS" => Color32::from_rgb(213, 94, 0),
+            "GALILEO" => Color32::from_rgb(0, 158, 115),
+            "BEIDOU" => Color32::from_rgb(240, 228, 66),
+            "QZSS" => Color32::from_rgb(204, 121, 167),
+            _ => Color32::WHITE,
+        },
+        ColorPalette::HighContrast => match constellation {
+            "GPS" => Color32::from_rgb(0, 220, 255),
+            "GLONASS" => Color32::from_rgb(255, 0, 255),
+            "GALILEO" => Color32::from_rgb(0, 255, 0),
+            "BEIDOU" => Color32::from_rgb(255, 255, 0),
+            "QZSS" => Color32::from_rgb(255, 128, 0),
+            _ => Color32::WHITE,
+        },
+    }
+}
+
+/// Color of a satellite that isn't used in the fix
+pub fn unused_color(palette: ColorPalette) -> Color32 {
+    match palette {
+        ColorPalette::Standard => Color32::GRAY,
+        ColorPalette::ColorblindSafe => Color32::from_gray(110),
+        ColorPalette::HighContrast => Color32::from_gray(70),
+    }
+}
+
+/// Share of the gray with the same brightness mixed into a dimmed color
+const DIM_DESATURATION: f32 = 0.5;
+
+/// Brightness kept by a dimmed color
+const DIM_BRIGHTNESS: f32 = 0.6;
+
+/// Desaturated, darker version of `color`, e.g. an unused satellite in its
+/// constellation's color. The hue is kept so the constellation stays visible.
+pub fn dimmed(color: Color32) -> Color32 {
+    let [r, g, b, a] = color.to_array();
+    let gray = 0.299 * r as f32 + 0.587 * g as f32 + 0.114 * b as f32;
+    let dim = |channel: u8| {
+        let desaturated = channel as f32 + (gray - channel as f32) * DIM_DESATURATION;
+        (desaturated * DIM_BRIGHTNESS).round() as u8
+    };
+    Color32::from_rgba_unmultiplied(dim(r), dim(g), dim(b), a)
+}
+
+/// Color of the "used" marker in the satellite table
+pub fn used_color(palette: ColorPalette) -> Color32 {
+    quality_color(palette, SignalQuality::Excellent)
+}
+
+/// Display color for a signal quality level
+pub fn quality_color(palette: ColorPalette, quality: SignalQuality) -> Color32 {
+    match palette {
+        ColorPalette::Standard => match quality {
+            SignalQuality::Excellent => Color32::GREEN,
+            SignalQuality::Good => Color32::from_rgb(144, 238, 144),
+            SignalQuality::Fair => Color32::YELLOW,
+            SignalQuality::Poor => Color32::from_rgb(255, 165, 0),
+            SignalQuality::VeryPoor => Color32::RED,
+            SignalQuality::Unknown => Color32::GRAY,
+        },
+        ColorPalette::ColorblindSafe => match quality {
+            SignalQuality::Excellent => Color32::from_rgb(0, 114, 178),
+            SignalQuality::Good => Color32::from_rgb(86, 180, 233),
+            SignalQuality::Fair => Color32::from_rgb(240, 228, 66),
+            SignalQuality::Poor => Color32::from_rgb(230, 159, 0),
+            SignalQuality::VeryPoor => Color32::from_rgb(213, 94, 0),
+            SignalQuality::Unknown => Color32::from_gray(110),
+        },
+        ColorPalette::HighContrast => match quality {
+            SignalQuality::Excellent => Color32::from_rgb(0, 255, 0),
+            SignalQuality::Good => Color32::from_rgb(0, 220, 255),
+            SignalQuality::Fair => Color32::from_rgb(255, 255, 0),
+            SignalQuality::Poor => Color32::from_rgb(255, 128, 0),
+            SignalQuality::VeryPoor => Color32::from_rgb(255, 0, 0),
+            SignalQuality::Unknown => Color32::from_gray(70),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gps::SnrThresholds;
+    use crate::display::gui::satellites::snr_quality;
+
+    #[test]
+    fn test_dimmed_keeps_constellation_hue() {
+        for palette in ColorPalette::ALL {
+            let colors: Vec<_> = LEGEND.iter().map(|(c, _)| constellation_color(palette, c)).collect();
+            let dimmed_colors: Vec<_> = colors.iter().map(|&c| dimmed(c)).collect();
+            for (i, (&color, &dim)) in colors.iter().zip(&dimmed_colors).enumerate() {
+                // Darker and grayer than the used color, but not gray
+                let brightness = |c: Color32| c.r() as u32 + c.g() as u32 + c.b() as u32;
+                assert!(brightness(dim) < brightness(color), "{:?} {:?}", palette, color);
+                assert!(!(dim.r() == dim.g() && dim.g() == dim.b()), "{:?} {:?}", palette, color);
+                assert_ne!(dim, unused_color(palette));
+
+                // The strongest channel stays the strongest, so the hue is recognisable
+                let strongest = |c: Color32| [c.r(), c.g(), c.b()].iter().enumerate().max_by_key(|(_, v)| **v).map(|(i, _)| i);
+                assert_eq!(strongest(dim), strongest(color));
+
+                // Constellations are still told apart when dimmed
+                assert!(!dimmed_colors[i + 1..].contains(&dim), "{:?}", palette);
+            }
+        }
+        assert_eq!(dimmed(Color32::from_rgb(200, 100, 0)), Color32::from_rgb(96, 66, 36));
+    }
+
+    #[test]
+    fn test_palettes_apply_to_every_call_site() {
+        let thresholds = SnrThresholds::default();
+        for palette in ColorPalette::ALL {
+            // SNR colors in the table and sky plot ring come from the same palette as the legend
+            for snr in [5.0, 25.0, 35.0, 45.0] {
+                let (quality, color) = snr_quality(Some(snr), &thresholds, palette);
+                assert_eq!(color, quality_color(palette, quality));
+            }
+
+            // Constellations are told apart from each other and from unused satellites
+            let colors: Vec<_> = LEGEND.iter().map(|(c, _)| constellation_color(palette, c)).collect();
+            for (i, color) in colors.iter().enumerate() {
+                assert!(!colors[i + 1..].contains(color), "{:?}", palette);
+                assert_ne!(*color, unused_color(palette));
+            }
+        }
+
+        for constellation in ["GPS", "GLONASS", "GALILEO"] {
+            assert_ne!(
+                constellation_color(ColorPalette::Standard, constellation),
+                constellation_color(ColorPalette::ColorblindSafe, constellation)
+            );
+        }
+        assert_ne!(
+            quality_color(ColorPalette::Standard, SignalQuality::Good),
+            quality_color(ColorPalette::HighContrast, SignalQuality::Good)
+        );
+    }
+}
@@ -1,14 +1,18 @@
-// src/display/gui/panels.rs v1
+// src/display/gui/panels.rs v3
 //! Main GPS data panel rendering
 
-use crate::gps::GpsData;
+use crate::{config::{CopyTemplate, DisplaySpeedUnit, DistanceUnits}, monitor::SentenceCounts, gps::{data::system_clock_command, AltitudeSource, CoordinateFormat, DataField, CLOCK_DRIFT_SAMPLES, NorthReference, FixValidity, GpsData, TextMessage, TextSeverity, FIELD_STALE_SECS, LOW_UPDATE_RATE_FRACTION}};
 use eframe::egui;
 
-fn format_coordinate(coord: Option<f64>) -> String {
-    match coord {
-        Some(val) => format!("{:.6}°", val),
-        None => "No fix".to_string(),
-    }
+fn format_coordinate(coord: Option<f64>, decimals: usize) -> String {
+    GpsData::format_coordinate(coord, decimals).trim_start().to_string()
+}
+
+/// Buttons clicked in the odometer panel
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OdometerAction {
+    ResetLifetime,
+    ClearReference,
 }
 
 fn format_value<T: std::fmt::Display>(value: Option<T>, unit: &str) -> String {
@@ -18,7 +22,54 @@ fn format_value<T: std::fmt::Display>(value: Option<T>, unit: &str) -> String {
     }
 }
 
-pub fn render_main_data_panel(ui: &mut egui::Ui, data: &GpsData) {
+/// Monospace value, greyed out when `field` hasn't been updated recently
+fn field_value(ui: &mut egui::Ui, data: &GpsData, field: DataField, text: String) -> egui::Response {
+    if data.is_field_stale(field, chrono::Duration::seconds(FIELD_STALE_SECS)) {
+        let age = data.field_age(field).map_or(0, |age| age.num_seconds());
+        ui.label(egui::RichText::new(text).monospace().weak())
+            .on_hover_text(format!("Not updated for {} s", age))
+    } else {
+        ui.monospace(text)
+    }
+}
+
+/// How the main data panel shows its values
+pub struct DataPanel<'a> {
+    pub units: DistanceUnits,
+    pub speed_unit: DisplaySpeedUnit,
+    pub altitude_source: AltitudeSource,
+    pub north_reference: NorthReference,
+    pub coordinate_decimals: usize,
+    pub coordinate_format: CoordinateFormat,
+    pub expected_update_hz: u32,
+    pub min_satellites_used: usize,
+    pub copy_templates: &'a [CopyTemplate],
+}
+
+impl DataPanel<'_> {
+    /// Main data panel. While disconnected the last known values are greyed
+    /// out and labeled, so they can't be mistaken for a live fix.
+    pub fn render(&self, ui: &mut egui::Ui, data: &GpsData, connected: bool) {
+        if !connected && data.timestamp.is_some() {
+            ui.colored_label(egui::Color32::from_rgb(255, 165, 0), "⏸ Disconnected — last known values");
+        }
+        ui.add_enabled_ui(connected, |ui| render_data_sections(ui, data, self));
+    }
+}
+
+fn render_data_sections(ui: &mut egui::Ui, data: &GpsData, panel: &DataPanel) {
+    let &DataPanel {
+        units,
+        speed_unit,
+        altitude_source,
+        north_reference,
+        coordinate_decimals,
+        coordinate_format,
+        expected_update_hz,
+        min_satellites_used,
+        copy_templates,
+    } = panel;
+
     ui.strong("📍 Position & Movement");
     ui.separator();
 
@@ -27,16 +78,49 @@ pub fn render_main_data_panel(ui: &mut egui::Ui, data: &GpsData) {
         .num_columns(2)
         .spacing([10.0, 8.0])
         .show(ui, |ui| {
-            ui.label("Latitude:");
-            ui.monospace(format_coordinate(data.latitude));
-            ui.end_row();
+            match (coordinate_format, data.latitude, data.longitude) {
+                (CoordinateFormat::Utm | CoordinateFormat::Mgrs, Some(lat), Some(lon)) => {
+                    ui.label(format!("{}:", coordinate_format.name()));
+                    field_value(ui, data, DataField::Position, coordinate_format.format_position(lat, lon, coordinate_decimals))
+                        .on_hover_text(GpsData::format_lat_lon(lat, lon, coordinate_decimals));
+                    ui.end_row();
+                }
+                _ => {
+                    ui.label("Latitude:");
+                    field_value(ui, data, DataField::Position, format_coordinate(data.latitude, coordinate_decimals));
+                    ui.end_row();
 
-            ui.label("Longitude:");
-            ui.monospace(format_coordinate(data.longitude));
-            ui.end_row();
+                    ui.label("Longitude:");
+                    field_value(ui, data, DataField::Position, format_coordinate(data.longitude, coordinate_decimals));
+                    ui.end_row();
+                }
+            }
+
+            if let (Some(lat), Some(lon)) = (data.latitude, data.longitude) {
+                if !copy_templates.is_empty() {
+                    ui.label("");
+                    ui.menu_button("📋 Copy", |ui| {
+                        for template in copy_templates {
+                            let text = template.apply(lat, lon, coordinate_decimals);
+                            if ui.button(&template.name).on_hover_text(&text).clicked() {
+                                ui.output_mut(|o| o.copied_text = text);
+                                ui.close_menu();
+                            }
+                        }
+                    }).response.on_hover_text("Copy the position as text or a map link; templates are set in Settings");
+                    ui.end_row();
+                }
+            }
 
             ui.label("Altitude:");
-            ui.monospace(format_value(data.altitude, "m"));
+            match data.altitude_from(altitude_source) {
+                Some((alt, AltitudeSource::Gps)) if data.is_2d_fix() => ui.label(
+                    egui::RichText::new(format!("{} (2D)", units.format_altitude(alt, 1))).monospace().weak(),
+                ).on_hover_text("2D fix: altitude is not being measured"),
+                Some((alt, AltitudeSource::Gps)) => ui.monospace(units.format_altitude(alt, 1)),
+                Some((alt, AltitudeSource::Barometric)) => ui.monospace(format!("{} (baro)", units.format_altitude(alt, 1))),
+                None => ui.monospace("Unknown"),
+            };
             ui.end_row();
 
             if let Some(accuracy) = data.accuracy {
@@ -44,6 +128,12 @@ pub fn render_main_data_panel(ui: &mut egui::Ui, data: &GpsData) {
                 ui.monospace(format!("{:.1} m", accuracy));
                 ui.end_row();
             }
+
+            if let Some(accuracy) = data.vertical_accuracy {
+                ui.label("Vertical Acc.:");
+                ui.monospace(format!("{:.1} m", accuracy));
+                ui.end_row();
+            }
         });
 
     ui.add_space(10.0);
@@ -54,18 +144,35 @@ pub fn render_main_data_panel(ui: &mut egui::Ui, data: &GpsData) {
         .spacing([10.0, 8.0])
         .show(ui, |ui| {
             ui.label("Speed:");
-            ui.monospace(format_value(data.speed, "km/h"));
+            field_value(ui, data, DataField::Velocity, format_value(data.speed.map(|kmh| format!("{:.1}", speed_unit.from_kmh(kmh))), speed_unit.label()));
             ui.end_row();
 
             ui.label("Course:");
-            ui.monospace(format_value(data.course, "°"));
+            match data.course_from(north_reference) {
+                Some((course, reference)) => {
+                    let hover = match (reference, data.magnetic_variation) {
+                        (NorthReference::Magnetic, Some(variation)) => format!(
+                            "Magnetic, variation {:.1}° {}",
+                            variation.abs(),
+                            if variation < 0.0 { "W" } else { "E" }
+                        ),
+                        _ if north_reference == NorthReference::Magnetic => "True: no magnetic variation reported".to_string(),
+                        _ => "True".to_string(),
+                    };
+                    field_value(ui, data, DataField::Velocity, format!("{:.1}° {}", course, reference.suffix()))
+                        .on_hover_text(hover);
+                }
+                None => {
+                    ui.monospace("Unknown");
+                }
+            }
             ui.end_row();
         });
 
     ui.add_space(10.0);
 
     // Signal Quality section (if GPS data available)
-    if data.satellites.is_some() || data.hdop.is_some() || data.fix_quality.is_some() {
+    if data.satellites.is_some() || data.hdop.is_some() || data.fix_quality.is_some() || data.connected_at.is_some() {
         ui.strong("📡 Signal Quality");
         ui.separator();
         
@@ -73,9 +180,29 @@ pub fn render_main_data_panel(ui: &mut egui::Ui, data: &GpsData) {
             .num_columns(2)
             .spacing([10.0, 8.0])
             .show(ui, |ui| {
+                let (score, label) = data.signal_health();
+                ui.label("Health:");
+                ui.add(
+                    egui::ProgressBar::new(score as f32 / 100.0)
+                        .desired_width(140.0)
+                        .fill(health_color(score))
+                        .text(format!("{} {}", score, label)),
+                );
+                ui.end_row();
+
                 if let Some(sats) = data.satellites {
                     ui.label("Satellites:");
-                    ui.monospace(format!("{}", sats));
+                    field_value(ui, data, DataField::Satellites, format!("{}", sats));
+                    ui.end_row();
+                }
+
+                if data.low_satellite_count(min_satellites_used) {
+                    ui.label("");
+                    ui.colored_label(egui::Color32::from_rgb(200, 180, 60), "⚠ Low satellite count")
+                        .on_hover_text(format!(
+                            "Fix from fewer than {} satellites; position may be unreliable",
+                            min_satellites_used
+                        ));
                     ui.end_row();
                 }
 
@@ -86,8 +213,184 @@ pub fn render_main_data_panel(ui: &mut egui::Ui, data: &GpsData) {
                 }
 
                 ui.label("Fix Type:");
-                ui.monospace(data.get_fix_description());
+                let fix_text = egui::RichText::new(data.get_fix_description()).monospace();
+                match data.fix_validity() {
+                    FixValidity::Invalid => ui.label(fix_text.color(egui::Color32::RED)),
+                    FixValidity::Estimated => ui.label(fix_text.color(egui::Color32::from_rgb(255, 165, 0))),
+                    FixValidity::Valid | FixValidity::Unknown => ui.label(fix_text),
+                };
                 ui.end_row();
+
+                if let Some(rate) = data.update_rate() {
+                    ui.label("Update Rate:");
+                    let text = if expected_update_hz > 0 {
+                        format!("{:.1} Hz (expected {})", rate, expected_update_hz)
+                    } else {
+                        format!("{:.1} Hz", rate)
+                    };
+                    if expected_update_hz > 0 && rate < expected_update_hz as f64 * LOW_UPDATE_RATE_FRACTION {
+                        ui.label(egui::RichText::new(text).monospace().color(egui::Color32::from_rgb(255, 165, 0)));
+                    } else {
+                        ui.monospace(text);
+                    }
+                    ui.end_row();
+                }
+
+                if let Some(offset) = data.clock_offset {
+                    ui.label("Clock Offset:");
+                    ui.monospace(format_clock_offset(offset));
+                    ui.end_row();
+                }
+
+                if let Some(drift) = data.clock_drift.estimate() {
+                    ui.label("Clock Drift:");
+                    ui.horizontal(|ui| {
+                        ui.monospace(describe_clock_drift(drift))
+                            .on_hover_text(format!("Average over the last {} GPS time readings", CLOCK_DRIFT_SAMPLES));
+                        if ui.small_button("📋").on_hover_text(
+                            "Copy a command that sets the system clock to GPS time. \
+                             Run it yourself with administrator rights; the clock is never set automatically."
+                        ).clicked() {
+                            let gps_now = chrono::Utc::now() + drift;
+                            ui.output_mut(|o| o.copied_text = system_clock_command(gps_now));
+                        }
+                    });
+                    ui.end_row();
+                }
+
+                if let Some(connected_at) = data.connected_at {
+                    ui.label("TTFF:");
+                    match data.time_to_first_fix {
+                        Some(ttff) => ui.monospace(format!("{:.1} s", ttff.num_milliseconds() as f64 / 1000.0)),
+                        None => ui.monospace(format!("Acquiring... {} s", (chrono::Utc::now() - connected_at).num_seconds())),
+                    };
+                    ui.end_row();
+                }
             });
     }
 }
+
+/// System clock error relative to GPS time, e.g. "+2.5 s (system clock behind)"
+fn format_clock_offset(offset: chrono::Duration) -> String {
+    let seconds = offset.num_milliseconds() as f64 / 1000.0;
+    if seconds.abs() < 0.5 {
+        format!("{:+.1} s (in sync)", seconds)
+    } else if seconds > 0.0 {
+        format!("{:+.1} s (system clock behind)", seconds)
+    } else {
+        format!("{:+.1} s (system clock ahead)", seconds)
+    }
+}
+
+/// Rolling clock drift as a sentence, e.g. "System clock is 2.3 s ahead"
+fn describe_clock_drift(drift: chrono::Duration) -> String {
+    let seconds = drift.num_milliseconds() as f64 / 1000.0;
+    if seconds.abs() < 0.05 {
+        "System clock matches GPS time".to_string()
+    } else if seconds > 0.0 {
+        format!("System clock is {:.1} s behind", seconds)
+    } else {
+        format!("System clock is {:.1} s ahead", -seconds)
+    }
+}
+
+/// Bar color for a signal health score
+fn health_color(score: u8) -> egui::Color32 {
+    match score {
+        80.. => egui::Color32::from_rgb(0, 160, 0),
+        60..=79 => egui::Color32::from_rgb(110, 170, 60),
+        40..=59 => egui::Color32::from_rgb(200, 160, 0),
+        _ => egui::Color32::from_rgb(200, 60, 40),
+    }
+}
+
+/// Count of each sentence type received, most frequent first
+pub fn render_sentence_panel(ui: &mut egui::Ui, counts: &SentenceCounts) {
+    ui.add_space(10.0);
+    ui.strong("📊 Sentence Types");
+    ui.separator();
+
+    if counts.total() == 0 {
+        ui.weak("No sentences received");
+        return;
+    }
+
+    egui::Grid::new("sentence_grid")
+        .num_columns(2)
+        .spacing([10.0, 4.0])
+        .striped(true)
+        .show(ui, |ui| {
+            for (kind, count) in counts.sorted() {
+                ui.monospace(kind);
+                ui.monospace(count.to_string());
+                ui.end_row();
+            }
+        });
+
+    let rate = counts.rate().map_or("--".to_string(), |rate| format!("{:.1}/s", rate));
+    ui.small(format!("{} total, {}", counts.total(), rate));
+}
+
+/// Text messages (TXT sentences) from the receiver, newest first
+pub fn render_receiver_messages_panel(ui: &mut egui::Ui, messages: &[TextMessage]) {
+    if messages.is_empty() {
+        return;
+    }
+
+    ui.add_space(10.0);
+    ui.strong("💬 Receiver Messages");
+    ui.separator();
+
+    for message in messages.iter().rev() {
+        let color = match message.severity {
+            TextSeverity::Error => egui::Color32::from_rgb(200, 60, 40),
+            TextSeverity::Warning => egui::Color32::from_rgb(200, 160, 0),
+            TextSeverity::Notice | TextSeverity::User => ui.visuals().text_color(),
+        };
+        ui.horizontal_wrapped(|ui| {
+            ui.monospace(message.received.format("%H:%M:%S").to_string());
+            ui.colored_label(color, &message.text)
+                .on_hover_text(message.severity.name());
+        });
+    }
+}
+
+/// Lifetime odometer readout. Returns true when the user asked to reset it.
+pub fn render_odometer_panel(ui: &mut egui::Ui, lifetime_m: f64, since_reference: Option<(&str, f64)>, units: DistanceUnits) -> Option<OdometerAction> {
+    let mut action = None;
+
+    ui.add_space(10.0);
+    ui.strong("🚗 Odometer");
+    ui.separator();
+
+    egui::Grid::new("odometer_grid")
+        .num_columns(2)
+        .spacing([10.0, 8.0])
+        .show(ui, |ui| {
+            ui.label("Lifetime:");
+            ui.monospace(units.format_distance(lifetime_m));
+            ui.end_row();
+
+            if let Some((name, distance)) = since_reference {
+                ui.label(format!("Since {}:", name))
+                    .on_hover_text("Distance along the path travelled since this waypoint was marked");
+                ui.horizontal(|ui| {
+                    ui.monospace(units.format_distance(distance));
+                    if ui.small_button("✖").on_hover_text("Stop measuring from this waypoint").clicked() {
+                        action = Some(OdometerAction::ClearReference);
+                    }
+                });
+                ui.end_row();
+            }
+        });
+
+    // Double-click so a stray click can't wipe the total
+    if ui.small_button("Reset").on_hover_text("Double-click to reset the lifetime total").double_clicked() {
+        action = Some(OdometerAction::ResetLifetime);
+    }
+    if since_reference.is_none() {
+        ui.small("Mark a waypoint with 📌 to measure the distance travelled since it");
+    }
+
+    action
+}
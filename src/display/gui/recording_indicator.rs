@@ -0,0 +1,76 @@
+// src/display/gui/recording_indicator.rs v1
+//! Blinking "REC" marker shown in the top bar and on the map while a track
+//! is being recorded, so a recording is neither forgotten nor left running
+
+use crate::{track_recorder::RecordingStatus, waypoint::format_duration};
+use eframe::egui;
+use std::time::Duration;
+
+/// Time the dot spends on, then off
+const BLINK_INTERVAL: Duration = Duration::from_millis(500);
+
+const RECORDING_COLOR: egui::Color32 = egui::Color32::from_rgb(230, 30, 30);
+const PAUSED_COLOR: egui::Color32 = egui::Color32::from_rgb(255, 165, 0);
+
+/// Indicator text, e.g. "REC  120 pts · 5m 3s", None when not recording
+pub fn label(status: RecordingStatus) -> Option<String> {
+    let (prefix, points, elapsed) = match status {
+        RecordingStatus::Stopped => return None,
+        RecordingStatus::Recording { points, elapsed } => ("REC", points, elapsed),
+        RecordingStatus::Paused { points, elapsed } => ("PAUSED", points, elapsed),
+    };
+    Some(format!("{}  {} pts · {}", prefix, points, format_duration(elapsed)))
+}
+
+/// Dot color, blinking while recording and steady while paused. Asks for the
+/// redraw that shows the next blink.
+fn dot_color(ctx: &egui::Context, status: RecordingStatus) -> egui::Color32 {
+    match status {
+        RecordingStatus::Recording { .. } => {
+            ctx.request_repaint_after(BLINK_INTERVAL);
+            let phase = (ctx.input(|i| i.time) / BLINK_INTERVAL.as_secs_f64()) as u64;
+            if phase.is_multiple_of(2) { RECORDING_COLOR } else { egui::Color32::TRANSPARENT }
+        }
+        _ => PAUSED_COLOR,
+    }
+}
+
+/// Indicator for the top bar; draws nothing when not recording
+pub fn render(ui: &mut egui::Ui, status: RecordingStatus) {
+    let Some(text) = label(status) else {
+        return;
+    };
+    let color = if matches!(status, RecordingStatus::Paused { .. }) { PAUSED_COLOR } else { RECORDING_COLOR };
+    ui.separator();
+    ui.colored_label(dot_color(ui.ctx(), status), "⏺");
+    ui.colored_label(color, egui::RichText::new(text).strong())
+        .on_hover_text("A track is being recorded; stop it from the Waypoints window");
+}
+
+/// Indicator in the top right corner of the map
+pub fn paint_overlay(ctx: &egui::Context, painter: &egui::Painter, rect: egui::Rect, status: RecordingStatus) {
+    let Some(text) = label(status) else {
+        return;
+    };
+    let galley = painter.layout_no_wrap(text, egui::FontId::proportional(14.0), egui::Color32::WHITE);
+    let dot_radius = 6.0;
+    let size = egui::vec2(dot_radius * 2.0 + 6.0 + galley.size().x, galley.size().y);
+    let origin = rect.right_top() + egui::vec2(-8.0 - size.x, 8.0);
+
+    painter.rect_filled(egui::Rect::from_min_size(origin, size).expand(4.0), 4.0, egui::Color32::from_black_alpha(160));
+    painter.circle_filled(origin + egui::vec2(dot_radius, size.y / 2.0), dot_radius, dot_color(ctx, status));
+    painter.galley(origin + egui::vec2(dot_radius * 2.0 + 6.0, 0.0), galley, egui::Color32::WHITE);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_label_follows_recorder_state() {
+        let elapsed = chrono::Duration::seconds(303);
+        assert_eq!(label(RecordingStatus::Recording { points: 120, elapsed }).as_deref(), Some("REC  120 pts · 5m 3s"));
+        assert_eq!(label(RecordingStatus::Paused { points: 120, elapsed }).as_deref(), Some("PAUSED  120 pts · 5m 3s"));
+        assert_eq!(label(RecordingStatus::Stopped), None);
+    }
+}
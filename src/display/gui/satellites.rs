@@ -1,17 +1,153 @@
-// src/display/gui/satellites.rs v1
+// src/display/gui/satellites.rs v3
 //! Satellite table rendering and sorting
 
-use crate::gps::GpsData;
+use crate::config::ColorPalette;
+use crate::gps::{data::SatelliteInfo, GpsData, SignalQuality, SnrThresholds};
 use eframe::egui;
 
 use super::app::SatelliteSortColumn;
+use super::palette::{quality_color, unused_color, used_color};
 
-pub struct SatellitePanel {
+/// Constellations offered in the visibility filter, in display order
+const CONSTELLATIONS: [&str; 6] = ["GPS", "GLONASS", "GALILEO", "BEIDOU", "QZSS", "SBAS"];
+
+/// Whether the sky plot and table show `sat`: not in a hidden constellation
+/// and, with `used_only`, used in the fix
+pub fn is_shown(sat: &SatelliteInfo, hidden_constellations: &[String], used_only: bool) -> bool {
+    !hidden_constellations.contains(&sat.constellation) && (sat.used || !used_only)
+}
+
+/// Quality level and its color for an SNR reading
+pub fn snr_quality(snr: Option<f32>, thresholds: &SnrThresholds, palette: ColorPalette) -> (SignalQuality, egui::Color32) {
+    let quality = thresholds.quality(snr);
+    (quality, quality_color(palette, quality))
+}
+
+/// One-line legend of the SNR color thresholds
+fn render_snr_legend(ui: &mut egui::Ui, thresholds: &SnrThresholds, palette: ColorPalette) {
+    ui.horizontal_wrapped(|ui| {
+        ui.spacing_mut().item_spacing.x = 8.0;
+        let levels = [
+            (SignalQuality::Excellent, format!("≥{:.0}", thresholds.excellent)),
+            (SignalQuality::Good, format!("≥{:.0}", thresholds.good)),
+            (SignalQuality::Fair, format!("≥{:.0}", thresholds.fair)),
+            (SignalQuality::Poor, format!("≥{:.0}", thresholds.poor)),
+            (SignalQuality::VeryPoor, format!("<{:.0}", thresholds.poor)),
+        ];
+        for (quality, range) in levels {
+            ui.colored_label(quality_color(palette, quality), format!("● {} {}", quality.description(), range));
+        }
+    });
+}
+
+/// Full-scale SNR of the per-constellation bars, in dB
+const SNR_BAR_MAX: f32 = 50.0;
+
+/// Session highs of the used-satellite count and average used SNR, for
+/// comparing antenna positions
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SatelliteHighs {
+    pub used: usize,
+    pub average_snr: Option<f64>,
+}
+
+impl SatelliteHighs {
+    /// Raise the high-water marks from the latest data
+    pub fn update(&mut self, data: &GpsData) {
+        self.used = self.used.max(data.used_satellite_count().unwrap_or(0));
+        if let Some(snr) = data.average_used_snr() {
+            self.average_snr = Some(self.average_snr.map_or(snr, |high| high.max(snr)));
+        }
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// Used/visible count and average used SNR bar for each shown constellation
+fn render_constellation_summary(
+    ui: &mut egui::Ui,
+    data: &GpsData,
+    hidden: &[String],
+    thresholds: &SnrThresholds,
+    palette: ColorPalette,
+) {
+    let grouped = data.satellites_by_constellation();
+    let averages = data.average_used_snr_by_constellation();
+    let mut constellations: Vec<&String> = grouped.keys().filter(|c| !hidden.contains(c)).collect();
+    // Known constellations in the usual order, anything else after them
+    constellations.sort_by_key(|c| (CONSTELLATIONS.iter().position(|known| known == c).unwrap_or(CONSTELLATIONS.len()), c.as_str()));
+
+    egui::Grid::new("constellation_summary")
+        .num_columns(3)
+        .spacing([8.0, 2.0])
+        .show(ui, |ui| {
+            for constellation in constellations {
+                let satellites = &grouped[constellation];
+                ui.small(constellation.as_str());
+                ui.small(format!("{}/{}", satellites.iter().filter(|s| s.used).count(), satellites.len()));
+                match averages.get(constellation) {
+                    Some(&snr) => {
+                        let (_, color) = snr_quality(Some(snr as f32), thresholds, palette);
+                        ui.add(
+                            egui::ProgressBar::new((snr as f32 / SNR_BAR_MAX).clamp(0.0, 1.0))
+                                .desired_width(80.0)
+                                .fill(color)
+                                .text(format!("{:.0} dB", snr)),
+                        ).on_hover_text("Average SNR of the satellites used in the fix");
+                    }
+                    None => {
+                        ui.small("-");
+                    }
+                }
+                ui.end_row();
+            }
+        });
+}
+
+/// Render per-constellation visibility checkboxes and the used-only toggle,
+/// which is disabled unless the receiver says which satellites it uses
+/// (`used_known`). Returns true if the selection changed.
+pub fn render_constellation_filter(ui: &mut egui::Ui, hidden: &mut Vec<String>, used_only: &mut bool, used_known: bool) -> bool {
+    let mut changed = false;
+
+    ui.horizontal_wrapped(|ui| {
+        changed |= ui.add_enabled_ui(used_known, |ui| ui.toggle_value(used_only, "Used only"))
+            .inner
+            .on_hover_text("Show only the satellites used in the fix")
+            .on_disabled_hover_text("The receiver doesn't report which satellites it uses")
+            .changed();
+        ui.separator();
+        ui.small("Show:");
+        for constellation in CONSTELLATIONS {
+            let mut visible = !hidden.iter().any(|h| h == constellation);
+            if ui.checkbox(&mut visible, constellation).changed() {
+                if visible {
+                    hidden.retain(|h| h != constellation);
+                } else {
+                    hidden.push(constellation.to_string());
+                }
+                changed = true;
+            }
+        }
+    });
+
+    changed
+}
+
+pub struct SatellitePanel<'a> {
     pub sort_column: SatelliteSortColumn,
     pub sort_ascending: bool,
+    pub hidden_constellations: &'a [String],
+    pub used_only: bool,
+    pub snr_thresholds: &'a SnrThresholds,
+    pub elevation_mask: f32,
+    pub palette: ColorPalette,
+    pub highs: &'a mut SatelliteHighs,
 }
 
-impl SatellitePanel {
+impl SatellitePanel<'_> {
     pub fn render(&mut self, ui: &mut egui::Ui, data: &GpsData) {
         ui.strong("🛰 Satellites");
         ui.separator();
@@ -22,9 +158,34 @@ impl SatellitePanel {
         }
 
         // Summary
-        let used_count = data.satellites_used();
         let total_count = data.satellites_info.len();
-        ui.label(format!("📊 {} used / {} visible", used_count, total_count));
+        let used = data.used_satellite_count().unwrap_or(0);
+        if self.elevation_mask > 0.0 && data.reports_used_satellites() {
+            ui.label(format!(
+                "📊 {} used above {:.0}° mask ({} reported) / {} visible",
+                data.satellites_used_above_mask(self.elevation_mask),
+                self.elevation_mask,
+                used,
+                total_count
+            ));
+        } else {
+            ui.label(format!("📊 {} used / {} visible", used, total_count));
+        }
+        self.render_highs(ui);
+        let hidden_count = data.satellites_info.iter()
+            .filter(|sat| self.hidden_constellations.contains(&sat.constellation))
+            .count();
+        if hidden_count > 0 {
+            ui.small(format!("{} satellites in hidden constellations not shown", hidden_count));
+        }
+        if self.used_only {
+            let unused_count = data.satellites_info.iter()
+                .filter(|sat| !sat.used && !self.hidden_constellations.contains(&sat.constellation))
+                .count();
+            ui.small(format!("{} unused satellites not shown", unused_count));
+        }
+        render_constellation_summary(ui, data, self.hidden_constellations, self.snr_thresholds, self.palette);
+        render_snr_legend(ui, self.snr_thresholds, self.palette);
         ui.add_space(5.0);
 
         // Calculate scroll area height
@@ -43,11 +204,29 @@ impl SatellitePanel {
 //        ui.small("💡 Click column headers to sort • Showing satellites above horizon");
     }
 
+    /// Session high badges with a button to start them again
+    fn render_highs(&mut self, ui: &mut egui::Ui) {
+        let badge = |ui: &mut egui::Ui, text: String| {
+            egui::Frame::none()
+                .fill(ui.visuals().faint_bg_color)
+                .rounding(4.0)
+                .inner_margin(egui::Margin::symmetric(6.0, 2.0))
+                .show(ui, |ui| ui.small(text));
+        };
+
+        ui.horizontal(|ui| {
+            ui.small("Session high:");
+            badge(ui, format!("⬆ {} used", self.highs.used));
+            badge(ui, self.highs.average_snr.map_or("⬆ -- dB".to_string(), |snr| format!("⬆ {:.1} dB avg", snr)));
+            if ui.small_button("↺").on_hover_text("Reset the session highs, e.g. at a new antenna position").clicked() {
+                self.highs.reset();
+            }
+        });
+    }
+
     fn render_table(&mut self, ui: &mut egui::Ui, data: &GpsData) {
         // Filter satellites above horizon
-        let mut visible_satellites: Vec<_> = data.satellites_info.iter()
-            .filter(|sat| sat.elevation.map_or(true, |el| el >= 0.0))
-            .collect();
+        let mut visible_satellites = self.shown_satellites(data);
         
         // Sort by selected column
         self.sort_satellites(&mut visible_satellites);
@@ -68,6 +247,14 @@ impl SatellitePanel {
             });
     }
 
+    /// Satellites above the horizon that pass the constellation and used-only filters
+    fn shown_satellites<'d>(&self, data: &'d GpsData) -> Vec<&'d SatelliteInfo> {
+        data.satellites_info.iter()
+            .filter(|sat| sat.elevation.is_none_or(|el| el >= 0.0))
+            .filter(|sat| is_shown(sat, self.hidden_constellations, self.used_only))
+            .collect()
+    }
+
     fn sort_satellites(&self, satellites: &mut Vec<&crate::gps::data::SatelliteInfo>) {
         match self.sort_column {
             SatelliteSortColumn::Constellation => {
@@ -84,7 +271,7 @@ impl SatellitePanel {
             }
             SatelliteSortColumn::Used => {
                 satellites.sort_by(|a, b| {
-                    let cmp = b.used.cmp(&a.used);
+                    let cmp = b.used_above_mask(self.elevation_mask).cmp(&a.used_above_mask(self.elevation_mask));
                     if self.sort_ascending { cmp } else { cmp.reverse() }
                 });
             }
@@ -95,21 +282,8 @@ impl SatellitePanel {
                 });
             }
             SatelliteSortColumn::Quality => {
-                let quality_rank = |quality: &str| -> u8 {
-                    match quality {
-                        "Excellent" => 0,
-                        "Good" => 1,
-                        "Fair" => 2,
-                        "Poor" => 3,
-                        "Very Poor" => 4,
-                        _ => 5,
-                    }
-                };
-                
                 satellites.sort_by(|a, b| {
-                    let a_rank = quality_rank(&a.signal_strength_description());
-                    let b_rank = quality_rank(&b.signal_strength_description());
-                    let cmp = a_rank.cmp(&b_rank);
+                    let cmp = self.snr_thresholds.quality(a.snr).cmp(&self.snr_thresholds.quality(b.snr));
                     if self.sort_ascending { cmp } else { cmp.reverse() }
                 });
             }
@@ -196,37 +370,23 @@ impl SatellitePanel {
             ui.monospace(format!("{}", sat.prn));
 
             // Used indicator
-            if sat.used {
-                ui.colored_label(egui::Color32::GREEN, "✓ Yes");
+            if sat.used_above_mask(self.elevation_mask) {
+                ui.colored_label(used_color(self.palette), "✓ Yes");
+            } else if sat.used {
+                ui.colored_label(unused_color(self.palette), "⊘ Masked");
             } else {
-                ui.colored_label(egui::Color32::GRAY, "○ No");
+                ui.colored_label(unused_color(self.palette), "○ No");
             }
 
-            // SNR with color coding
+            // SNR and quality with color coding
+            let (quality, color) = snr_quality(sat.snr, self.snr_thresholds, self.palette);
             if let Some(snr) = sat.snr {
-                let color = match snr {
-                    s if s >= 40.0 => egui::Color32::GREEN,
-                    s if s >= 35.0 => egui::Color32::from_rgb(144, 238, 144),
-                    s if s >= 25.0 => egui::Color32::YELLOW,
-                    s if s >= 15.0 => egui::Color32::from_rgb(255, 165, 0),
-                    _ => egui::Color32::RED,
-                };
                 ui.colored_label(color, format!("{:.1}", snr));
             } else {
                 ui.colored_label(egui::Color32::GRAY, "--");
             }
 
-            // Quality
-            let quality_text = sat.signal_strength_description();
-            let quality_color = match quality_text.as_str() {
-                "Excellent" => egui::Color32::GREEN,
-                "Good" => egui::Color32::from_rgb(144, 238, 144),
-                "Fair" => egui::Color32::YELLOW,
-                "Poor" => egui::Color32::from_rgb(255, 165, 0),
-                "Very Poor" => egui::Color32::RED,
-                _ => egui::Color32::GRAY,
-            };
-            ui.colored_label(quality_color, quality_text);
+            ui.colored_label(color, quality.description());
 
             // Elevation
             if let Some(el) = sat.elevation {
@@ -246,3 +406,80 @@ impl SatellitePanel {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gps::data::SatelliteInfo;
+
+    fn satellite(prn: u8, snr: f32, used: bool) -> SatelliteInfo {
+        SatelliteInfo { snr: Some(snr), used, ..SatelliteInfo::new(prn) }
+    }
+
+    #[test]
+    fn test_used_only_filter() {
+        let mut data = GpsData::new();
+        data.satellites_info = vec![
+            satellite(1, 40.0, true),
+            satellite(2, 20.0, false),
+            satellite(3, 35.0, true),
+            SatelliteInfo { constellation: "GLONASS".to_string(), ..satellite(70, 30.0, true) },
+            SatelliteInfo { elevation: Some(-3.0), ..satellite(4, 15.0, true) },
+        ];
+        let mut highs = SatelliteHighs::default();
+        let hidden = vec!["GLONASS".to_string()];
+        let mut panel = SatellitePanel {
+            sort_column: SatelliteSortColumn::Prn,
+            sort_ascending: true,
+            hidden_constellations: &hidden,
+            used_only: false,
+            snr_thresholds: &SnrThresholds::default(),
+            elevation_mask: 0.0,
+            palette: ColorPalette::default(),
+            highs: &mut highs,
+        };
+        let prns = |panel: &SatellitePanel| panel.shown_satellites(&data).iter().map(|sat| sat.prn).collect::<Vec<_>>();
+        assert_eq!(prns(&panel), [1, 2, 3]);
+
+        panel.used_only = true;
+        assert_eq!(prns(&panel), [1, 3]);
+        assert!(panel.shown_satellites(&data).iter().all(|sat| sat.used));
+
+        // The sky plot applies the same filter
+        let plotted: Vec<_> = data.satellites_info.iter().filter(|sat| is_shown(sat, &hidden, true)).map(|sat| sat.prn).collect();
+        assert_eq!(plotted, [1, 3, 4]);
+
+        // A receiver that marks none used can't be filtered this way
+        assert!(data.reports_used_satellites());
+        data.satellites_info.iter_mut().for_each(|sat| sat.used = false);
+        assert!(!data.reports_used_satellites());
+    }
+
+    #[test]
+    fn test_session_highs() {
+        let mut highs = SatelliteHighs::default();
+        let mut data = GpsData::new();
+        highs.update(&data);
+        assert_eq!(highs, SatelliteHighs::default());
+
+        data.satellites_info = (1..=4).map(|prn| satellite(prn, 30.0 + prn as f32, true)).collect();
+        highs.update(&data);
+        assert_eq!(highs.used, 4);
+        assert_eq!(highs.average_snr, Some(32.5));
+
+        // Fewer satellites but stronger: each mark keeps its own high
+        data.satellites_info = vec![satellite(1, 45.0, true), satellite(2, 20.0, false)];
+        highs.update(&data);
+        assert_eq!(highs.used, 4);
+        assert_eq!(highs.average_snr, Some(45.0));
+
+        // A receiver that marks none used still counts through GGA
+        data.satellites_info = (1..=6).map(|prn| satellite(prn, 30.0, false)).collect();
+        data.satellites = Some(6);
+        highs.update(&data);
+        assert_eq!(highs.used, 6);
+
+        highs.reset();
+        assert_eq!(highs, SatelliteHighs::default());
+    }
+}
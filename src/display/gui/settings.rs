@@ -1,8 +1,16 @@
-// src/display/gui/settings.rs v2
+// src/display/gui/settings.rs v7
 //! Settings UI for GPS source configuration
 
-use crate::config::GpsConfig;
+use crate::gps::{AltitudeSource, CoordinateFormat, NorthReference, SnrThresholds, SpeedUnit, COORDINATE_DECIMALS_RANGE};
+use crate::config::{ColorPalette, ConfigProfiles, CopyTemplate, CsvExport, CsvTimestamp, DataLog, DisplaySpeedUnit, DisplayTimeZone, DistanceUnits, GpsConfig, HorizonMask, HorizonPoint, MapOptions, MarkerStyle, NoFixWarning, RotationInterval, SerialFlowControl, SerialLine, SerialParity, SessionAutosave, SkyPlotGrid, Speedometer, SpeedZoomStep, StallReconnect, TrackRotation, WaypointFixHold, WaypointQualityGate};
+#[cfg(feature = "obd")]
+use crate::config::ObdConfig;
+#[cfg(feature = "status-server")]
+use crate::config::StatusEndpoint;
+use crate::monitor::detect_baudrate;
+use crate::map::{is_header_char, DEFAULT_USER_AGENT};
 use eframe::egui;
+use std::{path::Path, sync::mpsc};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum SourceType {
@@ -10,21 +18,71 @@ pub enum SourceType {
     Gpsd,
     #[cfg(windows)]
     Windows,
+    #[cfg(all(target_os = "linux", feature = "geoclue"))]
+    GeoClue,
 }
 
 pub struct SettingsWindow {
     pub open: bool,
     pub config: GpsConfig,
     source_type: SourceType,
+    auto_connect: bool,
+    restart_delay_ms: u32,
+    stall_reconnect: StallReconnect,
     // Temporary UI state
     serial_port: String,
     serial_baudrate: String,
+    baud_detection: Option<mpsc::Receiver<std::result::Result<u32, String>>>,  // Auto-detect in progress
+    serial_read_timeout: String,
+    serial_max_line_length: String,
+    rmc_speed_unit: SpeedUnit,
+    serial_line: SerialLine,
     gpsd_host: String,
     gpsd_port: String,
     #[cfg(windows)]
     windows_accuracy: String,
     #[cfg(windows)]
     windows_interval: String,
+    marker_style: MarkerStyle,
+    map_options: MapOptions,
+    tile_cache_dir: String,
+    tile_user_agent: String,
+    tile_contact: String,
+    no_fix_warning: NoFixWarning,
+    speedometer: Speedometer,
+    waypoint_quality_gate: WaypointQualityGate,
+    waypoint_fix_hold: WaypointFixHold,
+    track_rotation: TrackRotation,
+    data_log: DataLog,
+    snr_thresholds: SnrThresholds,
+    elevation_mask_deg: f32,
+    sky_plot_grid: SkyPlotGrid,
+    horizon_mask: HorizonMask,
+    satellite_timeout_secs: u64,
+    expected_update_hz: u32,
+    unfocused_repaint_secs: u32,
+    min_satellites_used: usize,
+    distance_units: DistanceUnits,
+    speed_unit: Option<DisplaySpeedUnit>,
+    color_palette: ColorPalette,
+    altitude_source: AltitudeSource,
+    north_reference: NorthReference,
+    exclude_2d_altitude: bool,
+    record_satellite_snapshots: bool,
+    recording_indicator: bool,
+    session_autosave: SessionAutosave,
+    time_zone: DisplayTimeZone,
+    coordinate_decimals: usize,
+    coordinate_format: CoordinateFormat,
+    copy_templates: Vec<CopyTemplate>,
+    csv_export: CsvExport,
+    #[cfg(feature = "obd")]
+    obd: ObdConfig,
+    #[cfg(feature = "status-server")]
+    status_endpoint: StatusEndpoint,
+    profiles: ConfigProfiles,
+    profile_name: String,
+    transfer_path: String,
     status_message: Option<String>,
 }
 
@@ -35,6 +93,8 @@ impl SettingsWindow {
             "gpsd" => SourceType::Gpsd,
             #[cfg(windows)]
             "windows" => SourceType::Windows,
+            #[cfg(all(target_os = "linux", feature = "geoclue"))]
+            "geoclue" => SourceType::GeoClue,
             _ => {
                 #[cfg(windows)]
                 {
@@ -47,28 +107,84 @@ impl SettingsWindow {
             }
         };
 
+        let profiles = ConfigProfiles::load().unwrap_or_default();
+
         Self {
             open: false,
             serial_port: config.serial_port.clone().unwrap_or_default(),
             serial_baudrate: config.serial_baudrate.map_or("9600".to_string(), |b| b.to_string()),
+            baud_detection: None,
+            serial_read_timeout: config.serial_read_timeout_ms.map_or("1000".to_string(), |t| t.to_string()),
+            serial_max_line_length: config.serial_max_line_length.map_or("1024".to_string(), |n| n.to_string()),
+            rmc_speed_unit: config.rmc_speed_unit,
+            serial_line: config.serial_line,
             gpsd_host: config.gpsd_host.clone().unwrap_or_else(|| "localhost".to_string()),
             gpsd_port: config.gpsd_port.map_or("2947".to_string(), |p| p.to_string()),
             #[cfg(windows)]
             windows_accuracy: config.windows_accuracy.map_or("10".to_string(), |a| a.to_string()),
             #[cfg(windows)]
             windows_interval: config.windows_interval.map_or("1".to_string(), |i| i.to_string()),
+            marker_style: config.marker_style.clone(),
+            map_options: config.map_options.clone(),
+            tile_cache_dir: config.tile_cache_dir.clone().unwrap_or_default(),
+            tile_user_agent: config.tile_user_agent.clone().unwrap_or_default(),
+            tile_contact: config.tile_contact.clone().unwrap_or_default(),
+            no_fix_warning: config.no_fix_warning.clone(),
+            speedometer: config.speedometer.clone(),
+            waypoint_quality_gate: config.waypoint_quality_gate.clone(),
+            waypoint_fix_hold: config.waypoint_fix_hold,
+            track_rotation: config.track_rotation.clone(),
+            data_log: config.data_log.clone(),
+            snr_thresholds: config.snr_thresholds.clone(),
+            elevation_mask_deg: config.elevation_mask_deg,
+            sky_plot_grid: config.sky_plot_grid,
+            horizon_mask: config.horizon_mask.clone(),
+            satellite_timeout_secs: config.satellite_timeout_secs,
+            expected_update_hz: config.expected_update_hz,
+            unfocused_repaint_secs: config.unfocused_repaint_secs,
+            min_satellites_used: config.min_satellites_used,
+            distance_units: config.distance_units,
+            speed_unit: config.speed_unit,
+            color_palette: config.color_palette,
+            altitude_source: config.altitude_source,
+            north_reference: config.north_reference,
+            exclude_2d_altitude: config.exclude_2d_altitude,
+            record_satellite_snapshots: config.record_satellite_snapshots,
+            recording_indicator: config.recording_indicator,
+            session_autosave: config.session_autosave.clone(),
+            time_zone: config.time_zone,
+            coordinate_decimals: config.coordinate_decimals,
+            coordinate_format: config.coordinate_format,
+            copy_templates: config.copy_templates.clone(),
+            csv_export: config.csv_export.clone(),
+            #[cfg(feature = "obd")]
+            obd: config.obd.clone(),
+            #[cfg(feature = "status-server")]
+            status_endpoint: config.status_endpoint.clone(),
+            auto_connect: config.auto_connect,
+            restart_delay_ms: config.restart_delay_ms,
+            stall_reconnect: config.stall_reconnect.clone(),
+            profile_name: profiles.active.clone().unwrap_or_default(),
+            profiles,
+            transfer_path: "gps-monitor-settings.json".to_string(),
             config,
             source_type,
             status_message: None,
         }
     }
 
+    /// Whether baud rate detection is running, for the app to keep polling it
+    pub fn is_detecting_baudrate(&self) -> bool {
+        self.baud_detection.is_some()
+    }
+
     pub fn show(&mut self, ctx: &egui::Context) -> bool {
         if !self.open {
             return false;
         }
 
         let mut config_changed = false;
+        self.poll_baud_detection();
         
         // We need to avoid .open() because it creates a borrow conflict
         // Instead, we'll manually handle the close button
@@ -78,6 +194,13 @@ impl SettingsWindow {
             .default_width(400.0);
             
         let response = window.show(ctx, |ui| {
+                if self.render_profile_settings(ui) {
+                    config_changed = true;
+                }
+
+                ui.add_space(10.0);
+                ui.separator();
+
                 ui.heading("GPS Source Configuration");
                 ui.separator();
 
@@ -94,10 +217,32 @@ impl SettingsWindow {
                     if ui.radio_value(&mut self.source_type, SourceType::Windows, "Windows Location").clicked() {
                         self.status_message = None;
                     }
+                    #[cfg(all(target_os = "linux", feature = "geoclue"))]
+                    if ui.radio_value(&mut self.source_type, SourceType::GeoClue, "GeoClue").clicked() {
+                        self.status_message = None;
+                    }
                 });
 
                 ui.add_space(10.0);
 
+                ui.checkbox(&mut self.auto_connect, "Connect on startup")
+                    .on_hover_text("Turn off to use the app as an offline track viewer; use ▶ Connect when needed");
+
+                ui.horizontal(|ui| {
+                    ui.label("Restart waits");
+                    ui.add(egui::DragValue::new(&mut self.restart_delay_ms).range(0..=10_000).speed(50).suffix(" ms"));
+                    ui.label("before reconnecting");
+                }).response.on_hover_text("Raise for devices that are slow to release the serial port");
+
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut self.stall_reconnect.enabled, "Reconnect when no data arrives for");
+                    ui.add_enabled(self.stall_reconnect.enabled, egui::DragValue::new(&mut self.stall_reconnect.threshold_secs)
+                        .range(5..=3600)
+                        .suffix(" s"));
+                }).response.on_hover_text("Catches a connection that ended silently, e.g. after the computer slept");
+
+                ui.add_space(10.0);
+
                 // Configuration fields based on source type
                 match self.source_type {
                     SourceType::Serial => {
@@ -110,11 +255,63 @@ impl SettingsWindow {
                     SourceType::Windows => {
                         self.render_windows_settings(ui);
                     }
+                    #[cfg(all(target_os = "linux", feature = "geoclue"))]
+                    SourceType::GeoClue => {
+                        ui.label("GeoClue location service");
+                        ui.small("Position from WiFi, cell towers or a built-in GPS, through the desktop's location service");
+                        ui.small("Location services must be enabled in the desktop privacy settings");
+                    }
                 }
 
                 ui.add_space(10.0);
                 ui.separator();
 
+                self.render_map_settings(ui);
+
+                ui.add_space(10.0);
+                ui.separator();
+
+                self.render_units_settings(ui);
+
+                ui.add_space(10.0);
+                ui.separator();
+
+                self.render_snr_settings(ui);
+
+                ui.add_space(10.0);
+                ui.separator();
+
+                self.render_alert_settings(ui);
+
+                ui.add_space(10.0);
+                ui.separator();
+
+                self.render_rotation_settings(ui);
+
+                ui.add_space(10.0);
+                ui.separator();
+
+                self.render_data_log_settings(ui);
+
+                ui.add_space(10.0);
+                ui.separator();
+
+                #[cfg(feature = "obd")]
+                {
+                    self.render_obd_settings(ui);
+
+                    ui.add_space(10.0);
+                    ui.separator();
+                }
+
+                #[cfg(feature = "status-server")]
+                {
+                    self.render_status_endpoint_settings(ui);
+
+                    ui.add_space(10.0);
+                    ui.separator();
+                }
+
                 // Status message
                 if let Some(ref msg) = self.status_message {
                     ui.colored_label(egui::Color32::GREEN, msg);
@@ -123,11 +320,9 @@ impl SettingsWindow {
 
                 // Action buttons
                 ui.horizontal(|ui| {
-                    if ui.button("💾 Save & Apply").clicked() {
-                        if self.validate_and_save() {
-                            config_changed = true;
-                            self.status_message = Some("Settings saved successfully!".to_string());
-                        }
+                    if ui.button("💾 Save & Apply").clicked() && self.validate_and_save() {
+                        config_changed = true;
+                        self.status_message = Some("Settings saved successfully!".to_string());
                     }
 
                     if ui.button("❌ Cancel").clicked() {
@@ -152,6 +347,112 @@ impl SettingsWindow {
         config_changed
     }
 
+    /// Profile switching plus settings file import/export. Returns true when the
+    /// active configuration was replaced.
+    fn render_profile_settings(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut switch_to = None;
+        let mut changed = false;
+
+        ui.heading("Profiles");
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            ui.label("Active:");
+            egui::ComboBox::from_id_source("profile_select")
+                .selected_text(self.profiles.active.as_deref().unwrap_or("(none)"))
+                .show_ui(ui, |ui| {
+                    for name in self.profiles.names() {
+                        let selected = self.profiles.active.as_deref() == Some(name);
+                        if ui.selectable_label(selected, name).clicked() && !selected {
+                            switch_to = Some(name.to_string());
+                        }
+                    }
+                });
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Name:");
+            ui.text_edit_singleline(&mut self.profile_name);
+
+            if ui.button("💾 Save Profile").clicked() && self.validate_and_save() {
+                let name = self.profile_name.clone();
+                match self.profiles.save_profile(&name, &self.config).and_then(|_| self.profiles.save()) {
+                    Ok(_) => {
+                        self.status_message = Some(format!("Saved profile '{}'", name.trim()));
+                        changed = true;
+                    }
+                    Err(e) => self.status_message = Some(format!("Error saving profile: {}", e)),
+                }
+            }
+
+            if ui.button("🗑 Delete").clicked() {
+                let name = self.profile_name.trim().to_string();
+                if self.profiles.remove(&name) {
+                    self.status_message = match self.profiles.save() {
+                        Ok(_) => Some(format!("Deleted profile '{}'", name)),
+                        Err(e) => Some(format!("Error saving profiles: {}", e)),
+                    };
+                }
+            }
+        });
+
+        if let Some(name) = switch_to {
+            match self.profiles.switch_to(&name) {
+                Ok(config) => {
+                    if let Err(e) = self.profiles.save() {
+                        self.status_message = Some(format!("Error saving profiles: {}", e));
+                    }
+                    changed = self.apply_config(config, format!("Switched to profile '{}'", name));
+                }
+                Err(e) => self.status_message = Some(format!("Error: {}", e)),
+            }
+        }
+
+        ui.add_space(5.0);
+        ui.horizontal(|ui| {
+            ui.label("File:");
+            ui.text_edit_singleline(&mut self.transfer_path);
+
+            if ui.button("📤 Export").clicked() {
+                self.status_message = match self.config.export_to_file(Path::new(&self.transfer_path)) {
+                    Ok(_) => Some(format!("Exported settings to {}", self.transfer_path)),
+                    Err(e) => Some(format!("Export failed: {}", e)),
+                };
+            }
+
+            if ui.button("📥 Import").clicked() {
+                match GpsConfig::import_from_file(Path::new(&self.transfer_path)) {
+                    Ok(config) => {
+                        let msg = format!("Imported settings from {}", self.transfer_path);
+                        changed = self.apply_config(config, msg);
+                    }
+                    Err(e) => self.status_message = Some(format!("Import failed: {}", e)),
+                }
+            }
+        });
+
+        ui.small("Export writes the last saved settings");
+
+        changed
+    }
+
+    /// Replace the whole configuration (profile switch or import), save it and
+    /// refresh the form fields. The export history is kept.
+    fn apply_config(&mut self, mut config: GpsConfig, message: String) -> bool {
+        config.keep_state_from(&self.config);
+        if let Err(e) = config.save() {
+            self.status_message = Some(format!("Error saving: {}", e));
+            return false;
+        }
+
+        let transfer_path = std::mem::take(&mut self.transfer_path);
+        *self = Self::new(config);
+        self.open = true;
+        self.transfer_path = transfer_path;
+        self.status_message = Some(message);
+        true
+    }
+
     fn render_serial_settings(&mut self, ui: &mut egui::Ui) {
         ui.label("Serial Port Settings:");
         
@@ -164,12 +465,117 @@ impl SettingsWindow {
                 ui.end_row();
 
                 ui.label("Baud Rate:");
-                ui.text_edit_singleline(&mut self.serial_baudrate);
+                ui.horizontal(|ui| {
+                    ui.text_edit_singleline(&mut self.serial_baudrate);
+                    if self.baud_detection.is_some() {
+                        ui.spinner();
+                    } else if ui.add_enabled(!self.serial_port.trim().is_empty(), egui::Button::new("Auto-detect"))
+                        .on_hover_text("Listen at common baud rates until valid NMEA arrives. Disconnect first.")
+                        .clicked()
+                    {
+                        self.start_baud_detection();
+                    }
+                });
+                ui.end_row();
+
+                ui.label("Read Timeout (ms):");
+                ui.text_edit_singleline(&mut self.serial_read_timeout);
+                ui.end_row();
+
+                ui.label("Max Line Length:");
+                ui.text_edit_singleline(&mut self.serial_max_line_length);
+                ui.end_row();
+
+                ui.label("RMC Speed Unit:");
+                egui::ComboBox::from_id_source("rmc_speed_unit")
+                    .selected_text(self.rmc_speed_unit.name())
+                    .show_ui(ui, |ui| {
+                        for unit in SpeedUnit::ALL {
+                            ui.selectable_value(&mut self.rmc_speed_unit, unit, unit.name());
+                        }
+                    });
+                ui.end_row();
+
+                ui.label("Data Bits:");
+                egui::ComboBox::from_id_source("serial_data_bits")
+                    .selected_text(self.serial_line.data_bits.to_string())
+                    .show_ui(ui, |ui| {
+                        for bits in [5, 6, 7, 8] {
+                            ui.selectable_value(&mut self.serial_line.data_bits, bits, bits.to_string());
+                        }
+                    });
+                ui.end_row();
+
+                ui.label("Parity:");
+                egui::ComboBox::from_id_source("serial_parity")
+                    .selected_text(self.serial_line.parity.name())
+                    .show_ui(ui, |ui| {
+                        for parity in SerialParity::ALL {
+                            ui.selectable_value(&mut self.serial_line.parity, parity, parity.name());
+                        }
+                    });
+                ui.end_row();
+
+                ui.label("Stop Bits:");
+                egui::ComboBox::from_id_source("serial_stop_bits")
+                    .selected_text(self.serial_line.stop_bits.to_string())
+                    .show_ui(ui, |ui| {
+                        for bits in [1, 2] {
+                            ui.selectable_value(&mut self.serial_line.stop_bits, bits, bits.to_string());
+                        }
+                    });
+                ui.end_row();
+
+                ui.label("Flow Control:");
+                egui::ComboBox::from_id_source("serial_flow_control")
+                    .selected_text(self.serial_line.flow_control.name())
+                    .show_ui(ui, |ui| {
+                        for flow in SerialFlowControl::ALL {
+                            ui.selectable_value(&mut self.serial_line.flow_control, flow, flow.name());
+                        }
+                    });
+                ui.end_row();
+
+                ui.label("DTR:");
+                control_line_combo(ui, "serial_dtr", &mut self.serial_line.dtr);
+                ui.end_row();
+
+                ui.label("RTS:");
+                control_line_combo(ui, "serial_rts", &mut self.serial_line.rts);
                 ui.end_row();
             });
 
         ui.add_space(5.0);
         ui.small("Examples: COM3, /dev/ttyUSB0, /dev/ttyACM0");
+        ui.small("Only change the speed unit if speeds read wrong; standard receivers use knots");
+        ui.small("Most receivers use 8N1 without flow control; some need DTR or RTS on to power up");
+    }
+
+    fn start_baud_detection(&mut self) {
+        let port = self.serial_port.trim().to_string();
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            // Drop the error right away; GpsError is not Send
+            let _ = tx.send(detect_baudrate(&port).map_err(|e| e.to_string()));
+        });
+        self.baud_detection = Some(rx);
+        self.status_message = Some(format!("Detecting baud rate on {}...", self.serial_port.trim()));
+    }
+
+    fn poll_baud_detection(&mut self) {
+        let Some(rx) = &self.baud_detection else {
+            return;
+        };
+        match rx.try_recv() {
+            Ok(Ok(baudrate)) => {
+                self.serial_baudrate = baudrate.to_string();
+                self.status_message = Some(format!("Detected {} baud", baudrate));
+            }
+            Ok(Err(e)) => self.status_message = Some(format!("Error: {}", e)),
+            Err(mpsc::TryRecvError::Empty) => return,
+            Err(mpsc::TryRecvError::Disconnected) => {}
+        }
+        self.baud_detection = None;
     }
 
     fn render_gpsd_settings(&mut self, ui: &mut egui::Ui) {
@@ -213,6 +619,620 @@ impl SettingsWindow {
         ui.small("Lower accuracy values request higher precision (uses more power)");
     }
 
+    fn render_map_settings(&mut self, ui: &mut egui::Ui) {
+        ui.label("Map Markers:");
+
+        egui::Grid::new("map_marker_settings")
+            .num_columns(2)
+            .spacing([10.0, 8.0])
+            .show(ui, |ui| {
+                ui.label("Position color:");
+                ui.color_edit_button_srgb(&mut self.marker_style.position_color);
+                ui.end_row();
+
+                ui.label("Position size:");
+                ui.add(egui::Slider::new(&mut self.marker_style.position_size, 4.0..=16.0).suffix(" px"));
+                ui.end_row();
+
+                ui.label("Waypoint color:");
+                ui.color_edit_button_srgb(&mut self.marker_style.waypoint_color);
+                ui.end_row();
+
+                ui.label("Waypoint size:");
+                ui.add(egui::Slider::new(&mut self.marker_style.waypoint_size, 3.0..=12.0).suffix(" px"));
+                ui.end_row();
+
+                ui.label("Track color:");
+                ui.color_edit_button_srgb(&mut self.marker_style.track_color);
+                ui.end_row();
+
+                ui.label("Recording color:");
+                ui.color_edit_button_srgb(&mut self.marker_style.active_track_color);
+                ui.end_row();
+
+                ui.label("Track width:");
+                ui.add(egui::Slider::new(&mut self.marker_style.track_width, 1.0..=10.0).suffix(" px"));
+                ui.end_row();
+
+                ui.label("Track opacity:");
+                ui.add(egui::Slider::new(&mut self.marker_style.track_opacity, 0.1..=1.0));
+                ui.end_row();
+            });
+
+        ui.add_space(5.0);
+        ui.small("Waypoints with their own color override the default");
+
+        ui.add_space(5.0);
+        ui.horizontal(|ui| {
+            ui.label("While following, keep the position");
+            ui.add(egui::Slider::new(&mut self.map_options.follow_offset, 0.0..=0.4).custom_formatter(|v, _| format!("{:.0}%", v * 100.0)));
+            ui.label("below center");
+        }).response.on_hover_text("Shows more of the road ahead; 0% keeps the position centered");
+
+        ui.horizontal(|ui| {
+            ui.label("Ignore position changes smaller than");
+            ui.add(egui::DragValue::new(&mut self.map_options.follow_dead_zone_px).range(0.0..=100.0).suffix(" px"));
+        }).response.on_hover_text("Keeps a parked map from twitching with GPS jitter; 0 recenters on every fix");
+
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.map_options.auto_follow, "Resume following after panning");
+            ui.add_enabled(
+                self.map_options.auto_follow,
+                egui::DragValue::new(&mut self.map_options.auto_follow_secs).range(1..=600).suffix(" s"),
+            );
+        });
+
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.map_options.show_breadcrumb, "Show breadcrumb trail of the last");
+            ui.add_enabled(
+                self.map_options.show_breadcrumb,
+                egui::DragValue::new(&mut self.map_options.breadcrumb_length).range(10..=10_000).suffix(" positions"),
+            );
+        });
+
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.map_options.prefetch_ahead, "Prefetch tiles along the path for the next");
+            ui.add_enabled(
+                self.map_options.prefetch_ahead,
+                egui::DragValue::new(&mut self.map_options.prefetch_secs).range(5..=600).suffix(" s"),
+            );
+        });
+        ui.small("Prefetching only runs while the map is following your position");
+
+        ui.horizontal(|ui| {
+            ui.label("Show waypoint names from zoom");
+            ui.add(egui::DragValue::new(&mut self.map_options.label_min_zoom).range(1..=18));
+        });
+        ui.small("Names that would overlap one already drawn are skipped");
+
+        ui.add_space(5.0);
+        ui.horizontal(|ui| {
+            ui.label("Tile cache:");
+            ui.add(egui::TextEdit::singleline(&mut self.tile_cache_dir)
+                .hint_text(GpsConfig::default_tile_cache_path().to_string_lossy()));
+            if !self.tile_cache_dir.is_empty() && ui.button("Default").clicked() {
+                self.tile_cache_dir.clear();
+            }
+        });
+        ui.small("A new tile cache location applies after restarting");
+
+        ui.add_space(5.0);
+        egui::Grid::new("tile_identity")
+            .num_columns(2)
+            .spacing([10.0, 4.0])
+            .show(ui, |ui| {
+                ui.label("Tile User-Agent:");
+                ui.add(egui::TextEdit::singleline(&mut self.tile_user_agent).hint_text(DEFAULT_USER_AGENT));
+                ui.end_row();
+
+                ui.label("Contact:");
+                ui.add(egui::TextEdit::singleline(&mut self.tile_contact).hint_text("email or URL"));
+                ui.end_row();
+            });
+        // Both are sent as a header, which only carries plain ASCII
+        self.tile_user_agent.retain(is_header_char);
+        self.tile_contact.retain(is_header_char);
+        ui.small("OpenStreetMap asks tile users to identify their application and a contact; applies after restarting");
+
+        ui.horizontal(|ui| {
+            ui.label("Keep");
+            ui.add(egui::DragValue::new(&mut self.map_options.memory_tiles).range(10..=5000));
+            ui.label("tiles in memory, download");
+            ui.add(egui::DragValue::new(&mut self.map_options.concurrent_downloads).range(1..=16));
+            ui.label("at once");
+        }).response.on_hover_text("Fewer tiles in memory suit constrained devices; more downloads at once load the map faster on a fast connection");
+        ui.small("Tile limits apply after restarting");
+        ui.checkbox(&mut self.map_options.warm_cache_on_open, "Load cached tiles for the map view when it opens");
+        ui.checkbox(&mut self.map_options.split_antimeridian, "Break tracks where they cross the ±180° meridian")
+            .on_hover_text("Otherwise a Pacific crossing is drawn as a line across the whole map");
+
+        ui.horizontal(|ui| {
+            ui.label("Heading smoothing:");
+            ui.add(egui::DragValue::new(&mut self.map_options.heading_smoothing_secs).range(0.0..=10.0).speed(0.1).suffix(" s"));
+            ui.label("hidden below");
+            ui.add(egui::DragValue::new(&mut self.map_options.heading_min_speed).range(0.0..=50.0).speed(0.5).suffix(" km/h"));
+        });
+        ui.small("Steadies the heading on the position marker; the course is unreliable when slow or stopped");
+
+        ui.add_space(5.0);
+        ui.checkbox(&mut self.map_options.speed_zoom, "Zoom out at higher speeds while following")
+            .on_hover_text("Changes zoom only when the speed moves into another band; zooming by hand in between is kept");
+        ui.add_enabled_ui(self.map_options.speed_zoom, |ui| {
+            let steps = &mut self.map_options.speed_zoom_steps;
+            let mut remove = None;
+            egui::Grid::new("speed_zoom_steps")
+                .num_columns(3)
+                .spacing([10.0, 4.0])
+                .show(ui, |ui| {
+                    for (i, step) in steps.iter_mut().enumerate() {
+                        ui.horizontal(|ui| {
+                            ui.label("From");
+                            ui.add(egui::DragValue::new(&mut step.min_speed).range(0.0..=300.0).speed(1.0).suffix(" km/h"));
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label("zoom");
+                            ui.add(egui::DragValue::new(&mut step.zoom).range(1..=18));
+                        });
+                        if ui.small_button("✖").on_hover_text("Remove this step").clicked() {
+                            remove = Some(i);
+                        }
+                        ui.end_row();
+                    }
+                });
+            if let Some(i) = remove.filter(|_| steps.len() > 1) {
+                steps.remove(i);
+            }
+            ui.horizontal(|ui| {
+                if ui.button("➕ Add step").clicked() {
+                    let last = steps.last().copied().unwrap_or(SpeedZoomStep { min_speed: 0.0, zoom: 16 });
+                    steps.push(SpeedZoomStep { min_speed: last.min_speed + 20.0, zoom: last.zoom.saturating_sub(1).max(1) });
+                }
+                if ui.button("Reset").clicked() {
+                    *steps = SpeedZoomStep::defaults();
+                }
+            });
+        });
+        ui.small("Steps are sorted by speed when saved");
+    }
+
+    fn render_units_settings(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Distance units:");
+            egui::ComboBox::from_id_source("distance_units")
+                .selected_text(self.distance_units.name())
+                .show_ui(ui, |ui| {
+                    for units in DistanceUnits::ALL {
+                        ui.selectable_value(&mut self.distance_units, units, units.name());
+                    }
+                });
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Speed unit:");
+            let follow = format!("Same as distance ({})", self.distance_units.speed_label());
+            egui::ComboBox::from_id_source("speed_unit")
+                .selected_text(self.speed_unit.map_or(follow.clone(), |unit| unit.label().to_string()))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.speed_unit, None, follow);
+                    for unit in DisplaySpeedUnit::ALL {
+                        ui.selectable_value(&mut self.speed_unit, Some(unit), unit.label());
+                    }
+                });
+        });
+
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.speedometer.enabled, "Show speedometer");
+            ui.add_enabled_ui(self.speedometer.enabled, |ui| {
+                ui.label("up to");
+                ui.add(egui::DragValue::new(&mut self.speedometer.max_speed)
+                    .range(10.0..=1000.0)
+                    .speed(5.0)
+                    .suffix(format!(" {}", self.speed_unit.unwrap_or_else(|| self.distance_units.speed_unit()).label())));
+            });
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Preferred altitude:");
+            egui::ComboBox::from_id_source("altitude_source")
+                .selected_text(self.altitude_source.name())
+                .show_ui(ui, |ui| {
+                    for source in AltitudeSource::ALL {
+                        ui.selectable_value(&mut self.altitude_source, source, source.name());
+                    }
+                });
+        }).response.on_hover_text("Used when both GPS and barometric altitude are available");
+
+        ui.horizontal(|ui| {
+            ui.label("Course relative to:");
+            egui::ComboBox::from_id_source("north_reference")
+                .selected_text(self.north_reference.name())
+                .show_ui(ui, |ui| {
+                    for reference in NorthReference::ALL {
+                        ui.selectable_value(&mut self.north_reference, reference, reference.name());
+                    }
+                });
+        }).response.on_hover_text("Magnetic needs the receiver to report magnetic variation; true course is shown until it does");
+
+        ui.checkbox(&mut self.exclude_2d_altitude, "Leave altitude out of track points recorded during a 2D fix")
+            .on_hover_text("A 2D fix repeats an old or assumed altitude rather than measuring one");
+
+        ui.checkbox(&mut self.record_satellite_snapshots, "Record satellite counts and average SNR with track points")
+            .on_hover_text("Exported in GPX extensions and extra CSV columns, for finding poor-geometry segments");
+
+        ui.checkbox(&mut self.recording_indicator, "Show a blinking REC indicator while recording")
+            .on_hover_text("In the top bar and on the map, with the points recorded and time since the start");
+
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.session_autosave.enabled, "Auto-save waypoints and tracks every");
+            ui.add_enabled(self.session_autosave.enabled, egui::DragValue::new(&mut self.session_autosave.interval_secs)
+                .range(10..=3600)
+                .suffix(" s"));
+        }).response.on_hover_text("Also saved on exit and restored on the next start, so a crash loses at most one interval");
+
+        ui.horizontal(|ui| {
+            ui.label("Time zone:");
+            let fixed = match self.time_zone {
+                DisplayTimeZone::Fixed(minutes) => DisplayTimeZone::Fixed(minutes),
+                _ => DisplayTimeZone::Fixed(0),
+            };
+            egui::ComboBox::from_id_source("time_zone")
+                .selected_text(self.time_zone.name())
+                .show_ui(ui, |ui| {
+                    for zone in [DisplayTimeZone::Utc, DisplayTimeZone::Local, fixed] {
+                        ui.selectable_value(&mut self.time_zone, zone, zone.name());
+                    }
+                });
+
+            if let DisplayTimeZone::Fixed(minutes) = &mut self.time_zone {
+                let mut hours = *minutes as f64 / 60.0;
+                ui.label("UTC");
+                ui.add(egui::DragValue::new(&mut hours).range(-12.0..=14.0).speed(0.25).fixed_decimals(2).suffix(" h"));
+                *minutes = (hours * 4.0).round() as i32 * 15;
+            }
+        }).response.on_hover_text("Only affects displayed times; recorded and exported times stay in UTC");
+
+        ui.horizontal(|ui| {
+            ui.label("Coordinate decimals:");
+            ui.add(egui::DragValue::new(&mut self.coordinate_decimals).range(COORDINATE_DECIMALS_RANGE));
+        }).response.on_hover_text("Places shown and written to CSV/GeoJSON exports: 6 is about 0.1 m, 8 or 9 suits RTK");
+
+        ui.horizontal(|ui| {
+            ui.label("Coordinate format:");
+            egui::ComboBox::from_id_source("coordinate_format")
+                .selected_text(self.coordinate_format.name())
+                .show_ui(ui, |ui| {
+                    for format in CoordinateFormat::ALL {
+                        ui.selectable_value(&mut self.coordinate_format, format, format.name());
+                    }
+                });
+        }).response.on_hover_text("UTM and MGRS are shown to 1 m; positions beyond 84°N or 80°S stay in degrees. Exports always use latitude/longitude");
+
+        ui.add_space(5.0);
+        ui.label("Copy position as:");
+        let mut remove = None;
+        egui::Grid::new("copy_templates")
+            .num_columns(3)
+            .spacing([10.0, 4.0])
+            .show(ui, |ui| {
+                for (index, template) in self.copy_templates.iter_mut().enumerate() {
+                    ui.add(egui::TextEdit::singleline(&mut template.name).desired_width(110.0));
+                    ui.add(egui::TextEdit::singleline(&mut template.template).desired_width(260.0));
+                    if ui.small_button("✖").clicked() {
+                        remove = Some(index);
+                    }
+                    ui.end_row();
+                }
+            });
+        if let Some(index) = remove {
+            self.copy_templates.remove(index);
+        }
+        ui.horizontal(|ui| {
+            if ui.small_button("➕ Add template").clicked() {
+                self.copy_templates.push(CopyTemplate::new("New template", "{lat},{lon}"));
+            }
+            if ui.small_button("Reset to defaults").clicked() {
+                self.copy_templates = CopyTemplate::defaults();
+            }
+        });
+        ui.small("{lat} and {lon} are replaced by the position in decimal degrees, to the places set above");
+
+        ui.horizontal(|ui| {
+            ui.label("Redraw in the background every");
+            ui.add(egui::DragValue::new(&mut self.unfocused_repaint_secs).range(1..=60).suffix(" s"));
+        }).response.on_hover_text("Saves power while the window isn't focused; the connection keeps running. Track recording still redraws every second, 1 s keeps the full rate");
+
+        ui.add_space(5.0);
+        ui.label("CSV export:");
+        ui.horizontal(|ui| {
+            ui.label("Timestamps:");
+            egui::ComboBox::from_id_source("csv_timestamp")
+                .selected_text(self.csv_export.timestamp.name())
+                .show_ui(ui, |ui| {
+                    for format in CsvTimestamp::ALL {
+                        ui.selectable_value(&mut self.csv_export.timestamp, format, format.name());
+                    }
+                });
+            ui.label("Units:");
+            egui::ComboBox::from_id_source("csv_units")
+                .selected_text(self.csv_export.units.name())
+                .show_ui(ui, |ui| {
+                    for units in DistanceUnits::ALL {
+                        ui.selectable_value(&mut self.csv_export.units, units, units.name());
+                    }
+                });
+        });
+        ui.checkbox(&mut self.csv_export.decimal_comma, "Decimal comma (fields separated by ;)")
+            .on_hover_text("For spreadsheets in locales that write 1,5 rather than 1.5");
+        ui.checkbox(&mut self.csv_export.segment_column, "Segment column")
+            .on_hover_text("Numbers each track point's segment, so pauses in a recording show and survive re-import");
+    }
+
+    fn render_snr_settings(&mut self, ui: &mut egui::Ui) {
+        ui.label("Signal Quality Thresholds (SNR dB):");
+
+        egui::Grid::new("snr_threshold_settings")
+            .num_columns(2)
+            .spacing([10.0, 8.0])
+            .show(ui, |ui| {
+                let levels = [
+                    ("Excellent ≥", &mut self.snr_thresholds.excellent),
+                    ("Good ≥", &mut self.snr_thresholds.good),
+                    ("Fair ≥", &mut self.snr_thresholds.fair),
+                    ("Poor ≥", &mut self.snr_thresholds.poor),
+                ];
+                for (label, value) in levels {
+                    ui.label(label);
+                    ui.add(egui::DragValue::new(value).range(0.0..=99.0).speed(0.5));
+                    ui.end_row();
+                }
+            });
+
+        ui.horizontal(|ui| {
+            ui.small("Adjust for receivers that report C/N0 on a different scale");
+            if ui.small_button("Reset").clicked() {
+                self.snr_thresholds = SnrThresholds::default();
+            }
+        });
+
+        ui.add_space(5.0);
+        ui.horizontal(|ui| {
+            ui.label("Color palette:");
+            egui::ComboBox::from_id_source("color_palette")
+                .selected_text(self.color_palette.name())
+                .show_ui(ui, |ui| {
+                    for palette in ColorPalette::ALL {
+                        ui.selectable_value(&mut self.color_palette, palette, palette.name());
+                    }
+                });
+        }).response.on_hover_text("Constellation and signal quality colors in the sky plot and satellite table");
+
+        ui.add_space(5.0);
+        ui.horizontal(|ui| {
+            ui.label("Elevation mask:");
+            ui.add(egui::DragValue::new(&mut self.elevation_mask_deg).range(0.0..=90.0).speed(0.5).suffix("°"));
+        });
+        ui.small("Used satellites below the mask are shown as unused (display only)");
+
+        ui.add_space(5.0);
+        ui.horizontal(|ui| {
+            ui.label("Sky plot rings every:");
+            egui::ComboBox::from_id_source("sky_plot_ring_interval")
+                .selected_text(format!("{}°", self.sky_plot_grid.ring_interval_deg))
+                .show_ui(ui, |ui| {
+                    for interval in SkyPlotGrid::RING_INTERVALS {
+                        ui.selectable_value(&mut self.sky_plot_grid.ring_interval_deg, interval, format!("{}°", interval));
+                    }
+                });
+        });
+        ui.checkbox(&mut self.sky_plot_grid.azimuth_labels, "Azimuth spokes and labels every 30°");
+        ui.checkbox(&mut self.sky_plot_grid.size_by_snr, "Size satellites by signal strength")
+            .on_hover_text("Stronger satellites are drawn bigger; used ones keep their constellation color");
+        ui.checkbox(&mut self.sky_plot_grid.tint_unused, "Tint unused satellites by constellation")
+            .on_hover_text("Unused satellites are drawn in a dimmed constellation color instead of gray");
+
+        ui.add_space(5.0);
+        ui.checkbox(&mut self.horizon_mask.enabled, "Shade horizon mask on the sky plot")
+            .on_hover_text("Obstructions such as buildings or terrain; satellites behind them are drawn hollow");
+        ui.add_enabled_ui(self.horizon_mask.enabled, |ui| {
+            let mut remove = None;
+            egui::Grid::new("horizon_mask_points")
+                .num_columns(3)
+                .spacing([10.0, 4.0])
+                .show(ui, |ui| {
+                    ui.label("Azimuth");
+                    ui.label("Up to elevation");
+                    ui.end_row();
+                    for (index, point) in self.horizon_mask.points.iter_mut().enumerate() {
+                        ui.add(egui::DragValue::new(&mut point.azimuth_deg).range(0.0..=359.0).speed(1.0).suffix("°"));
+                        ui.add(egui::DragValue::new(&mut point.min_elevation_deg).range(0.0..=90.0).speed(0.5).suffix("°"));
+                        if ui.small_button("✖").clicked() {
+                            remove = Some(index);
+                        }
+                        ui.end_row();
+                    }
+                });
+            if let Some(index) = remove {
+                self.horizon_mask.points.remove(index);
+            }
+            if ui.small_button("➕ Add point").clicked() {
+                let azimuth_deg = self.horizon_mask.points.last().map_or(0.0, |p| (p.azimuth_deg + 45.0) % 360.0);
+                self.horizon_mask.points.push(HorizonPoint { azimuth_deg, min_elevation_deg: 10.0 });
+            }
+        });
+        ui.small("The elevation between points is interpolated; points are sorted by azimuth when saved");
+
+        ui.add_space(5.0);
+        ui.horizontal(|ui| {
+            ui.label("Drop satellites not seen for:");
+            ui.add(egui::DragValue::new(&mut self.satellite_timeout_secs).range(0..=600).suffix(" s"));
+        }).response.on_hover_text("0 keeps satellites until their constellation reports again");
+    }
+
+    fn render_alert_settings(&mut self, ui: &mut egui::Ui) {
+        ui.label("Alerts:");
+
+        ui.checkbox(&mut self.no_fix_warning.enabled, "Warn when there is no GPS fix");
+        ui.add_enabled_ui(self.no_fix_warning.enabled, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("After:");
+                ui.add(egui::DragValue::new(&mut self.no_fix_warning.threshold_secs).range(1..=3600).suffix(" s"));
+                ui.checkbox(&mut self.no_fix_warning.beep, "Beep");
+            });
+        });
+
+        ui.add_space(5.0);
+        ui.small("Disable for indoor testing where no fix is expected");
+
+        ui.add_space(5.0);
+        ui.horizontal(|ui| {
+            ui.label("Expected update rate:");
+            ui.add(egui::DragValue::new(&mut self.expected_update_hz).range(0..=50).suffix(" Hz"));
+        }).response.on_hover_text("Warn when fixes arrive well below the receiver's configured rate; 0 turns this off");
+
+        ui.horizontal(|ui| {
+            ui.label("Minimum satellites used:");
+            ui.add(egui::DragValue::new(&mut self.min_satellites_used).range(0..=12));
+        }).response.on_hover_text("Show a low satellite count caution for fixes from fewer satellites; 0 turns this off");
+
+        ui.add_space(10.0);
+        ui.label("Waypoint Quality:");
+        let gate = &mut self.waypoint_quality_gate;
+        ui.checkbox(&mut gate.enabled, "Only allow saving waypoints with a good fix");
+        ui.add_enabled_ui(gate.enabled, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Max HDOP:");
+                ui.add(egui::DragValue::new(&mut gate.max_hdop).range(0.5..=20.0).speed(0.1));
+                ui.label("Min satellites:");
+                ui.add(egui::DragValue::new(&mut gate.min_satellites).range(0..=32));
+                ui.checkbox(&mut gate.require_3d, "Require 3D fix");
+            });
+        });
+
+        let hold = &mut self.waypoint_fix_hold;
+        ui.checkbox(&mut hold.enabled, "Hold the last good fix through brief dropouts")
+            .on_hover_text("The waypoint dialog keeps showing the last fix, with its age, instead of \"No fix\"");
+        ui.add_enabled_ui(hold.enabled, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Allow saving from a held fix up to:");
+                ui.add(egui::DragValue::new(&mut hold.max_age_secs).range(1..=120).suffix(" s"));
+            });
+        });
+    }
+
+    fn render_rotation_settings(&mut self, ui: &mut egui::Ui) {
+        ui.label("Track Log Rotation:");
+
+        let default_dir = TrackRotation::default().output_path();
+        let rotation = &mut self.track_rotation;
+        ui.checkbox(&mut rotation.enabled, "Export recordings to dated GPX files");
+        ui.add_enabled_ui(rotation.enabled, |ui| {
+            egui::Grid::new("rotation_settings")
+                .num_columns(2)
+                .spacing([10.0, 8.0])
+                .show(ui, |ui| {
+                    ui.label("New file:");
+                    egui::ComboBox::from_id_source("rotation_interval")
+                        .selected_text(rotation.interval.name())
+                        .show_ui(ui, |ui| {
+                            for interval in RotationInterval::ALL {
+                                ui.selectable_value(&mut rotation.interval, interval, interval.name());
+                            }
+                        });
+                    ui.end_row();
+
+                    ui.label("Directory:");
+                    ui.add(egui::TextEdit::singleline(&mut rotation.output_dir)
+                        .hint_text(default_dir.to_string_lossy()));
+                    ui.end_row();
+                });
+        });
+
+        ui.add_space(5.0);
+        ui.small("Periods start on UTC hour or day boundaries; recording continues in a fresh track");
+    }
+
+    fn render_data_log_settings(&mut self, ui: &mut egui::Ui) {
+        ui.label("Data Log:");
+
+        let default_dir = DataLog::default().output_path();
+        let log = &mut self.data_log;
+        ui.checkbox(&mut log.enabled, "Log every fix to CSV while connected");
+        ui.add_enabled_ui(log.enabled, |ui| {
+            egui::Grid::new("data_log_settings")
+                .num_columns(2)
+                .spacing([10.0, 8.0])
+                .show(ui, |ui| {
+                    ui.label("New file after:");
+                    ui.add(egui::DragValue::new(&mut log.max_size_mb).range(0..=1024).suffix(" MB"))
+                        .on_hover_text("0 for no size limit");
+                    ui.end_row();
+
+                    ui.label("And every:");
+                    egui::ComboBox::from_id_source("data_log_interval")
+                        .selected_text(log.interval.map_or("Never", |interval| interval.name()))
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut log.interval, None, "Never");
+                            for interval in RotationInterval::ALL {
+                                ui.selectable_value(&mut log.interval, Some(interval), interval.name());
+                            }
+                        });
+                    ui.end_row();
+
+                    ui.label("Directory:");
+                    ui.add(egui::TextEdit::singleline(&mut log.output_dir)
+                        .hint_text(default_dir.to_string_lossy()));
+                    ui.end_row();
+                });
+        });
+
+        ui.add_space(5.0);
+        ui.small("Decoded values, one row per fix; takes effect on the next connect");
+    }
+
+    #[cfg(feature = "obd")]
+    fn render_obd_settings(&mut self, ui: &mut egui::Ui) {
+        ui.label("OBD-II Adapter:");
+
+        ui.checkbox(&mut self.obd.enabled, "Record vehicle data from an ELM327 adapter");
+        ui.add_enabled_ui(self.obd.enabled, |ui| {
+            egui::Grid::new("obd_settings")
+                .num_columns(2)
+                .spacing([10.0, 8.0])
+                .show(ui, |ui| {
+                    ui.label("Port:");
+                    ui.text_edit_singleline(&mut self.obd.port);
+                    ui.end_row();
+
+                    ui.label("Baud Rate:");
+                    ui.add(egui::DragValue::new(&mut self.obd.baudrate).range(1200..=500000));
+                    ui.end_row();
+
+                    ui.label("Poll Interval:");
+                    ui.add(egui::DragValue::new(&mut self.obd.poll_interval_ms).range(100..=10000).suffix(" ms"));
+                    ui.end_row();
+                });
+        });
+
+        ui.add_space(5.0);
+        ui.small("Speed, RPM, throttle, load and coolant temperature are added to recorded track points");
+    }
+
+    #[cfg(feature = "status-server")]
+    fn render_status_endpoint_settings(&mut self, ui: &mut egui::Ui) {
+        ui.label("Status Endpoint:");
+
+        ui.checkbox(&mut self.status_endpoint.enabled, "Serve the latest data as JSON at /status");
+        ui.add_enabled_ui(self.status_endpoint.enabled, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Listen on:");
+                ui.add(egui::TextEdit::singleline(&mut self.status_endpoint.address).hint_text("127.0.0.1:8947"));
+            });
+        });
+
+        ui.add_space(5.0);
+        ui.small("Use 0.0.0.0 to allow other devices on the network; there is no authentication");
+    }
+
     fn validate_and_save(&mut self) -> bool {
         match self.source_type {
             SourceType::Serial => {
@@ -229,7 +1249,26 @@ impl SettingsWindow {
                     }
                 };
 
+                let read_timeout = match self.serial_read_timeout.parse::<u32>() {
+                    Ok(t) if t > 0 => t,
+                    _ => {
+                        self.status_message = Some("Error: Invalid read timeout".to_string());
+                        return false;
+                    }
+                };
+
+                let max_line_length = match self.serial_max_line_length.parse::<u32>() {
+                    Ok(n) if n >= 82 => n,
+                    _ => {
+                        self.status_message = Some("Error: Max line length must be at least 82".to_string());
+                        return false;
+                    }
+                };
+
                 self.config.update_serial(self.serial_port.clone(), baudrate);
+                self.config.update_serial_limits(read_timeout, max_line_length);
+                self.config.rmc_speed_unit = self.rmc_speed_unit;
+                self.config.serial_line = self.serial_line;
             }
             SourceType::Gpsd => {
                 if self.gpsd_host.is_empty() {
@@ -267,6 +1306,75 @@ impl SettingsWindow {
 
                 self.config.update_windows(accuracy, interval);
             }
+            #[cfg(all(target_os = "linux", feature = "geoclue"))]
+            SourceType::GeoClue => {
+                self.config.update_source("geoclue");
+            }
+        }
+
+        if !self.snr_thresholds.is_valid() {
+            self.status_message = Some("Error: SNR thresholds must decrease from Excellent to Poor".to_string());
+            return false;
+        }
+
+        #[cfg(feature = "obd")]
+        if self.obd.enabled && self.obd.port.trim().is_empty() {
+            self.status_message = Some("Error: OBD-II port cannot be empty".to_string());
+            return false;
+        }
+
+        self.config.auto_connect = self.auto_connect;
+        self.config.restart_delay_ms = self.restart_delay_ms;
+        self.config.stall_reconnect = self.stall_reconnect.clone();
+        self.config.marker_style = self.marker_style.clone();
+        self.config.snr_thresholds = self.snr_thresholds.clone();
+        self.config.elevation_mask_deg = self.elevation_mask_deg;
+        self.config.sky_plot_grid = self.sky_plot_grid;
+        self.config.horizon_mask = self.horizon_mask.clone();
+        self.config.satellite_timeout_secs = self.satellite_timeout_secs;
+        self.config.expected_update_hz = self.expected_update_hz;
+        self.config.unfocused_repaint_secs = self.unfocused_repaint_secs;
+        self.config.min_satellites_used = self.min_satellites_used;
+        self.config.map_options = self.map_options.clone();
+        let tile_cache_dir = self.tile_cache_dir.trim();
+        self.config.tile_cache_dir = (!tile_cache_dir.is_empty()).then(|| tile_cache_dir.to_string());
+        let tile_user_agent = self.tile_user_agent.trim();
+        self.config.tile_user_agent = (!tile_user_agent.is_empty()).then(|| tile_user_agent.to_string());
+        let tile_contact = self.tile_contact.trim();
+        self.config.tile_contact = (!tile_contact.is_empty()).then(|| tile_contact.to_string());
+        self.config.no_fix_warning = self.no_fix_warning.clone();
+        self.config.speedometer = self.speedometer.clone();
+        self.config.waypoint_quality_gate = self.waypoint_quality_gate.clone();
+        self.config.waypoint_fix_hold = self.waypoint_fix_hold;
+        self.config.track_rotation = self.track_rotation.clone();
+        self.config.data_log = self.data_log.clone();
+        self.config.distance_units = self.distance_units;
+        self.config.speed_unit = self.speed_unit;
+        self.config.color_palette = self.color_palette;
+        self.config.altitude_source = self.altitude_source;
+        self.config.north_reference = self.north_reference;
+        self.config.exclude_2d_altitude = self.exclude_2d_altitude;
+        self.config.record_satellite_snapshots = self.record_satellite_snapshots;
+        self.config.recording_indicator = self.recording_indicator;
+        self.config.session_autosave = self.session_autosave.clone();
+        self.config.time_zone = self.time_zone;
+        self.config.coordinate_decimals = self.coordinate_decimals;
+        self.config.coordinate_format = self.coordinate_format;
+        self.config.copy_templates = self.copy_templates.clone();
+        self.config.csv_export = self.csv_export.clone();
+        #[cfg(feature = "obd")]
+        {
+            self.config.obd = self.obd.clone();
+        }
+        #[cfg(feature = "status-server")]
+        {
+            self.config.status_endpoint = self.status_endpoint.clone();
+        }
+
+        self.config.normalize();
+        if let Err(e) = self.config.validate() {
+            self.status_message = Some(format!("Error: {}", e));
+            return false;
         }
 
         // Save to storage
@@ -279,7 +1387,29 @@ impl SettingsWindow {
         }
     }
 
+    /// Pick up a marker style changed outside the settings window
+    pub fn set_marker_style(&mut self, style: MarkerStyle) {
+        self.marker_style = style.clone();
+        self.config.marker_style = style;
+    }
+
     pub fn get_config(&self) -> &GpsConfig {
         &self.config
     }
 }
+
+/// Choice for a serial control line: leave it alone, or force it on or off
+fn control_line_combo(ui: &mut egui::Ui, id: &str, state: &mut Option<bool>) {
+    let label = |state: Option<bool>| match state {
+        None => "Leave as is",
+        Some(true) => "On",
+        Some(false) => "Off",
+    };
+    egui::ComboBox::from_id_source(id)
+        .selected_text(label(*state))
+        .show_ui(ui, |ui| {
+            for choice in [None, Some(true), Some(false)] {
+                ui.selectable_value(state, choice, label(choice));
+            }
+        });
+}
@@ -1,51 +1,168 @@
-// src/display/gui/skyplot.rs v1
+// src/display/gui/skyplot.rs v7
 //! Sky plot rendering - polar coordinate satellite visualization
 
-use crate::gps::GpsData;
+use crate::config::{ColorPalette, GpsConfig, HorizonMask, SkyPlotGrid};
+use crate::gps::{data::SatelliteInfo, GpsData, SnrThresholds};
 use eframe::egui;
 
-pub fn render_sky_plot(ui: &mut egui::Ui, data: &GpsData) {
-    ui.strong("🌌 Sky Plot");
-    ui.separator();
+use super::palette::{self, constellation_color, dimmed, unused_color};
+use super::satellites::{is_shown, snr_quality};
 
-    if data.satellites_info.is_empty() {
-        ui.weak("No satellite position data");
-        return;
+/// Clicks this far (pixels) from a satellite's center still select it
+const HIT_RADIUS: f32 = 12.0;
+
+/// SNR (dB-Hz) drawn at the largest dot size when sizing by signal strength
+const FULL_SIZE_SNR: f32 = 50.0;
+
+/// Smallest and largest dot radius when sizing by signal strength
+const SNR_DOT_RADIUS: (f32, f32) = (2.5, 11.0);
+
+/// Bearings tried for the elevation ring labels, preferred first
+const LABEL_BEARINGS: [f32; 5] = [90.0, 60.0, 120.0, 30.0, 150.0];
+
+/// A satellite closer than this (pixels) to a ring label would be covered by it
+const LABEL_CLEARANCE: f32 = 12.0;
+
+/// Azimuth step of the shaded horizon mask outline
+const HORIZON_STEP_DEG: usize = 2;
+
+/// Screen position of a satellite on a plot of `radius` around `center`
+fn satellite_position(center: egui::Pos2, radius: f32, elevation: f32, azimuth: f32) -> egui::Pos2 {
+    let sat_radius = radius * (90.0 - elevation) / 90.0;
+    let azimuth_rad = azimuth.to_radians();
+    center + egui::vec2(azimuth_rad.sin() * sat_radius, -azimuth_rad.cos() * sat_radius)
+}
+
+/// Satellites drawn on the plot with their positions
+fn plotted_satellites<'a>(
+    data: &'a GpsData,
+    hidden_constellations: &'a [String],
+    used_only: bool,
+    center: egui::Pos2,
+    radius: f32,
+) -> impl Iterator<Item = (&'a SatelliteInfo, egui::Pos2)> + 'a {
+    data.satellites_info.iter()
+        .filter(move |sat| is_shown(sat, hidden_constellations, used_only))
+        .filter_map(move |sat| Some((sat, satellite_position(center, radius, sat.elevation?, sat.azimuth?))))
+}
+
+/// The satellite nearest `pos` within `HIT_RADIUS`, so the closest of
+/// overlapping dots wins
+fn satellite_at<'a>(satellites: impl Iterator<Item = (&'a SatelliteInfo, egui::Pos2)>, pos: egui::Pos2) -> Option<&'a SatelliteInfo> {
+    satellites
+        .map(|(sat, sat_pos)| (sat, sat_pos.distance(pos)))
+        .filter(|(_, distance)| *distance <= HIT_RADIUS)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(sat, _)| sat)
+}
+
+/// Which satellites the sky plot shows and how
+pub struct SkyPlot<'a> {
+    pub hidden_constellations: &'a [String],
+    pub used_only: bool,
+    pub snr_thresholds: &'a SnrThresholds,
+    pub elevation_mask: f32,
+    pub palette: ColorPalette,
+    pub grid: &'a SkyPlotGrid,
+    pub horizon: &'a HorizonMask,
+}
+
+impl<'a> SkyPlot<'a> {
+    /// Sky plot with the configured filters and style
+    pub fn from_config(config: &'a GpsConfig, used_only: bool) -> Self {
+        Self {
+            hidden_constellations: &config.hidden_constellations,
+            used_only,
+            snr_thresholds: &config.snr_thresholds,
+            elevation_mask: config.elevation_mask_deg,
+            palette: config.color_palette,
+            grid: &config.sky_plot_grid,
+            horizon: &config.horizon_mask,
+        }
     }
 
-    // Calculate responsive plot size
-    let available_size = ui.available_size();
-    let max_plot_size = available_size.x.min(available_size.y - 60.0);
-    let plot_size = max_plot_size.max(150.0).min(350.0);
-    let radius = plot_size / 2.0 - 20.0;
+    pub fn render(&self, ui: &mut egui::Ui, data: &GpsData) {
+        let &SkyPlot { hidden_constellations, used_only, elevation_mask, palette, grid, horizon, .. } = self;
 
-    // Allocate space for the plot
-    let (rect, _response) = ui.allocate_exact_size(
-        [plot_size, plot_size].into(),
-        egui::Sense::hover()
-    );
+        ui.strong("🌌 Sky Plot");
+        ui.separator();
 
-    if ui.is_rect_visible(rect) {
-        let painter = ui.painter();
+        if data.satellites_info.is_empty() {
+            ui.weak("No satellite position data");
+            return;
+        }
+
+        // Calculate responsive plot size
+        let available_size = ui.available_size();
+        let max_plot_size = available_size.x.min(available_size.y - 60.0);
+        let plot_size = max_plot_size.clamp(150.0, 350.0);
+        let radius = plot_size / 2.0 - 20.0;
+
+        // Allocate space for the plot
+        let (rect, response) = ui.allocate_exact_size(
+            [plot_size, plot_size].into(),
+            egui::Sense::click()
+        );
+
+        // Clicking a satellite shows its details until clicking elsewhere on the plot
+        let selection_id = ui.make_persistent_id("sky_plot_selection");
+        if let Some(pos) = response.interact_pointer_pos().filter(|_| response.clicked()) {
+            let hit = satellite_at(plotted_satellites(data, hidden_constellations, used_only, rect.center(), radius), pos)
+                .map(|sat| (sat.constellation.clone(), sat.prn));
+            ui.data_mut(|d| d.insert_temp(selection_id, hit));
+        }
+        let selection: Option<(String, u8)> = ui.data(|d| d.get_temp(selection_id)).flatten();
+        let selected = selection.and_then(|(constellation, prn)| {
+            plotted_satellites(data, hidden_constellations, used_only, rect.center(), radius)
+                .find(|(sat, _)| sat.prn == prn && sat.constellation == constellation)
+        });
+
+        if ui.is_rect_visible(rect) {
+            let painter = ui.painter();
         
-        draw_background(painter, rect.center(), radius);
-        draw_cardinal_directions(painter, rect.center(), radius);
-        draw_satellites(painter, rect.center(), radius, plot_size, data);
-        draw_elevation_labels(painter, rect.center(), radius, plot_size);
-    }
+            let rings = grid.rings();
+            draw_background(painter, rect.center(), radius, &rings);
+            if grid.azimuth_labels {
+                draw_azimuth_spokes(painter, rect.center(), radius, plot_size);
+            }
+            draw_cardinal_directions(painter, rect.center(), radius);
+            draw_elevation_mask(painter, rect.center(), radius, elevation_mask);
+            draw_horizon_mask(painter, rect.center(), radius, horizon);
+            draw_satellites(painter, rect.center(), radius, plot_size, data, self);
+            let satellites: Vec<egui::Pos2> = plotted_satellites(data, hidden_constellations, used_only, rect.center(), radius)
+                .map(|(_, pos)| pos)
+                .collect();
+            let bearing = label_bearing(&satellites, rect.center(), radius, &rings);
+            draw_elevation_labels(painter, rect.center(), radius, plot_size, &rings, bearing);
+            if let Some((_, pos)) = selected {
+                painter.circle_stroke(pos, HIT_RADIUS, egui::Stroke::new(1.5, egui::Color32::WHITE));
+            }
+        }
 
-    // Legend
-    ui.add_space(5.0);
-    ui.horizontal(|ui| {
-        ui.small("Legend:");
-        ui.colored_label(egui::Color32::from_rgb(0, 150, 255), "● GPS");
-        ui.colored_label(egui::Color32::from_rgb(255, 100, 100), "● GLO");
-        ui.colored_label(egui::Color32::from_rgb(100, 255, 100), "● GAL");
-        ui.colored_label(egui::Color32::from_rgb(255, 255, 100), "● BDS");
-    });
+        if let Some((sat, pos)) = selected {
+            show_satellite_details(ui, selection_id, sat, pos, self);
+        }
+
+        // Legend
+        ui.add_space(5.0);
+        ui.horizontal(|ui| {
+            ui.small("Legend:");
+            for (constellation, label) in palette::LEGEND {
+                ui.colored_label(constellation_color(palette, constellation), format!("● {}", label));
+            }
+            if grid.tint_unused {
+                ui.colored_label(dimmed(constellation_color(palette, "GPS")), "● Unused (dimmed)");
+            } else {
+                ui.colored_label(unused_color(palette), "● Unused");
+            }
+            if horizon.enabled && !horizon.points.is_empty() {
+                ui.colored_label(egui::Color32::GRAY, "○ Behind horizon");
+            }
+        });
+    }
 }
 
-fn draw_background(painter: &egui::Painter, center: egui::Pos2, radius: f32) {
+fn draw_background(painter: &egui::Painter, center: egui::Pos2, radius: f32, rings: &[u32]) {
     // Horizon circle
     painter.circle_stroke(
         center,
@@ -53,19 +170,41 @@ fn draw_background(painter: &egui::Painter, center: egui::Pos2, radius: f32) {
         egui::Stroke::new(2.0, egui::Color32::GRAY)
     );
 
-    // 60° elevation circle
-    painter.circle_stroke(
-        center,
-        radius * 2.0 / 3.0,
-        egui::Stroke::new(1.0, egui::Color32::DARK_GRAY)
-    );
+    // Elevation circles
+    for &elevation in rings {
+        painter.circle_stroke(
+            center,
+            ring_radius(radius, elevation),
+            egui::Stroke::new(1.0, egui::Color32::DARK_GRAY)
+        );
+    }
+}
 
-    // 30° elevation circle
-    painter.circle_stroke(
-        center,
-        radius / 3.0,
-        egui::Stroke::new(1.0, egui::Color32::DARK_GRAY)
-    );
+/// Radius of the circle at `elevation` degrees on a plot of `radius`
+fn ring_radius(radius: f32, elevation: u32) -> f32 {
+    radius * (90.0 - elevation as f32) / 90.0
+}
+
+/// Faint spokes with bearing labels every 30° between the cardinal directions
+fn draw_azimuth_spokes(painter: &egui::Painter, center: egui::Pos2, radius: f32, plot_size: f32) {
+    let label_font_size = (plot_size / 30.0).clamp(7.0, 10.0);
+
+    for azimuth in (30..360).step_by(30).filter(|azimuth| azimuth % 90 != 0) {
+        let angle_rad = (azimuth as f32).to_radians();
+        let direction = egui::vec2(angle_rad.sin(), -angle_rad.cos());
+
+        painter.line_segment(
+            [center, center + direction * radius],
+            egui::Stroke::new(0.5, egui::Color32::from_gray(70))
+        );
+        painter.text(
+            center + direction * (radius + 10.0),
+            egui::Align2::CENTER_CENTER,
+            azimuth.to_string(),
+            egui::FontId::monospace(label_font_size),
+            egui::Color32::GRAY
+        );
+    }
 }
 
 fn draw_cardinal_directions(painter: &egui::Painter, center: egui::Pos2, radius: f32) {
@@ -104,100 +243,304 @@ fn draw_cardinal_directions(painter: &egui::Painter, center: egui::Pos2, radius:
     }
 }
 
-fn draw_satellites(
-    painter: &egui::Painter,
-    center: egui::Pos2,
-    radius: f32,
-    plot_size: f32,
-    data: &GpsData
-) {
-    for sat in &data.satellites_info {
-        if let (Some(elevation), Some(azimuth)) = (sat.elevation, sat.azimuth) {
-            // Convert polar to screen coordinates
-            let elev_normalized = (90.0 - elevation) / 90.0;
-            let sat_radius = radius * elev_normalized;
-            
-            let azimuth_rad = azimuth.to_radians();
-            let sat_pos = center + egui::vec2(
-                azimuth_rad.sin() * sat_radius,
-                -azimuth_rad.cos() * sat_radius
-            );
-
-            // Determine color and size based on constellation and usage
-            let (sat_color, sat_size) = get_satellite_style(sat, plot_size);
-
-            // Draw satellite dot
+/// Shade the band between the horizon and the elevation mask
+fn draw_elevation_mask(painter: &egui::Painter, center: egui::Pos2, radius: f32, elevation_mask: f32) {
+    if elevation_mask <= 0.0 {
+        return;
+    }
+    let mask_radius = radius * (90.0 - elevation_mask.min(90.0)) / 90.0;
+    let band_width = radius - mask_radius;
+    painter.circle_stroke(
+        center,
+        mask_radius + band_width / 2.0,
+        egui::Stroke::new(band_width, egui::Color32::from_black_alpha(90)),
+    );
+    painter.circle_stroke(center, mask_radius, egui::Stroke::new(1.0, egui::Color32::from_rgb(200, 120, 0)));
+}
+
+/// Points along the horizon mask's elevation profile, once around from north
+fn horizon_outline(center: egui::Pos2, radius: f32, horizon: &HorizonMask) -> Vec<egui::Pos2> {
+    (0..360 / HORIZON_STEP_DEG)
+        .map(|step| {
+            let azimuth = (step * HORIZON_STEP_DEG) as f32;
+            satellite_position(center, radius, horizon.min_elevation_at(azimuth), azimuth)
+        })
+        .collect()
+}
+
+/// Shade the sky between the horizon and the obstructions of `horizon`
+fn draw_horizon_mask(painter: &egui::Painter, center: egui::Pos2, radius: f32, horizon: &HorizonMask) {
+    if !horizon.enabled || horizon.points.is_empty() {
+        return;
+    }
+    let outline = horizon_outline(center, radius, horizon);
+    let fill = egui::Color32::from_rgba_unmultiplied(120, 80, 40, 110);
+
+    // A ring of quads from the plot edge in to the outline, as one mesh so
+    // neighbouring sectors don't show seams
+    let mut mesh = egui::Mesh::default();
+    for (step, inner) in outline.iter().enumerate() {
+        let azimuth = (step * HORIZON_STEP_DEG) as f32;
+        mesh.colored_vertex(satellite_position(center, radius, 0.0, azimuth), fill);
+        mesh.colored_vertex(*inner, fill);
+    }
+    let count = outline.len() as u32;
+    for i in 0..count {
+        let (outer, inner) = (2 * i, 2 * i + 1);
+        let (next_outer, next_inner) = (2 * ((i + 1) % count), 2 * ((i + 1) % count) + 1);
+        mesh.add_triangle(outer, inner, next_inner);
+        mesh.add_triangle(outer, next_inner, next_outer);
+    }
+    painter.add(mesh);
+    painter.add(egui::Shape::closed_line(outline, egui::Stroke::new(1.0, egui::Color32::from_rgb(200, 140, 60))));
+}
+
+fn draw_satellites(painter: &egui::Painter, center: egui::Pos2, radius: f32, plot_size: f32, data: &GpsData, plot: &SkyPlot) {
+    let &SkyPlot { hidden_constellations, used_only, snr_thresholds, elevation_mask, palette, grid, horizon } = plot;
+    for (sat, sat_pos) in plotted_satellites(data, hidden_constellations, used_only, center, radius) {
+        // Determine color and size based on constellation and usage
+        let used = sat.used_above_mask(elevation_mask);
+        let (sat_color, mut sat_size) = get_satellite_style(sat, used, plot_size, palette, grid.tint_unused);
+        if grid.size_by_snr {
+            sat_size = snr_dot_radius(sat.snr, plot_size);
+        }
+
+        // Draw satellite dot, hollow when behind the horizon mask
+        if behind_horizon(sat, horizon) {
+            painter.circle_stroke(sat_pos, sat_size, egui::Stroke::new(1.5, sat_color));
+        } else {
             painter.circle_filled(sat_pos, sat_size, sat_color);
+        }
+
+        // Draw PRN label
+        let text_pos = sat_pos + egui::vec2(sat_size + 2.0, 0.0);
+        let font_size = (plot_size / 25.0).clamp(8.0, 12.0);
+        painter.text(
+            text_pos,
+            egui::Align2::LEFT_CENTER,
+            sat.prn.to_string(),
+            egui::FontId::monospace(font_size),
+            egui::Color32::WHITE
+        );
 
-            // Draw PRN label
-            let text_pos = sat_pos + egui::vec2(sat_size + 2.0, 0.0);
-            let font_size = (plot_size / 25.0).max(8.0).min(12.0);
-            painter.text(
-                text_pos,
-                egui::Align2::LEFT_CENTER,
-                sat.prn.to_string(),
-                egui::FontId::monospace(font_size),
-                egui::Color32::WHITE
-            );
-
-            // Draw signal strength ring for used satellites
-            if sat.used {
-                if let Some(snr) = sat.snr {
-                    let ring_color = get_snr_color(snr);
-                    painter.circle_stroke(
-                        sat_pos,
-                        sat_size + 2.0,
-                        egui::Stroke::new(1.5, ring_color)
-                    );
-                }
+        // Draw signal strength ring for used satellites
+        if used {
+            if let Some(snr) = sat.snr {
+                let (_, ring_color) = snr_quality(Some(snr), snr_thresholds, palette);
+                painter.circle_stroke(
+                    sat_pos,
+                    sat_size + 2.0,
+                    egui::Stroke::new(1.5, ring_color)
+                );
             }
         }
     }
 }
 
-fn get_satellite_style(sat: &crate::gps::data::SatelliteInfo, plot_size: f32) -> (egui::Color32, f32) {
-    if sat.used {
-        let color = match sat.constellation.as_str() {
-            "GPS" => egui::Color32::from_rgb(0, 150, 255),
-            "GLONASS" => egui::Color32::from_rgb(255, 100, 100),
-            "GALILEO" => egui::Color32::from_rgb(100, 255, 100),
-            "BEIDOU" => egui::Color32::from_rgb(255, 255, 100),
-            "QZSS" => egui::Color32::from_rgb(255, 150, 0),
-            _ => egui::Color32::WHITE,
+/// Small popup next to the selected satellite with everything known about it
+fn show_satellite_details(ui: &egui::Ui, id: egui::Id, sat: &SatelliteInfo, pos: egui::Pos2, plot: &SkyPlot) {
+    let &SkyPlot { snr_thresholds, elevation_mask, palette, horizon, .. } = plot;
+    let value = |v: Option<f32>, unit: &str| v.map_or("-".to_string(), |v| format!("{:.0}{}", v, unit));
+    egui::Area::new(id.with("popup"))
+        .order(egui::Order::Foreground)
+        .fixed_pos(pos + egui::vec2(HIT_RADIUS, HIT_RADIUS))
+        .show(ui.ctx(), |ui| {
+            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                egui::Grid::new(id.with("details")).num_columns(2).spacing([10.0, 2.0]).show(ui, |ui| {
+                    ui.label("PRN:");
+                    ui.monospace(sat.prn.to_string());
+                    ui.end_row();
+                    ui.label("Constellation:");
+                    ui.colored_label(constellation_color(palette, &sat.constellation), &sat.constellation);
+                    ui.end_row();
+                    ui.label("SNR:");
+                    let (quality, color) = snr_quality(sat.snr, snr_thresholds, palette);
+                    ui.colored_label(color, format!("{} ({})", value(sat.snr, " dB"), quality.description()));
+                    ui.end_row();
+                    ui.label("Elevation:");
+                    ui.monospace(value(sat.elevation, "°"));
+                    ui.end_row();
+                    ui.label("Azimuth:");
+                    ui.monospace(value(sat.azimuth, "°"));
+                    ui.end_row();
+                    ui.label("Used:");
+                    ui.label(match (sat.used, sat.used_above_mask(elevation_mask)) {
+                        (true, true) => "Yes",
+                        (true, false) => "Below mask",
+                        (false, _) => "No",
+                    });
+                    ui.end_row();
+                    if behind_horizon(sat, horizon) {
+                        ui.label("Horizon:");
+                        ui.label("Behind mask");
+                        ui.end_row();
+                    }
+                });
+            });
+        });
+}
+
+fn behind_horizon(sat: &SatelliteInfo, horizon: &HorizonMask) -> bool {
+    matches!((sat.elevation, sat.azimuth), (Some(elevation), Some(azimuth)) if horizon.hides(elevation, azimuth))
+}
+
+fn get_satellite_style(sat: &crate::gps::data::SatelliteInfo, used: bool, plot_size: f32, palette: ColorPalette, tint_unused: bool) -> (egui::Color32, f32) {
+    if used {
+        let size = (plot_size / 30.0).clamp(4.0, 10.0);
+        (constellation_color(palette, &sat.constellation), size)
+    } else {
+        let size = (plot_size / 50.0).clamp(3.0, 6.0);
+        let color = if tint_unused {
+            dimmed(constellation_color(palette, &sat.constellation))
+        } else {
+            unused_color(palette)
         };
-        let size = (plot_size / 30.0).max(4.0).min(10.0);
         (color, size)
-    } else {
-        let size = (plot_size / 50.0).max(3.0).min(6.0);
-        (egui::Color32::GRAY, size)
     }
 }
 
-fn get_snr_color(snr: f32) -> egui::Color32 {
-    match snr {
-        s if s >= 40.0 => egui::Color32::GREEN,
-        s if s >= 35.0 => egui::Color32::YELLOW,
-        s if s >= 25.0 => egui::Color32::from_rgb(255, 165, 0),
-        _ => egui::Color32::RED,
+/// Dot radius growing with SNR up to `FULL_SIZE_SNR`, scaled with the plot
+/// but kept within `SNR_DOT_RADIUS` so weak satellites stay visible
+fn snr_dot_radius(snr: Option<f32>, plot_size: f32) -> f32 {
+    let (min, max) = SNR_DOT_RADIUS;
+    let strength = (snr.unwrap_or(0.0) / FULL_SIZE_SNR).clamp(0.0, 1.0);
+    let largest = (plot_size / 25.0).clamp(min, max);
+    min + (largest - min) * strength
+}
+
+/// Where the label of the ring at `elevation` starts along `bearing`
+fn elevation_label_anchor(center: egui::Pos2, radius: f32, elevation: u32, bearing: f32) -> egui::Pos2 {
+    let bearing_rad = bearing.to_radians();
+    let distance = ring_radius(radius, elevation) + 5.0;
+    center + egui::vec2(bearing_rad.sin() * distance, -bearing_rad.cos() * distance)
+}
+
+/// Bearing for the ring labels that covers the fewest satellites, so
+/// crowded high-elevation sky near the center stays readable. East unless
+/// a satellite is in the way.
+fn label_bearing(satellites: &[egui::Pos2], center: egui::Pos2, radius: f32, rings: &[u32]) -> f32 {
+    let covered = |bearing: f32| {
+        rings.iter()
+            .map(|&elevation| elevation_label_anchor(center, radius, elevation, bearing) + egui::vec2(LABEL_CLEARANCE, 0.0))
+            .filter(|label| satellites.iter().any(|sat| sat.distance(*label) < LABEL_CLEARANCE + HIT_RADIUS / 2.0))
+            .count()
+    };
+    LABEL_BEARINGS.into_iter().min_by_key(|&bearing| covered(bearing)).unwrap_or(90.0)
+}
+
+fn draw_elevation_labels(painter: &egui::Painter, center: egui::Pos2, radius: f32, plot_size: f32, rings: &[u32], bearing: f32) {
+    let label_font_size = (plot_size / 30.0).clamp(7.0, 10.0);
+
+    for &elevation in rings {
+        painter.text(
+            elevation_label_anchor(center, radius, elevation, bearing),
+            egui::Align2::LEFT_CENTER,
+            format!("{}°", elevation),
+            egui::FontId::monospace(label_font_size),
+            egui::Color32::DARK_GRAY
+        );
     }
 }
 
-fn draw_elevation_labels(painter: &egui::Painter, center: egui::Pos2, radius: f32, plot_size: f32) {
-    let label_font_size = (plot_size / 30.0).max(7.0).min(10.0);
-    
-    painter.text(
-        center + egui::vec2(radius / 3.0 + 5.0, 0.0),
-        egui::Align2::LEFT_CENTER,
-        "60°",
-        egui::FontId::monospace(label_font_size),
-        egui::Color32::DARK_GRAY
-    );
-    
-    painter.text(
-        center + egui::vec2(radius * 2.0 / 3.0 + 5.0, 0.0),
-        egui::Align2::LEFT_CENTER,
-        "30°",
-        egui::FontId::monospace(label_font_size),
-        egui::Color32::DARK_GRAY
-    );
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_click_picks_nearest_satellite() {
+        let mut data = GpsData::new();
+        for (prn, elevation, azimuth) in [(5, 45.0, 90.0), (12, 47.0, 92.0), (20, 10.0, 270.0)] {
+            let mut sat = SatelliteInfo::new(prn);
+            sat.elevation = Some(elevation);
+            sat.azimuth = Some(azimuth);
+            data.satellites_info.push(sat);
+        }
+        // Reported but with no position, so not on the plot
+        data.satellites_info.push(SatelliteInfo::new(30));
+
+        let center = egui::pos2(150.0, 150.0);
+        let radius = 90.0;
+        let pick = |pos: egui::Pos2, hidden: &[String]| {
+            satellite_at(plotted_satellites(&data, hidden, false, center, radius), pos).map(|sat| sat.prn)
+        };
+
+        // 45° elevation due east is half way out along the x axis
+        assert_eq!(satellite_position(center, radius, 45.0, 90.0), egui::pos2(195.0, 150.0));
+
+        // Overlapping dots: the nearer one wins
+        let prn5 = satellite_position(center, radius, 45.0, 90.0);
+        let prn12 = satellite_position(center, radius, 47.0, 92.0);
+        assert_eq!(pick(prn5 + egui::vec2(-1.0, 0.0), &[]), Some(5));
+        assert_eq!(pick(prn12 + egui::vec2(0.5, 0.5), &[]), Some(12));
+
+        // Empty sky, and hidden constellations can't be picked
+        assert_eq!(pick(center, &[]), None);
+        let west = satellite_position(center, radius, 10.0, 270.0);
+        assert_eq!(pick(west, &[]), Some(20));
+        assert_eq!(pick(west, &["GPS".to_string()]), None);
+    }
+
+    #[test]
+    fn test_horizon_outline() {
+        use crate::config::HorizonPoint;
+        let center = egui::pos2(150.0, 150.0);
+        let radius = 90.0;
+        let horizon = HorizonMask {
+            enabled: true,
+            points: vec![
+                HorizonPoint { azimuth_deg: 90.0, min_elevation_deg: 45.0 },
+                HorizonPoint { azimuth_deg: 270.0, min_elevation_deg: 0.0 },
+            ],
+        };
+
+        let outline = horizon_outline(center, radius, &horizon);
+        assert_eq!(outline.len(), 360 / HORIZON_STEP_DEG);
+        // Due east the building reaches half way up; due west there's open sky
+        assert_eq!(outline[90 / HORIZON_STEP_DEG], satellite_position(center, radius, 45.0, 90.0));
+        assert_eq!(outline[270 / HORIZON_STEP_DEG], satellite_position(center, radius, 0.0, 270.0));
+
+        let sat = |elevation, azimuth| SatelliteInfo { elevation: Some(elevation), azimuth: Some(azimuth), ..SatelliteInfo::new(5) };
+        assert!(behind_horizon(&sat(30.0, 90.0), &horizon));
+        assert!(!behind_horizon(&sat(30.0, 270.0), &horizon));
+        assert!(!behind_horizon(&SatelliteInfo::new(5), &horizon));
+    }
+
+    #[test]
+    fn test_snr_dot_radius() {
+        let (min, max) = SNR_DOT_RADIUS;
+        let plot_size = 300.0;
+
+        // Stronger is bigger, saturating at full strength
+        assert_eq!(snr_dot_radius(None, plot_size), min);
+        assert_eq!(snr_dot_radius(Some(0.0), plot_size), min);
+        assert!(snr_dot_radius(Some(20.0), plot_size) < snr_dot_radius(Some(40.0), plot_size));
+        assert_eq!(snr_dot_radius(Some(FULL_SIZE_SNR), plot_size), max);
+        assert_eq!(snr_dot_radius(Some(99.0), plot_size), max);
+        assert_eq!(snr_dot_radius(Some(-5.0), plot_size), min);
+
+        // A small plot shrinks the biggest dots but never below the smallest
+        assert!(snr_dot_radius(Some(45.0), 120.0) < snr_dot_radius(Some(45.0), plot_size));
+        assert_eq!(snr_dot_radius(Some(45.0), 10.0), min);
+    }
+
+    #[test]
+    fn test_ring_labels_avoid_satellites() {
+        let center = egui::pos2(150.0, 150.0);
+        let radius = 90.0;
+        let rings = SkyPlotGrid::default().rings();
+        assert_eq!(rings, vec![30, 60]);
+        assert_eq!(SkyPlotGrid { ring_interval_deg: 15, ..SkyPlotGrid::default() }.rings(), vec![15, 30, 45, 60, 75]);
+
+        // Clear sky keeps the labels along the east spoke
+        assert_eq!(label_bearing(&[], center, radius, &rings), 90.0);
+
+        // A satellite sitting on the 60° label pushes them elsewhere
+        let on_label = elevation_label_anchor(center, radius, 60, 90.0) + egui::vec2(LABEL_CLEARANCE, 0.0);
+        let bearing = label_bearing(&[on_label], center, radius, &rings);
+        assert_ne!(bearing, 90.0);
+        for elevation in rings {
+            let label = elevation_label_anchor(center, radius, elevation, bearing);
+            assert!(label.distance(on_label) > LABEL_CLEARANCE);
+        }
+    }
 }
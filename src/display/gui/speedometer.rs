@@ -0,0 +1,131 @@
+// src/display/gui/speedometer.rs v2
+//! Analog speedometer gauge
+
+use crate::config::DisplaySpeedUnit;
+use eframe::egui;
+use std::f32::consts::PI;
+
+/// Points along the gauge arc; enough for a smooth curve at the largest size
+const ARC_SEGMENTS: usize = 64;
+
+/// Scale fractions where the arc turns from green to yellow and from yellow to red
+const CAUTION_FRACTION: f64 = 0.6;
+const DANGER_FRACTION: f64 = 0.85;
+
+/// Semicircular gauge for `speed_kmh`, labelled in `unit` and scaled from
+/// zero to `max_speed` (in the same unit). Speeds past the top pin the needle.
+pub fn render_speedometer(ui: &mut egui::Ui, speed_kmh: Option<f64>, max_speed: f64, unit: DisplaySpeedUnit) {
+    ui.add_space(10.0);
+    ui.strong("🏁 Speedometer");
+    ui.separator();
+
+    let max_speed = max_speed.max(1.0);
+    let width = ui.available_width().clamp(160.0, 320.0);
+    let (rect, _response) = ui.allocate_exact_size(egui::vec2(width, width * 0.62), egui::Sense::hover());
+    if !ui.is_rect_visible(rect) {
+        return;
+    }
+
+    let painter = ui.painter();
+    let radius = width / 2.0 - 12.0;
+    let center = egui::pos2(rect.center().x, rect.top() + radius + 10.0);
+    let speed = speed_kmh.map(|kmh| unit.from_kmh(kmh));
+
+    draw_arc(painter, center, radius, 0.0, 1.0, egui::Stroke::new(8.0, egui::Color32::from_gray(60)));
+    draw_arc(painter, center, radius, 0.0, CAUTION_FRACTION, egui::Stroke::new(8.0, egui::Color32::from_rgb(0, 160, 0)));
+    draw_arc(painter, center, radius, CAUTION_FRACTION, DANGER_FRACTION, egui::Stroke::new(8.0, egui::Color32::from_rgb(200, 160, 0)));
+    draw_arc(painter, center, radius, DANGER_FRACTION, 1.0, egui::Stroke::new(8.0, egui::Color32::from_rgb(200, 60, 40)));
+    draw_ticks(painter, center, radius - 6.0, max_speed, ui.visuals().text_color());
+
+    // Needle, greyed and resting at zero without a speed
+    let fraction = speed.map_or(0.0, |s| (s / max_speed).clamp(0.0, 1.0));
+    let needle_color = if speed.is_some() { egui::Color32::from_rgb(230, 50, 50) } else { egui::Color32::GRAY };
+    painter.line_segment(
+        [center, gauge_point(center, radius * 0.8, fraction)],
+        egui::Stroke::new(3.0, needle_color),
+    );
+    painter.circle_filled(center, 6.0, needle_color);
+
+    let readout = speed.map_or("--".to_string(), |s| format!("{:.0}", s));
+    painter.text(
+        center - egui::vec2(0.0, radius * 0.3),
+        egui::Align2::CENTER_CENTER,
+        readout,
+        egui::FontId::monospace(radius * 0.28),
+        ui.visuals().strong_text_color(),
+    );
+    painter.text(
+        center + egui::vec2(0.0, 10.0),
+        egui::Align2::CENTER_TOP,
+        unit.label(),
+        egui::FontId::proportional(12.0),
+        egui::Color32::GRAY,
+    );
+}
+
+/// Screen position `radius` from `center` at `fraction` of the scale.
+/// Zero is at the left, the top of the scale at the right.
+fn gauge_point(center: egui::Pos2, radius: f32, fraction: f64) -> egui::Pos2 {
+    let angle = PI - fraction as f32 * PI;
+    center + egui::vec2(angle.cos(), -angle.sin()) * radius
+}
+
+fn draw_arc(painter: &egui::Painter, center: egui::Pos2, radius: f32, from: f64, to: f64, stroke: egui::Stroke) {
+    let steps = ((to - from) * ARC_SEGMENTS as f64).ceil().max(1.0) as usize;
+    let points = (0..=steps)
+        .map(|i| gauge_point(center, radius, from + (to - from) * i as f64 / steps as f64))
+        .collect();
+    painter.add(egui::Shape::line(points, stroke));
+}
+
+/// Major ticks with labels at round values, and a minor tick halfway between each
+fn draw_ticks(painter: &egui::Painter, center: egui::Pos2, radius: f32, max_speed: f64, color: egui::Color32) {
+    let step = tick_step(max_speed);
+    let minor = step / 2.0;
+    let mut value = 0.0;
+    while value <= max_speed + 1e-9 {
+        let fraction = value / max_speed;
+        let major = (value / step).fract().abs() < 1e-6;
+        let length = if major { 12.0 } else { 6.0 };
+        painter.line_segment(
+            [gauge_point(center, radius - length, fraction), gauge_point(center, radius, fraction)],
+            egui::Stroke::new(if major { 2.0 } else { 1.0 }, color),
+        );
+        if major {
+            painter.text(
+                gauge_point(center, radius - 24.0, fraction),
+                egui::Align2::CENTER_CENTER,
+                format!("{:.0}", value),
+                egui::FontId::proportional(11.0),
+                color,
+            );
+        }
+        value += minor;
+    }
+}
+
+/// Round spacing (1, 2 or 5 times a power of ten) giving at most ten labelled ticks
+fn tick_step(max_speed: f64) -> f64 {
+    let rough = max_speed / 10.0;
+    let magnitude = 10f64.powf(rough.log10().floor());
+    [1.0, 2.0, 5.0, 10.0]
+        .into_iter()
+        .map(|m| m * magnitude)
+        .find(|&step| step >= rough)
+        .unwrap_or(10.0 * magnitude)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tick_step() {
+        assert_eq!(tick_step(160.0), 20.0);
+        assert_eq!(tick_step(200.0), 20.0);
+        assert_eq!(tick_step(120.0), 20.0);
+        assert_eq!(tick_step(100.0), 10.0);
+        assert_eq!(tick_step(40.0), 5.0);
+        assert!(160.0 / tick_step(160.0) <= 10.0);
+    }
+}
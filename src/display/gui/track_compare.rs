@@ -0,0 +1,109 @@
+// src/display/gui/track_compare.rs v1
+//! Side-by-side comparison of two saved tracks, e.g. two drives of the same route
+
+use crate::config::{DisplaySpeedUnit, DistanceUnits};
+use crate::waypoint::{format_duration, Track};
+use eframe::egui;
+
+/// Line and legend colors of the first and second compared track
+pub const COMPARE_COLORS: [egui::Color32; 2] = [
+    egui::Color32::from_rgb(30, 110, 255),
+    egui::Color32::from_rgb(255, 120, 0),
+];
+
+/// One statistic for both tracks, and the second track's value relative to the first's
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComparisonRow {
+    pub label: &'static str,
+    pub values: [String; 2],
+    pub difference: String,
+}
+
+/// Distance, duration, average speed and elevation gain of both tracks, from
+/// the same `Track` statistics as the summary report
+pub fn comparison_rows(tracks: [&Track; 2], units: DistanceUnits, speed_unit: DisplaySpeedUnit) -> Vec<ComparisonRow> {
+    let row = |label, values: [Option<f64>; 2], format: &dyn Fn(f64) -> String| ComparisonRow {
+        label,
+        values: values.map(|value| value.map_or_else(|| "-".to_string(), format)),
+        difference: match values {
+            [Some(first), Some(second)] => {
+                let sign = if second < first { "−" } else { "+" };
+                format!("{}{}", sign, format((second - first).abs()))
+            }
+            _ => "-".to_string(),
+        },
+    };
+    let duration_secs = |track: &Track| track.duration().map(|d| d.num_seconds() as f64);
+
+    vec![
+        row("Distance", tracks.map(|t| Some(t.total_distance())), &|m| units.format_distance(m)),
+        row("Duration", tracks.map(duration_secs), &|secs| format_duration(chrono::Duration::seconds(secs as i64))),
+        row("Average speed", tracks.map(Track::average_speed), &|kmh| speed_unit.format_speed(kmh, 1)),
+        row("Elevation gain", tracks.map(|t| Some(t.elevation_gain_loss().0)), &|m| units.format_altitude(m, 0)),
+    ]
+}
+
+/// Table of `comparison_rows` headed by the track names in their map colors
+pub fn render_comparison_table(ui: &mut egui::Ui, tracks: [&Track; 2], units: DistanceUnits, speed_unit: DisplaySpeedUnit) {
+    egui::Grid::new("track_comparison")
+        .num_columns(4)
+        .spacing([15.0, 4.0])
+        .striped(true)
+        .show(ui, |ui| {
+            ui.label("");
+            for (track, color) in tracks.iter().zip(COMPARE_COLORS) {
+                ui.label(egui::RichText::new(format!("━ {}", track.name)).strong().color(color));
+            }
+            ui.strong("Difference");
+            ui.end_row();
+
+            for row in comparison_rows(tracks, units, speed_unit) {
+                ui.label(row.label);
+                for value in &row.values {
+                    ui.monospace(value);
+                }
+                ui.monospace(&row.difference);
+                ui.end_row();
+            }
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::waypoint::TrackPoint;
+    use chrono::{DateTime, Utc};
+
+    fn drive(name: &str, minutes: i64, end_altitude: f64) -> Track {
+        let t0 = DateTime::parse_from_rfc3339("2024-05-01T08:00:00Z").unwrap().with_timezone(&Utc);
+        let mut track = Track::new(name.to_string());
+        track.add_point(TrackPoint::new(42.0, -71.0, Some(100.0), t0));
+        track.add_point(TrackPoint::new(42.09, -71.0, Some(end_altitude), t0 + chrono::Duration::minutes(minutes)));
+        track
+    }
+
+    #[test]
+    fn test_comparison_rows() {
+        let (monday, tuesday) = (drive("Monday", 10, 150.0), drive("Tuesday", 15, 120.0));
+        let rows = comparison_rows([&monday, &tuesday], DistanceUnits::Metric, DisplaySpeedUnit::KilometersPerHour);
+
+        let labels: Vec<_> = rows.iter().map(|row| row.label).collect();
+        assert_eq!(labels, ["Distance", "Duration", "Average speed", "Elevation gain"]);
+
+        assert_eq!(rows[0].values, ["10.01 km".to_string(), "10.01 km".to_string()]);
+        assert_eq!(rows[0].difference, "+0 m");
+        assert_eq!(rows[1].values, ["10m 0s".to_string(), "15m 0s".to_string()]);
+        assert_eq!(rows[1].difference, "+5m 0s");
+        assert_eq!(rows[2].values, ["60.0 km/h".to_string(), "40.0 km/h".to_string()]);
+        assert_eq!(rows[2].difference, "−20.0 km/h");
+        assert_eq!(rows[3].values, ["50 m".to_string(), "20 m".to_string()]);
+        assert_eq!(rows[3].difference, "−30 m");
+
+        // A track without times has no duration or speed to compare
+        let mut untimed = drive("Sketch", 0, 100.0);
+        untimed.segments[0].points.truncate(1);
+        let rows = comparison_rows([&monday, &untimed], DistanceUnits::Metric, DisplaySpeedUnit::KilometersPerHour);
+        assert_eq!(rows[2].values[1], "-");
+        assert_eq!(rows[2].difference, "-");
+    }
+}
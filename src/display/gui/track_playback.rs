@@ -0,0 +1,146 @@
+// src/display/gui/track_playback.rs v1
+//! Playback of a recorded track on the map with a timeline scrubber
+
+use crate::{config::DisplayTimeZone, waypoint::Track};
+use eframe::egui;
+use std::time::Instant;
+
+const SPEEDS: [f64; 6] = [1.0, 2.0, 5.0, 10.0, 30.0, 60.0];
+
+pub struct TrackPlayback {
+    track_index: Option<usize>,
+    offset_secs: f64,   // Playback position, seconds from track start
+    playing: bool,
+    speed: f64,         // Playback speed multiplier
+    last_tick: Option<Instant>,
+}
+
+impl TrackPlayback {
+    pub fn new() -> Self {
+        Self {
+            track_index: None,
+            offset_secs: 0.0,
+            playing: false,
+            speed: 10.0,
+            last_tick: None,
+        }
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    /// Get the selected track, if it still exists
+    pub fn selected_track<'a>(&self, tracks: &'a [Track]) -> Option<&'a Track> {
+        tracks.get(self.track_index?)
+    }
+
+    /// Advance the playback position by the wall-clock time since the last tick
+    pub fn tick(&mut self, tracks: &[Track]) {
+        let now = Instant::now();
+        let elapsed = self.last_tick.map_or(0.0, |t| now.duration_since(t).as_secs_f64());
+        self.last_tick = Some(now);
+
+        if !self.playing {
+            return;
+        }
+
+        let length = match self.selected_track(tracks) {
+            Some(track) => Self::track_length_secs(track),
+            None => {
+                self.playing = false;
+                return;
+            }
+        };
+
+        self.offset_secs += elapsed * self.speed;
+        if self.offset_secs >= length {
+            self.offset_secs = length;
+            self.playing = false;
+        }
+    }
+
+    /// Current interpolated position (lat, lon) of the playback marker
+    pub fn position(&self, tracks: &[Track]) -> Option<(f64, f64)> {
+        let track = self.selected_track(tracks)?;
+        let start = track.start_time()?;
+        let time = start + chrono::Duration::milliseconds((self.offset_secs * 1000.0) as i64);
+        track.position_at(time)
+    }
+
+    pub fn render_controls(&mut self, ui: &mut egui::Ui, tracks: &[Track], time_zone: DisplayTimeZone) {
+        // Drop the selection if the track list was cleared
+        if self.selected_track(tracks).is_none() {
+            self.track_index = None;
+            self.playing = false;
+        }
+
+        ui.horizontal(|ui| {
+            ui.label("Playback:");
+
+            let selected_text = self.selected_track(tracks)
+                .map_or("None".to_string(), |t| t.name.clone());
+            egui::ComboBox::from_id_source("playback_track")
+                .selected_text(selected_text)
+                .show_ui(ui, |ui| {
+                    if ui.selectable_label(self.track_index.is_none(), "None").clicked() {
+                        self.track_index = None;
+                        self.playing = false;
+                    }
+                    for (i, track) in tracks.iter().enumerate() {
+                        if ui.selectable_label(self.track_index == Some(i), &track.name).clicked() {
+                            self.track_index = Some(i);
+                            self.offset_secs = 0.0;
+                            self.playing = false;
+                        }
+                    }
+                });
+
+            let track = match self.selected_track(tracks) {
+                Some(track) => track,
+                None => return,
+            };
+            let length = Self::track_length_secs(track);
+
+            let play_label = if self.playing { "⏸" } else { "▶" };
+            if ui.button(play_label).clicked() {
+                if !self.playing && self.offset_secs >= length {
+                    self.offset_secs = 0.0; // Restart from the beginning
+                }
+                self.playing = !self.playing;
+            }
+
+            egui::ComboBox::from_id_source("playback_speed")
+                .width(60.0)
+                .selected_text(format!("{}x", self.speed))
+                .show_ui(ui, |ui| {
+                    for speed in SPEEDS {
+                        ui.selectable_value(&mut self.speed, speed, format!("{}x", speed));
+                    }
+                });
+
+            ui.add(
+                egui::Slider::new(&mut self.offset_secs, 0.0..=length.max(1.0))
+                    .show_value(false)
+            );
+
+            if let Some(start) = track.start_time() {
+                let time = start + chrono::Duration::seconds(self.offset_secs as i64);
+                ui.monospace(time_zone.format(&time, "%H:%M:%S"));
+            }
+        });
+    }
+
+    fn track_length_secs(track: &Track) -> f64 {
+        match (track.start_time(), track.end_time()) {
+            (Some(start), Some(end)) => (end - start).num_milliseconds() as f64 / 1000.0,
+            _ => 0.0,
+        }
+    }
+}
+
+impl Default for TrackPlayback {
+    fn default() -> Self {
+        Self::new()
+    }
+}
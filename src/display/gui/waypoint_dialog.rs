@@ -1,26 +1,175 @@
-// src/display/gui/waypoint_dialog.rs v6
+// src/display/gui/waypoint_dialog.rs v23
 //! Waypoint recording and track recording dialog UI
 
-use crate::{gps::GpsData, waypoint::{Waypoint, WaypointExporter, WaypointFormat}};
-use super::track_recorder::TrackRecorder;
+use crate::{config::{CsvExport, DisplaySpeedUnit, DisplayTimeZone, DistanceUnits, ImportThinning, RecentExport, TrackRotation, WaypointFixHold, WaypointQualityGate}, gps::{CoordinateFormat, GpsData, DEFAULT_COORDINATE_DECIMALS}, track_recorder::{RecordingCheckpoint, RecordingMode, RecordingStatus, TrackRecorder}, waypoint::{ElevationCorrector, ExportPreview, MapMatcher, NoElevationCorrection, Route, Session, Track, Waypoint, WaypointExporter, WaypointFormat}};
+use super::track_compare;
 use eframe::egui;
-use std::path::PathBuf;
+use std::{borrow::Cow, path::{Path, PathBuf}, sync::{atomic::{AtomicBool, Ordering}, mpsc, Arc}, time::{Duration, Instant}};
+
+/// Lines of a text export shown by "Preview"
+const PREVIEW_LINES: usize = 50;
+
+/// Time window of a saved track being exported on its own
+struct TrackSlice {
+    index: usize,
+    start_secs: f64,  // Seconds from the track start
+    end_secs: f64,
+}
+
+/// Name, description and map color of a saved track being edited
+struct TrackEdit {
+    index: usize,
+    name: String,
+    description: String,
+    use_color: bool,
+    color: [u8; 3],
+}
+
+/// A saved track being matched to roads in the background
+struct MapMatching {
+    index: usize,
+    name: String,  // To tell whether the track at `index` is still the one sent
+    result: mpsc::Receiver<Track>,
+}
+
+enum CheckpointAction {
+    Resume,
+    Save,
+    Discard,
+}
+
+/// Order of the saved waypoints list
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum WaypointSort {
+    #[default]
+    Time,
+    Name,
+    Distance,
+}
+
+impl WaypointSort {
+    const ALL: [WaypointSort; 3] = [WaypointSort::Time, WaypointSort::Name, WaypointSort::Distance];
+
+    fn name(self) -> &'static str {
+        match self {
+            WaypointSort::Time => "Time",
+            WaypointSort::Name => "Name",
+            WaypointSort::Distance => "Distance",
+        }
+    }
+}
+
+/// Waypoints whose name contains `query` (case-insensitive), each paired with
+/// its distance from `position` when there is one, in the requested order
+fn filter_waypoints<'a>(
+    waypoints: &'a [Waypoint],
+    query: &str,
+    sort: WaypointSort,
+    position: Option<(f64, f64)>,
+) -> Vec<(&'a Waypoint, Option<f64>)> {
+    let query = query.trim().to_lowercase();
+    let mut shown: Vec<_> = waypoints
+        .iter()
+        .filter(|wp| wp.name.to_lowercase().contains(&query))
+        .map(|wp| (wp, position.map(|(lat, lon)| wp.distance_from(lat, lon))))
+        .collect();
+
+    match sort {
+        WaypointSort::Time => shown.sort_by_key(|(wp, _)| wp.timestamp),
+        WaypointSort::Name => shown.sort_by_cached_key(|(wp, _)| wp.name.to_lowercase()),
+        // Without a fix every distance is unknown and the saved order is kept
+        WaypointSort::Distance => shown.sort_by(|(_, a), (_, b)| {
+            a.unwrap_or(f64::INFINITY).total_cmp(&b.unwrap_or(f64::INFINITY))
+        }),
+    }
+    shown
+}
+
+/// Byte count in B, KB or MB for the export preview
+fn format_size(bytes: usize) -> String {
+    match bytes {
+        0..=1023 => format!("{} B", bytes),
+        1024..=1_048_575 => format!("{:.1} KB", bytes as f64 / 1024.0),
+        _ => format!("{:.1} MB", bytes as f64 / 1_048_576.0),
+    }
+}
+
+/// Most recent position fix and when it arrived, kept through dropouts
+#[derive(Default)]
+struct LastGoodFix {
+    fix: Option<(GpsData, Instant)>,
+}
+
+impl LastGoodFix {
+    /// Keep `data` if it's a new fix; repeats of the held fix don't reset its age
+    fn update(&mut self, data: &GpsData, now: Instant) {
+        if !data.has_fix() {
+            return;
+        }
+        let changed = self.fix.as_ref().is_none_or(|(held, _)| {
+            (held.timestamp, held.latitude, held.longitude) != (data.timestamp, data.latitude, data.longitude)
+        });
+        if changed {
+            self.fix = Some((data.clone(), now));
+        }
+    }
+
+    /// The held fix and how long ago it arrived
+    fn get(&self, now: Instant) -> Option<(&GpsData, Duration)> {
+        self.fix.as_ref().map(|(data, received)| (data, now.saturating_duration_since(*received)))
+    }
+}
 
 pub struct WaypointDialog {
     pub open: bool,
     waypoint_name: String,
     waypoint_description: String,
+    use_waypoint_color: bool,
+    waypoint_color: [u8; 3],
     pub exporter: WaypointExporter,  // Made public so MapWindow can access it
+    waypoint_filter: String,
+    waypoint_sort: WaypointSort,
+    distance_units: DistanceUnits,
+    speed_unit: DisplaySpeedUnit,
     selected_format: WaypointFormat,
     export_path: String,
+    include_live_track: bool,  // Exports also get a snapshot of the recording in progress
+    recent_exports: Vec<RecentExport>,
+    recent_exports_changed: bool,  // Not yet picked up by the app to save
+    import_thinning: ImportThinning,
+    import_thinning_changed: bool,  // Not yet picked up by the app to save
     status_message: Option<String>,
 
     // Track recording
     track_recorder: TrackRecorder,
+    checkpoint_path: Option<PathBuf>,  // Where the recording is checkpointed, if anywhere
+    pending_checkpoint: Option<RecordingCheckpoint>,  // Unfinished recording found at startup
     track_name_input: String,
     show_track_settings: bool,
     min_distance_str: String,
     min_time_str: String,
+    max_points_str: String,
+    idle_timeout_str: String,
+    max_hdop_str: String,
+    time_zone: DisplayTimeZone,
+    coordinate_decimals: usize,
+    coordinate_format: CoordinateFormat,
+    quality_gate: WaypointQualityGate,
+    fix_hold: WaypointFixHold,
+    last_good_fix: LastGoodFix,
+    elevation_corrector: Box<dyn ElevationCorrector>,
+    map_matcher: Option<(Arc<dyn MapMatcher>, tokio::runtime::Handle)>,  // "Snap to road" is offered once one is installed
+    map_matching: Option<MapMatching>,
+    smoothing_window: usize,
+    track_slice: Option<TrackSlice>,
+    compare_selection: Vec<usize>,  // Saved tracks ticked for comparison, at most two, oldest first
+    track_edit: Option<TrackEdit>,
+    export_preview: Option<(WaypointFormat, ExportPreview)>,  // Hidden once another format is picked
+    route_name: String,
+    route_order: Vec<usize>,  // Indices of saved waypoints in the route being built
+    reference_request: Option<String>,  // Waypoint marked to measure distance from, for the app to pick up
+    navigate_request: Option<Waypoint>,  // Waypoint to navigate to on the map, for the app to pick up
+    saving_session: Arc<AtomicBool>,  // An auto-save is still being written
 }
 
 impl WaypointDialog {
@@ -30,23 +179,314 @@ impl WaypointDialog {
             open: false,
             waypoint_name: String::new(),
             waypoint_description: String::new(),
+            use_waypoint_color: false,
+            waypoint_color: [255, 0, 0],
             exporter: WaypointExporter::new(),
+            waypoint_filter: String::new(),
+            waypoint_sort: WaypointSort::default(),
+            distance_units: DistanceUnits::default(),
+            speed_unit: DistanceUnits::default().speed_unit(),
             selected_format: WaypointFormat::GPX,
             export_path: String::new(),
+            include_live_track: false,
+            recent_exports: Vec::new(),
+            recent_exports_changed: false,
+            import_thinning: ImportThinning::default(),
+            import_thinning_changed: false,
             status_message: None,
             track_name_input: String::new(),
             show_track_settings: false,
             min_distance_str: track_recorder.get_min_distance().to_string(),
             min_time_str: track_recorder.get_min_time_seconds().to_string(),
+            max_points_str: track_recorder.get_max_points().map_or(String::new(), |n| n.to_string()),
+            idle_timeout_str: track_recorder.get_idle_timeout().map_or(String::new(), |t| t.as_secs().to_string()),
+            max_hdop_str: track_recorder.get_fix_filter().max_hdop.map_or(String::new(), |h| h.to_string()),
             track_recorder,
+            checkpoint_path: None,
+            pending_checkpoint: None,
+            time_zone: DisplayTimeZone::default(),
+            coordinate_decimals: DEFAULT_COORDINATE_DECIMALS,
+            coordinate_format: CoordinateFormat::default(),
+            quality_gate: WaypointQualityGate::default(),
+            fix_hold: WaypointFixHold::default(),
+            last_good_fix: LastGoodFix::default(),
+            elevation_corrector: Box::new(NoElevationCorrection),
+            map_matcher: None,
+            map_matching: None,
+            smoothing_window: 5,
+            track_slice: None,
+            compare_selection: Vec::new(),
+            track_edit: None,
+            export_preview: None,
+            route_name: String::new(),
+            route_order: Vec::new(),
+            reference_request: None,
+            navigate_request: None,
+            saving_session: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Merge OBD-II readings into recorded track points
+    #[cfg(feature = "obd")]
+    pub fn set_obd_source(&mut self, obd_data: std::sync::Arc<std::sync::RwLock<crate::obd::ObdData>>) {
+        self.track_recorder.set_obd_source(obd_data);
+    }
+
+    /// Time zone for displayed timestamps (e.g. after settings are saved)
+    pub fn set_time_zone(&mut self, time_zone: DisplayTimeZone) {
+        self.time_zone = time_zone;
+    }
+
+    /// Precision for displayed coordinates and CSV/GeoJSON export
+    pub fn set_coordinate_decimals(&mut self, decimals: usize) {
+        self.coordinate_decimals = decimals;
+        self.exporter.set_coordinate_decimals(Some(decimals));
+    }
+
+    /// Show positions as lat/lon, UTM or MGRS; exports are unaffected
+    pub fn set_coordinate_format(&mut self, format: CoordinateFormat) {
+        self.coordinate_format = format;
+    }
+
+    /// Timestamp, unit and separator choices for CSV exports
+    pub fn set_csv_export(&mut self, options: CsvExport) {
+        self.exporter.set_csv_export(options);
+    }
+
+    /// Units for distances in the waypoint list (e.g. after settings are saved)
+    pub fn set_distance_units(&mut self, units: DistanceUnits) {
+        self.distance_units = units;
+    }
+
+    /// Unit for the recording's average speed
+    pub fn set_speed_unit(&mut self, unit: DisplaySpeedUnit) {
+        self.speed_unit = unit;
+    }
+
+    /// Fix requirements for saving waypoints (e.g. after settings are saved)
+    pub fn set_quality_gate(&mut self, gate: WaypointQualityGate) {
+        self.quality_gate = gate;
+    }
+
+    /// Whether the position readout holds the last good fix through dropouts
+    pub fn set_fix_hold(&mut self, hold: WaypointFixHold) {
+        self.fix_hold = hold;
+    }
+
+    /// Whether track points recorded during a 2D fix keep their altitude
+    pub fn set_exclude_2d_altitude(&mut self, exclude: bool) {
+        self.track_recorder.set_exclude_2d_altitude(exclude);
+    }
+
+    /// The track being recorded, which isn't in the exporter until it stops
+    pub fn active_track(&self) -> Option<&Track> {
+        self.track_recorder.current_track()
+    }
+
+    /// The two saved tracks selected for comparison, if two are
+    pub fn compared_tracks(&self) -> Option<[usize; 2]> {
+        match self.compare_selection[..] {
+            [first, second] if first.max(second) < self.exporter.track_count() => Some([first, second]),
+            _ => None,
+        }
+    }
+
+    pub fn set_satellite_snapshots(&mut self, enabled: bool) {
+        self.track_recorder.set_satellite_snapshots(enabled);
+    }
+
+    /// Dated GPX export of the recording in progress (e.g. after settings are saved)
+    pub fn set_track_rotation(&mut self, rotation: &TrackRotation) {
+        self.track_recorder.set_rotation(rotation);
+    }
+
+    /// Source of corrected elevations for newly saved waypoints
+    pub fn set_elevation_corrector(&mut self, corrector: Box<dyn ElevationCorrector>) {
+        self.elevation_corrector = corrector;
+    }
+
+    /// Map matching offered for saved tracks before export, run on `runtime`
+    /// as it usually waits on a remote service
+    pub fn set_map_matcher(&mut self, matcher: Box<dyn MapMatcher>, runtime: tokio::runtime::Handle) {
+        self.map_matcher = Some((Arc::from(matcher), runtime));
+    }
+
+    /// Match the saved track at `index` to roads off the UI thread
+    fn start_map_matching(&mut self, index: usize, ctx: &egui::Context) {
+        let Some((matcher, runtime)) = &self.map_matcher else {
+            return;
+        };
+        let track = self.exporter.get_tracks()[index].clone();
+        let name = track.name.clone();
+        let (tx, rx) = mpsc::channel();
+        let matcher = Arc::clone(matcher);
+        let ctx = ctx.clone();
+        runtime.spawn_blocking(move || {
+            let _ = tx.send(track.map_matched(matcher.as_ref()));
+            ctx.request_repaint();
+        });
+        self.status_message = Some(format!("Matching '{}' to roads...", name));
+        self.map_matching = Some(MapMatching { index, name, result: rx });
+    }
+
+    /// Put a finished map match in place of the track it was made from
+    fn poll_map_matching(&mut self) {
+        let Some(matching) = &self.map_matching else {
+            return;
+        };
+        match matching.result.try_recv() {
+            Ok(track) => {
+                let unchanged = self.exporter.get_tracks().get(matching.index).is_some_and(|t| t.name == matching.name);
+                if unchanged {
+                    self.status_message = Some(format!("Matched '{}' to roads", track.name));
+                    self.exporter.replace_track(matching.index, track);
+                } else {
+                    self.status_message = Some(format!("'{}' changed while matching; result discarded", matching.name));
+                }
+            }
+            Err(mpsc::TryRecvError::Empty) => return,
+            Err(mpsc::TryRecvError::Disconnected) => {
+                self.status_message = Some(format!("Matching '{}' to roads failed", matching.name));
+            }
+        }
+        self.map_matching = None;
+    }
+
+    /// Files recently exported to, offered for exporting to again, as kept in the config
+    pub fn set_recent_exports(&mut self, recent: Vec<RecentExport>) {
+        self.recent_exports = recent;
+    }
+
+    /// Recent exports list if it changed since the last call, for saving to the config
+    pub fn take_recent_exports_change(&mut self) -> Option<Vec<RecentExport>> {
+        std::mem::take(&mut self.recent_exports_changed).then(|| self.recent_exports.clone())
+    }
+
+    pub fn set_import_thinning(&mut self, thinning: ImportThinning) {
+        self.import_thinning = thinning;
+    }
+
+    /// Import thinning options if the user changed them since the last call, for saving to the config
+    pub fn take_import_thinning_change(&mut self) -> Option<ImportThinning> {
+        std::mem::take(&mut self.import_thinning_changed).then(|| self.import_thinning.clone())
+    }
+
+    /// Name of a waypoint just marked as the reference for "distance since"
+    pub fn take_reference_request(&mut self) -> Option<String> {
+        self.reference_request.take()
+    }
+
+    /// Waypoint just chosen to navigate to on the map
+    pub fn take_navigate_request(&mut self) -> Option<Waypoint> {
+        self.navigate_request.take()
+    }
+
+    /// Whether a track is being recorded, which needs regular `update_from_gps` calls
+    pub fn is_recording(&self) -> bool {
+        self.track_recorder.is_recording()
+    }
+
+    /// When the recording's idle timeout runs out, see `TrackRecorder::idle_deadline`
+    pub fn idle_deadline(&self) -> Option<Instant> {
+        self.track_recorder.idle_deadline()
+    }
+
+    /// Recording, paused or stopped, for the recording indicator
+    pub fn recording_status(&self) -> RecordingStatus {
+        self.track_recorder.status(chrono::Utc::now())
+    }
+
+    /// Checkpoint recordings to `path`, first picking up an unfinished one
+    /// left there by an earlier run to offer resuming or saving it
+    pub fn set_checkpoint_path(&mut self, path: PathBuf) {
+        self.pending_checkpoint = RecordingCheckpoint::load(&path).unwrap_or_else(|e| {
+            eprintln!("Ignoring recording checkpoint: {}", e);
+            None
+        });
+        self.show_track_settings |= self.pending_checkpoint.is_some();
+        self.track_recorder.set_checkpoint_path(Some(path.clone()));
+        self.checkpoint_path = Some(path);
+    }
+
+    /// Whether an interrupted recording is waiting to be resumed or saved
+    pub fn has_pending_checkpoint(&self) -> bool {
+        self.pending_checkpoint.is_some()
+    }
+
+    /// Replace the waypoints, tracks and routes with those saved at `path` by
+    /// an earlier run, if any. Selections made by index into the replaced
+    /// lists are dropped.
+    pub fn restore_session(&mut self, path: &Path) {
+        match Session::load(path) {
+            Ok(Some(session)) => {
+                self.status_message = Some(format!(
+                    "Restored {} waypoints and {} tracks from the last session",
+                    session.waypoints.len(),
+                    session.tracks.len()
+                ));
+                self.exporter.restore_session(session);
+                self.compare_selection.clear();
+                self.track_edit = None;
+                self.track_slice = None;
+                self.map_matching = None;
+                self.route_order.clear();
+            }
+            Ok(None) => {}
+            Err(e) => eprintln!("Ignoring saved session: {}", e),
+        }
+    }
+
+    /// Save the waypoints, tracks and routes to `path` on a background thread,
+    /// and checkpoint a recording in progress. Skipped while the previous
+    /// save is still being written.
+    pub fn autosave(&mut self, path: PathBuf) {
+        self.track_recorder.save_checkpoint();
+        if self.saving_session.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        let session = self.exporter.session();
+        let saving = Arc::clone(&self.saving_session);
+        std::thread::spawn(move || {
+            if let Err(e) = session.save(&path) {
+                eprintln!("Session auto-save failed: {}", e);
+            }
+            saving.store(false, Ordering::Release);
+        });
+    }
+
+    /// Save the session to `path` before exiting, waiting for it to be written
+    pub fn save_session(&mut self, path: &Path) {
+        self.track_recorder.save_checkpoint();
+        // Both would write the same temporary file
+        while self.saving_session.load(Ordering::Acquire) {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        if let Err(e) = self.exporter.session().save(path) {
+            eprintln!("Failed to save session: {}", e);
         }
     }
 
     pub fn update_from_gps(&mut self, gps_data: &GpsData) {
-        self.track_recorder.update(gps_data);
+        self.last_good_fix.update(gps_data, Instant::now());
+
+        let stopped = self.track_recorder.update(gps_data, Instant::now());
+        // Periods finished by rotation are kept with the other tracks
+        for track in self.track_recorder.take_rotated_tracks() {
+            self.exporter.add_track(track);
+        }
+        if let Some((track, reason)) = stopped {
+            self.status_message = Some(format!(
+                "Recording stopped automatically ({}): {} saved",
+                reason.description(),
+                track.name
+            ));
+            self.exporter.add_track(track);
+            self.track_name_input.clear();
+        }
     }
 
     pub fn show(&mut self, ctx: &egui::Context, gps_data: &GpsData) {
+        self.poll_map_matching();
         if !self.open {
             return;
         }
@@ -93,9 +533,75 @@ impl WaypointDialog {
                 // Summary and export section
                 self.render_export_section(ui);
             });
+
+        self.render_track_edit(ctx);
+    }
+
+    /// Popup editing a saved track's name, description and map color
+    fn render_track_edit(&mut self, ctx: &egui::Context) {
+        let Some(ref mut edit) = self.track_edit else {
+            return;
+        };
+        let mut save = false;
+        let mut close = false;
+
+        egui::Window::new("✏ Edit Track")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                egui::Grid::new("track_edit_grid")
+                    .num_columns(2)
+                    .spacing([10.0, 5.0])
+                    .show(ui, |ui| {
+                        ui.label("Name:");
+                        ui.text_edit_singleline(&mut edit.name);
+                        ui.end_row();
+
+                        ui.label("Description:");
+                        ui.add(egui::TextEdit::multiline(&mut edit.description).desired_rows(3));
+                        ui.end_row();
+
+                        ui.checkbox(&mut edit.use_color, "Map color:");
+                        ui.add_enabled_ui(edit.use_color, |ui| {
+                            ui.color_edit_button_srgb(&mut edit.color);
+                        });
+                        ui.end_row();
+                    });
+
+                ui.horizontal(|ui| {
+                    save = ui.add_enabled(!edit.name.trim().is_empty(), egui::Button::new("💾 Save")).clicked();
+                    close = ui.button("Cancel").clicked();
+                });
+            });
+
+        if save {
+            if let Some(mut track) = self.exporter.get_tracks().get(edit.index).cloned() {
+                track.name = edit.name.trim().to_string();
+                let description = edit.description.trim();
+                track.description = (!description.is_empty()).then(|| description.to_string());
+                track.color = edit.use_color.then_some(edit.color);
+                self.status_message = Some(format!("Updated track '{}'", track.name));
+                self.exporter.replace_track(edit.index, track);
+            }
+        }
+        if save || close {
+            self.track_edit = None;
+        }
     }
 
     fn render_waypoint_tab(&mut self, ui: &mut egui::Ui, gps_data: &GpsData) {
+        // During a dropout show (and save from) the last good fix, with its age
+        let held = if self.fix_hold.enabled && !gps_data.has_fix() {
+            self.last_good_fix.get(Instant::now()).map(|(data, age)| (data.clone(), age))
+        } else {
+            None
+        };
+        let (position, held_age) = match &held {
+            Some((data, age)) => (data, Some(*age)),
+            None => (gps_data, None),
+        };
+        let held_too_old = held_age.is_some_and(|age| !self.fix_hold.allows(age));
+
         // Current position info
         ui.group(|ui| {
             ui.label("Current Position:");
@@ -103,20 +609,36 @@ impl WaypointDialog {
                 .num_columns(2)
                 .spacing([10.0, 5.0])
                 .show(ui, |ui| {
-                    ui.label("Latitude:");
-                    ui.monospace(GpsData::format_coordinate(gps_data.latitude));
-                    ui.end_row();
+                    match (self.coordinate_format, position.latitude, position.longitude) {
+                        (CoordinateFormat::Utm | CoordinateFormat::Mgrs, Some(lat), Some(lon)) => {
+                            ui.label(format!("{}:", self.coordinate_format.name()));
+                            ui.monospace(self.coordinate_format.format_position(lat, lon, self.coordinate_decimals));
+                            ui.end_row();
+                        }
+                        _ => {
+                            ui.label("Latitude:");
+                            ui.monospace(GpsData::format_coordinate(position.latitude, self.coordinate_decimals));
+                            ui.end_row();
 
-                    ui.label("Longitude:");
-                    ui.monospace(GpsData::format_coordinate(gps_data.longitude));
-                    ui.end_row();
+                            ui.label("Longitude:");
+                            ui.monospace(GpsData::format_coordinate(position.longitude, self.coordinate_decimals));
+                            ui.end_row();
+                        }
+                    }
 
-                    if let Some(alt) = gps_data.altitude {
+                    if let Some(alt) = position.altitude {
                         ui.label("Altitude:");
-                        ui.monospace(format!("{:.1} m", alt));
+                        ui.monospace(self.distance_units.format_altitude(alt, 1));
                         ui.end_row();
                     }
                 });
+
+            if let Some(age) = held_age {
+                ui.colored_label(
+                    egui::Color32::from_rgb(255, 165, 0),
+                    format!("⏱ No fix — last good fix {}s ago, may be slightly stale", age.as_secs()),
+                );
+            }
         });
 
         ui.add_space(10.0);
@@ -135,18 +657,36 @@ impl WaypointDialog {
                 ui.text_edit_singleline(&mut self.waypoint_description);
             });
 
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut self.use_waypoint_color, "Marker color:");
+                ui.add_enabled_ui(self.use_waypoint_color, |ui| {
+                    ui.color_edit_button_srgb(&mut self.waypoint_color);
+                });
+            });
+
             ui.add_space(5.0);
 
-            let can_save = gps_data.has_fix() && !self.waypoint_name.is_empty();
+            let unmet = self.quality_gate.unmet_requirements(position);
+            let can_save = position.has_fix() && !held_too_old && unmet.is_empty() && !self.waypoint_name.is_empty();
             
             ui.horizontal(|ui| {
                 if ui.add_enabled(can_save, egui::Button::new("💾 Save Waypoint")).clicked() {
-                    self.save_waypoint(gps_data);
+                    self.save_waypoint(position);
+                    if let (Some(age), Some(message)) = (held_age, self.status_message.as_mut()) {
+                        message.push_str(&format!(" (from the fix {}s ago)", age.as_secs()));
+                    }
                 }
 
                 if !can_save {
-                    if !gps_data.has_fix() {
+                    if !position.has_fix() {
                         ui.colored_label(egui::Color32::YELLOW, "⚠ No GPS fix");
+                    } else if held_too_old {
+                        ui.colored_label(
+                            egui::Color32::YELLOW,
+                            format!("⚠ Last fix too old to save (over {}s)", self.fix_hold.max_age_secs),
+                        );
+                    } else if !unmet.is_empty() {
+                        ui.colored_label(egui::Color32::YELLOW, format!("⚠ Fix too poor: {}", unmet.join(", ")));
                     } else if self.waypoint_name.is_empty() {
                         ui.colored_label(egui::Color32::YELLOW, "⚠ Name required");
                     }
@@ -164,6 +704,7 @@ impl WaypointDialog {
                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                     if ui.button("🗑 Clear").clicked() {
                         self.exporter.clear_waypoints();
+                        self.route_order.clear();
                         self.status_message = Some("Waypoints cleared".to_string());
                     }
                 });
@@ -173,31 +714,192 @@ impl WaypointDialog {
 
             if self.exporter.waypoint_count() == 0 {
                 ui.weak("No waypoints saved yet");
+                return;
+            }
+
+            ui.horizontal(|ui| {
+                ui.label("🔍");
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.waypoint_filter)
+                        .hint_text("Filter by name")
+                        .desired_width(160.0),
+                );
+                if !self.waypoint_filter.is_empty() && ui.small_button("✖").clicked() {
+                    self.waypoint_filter.clear();
+                }
+                ui.label("Sort:");
+                egui::ComboBox::from_id_source("waypoint_sort")
+                    .selected_text(self.waypoint_sort.name())
+                    .show_ui(ui, |ui| {
+                        for sort in WaypointSort::ALL {
+                            ui.selectable_value(&mut self.waypoint_sort, sort, sort.name());
+                        }
+                    });
+            });
+
+            let position = if gps_data.has_fix() {
+                gps_data.latitude.zip(gps_data.longitude)
             } else {
-                egui::ScrollArea::vertical().max_height(120.0).show(ui, |ui| {
-                    egui::Grid::new("waypoint_list")
-                        .num_columns(3)
-                        .spacing([10.0, 5.0])
-                        .striped(true)
-                        .show(ui, |ui| {
-                            ui.strong("Name");
-                            ui.strong("Position");
-                            ui.strong("Time");
+                None
+            };
+            let shown = filter_waypoints(self.exporter.get_waypoints(), &self.waypoint_filter, self.waypoint_sort, position);
+            if shown.is_empty() {
+                ui.weak("No waypoints match the filter");
+                return;
+            }
+
+            egui::ScrollArea::vertical().max_height(120.0).show(ui, |ui| {
+                egui::Grid::new("waypoint_list")
+                    .num_columns(5)
+                    .spacing([10.0, 5.0])
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.strong("Name");
+                        ui.strong("Position");
+                        ui.strong("Time");
+                        ui.strong("Distance");
+                        ui.end_row();
+
+                        let mut marked = None;
+                        let mut navigate = None;
+                        for (wp, distance) in shown {
+                            ui.label(&wp.name);
+                            ui.monospace(self.coordinate_format.format_position(wp.latitude, wp.longitude, self.coordinate_decimals));
+                            ui.monospace(self.time_zone.format(&wp.timestamp, "%H:%M:%S"));
+                            ui.monospace(distance.map_or("--".to_string(), |d| self.distance_units.format_distance(d)));
+                            ui.horizontal(|ui| {
+                                if ui.small_button("📌")
+                                    .on_hover_text("Measure the distance travelled from here on, shown under Odometer")
+                                    .clicked()
+                                {
+                                    marked = Some(wp.name.clone());
+                                }
+                                if ui.small_button("🧭")
+                                    .on_hover_text("Navigate: show the way there on the map")
+                                    .clicked()
+                                {
+                                    navigate = Some(wp.clone());
+                                }
+                            });
                             ui.end_row();
+                        }
+                        if navigate.is_some() {
+                            self.navigate_request = navigate;
+                        }
+                        if let Some(name) = marked {
+                            self.status_message = Some(format!("Measuring distance travelled since '{}'", name));
+                            self.reference_request = Some(name);
+                        }
+                    });
+            });
+        });
 
-                            for wp in self.exporter.get_waypoints() {
-                                ui.label(&wp.name);
-                                ui.monospace(format!("{:.6}, {:.6}", wp.latitude, wp.longitude));
-                                ui.monospace(wp.timestamp.format("%H:%M:%S").to_string());
-                                ui.end_row();
+        ui.add_space(10.0);
+        self.render_route_builder(ui);
+    }
+
+    /// Build routes (GPX `<rte>`) by picking saved waypoints in order
+    fn render_route_builder(&mut self, ui: &mut egui::Ui) {
+        ui.group(|ui| {
+            ui.strong(format!("Routes ({})", self.exporter.route_count()));
+            ui.separator();
+
+            let waypoints = self.exporter.get_waypoints();
+            if waypoints.len() < 2 {
+                ui.weak("Save at least two waypoints to build a route");
+            } else {
+                self.route_order.retain(|&i| i < waypoints.len());
+
+                ui.horizontal(|ui| {
+                    let mut added = None;
+                    egui::ComboBox::from_id_source("route_add_point")
+                        .selected_text("➕ Add waypoint")
+                        .show_ui(ui, |ui| {
+                            for (i, wp) in waypoints.iter().enumerate() {
+                                if ui.selectable_label(false, &wp.name).clicked() {
+                                    added = Some(i);
+                                }
                             }
                         });
+                    if let Some(i) = added {
+                        self.route_order.push(i);
+                    }
+                    if !self.route_order.is_empty() && ui.button("Clear").clicked() {
+                        self.route_order.clear();
+                    }
+                });
+
+                // The route so far, with reordering
+                let mut action = None;
+                for (position, &i) in self.route_order.iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        ui.monospace(format!("{:>2}.", position + 1));
+                        ui.label(&waypoints[i].name);
+                        if ui.add_enabled(position > 0, egui::Button::new("⬆").small()).clicked() {
+                            action = Some((position, position - 1));
+                        }
+                        if ui.add_enabled(position + 1 < self.route_order.len(), egui::Button::new("⬇").small()).clicked() {
+                            action = Some((position, position + 1));
+                        }
+                        if ui.small_button("✖").clicked() {
+                            action = Some((position, usize::MAX));
+                        }
+                    });
+                }
+                match action {
+                    Some((position, usize::MAX)) => {
+                        self.route_order.remove(position);
+                    }
+                    Some((a, b)) => self.route_order.swap(a, b),
+                    None => {}
+                }
+
+                let route = Route::from_waypoints(self.route_name.trim().to_string(), waypoints, &self.route_order);
+                ui.horizontal(|ui| {
+                    ui.label("Name:");
+                    ui.text_edit_singleline(&mut self.route_name);
+                    let can_save = route.points.len() >= 2 && !route.name.is_empty();
+                    if ui.add_enabled(can_save, egui::Button::new("💾 Save Route")).clicked() {
+                        self.status_message = Some(format!(
+                            "Route '{}' saved: {} points, {}",
+                            route.name,
+                            route.points.len(),
+                            self.distance_units.format_distance(route.total_distance()),
+                        ));
+                        self.exporter.add_route(route);
+                        self.route_name.clear();
+                        self.route_order.clear();
+                    }
                 });
             }
+
+            if self.exporter.route_count() > 0 {
+                ui.add_space(5.0);
+                let mut removed = None;
+                for (i, route) in self.exporter.get_routes().iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        ui.label(&route.name);
+                        ui.weak(format!(
+                            "{} points, {}",
+                            route.points.len(),
+                            self.distance_units.format_distance(route.total_distance()),
+                        ));
+                        if ui.small_button("🗑").on_hover_text("Delete route").clicked() {
+                            removed = Some(i);
+                        }
+                    });
+                }
+                if let Some(i) = removed {
+                    self.exporter.remove_route(i);
+                }
+                ui.small("Routes are written to GPX exports only");
+            }
         });
     }
 
     fn render_track_tab(&mut self, ui: &mut egui::Ui, gps_data: &GpsData) {
+        self.render_checkpoint_prompt(ui);
+
         // Recording controls
         ui.group(|ui| {
             ui.strong("Track Recording");
@@ -211,14 +913,17 @@ impl WaypointDialog {
 
                 ui.add_space(5.0);
 
-                let can_start = gps_data.has_fix();
+                // A new recording would overwrite the unfinished one's checkpoint
+                let can_start = gps_data.has_fix() && !self.has_pending_checkpoint();
                 ui.horizontal(|ui| {
                     if ui.add_enabled(can_start, egui::Button::new("🔴 Start Recording")).clicked() {
                         self.track_recorder.start_recording(self.track_name_input.clone());
                         self.status_message = Some(format!("Recording started: {}", self.track_recorder.get_track_name()));
                     }
 
-                    if !can_start {
+                    if self.has_pending_checkpoint() {
+                        ui.colored_label(egui::Color32::YELLOW, "⚠ Resume, save or discard the unfinished recording first");
+                    } else if !can_start {
                         ui.colored_label(egui::Color32::YELLOW, "⚠ No GPS fix");
                     }
                 });
@@ -238,17 +943,34 @@ impl WaypointDialog {
                             ui.monospace(format!("{}", stats.points));
                             ui.end_row();
 
+                            let filtered = self.track_recorder.get_filtered_points();
+                            if filtered > 0 {
+                                ui.label("Skipped:");
+                                ui.monospace(format!("{} (fix quality)", filtered));
+                                ui.end_row();
+                            }
+
                             ui.label("Distance:");
                             ui.monospace(format!("{:.2} km", stats.distance_km));
                             ui.end_row();
 
+                            ui.label("Started:");
+                            ui.monospace(self.time_zone.format_with_zone(&stats.start_time, "%H:%M:%S"));
+                            ui.end_row();
+
                             ui.label("Duration:");
                             ui.monospace(stats.format_duration());
                             ui.end_row();
 
                             if let Some(avg_speed) = stats.avg_speed {
                                 ui.label("Avg Speed:");
-                                ui.monospace(format!("{:.1} km/h", avg_speed));
+                                ui.monospace(self.speed_unit.format_speed(avg_speed, 1));
+                                ui.end_row();
+                            }
+
+                            if let Some(file) = self.track_recorder.last_rotation_file() {
+                                ui.label("Last file:");
+                                ui.monospace(file.file_name().unwrap_or_default().to_string_lossy());
                                 ui.end_row();
                             }
                         });
@@ -258,7 +980,7 @@ impl WaypointDialog {
 
                 ui.horizontal(|ui| {
                     if ui.button("⏹ Stop & Save").clicked() {
-                        if let Some(track) = self.track_recorder.stop_recording() {
+                        if let Some(track) = self.track_recorder.finish_recording() {
                             self.exporter.add_track(track);
                             self.status_message = Some("Track saved!".to_string());
                             self.track_name_input.clear();
@@ -286,6 +1008,19 @@ impl WaypointDialog {
             ui.strong("Recording Settings");
             ui.separator();
 
+            ui.horizontal(|ui| {
+                ui.label("Mode:");
+                let mut mode = self.track_recorder.get_mode();
+                egui::ComboBox::from_id_source("recording_mode")
+                    .selected_text(mode.name())
+                    .show_ui(ui, |ui| {
+                        for option in RecordingMode::ALL {
+                            ui.selectable_value(&mut mode, option, option.name());
+                        }
+                    });
+                self.track_recorder.set_mode(mode);
+            });
+
             ui.horizontal(|ui| {
                 ui.label("Min Distance:");
                 if ui.text_edit_singleline(&mut self.min_distance_str).changed() {
@@ -306,8 +1041,41 @@ impl WaypointDialog {
                 ui.label("seconds");
             });
 
+            ui.horizontal(|ui| {
+                ui.label("Max Points:");
+                if ui.text_edit_singleline(&mut self.max_points_str).changed() {
+                    self.track_recorder.set_max_points(self.max_points_str.parse::<usize>().ok());
+                }
+                ui.label("(blank = unlimited)");
+            });
+
+            ui.horizontal(|ui| {
+                ui.label("Idle Stop:");
+                if ui.text_edit_singleline(&mut self.idle_timeout_str).changed() {
+                    let timeout = self.idle_timeout_str.parse::<u64>().ok().map(Duration::from_secs);
+                    self.track_recorder.set_idle_timeout(timeout);
+                }
+                ui.label("seconds (blank = off)");
+            });
+
+            let mut filter = self.track_recorder.get_fix_filter();
+            ui.horizontal(|ui| {
+                ui.label("Max HDOP:");
+                if ui.text_edit_singleline(&mut self.max_hdop_str).changed() {
+                    filter.max_hdop = self.max_hdop_str.parse::<f64>().ok().filter(|h| *h > 0.0);
+                }
+                ui.label("(blank = any)");
+            });
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut filter.require_3d, "Require 3D fix");
+                ui.checkbox(&mut filter.skip_estimated, "Skip estimated fixes");
+            });
+            self.track_recorder.set_fix_filter(filter);
+
             ui.add_space(3.0);
-            ui.small("Points recorded only when both thresholds exceeded");
+            ui.small(self.track_recorder.get_mode().description());
+            ui.small("Recording stops and saves at the point limit or after the idle time");
+            ui.small("Fixes below the HDOP / 3D / estimated criteria are skipped");
         });
 
         ui.add_space(10.0);
@@ -320,6 +1088,9 @@ impl WaypointDialog {
                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                     if ui.button("🗑 Clear").clicked() {
                         self.exporter.clear_tracks();
+                        self.compare_selection.clear();
+                        self.track_edit = None;
+                        self.map_matching = None;
                         self.status_message = Some("Tracks cleared".to_string());
                     }
                 });
@@ -330,40 +1101,206 @@ impl WaypointDialog {
             if self.exporter.track_count() == 0 {
                 ui.weak("No tracks saved yet");
             } else {
+                ui.horizontal(|ui| {
+                    ui.label("Smoothing window:");
+                    ui.add(egui::DragValue::new(&mut self.smoothing_window).range(3..=31).suffix(" points"));
+                }).response.on_hover_text("Points averaged around each position when smoothing a track");
+
+                let mut smooth = None;
+                let mut snap = None;
+                let mut slice = None;
+                let mut edit = None;
                 egui::ScrollArea::vertical().max_height(120.0).show(ui, |ui| {
                     egui::Grid::new("track_list")
-                        .num_columns(3)
+                        .num_columns(5)
                         .spacing([10.0, 5.0])
                         .striped(true)
                         .show(ui, |ui| {
+                            ui.strong("").on_hover_text("Tick two tracks to compare them");
                             ui.strong("Name");
                             ui.strong("Points");
                             ui.strong("Distance");
+                            ui.strong("");
                             ui.end_row();
 
-                            for track in self.exporter.get_tracks() {
-                                ui.label(&track.name);
+                            for (index, track) in self.exporter.get_tracks().iter().enumerate() {
+                                let mut compared = self.compare_selection.contains(&index);
+                                if ui.checkbox(&mut compared, "").on_hover_text("Compare").changed() {
+                                    self.compare_selection.retain(|&i| i != index);
+                                    if compared {
+                                        self.compare_selection.push(index);
+                                        if self.compare_selection.len() > 2 {
+                                            self.compare_selection.remove(0);
+                                        }
+                                    }
+                                }
+                                let name = ui.label(&track.name);
+                                if let Some(ref description) = track.description {
+                                    name.on_hover_text(description);
+                                }
                                 ui.monospace(format!("{}", track.total_points()));
                                 ui.monospace(format!("{:.2} km", track.total_distance() / 1000.0));
+                                ui.horizontal(|ui| {
+                                    if ui.small_button("✏").on_hover_text("Edit name, description and map color").clicked() {
+                                        edit = Some(index);
+                                    }
+                                    if ui.small_button("〰 Smooth")
+                                        .on_hover_text("Average out GPS wander before export")
+                                        .clicked()
+                                    {
+                                        smooth = Some(index);
+                                    }
+                                    if self.map_matcher.is_some()
+                                        && ui.add_enabled(self.map_matching.is_none(), egui::Button::new("🛣 Snap to road").small()).clicked()
+                                    {
+                                        snap = Some(index);
+                                    }
+                                    if ui.small_button("✂ Slice")
+                                        .on_hover_text("Export only part of the track between two times")
+                                        .clicked()
+                                    {
+                                        slice = Some(index);
+                                    }
+                                });
                                 ui.end_row();
                             }
                         });
                 });
+
+                if let Some(index) = smooth {
+                    let track = self.exporter.get_tracks()[index].smooth(self.smoothing_window);
+                    self.status_message = Some(format!("Smoothed '{}' over {} points", track.name, self.smoothing_window));
+                    self.exporter.replace_track(index, track);
+                }
+                if let Some(index) = snap {
+                    self.start_map_matching(index, ui.ctx());
+                }
+                if let Some(index) = edit {
+                    let track = &self.exporter.get_tracks()[index];
+                    self.track_edit = Some(TrackEdit {
+                        index,
+                        name: track.name.clone(),
+                        description: track.description.clone().unwrap_or_default(),
+                        use_color: track.color.is_some(),
+                        color: track.color.unwrap_or([255, 0, 0]),
+                    });
+                }
+                if let Some(index) = slice {
+                    let track = &self.exporter.get_tracks()[index];
+                    let length = track.duration().map_or(0.0, |d| d.num_milliseconds() as f64 / 1000.0);
+                    self.track_slice = Some(TrackSlice { index, start_secs: 0.0, end_secs: length });
+                }
+
+                self.render_track_slice(ui);
+                self.render_track_comparison(ui);
+            }
+        });
+    }
+
+    /// Statistics of the two tracks ticked for comparison, which the map shows in the same colors
+    fn render_track_comparison(&mut self, ui: &mut egui::Ui) {
+        let Some([first, second]) = self.compared_tracks() else {
+            return;
+        };
+        let tracks = self.exporter.get_tracks();
+
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.strong("Compare");
+            if ui.small_button("✖").on_hover_text("Stop comparing").clicked() {
+                self.compare_selection.clear();
             }
         });
+        track_compare::render_comparison_table(ui, [&tracks[first], &tracks[second]], self.distance_units, self.speed_unit);
+    }
+
+    /// Start and end pickers for the track being sliced, and its export
+    fn render_track_slice(&mut self, ui: &mut egui::Ui) {
+        let Some(ref mut slice) = self.track_slice else {
+            return;
+        };
+        // Drop the slice if its track was removed or replaced by an empty one
+        let Some((track, start)) = self.exporter.get_tracks().get(slice.index)
+            .and_then(|track| Some((track, track.start_time()?)))
+        else {
+            self.track_slice = None;
+            return;
+        };
+        let length = track.duration().map_or(0.0, |d| d.num_milliseconds() as f64 / 1000.0);
+        let time_zone = self.time_zone;
+        let at = move |secs: f64| start + chrono::Duration::milliseconds((secs * 1000.0) as i64);
+        let clock = move |secs: f64, _: std::ops::RangeInclusive<usize>| time_zone.format_with_zone(&at(secs), "%H:%M:%S");
+
+        ui.separator();
+        ui.strong(format!("✂ Slice of '{}'", track.name));
+        egui::Grid::new("track_slice_grid")
+            .num_columns(2)
+            .spacing([10.0, 5.0])
+            .show(ui, |ui| {
+                ui.label("From:");
+                ui.add(egui::Slider::new(&mut slice.start_secs, 0.0..=length).custom_formatter(clock));
+                ui.end_row();
+
+                ui.label("To:");
+                ui.add(egui::Slider::new(&mut slice.end_secs, 0.0..=length).custom_formatter(clock));
+                ui.end_row();
+            });
+        slice.end_secs = slice.end_secs.max(slice.start_secs);
+
+        let sliced = track.slice_by_time(at(slice.start_secs), at(slice.end_secs));
+        ui.label(format!(
+            "{} of {} points, {:.2} km",
+            sliced.total_points(),
+            track.total_points(),
+            sliced.total_distance() / 1000.0
+        ));
+
+        let mut export = false;
+        let mut cancel = false;
+        ui.horizontal(|ui| {
+            let can_export = sliced.total_points() > 0 && !self.export_path.is_empty();
+            export = ui.add_enabled(can_export, egui::Button::new("💾 Export Slice"))
+                .on_hover_text("Saved as <export path>_slice in the selected format")
+                .clicked();
+            cancel = ui.button("Cancel").clicked();
+        });
+
+        if export {
+            self.export_slice(&sliced);
+        }
+        if export || cancel {
+            self.track_slice = None;
+        }
+    }
+
+    /// What an export writes: the saved data, plus the track being recorded
+    /// so far when `include_live_track` is set. Recording carries on.
+    fn export_contents(&self) -> Cow<'_, WaypointExporter> {
+        let live = self.track_recorder.current_track().filter(|track| self.include_live_track && track.total_points() > 0);
+        match live {
+            Some(track) => {
+                let mut exporter = self.exporter.clone();
+                exporter.add_track(track.clone());
+                Cow::Owned(exporter)
+            }
+            None => Cow::Borrowed(&self.exporter),
+        }
     }
 
     fn render_export_section(&mut self, ui: &mut egui::Ui) {
         ui.group(|ui| {
-            let total_items = self.exporter.waypoint_count() + self.exporter.track_count();
+            let live_track = self.include_live_track && self.track_recorder.current_track().is_some_and(|t| t.total_points() > 0);
+            let total_items = self.exporter.waypoint_count() + self.exporter.track_count() + self.exporter.route_count() + usize::from(live_track);
             ui.horizontal(|ui| {
-                ui.strong(format!("Export Data ({} waypoints, {} tracks)", 
+                ui.strong(format!("Export Data ({} waypoints, {} tracks, {} routes)", 
                     self.exporter.waypoint_count(), 
-                    self.exporter.track_count()));
+                    self.exporter.track_count(),
+                    self.exporter.route_count()));
                 
                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                     if ui.button("🗑 Clear All").clicked() {
                         self.exporter.clear();
+                        self.route_order.clear();
                         self.status_message = Some("All data cleared".to_string());
                     }
                 });
@@ -380,6 +1317,8 @@ impl WaypointDialog {
                         ui.selectable_value(&mut self.selected_format, WaypointFormat::GeoJSON, WaypointFormat::GeoJSON.display_name());
                         ui.selectable_value(&mut self.selected_format, WaypointFormat::KML, WaypointFormat::KML.display_name());
                         ui.selectable_value(&mut self.selected_format, WaypointFormat::CSV, WaypointFormat::CSV.display_name());
+                        ui.selectable_value(&mut self.selected_format, WaypointFormat::JsonLines, WaypointFormat::JsonLines.display_name());
+                        ui.selectable_value(&mut self.selected_format, WaypointFormat::FIT, WaypointFormat::FIT.display_name());
                     });
             });
 
@@ -389,18 +1328,212 @@ impl WaypointDialog {
                 ui.label(format!(".{}", self.selected_format.extension()));
             });
 
+            ui.add_enabled(self.track_recorder.current_track().is_some(), egui::Checkbox::new(&mut self.include_live_track, "Include the track being recorded"))
+                .on_hover_text("Export the recording so far as an extra track, without stopping it");
+
             ui.add_space(5.0);
 
             let can_export = total_items > 0 && !self.export_path.is_empty();
 
-            if ui.add_enabled(can_export, egui::Button::new("💾 Export to File")).clicked() {
-                self.export_data();
-            }
+            ui.horizontal(|ui| {
+                if ui.add_enabled(can_export, egui::Button::new("💾 Export to File")).clicked() {
+                    self.export_data();
+                }
+
+                if ui.add_enabled(total_items > 0, egui::Button::new("👁 Preview"))
+                    .on_hover_text("Show the start of the file the export would write")
+                    .clicked()
+                {
+                    match self.export_contents().export_preview(self.selected_format, PREVIEW_LINES) {
+                        Ok(preview) => self.export_preview = Some((self.selected_format, preview)),
+                        Err(e) => {
+                            self.export_preview = None;
+                            self.status_message = Some(format!("Preview failed: {}", e));
+                        }
+                    }
+                }
+
+                let can_import = !self.export_path.is_empty();
+                if ui.add_enabled(can_import, egui::Button::new("📂 Import from File")).clicked() {
+                    self.import_data();
+                }
+                let thinning = &mut self.import_thinning;
+                let mut changed = ui.checkbox(&mut thinning.enabled, "Thin")
+                    .on_hover_text("Simplify imported tracks, dropping points within the tolerance of the line")
+                    .changed();
+                changed |= ui.add_enabled(thinning.enabled, egui::DragValue::new(&mut thinning.tolerance_m)
+                    .range(0.5..=500.0)
+                    .speed(0.5)
+                    .suffix(" m"))
+                    .changed();
+                self.import_thinning_changed |= changed;
+
+                let can_export_matrix = self.exporter.waypoint_count() >= 2 && !self.export_path.is_empty();
+                if ui.add_enabled(can_export_matrix, egui::Button::new("📐 Distance Matrix"))
+                    .on_hover_text("Export distances and bearings between all waypoints as CSV")
+                    .clicked()
+                {
+                    self.export_matrix();
+                }
+
+                let can_export_summary = self.exporter.track_count() > 0 && !self.export_path.is_empty();
+                if ui.add_enabled(can_export_summary, egui::Button::new("📄 Track Summary"))
+                    .on_hover_text("Save distance, time, speed and elevation statistics for each track as Markdown")
+                    .clicked()
+                {
+                    self.export_summary();
+                }
+            });
 
             if !can_export && total_items == 0 {
                 ui.colored_label(egui::Color32::YELLOW, "⚠ No data to export");
             }
+
+            self.render_export_preview(ui);
+
+            if !self.recent_exports.is_empty() {
+                ui.add_space(5.0);
+                self.render_recent_exports(ui, total_items > 0);
+            }
+        });
+    }
+
+    /// Start of the last previewed export, while its format is still selected
+    fn render_export_preview(&mut self, ui: &mut egui::Ui) {
+        let Some((format, preview)) = &self.export_preview else {
+            return;
+        };
+        if *format != self.selected_format {
+            return;
+        }
+
+        ui.add_space(5.0);
+        let mut close = false;
+        ui.horizontal(|ui| {
+            let size = format_size(preview.total_bytes);
+            let summary = match preview.total_lines {
+                Some(lines) if preview.truncated => format!("Preview: {}, {} lines (first {} shown)", size, lines, PREVIEW_LINES),
+                Some(lines) => format!("Preview: {}, {} lines", size, lines),
+                None => format!("Preview: {}", size),
+            };
+            ui.label(summary);
+            close = ui.small_button("✖").on_hover_text("Close preview").clicked();
+        });
+
+        egui::ScrollArea::both()
+            .id_source("export_preview")
+            .max_height(200.0)
+            .show(ui, |ui| {
+                ui.add(
+                    egui::TextEdit::multiline(&mut preview.text.as_str())
+                        .code_editor()
+                        .desired_width(f32::INFINITY),
+                );
+            });
+
+        if close {
+            self.export_preview = None;
+        }
+    }
+
+    /// Earlier export files with one-click re-export of the current data
+    fn render_recent_exports(&mut self, ui: &mut egui::Ui, has_data: bool) {
+        ui.label("Recent exports:");
+
+        let mut reexport = None;
+        let mut forget = None;
+        for (i, recent) in self.recent_exports.iter().enumerate() {
+            ui.horizontal(|ui| {
+                if ui.add_enabled(has_data, egui::Button::new("🔁").small())
+                    .on_hover_text("Export the current data to this file again")
+                    .clicked()
+                {
+                    reexport = Some(recent.clone());
+                }
+                if ui.small_button("✖").on_hover_text("Remove from the list").clicked() {
+                    forget = Some(i);
+                }
+
+                let name = recent.path.file_name().map_or_else(
+                    || recent.path.display().to_string(),
+                    |name| name.to_string_lossy().into_owned(),
+                );
+                let label = format!("{} ({})", name, recent.format.extension().to_uppercase());
+                if export_folder_exists(&recent.path) {
+                    ui.label(label).on_hover_text(recent.path.display().to_string());
+                } else {
+                    ui.weak(label).on_hover_text(format!("{} (folder not found)", recent.path.display()));
+                }
+            });
+        }
+
+        if let Some(i) = forget {
+            self.recent_exports.remove(i);
+            self.recent_exports_changed = true;
+        }
+        if let Some(recent) = reexport {
+            self.export_to(recent.path, recent.format);
+        }
+    }
+
+    fn render_checkpoint_prompt(&mut self, ui: &mut egui::Ui) {
+        let Some(checkpoint) = &self.pending_checkpoint else {
+            return;
+        };
+
+        let mut action = None;
+        ui.group(|ui| {
+            ui.colored_label(egui::Color32::YELLOW, "⚠ Unfinished recording found");
+            ui.label(format!(
+                "{}: {} points, {:.2} km, started {}",
+                checkpoint.track.name,
+                checkpoint.track.total_points(),
+                checkpoint.track.total_distance() / 1000.0,
+                self.time_zone.format_with_zone(&checkpoint.start_time, "%Y-%m-%d %H:%M")
+            ));
+            ui.horizontal(|ui| {
+                if ui.add_enabled(!self.track_recorder.is_recording(), egui::Button::new("▶ Resume Recording")).clicked() {
+                    action = Some(CheckpointAction::Resume);
+                }
+                if ui.button("💾 Save as Track").clicked() {
+                    action = Some(CheckpointAction::Save);
+                }
+                if ui.button("❌ Discard").clicked() {
+                    action = Some(CheckpointAction::Discard);
+                }
+            });
         });
+        ui.add_space(10.0);
+
+        if let Some(action) = action {
+            self.apply_checkpoint_action(action);
+        }
+    }
+
+    /// Resume the unfinished recording, or save or discard it and remove its checkpoint
+    fn apply_checkpoint_action(&mut self, action: CheckpointAction) {
+        let Some(checkpoint) = self.pending_checkpoint.take() else {
+            return;
+        };
+        match action {
+            CheckpointAction::Resume => {
+                self.track_recorder.resume_from_checkpoint(checkpoint);
+                self.status_message = Some(format!("Recording resumed: {}", self.track_recorder.get_track_name()));
+            }
+            CheckpointAction::Save => {
+                self.status_message = Some(format!("Track '{}' recovered", checkpoint.track.name));
+                self.exporter.add_track(checkpoint.track);
+                if let Some(path) = &self.checkpoint_path {
+                    RecordingCheckpoint::remove(path);
+                }
+            }
+            CheckpointAction::Discard => {
+                self.status_message = Some("Unfinished recording discarded".to_string());
+                if let Some(path) = &self.checkpoint_path {
+                    RecordingCheckpoint::remove(path);
+                }
+            }
+        }
     }
 
     fn save_waypoint(&mut self, gps_data: &GpsData) {
@@ -410,11 +1543,15 @@ impl WaypointDialog {
             Some(self.waypoint_description.clone())
         };
 
-        if let Some(waypoint) = Waypoint::from_gps_data(
+        if let Some(mut waypoint) = Waypoint::from_gps_data_corrected(
             gps_data,
             self.waypoint_name.clone(),
             desc,
+            self.elevation_corrector.as_ref(),
         ) {
+            if self.use_waypoint_color {
+                waypoint.color = Some(self.waypoint_color);
+            }
             self.exporter.add_waypoint(waypoint);
             self.status_message = Some(format!("Waypoint '{}' saved!", self.waypoint_name));
             
@@ -434,11 +1571,83 @@ impl WaypointDialog {
             path.set_extension(self.selected_format.extension());
         }
 
-        match self.exporter.export_to_file(&path, self.selected_format) {
+        self.export_to(path, self.selected_format);
+    }
+
+    /// Export everything to `path`, remembering it in the recent exports on success
+    fn export_to(&mut self, path: PathBuf, format: WaypointFormat) {
+        if !export_folder_exists(&path) {
+            self.status_message = Some(format!("✗ Export failed: folder for {} not found", path.display()));
+            return;
+        }
+
+        let contents = self.export_contents();
+        match contents.export_to_file(&path, format) {
             Ok(_) => {
                 self.status_message = Some(format!(
                     "✓ Exported {} waypoints and {} tracks to {}",
+                    contents.waypoint_count(),
+                    contents.track_count(),
+                    path.display()
+                ));
+                RecentExport::remember(&mut self.recent_exports, RecentExport { path, format });
+                self.recent_exports_changed = true;
+            }
+            Err(e) => {
+                self.status_message = Some(format!("✗ Export failed: {}", e));
+            }
+        }
+    }
+
+    fn export_slice(&mut self, track: &Track) {
+        let mut path = PathBuf::from(format!("{}_slice", self.export_path));
+        path.set_extension(self.selected_format.extension());
+
+        if !export_folder_exists(&path) {
+            self.status_message = Some(format!("✗ Export failed: folder for {} not found", path.display()));
+            return;
+        }
+        match self.exporter.export_track_to_file(track, &path, self.selected_format) {
+            Ok(_) => {
+                self.status_message = Some(format!(
+                    "✓ Exported {} points of '{}' to {}",
+                    track.total_points(),
+                    track.name,
+                    path.display()
+                ));
+            }
+            Err(e) => {
+                self.status_message = Some(format!("✗ Export failed: {}", e));
+            }
+        }
+    }
+
+    fn export_matrix(&mut self) {
+        let mut path = PathBuf::from(format!("{}_matrix", self.export_path));
+        path.set_extension("csv");
+
+        match self.exporter.export_matrix_to_file(&path) {
+            Ok(_) => {
+                self.status_message = Some(format!(
+                    "✓ Exported distance matrix for {} waypoints to {}",
                     self.exporter.waypoint_count(),
+                    path.display()
+                ));
+            }
+            Err(e) => {
+                self.status_message = Some(format!("✗ Export failed: {}", e));
+            }
+        }
+    }
+
+    fn export_summary(&mut self) {
+        let mut path = PathBuf::from(format!("{}_summary", self.export_path));
+        path.set_extension("md");
+
+        match self.exporter.export_summaries_to_file(&path, self.distance_units, self.time_zone) {
+            Ok(_) => {
+                self.status_message = Some(format!(
+                    "✓ Saved summary of {} tracks to {}",
                     self.exporter.track_count(),
                     path.display()
                 ));
@@ -448,6 +1657,39 @@ impl WaypointDialog {
             }
         }
     }
+
+    fn import_data(&mut self) {
+        let mut path = PathBuf::from(&self.export_path);
+
+        if path.extension().is_none() {
+            path.set_extension(self.selected_format.extension());
+        }
+
+        match self.exporter.import_from_file(&path, self.selected_format, self.import_thinning.tolerance()) {
+            Ok(summary) => {
+                let thinned = if summary.points_after < summary.points_before {
+                    format!(" (track points thinned from {} to {})", summary.points_before, summary.points_after)
+                } else {
+                    String::new()
+                };
+                self.status_message = Some(format!(
+                    "✓ Imported {} waypoints and {} tracks from {}{}",
+                    summary.waypoints,
+                    summary.tracks,
+                    path.display(),
+                    thinned
+                ));
+            }
+            Err(e) => {
+                self.status_message = Some(format!("✗ Import failed: {}", e));
+            }
+        }
+    }
+}
+
+/// Whether the folder an export would be written to still exists
+fn export_folder_exists(path: &std::path::Path) -> bool {
+    path.parent().is_none_or(|dir| dir.as_os_str().is_empty() || dir.is_dir())
 }
 
 impl Default for WaypointDialog {
@@ -455,3 +1697,182 @@ impl Default for WaypointDialog {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn waypoint(name: &str, latitude: f64, minute: u32) -> Waypoint {
+        Waypoint {
+            name: name.to_string(),
+            latitude,
+            longitude: 0.0,
+            elevation: None,
+            timestamp: Utc.with_ymd_and_hms(2024, 5, 1, 12, minute, 0).unwrap(),
+            description: None,
+            color: None,
+        }
+    }
+
+    #[test]
+    fn test_checkpoint_prompt_uses_the_configured_path() {
+        let dir = std::env::temp_dir().join(format!("gps-monitor-dialog-checkpoint-{}", std::process::id()));
+        let path = dir.join("recording_checkpoint.jsonl");
+        let mut track = Track::new("Interrupted".to_string());
+        track.add_point(crate::waypoint::TrackPoint::new(42.0, -71.0, None, Utc::now()));
+        RecordingCheckpoint { track, start_time: Utc::now() }.save(&path).unwrap();
+
+        // Nothing is looked for until a path is given
+        let mut dialog = WaypointDialog::new();
+        assert!(!dialog.has_pending_checkpoint());
+
+        dialog.set_checkpoint_path(path.clone());
+        assert!(dialog.has_pending_checkpoint());
+        dialog.apply_checkpoint_action(CheckpointAction::Save);
+        assert!(!dialog.has_pending_checkpoint());
+        assert_eq!(dialog.exporter.get_tracks().len(), 1);
+        assert!(!path.exists());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_map_matching_runs_off_the_ui_thread() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let ctx = egui::Context::default();
+        let (release, gate) = std::sync::mpsc::channel::<()>();
+        let gate = std::sync::Mutex::new(gate);
+        let snap = move |points: &[crate::waypoint::TrackPoint]| {
+            gate.lock().unwrap().recv().ok()?;
+            Some(points.iter().map(|p| crate::waypoint::TrackPoint { longitude: -71.5, ..p.clone() }).collect())
+        };
+        let mut dialog = WaypointDialog::new();
+        dialog.set_map_matcher(Box::new(snap), runtime.handle().clone());
+        let mut track = Track::new("Commute".to_string());
+        track.add_point(crate::waypoint::TrackPoint::new(42.0, -71.0, None, Utc::now()));
+        dialog.exporter.add_track(track.clone());
+
+        // The track is left alone until the matcher has finished
+        dialog.start_map_matching(0, &ctx);
+        dialog.poll_map_matching();
+        assert_eq!(dialog.exporter.get_tracks()[0].segments[0].points[0].longitude, -71.0);
+        release.send(()).unwrap();
+        while dialog.map_matching.is_some() {
+            std::thread::sleep(Duration::from_millis(5));
+            dialog.poll_map_matching();
+        }
+        assert_eq!(dialog.exporter.get_tracks()[0].segments[0].points[0].longitude, -71.5);
+
+        // A result for a track that has since been replaced is dropped
+        dialog.start_map_matching(0, &ctx);
+        dialog.exporter.clear_tracks();
+        dialog.exporter.add_track(Track::new("Other".to_string()));
+        release.send(()).unwrap();
+        while dialog.map_matching.is_some() {
+            std::thread::sleep(Duration::from_millis(5));
+            dialog.poll_map_matching();
+        }
+        assert_eq!(dialog.exporter.get_tracks()[0].name, "Other");
+        assert!(dialog.exporter.get_tracks()[0].segments.iter().all(|s| s.is_empty()));
+    }
+
+    #[test]
+    fn test_restore_session_clears_comparison() {
+        let dir = std::env::temp_dir().join(format!("gps-monitor-dialog-session-{}", std::process::id()));
+        let path = dir.join("session.json");
+        // As many tracks as before, so the old indices would still be in range
+        let tracks = vec![Track::new("Saturday".to_string()), Track::new("Sunday".to_string())];
+        let session = Session { tracks, ..Session::default() };
+        session.save(&path).unwrap();
+
+        let mut dialog = WaypointDialog::new();
+        for name in ["Morning", "Evening"] {
+            dialog.exporter.add_track(Track::new(name.to_string()));
+        }
+        dialog.compare_selection = vec![0, 1];
+        assert_eq!(dialog.compared_tracks(), Some([0, 1]));
+
+        dialog.restore_session(&path);
+        assert_eq!(dialog.exporter.track_count(), 2);
+        assert!(dialog.compare_selection.is_empty());
+        assert_eq!(dialog.compared_tracks(), None);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_export_includes_live_track() {
+        let mut dialog = WaypointDialog::new();
+        dialog.track_recorder.set_mode(RecordingMode::DistanceOnly);
+        dialog.exporter.add_waypoint(waypoint("Trailhead", 1.0, 0));
+
+        dialog.track_recorder.start_recording("Morning Walk".to_string());
+        let fix = GpsData {
+            latitude: Some(42.0),
+            longitude: Some(-71.0),
+            fix_quality: Some(1),
+            timestamp: Some(Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap()),
+            ..GpsData::default()
+        };
+        dialog.track_recorder.update(&fix, Instant::now());
+        assert_eq!(dialog.track_recorder.current_track().map(Track::total_points), Some(1));
+
+        let gpx = |dialog: &WaypointDialog| dialog.export_contents().export_preview(WaypointFormat::GPX, 1000).unwrap().text;
+
+        // Off: only the saved data
+        assert!(!gpx(&dialog).contains("Morning Walk"));
+
+        // On: the recording so far as an extra track, and recording carries on
+        dialog.include_live_track = true;
+        let contents = dialog.export_contents();
+        assert_eq!((contents.waypoint_count(), contents.track_count()), (1, 1));
+        assert!(gpx(&dialog).contains("<name>Morning Walk</name>"));
+        assert!(dialog.track_recorder.is_recording());
+        assert_eq!(dialog.exporter.track_count(), 0);
+    }
+
+    #[test]
+    fn test_filter_and_sort_waypoints() {
+        let waypoints = vec![waypoint("Trailhead", 1.0, 30), waypoint("camp", 0.5, 10), waypoint("Summit Camp", 0.1, 20)];
+        let names = |shown: Vec<(&Waypoint, Option<f64>)>| shown.iter().map(|(wp, _)| wp.name.clone()).collect::<Vec<_>>();
+
+        assert_eq!(names(filter_waypoints(&waypoints, "CAMP", WaypointSort::Time, None)), ["camp", "Summit Camp"]);
+        assert_eq!(names(filter_waypoints(&waypoints, "", WaypointSort::Name, None)), ["camp", "Summit Camp", "Trailhead"]);
+        assert_eq!(names(filter_waypoints(&waypoints, " ", WaypointSort::Distance, Some((0.0, 0.0)))), ["Summit Camp", "camp", "Trailhead"]);
+        // No fix: distances are unknown and the saved order is kept
+        assert_eq!(names(filter_waypoints(&waypoints, "", WaypointSort::Distance, None)), ["Trailhead", "camp", "Summit Camp"]);
+        assert!(filter_waypoints(&waypoints, "lake", WaypointSort::Time, None).is_empty());
+    }
+
+    #[test]
+    fn test_last_good_fix_survives_dropouts() {
+        let start = Instant::now();
+        let mut fix = GpsData::new();
+        fix.latitude = Some(42.0);
+        fix.longitude = Some(-71.0);
+        fix.timestamp = Some(Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap());
+
+        let mut last = LastGoodFix::default();
+        last.update(&GpsData::new(), start);
+        assert!(last.get(start).is_none());
+
+        last.update(&fix, start);
+        // The same fix repeated, then lost, keeps the original age
+        last.update(&fix, start + Duration::from_secs(1));
+        last.update(&GpsData::new(), start + Duration::from_secs(2));
+        let (held, age) = last.get(start + Duration::from_secs(3)).unwrap();
+        assert_eq!(held.latitude, Some(42.0));
+        assert_eq!(age, Duration::from_secs(3));
+
+        let hold = WaypointFixHold { enabled: true, max_age_secs: 10 };
+        assert!(hold.allows(age));
+        assert!(!hold.allows(last.get(start + Duration::from_secs(11)).unwrap().1));
+
+        // A new fix replaces the held one
+        fix.latitude = Some(42.001);
+        fix.timestamp = Some(Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 5).unwrap());
+        last.update(&fix, start + Duration::from_secs(5));
+        let (held, age) = last.get(start + Duration::from_secs(5)).unwrap();
+        assert_eq!(held.latitude, Some(42.001));
+        assert_eq!(age, Duration::ZERO);
+    }
+}
@@ -1,4 +1,4 @@
-// src/display/mod.rs v4
+// src/display/mod.rs v6
 //! Display modules - Pure egui implementation
 
 #[cfg(feature = "gui")]
@@ -6,8 +6,3 @@ pub mod gui;
 
 // Always include terminal module for non-GUI builds
 pub mod terminal;
-
-#[cfg(not(feature = "gui"))]
-pub mod gui {
-    // Stub for when GUI is not enabled
-}
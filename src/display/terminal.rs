@@ -1,9 +1,15 @@
-// src/display/terminal.rs
-//! Terminal-based display implementation
+// src/display/terminal.rs v5
+//! Terminal-based display implementation, and the `--terminal` mode that
+//! runs the monitor with it instead of the GUI
 
 use crate::{
-    gps::GpsData,
+    config::{DisplaySpeedUnit, DisplayTimeZone, DistanceUnits, GpsConfig},
+    data_log::DataLogger,
+    gps::{AltitudeSource, ConnectionStatus, CoordinateFormat, GpsData, DEFAULT_COORDINATE_DECIMALS},
     error::{Result, GpsError},
+    headless::spawn_connection,
+    lock::RecoverPoison,
+    monitor::GpsMonitor,
 };
 use crossterm::{
     cursor::{Hide, MoveTo, Show},
@@ -12,6 +18,7 @@ use crossterm::{
     terminal::{Clear, ClearType, DisableLineWrap, EnableLineWrap},
 };
 use std::{
+    future::Future,
     io::{self, Write},
     sync::{
         atomic::{AtomicBool, Ordering},
@@ -21,11 +28,76 @@ use std::{
 };
 use tokio::time::sleep;
 
-pub struct TerminalDisplay;
+/// Data older than this is flagged in the status line while connected
+const STALE_DATA_SECS: i64 = 5;
+
+/// How often the dashboard is redrawn
+const REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+pub struct TerminalDisplay {
+    time_zone: DisplayTimeZone,
+    coordinate_decimals: usize,
+    coordinate_format: CoordinateFormat,
+    altitude_source: AltitudeSource,
+    distance_units: DistanceUnits,
+    speed_unit: DisplaySpeedUnit,
+}
 
 impl TerminalDisplay {
     pub fn new() -> Self {
-        Self
+        Self::with_time_zone(DisplayTimeZone::default())
+    }
+
+    /// Show timestamps in `time_zone` instead of UTC
+    pub fn with_time_zone(time_zone: DisplayTimeZone) -> Self {
+        Self {
+            time_zone,
+            coordinate_decimals: DEFAULT_COORDINATE_DECIMALS,
+            coordinate_format: CoordinateFormat::default(),
+            altitude_source: AltitudeSource::default(),
+            distance_units: DistanceUnits::default(),
+            speed_unit: DistanceUnits::default().speed_unit(),
+        }
+    }
+
+    /// Show coordinates with `decimals` places (3 to 9)
+    pub fn coordinate_decimals(mut self, decimals: usize) -> Self {
+        self.coordinate_decimals = decimals;
+        self
+    }
+
+    /// Also show the position as a UTM or MGRS grid reference
+    pub fn coordinate_format(mut self, format: CoordinateFormat) -> Self {
+        self.coordinate_format = format;
+        self
+    }
+
+    /// Prefer GPS or barometric altitude when the receiver reports both
+    pub fn altitude_source(mut self, source: AltitudeSource) -> Self {
+        self.altitude_source = source;
+        self
+    }
+
+    /// Show altitudes in feet for imperial units, meters otherwise
+    pub fn distance_units(mut self, units: DistanceUnits) -> Self {
+        self.distance_units = units;
+        self
+    }
+
+    /// Show speeds in `unit` rather than km/h
+    pub fn speed_unit(mut self, unit: DisplaySpeedUnit) -> Self {
+        self.speed_unit = unit;
+        self
+    }
+
+    /// Display for the time zone, coordinate, altitude and unit settings of `config`
+    pub fn from_config(config: &GpsConfig) -> Self {
+        Self::with_time_zone(config.time_zone)
+            .coordinate_decimals(config.coordinate_decimals)
+            .coordinate_format(config.coordinate_format)
+            .altitude_source(config.altitude_source)
+            .distance_units(config.distance_units)
+            .speed_unit(config.display_speed_unit())
     }
 
     /// Start the terminal display loop
@@ -36,7 +108,7 @@ impl TerminalDisplay {
     ) -> Result<()> {
         let mut stdout = io::stdout();
         execute!(stdout, Hide, DisableLineWrap)
-            .map_err(|e| GpsError::Io(e))?;
+            .map_err(GpsError::Io)?;
 
         // Set up Ctrl+C handler
         let running_clone = Arc::clone(&running);
@@ -46,29 +118,30 @@ impl TerminalDisplay {
         });
 
         while running.load(Ordering::Relaxed) {
-            execute!(stdout, Clear(ClearType::All), MoveTo(0, 0))
-                .map_err(|e| GpsError::Io(e))?;
-
-            let gps_data = data.read().unwrap().clone();
-            self.render_display(&mut stdout, &gps_data)?;
-
-            stdout.flush().map_err(|e| GpsError::Io(e))?;
-            sleep(Duration::from_secs(1)).await;
+            self.draw(&mut stdout, &data.read_recover().clone())?;
+            sleep(REFRESH_INTERVAL).await;
         }
 
         execute!(stdout, Show, EnableLineWrap)
-            .map_err(|e| GpsError::Io(e))?;
+            .map_err(GpsError::Io)?;
         println!("\nShutting down...");
         Ok(())
     }
 
+    /// Clear the screen and render one frame of `data`
+    fn draw(&self, out: &mut impl Write, data: &GpsData) -> Result<()> {
+        execute!(out, Clear(ClearType::All), MoveTo(0, 0)).map_err(GpsError::Io)?;
+        self.render_display(out, data)?;
+        out.flush().map_err(GpsError::Io)
+    }
+
     fn render_satellite_section(&self, stdout: &mut impl Write, data: &GpsData) -> Result<()> {
         execute!(
             stdout,
             SetForegroundColor(Color::Blue),
             Print("SATELLITES:\n"),
             ResetColor
-        ).map_err(|e| GpsError::Io(e))?;
+        ).map_err(GpsError::Io)?;
 
         let used_count = data.satellites_used();
         let total_count = data.satellites_info.len();
@@ -76,19 +149,22 @@ impl TerminalDisplay {
         execute!(
             stdout,
             Print(format!("  Total: {} visible, {} used in fix\n", total_count, used_count))
-        ).map_err(|e| GpsError::Io(e))?;
+        ).map_err(GpsError::Io)?;
 
         // Group by constellation and show summary
         let grouped = data.satellites_by_constellation();
+        let averages = data.average_used_snr_by_constellation();
         for (constellation, satellites) in grouped {
             let used_in_constellation = satellites.iter().filter(|s| s.used).count();
+            let snr = averages.get(&constellation)
+                .map_or(String::new(), |snr| format!(", avg {:.0} dB", snr));
             execute!(
                 stdout,
-                Print(format!("  {}: {}/{} used\n", constellation, used_in_constellation, satellites.len()))
-            ).map_err(|e| GpsError::Io(e))?;
+                Print(format!("  {}: {}/{} used{}\n", constellation, used_in_constellation, satellites.len(), snr))
+            ).map_err(GpsError::Io)?;
         }
 
-        execute!(stdout, Print("\n")).map_err(|e| GpsError::Io(e))?;
+        execute!(stdout, Print("\n")).map_err(GpsError::Io)?;
         Ok(())
     }
 
@@ -105,18 +181,27 @@ impl TerminalDisplay {
             Print("=".repeat(60)),
             Print("\n"),
             ResetColor
-        ).map_err(|e| GpsError::Io(e))?;
+        ).map_err(GpsError::Io)?;
+
+        // Connection health, so stale data isn't mistaken for live data
+        let (status_color, status_text) = Self::connection_status(data);
+        execute!(
+            stdout,
+            SetForegroundColor(status_color),
+            Print(format!("STATUS: {}\n", status_text)),
+            ResetColor
+        ).map_err(GpsError::Io)?;
 
         // Timestamp and source
         let timestamp_str = match data.timestamp {
-            Some(ts) => ts.format("%Y-%m-%d %H:%M:%S UTC").to_string(),
+            Some(ts) => self.time_zone.format_with_zone(&ts, "%Y-%m-%d %H:%M:%S"),
             None => "No data received".to_string(),
         };
         let source_str = data.source.as_deref().unwrap_or("Unknown");
         execute!(
             stdout,
             Print(format!("Last Update: {} ({})\n\n", timestamp_str, source_str))
-        ).map_err(|e| GpsError::Io(e))?;
+        ).map_err(GpsError::Io)?;
 
         // Position section
         self.render_position_section(stdout, data)?;
@@ -146,42 +231,83 @@ impl TerminalDisplay {
             Print("Press Ctrl+C to exit"),
             Print("\n"),
             ResetColor
-        ).map_err(|e| GpsError::Io(e))?;
+        ).map_err(GpsError::Io)?;
 
         Ok(())
     }
 
+    fn connection_status(data: &GpsData) -> (Color, String) {
+        match &data.connection {
+            ConnectionStatus::Connected => match data.age_seconds() {
+                Some(age) if age >= STALE_DATA_SECS => (Color::Yellow, format!("Connected, no data for {}s", age)),
+                None => (Color::Yellow, "Connected, waiting for data".to_string()),
+                Some(_) => (Color::Green, "Connected".to_string()),
+            },
+            status @ (ConnectionStatus::Connecting | ConnectionStatus::Reconnecting { .. }) => {
+                (Color::Yellow, status.description())
+            }
+            status => (Color::Red, status.description()),
+        }
+    }
+
     fn render_position_section(&self, stdout: &mut impl Write, data: &GpsData) -> Result<()> {
         execute!(
             stdout,
             SetForegroundColor(Color::Yellow),
             Print("POSITION:\n"),
             ResetColor
-        ).map_err(|e| GpsError::Io(e))?;
+        ).map_err(GpsError::Io)?;
 
         execute!(
             stdout,
-            Print(format!("  Latitude:  {}\n", GpsData::format_coordinate(data.latitude)))
-        ).map_err(|e| GpsError::Io(e))?;
+            Print(format!("  Latitude:  {}\n", GpsData::format_coordinate(data.latitude, self.coordinate_decimals)))
+        ).map_err(GpsError::Io)?;
 
         execute!(
             stdout,
-            Print(format!("  Longitude: {}\n", GpsData::format_coordinate(data.longitude)))
-        ).map_err(|e| GpsError::Io(e))?;
+            Print(format!("  Longitude: {}\n", GpsData::format_coordinate(data.longitude, self.coordinate_decimals)))
+        ).map_err(GpsError::Io)?;
+
+        if let (CoordinateFormat::Utm | CoordinateFormat::Mgrs, Some(lat), Some(lon)) = (self.coordinate_format, data.latitude, data.longitude) {
+            execute!(
+                stdout,
+                Print(format!("  {:<10} {}\n", format!("{}:", self.coordinate_format.name()), self.coordinate_format.format_position(lat, lon, self.coordinate_decimals)))
+            ).map_err(GpsError::Io)?;
+        }
 
+        let altitude = data.altitude_from(self.altitude_source);
+        let note = match altitude {
+            Some((_, AltitudeSource::Barometric)) => " (baro)",
+            Some((_, AltitudeSource::Gps)) if data.is_2d_fix() => " (2D)",
+            _ => "",
+        };
         execute!(
             stdout,
-            Print(format!("  Altitude:  {}\n", GpsData::format_value(data.altitude, "m")))
-        ).map_err(|e| GpsError::Io(e))?;
+            Print(format!(
+                "  Altitude:  {}{}\n",
+                GpsData::format_value(
+                    altitude.map(|(alt, _)| format!("{:.1}", self.distance_units.altitude_from_meters(alt))),
+                    self.distance_units.altitude_label()
+                ),
+                note
+            ))
+        ).map_err(GpsError::Io)?;
 
         if let Some(acc) = data.accuracy {
             execute!(
                 stdout,
                 Print(format!("  Accuracy:  {:>12.1} m\n", acc))
-            ).map_err(|e| GpsError::Io(e))?;
+            ).map_err(GpsError::Io)?;
         }
 
-        execute!(stdout, Print("\n")).map_err(|e| GpsError::Io(e))?;
+        if let Some(acc) = data.vertical_accuracy {
+            execute!(
+                stdout,
+                Print(format!("  Vert. Acc: {:>12.1} m\n", acc))
+            ).map_err(GpsError::Io)?;
+        }
+
+        execute!(stdout, Print("\n")).map_err(GpsError::Io)?;
         Ok(())
     }
 
@@ -191,17 +317,20 @@ impl TerminalDisplay {
             SetForegroundColor(Color::Cyan),
             Print("MOVEMENT:\n"),
             ResetColor
-        ).map_err(|e| GpsError::Io(e))?;
+        ).map_err(GpsError::Io)?;
 
         execute!(
             stdout,
-            Print(format!("  Speed:     {}\n", GpsData::format_value(data.speed, "km/h")))
-        ).map_err(|e| GpsError::Io(e))?;
+            Print(format!(
+                "  Speed:     {}\n",
+                GpsData::format_value(data.speed.map(|kmh| format!("{:.1}", self.speed_unit.from_kmh(kmh))), self.speed_unit.label())
+            ))
+        ).map_err(GpsError::Io)?;
 
         execute!(
             stdout,
             Print(format!("  Course:    {}\n\n", GpsData::format_value(data.course, "°")))
-        ).map_err(|e| GpsError::Io(e))?;
+        ).map_err(GpsError::Io)?;
 
         Ok(())
     }
@@ -212,23 +341,23 @@ impl TerminalDisplay {
             SetForegroundColor(Color::Magenta),
             Print("QUALITY:\n"),
             ResetColor
-        ).map_err(|e| GpsError::Io(e))?;
+        ).map_err(GpsError::Io)?;
 
         execute!(
             stdout,
             Print(format!("  Satellites: {}\n", GpsData::format_value(data.satellites, "")))
-        ).map_err(|e| GpsError::Io(e))?;
+        ).map_err(GpsError::Io)?;
 
         execute!(
             stdout,
             Print(format!("  HDOP:       {}\n", GpsData::format_value(data.hdop, "")))
-        ).map_err(|e| GpsError::Io(e))?;
+        ).map_err(GpsError::Io)?;
 
         let fix_type = data.get_fix_description();
         execute!(
             stdout,
             Print(format!("  Fix Type:   {:>11}\n\n", fix_type))
-        ).map_err(|e| GpsError::Io(e))?;
+        ).map_err(GpsError::Io)?;
 
         Ok(())
     }
@@ -239,7 +368,7 @@ impl TerminalDisplay {
             SetForegroundColor(Color::Blue),
             Print("RAW DATA:\n"),
             ResetColor
-        ).map_err(|e| GpsError::Io(e))?;
+        ).map_err(GpsError::Io)?;
 
         let raw_display = if data.raw_data.is_empty() {
             "No data"
@@ -250,7 +379,7 @@ impl TerminalDisplay {
         execute!(
             stdout,
             Print(format!("  {}\n\n", raw_display))
-        ).map_err(|e| GpsError::Io(e))?;
+        ).map_err(GpsError::Io)?;
 
         Ok(())
     }
@@ -261,3 +390,125 @@ impl Default for TerminalDisplay {
         Self::new()
     }
 }
+
+/// Run the monitor on the configured source with the dashboard drawn to
+/// `out` until `shutdown` resolves, reconnecting whenever the source is lost.
+/// Returns the data as last drawn.
+pub async fn run_terminal<W: Write, S: Future<Output = ()>>(config: &GpsConfig, out: &mut W, shutdown: S) -> Result<GpsData> {
+    run_terminal_until(config, out, shutdown, |_| false).await
+}
+
+/// `run_terminal`, also stopping once `done` is true of the data drawn
+async fn run_terminal_until<W, S, F>(config: &GpsConfig, out: &mut W, shutdown: S, done: F) -> Result<GpsData>
+where
+    W: Write,
+    S: Future<Output = ()>,
+    F: Fn(&GpsData) -> bool,
+{
+    config.validate()?;
+
+    let mut monitor = GpsMonitor::new();
+    let satellite_timeout = config.satellite_timeout_secs;
+    monitor.set_satellite_max_age((satellite_timeout > 0).then(|| Duration::from_secs(satellite_timeout)));
+    if config.data_log.enabled {
+        DataLogger::new(config.data_log.clone()).attach(&mut monitor);
+    }
+
+    spawn_connection(&monitor, config);
+
+    let display = TerminalDisplay::from_config(config);
+    execute!(out, Hide, DisableLineWrap).map_err(GpsError::Io)?;
+    tokio::pin!(shutdown);
+    let result = loop {
+        let data = monitor.get_data();
+        if let Err(e) = display.draw(out, &data) {
+            break Err(e);
+        }
+        if done(&data) {
+            break Ok(());
+        }
+        tokio::select! {
+            _ = &mut shutdown => break Ok(()),
+            _ = sleep(REFRESH_INTERVAL) => {}
+        }
+    };
+
+    monitor.stop();
+    execute!(out, Show, EnableLineWrap).map_err(GpsError::Io)?;
+    result.map(|()| monitor.get_data())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::AsyncWriteExt;
+
+    #[tokio::test]
+    async fn test_terminal_mode_shows_stand_in_gpsd() {
+        // A stand-in gpsd with one fix
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            socket.write_all(b"{\"class\":\"TPV\",\"mode\":3,\"lat\":42.5,\"lon\":-71.1}\n").await.unwrap();
+            tokio::time::sleep(Duration::from_secs(5)).await;
+        });
+
+        let config = GpsConfig {
+            source_type: "gpsd".to_string(),
+            gpsd_host: Some("127.0.0.1".to_string()),
+            gpsd_port: Some(port),
+            ..GpsConfig::default()
+        };
+        // The timeout only bounds a failing run; a passing one stops once the fix is drawn
+        let mut out = Vec::new();
+        let data = run_terminal_until(&config, &mut out, sleep(Duration::from_secs(30)), |data| data.has_fix())
+            .await
+            .unwrap();
+
+        assert_eq!(data.latitude, Some(42.5));
+        let text = String::from_utf8_lossy(&out);
+        assert!(text.contains("GPS Monitor"));
+        assert!(text.contains("STATUS: Connected"));
+    }
+
+    #[test]
+    fn test_altitude_follows_configured_source() {
+        let mut data = GpsData::new();
+        data.altitude = Some(120.0);
+        data.baro_altitude = Some(118.5);
+
+        let render = |config: &GpsConfig| {
+            let mut out = Vec::new();
+            TerminalDisplay::from_config(config).render_display(&mut out, &data).unwrap();
+            String::from_utf8_lossy(&out).into_owned()
+        };
+        assert!(render(&GpsConfig::default()).contains("120.0 m\n"));
+        let baro = GpsConfig { altitude_source: AltitudeSource::Barometric, ..GpsConfig::default() };
+        assert!(render(&baro).contains("118.5 m (baro)"));
+    }
+
+    #[test]
+    fn test_units_follow_config() {
+        let mut data = GpsData::new();
+        data.altitude = Some(30.48);
+        data.speed = Some(100.0);
+
+        let render = |config: &GpsConfig| {
+            let mut out = Vec::new();
+            TerminalDisplay::from_config(config).render_display(&mut out, &data).unwrap();
+            String::from_utf8_lossy(&out).into_owned()
+        };
+        let metric = render(&GpsConfig::default());
+        assert!(metric.contains("30.5 m\n"), "{}", metric);
+        assert!(metric.contains("100.0 km/h\n"), "{}", metric);
+
+        let imperial = render(&GpsConfig { distance_units: DistanceUnits::Imperial, ..GpsConfig::default() });
+        assert!(imperial.contains("100.0 ft\n"), "{}", imperial);
+        assert!(imperial.contains("62.1 mph\n"), "{}", imperial);
+
+        // An explicit speed unit wins over the distance units
+        let knots = render(&GpsConfig { speed_unit: Some(DisplaySpeedUnit::Knots), ..GpsConfig::default() });
+        assert!(knots.contains("54.0 kn\n"), "{}", knots);
+    }
+}
@@ -14,6 +14,8 @@ pub enum GpsError {
     Parse(String),
     #[cfg(windows)]
     Windows(windows::core::Error),
+    #[cfg(all(target_os = "linux", feature = "geoclue"))]
+    DBus(zbus::Error),
     #[cfg(all(unix, not(target_os = "macos"), feature = "gui"))]
     Gui(eframe::Error),
     Other(String),
@@ -29,6 +31,8 @@ impl fmt::Display for GpsError {
             GpsError::Parse(msg) => write!(f, "Parse error: {}", msg),
             #[cfg(windows)]
             GpsError::Windows(e) => write!(f, "Windows error: {}", e),
+            #[cfg(all(target_os = "linux", feature = "geoclue"))]
+            GpsError::DBus(e) => write!(f, "DBus error: {}", e),
             #[cfg(all(unix, not(target_os = "macos"), feature = "gui"))]
             GpsError::Gui(e) => write!(f, "GUI error: {}", e),
             GpsError::Other(msg) => write!(f, "Error: {}", msg),
@@ -63,6 +67,13 @@ impl From<windows::core::Error> for GpsError {
     }
 }
 
+#[cfg(all(target_os = "linux", feature = "geoclue"))]
+impl From<zbus::Error> for GpsError {
+    fn from(error: zbus::Error) -> Self {
+        GpsError::DBus(error)
+    }
+}
+
 #[cfg(all(unix, not(target_os = "macos"), feature = "gui"))]
 impl From<eframe::Error> for GpsError {
     fn from(error: eframe::Error) -> Self {
@@ -0,0 +1,341 @@
+// src/gps/coordinates.rs v1
+//! Parsing of user-entered coordinates (decimal degrees, DM or DMS) and
+//! conversion to UTM and MGRS grid references
+
+use crate::error::{GpsError, Result};
+use crate::gps::data::GpsData;
+use serde::{Deserialize, Serialize};
+
+/// Parse a "lat, lon" pair. Each half may be decimal degrees ("42.4389"),
+/// degrees and decimal minutes ("42 26.33") or degrees, minutes and seconds
+/// ("42°26'20\""), signed or with an N/S/E/W hemisphere letter before or after.
+/// The comma may be omitted when hemisphere letters separate the two halves.
+pub fn parse_lat_lon(input: &str) -> Result<(f64, f64)> {
+    let (lat_str, lon_str) = split_pair(input.trim())
+        .ok_or_else(|| GpsError::Parse(format!("Expected \"lat, lon\", got \"{}\"", input.trim())))?;
+
+    let lat = parse_component(lat_str, Axis::Latitude)?;
+    let lon = parse_component(lon_str, Axis::Longitude)?;
+    Ok((lat, lon))
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Axis {
+    Latitude,
+    Longitude,
+}
+
+impl Axis {
+    fn name(&self) -> &'static str {
+        match self {
+            Axis::Latitude => "latitude",
+            Axis::Longitude => "longitude",
+        }
+    }
+
+    fn limit(&self) -> f64 {
+        match self {
+            Axis::Latitude => 90.0,
+            Axis::Longitude => 180.0,
+        }
+    }
+}
+
+fn split_pair(input: &str) -> Option<(&str, &str)> {
+    if let Some((lat, lon)) = input.split_once(',') {
+        return Some((lat, lon));
+    }
+
+    // "42 26 20 N 71 7 9 W": split after the latitude hemisphere letter
+    if let Some(pos) = input.find(['N', 'S', 'n', 's']) {
+        let (lat, lon) = input.split_at(pos + 1);
+        if !lon.trim().is_empty() && !lat.trim_end_matches(['N', 'S', 'n', 's']).trim().is_empty() {
+            return Some((lat, lon));
+        }
+    }
+
+    // Two plain decimal numbers separated by whitespace
+    let mut parts = input.split_whitespace();
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(lat), Some(lon), None) => Some((lat, lon)),
+        _ => None,
+    }
+}
+
+fn parse_component(raw: &str, axis: Axis) -> Result<f64> {
+    let invalid = || GpsError::Parse(format!("Invalid {}: \"{}\"", axis.name(), raw.trim()));
+
+    let mut text = raw.trim().to_uppercase();
+    let mut negative = false;
+
+    // Hemisphere letter at either end
+    let hemisphere = text.chars().next().filter(|c| c.is_ascii_alphabetic())
+        .or_else(|| text.chars().last().filter(|c| c.is_ascii_alphabetic()));
+    if let Some(letter) = hemisphere {
+        negative = match (axis, letter) {
+            (Axis::Latitude, 'N') | (Axis::Longitude, 'E') => false,
+            (Axis::Latitude, 'S') | (Axis::Longitude, 'W') => true,
+            _ => return Err(invalid()),
+        };
+        text = text.trim_matches(letter).to_string();
+    }
+
+    // A sign and a hemisphere letter together are ambiguous
+    let text = text.trim();
+    let (signed, text) = match text.strip_prefix('-') {
+        Some(rest) if hemisphere.is_none() => (true, rest),
+        Some(_) => return Err(invalid()),
+        None => (false, text),
+    };
+
+    let value = parse_unsigned(text, axis).ok_or_else(invalid)?;
+    Ok(if negative || signed { -value } else { value })
+}
+
+/// Degrees with optional minutes and seconds, separated by spaces or symbols
+fn parse_unsigned(text: &str, axis: Axis) -> Option<f64> {
+    let numbers: Vec<f64> = text
+        .split(|c: char| c.is_whitespace() || matches!(c, '°' | '\'' | '"' | '′' | '″'))
+        .filter(|part| !part.is_empty())
+        .map(|part| part.parse::<f64>().ok().filter(|n| n.is_finite() && *n >= 0.0))
+        .collect::<Option<_>>()?;
+
+    let value = match numbers.as_slice() {
+        [degrees] => *degrees,
+        [degrees, minutes] if *minutes < 60.0 => degrees + minutes / 60.0,
+        [degrees, minutes, seconds] if *minutes < 60.0 && *seconds < 60.0 => {
+            degrees + minutes / 60.0 + seconds / 3600.0
+        }
+        _ => return None,
+    };
+
+    (value <= axis.limit()).then_some(value)
+}
+
+/// How positions are shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CoordinateFormat {
+    #[default]
+    Decimal,
+    Utm,
+    Mgrs,
+}
+
+impl CoordinateFormat {
+    pub const ALL: [CoordinateFormat; 3] = [CoordinateFormat::Decimal, CoordinateFormat::Utm, CoordinateFormat::Mgrs];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CoordinateFormat::Decimal => "Decimal degrees",
+            CoordinateFormat::Utm => "UTM",
+            CoordinateFormat::Mgrs => "MGRS",
+        }
+    }
+
+    /// Format a position, falling back to decimal degrees with `decimals`
+    /// places outside the UTM grid (the polar caps)
+    pub fn format_position(&self, lat: f64, lon: f64, decimals: usize) -> String {
+        let grid = match self {
+            CoordinateFormat::Decimal => None,
+            CoordinateFormat::Utm => Utm::from_lat_lon(lat, lon).map(|utm| utm.to_string()),
+            CoordinateFormat::Mgrs => Utm::from_lat_lon(lat, lon).map(|utm| utm.to_mgrs()),
+        };
+        grid.unwrap_or_else(|| GpsData::format_lat_lon(lat, lon, decimals))
+    }
+}
+
+/// WGS84 ellipsoid
+const WGS84_A: f64 = 6_378_137.0;
+const WGS84_F: f64 = 1.0 / 298.257_223_563;
+
+/// UTM scale factor on the central meridian
+const UTM_K0: f64 = 0.9996;
+const UTM_FALSE_EASTING: f64 = 500_000.0;
+const UTM_FALSE_NORTHING_SOUTH: f64 = 10_000_000.0;
+
+/// Latitude bands C to X, 8° each from 80°S (X is 12°, up to 84°N)
+const LATITUDE_BANDS: &[u8] = b"CDEFGHJKLMNPQRSTUVWX";
+
+/// MGRS 100 km square letters; I and O are never used
+const MGRS_COLUMN_SETS: [&[u8]; 3] = [b"ABCDEFGH", b"JKLMNPQR", b"STUVWXYZ"];
+const MGRS_ROW_LETTERS: &[u8] = b"ABCDEFGHJKLMNPQRSTUV";
+
+/// A position on the Universal Transverse Mercator grid
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Utm {
+    pub zone: u8,  // 1 to 60
+    pub band: char,  // Latitude band letter, N and above in the northern hemisphere
+    pub easting: f64,  // Metres
+    pub northing: f64,  // Metres, with a 10,000 km false northing in the south
+}
+
+impl Utm {
+    /// Convert WGS84 latitude/longitude to UTM, including the Norway and
+    /// Svalbard zone exceptions. None outside 80°S to 84°N, which UTM
+    /// doesn't cover (the poles use UPS instead).
+    pub fn from_lat_lon(lat: f64, lon: f64) -> Option<Utm> {
+        if !(-80.0..=84.0).contains(&lat) || !(-180.0..=180.0).contains(&lon) {
+            return None;
+        }
+
+        let zone = utm_zone(lat, lon);
+        let band_index = (((lat + 80.0) / 8.0).floor() as usize).min(LATITUDE_BANDS.len() - 1);
+        let band = LATITUDE_BANDS[band_index] as char;
+
+        // Krüger series, accurate to well under a millimetre within a zone
+        let n = WGS84_F / (2.0 - WGS84_F);
+        let rectifying_radius = WGS84_A / (1.0 + n) * (1.0 + n.powi(2) / 4.0 + n.powi(4) / 64.0);
+        let alpha = [
+            n / 2.0 - 2.0 * n.powi(2) / 3.0 + 5.0 * n.powi(3) / 16.0,
+            13.0 * n.powi(2) / 48.0 - 3.0 * n.powi(3) / 5.0,
+            61.0 * n.powi(3) / 240.0,
+        ];
+
+        let central_meridian = (zone as f64 - 1.0) * 6.0 - 180.0 + 3.0;
+        let phi = lat.to_radians();
+        let lambda = (lon - central_meridian).to_radians();
+
+        let e_term = 2.0 * n.sqrt() / (1.0 + n);
+        let t = (phi.sin().atanh() - e_term * (e_term * phi.sin()).atanh()).sinh();
+        let xi = (t / lambda.cos()).atan();
+        let eta = (lambda.sin() / (1.0 + t * t).sqrt()).atanh();
+
+        let (mut x, mut y) = (eta, xi);
+        for (j, a) in alpha.iter().enumerate() {
+            let k = 2.0 * (j as f64 + 1.0);
+            x += a * (k * xi).cos() * (k * eta).sinh();
+            y += a * (k * xi).sin() * (k * eta).cosh();
+        }
+
+        let easting = UTM_FALSE_EASTING + UTM_K0 * rectifying_radius * x;
+        let mut northing = UTM_K0 * rectifying_radius * y;
+        if lat < 0.0 {
+            northing += UTM_FALSE_NORTHING_SOUTH;
+        }
+
+        Some(Utm { zone, band, easting, northing })
+    }
+
+    /// MGRS grid reference to 1 m, e.g. "18SUJ2347906481". Digits are
+    /// truncated rather than rounded, so the reference names the square the
+    /// position is in.
+    pub fn to_mgrs(&self) -> String {
+        let column_letters = MGRS_COLUMN_SETS[(self.zone as usize - 1) % 3];
+        let column = ((self.easting / 100_000.0).floor() as usize).clamp(1, 8) - 1;
+
+        // Row letters repeat every 2,000 km and are offset by five in even zones
+        let mut row = (self.northing / 100_000.0).floor() as usize % MGRS_ROW_LETTERS.len();
+        if self.zone.is_multiple_of(2) {
+            row = (row + 5) % MGRS_ROW_LETTERS.len();
+        }
+
+        format!(
+            "{}{}{}{}{:05}{:05}",
+            self.zone,
+            self.band,
+            column_letters[column] as char,
+            MGRS_ROW_LETTERS[row] as char,
+            (self.easting.floor() as u64) % 100_000,
+            (self.northing.floor() as u64) % 100_000,
+        )
+    }
+}
+
+impl std::fmt::Display for Utm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{} {:.0}E {:.0}N", self.zone, self.band, self.easting, self.northing)
+    }
+}
+
+fn utm_zone(lat: f64, lon: f64) -> u8 {
+    // Southwest Norway is widened into zone 32
+    if (56.0..64.0).contains(&lat) && (3.0..12.0).contains(&lon) {
+        return 32;
+    }
+    // Svalbard uses only the odd zones 31 to 37
+    if (72.0..=84.0).contains(&lat) && (0.0..42.0).contains(&lon) {
+        return match lon {
+            lon if lon < 9.0 => 31,
+            lon if lon < 21.0 => 33,
+            lon if lon < 33.0 => 35,
+            _ => 37,
+        };
+    }
+    (((lon + 180.0) / 6.0).floor() as u8 + 1).min(60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close((lat, lon): (f64, f64), (expected_lat, expected_lon): (f64, f64)) {
+        assert!((lat - expected_lat).abs() < 1e-6, "lat {} != {}", lat, expected_lat);
+        assert!((lon - expected_lon).abs() < 1e-6, "lon {} != {}", lon, expected_lon);
+    }
+
+    #[test]
+    fn test_parse_lat_lon_formats() {
+        assert_close(parse_lat_lon("42.438878, -71.119277").unwrap(), (42.438878, -71.119277));
+        assert_close(parse_lat_lon("42.5 -71.25").unwrap(), (42.5, -71.25));
+        assert_close(parse_lat_lon("42.5N, 71.25W").unwrap(), (42.5, -71.25));
+        assert_close(parse_lat_lon("S 33 52.2, E 151 12.6").unwrap(), (-33.87, 151.21));
+        assert_close(
+            parse_lat_lon("42°26'20\"N 71°07'09\"W").unwrap(),
+            (42.0 + 26.0 / 60.0 + 20.0 / 3600.0, -(71.0 + 7.0 / 60.0 + 9.0 / 3600.0)),
+        );
+    }
+
+    #[test]
+    fn test_parse_lat_lon_rejects_bad_input() {
+        assert!(parse_lat_lon("").is_err());
+        assert!(parse_lat_lon("42.5").is_err());
+        assert!(parse_lat_lon("91, 0").is_err());
+        assert!(parse_lat_lon("0, 181").is_err());
+        assert!(parse_lat_lon("42 61, 71").is_err());
+        assert!(parse_lat_lon("42.5E, 71W").is_err());
+        assert!(parse_lat_lon("abc, def").is_err());
+    }
+
+    fn assert_utm(lat: f64, lon: f64, zone: u8, band: char, easting: f64, northing: f64) {
+        let utm = Utm::from_lat_lon(lat, lon).unwrap();
+        assert_eq!((utm.zone, utm.band), (zone, band));
+        assert!((utm.easting - easting).abs() < 0.01, "easting {} != {}", utm.easting, easting);
+        assert!((utm.northing - northing).abs() < 0.01, "northing {} != {}", utm.northing, northing);
+    }
+
+    #[test]
+    fn test_utm_reference_points() {
+        // Equator on the prime meridian, the textbook zone 31 edge case
+        assert_utm(0.0, 0.0, 31, 'N', 166_021.44, 0.0);
+        // CN Tower, Toronto
+        assert_utm(43.642567, -79.387139, 17, 'T', 630_084.30, 4_833_438.59);
+        // Washington Monument
+        assert_utm(38.889484, -77.035278, 18, 'S', 323_479.93, 4_306_481.42);
+        // Sydney Opera House, southern hemisphere false northing
+        assert_utm(-33.856784, 151.215297, 56, 'H', 334_900.26, 6_252_290.52);
+        // Bergen is in the widened Norway zone 32, not 31
+        assert_utm(60.0, 5.0, 32, 'V', 276_979.93, 6_658_157.20);
+
+        assert!(Utm::from_lat_lon(85.0, 0.0).is_none());
+        assert!(Utm::from_lat_lon(-80.5, 0.0).is_none());
+    }
+
+    #[test]
+    fn test_mgrs_references() {
+        let mgrs = |lat, lon| Utm::from_lat_lon(lat, lon).unwrap().to_mgrs();
+        assert_eq!(mgrs(0.0, 0.0), "31NAA6602100000");
+        assert_eq!(mgrs(38.889484, -77.035278), "18SUJ2347906481");
+        assert_eq!(mgrs(43.642567, -79.387139), "17TPJ3008433438");
+        assert_eq!(mgrs(-33.856784, 151.215297), "56HLH3490052290");
+    }
+
+    #[test]
+    fn test_format_position() {
+        assert_eq!(CoordinateFormat::Decimal.format_position(38.889484, -77.035278, 6), "38.889484, -77.035278");
+        assert_eq!(CoordinateFormat::Utm.format_position(38.889484, -77.035278, 6), "18S 323480E 4306481N");
+        assert_eq!(CoordinateFormat::Mgrs.format_position(38.889484, -77.035278, 6), "18SUJ2347906481");
+        // No UTM grid at the poles
+        assert_eq!(CoordinateFormat::Mgrs.format_position(89.5, 10.0, 3), "89.500, 10.000");
+    }
+}
@@ -2,9 +2,257 @@
 //! GPS data structures and utilities
 
 use chrono::{DateTime, Utc};
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::ops::RangeInclusive;
 
-#[derive(Debug, Clone, Default)]
+/// Decimal places shown for coordinates unless configured otherwise (~0.1 m)
+pub const DEFAULT_COORDINATE_DECIMALS: usize = 6;
+
+/// Supported coordinate precision, from ~100 m to sub-millimetre for RTK
+pub const COORDINATE_DECIMALS_RANGE: RangeInclusive<usize> = 3..=9;
+
+/// Meters in one international foot
+pub const METERS_PER_FOOT: f64 = 0.3048;
+
+/// A GPS time reading this far from the established clock offset means the
+/// system clock was stepped, so the offset is measured again
+pub const CLOCK_JUMP_THRESHOLD_MS: i64 = 2000;
+
+/// Clock offset readings averaged for the displayed clock drift
+pub const CLOCK_DRIFT_SAMPLES: usize = 30;
+
+/// Fixes from fewer satellites than this are flagged unless configured otherwise
+pub const DEFAULT_MIN_SATELLITES_USED: usize = 5;
+
+/// Fields not updated for this long are shown as stale
+pub const FIELD_STALE_SECS: i64 = 5;
+
+/// Sliding window the measured update rate is averaged over
+pub const UPDATE_RATE_WINDOW_SECS: i64 = 5;
+
+/// A measured rate below this share of the expected rate counts as low
+pub const LOW_UPDATE_RATE_FRACTION: f64 = 0.8;
+
+/// Receiver text (TXT) messages kept for display
+pub const MAX_TEXT_MESSAGES: usize = 20;
+
+/// Error-level receiver messages count as an active fault for this long;
+/// antenna faults last until the receiver reports the antenna OK again
+pub const TEXT_ERROR_SECS: i64 = 60;
+
+/// Signal health weights (summing to 100) and the values that earn full marks.
+/// Each part scales linearly between its worst and best value.
+const HEALTH_SATELLITE_POINTS: f64 = 40.0;
+const HEALTH_FULL_SATELLITES: f64 = 10.0;  // Used satellites, from 0
+const HEALTH_HDOP_POINTS: f64 = 30.0;
+const HEALTH_HDOP_RANGE: (f64, f64) = (1.0, 10.0);  // Best, worst
+const HEALTH_SNR_POINTS: f64 = 30.0;
+const HEALTH_SNR_RANGE: (f64, f64) = (20.0, 45.0);  // Average used SNR in dB, worst to best
+
+/// Signal quality bucket for a satellite's SNR, best first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SignalQuality {
+    Excellent,
+    Good,
+    Fair,
+    Poor,
+    VeryPoor,
+    Unknown,
+}
+
+impl SignalQuality {
+    pub fn description(&self) -> &'static str {
+        match self {
+            SignalQuality::Excellent => "Excellent",
+            SignalQuality::Good => "Good",
+            SignalQuality::Fair => "Fair",
+            SignalQuality::Poor => "Poor",
+            SignalQuality::VeryPoor => "Very Poor",
+            SignalQuality::Unknown => "Unknown",
+        }
+    }
+}
+
+/// Minimum SNR (dB) for each quality level. Receivers that report C/N0 on a
+/// different scale can shift these.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SnrThresholds {
+    pub excellent: f32,
+    pub good: f32,
+    pub fair: f32,
+    pub poor: f32,
+}
+
+impl Default for SnrThresholds {
+    fn default() -> Self {
+        Self {
+            excellent: 40.0,
+            good: 35.0,
+            fair: 25.0,
+            poor: 15.0,
+        }
+    }
+}
+
+impl SnrThresholds {
+    pub fn quality(&self, snr: Option<f32>) -> SignalQuality {
+        match snr {
+            Some(snr) if snr >= self.excellent => SignalQuality::Excellent,
+            Some(snr) if snr >= self.good => SignalQuality::Good,
+            Some(snr) if snr >= self.fair => SignalQuality::Fair,
+            Some(snr) if snr >= self.poor => SignalQuality::Poor,
+            Some(_) => SignalQuality::VeryPoor,
+            None => SignalQuality::Unknown,
+        }
+    }
+
+    /// Thresholds must decrease from excellent to poor
+    pub fn is_valid(&self) -> bool {
+        self.excellent > self.good && self.good > self.fair && self.fair > self.poor
+    }
+}
+
+/// Which altitude to display when both GPS and barometric values are available
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AltitudeSource {
+    #[default]
+    Gps,
+    Barometric,
+}
+
+impl AltitudeSource {
+    pub const ALL: [AltitudeSource; 2] = [AltitudeSource::Gps, AltitudeSource::Barometric];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            AltitudeSource::Gps => "GPS",
+            AltitudeSource::Barometric => "Barometric",
+        }
+    }
+}
+
+/// North reference for displayed headings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NorthReference {
+    #[default]
+    True,
+    Magnetic,
+}
+
+impl NorthReference {
+    pub const ALL: [NorthReference; 2] = [NorthReference::True, NorthReference::Magnetic];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            NorthReference::True => "True north",
+            NorthReference::Magnetic => "Magnetic north",
+        }
+    }
+
+    /// Short label shown after a heading
+    pub fn suffix(&self) -> &'static str {
+        match self {
+            NorthReference::True => "T",
+            NorthReference::Magnetic => "M",
+        }
+    }
+}
+
+/// Message type of a TXT sentence
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TextSeverity {
+    Error,
+    Warning,
+    Notice,
+    User,
+}
+
+impl TextSeverity {
+    /// From the TXT message type field: 00 error, 01 warning, 02 notice, 07 user
+    pub fn from_code(code: u8) -> Self {
+        match code {
+            0 => TextSeverity::Error,
+            1 => TextSeverity::Warning,
+            7 => TextSeverity::User,
+            _ => TextSeverity::Notice,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TextSeverity::Error => "Error",
+            TextSeverity::Warning => "Warning",
+            TextSeverity::Notice => "Notice",
+            TextSeverity::User => "User",
+        }
+    }
+}
+
+/// Informational text sent by the receiver (boot banner, antenna status, ...)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TextMessage {
+    pub severity: TextSeverity,
+    pub text: String,
+    pub received: DateTime<Utc>,
+}
+
+impl TextMessage {
+    /// Antenna status report, Some(true) for a fault (open or short circuit)
+    pub fn antenna_fault(&self) -> Option<bool> {
+        let text = self.text.to_uppercase();
+        if !text.contains("ANT") {
+            return None;
+        }
+        if text.contains("OPEN") || text.contains("SHORT") {
+            Some(true)
+        } else if text.contains("OK") {
+            Some(false)
+        } else {
+            None
+        }
+    }
+}
+
+/// State of the connection to the GPS source, as seen by the displays
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConnectionStatus {
+    #[default]
+    Disconnected,
+    Connecting,
+    Connected,
+    Reconnecting { attempt: u32 },
+    Lost(String),  // Why the source stopped
+}
+
+impl ConnectionStatus {
+    pub fn description(&self) -> String {
+        match self {
+            ConnectionStatus::Disconnected => "Disconnected".to_string(),
+            ConnectionStatus::Connecting => "Connecting...".to_string(),
+            ConnectionStatus::Connected => "Connected".to_string(),
+            ConnectionStatus::Reconnecting { attempt } => format!("Reconnecting (attempt {})...", attempt),
+            ConnectionStatus::Lost(reason) => format!("Connection lost: {}", reason),
+        }
+    }
+}
+
+/// Fix validity as flagged by the receiver in RMC (status and mode indicator)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FixValidity {
+    Valid,
+    Estimated,  // Dead reckoning
+    Invalid,
+    Unknown,    // No RMC status seen
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct SatelliteInfo {
     pub prn: u8,           // Satellite PRN/ID number
     pub elevation: Option<f32>,  // Elevation angle in degrees
@@ -12,6 +260,8 @@ pub struct SatelliteInfo {
     pub snr: Option<f32>,        // Signal-to-noise ratio in dB
     pub used: bool,              // Whether satellite is used in fix
     pub constellation: String,   // GPS, GLONASS, GALILEO, BEIDOU, etc.
+    pub last_seen: DateTime<Utc>,  // When the receiver last reported this satellite
+    pub source: Option<String>,    // Source that reported it, as in GpsData::source
 }
 
 impl SatelliteInfo {
@@ -23,6 +273,8 @@ impl SatelliteInfo {
             snr: None,
             used: false,
             constellation: Self::determine_constellation(prn),
+            last_seen: Utc::now(),
+            source: None,
         }
     }
 
@@ -39,35 +291,289 @@ impl SatelliteInfo {
         }
     }
 
+    /// Whether the receiver uses this satellite and it sits at or above the
+    /// elevation mask. Satellites with unknown elevation are not masked.
+    pub fn used_above_mask(&self, mask_deg: f32) -> bool {
+        self.used && self.elevation.is_none_or(|el| el >= mask_deg)
+    }
+
+    /// Signal quality using the default thresholds
     pub fn signal_strength_description(&self) -> String {
-        match self.snr {
-            Some(snr) if snr >= 40.0 => "Excellent".to_string(),
-            Some(snr) if snr >= 35.0 => "Good".to_string(),
-            Some(snr) if snr >= 25.0 => "Fair".to_string(),
-            Some(snr) if snr >= 15.0 => "Poor".to_string(),
-            Some(_) => "Very Poor".to_string(),
-            None => "Unknown".to_string(),
+        SnrThresholds::default().quality(self.snr).description().to_string()
+    }
+}
+
+/// Counts events over a sliding window to measure an update rate
+#[derive(Debug, Clone, Default)]
+pub struct RateMeter {
+    started: Option<DateTime<Utc>>,
+    events: VecDeque<DateTime<Utc>>,
+}
+
+impl RateMeter {
+    fn window() -> chrono::Duration {
+        chrono::Duration::seconds(UPDATE_RATE_WINDOW_SECS)
+    }
+
+    pub fn record(&mut self, time: DateTime<Utc>) {
+        self.started.get_or_insert(time);
+        self.events.push_back(time);
+        while self.events.front().is_some_and(|&t| t <= time - Self::window()) {
+            self.events.pop_front();
+        }
+    }
+
+    /// Events per second over the window ending at `now`. `None` until the
+    /// meter has been running for a whole window.
+    pub fn rate(&self, now: DateTime<Utc>) -> Option<f64> {
+        let window_start = now - Self::window();
+        if self.started? > window_start {
+            return None;
+        }
+        let count = self.events.iter().filter(|&&t| t > window_start && t <= now).count();
+        Some(count as f64 / UPDATE_RATE_WINDOW_SECS as f64)
+    }
+
+    pub fn reset(&mut self) {
+        self.started = None;
+        self.events.clear();
+    }
+}
+
+/// A clock offset replaced after a jump, waiting for the monitor to report it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClockJump {
+    pub from: chrono::Duration,
+    pub to: chrono::Duration,
+}
+
+impl std::fmt::Display for ClockJump {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Clock jump detected: offset changed from {:.1} s to {:.1} s",
+            self.from.num_milliseconds() as f64 / 1000.0,
+            self.to.num_milliseconds() as f64 / 1000.0
+        )
+    }
+}
+
+/// Rolling average of GPS time minus system time over the last
+/// `CLOCK_DRIFT_SAMPLES` readings. Unlike the held clock offset it follows
+/// every reading, so it shows the system clock drifting between jumps.
+#[derive(Debug, Clone, Default)]
+pub struct ClockDrift {
+    samples_ms: VecDeque<i64>,
+}
+
+impl ClockDrift {
+    /// Add a reading of `gps_time`, received when the system clock read `system_time`
+    pub fn record(&mut self, gps_time: DateTime<Utc>, system_time: DateTime<Utc>) {
+        self.samples_ms.push_back((gps_time - system_time).num_milliseconds());
+        if self.samples_ms.len() > CLOCK_DRIFT_SAMPLES {
+            self.samples_ms.pop_front();
         }
     }
+
+    /// Average GPS time minus system time; positive when the system clock is behind
+    pub fn estimate(&self) -> Option<chrono::Duration> {
+        let count = self.samples_ms.len() as i64;
+        (count > 0).then(|| chrono::Duration::milliseconds(self.samples_ms.iter().sum::<i64>() / count))
+    }
+
+    pub fn reset(&mut self) {
+        self.samples_ms.clear();
+    }
+}
+
+/// Fix events kept for the events table and export, oldest dropped first
+pub const MAX_FIX_EVENTS: usize = 500;
+
+/// What changed about the fix
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FixEventKind {
+    Acquired,
+    Lost,
+    ModeChanged,
+}
+
+impl FixEventKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            FixEventKind::Acquired => "Fix acquired",
+            FixEventKind::Lost => "Fix lost",
+            FixEventKind::ModeChanged => "Mode changed",
+        }
+    }
+}
+
+/// One fix acquisition, loss or mode change
+#[derive(Debug, Clone, PartialEq)]
+pub struct FixEvent {
+    pub kind: FixEventKind,
+    pub time: DateTime<Utc>,              // System time the sentence was received
+    pub gps_time: Option<DateTime<Utc>>,  // Last GPS time known at that point
+    pub mode: Option<u8>,                 // Fix mode afterwards, where the source reports one
+    pub outage: Option<chrono::Duration>, // For an acquisition: how long the fix was lost
 }
 
+/// Transitions of `GpsData::has_valid_fix` and of the fix mode, for
+/// tracking down intermittent reception. A fix missing from the start
+/// isn't an outage; only one that was lost is timed.
 #[derive(Debug, Clone, Default)]
+pub struct FixEventLog {
+    events: VecDeque<FixEvent>,
+    has_fix: Option<bool>,
+    mode: Option<u8>,
+    lost_at: Option<DateTime<Utc>>,
+}
+
+impl FixEventLog {
+    /// Note the fix state after a sentence received at `time`, adding an
+    /// event if it changed
+    pub fn record(&mut self, has_fix: bool, mode: Option<u8>, time: DateTime<Utc>, gps_time: Option<DateTime<Utc>>) {
+        let kind = match self.has_fix {
+            Some(had_fix) if had_fix == has_fix => {
+                let mode_changed = has_fix && mode.is_some() && self.mode.is_some() && mode != self.mode;
+                mode_changed.then_some(FixEventKind::ModeChanged)
+            }
+            None if !has_fix => None,
+            _ if has_fix => Some(FixEventKind::Acquired),
+            _ => Some(FixEventKind::Lost),
+        };
+        self.has_fix = Some(has_fix);
+        if mode.is_some() {
+            self.mode = mode;
+        }
+
+        let Some(kind) = kind else {
+            return;
+        };
+        let outage = match kind {
+            FixEventKind::Lost => {
+                self.lost_at = Some(time);
+                None
+            }
+            FixEventKind::Acquired => self.lost_at.take().map(|lost_at| time - lost_at),
+            FixEventKind::ModeChanged => None,
+        };
+        self.events.push_back(FixEvent { kind, time, gps_time, mode, outage });
+        if self.events.len() > MAX_FIX_EVENTS {
+            self.events.pop_front();
+        }
+    }
+
+    /// Events so far, oldest first
+    pub fn events(&self) -> impl DoubleEndedIterator<Item = &FixEvent> {
+        self.events.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// How long the fix has been lost at `now`, while it still is
+    pub fn current_outage(&self, now: DateTime<Utc>) -> Option<chrono::Duration> {
+        self.lost_at.map(|lost_at| now - lost_at)
+    }
+
+    pub fn clear(&mut self) {
+        self.events.clear();
+    }
+
+    /// All events as CSV with a header line; outages in seconds
+    pub fn to_csv(&self) -> String {
+        let time = |t: DateTime<Utc>| t.to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
+        let mut csv = String::from("time,gps_time,event,mode,outage_s\n");
+        for event in &self.events {
+            csv.push_str(&format!(
+                "{},{},{},{},{}\n",
+                time(event.time),
+                event.gps_time.map_or(String::new(), time),
+                event.kind.label(),
+                event.mode.map_or(String::new(), |m| m.to_string()),
+                event.outage.map_or(String::new(), |d| format!("{:.1}", d.num_milliseconds() as f64 / 1000.0)),
+            ));
+        }
+        csv
+    }
+}
+
+/// Shell command that sets the system clock to `time` (UTC) on this platform.
+/// It's only offered for the user to run with the needed privileges; the
+/// clock is never set automatically.
+pub fn system_clock_command(time: DateTime<Utc>) -> String {
+    if cfg!(windows) {
+        format!("Set-Date -Date ([DateTime]::Parse('{}').ToLocalTime())", time.format("%Y-%m-%dT%H:%M:%SZ"))
+    } else if cfg!(target_os = "macos") {
+        format!("sudo date -u {}", time.format("%m%d%H%M%Y.%S"))
+    } else {
+        format!("sudo date -u -s '{}'", time.format("%Y-%m-%d %H:%M:%S"))
+    }
+}
+
+/// Groups of fields that different sentences update independently
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataField {
+    Position,    // Latitude/longitude (GGA, GNS)
+    Velocity,    // Speed and course (RMC)
+    Satellites,  // Satellites in view (GSV)
+}
+
+/// Latest state of the GPS source. Serializes to JSON with durations in
+/// seconds; the update rate meter is left out.
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct GpsData {
     pub timestamp: Option<DateTime<Utc>>,
     pub latitude: Option<f64>,
     pub longitude: Option<f64>,
     pub altitude: Option<f64>,
+    pub geoid_separation: Option<f64>,  // meters, height of the geoid above the WGS84 ellipsoid
+    pub baro_altitude: Option<f64>,  // meters, from an external barometric sensor
     pub speed: Option<f64>,      // km/h
     pub course: Option<f64>,     // degrees
+    pub magnetic_variation: Option<f64>,  // degrees, east positive
     pub satellites: Option<u8>,
     pub fix_quality: Option<u8>,
     pub hdop: Option<f64>,
     pub mode: Option<u8>,
-    pub accuracy: Option<f64>,   // meters
+    pub rmc_status: Option<char>,      // RMC status: A = active, V = void
+    pub mode_indicator: Option<char>,  // RMC mode (NMEA 2.3+): A, D, E, N, ...
+    pub accuracy: Option<f64>,   // meters, horizontal
+    pub vertical_accuracy: Option<f64>,  // meters
     pub source: Option<String>,  // GPS, Network, etc.
     pub raw_data: String,
     pub raw_history: Vec<String>, // Recent NMEA sentences
     pub satellites_info: Vec<SatelliteInfo>, // Detailed satellite information
+    pub text_messages: Vec<TextMessage>,  // Recent TXT messages, oldest first
+    pub connected_at: Option<DateTime<Utc>>,  // When the current connection started
+    pub connection: ConnectionStatus,
+    #[serde(serialize_with = "serialize_duration_secs")]
+    pub time_to_first_fix: Option<chrono::Duration>,
+    pub gps_time: Option<DateTime<Utc>>,           // Date and time from the last valid RMC
+    #[serde(serialize_with = "serialize_duration_secs")]
+    pub clock_offset: Option<chrono::Duration>,    // GPS time minus system time, once GPS time is known
+    pub position_updated: Option<DateTime<Utc>>,    // Receive time of the last position
+    pub velocity_updated: Option<DateTime<Utc>>,    // Receive time of the last speed or course
+    pub satellites_updated: Option<DateTime<Utc>>,  // Receive time of the last satellite report
+    #[serde(skip)]
+    pub clock_drift: ClockDrift,                    // Rolling clock offset, for display
+    #[serde(skip)]
+    pub clock_jump: Option<ClockJump>,              // Last offset jump, until the monitor reports it
+    #[serde(skip)]
+    pub fix_rate: RateMeter,                        // Position updates, for the measured update rate
+    #[serde(skip)]
+    pub fix_events: FixEventLog,                    // Fix acquisitions and losses, kept across reconnects
+    #[serde(skip)]
+    pub used_prns: HashMap<String, Vec<u8>>,        // Satellites each constellation's last GSA listed as used
+}
+
+fn serialize_duration_secs<S: serde::Serializer>(duration: &Option<chrono::Duration>, serializer: S) -> Result<S::Ok, S::Error> {
+    duration.map(|d| d.num_milliseconds() as f64 / 1000.0).serialize(serializer)
 }
 
 impl GpsData {
@@ -80,6 +586,76 @@ impl GpsData {
         self.latitude.is_some() && self.longitude.is_some()
     }
 
+    /// A 2D fix: the altitude is held from an earlier fix or assumed, not measured
+    pub fn is_2d_fix(&self) -> bool {
+        self.mode == Some(2)
+    }
+
+    /// A 3D fix. NMEA sources don't report a fix mode; an altitude implies one there.
+    pub fn is_3d_fix(&self) -> bool {
+        match self.mode {
+            Some(mode) => mode >= 3,
+            None => self.fix_quality.is_some_and(|q| q > 0) && self.altitude.is_some(),
+        }
+    }
+
+    /// The receiver marks the position as dead-reckoned rather than measured
+    pub fn is_estimated(&self) -> bool {
+        self.fix_quality == Some(6) || self.fix_validity() == FixValidity::Estimated
+    }
+
+    /// Check if the receiver currently reports a usable fix.
+    ///
+    /// Unlike `has_fix`, this also honours the reported fix quality / mode, since
+    /// the last known position is kept after the fix is lost.
+    pub fn has_valid_fix(&self) -> bool {
+        self.has_fix()
+            && self.is_recent()
+            && self.fix_quality != Some(0)
+            && self.mode.is_none_or(|m| m >= 2)
+            && self.fix_validity() != FixValidity::Invalid
+    }
+
+    /// Validity the receiver itself reports for the fix. A void status or
+    /// an "N" mode indicator means the position must not be trusted.
+    pub fn fix_validity(&self) -> FixValidity {
+        match (self.rmc_status, self.mode_indicator) {
+            (Some('V'), _) | (_, Some('N')) => FixValidity::Invalid,
+            (_, Some('E')) => FixValidity::Estimated,
+            (Some(_), _) | (_, Some(_)) => FixValidity::Valid,
+            (None, None) => FixValidity::Unknown,
+        }
+    }
+
+    /// Altitude from the preferred source, falling back to the other one when
+    /// the preferred value is missing. Returns the source actually used.
+    pub fn altitude_from(&self, preferred: AltitudeSource) -> Option<(f64, AltitudeSource)> {
+        let gps = self.altitude.map(|alt| (alt, AltitudeSource::Gps));
+        let baro = self.baro_altitude.map(|alt| (alt, AltitudeSource::Barometric));
+        match preferred {
+            AltitudeSource::Gps => gps.or(baro),
+            AltitudeSource::Barometric => baro.or(gps),
+        }
+    }
+
+    /// Course relative to the preferred north. Magnetic needs a reported
+    /// variation; without one the true course is returned. Returns the
+    /// reference actually used.
+    pub fn course_from(&self, preferred: NorthReference) -> Option<(f64, NorthReference)> {
+        self.course.map(|course| self.relative_to_north(course, preferred))
+    }
+
+    /// A bearing from true north, e.g. to a waypoint, restated relative to
+    /// the preferred north as `course_from` does for the course
+    pub fn relative_to_north(&self, bearing: f64, preferred: NorthReference) -> (f64, NorthReference) {
+        match (preferred, self.magnetic_variation) {
+            (NorthReference::Magnetic, Some(variation)) => {
+                ((bearing - variation).rem_euclid(360.0), NorthReference::Magnetic)
+            }
+            _ => (bearing, NorthReference::True),
+        }
+    }
+
     /// Get the age of the GPS data in seconds
     pub fn age_seconds(&self) -> Option<i64> {
         self.timestamp.map(|ts| Utc::now().signed_duration_since(ts).num_seconds())
@@ -87,7 +663,58 @@ impl GpsData {
 
     /// Check if the GPS data is recent (within 10 seconds)
     pub fn is_recent(&self) -> bool {
-        self.age_seconds().map_or(false, |age| age < 10)
+        self.age_seconds().is_some_and(|age| age < 10)
+    }
+
+    /// Start timing a new connection. Clears the previous fix so stale
+    /// position data can't count as the first fix, and everything the
+    /// previous source reported about its sky and receiver so it can't mix
+    /// with the new source's.
+    pub fn mark_connected(&mut self) {
+        self.connected_at = Some(Utc::now());
+        self.time_to_first_fix = None;
+        self.latitude = None;
+        self.longitude = None;
+        self.position_updated = None;
+        self.fix_rate.reset();
+        self.fix_quality = None;
+        self.mode = None;
+        self.rmc_status = None;
+        self.mode_indicator = None;
+        self.source = None;
+        self.satellites = None;
+        self.satellites_info.clear();
+        self.used_prns.clear();
+        self.satellites_updated = None;
+        self.hdop = None;
+        self.text_messages.clear();
+    }
+
+    /// Drop satellites that haven't been reported within `max_age`, e.g. a
+    /// constellation that stopped sending GSV. Returns how many were removed.
+    pub fn prune_stale_satellites(&mut self, max_age: chrono::Duration) -> usize {
+        let cutoff = Utc::now() - max_age;
+        let before = self.satellites_info.len();
+        self.satellites_info.retain(|sat| sat.last_seen >= cutoff);
+        before - self.satellites_info.len()
+    }
+
+    /// Record the time to first fix once a valid fix arrives after `mark_connected`
+    pub fn update_time_to_first_fix(&mut self) {
+        if self.time_to_first_fix.is_some() || !self.has_valid_fix() {
+            return;
+        }
+        if let Some(connected_at) = self.connected_at {
+            self.time_to_first_fix = Some(Utc::now() - connected_at);
+        }
+    }
+
+    /// Add a fix event if the fix was acquired, lost or changed mode with
+    /// the sentence just parsed
+    pub fn update_fix_events(&mut self) {
+        let time = self.timestamp.unwrap_or_else(Utc::now);
+        let (has_fix, mode, gps_time) = (self.has_valid_fix(), self.mode, self.gps_time);
+        self.fix_events.record(has_fix, mode, time, gps_time);
     }
 
     /// Update the timestamp to now
@@ -95,6 +722,86 @@ impl GpsData {
         self.timestamp = Some(Utc::now());
     }
 
+    /// Record that `field` was just updated, at the receive time of the
+    /// sentence being parsed
+    pub fn mark_updated(&mut self, field: DataField) {
+        let now = self.timestamp.unwrap_or_else(Utc::now);
+        *self.field_time_mut(field) = Some(now);
+        if field == DataField::Position {
+            self.fix_rate.record(now);
+        }
+    }
+
+    /// Measured position updates per second, once a full window has passed
+    pub fn update_rate(&self) -> Option<f64> {
+        self.fix_rate.rate(Utc::now())
+    }
+
+    /// When `field` was last updated, if ever
+    pub fn field_updated(&self, field: DataField) -> Option<DateTime<Utc>> {
+        match field {
+            DataField::Position => self.position_updated,
+            DataField::Velocity => self.velocity_updated,
+            DataField::Satellites => self.satellites_updated,
+        }
+    }
+
+    fn field_time_mut(&mut self, field: DataField) -> &mut Option<DateTime<Utc>> {
+        match field {
+            DataField::Position => &mut self.position_updated,
+            DataField::Velocity => &mut self.velocity_updated,
+            DataField::Satellites => &mut self.satellites_updated,
+        }
+    }
+
+    /// Time since `field` was last updated
+    pub fn field_age(&self, field: DataField) -> Option<chrono::Duration> {
+        self.field_updated(field).map(|updated| Utc::now() - updated)
+    }
+
+    /// Whether `field` holds a value older than `max_age`. A field that was
+    /// never updated has nothing to show and doesn't count as stale.
+    pub fn is_field_stale(&self, field: DataField, max_age: chrono::Duration) -> bool {
+        self.field_age(field).is_some_and(|age| age > max_age)
+    }
+
+    /// Timestamp on the GPS clock: the system receive time plus the clock
+    /// offset, once one has been measured
+    pub fn corrected_timestamp(&self) -> Option<DateTime<Utc>> {
+        self.timestamp.map(|ts| ts + self.clock_offset.unwrap_or_else(chrono::Duration::zero))
+    }
+
+    /// Compare a reliable GPS time with the system time the sentence arrived
+    /// at (`timestamp`). The first reading sets the clock offset; later ones
+    /// only replace it after a jump of more than `CLOCK_JUMP_THRESHOLD_MS`, so
+    /// serial latency doesn't make it wander. Every reading goes into the
+    /// rolling `clock_drift`. A jump is left in `clock_jump` for the monitor
+    /// to report. Returns true when the offset changed.
+    pub fn update_clock_offset(&mut self, gps_time: DateTime<Utc>) -> bool {
+        self.gps_time = Some(gps_time);
+        let system_time = self.timestamp.unwrap_or_else(Utc::now);
+        let measured = gps_time - system_time;
+        match self.clock_offset {
+            Some(offset) if (measured - offset).num_milliseconds().abs() <= CLOCK_JUMP_THRESHOLD_MS => {
+                self.clock_drift.record(gps_time, system_time);
+                false
+            }
+            Some(offset) => {
+                self.clock_jump = Some(ClockJump { from: offset, to: measured });
+                // Readings from before the jump would drag the average
+                self.clock_drift.reset();
+                self.clock_drift.record(gps_time, system_time);
+                self.clock_offset = Some(measured);
+                true
+            }
+            None => {
+                self.clock_drift.record(gps_time, system_time);
+                self.clock_offset = Some(measured);
+                true
+            }
+        }
+    }
+
     /// Set the data source
     pub fn set_source(&mut self, source: &str) {
         self.source = Some(source.to_string());
@@ -111,8 +818,39 @@ impl GpsData {
         }
     }
 
-    /// Get fix type description
+    /// Keep a receiver text message, dropping the oldest past `MAX_TEXT_MESSAGES`
+    pub fn add_text_message(&mut self, message: TextMessage) {
+        self.text_messages.push(message);
+        if self.text_messages.len() > MAX_TEXT_MESSAGES {
+            self.text_messages.remove(0);
+        }
+    }
+
+    /// A receiver-reported problem worth flagging: the last antenna fault
+    /// not followed by an antenna OK, or an error message from the last
+    /// `TEXT_ERROR_SECS`
+    pub fn receiver_fault(&self, now: DateTime<Utc>) -> Option<&TextMessage> {
+        let antenna = self.text_messages.iter().rev()
+            .find(|message| message.antenna_fault().is_some())
+            .filter(|message| message.antenna_fault() == Some(true));
+        antenna.or_else(|| {
+            self.text_messages.iter().rev().find(|message| {
+                message.severity == TextSeverity::Error
+                    && now - message.received < chrono::Duration::seconds(TEXT_ERROR_SECS)
+            })
+        })
+    }
+
+    /// Get fix type description, qualified by the receiver's RMC validity flags
     pub fn get_fix_description(&self) -> String {
+        match self.fix_validity() {
+            FixValidity::Invalid => "Invalid (receiver flagged void)".to_string(),
+            FixValidity::Estimated => format!("{} (estimated)", self.base_fix_description()),
+            _ => self.base_fix_description(),
+        }
+    }
+
+    fn base_fix_description(&self) -> String {
         if let Some(quality) = self.fix_quality {
             match quality {
                 0 => "No fix".to_string(),
@@ -138,14 +876,25 @@ impl GpsData {
         }
     }
 
-    /// Format coordinate for display
-    pub fn format_coordinate(coord: Option<f64>) -> String {
+    /// Clamp a configured coordinate precision to `COORDINATE_DECIMALS_RANGE`
+    pub fn coordinate_decimals(decimals: usize) -> usize {
+        decimals.clamp(*COORDINATE_DECIMALS_RANGE.start(), *COORDINATE_DECIMALS_RANGE.end())
+    }
+
+    /// Format coordinate for display, right-aligned, with `decimals` places
+    pub fn format_coordinate(coord: Option<f64>, decimals: usize) -> String {
         match coord {
-            Some(val) => format!("{:>12.6}°", val),
+            Some(val) => format!("{:>12.*}°", Self::coordinate_decimals(decimals), val),
             None => "No fix".to_string(),
         }
     }
 
+    /// Format a "lat, lon" pair with `decimals` places, e.g. "42.438878, -71.119277"
+    pub fn format_lat_lon(lat: f64, lon: f64, decimals: usize) -> String {
+        let decimals = Self::coordinate_decimals(decimals);
+        format!("{:.*}, {:.*}", decimals, lat, decimals, lon)
+    }
+
     /// Format value with unit for display
     pub fn format_value<T: std::fmt::Display>(value: Option<T>, unit: &str) -> String {
         match value {
@@ -159,6 +908,88 @@ impl GpsData {
         self.satellites_info.iter().filter(|sat| sat.used).count()
     }
 
+    /// Whether any satellite is marked used, i.e. the source says which ones it uses
+    pub fn reports_used_satellites(&self) -> bool {
+        self.satellites_info.iter().any(|sat| sat.used)
+    }
+
+    /// Mark the satellites of `constellation` listed in `prns` as used and the
+    /// others as not, including ones only reported after this
+    pub fn set_used_satellites(&mut self, constellation: &str, prns: Vec<u8>) {
+        for sat in self.satellites_info.iter_mut().filter(|sat| sat.constellation == constellation) {
+            sat.used = prns.contains(&sat.prn);
+        }
+        self.used_prns.insert(constellation.to_string(), prns);
+    }
+
+    /// Whether the last satellite list for `constellation` has `prn` as used
+    pub fn listed_as_used(&self, constellation: &str, prn: u8) -> bool {
+        self.used_prns.get(constellation).is_some_and(|prns| prns.contains(&prn))
+    }
+
+    /// Satellites used in the fix, from the satellites marked used when there
+    /// are any and the GGA count otherwise, e.g. from a receiver without GSA
+    pub fn used_satellite_count(&self) -> Option<usize> {
+        match self.satellites_used() {
+            0 => self.satellites.map(usize::from),
+            used => Some(used),
+        }
+    }
+
+    /// Whether a valid fix rests on fewer than `min_used` satellites, which is
+    /// still a fix but a shaky one. `min_used` of 0 turns this off.
+    pub fn low_satellite_count(&self, min_used: usize) -> bool {
+        min_used > 0
+            && self.has_valid_fix()
+            && self.used_satellite_count().is_some_and(|used| used < min_used)
+    }
+
+    /// Count of satellites used in the fix that are at or above the elevation mask
+    pub fn satellites_used_above_mask(&self, mask_deg: f32) -> usize {
+        self.satellites_info.iter().filter(|sat| sat.used_above_mask(mask_deg)).count()
+    }
+
+    /// Average SNR in dB of the satellites used in the fix that report one
+    pub fn average_used_snr(&self) -> Option<f64> {
+        let used_snrs: Vec<f64> = self.satellites_info.iter()
+            .filter(|sat| sat.used)
+            .filter_map(|sat| sat.snr.map(f64::from))
+            .collect();
+        (!used_snrs.is_empty()).then(|| used_snrs.iter().sum::<f64>() / used_snrs.len() as f64)
+    }
+
+    /// Overall reception score from 0 to 100 with a label.
+    ///
+    /// Used satellites (0 to 10) earn up to 40 points, HDOP (10 down to 1) up
+    /// to 30, and the average SNR of used satellites (20 to 45 dB) up to 30.
+    /// Missing values earn nothing, and there is no score without a fix.
+    pub fn signal_health(&self) -> (u8, &'static str) {
+        if !self.has_fix() {
+            return (0, "Poor");
+        }
+
+        let used = match self.satellites_used() {
+            0 => self.satellites.unwrap_or(0) as usize,
+            used => used,
+        };
+        let average_snr = self.average_used_snr();
+
+        // Fraction of the way from `worst` to `best`, clamped to 0..=1
+        let scale = |value: f64, worst: f64, best: f64| ((value - worst) / (best - worst)).clamp(0.0, 1.0);
+        let score = HEALTH_SATELLITE_POINTS * scale(used as f64, 0.0, HEALTH_FULL_SATELLITES)
+            + self.hdop.map_or(0.0, |hdop| HEALTH_HDOP_POINTS * scale(hdop, HEALTH_HDOP_RANGE.1, HEALTH_HDOP_RANGE.0))
+            + average_snr.map_or(0.0, |snr| HEALTH_SNR_POINTS * scale(snr, HEALTH_SNR_RANGE.0, HEALTH_SNR_RANGE.1));
+
+        let score = score.round() as u8;
+        let label = match score {
+            80.. => "Excellent",
+            60..=79 => "Good",
+            40..=59 => "Fair",
+            _ => "Poor",
+        };
+        (score, label)
+    }
+
     /// Get satellites grouped by constellation
     pub fn satellites_by_constellation(&self) -> HashMap<String, Vec<&SatelliteInfo>> {
         let mut grouped = HashMap::new();
@@ -167,4 +998,354 @@ impl GpsData {
         }
         grouped
     }
+
+    /// Average SNR in dB of the used satellites in each constellation.
+    /// Constellations with no used satellites reporting SNR are left out.
+    pub fn average_used_snr_by_constellation(&self) -> HashMap<String, f64> {
+        self.satellites_by_constellation()
+            .into_iter()
+            .filter_map(|(constellation, satellites)| {
+                let snrs: Vec<f64> = satellites.iter()
+                    .filter(|sat| sat.used)
+                    .filter_map(|sat| sat.snr.map(f64::from))
+                    .collect();
+                (!snrs.is_empty()).then(|| (constellation, snrs.iter().sum::<f64>() / snrs.len() as f64))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snr_threshold_boundaries() {
+        let thresholds = SnrThresholds::default();
+        assert_eq!(thresholds.quality(Some(40.0)), SignalQuality::Excellent);
+        assert_eq!(thresholds.quality(Some(39.9)), SignalQuality::Good);
+        assert_eq!(thresholds.quality(Some(35.0)), SignalQuality::Good);
+        assert_eq!(thresholds.quality(Some(34.9)), SignalQuality::Fair);
+        assert_eq!(thresholds.quality(Some(25.0)), SignalQuality::Fair);
+        assert_eq!(thresholds.quality(Some(24.9)), SignalQuality::Poor);
+        assert_eq!(thresholds.quality(Some(15.0)), SignalQuality::Poor);
+        assert_eq!(thresholds.quality(Some(14.9)), SignalQuality::VeryPoor);
+        assert_eq!(thresholds.quality(None), SignalQuality::Unknown);
+
+        let shifted = SnrThresholds { excellent: 45.0, good: 40.0, fair: 30.0, poor: 20.0 };
+        assert!(shifted.is_valid());
+        assert_eq!(shifted.quality(Some(42.0)), SignalQuality::Good);
+
+        let inverted = SnrThresholds { excellent: 30.0, good: 35.0, ..SnrThresholds::default() };
+        assert!(!inverted.is_valid());
+    }
+
+    #[test]
+    fn test_used_above_mask_count() {
+        let sat = |prn: u8, elevation: Option<f32>, used: bool| SatelliteInfo {
+            elevation,
+            used,
+            ..SatelliteInfo::new(prn)
+        };
+        let mut data = GpsData::new();
+        data.satellites_info = vec![
+            sat(1, Some(5.0), true),
+            sat(2, Some(10.0), true),
+            sat(3, Some(45.0), true),
+            sat(4, Some(60.0), false),
+            sat(5, None, true),
+        ];
+
+        assert_eq!(data.satellites_used_above_mask(0.0), data.satellites_used());
+        assert_eq!(data.satellites_used_above_mask(0.0), 4);
+        // Exactly at the mask still counts
+        assert_eq!(data.satellites_used_above_mask(10.0), 3);
+        assert_eq!(data.satellites_used_above_mask(50.0), 1);
+    }
+
+    #[test]
+    fn test_altitude_source_precedence() {
+        let mut data = GpsData::new();
+        assert_eq!(data.altitude_from(AltitudeSource::Gps), None);
+
+        data.baro_altitude = Some(120.0);
+        assert_eq!(data.altitude_from(AltitudeSource::Gps), Some((120.0, AltitudeSource::Barometric)));
+
+        data.altitude = Some(100.0);
+        assert_eq!(data.altitude_from(AltitudeSource::Gps), Some((100.0, AltitudeSource::Gps)));
+        assert_eq!(data.altitude_from(AltitudeSource::Barometric), Some((120.0, AltitudeSource::Barometric)));
+
+        data.baro_altitude = None;
+        assert_eq!(data.altitude_from(AltitudeSource::Barometric), Some((100.0, AltitudeSource::Gps)));
+    }
+
+    #[test]
+    fn test_course_north_reference() {
+        let mut data = GpsData::new();
+        data.course = Some(84.4);
+        assert_eq!(data.course_from(NorthReference::Magnetic), Some((84.4, NorthReference::True)));
+
+        // Westerly variation: magnetic headings read higher than true
+        data.magnetic_variation = Some(-3.1);
+        let (course, reference) = data.course_from(NorthReference::Magnetic).unwrap();
+        assert!((course - 87.5).abs() < 1e-9);
+        assert_eq!(reference, NorthReference::Magnetic);
+        assert_eq!(data.course_from(NorthReference::True), Some((84.4, NorthReference::True)));
+
+        // Easterly variation wraps below north
+        data.course = Some(2.0);
+        data.magnetic_variation = Some(5.0);
+        assert_eq!(data.course_from(NorthReference::Magnetic), Some((357.0, NorthReference::Magnetic)));
+
+        // Other bearings are restated the same way, with or without a course
+        data.course = None;
+        assert_eq!(data.relative_to_north(90.0, NorthReference::Magnetic), (85.0, NorthReference::Magnetic));
+        assert_eq!(data.relative_to_north(90.0, NorthReference::True), (90.0, NorthReference::True));
+    }
+
+    #[test]
+    fn test_coordinate_precision() {
+        assert_eq!(GpsData::format_coordinate(Some(42.438878123), 6).trim(), "42.438878°");
+        assert_eq!(GpsData::format_coordinate(Some(42.438878123), 9).trim(), "42.438878123°");
+        assert_eq!(GpsData::format_lat_lon(42.438878, -71.119277, 3), "42.439, -71.119");
+
+        // Out-of-range settings are clamped rather than rejected
+        assert_eq!(GpsData::format_lat_lon(42.438878, -71.119277, 0), "42.439, -71.119");
+        assert_eq!(GpsData::coordinate_decimals(20), 9);
+        assert_eq!(GpsData::format_coordinate(None, 6), "No fix");
+    }
+
+    #[test]
+    fn test_signal_health() {
+        let sat = |prn: u8, snr: f32| SatelliteInfo {
+            snr: Some(snr),
+            used: true,
+            ..SatelliteInfo::new(prn)
+        };
+        let mut data = GpsData::new();
+        assert_eq!(data.signal_health(), (0, "Poor"));
+
+        // Open sky: many strong satellites and low HDOP
+        data.latitude = Some(42.0);
+        data.longitude = Some(-71.0);
+        data.hdop = Some(0.8);
+        data.satellites_info = (1..=12).map(|prn| sat(prn, 44.0)).collect();
+        let (score, label) = data.signal_health();
+        assert!(score >= 95, "score {}", score);
+        assert_eq!(label, "Excellent");
+
+        // Urban canyon: few weak satellites
+        data.hdop = Some(6.0);
+        data.satellites_info = (1..=4).map(|prn| sat(prn, 24.0)).collect();
+        assert_eq!(data.signal_health().1, "Poor");
+
+        // GGA count stands in when there is no GSV detail
+        data.hdop = Some(1.0);
+        data.satellites_info.clear();
+        data.satellites = Some(10);
+        assert_eq!(data.signal_health(), (70, "Good"));
+    }
+
+    #[test]
+    fn test_clock_offset() {
+        use chrono::TimeZone;
+        let gps_time = Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap();
+        let mut data = GpsData::new();
+        assert_eq!(data.corrected_timestamp(), None);
+
+        // System clock 90 s behind
+        data.timestamp = Some(gps_time - chrono::Duration::seconds(90));
+        assert!(data.update_clock_offset(gps_time));
+        assert_eq!(data.clock_offset, Some(chrono::Duration::seconds(90)));
+        assert_eq!(data.corrected_timestamp(), Some(gps_time));
+
+        // Serial latency doesn't move an established offset
+        data.timestamp = Some(gps_time + chrono::Duration::seconds(1) - chrono::Duration::milliseconds(89_700));
+        assert!(!data.update_clock_offset(gps_time + chrono::Duration::seconds(1)));
+        assert_eq!(data.clock_offset, Some(chrono::Duration::seconds(90)));
+        assert_eq!(data.clock_jump, None);
+
+        // The system clock is corrected mid-session
+        data.timestamp = Some(gps_time + chrono::Duration::seconds(2));
+        assert!(data.update_clock_offset(gps_time + chrono::Duration::seconds(2)));
+        assert_eq!(data.clock_offset, Some(chrono::Duration::zero()));
+        assert_eq!(data.clock_jump, Some(ClockJump { from: chrono::Duration::seconds(90), to: chrono::Duration::zero() }));
+        assert_eq!(data.clock_drift.estimate(), Some(chrono::Duration::zero()));
+    }
+
+    #[test]
+    fn test_clock_drift() {
+        use chrono::TimeZone;
+        let gps_time = Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap();
+        let mut drift = ClockDrift::default();
+        assert_eq!(drift.estimate(), None);
+
+        // System clock 2.3 s ahead, read with varying latency
+        for (i, latency_ms) in [0, 100, 200].into_iter().enumerate() {
+            let gps = gps_time + chrono::Duration::seconds(i as i64);
+            drift.record(gps, gps + chrono::Duration::milliseconds(2_200 + latency_ms));
+        }
+        assert_eq!(drift.estimate(), Some(chrono::Duration::milliseconds(-2_300)));
+
+        // Only the most recent readings count
+        for i in 0..CLOCK_DRIFT_SAMPLES as i64 {
+            let gps = gps_time + chrono::Duration::seconds(10 + i);
+            drift.record(gps, gps - chrono::Duration::milliseconds(500));
+        }
+        assert_eq!(drift.estimate(), Some(chrono::Duration::milliseconds(500)));
+
+        let command = system_clock_command(gps_time);
+        if cfg!(target_os = "linux") {
+            assert_eq!(command, "sudo date -u -s '2024-05-01 12:00:00'");
+        }
+    }
+
+    #[test]
+    fn test_fix_events() {
+        use chrono::TimeZone;
+        let t0 = Utc.with_ymd_and_hms(2024, 5, 1, 14, 0, 0).unwrap();
+        let at = |secs: i64| t0 + chrono::Duration::seconds(secs);
+        let mut log = FixEventLog::default();
+
+        // No fix from the start isn't an outage
+        log.record(false, Some(1), at(0), None);
+        log.record(true, Some(2), at(20), Some(at(20)));
+        log.record(true, Some(3), at(21), Some(at(21)));
+        log.record(true, Some(3), at(22), Some(at(22)));
+        // A source without fix modes keeps the last one known
+        log.record(true, None, at(23), Some(at(23)));
+        log.record(false, Some(1), at(180), Some(at(179)));
+        log.record(false, Some(1), at(181), Some(at(179)));
+        assert_eq!(log.current_outage(at(185)), Some(chrono::Duration::seconds(5)));
+        log.record(true, Some(3), at(192), Some(at(192)));
+        assert_eq!(log.current_outage(at(195)), None);
+
+        let kinds: Vec<_> = log.events().map(|e| e.kind).collect();
+        assert_eq!(kinds, [FixEventKind::Acquired, FixEventKind::ModeChanged, FixEventKind::Lost, FixEventKind::Acquired]);
+        let outages: Vec<_> = log.events().map(|e| e.outage).collect();
+        assert_eq!(outages, [None, None, None, Some(chrono::Duration::seconds(12))]);
+        assert_eq!(log.events().nth(2).unwrap().gps_time, Some(at(179)));
+
+        let csv = log.to_csv();
+        assert_eq!(csv.lines().count(), 5);
+        assert_eq!(csv.lines().nth(3), Some("2024-05-01T14:03:00.000Z,2024-05-01T14:02:59.000Z,Fix lost,1,"));
+        assert_eq!(csv.lines().nth(4), Some("2024-05-01T14:03:12.000Z,2024-05-01T14:03:12.000Z,Fix acquired,3,12.0"));
+
+        for i in 0..MAX_FIX_EVENTS as i64 {
+            log.record(i % 2 == 1, Some(3), at(200 + i), None);
+        }
+        assert_eq!(log.len(), MAX_FIX_EVENTS);
+        assert_eq!(log.events().next().unwrap().time, at(200));
+    }
+
+    #[test]
+    fn test_update_rate() {
+        use chrono::TimeZone;
+        let t0 = Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap();
+        let mut meter = RateMeter::default();
+        assert_eq!(meter.rate(t0), None);
+
+        // 10 Hz for 6 seconds
+        for i in 0..60 {
+            meter.record(t0 + chrono::Duration::milliseconds(i * 100));
+        }
+        let now = t0 + chrono::Duration::milliseconds(5900);
+        assert_eq!(meter.rate(t0 + chrono::Duration::seconds(3)), None);
+        assert_eq!(meter.rate(now), Some(10.0));
+
+        // Dropping to 1 Hz shows once the window has moved past the fast updates
+        for i in 1..=5 {
+            meter.record(now + chrono::Duration::seconds(i));
+        }
+        assert_eq!(meter.rate(now + chrono::Duration::seconds(5)), Some(1.0));
+
+        // No updates at all for a whole window
+        assert_eq!(meter.rate(now + chrono::Duration::seconds(20)), Some(0.0));
+
+        meter.reset();
+        assert_eq!(meter.rate(now + chrono::Duration::seconds(20)), None);
+    }
+
+    #[test]
+    fn test_average_used_snr_by_constellation() {
+        let mut data = GpsData::new();
+        let mut add = |prn: u8, constellation: &str, snr: Option<f32>, used: bool| {
+            let mut sat = SatelliteInfo::new(prn);
+            sat.constellation = constellation.to_string();
+            sat.snr = snr;
+            sat.used = used;
+            data.satellites_info.push(sat);
+        };
+        add(1, "GPS", Some(20.0), true);
+        add(2, "GPS", Some(30.0), true);
+        add(3, "GPS", Some(50.0), false);  // Not used: left out
+        add(4, "GPS", None, true);          // No SNR: left out
+        add(211, "GALILEO", Some(44.0), true);
+        add(212, "GALILEO", Some(46.0), true);
+        add(65, "GLONASS", Some(40.0), false);
+
+        let averages = data.average_used_snr_by_constellation();
+        assert_eq!(averages.get("GPS"), Some(&25.0));
+        assert_eq!(averages.get("GALILEO"), Some(&45.0));
+        assert_eq!(averages.get("GLONASS"), None);
+        assert_eq!(averages.len(), 2);
+    }
+
+    #[test]
+    fn test_low_satellite_count() {
+        let mut data = GpsData::new();
+        data.latitude = Some(42.0);
+        data.longitude = Some(-71.0);
+        data.fix_quality = Some(1);
+        data.update_timestamp();
+
+        // Unknown count: no caution
+        assert!(!data.low_satellite_count(5));
+
+        // GGA count only
+        data.satellites = Some(4);
+        assert!(data.low_satellite_count(5));
+        data.satellites = Some(5);
+        assert!(!data.low_satellite_count(5));
+
+        // Satellites in view but none marked used, e.g. without GSA: still the GGA count
+        data.satellites_info = (1..=3).map(SatelliteInfo::new).collect();
+        assert!(!data.low_satellite_count(5));
+        data.satellites_info.clear();
+
+        // The satellite list takes precedence over the GGA count
+        for prn in 1..=5 {
+            let mut sat = SatelliteInfo::new(prn);
+            sat.used = prn <= 4;
+            data.satellites_info.push(sat);
+        }
+        assert!(data.low_satellite_count(5));
+        assert!(!data.low_satellite_count(4));
+        data.satellites_info[4].used = true;
+        assert!(!data.low_satellite_count(5));
+        assert!(data.low_satellite_count(6));
+
+        // Disabled, and never shown without a fix (that's the no-fix warning)
+        assert!(!data.low_satellite_count(0));
+        data.fix_quality = Some(0);
+        assert!(!data.low_satellite_count(6));
+    }
+
+    #[test]
+    fn test_stale_satellites_pruned() {
+        let mut data = GpsData::new();
+        crate::gps::nmea::parse_nmea_sentence(&mut data, "$GPGSV,1,1,02,01,40,083,46,02,17,308,41*7A");
+        crate::gps::nmea::parse_nmea_sentence(&mut data, "$GLGSV,1,1,02,65,30,120,38,66,55,200,40*6A");
+        assert_eq!(data.satellites_info.len(), 4);
+
+        // GLONASS stops reporting; only GPS keeps refreshing
+        for sat in &mut data.satellites_info {
+            sat.last_seen -= chrono::Duration::seconds(20);
+        }
+        crate::gps::nmea::parse_nmea_sentence(&mut data, "$GPGSV,1,1,02,01,40,083,46,02,17,308,41*7A");
+
+        assert_eq!(data.prune_stale_satellites(chrono::Duration::seconds(10)), 2);
+        assert!(data.satellites_info.iter().all(|sat| sat.constellation == "GPS"));
+        assert_eq!(data.prune_stale_satellites(chrono::Duration::seconds(10)), 0);
+    }
 }
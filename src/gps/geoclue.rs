@@ -0,0 +1,180 @@
+// src/gps/geoclue.rs v1
+//! GeoClue2 location over DBus, for Linux machines without a GPS receiver
+//! (position from WiFi, cell towers or a built-in modem)
+
+use super::data::{DataField, GpsData};
+use crate::error::Result;
+use futures_util::StreamExt;
+use zbus::{dbus_proxy, zvariant::{ObjectPath, OwnedObjectPath}, CacheProperties, Connection};
+
+/// Name GeoClue shows in its permission prompts and uses for its per-app settings
+const DESKTOP_ID: &str = "gps-monitor";
+
+/// GeoClue's highest accuracy level, which uses GPS where the machine has one
+const ACCURACY_LEVEL_EXACT: u32 = 8;
+
+#[dbus_proxy(
+    interface = "org.freedesktop.GeoClue2.Manager",
+    default_service = "org.freedesktop.GeoClue2",
+    default_path = "/org/freedesktop/GeoClue2/Manager"
+)]
+trait Manager {
+    fn get_client(&self) -> zbus::Result<OwnedObjectPath>;
+}
+
+#[dbus_proxy(interface = "org.freedesktop.GeoClue2.Client", default_service = "org.freedesktop.GeoClue2")]
+trait Client {
+    fn start(&self) -> zbus::Result<()>;
+    fn stop(&self) -> zbus::Result<()>;
+
+    #[dbus_proxy(property)]
+    fn set_desktop_id(&self, id: &str) -> zbus::Result<()>;
+
+    #[dbus_proxy(property)]
+    fn set_requested_accuracy_level(&self, level: u32) -> zbus::Result<()>;
+
+    #[dbus_proxy(signal)]
+    fn location_updated(&self, old_location: ObjectPath<'_>, new_location: ObjectPath<'_>) -> zbus::Result<()>;
+}
+
+#[dbus_proxy(interface = "org.freedesktop.GeoClue2.Location", default_service = "org.freedesktop.GeoClue2")]
+trait Location {
+    #[dbus_proxy(property)]
+    fn latitude(&self) -> zbus::Result<f64>;
+    #[dbus_proxy(property)]
+    fn longitude(&self) -> zbus::Result<f64>;
+    #[dbus_proxy(property)]
+    fn accuracy(&self) -> zbus::Result<f64>;
+    #[dbus_proxy(property)]
+    fn altitude(&self) -> zbus::Result<f64>;
+    #[dbus_proxy(property)]
+    fn speed(&self) -> zbus::Result<f64>;
+    #[dbus_proxy(property)]
+    fn heading(&self) -> zbus::Result<f64>;
+}
+
+/// One location reported by GeoClue. Unknown altitude is reported as the
+/// lowest f64, unknown speed and heading as negative values.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeoClueLocation {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub accuracy: f64,   // meters
+    pub altitude: f64,   // meters
+    pub speed: f64,      // m/s
+    pub heading: f64,    // degrees
+}
+
+/// Update GPS data from a GeoClue location
+pub fn update_from_location(data: &mut GpsData, location: &GeoClueLocation) {
+    data.update_timestamp();
+    data.set_source("GeoClue");
+
+    data.latitude = Some(location.latitude);
+    data.longitude = Some(location.longitude);
+    data.altitude = (location.altitude > f64::MIN && location.altitude.is_finite()).then_some(location.altitude);
+    data.accuracy = (location.accuracy >= 0.0).then_some(location.accuracy);
+    data.mark_updated(DataField::Position);
+
+    let speed = (location.speed >= 0.0).then_some(location.speed * 3.6);  // m/s to km/h
+    let course = (location.heading >= 0.0).then_some(location.heading);
+    if speed.is_some() || course.is_some() {
+        data.speed = speed;
+        data.course = course;
+        data.mark_updated(DataField::Velocity);
+    }
+
+    data.raw_data = format!(
+        "Source: GeoClue, Accuracy: {:.1}m",
+        data.accuracy.unwrap_or(0.0)
+    );
+}
+
+/// A started GeoClue client and its stream of location updates
+pub struct GeoClueClient {
+    connection: Connection,
+    client: ClientProxy<'static>,
+    updates: LocationUpdatedStream<'static>,
+}
+
+impl GeoClueClient {
+    /// Ask GeoClue on the system bus for a client and start location updates
+    pub async fn start() -> Result<Self> {
+        let connection = Connection::system().await?;
+        let client_path = ManagerProxy::new(&connection).await?.get_client().await?;
+        let client = ClientProxy::builder(&connection)
+            .path(client_path)?
+            .cache_properties(CacheProperties::No)
+            .build()
+            .await?;
+
+        client.set_desktop_id(DESKTOP_ID).await?;
+        client.set_requested_accuracy_level(ACCURACY_LEVEL_EXACT).await?;
+        // Subscribe before starting so the first location isn't missed
+        let updates = client.receive_location_updated().await?;
+        client.start().await?;
+
+        Ok(Self { connection, client, updates })
+    }
+
+    /// Wait for the next location, `None` once GeoClue goes away
+    pub async fn next_location(&mut self) -> Result<Option<GeoClueLocation>> {
+        let Some(signal) = self.updates.next().await else {
+            return Ok(None);
+        };
+        let path = signal.args()?.new_location().to_owned();
+        let location = LocationProxy::builder(&self.connection)
+            .path(path)?
+            .cache_properties(CacheProperties::No)
+            .build()
+            .await?;
+
+        Ok(Some(GeoClueLocation {
+            latitude: location.latitude().await?,
+            longitude: location.longitude().await?,
+            accuracy: location.accuracy().await?,
+            altitude: location.altitude().await?,
+            speed: location.speed().await?,
+            heading: location.heading().await?,
+        }))
+    }
+
+    pub async fn stop(&self) {
+        if let Err(e) = self.client.stop().await {
+            eprintln!("Error stopping GeoClue client: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unknown_values_left_empty() {
+        let mut data = GpsData::new();
+        update_from_location(&mut data, &GeoClueLocation {
+            latitude: 42.4389,
+            longitude: -71.1193,
+            accuracy: 25.0,
+            altitude: f64::MIN,
+            speed: -1.0,
+            heading: -1.0,
+        });
+        assert_eq!((data.latitude, data.longitude, data.accuracy), (Some(42.4389), Some(-71.1193), Some(25.0)));
+        assert_eq!((data.altitude, data.speed, data.course), (None, None, None));
+        assert_eq!(data.source.as_deref(), Some("GeoClue"));
+
+        update_from_location(&mut data, &GeoClueLocation {
+            latitude: 42.44,
+            longitude: -71.12,
+            accuracy: 5.0,
+            altitude: 31.0,
+            speed: 10.0,
+            heading: 270.0,
+        });
+        assert_eq!(data.altitude, Some(31.0));
+        assert_eq!(data.speed, Some(36.0));
+        assert_eq!(data.course, Some(270.0));
+    }
+}
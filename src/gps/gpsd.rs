@@ -1,7 +1,7 @@
 // src/gps/gpsd.rs
 //! GPSD client implementation
 
-use super::data::{GpsData, SatelliteInfo};
+use super::data::{DataField, GpsData, SatelliteInfo};
 use crate::error::{Result, GpsError};
 use serde::Deserialize;
 use std::collections::HashMap;
@@ -24,7 +24,7 @@ pub async fn connect_gpsd(host: &str, port: u16) -> Result<BufReader<TcpStream>>
         .map_err(|e| GpsError::Connection(format!("Failed to connect to gpsd at {}:{}: {}", host, port, e)))?;
 
     // Send WATCH command to start receiving JSON data
-    let watch_cmd = format!("?WATCH={{\"enable\":true,\"json\":true}}\n");
+    let watch_cmd = "?WATCH={\"enable\":true,\"json\":true}\n".to_string();
     stream
         .write_all(watch_cmd.as_bytes())
         .await
@@ -53,13 +53,18 @@ pub fn parse_gpsd_json(data: &mut GpsData, line: &str) -> Result<()> {
 
 /// Parse TPV (Time Position Velocity) message
 fn parse_tpv_message(data: &mut GpsData, msg_data: &HashMap<String, serde_json::Value>) {
-    if let Some(lat) = msg_data.get("lat").and_then(|v| v.as_f64()) {
+    let lat = msg_data.get("lat").and_then(|v| v.as_f64());
+    let lon = msg_data.get("lon").and_then(|v| v.as_f64());
+    if let Some(lat) = lat {
         data.latitude = Some(lat);
     }
-    
-    if let Some(lon) = msg_data.get("lon").and_then(|v| v.as_f64()) {
+    if let Some(lon) = lon {
         data.longitude = Some(lon);
     }
+    // One update per message, however many of the coordinates it carried
+    if lat.is_some() || lon.is_some() {
+        data.mark_updated(DataField::Position);
+    }
     
     if let Some(alt) = msg_data.get("alt").and_then(|v| v.as_f64()) {
         data.altitude = Some(alt);
@@ -67,26 +72,72 @@ fn parse_tpv_message(data: &mut GpsData, msg_data: &HashMap<String, serde_json::
     
     if let Some(speed) = msg_data.get("speed").and_then(|v| v.as_f64()) {
         data.speed = Some(speed * 3.6); // Convert m/s to km/h
+        data.mark_updated(DataField::Velocity);
     }
     
     if let Some(track) = msg_data.get("track").and_then(|v| v.as_f64()) {
         data.course = Some(track);
+        data.mark_updated(DataField::Velocity);
+    }
+
+    if let Some(magvar) = msg_data.get("magvar").and_then(|v| v.as_f64()) {
+        data.magnetic_variation = Some(magvar);
     }
     
     if let Some(mode) = msg_data.get("mode").and_then(|v| v.as_u64()) {
         data.mode = Some(mode as u8);
     }
+
+    // Fix time, like RMC's, only trusted with a fix
+    let fix_time = msg_data.get("time").and_then(|v| v.as_str())
+        .and_then(|time| chrono::DateTime::parse_from_rfc3339(time).ok());
+    if let (Some(time), Some(2..)) = (fix_time, data.mode) {
+        data.update_clock_offset(time.with_timezone(&chrono::Utc));
+    }
+
+    // Corrections are reported in `status`, apart from the 2D/3D `mode`.
+    // Plain fixes leave the quality unset so the mode still describes them.
+    let status = msg_data.get("status").and_then(|v| v.as_u64());
+    data.fix_quality = match data.mode {
+        Some(mode) if mode >= 2 => status.and_then(fix_quality_from_status),
+        _ => None,
+    };
+
+    // Estimated errors in meters (95% confidence). gpsd drops these when it
+    // can't estimate them, and so do we rather than keep stale values.
+    let epx = msg_data.get("epx").and_then(|v| v.as_f64());
+    let epy = msg_data.get("epy").and_then(|v| v.as_f64());
+    data.accuracy = match (epx, epy) {
+        (Some(x), Some(y)) => Some(x.hypot(y)),
+        (x, y) => x.or(y),
+    };
+    data.vertical_accuracy = msg_data.get("epv").and_then(|v| v.as_f64());
+}
+
+/// GGA fix quality for a gpsd TPV `status`, None for an uncorrected or unknown fix
+fn fix_quality_from_status(status: u64) -> Option<u8> {
+    match status {
+        2 => Some(2),     // DGPS
+        3 => Some(4),     // RTK fixed
+        4 => Some(5),     // RTK float
+        5 | 6 => Some(6), // Dead reckoning, alone or with GNSS
+        7 => Some(7),     // Surveyed position (time mode)
+        8 => Some(8),     // Simulated
+        _ => None,
+    }
 }
 
 /// Parse SKY (satellite data) message
 fn parse_sky_message(data: &mut GpsData, msg_data: &HashMap<String, serde_json::Value>) {
     if let Some(satellites) = msg_data.get("satellites").and_then(|v| v.as_array()) {
         data.satellites_info.clear(); // Clear existing satellite data
+        data.mark_updated(DataField::Satellites);
         
         for sat_value in satellites {
             if let Some(sat_obj) = sat_value.as_object() {
                 if let Some(prn) = sat_obj.get("PRN").and_then(|v| v.as_u64()) {
                     let mut sat_info = SatelliteInfo::new(prn as u8);
+                    sat_info.source = data.source.clone();
                     
                     // Elevation
                     if let Some(el) = sat_obj.get("el").and_then(|v| v.as_f64()) {
@@ -158,6 +209,47 @@ mod tests {
         assert_eq!(data.mode, Some(3));
         assert!((data.speed.unwrap() - 0.3276).abs() < 0.001); // 0.091 m/s * 3.6 = 0.3276 km/h
         assert_eq!(data.course, Some(10.3797));
+
+        // Horizontal error combines the longitude and latitude errors
+        assert!((data.accuracy.unwrap() - 22.924).abs() < 0.001);
+        assert_eq!(data.vertical_accuracy, Some(124.484));
+        assert_eq!(data.gps_time.map(|t| t.to_rfc3339()).as_deref(), Some("2023-01-01T12:00:00+00:00"));
+        assert!(data.clock_offset.is_some());
+
+        // A TPV without estimates clears them
+        parse_gpsd_json(&mut data, r#"{"class":"TPV","mode":2,"lat":48.117,"lon":11.517}"#).unwrap();
+        assert_eq!((data.accuracy, data.vertical_accuracy), (None, None));
+    }
+
+    #[test]
+    fn test_tpv_status() {
+        let mut data = GpsData::new();
+        parse_gpsd_json(&mut data, r#"{"class":"TPV","mode":3,"status":2,"lat":48.117,"lon":11.517}"#).unwrap();
+        assert_eq!(data.fix_quality, Some(2));
+        assert_eq!(data.get_fix_description(), "DGPS");
+
+        parse_gpsd_json(&mut data, r#"{"class":"TPV","mode":3,"status":3,"lat":48.117,"lon":11.517}"#).unwrap();
+        assert_eq!(data.get_fix_description(), "RTK");
+
+        // An uncorrected fix, or none at all, falls back to the mode
+        parse_gpsd_json(&mut data, r#"{"class":"TPV","mode":3,"status":1,"lat":48.117,"lon":11.517}"#).unwrap();
+        assert_eq!(data.get_fix_description(), "3D fix");
+        parse_gpsd_json(&mut data, r#"{"class":"TPV","mode":1,"status":2}"#).unwrap();
+        assert_eq!(data.get_fix_description(), "No fix");
+    }
+
+    #[test]
+    fn test_tpv_update_rate() {
+        use crate::gps::data::UPDATE_RATE_WINDOW_SECS;
+        let now = chrono::Utc::now();
+        let mut data = GpsData::new();
+
+        // One TPV a second counts as one position update each
+        for i in (0..UPDATE_RATE_WINDOW_SECS * 2).rev() {
+            data.timestamp = Some(now - chrono::Duration::milliseconds(i * 1000 + 500));
+            parse_gpsd_json(&mut data, r#"{"class":"TPV","mode":3,"lat":48.117,"lon":11.517}"#).unwrap();
+        }
+        assert_eq!(data.update_rate(), Some(1.0));
     }
 
     #[test]
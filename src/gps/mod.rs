@@ -1,11 +1,18 @@
 // src/gps/mod.rs
 //! GPS data handling and parsing
 
+pub mod coordinates;
 pub mod data;
 pub mod nmea;
+pub mod nmea_decoder;
 pub mod gpsd;
 
 #[cfg(windows)]
 pub mod windows;
 
-pub use data::GpsData;
+#[cfg(all(target_os = "linux", feature = "geoclue"))]
+pub mod geoclue;
+
+pub use data::{AltitudeSource, NorthReference, ClockDrift, ClockJump, ConnectionStatus, CLOCK_DRIFT_SAMPLES, FixEvent, FixEventKind, FixEventLog, MAX_FIX_EVENTS, CLOCK_JUMP_THRESHOLD_MS, DataField, FIELD_STALE_SECS, FixValidity, GpsData, LOW_UPDATE_RATE_FRACTION, RateMeter, UPDATE_RATE_WINDOW_SECS, SignalQuality, SnrThresholds, COORDINATE_DECIMALS_RANGE, DEFAULT_COORDINATE_DECIMALS, METERS_PER_FOOT, DEFAULT_MIN_SATELLITES_USED, TextMessage, TextSeverity};
+pub use coordinates::{CoordinateFormat, Utm};
+pub use nmea::SpeedUnit;
@@ -1,61 +1,218 @@
 // src/gps/nmea.rs
 //! NMEA sentence parsing
 
-use super::data::{GpsData, SatelliteInfo};
+use super::data::{DataField, GpsData, SatelliteInfo, TextMessage, TextSeverity, METERS_PER_FOOT};
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+
+/// Unit of the RMC speed-over-ground field.
+///
+/// The NMEA standard is knots, but some receivers put km/h or m/s there.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpeedUnit {
+    #[default]
+    Knots,
+    KilometersPerHour,
+    MetersPerSecond,
+}
+
+impl SpeedUnit {
+    pub const ALL: [SpeedUnit; 3] = [SpeedUnit::Knots, SpeedUnit::KilometersPerHour, SpeedUnit::MetersPerSecond];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SpeedUnit::Knots => "knots (standard)",
+            SpeedUnit::KilometersPerHour => "km/h",
+            SpeedUnit::MetersPerSecond => "m/s",
+        }
+    }
+
+    /// Convert a value in this unit to km/h
+    pub fn to_kmh(&self, value: f64) -> f64 {
+        match self {
+            SpeedUnit::Knots => value * 1.852,
+            SpeedUnit::KilometersPerHour => value,
+            SpeedUnit::MetersPerSecond => value * 3.6,
+        }
+    }
+}
+
+/// XOR of the bytes between '$' and '*', which the "*hh" suffix carries in hex
+pub fn checksum(body: &str) -> u8 {
+    body.bytes().fold(0u8, |acc, b| acc ^ b)
+}
+
+/// Whether `line` is a "$...*hh" sentence whose checksum matches its body
+pub fn has_valid_checksum(line: &str) -> bool {
+    let Some((body, checksum)) = line.trim().strip_prefix('$').and_then(|s| s.split_once('*')) else {
+        return false;
+    };
+    if body.is_empty() || checksum.len() != 2 {
+        return false;
+    }
+    u8::from_str_radix(checksum, 16).is_ok_and(|expected| expected == self::checksum(body))
+}
+
+/// The sentence in `line` from its '$' (or '!') on, without the noise dirty
+/// serial and TCP streams add: bytes before the start, control characters
+/// such as NULs or stray CRs, and surrounding whitespace. None without a start.
+pub fn clean_sentence(line: &str) -> Option<Cow<'_, str>> {
+    let start = line.find(['$', '!'])?;
+    let sentence = line[start..].trim_end();
+    if sentence.contains(|c: char| c.is_control()) {
+        Some(Cow::Owned(sentence.chars().filter(|c| !c.is_control()).collect()))
+    } else {
+        Some(Cow::Borrowed(sentence))
+    }
+}
 
 /// Parse a single NMEA sentence and update GPS data
 pub fn parse_nmea_sentence(data: &mut GpsData, line: &str) {
+    parse_nmea_sentence_with_speed_unit(data, line, SpeedUnit::Knots);
+}
+
+/// Parse a single NMEA sentence, reading RMC speed in `rmc_speed_unit`
+pub fn parse_nmea_sentence_with_speed_unit(data: &mut GpsData, line: &str, rmc_speed_unit: SpeedUnit) {
+    let Some(line) = clean_sentence(line) else {
+        return;
+    };
+    let line = line.as_ref();
     let parts: Vec<&str> = line.split(',').collect();
 
     if line.starts_with("$GPGGA") || line.starts_with("$GNGGA") {
         parse_gpgga(data, &parts);
+    } else if line.starts_with("$GNGNS") || line.starts_with("$GPGNS") || line.starts_with("$GLGNS") || line.starts_with("$GAGNS") || line.starts_with("$GBGNS") {
+        parse_gns(data, &parts);
     } else if line.starts_with("$GPRMC") || line.starts_with("$GNRMC") {
-        parse_gprmc(data, &parts);
+        parse_gprmc(data, &parts, rmc_speed_unit);
+    } else if ["$GPGSA", "$GNGSA", "$GLGSA", "$GAGSA", "$GBGSA"].iter().any(|talker| line.starts_with(talker)) {
+        parse_gsa(data, &parts);
     } else if line.starts_with("$GPGSV") || line.starts_with("$GLGSV") || line.starts_with("$GAGSV") || line.starts_with("$GBGSV") {
         parse_gsv(data, &parts, line);
+    } else if line.starts_with('$') && line.get(3..6) == Some("TXT") {
+        parse_txt(data, &parts);
     }
 }
 
+/// Parse a TXT (text transmission) sentence: total, number, message type, text.
+/// Long messages split over several sentences are kept as separate parts.
+fn parse_txt(data: &mut GpsData, parts: &[&str]) {
+    if parts.len() < 5 {
+        return;
+    }
+
+    // The text may itself contain commas; drop the checksum from the end
+    let text = parts[4..].join(",");
+    let text = text.split('*').next().unwrap_or("").trim();
+    if text.is_empty() {
+        return;
+    }
+
+    let severity = TextSeverity::from_code(parts[3].parse().unwrap_or(2));
+    data.add_text_message(TextMessage { severity, text: text.to_string(), received: Utc::now() });
+}
+
+/// A height field in meters, converted when its unit field says feet. Anything
+/// else is taken as meters, the only unit most receivers send.
+fn height_meters(value: &str, unit: &str) -> Option<f64> {
+    let height = value.parse::<f64>().ok()?;
+    Some(if unit.eq_ignore_ascii_case("F") { height * METERS_PER_FOOT } else { height })
+}
+
 /// Parse GPGGA (Global Positioning System Fix Data) sentence
 fn parse_gpgga(data: &mut GpsData, parts: &[&str]) {
     if parts.len() < 15 {
         return;
     }
 
-    // Latitude (field 2 and 3)
-    if !parts[2].is_empty() && !parts[3].is_empty() {
-        if let Ok(lat) = parts[2].parse::<f64>() {
-            let lat_deg = (lat / 100.0) as i32;
-            let lat_min = lat % 100.0;
-            let mut latitude = lat_deg as f64 + lat_min / 60.0;
-            if parts[3] == "S" {
-                latitude = -latitude;
-            }
-            data.latitude = Some(latitude);
+    // Latitude (field 2 and 3), longitude (field 4 and 5)
+    parse_position(data, parts, 2);
+
+    // Fix quality (field 6)
+    if !parts[6].is_empty() {
+        if let Ok(quality) = parts[6].parse::<u8>() {
+            data.fix_quality = Some(quality);
+        }
+    }
+
+    // Number of satellites (field 7)
+    if !parts[7].is_empty() {
+        if let Ok(sats) = parts[7].parse::<u8>() {
+            data.satellites = Some(sats);
         }
     }
 
-    // Longitude (field 4 and 5)
-    if !parts[4].is_empty() && !parts[5].is_empty() {
-        if let Ok(lon) = parts[4].parse::<f64>() {
-            let lon_deg = (lon / 100.0) as i32;
-            let lon_min = lon % 100.0;
-            let mut longitude = lon_deg as f64 + lon_min / 60.0;
-            if parts[5] == "W" {
-                longitude = -longitude;
+    // HDOP (field 8)
+    if !parts[8].is_empty() {
+        if let Ok(hdop) = parts[8].parse::<f64>() {
+            data.hdop = Some(hdop);
+        }
+    }
+
+    // Altitude (field 9) and its unit (field 10)
+    if let Some(alt) = height_meters(parts[9], parts[10]) {
+        data.altitude = Some(alt);
+    }
+
+    // Geoid separation (field 11) and its unit (field 12)
+    if let Some(separation) = height_meters(parts[11], parts[12]) {
+        data.geoid_separation = Some(separation);
+    }
+}
+
+/// Parse a GSA (DOP and Active Satellites) sentence: the fix mode and the
+/// satellites used in the fix
+fn parse_gsa(data: &mut GpsData, parts: &[&str]) {
+    // Fix mode (field 2): 1 = no fix, 2 = 2D, 3 = 3D
+    if let Some(Ok(mode)) = parts.get(2).map(|mode| mode.parse::<u8>()) {
+        if (1..=3).contains(&mode) {
+            data.mode = Some(mode);
+        }
+    }
+
+    // PRNs of the satellites used (fields 3 to 14)
+    let prns: Vec<u8> = parts.iter().skip(3).take(12).filter_map(|prn| prn.parse().ok()).collect();
+
+    // Named as the GSV sentences of the same constellation name it. A combined
+    // GNGSA says which constellation it lists by the NMEA 4.10 system ID
+    // (field 18); older receivers only tell them apart by PRN range.
+    let system_id = parts.get(18).and_then(|id| id.split('*').next()?.parse::<u8>().ok());
+    let constellation = match (parts[0].get(1..3), system_id) {
+        (Some("GP"), _) | (Some("GN"), Some(1)) => "GPS",
+        (Some("GL"), _) | (Some("GN"), Some(2)) => "GLONASS",
+        (Some("GA"), _) | (Some("GN"), Some(3)) => "GALILEO",
+        (Some("GB"), _) | (Some("GN"), Some(4)) => "BEIDOU",
+        (Some("GN"), None) => {
+            let (glonass, gps): (Vec<u8>, Vec<u8>) = prns.into_iter().partition(|prn| (65..=96).contains(prn));
+            for (constellation, prns) in [("GPS", gps), ("GLONASS", glonass)] {
+                if !prns.is_empty() {
+                    data.set_used_satellites(constellation, prns);
+                }
             }
-            data.longitude = Some(longitude);
+            return;
         }
+        _ => return,
+    };
+    data.set_used_satellites(constellation, prns);
+}
+
+/// Parse GNS (GNSS Fix Data) sentence
+fn parse_gns(data: &mut GpsData, parts: &[&str]) {
+    if parts.len() < 10 {
+        return;
     }
 
-    // Fix quality (field 6)
+    // Latitude (field 2 and 3), longitude (field 4 and 5)
+    parse_position(data, parts, 2);
+
+    // Mode indicator (field 6), one character per constellation
     if !parts[6].is_empty() {
-        if let Ok(quality) = parts[6].parse::<u8>() {
-            data.fix_quality = Some(quality);
-        }
+        data.fix_quality = Some(gns_mode_to_fix_quality(parts[6]));
     }
 
-    // Number of satellites (field 7)
+    // Number of satellites in use (field 7)
     if !parts[7].is_empty() {
         if let Ok(sats) = parts[7].parse::<u8>() {
             data.satellites = Some(sats);
@@ -77,16 +234,86 @@ fn parse_gpgga(data: &mut GpsData, parts: &[&str]) {
     }
 }
 
+/// Map a GNS mode-indicator string to a GGA-style fix quality, using the
+/// best mode reported by any constellation
+fn gns_mode_to_fix_quality(mode: &str) -> u8 {
+    // Ordered from best to worst
+    const PREFERENCE: [(char, u8); 8] = [
+        ('R', 4), // RTK
+        ('F', 5), // Float RTK
+        ('P', 3), // Precise
+        ('D', 2), // Differential
+        ('A', 1), // Autonomous
+        ('E', 6), // Estimated (dead reckoning)
+        ('M', 7), // Manual input
+        ('S', 8), // Simulator
+    ];
+
+    PREFERENCE.iter()
+        .find(|(c, _)| mode.contains(*c))
+        .map_or(0, |&(_, quality)| quality)
+}
+
+/// Parse a ddmm.mmmm latitude and dddmm.mmmm longitude starting at `index`
+/// (lat, N/S, lon, E/W)
+fn parse_position(data: &mut GpsData, parts: &[&str], index: usize) {
+    let lat = parse_coordinate(parts[index], parts[index + 1], "S");
+    let lon = parse_coordinate(parts[index + 2], parts[index + 3], "W");
+    if lat.is_some() || lon.is_some() {
+        data.mark_updated(DataField::Position);
+    }
+
+    if let Some(lat) = lat {
+        data.latitude = Some(lat);
+    }
+
+    if let Some(lon) = lon {
+        data.longitude = Some(lon);
+    }
+}
+
+/// Convert an NMEA ddmm.mmmm value and hemisphere to signed decimal degrees
+pub(super) fn parse_coordinate(value: &str, hemisphere: &str, negative: &str) -> Option<f64> {
+    if value.is_empty() || hemisphere.is_empty() {
+        return None;
+    }
+
+    let raw = value.parse::<f64>().ok()?;
+    let degrees = (raw / 100.0) as i32;
+    let minutes = raw % 100.0;
+    let mut coord = degrees as f64 + minutes / 60.0;
+    if hemisphere == negative {
+        coord = -coord;
+    }
+    Some(coord)
+}
+
 /// Parse GPRMC (Recommended Minimum Course) sentence
-fn parse_gprmc(data: &mut GpsData, parts: &[&str]) {
+fn parse_gprmc(data: &mut GpsData, parts: &[&str], speed_unit: SpeedUnit) {
     if parts.len() < 10 {
         return;
     }
 
-    // Speed over ground in knots (field 7)
+    // Status (field 2): A = active, V = void
+    data.rmc_status = parts[2].chars().next();
+
+    // UTC time (field 1) and date (field 9), only trusted with an active fix
+    if data.rmc_status == Some('A') {
+        if let Some(gps_time) = parse_rmc_date_time(parts[1], parts[9]) {
+            data.update_clock_offset(gps_time);
+        }
+    }
+
+    // Mode indicator (field 12, NMEA 2.3+), possibly followed by the checksum
+    data.mode_indicator = parts.get(12)
+        .and_then(|mode| mode.split('*').next())
+        .and_then(|mode| mode.chars().next());
+
+    // Speed over ground (field 7), normally knots
     if !parts[7].is_empty() {
-        if let Ok(speed_knots) = parts[7].parse::<f64>() {
-            data.speed = Some(speed_knots * 1.852); // Convert knots to km/h
+        if let Ok(speed) = parts[7].parse::<f64>() {
+            data.speed = Some(speed_unit.to_kmh(speed));
+            data.mark_updated(DataField::Velocity);
         }
     }
 
@@ -94,8 +321,24 @@ fn parse_gprmc(data: &mut GpsData, parts: &[&str]) {
     if !parts[8].is_empty() {
         if let Ok(course) = parts[8].parse::<f64>() {
             data.course = Some(course);
+            data.mark_updated(DataField::Velocity);
         }
     }
+
+    // Magnetic variation (field 10) and direction (field 11, may carry the checksum)
+    let variation = parts.get(10).and_then(|v| v.parse::<f64>().ok());
+    match (variation, parts.get(11).and_then(|d| d.split('*').next())) {
+        (Some(variation), Some("E")) => data.magnetic_variation = Some(variation),
+        (Some(variation), Some("W")) => data.magnetic_variation = Some(-variation),
+        _ => {}
+    }
+}
+
+/// Combine RMC "hhmmss.ss" time and "ddmmyy" date fields
+fn parse_rmc_date_time(time: &str, date: &str) -> Option<DateTime<Utc>> {
+    let date = NaiveDate::parse_from_str(date, "%d%m%y").ok()?;
+    let time = NaiveTime::parse_from_str(time, "%H%M%S%.f").ok()?;
+    Some(date.and_time(time).and_utc())
 }
 
 /// Parse GSV (Satellites in View) sentence
@@ -117,54 +360,60 @@ fn parse_gsv(data: &mut GpsData, parts: &[&str], line: &str) {
         "UNKNOWN"
     };
 
+    // Split the checksum off the final field so it can't be read as a value
+    let mut fields = parts.to_vec();
+    if let Some(last) = fields.last_mut() {
+        *last = last.split('*').next().unwrap_or("");
+    }
+    let field = |index: usize| fields.get(index).copied().filter(|f| !f.is_empty());
+
     // Parse message number and total messages
-    let message_num = parts[2].parse::<u8>().unwrap_or(0);
+    let message_num = parts[2].parse::<usize>().unwrap_or(0);
     let _total_messages = parts[1].parse::<u8>().unwrap_or(0);
 
     // If this is the first message, clear existing satellites for this constellation
     if message_num == 1 {
         data.satellites_info.retain(|sat| sat.constellation != constellation);
     }
+    data.mark_updated(DataField::Satellites);
 
-    // Parse satellite information (up to 4 satellites per message)
-    let mut sat_index = 4; // Start after header fields
-    while sat_index + 3 < parts.len() {
-        if let Ok(prn) = parts[sat_index].parse::<u8>() {
-            let mut sat_info = SatelliteInfo::new(prn);
-            sat_info.constellation = constellation.to_string();
+    // Satellites in this message: up to 4, fewer in the last message of a
+    // group. Counting from field 3 ignores the NMEA 4.10 signal ID that may
+    // follow the last block. Without a usable count, take the blocks present.
+    let in_message = match field(3).and_then(|f| f.parse::<usize>().ok()) {
+        Some(in_view) if message_num > 0 => in_view.saturating_sub((message_num - 1) * 4).min(4),
+        _ => fields.len().saturating_sub(4).div_ceil(4).min(4),
+    };
 
-            // Elevation
-            if !parts[sat_index + 1].is_empty() {
-                sat_info.elevation = parts[sat_index + 1].parse::<f32>().ok();
-            }
+    for block in 0..in_message {
+        let base = 4 + block * 4;
+        let Some(prn) = field(base).and_then(|f| f.parse::<u8>().ok()) else {
+            continue;
+        };
 
-            // Azimuth
-            if !parts[sat_index + 2].is_empty() {
-                sat_info.azimuth = parts[sat_index + 2].parse::<f32>().ok();
-            }
+        let mut sat_info = SatelliteInfo::new(prn);
+        sat_info.constellation = constellation.to_string();
+        sat_info.source = data.source.clone();
 
-            // SNR (may be empty)
-            if sat_index + 3 < parts.len() && !parts[sat_index + 3].is_empty() {
-                // Remove checksum if present
-                let snr_str = parts[sat_index + 3].split('*').next().unwrap_or(parts[sat_index + 3]);
-                sat_info.snr = snr_str.parse::<f32>().ok();
-            }
+        // Elevation, azimuth and SNR may each be empty or cut off
+        sat_info.elevation = field(base + 1).and_then(|f| f.parse::<f32>().ok());
+        sat_info.azimuth = field(base + 2).and_then(|f| f.parse::<f32>().ok());
+        sat_info.snr = field(base + 3).and_then(|f| f.parse::<f32>().ok());
+        sat_info.used = data.listed_as_used(constellation, prn);
 
-            // Add or update satellite info
-            if let Some(existing) = data.satellites_info.iter_mut().find(|s| s.prn == prn) {
-                *existing = sat_info;
-            } else {
-                data.satellites_info.push(sat_info);
-            }
+        // Add or update satellite info
+        if let Some(existing) = data.satellites_info.iter_mut().find(|s| s.prn == prn) {
+            *existing = sat_info;
+        } else {
+            data.satellites_info.push(sat_info);
         }
-
-        sat_index += 4;
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::gps::data::FixValidity;
 
     #[test]
     fn test_gpgga_parsing() {
@@ -181,6 +430,52 @@ mod tests {
         assert_eq!(data.fix_quality, Some(1));
     }
 
+    #[test]
+    fn test_gpgga_altitude_in_feet() {
+        let mut data = GpsData::new();
+        parse_nmea_sentence(&mut data, "$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,1789.4,F,153.9,F,,*41");
+        assert!((data.altitude.unwrap() - 1789.4 * METERS_PER_FOOT).abs() < 1e-9);
+        assert!((data.geoid_separation.unwrap() - 153.9 * METERS_PER_FOOT).abs() < 1e-9);
+
+        // Meters are stored as they are
+        parse_nmea_sentence(&mut data, "$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47");
+        assert_eq!(data.altitude, Some(545.4));
+        assert_eq!(data.geoid_separation, Some(46.9));
+    }
+
+    #[test]
+    fn test_gpgga_zero_and_negative_altitude() {
+        let mut data = GpsData::new();
+        parse_nmea_sentence(&mut data, "$GPGGA,123519,3630.000,N,11651.000,W,1,08,0.9,-85.9,M,-32.1,M,,*4B");
+        assert_eq!(data.altitude, Some(-85.9));
+
+        parse_nmea_sentence(&mut data, "$GPGGA,123520,3630.000,N,11651.000,W,1,08,0.9,0.0,M,-32.1,M,,*6B");
+        assert_eq!(data.altitude, Some(0.0));
+    }
+
+    #[test]
+    fn test_gns_parsing() {
+        let mut data = GpsData::new();
+        let gns = "$GNGNS,014035.00,4332.69262,S,17235.48549,E,RR,13,0.9,25.63,11.24,,*70";
+
+        parse_nmea_sentence(&mut data, gns);
+
+        assert!((data.latitude.unwrap() - -43.544877).abs() < 0.00001);
+        assert!((data.longitude.unwrap() - 172.591425).abs() < 0.00001);
+        assert_eq!(data.fix_quality, Some(4));
+        assert_eq!(data.satellites, Some(13));
+        assert_eq!(data.hdop, Some(0.9));
+        assert_eq!(data.altitude, Some(25.63));
+    }
+
+    #[test]
+    fn test_gns_mode_indicator() {
+        assert_eq!(gns_mode_to_fix_quality("NN"), 0);
+        assert_eq!(gns_mode_to_fix_quality("AN"), 1);
+        assert_eq!(gns_mode_to_fix_quality("ADN"), 2);
+        assert_eq!(gns_mode_to_fix_quality("FR"), 4);
+    }
+
     #[test]
     fn test_gprmc_parsing() {
         let mut data = GpsData::new();
@@ -193,6 +488,7 @@ mod tests {
         // Speed should be converted from knots to km/h
         assert!((data.speed.unwrap() - 41.5).abs() < 0.1);
         assert_eq!(data.course, Some(84.4));
+        assert_eq!(data.magnetic_variation, Some(-3.1));
     }
 
     #[test]
@@ -210,6 +506,47 @@ mod tests {
         assert_eq!(data.satellites_info[0].snr, Some(46.0));
     }
 
+    #[test]
+    fn test_gsv_partial_final_block() {
+        let mut data = GpsData::new();
+        parse_nmea_sentence(&mut data, "$GPGSV,1,1,03,01,40,083,46,02,17,308,41,14,22*79");
+
+        assert_eq!(data.satellites_info.len(), 3);
+        let last = &data.satellites_info[2];
+        assert_eq!(last.prn, 14);
+        assert_eq!(last.elevation, Some(22.0));
+        assert_eq!(last.azimuth, None);
+        assert_eq!(last.snr, None);
+
+        // A trailing signal ID is not mistaken for another satellite
+        parse_nmea_sentence(&mut data, "$GPGSV,2,2,06,03,12,045,33,04,60,270,40,1*60");
+        let prns: Vec<u8> = data.satellites_info.iter().map(|sat| sat.prn).collect();
+        assert_eq!(prns, vec![1, 2, 14, 3, 4]);
+    }
+
+    #[test]
+    fn test_gsv_empty_trailing_snr() {
+        let mut data = GpsData::new();
+        parse_nmea_sentence(&mut data, "$GPGSV,1,1,02,01,40,083,46,02,17,308,*78");
+
+        assert_eq!(data.satellites_info.len(), 2);
+        assert_eq!(data.satellites_info[0].snr, Some(46.0));
+        assert_eq!(data.satellites_info[1].prn, 2);
+        assert_eq!(data.satellites_info[1].azimuth, Some(308.0));
+        assert_eq!(data.satellites_info[1].snr, None);
+    }
+
+    #[test]
+    fn test_has_valid_checksum() {
+        assert!(has_valid_checksum("$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47"));
+        assert!(has_valid_checksum("$GPGSV,1,1,02,01,40,083,46,02,17,308,*78\r\n"));
+        assert!(!has_valid_checksum("$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*48"));
+        assert!(!has_valid_checksum("$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,"));
+        assert!(!has_valid_checksum("GPGGA,123519*47"));
+        assert!(!has_valid_checksum("$*00"));
+        assert!(!has_valid_checksum("\u{fffd}\u{fffd}$G\u{fffd}*zz"));
+    }
+
     #[test]
     fn test_invalid_sentence() {
         let mut data = GpsData::new();
@@ -221,4 +558,183 @@ mod tests {
         assert!(data.latitude.is_none());
         assert!(data.longitude.is_none());
     }
+
+    #[test]
+    fn test_rmc_status_and_mode_indicator() {
+        let mut data = GpsData::new();
+        parse_nmea_sentence(&mut data, "$GPRMC,123519,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W,D*6A");
+        assert_eq!(data.rmc_status, Some('A'));
+        assert_eq!(data.mode_indicator, Some('D'));
+        assert_eq!(data.fix_validity(), FixValidity::Valid);
+
+        parse_nmea_sentence(&mut data, "$GPRMC,123520,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W,E*6A");
+        assert_eq!(data.fix_validity(), FixValidity::Estimated);
+        data.fix_quality = Some(1);
+        assert_eq!(data.get_fix_description(), "GPS (estimated)");
+
+        // Void status, even with a position present
+        data.latitude = Some(48.1173);
+        data.longitude = Some(11.5167);
+        data.update_timestamp();
+        assert!(data.has_valid_fix());
+        parse_nmea_sentence(&mut data, "$GPRMC,123521,V,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W,N*6A");
+        assert_eq!(data.fix_validity(), FixValidity::Invalid);
+        assert!(!data.has_valid_fix());
+        assert_eq!(data.get_fix_description(), "Invalid (receiver flagged void)");
+
+        // Pre-2.3 RMC has no mode indicator
+        parse_nmea_sentence(&mut data, "$GPRMC,123519,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W*6A");
+        assert_eq!(data.mode_indicator, None);
+        assert_eq!(data.fix_validity(), FixValidity::Valid);
+    }
+
+    #[test]
+    fn test_rmc_time_sets_clock_offset() {
+        use chrono::TimeZone;
+        let mut data = GpsData::new();
+
+        // Void fixes don't establish GPS time
+        parse_nmea_sentence(&mut data, "$GPRMC,123519,V,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W*7D");
+        assert_eq!(data.clock_offset, None);
+
+        data.timestamp = Some(Utc.with_ymd_and_hms(1994, 3, 23, 12, 30, 0).unwrap());
+        parse_nmea_sentence(&mut data, "$GPRMC,123519.50,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W*6A");
+        assert_eq!(data.gps_time, Some(Utc.with_ymd_and_hms(1994, 3, 23, 12, 35, 19).unwrap() + chrono::Duration::milliseconds(500)));
+        assert_eq!(data.clock_offset, Some(chrono::Duration::milliseconds(319_500)));
+    }
+
+    #[test]
+    fn test_gsa_fix_mode() {
+        let mut data = GpsData::new();
+        parse_nmea_sentence(&mut data, "$GPGSA,A,2,04,05,09,,,,,,,,,,2.5,1.3,2.1*39");
+        assert_eq!(data.mode, Some(2));
+        assert!(data.is_2d_fix());
+
+        parse_nmea_sentence(&mut data, "$GNGSA,A,3,04,05,09,12,,,,,,,,,2.5,1.3,2.1*39");
+        assert_eq!(data.mode, Some(3));
+        assert!(!data.is_2d_fix());
+
+        // An empty mode field leaves the last one in place
+        parse_nmea_sentence(&mut data, "$GPGSA,A,,,,,,,,,,,,,,,,*6E");
+        assert_eq!(data.mode, Some(3));
+    }
+
+    #[test]
+    fn test_gsa_used_satellites() {
+        let mut data = GpsData::new();
+        parse_nmea_sentence(&mut data, "$GPGSV,2,1,07,05,45,090,40,12,30,270,35,15,60,120,42,18,20,200,30*7A");
+        parse_nmea_sentence(&mut data, "$GLGSV,1,1,02,65,40,100,36,71,25,220,31*67");
+        let used = |data: &GpsData| {
+            let mut used: Vec<u8> = data.satellites_info.iter().filter(|sat| sat.used).map(|sat| sat.prn).collect();
+            used.sort();
+            used
+        };
+
+        // One GNGSA per constellation, told apart by the system ID
+        parse_nmea_sentence(&mut data, "$GNGSA,A,3,05,12,15,24,29,,,,,,,,1.8,0.9,1.5,1*3A");
+        parse_nmea_sentence(&mut data, "$GNGSA,A,3,65,,,,,,,,,,,,1.8,0.9,1.5,2*35");
+        assert_eq!(used(&data), [5, 12, 15, 65]);
+
+        // Satellites reported after the GSA are marked too
+        parse_nmea_sentence(&mut data, "$GPGSV,2,2,07,24,50,300,38,25,10,010,20,29,70,040,44*48");
+        assert_eq!(used(&data), [5, 12, 15, 24, 29, 65]);
+
+        // Without a system ID, GLONASS is recognized by its PRN range
+        parse_nmea_sentence(&mut data, "$GNGSA,A,3,05,12,65,,,,,,,,,,1.8,0.9,1.5*2D");
+        assert_eq!(used(&data), [5, 12, 65]);
+    }
+
+    #[test]
+    fn test_dirty_sentences() {
+        let gga = "$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47";
+        assert_eq!(clean_sentence(gga), Some(Cow::Borrowed(gga)));
+        assert_eq!(clean_sentence("\u{ff}\u{3}x$GPGGA,1*00\r\n").as_deref(), Some("$GPGGA,1*00"));
+        assert_eq!(clean_sentence("$GPGGA,1\0,2\0*00").as_deref(), Some("$GPGGA,1,2*00"));
+        assert_eq!(clean_sentence("no sentence here"), None);
+
+        for dirty in [
+            format!("\u{fffd}~{}", gga),
+            format!("\0\0{}\r", gga),
+            format!("{}{}", &gga[..20], &format!("\0{}", &gga[20..])),
+        ] {
+            let mut data = GpsData::new();
+            parse_nmea_sentence(&mut data, &dirty);
+            assert_eq!(data.fix_quality, Some(1), "{:?}", dirty);
+            assert_eq!(data.altitude, Some(545.4));
+        }
+    }
+
+    #[test]
+    fn test_rmc_speed_units() {
+        let rmc = "$GPRMC,123519,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W*6A";
+        let speed_with = |unit| {
+            let mut data = GpsData::new();
+            parse_nmea_sentence_with_speed_unit(&mut data, rmc, unit);
+            data.speed.unwrap()
+        };
+
+        assert!((speed_with(SpeedUnit::Knots) - 41.4848).abs() < 1e-9);
+        assert!((speed_with(SpeedUnit::KilometersPerHour) - 22.4).abs() < 1e-9);
+        assert!((speed_with(SpeedUnit::MetersPerSecond) - 80.64).abs() < 1e-9);
+
+        // The default entry point keeps the standard knots behavior
+        let mut data = GpsData::new();
+        parse_nmea_sentence(&mut data, rmc);
+        assert_eq!(data.speed, Some(speed_with(SpeedUnit::Knots)));
+    }
+
+    #[test]
+    fn test_field_ages_update_independently() {
+        let mut data = GpsData::new();
+        assert_eq!(data.field_updated(DataField::Position), None);
+
+        let t0 = Utc::now() - chrono::Duration::seconds(30);
+        data.timestamp = Some(t0);
+        parse_nmea_sentence(&mut data, "$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47");
+        assert_eq!(data.field_updated(DataField::Position), Some(t0));
+        assert_eq!(data.field_updated(DataField::Velocity), None);
+        assert_eq!(data.field_updated(DataField::Satellites), None);
+
+        // Only velocity moves on with an RMC (which carries no position here)
+        let t1 = t0 + chrono::Duration::seconds(10);
+        data.timestamp = Some(t1);
+        parse_nmea_sentence(&mut data, "$GPRMC,123519,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W*6A");
+        assert_eq!(data.field_updated(DataField::Velocity), Some(t1));
+        assert_eq!(data.field_updated(DataField::Position), Some(t0));
+
+        let t2 = t1 + chrono::Duration::seconds(10);
+        data.timestamp = Some(t2);
+        parse_nmea_sentence(&mut data, "$GPGSV,1,1,02,01,40,083,46,02,17,308,41*7A");
+        assert_eq!(data.field_updated(DataField::Satellites), Some(t2));
+        assert_eq!(data.field_updated(DataField::Velocity), Some(t1));
+
+        let max_age = chrono::Duration::seconds(15);
+        assert!(data.is_field_stale(DataField::Position, max_age));
+        assert!(!data.is_field_stale(DataField::Satellites, max_age));
+        assert!(data.field_age(DataField::Position).unwrap() > data.field_age(DataField::Velocity).unwrap());
+
+        // A GGA without coordinates doesn't refresh the position
+        data.timestamp = Some(t2);
+        parse_nmea_sentence(&mut data, "$GPGGA,123520,,,,,0,00,,,M,,M,,*66");
+        assert_eq!(data.field_updated(DataField::Position), Some(t0));
+    }
+
+    #[test]
+    fn test_txt_antenna_fault() {
+        let mut data = GpsData::new();
+        parse_nmea_sentence(&mut data, "$GNTXT,01,01,02,u-blox AG - www.u-blox.com*4E");
+        assert_eq!(data.text_messages.len(), 1);
+        assert_eq!(data.text_messages[0].severity, TextSeverity::Notice);
+        assert_eq!(data.text_messages[0].text, "u-blox AG - www.u-blox.com");
+        assert!(data.receiver_fault(Utc::now()).is_none());
+
+        parse_nmea_sentence(&mut data, "$GPTXT,01,01,01,ANTENNA OPEN*25");
+        assert_eq!(data.receiver_fault(Utc::now()).map(|m| m.text.as_str()), Some("ANTENNA OPEN"));
+
+        // Stays active until the antenna is reported OK again
+        let later = Utc::now() + chrono::Duration::seconds(600);
+        assert!(data.receiver_fault(later).is_some());
+        parse_nmea_sentence(&mut data, "$GPTXT,01,01,01,ANTENNA OK*35");
+        assert!(data.receiver_fault(Utc::now()).is_none());
+    }
 }
@@ -0,0 +1,323 @@
+// src/gps/nmea_decoder.rs v3
+//! Field-by-field NMEA sentence decoding for inspection (does not touch GpsData)
+
+use super::data::GpsData;
+use super::nmea::{self, parse_coordinate, SpeedUnit};
+
+/// One field of a decoded sentence
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedField {
+    pub name: String,
+    pub raw: String,
+    pub value: String,
+}
+
+/// A sentence broken into labeled fields
+#[derive(Debug, Clone)]
+pub struct DecodedSentence {
+    pub talker: String,
+    pub sentence_type: String,
+    pub description: &'static str,
+    pub fields: Vec<DecodedField>,
+}
+
+/// Decode a raw NMEA sentence into labeled fields. Unknown sentence types
+/// get generic "Field N" names with their raw values.
+pub fn decode_sentence(line: &str) -> DecodedSentence {
+    decode_sentence_with_speed_unit(line, SpeedUnit::Knots)
+}
+
+/// Decode a raw NMEA sentence, reading RMC speed in `rmc_speed_unit` as the
+/// monitor does for a receiver configured that way
+pub fn decode_sentence_with_speed_unit(line: &str, rmc_speed_unit: SpeedUnit) -> DecodedSentence {
+    let line = line.trim();
+    let body = line.strip_prefix('$').unwrap_or(line);
+    let (body, checksum) = match body.split_once('*') {
+        Some((body, checksum)) => (body, Some(checksum)),
+        None => (body, None),
+    };
+
+    let parts: Vec<&str> = body.split(',').collect();
+    let address = parts[0];
+    let split = address.get(..2).zip(address.get(2..))
+        .filter(|_| !address.starts_with('P') && address.len() >= 5);
+    let (talker, sentence_type) = match split {
+        Some((talker, sentence_type)) => (talker.to_string(), sentence_type.to_string()),
+        // Proprietary sentences have no standard talker/type split, and
+        // neither does an address garbled into non-ASCII
+        None => (String::new(), address.to_string()),
+    };
+
+    let values = &parts[1..];
+    let (description, mut fields) = match sentence_type.as_str() {
+        "GGA" => ("Global Positioning System Fix Data", decode_gga(values)),
+        "RMC" => ("Recommended Minimum Specific GNSS Data", decode_rmc(values, rmc_speed_unit)),
+        "GNS" => ("GNSS Fix Data", decode_gns(values)),
+        "GSA" => ("GNSS DOP and Active Satellites", decode_gsa(values)),
+        "GSV" => ("GNSS Satellites in View", decode_gsv(values)),
+        "VTG" => ("Course Over Ground and Ground Speed", decode_vtg(values)),
+        _ => ("Unknown sentence type", decode_generic(values)),
+    };
+
+    if let Some(checksum) = checksum {
+        let computed = nmea::checksum(body);
+        let value = match u8::from_str_radix(checksum, 16) {
+            Ok(expected) if expected == computed => "Valid".to_string(),
+            _ => format!("Mismatch (computed {:02X})", computed),
+        };
+        fields.push(field("Checksum", checksum, value));
+    }
+
+    DecodedSentence {
+        talker,
+        sentence_type,
+        description,
+        fields,
+    }
+}
+
+fn field(name: &str, raw: &str, value: String) -> DecodedField {
+    DecodedField {
+        name: name.to_string(),
+        raw: raw.to_string(),
+        value,
+    }
+}
+
+/// Pair values with names, interpreting each with `interpret`. Extra values
+/// are kept as generic fields.
+fn label_fields(values: &[&str], names: &[&str], interpret: impl Fn(usize, &str) -> String) -> Vec<DecodedField> {
+    values.iter().enumerate()
+        .map(|(i, raw)| match names.get(i) {
+            Some(name) => field(name, raw, if raw.is_empty() { "(empty)".to_string() } else { interpret(i, raw) }),
+            None => field(&format!("Field {}", i + 1), raw, raw.to_string()),
+        })
+        .collect()
+}
+
+fn decode_generic(values: &[&str]) -> Vec<DecodedField> {
+    label_fields(values, &[], |_, raw| raw.to_string())
+}
+
+fn decode_gga(values: &[&str]) -> Vec<DecodedField> {
+    const NAMES: [&str; 14] = [
+        "UTC time", "Latitude", "N/S", "Longitude", "E/W", "Fix quality", "Satellites in use",
+        "HDOP", "Altitude", "Altitude units", "Geoid separation", "Separation units",
+        "DGPS age", "DGPS station",
+    ];
+    label_fields(values, &NAMES, |i, raw| match i {
+        0 => format_time(raw),
+        1 => format_coordinate(raw, values.get(2), "S"),
+        3 => format_coordinate(raw, values.get(4), "W"),
+        5 => raw.parse::<u8>()
+            .map(|q| GpsData { fix_quality: Some(q), ..GpsData::default() }.get_fix_description())
+            .unwrap_or_else(|_| raw.to_string()),
+        8 | 10 => format!("{} m", raw),
+        12 => format!("{} s", raw),
+        _ => raw.to_string(),
+    })
+}
+
+fn decode_rmc(values: &[&str], speed_unit: SpeedUnit) -> Vec<DecodedField> {
+    let speed = match speed_unit {
+        SpeedUnit::Knots => "Speed (knots)",
+        SpeedUnit::KilometersPerHour => "Speed (km/h)",
+        SpeedUnit::MetersPerSecond => "Speed (m/s)",
+    };
+    let names = [
+        "UTC time", "Status", "Latitude", "N/S", "Longitude", "E/W", speed,
+        "Course", "Date", "Magnetic variation", "Variation E/W", "Mode indicator",
+    ];
+    label_fields(values, &names, |i, raw| match i {
+        0 => format_time(raw),
+        1 => match raw {
+            "A" => "Active (valid)".to_string(),
+            "V" => "Void (invalid)".to_string(),
+            _ => raw.to_string(),
+        },
+        2 => format_coordinate(raw, values.get(3), "S"),
+        4 => format_coordinate(raw, values.get(5), "W"),
+        6 => raw.parse::<f64>()
+            .map(|speed| format!("{:.1} km/h", speed_unit.to_kmh(speed)))
+            .unwrap_or_else(|_| raw.to_string()),
+        7 | 9 => format!("{}°", raw),
+        8 => format_date(raw),
+        11 => mode_indicator(raw),
+        _ => raw.to_string(),
+    })
+}
+
+fn decode_gns(values: &[&str]) -> Vec<DecodedField> {
+    const NAMES: [&str; 13] = [
+        "UTC time", "Latitude", "N/S", "Longitude", "E/W", "Mode indicator", "Satellites in use",
+        "HDOP", "Altitude", "Geoid separation", "DGPS age", "DGPS station", "Navigational status",
+    ];
+    label_fields(values, &NAMES, |i, raw| match i {
+        0 => format_time(raw),
+        1 => format_coordinate(raw, values.get(2), "S"),
+        3 => format_coordinate(raw, values.get(4), "W"),
+        5 => raw.chars().map(|c| mode_indicator(&c.to_string())).collect::<Vec<_>>().join(", "),
+        8 | 9 => format!("{} m", raw),
+        10 => format!("{} s", raw),
+        _ => raw.to_string(),
+    })
+}
+
+fn decode_gsa(values: &[&str]) -> Vec<DecodedField> {
+    let mut names = vec!["Selection mode".to_string(), "Fix type".to_string()];
+    names.extend((1..=12).map(|n| format!("Satellite {}", n)));
+    names.extend(["PDOP", "HDOP", "VDOP", "System ID"].map(str::to_string));
+    let names: Vec<&str> = names.iter().map(String::as_str).collect();
+
+    label_fields(values, &names, |i, raw| match i {
+        0 => match raw {
+            "A" => "Automatic 2D/3D".to_string(),
+            "M" => "Manual".to_string(),
+            _ => raw.to_string(),
+        },
+        1 => match raw {
+            "1" => "No fix".to_string(),
+            "2" => "2D fix".to_string(),
+            "3" => "3D fix".to_string(),
+            _ => raw.to_string(),
+        },
+        2..=13 => format!("PRN {}", raw),
+        _ => raw.to_string(),
+    })
+}
+
+fn decode_gsv(values: &[&str]) -> Vec<DecodedField> {
+    let mut names = vec!["Total messages".to_string(), "Message number".to_string(), "Satellites in view".to_string()];
+    // Up to four satellites per message, plus an optional signal ID at the end
+    let sat_fields = values.len().saturating_sub(3);
+    for n in 1..=sat_fields / 4 {
+        names.extend([
+            format!("Sat {} PRN", n),
+            format!("Sat {} elevation", n),
+            format!("Sat {} azimuth", n),
+            format!("Sat {} SNR", n),
+        ]);
+    }
+    if sat_fields % 4 == 1 {
+        names.push("Signal ID".to_string());
+    }
+    let names: Vec<&str> = names.iter().map(String::as_str).collect();
+
+    label_fields(values, &names, |i, raw| {
+        if i < 3 || i - 3 >= sat_fields / 4 * 4 {
+            return raw.to_string();
+        }
+        match (i - 3) % 4 {
+            1 | 2 => format!("{}°", raw),
+            3 => format!("{} dB", raw),
+            _ => raw.to_string(),
+        }
+    })
+}
+
+fn decode_vtg(values: &[&str]) -> Vec<DecodedField> {
+    const NAMES: [&str; 9] = [
+        "Course (true)", "T", "Course (magnetic)", "M", "Speed (knots)", "N",
+        "Speed (km/h)", "K", "Mode indicator",
+    ];
+    label_fields(values, &NAMES, |i, raw| match i {
+        0 | 2 => format!("{}°", raw),
+        4 => format!("{} kn", raw),
+        6 => format!("{} km/h", raw),
+        8 => mode_indicator(raw),
+        _ => raw.to_string(),
+    })
+}
+
+/// hhmmss.ss -> hh:mm:ss.ss UTC
+fn format_time(raw: &str) -> String {
+    if raw.len() < 6 || !raw.is_char_boundary(6) {
+        return raw.to_string();
+    }
+    format!("{}:{}:{} UTC", &raw[0..2], &raw[2..4], &raw[4..])
+}
+
+/// ddmmyy -> yyyy-mm-dd, treating 80-99 as the 1900s
+fn format_date(raw: &str) -> String {
+    if raw.len() != 6 || !raw.is_ascii() {
+        return raw.to_string();
+    }
+    let Ok(year) = raw[4..6].parse::<u32>() else {
+        return raw.to_string();
+    };
+    let century = if year >= 80 { 1900 } else { 2000 };
+    format!("{}-{}-{}", century + year, &raw[2..4], &raw[0..2])
+}
+
+fn format_coordinate(raw: &str, hemisphere: Option<&&str>, negative: &str) -> String {
+    hemisphere
+        .and_then(|h| parse_coordinate(raw, h, negative))
+        .map_or_else(|| raw.to_string(), |coord| format!("{:.6}°", coord))
+}
+
+fn mode_indicator(raw: &str) -> String {
+    let description = match raw {
+        "A" => "Autonomous",
+        "D" => "Differential",
+        "E" => "Estimated",
+        "F" => "Float RTK",
+        "M" => "Manual",
+        "N" => "No fix",
+        "P" => "Precise",
+        "R" => "RTK",
+        "S" => "Simulator",
+        _ => return raw.to_string(),
+    };
+    format!("{} ({})", description, raw)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value_of<'a>(decoded: &'a DecodedSentence, name: &str) -> &'a str {
+        &decoded.fields.iter().find(|f| f.name == name).unwrap().value
+    }
+
+    #[test]
+    fn test_decode_gga() {
+        let decoded = decode_sentence("$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47");
+        assert_eq!(decoded.talker, "GP");
+        assert_eq!(decoded.sentence_type, "GGA");
+        assert_eq!(value_of(&decoded, "UTC time"), "12:35:19 UTC");
+        assert_eq!(value_of(&decoded, "Latitude"), "48.117300°");
+        assert_eq!(value_of(&decoded, "Fix quality"), "GPS");
+        assert_eq!(value_of(&decoded, "Altitude"), "545.4 m");
+        assert_eq!(value_of(&decoded, "DGPS age"), "(empty)");
+        assert_eq!(value_of(&decoded, "Checksum"), "Valid");
+    }
+
+    #[test]
+    fn test_decode_rmc_and_bad_checksum() {
+        let decoded = decode_sentence("$GPRMC,123519,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W*00");
+        assert_eq!(value_of(&decoded, "Status"), "Active (valid)");
+        assert_eq!(value_of(&decoded, "Speed (knots)"), "41.5 km/h");
+        assert_eq!(value_of(&decoded, "Date"), "1994-03-23");
+        assert_eq!(value_of(&decoded, "Checksum"), "Mismatch (computed 6A)");
+
+        // A receiver set to report RMC speed in m/s
+        let decoded = decode_sentence_with_speed_unit("$GPRMC,123519,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W*00", SpeedUnit::MetersPerSecond);
+        assert_eq!(value_of(&decoded, "Speed (m/s)"), "80.6 km/h");
+    }
+
+    #[test]
+    fn test_decode_gsv_and_unknown() {
+        let gsv = decode_sentence("$GPGSV,2,1,08,01,40,083,46,02,17,308,41,12,07,344,39,14,22,228,45*75");
+        assert_eq!(value_of(&gsv, "Sat 1 elevation"), "40°");
+        assert_eq!(value_of(&gsv, "Sat 4 SNR"), "45 dB");
+
+        let unknown = decode_sentence("$PUBX,00,081350.00,4717.113210,N*00");
+        assert_eq!(unknown.description, "Unknown sentence type");
+        assert_eq!(unknown.fields[0].name, "Field 1");
+        assert_eq!(unknown.fields[0].value, "00");
+
+        let garbled = decode_sentence("$Gé GSV,1,1,00*00");
+        assert_eq!(garbled.talker, "");
+        assert_eq!(garbled.sentence_type, "Gé GSV");
+    }
+}
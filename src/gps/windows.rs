@@ -3,8 +3,8 @@
 
 #[cfg(windows)]
 use {
-    super::data::GpsData,
-    crate::error::{Result, GpsError},
+    super::data::{DataField, GpsData},
+    crate::{error::{Result, GpsError}, lock::RecoverPoison},
     std::time::Duration,
     tokio::time::sleep,
     windows::{
@@ -80,12 +80,14 @@ pub fn update_from_position(data: &mut GpsData, position: &Geoposition) -> Resul
             if let Ok(pos) = point.Position() {
                 data.latitude = Some(pos.Latitude);
                 data.longitude = Some(pos.Longitude);
+                data.mark_updated(DataField::Position);
                 
-                // Altitude (optional) - it's a direct f64 value, not a Result
-                let alt = pos.Altitude;
-                if alt != 0.0 {
-                    data.altitude = Some(alt);
-                }
+                // Altitude is a plain f64 that reads 0.0 when the fix has
+                // none; an unspecified reference system marks that case, so
+                // zero and below-sea-level altitudes are kept otherwise
+                let has_altitude = point.AltitudeReferenceSystem()
+                    .is_ok_and(|system| system != AltitudeReferenceSystem::Unspecified);
+                data.altitude = (has_altitude && pos.Altitude.is_finite()).then_some(pos.Altitude);
             }
         }
         
@@ -98,6 +100,7 @@ pub fn update_from_position(data: &mut GpsData, position: &Geoposition) -> Resul
         if let Ok(heading) = coordinate.Heading() {
             if let Ok(h) = heading.Value() {
                 data.course = Some(h);
+                data.mark_updated(DataField::Velocity);
             }
         }
         
@@ -105,6 +108,7 @@ pub fn update_from_position(data: &mut GpsData, position: &Geoposition) -> Resul
         if let Ok(speed) = coordinate.Speed() {
             if let Ok(s) = speed.Value() {
                 data.speed = Some(s * 3.6); // Convert m/s to km/h
+                data.mark_updated(DataField::Velocity);
             }
         }
     }
@@ -133,10 +137,11 @@ pub async fn run_location_monitoring(
     while running.load(Ordering::Relaxed) {
         match get_position(&geolocator).await {
             Ok(position) => {
-                let mut data_guard = data.write().unwrap();
+                let mut data_guard = data.write_recover();
                 if let Err(e) = update_from_position(&mut data_guard, &position) {
                     eprintln!("Error updating position data: {}", e);
                 }
+                data_guard.update_time_to_first_fix();
             }
             Err(e) => {
                 eprintln!("Error getting Windows location: {}", e);
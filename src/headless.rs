@@ -0,0 +1,367 @@
+// src/headless.rs v3
+//! Unattended logging without the GUI, e.g. a Raspberry Pi on battery:
+//! connect, record a track, write the CSV and raw logs and serve the status
+//! endpoint, with nothing rendered. Started with `gps-monitor --headless`.
+
+use crate::{
+    config::GpsConfig,
+    data_log::DataLogger,
+    error::{GpsError, Result},
+    gps::{ConnectionStatus, GpsData},
+    lock::RecoverPoison,
+    monitor::{GpsMonitor, GpsSource},
+    track_recorder::{unused_path, RecordingCheckpoint, TrackRecorder},
+    waypoint::{Track, WaypointExporter, WaypointFormat},
+};
+use std::{
+    fs::{self, OpenOptions},
+    future::Future,
+    io::{LineWriter, Write},
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, RwLock,
+    },
+    time::{Duration, Instant},
+};
+#[cfg(feature = "status-server")]
+use crate::status_server::StatusServer;
+
+/// How often the recorder samples the latest fix
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Wait before connecting again after the source is lost
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// How often a running connection is checked for having been lost
+const CONNECTION_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Callback invoked with each status line of a headless run, such as a
+/// track being saved or a log failing
+pub type StatusObserver = Arc<dyn Fn(&str) + Send + Sync>;
+
+/// What a headless run writes besides the configured logs
+#[derive(Debug, Clone)]
+pub struct HeadlessOptions {
+    pub raw_log: Option<PathBuf>,     // Append every sentence received to this file
+    pub checkpoint: Option<PathBuf>,  // Resume from and keep saving the recording here
+    pub poll_interval: Duration,
+}
+
+impl Default for HeadlessOptions {
+    fn default() -> Self {
+        Self {
+            raw_log: None,
+            checkpoint: Some(RecordingCheckpoint::default_path()),
+            poll_interval: DEFAULT_POLL_INTERVAL,
+        }
+    }
+}
+
+/// The monitor and a track recorder wired together for an unattended run
+pub struct HeadlessLogger {
+    config: GpsConfig,
+    options: HeadlessOptions,
+    data: Arc<RwLock<GpsData>>,
+    running: Arc<AtomicBool>,
+    recorder: TrackRecorder,
+    saved: Vec<PathBuf>,
+    status: Option<StatusObserver>,
+}
+
+impl HeadlessLogger {
+    pub fn new(config: GpsConfig, options: HeadlessOptions) -> Self {
+        let mut recorder = TrackRecorder::new();
+        recorder.set_rotation(&config.track_rotation);
+        recorder.set_exclude_2d_altitude(config.exclude_2d_altitude);
+        recorder.set_satellite_snapshots(config.record_satellite_snapshots);
+        recorder.set_checkpoint_path(options.checkpoint.clone());
+
+        Self {
+            config,
+            options,
+            data: Arc::new(RwLock::new(GpsData::new())),
+            running: Arc::new(AtomicBool::new(false)),
+            recorder,
+            saved: Vec::new(),
+            status: None,
+        }
+    }
+
+    /// The recorder, to change its thresholds before `run`
+    pub fn recorder_mut(&mut self) -> &mut TrackRecorder {
+        &mut self.recorder
+    }
+
+    /// Observe what the run does and what goes wrong with it. Nothing is
+    /// reported without one; connection problems also show in the data's
+    /// connection status.
+    pub fn set_status_observer<F>(&mut self, observer: F)
+    where
+        F: Fn(&str) + Send + Sync + 'static,
+    {
+        self.status = Some(Arc::new(observer));
+    }
+
+    fn report(&self, message: &str) {
+        if let Some(status) = &self.status {
+            status(message);
+        }
+    }
+
+    /// Record until `shutdown` resolves, reconnecting whenever the source is
+    /// lost. Returns the track files written.
+    pub async fn run<S: Future<Output = ()>>(mut self, shutdown: S) -> Result<Vec<PathBuf>> {
+        self.config.validate()?;
+        self.running.store(true, Ordering::Relaxed);
+
+        let mut monitor = GpsMonitor::new_with_shared(Arc::clone(&self.data), Arc::clone(&self.running));
+        let satellite_timeout = self.config.satellite_timeout_secs;
+        monitor.set_satellite_max_age((satellite_timeout > 0).then(|| Duration::from_secs(satellite_timeout)));
+        if self.config.data_log.enabled {
+            DataLogger::new(self.config.data_log.clone()).attach(&mut monitor);
+        }
+        if let Some(path) = &self.options.raw_log {
+            attach_raw_log(&mut monitor, path, self.status.clone())?;
+        }
+
+        #[cfg(feature = "status-server")]
+        let status_server = self.start_status_server()?;
+
+        self.start_recording();
+
+        spawn_connection(&monitor, &self.config);
+
+        tokio::pin!(shutdown);
+        let mut ticker = tokio::time::interval(self.options.poll_interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            tokio::select! {
+                _ = &mut shutdown => break,
+                _ = ticker.tick() => self.poll(),
+            }
+        }
+
+        monitor.stop();
+        #[cfg(feature = "status-server")]
+        if let Some(task) = status_server {
+            task.abort();
+        }
+
+        if let Some(track) = self.recorder.finish_recording() {
+            self.save_track(track);
+        }
+        Ok(self.saved)
+    }
+
+    /// Continue an interrupted recording, e.g. after the battery ran out, or start a new one
+    fn start_recording(&mut self) {
+        let checkpoint = self.options.checkpoint.as_deref().and_then(|path| {
+            RecordingCheckpoint::load(path).unwrap_or_else(|e| {
+                self.report(&format!("Ignoring recording checkpoint: {}", e));
+                None
+            })
+        });
+        match checkpoint {
+            Some(checkpoint) => {
+                self.report(&format!("Resuming recording '{}'", checkpoint.track.name));
+                self.recorder.resume_from_checkpoint(checkpoint);
+            }
+            None => self.recorder.start_recording(String::new()),
+        }
+    }
+
+    /// Record the latest fix, starting over if the recorder stopped itself
+    fn poll(&mut self) {
+        let data = self.data.read_recover().clone();
+        let stopped = self.recorder.update(&data, Instant::now());
+        // Already written to their files; dropping them keeps a long run's memory flat
+        self.recorder.take_rotated_tracks();
+        if let Some((track, reason)) = stopped {
+            self.report(&format!("Recording stopped ({}), starting a new track", reason.description()));
+            self.save_track(track);
+            self.recorder.start_recording(String::new());
+        }
+    }
+
+    /// Write a finished track as GPX, unless rotation already exported it
+    fn save_track(&mut self, track: Track) {
+        if self.config.track_rotation.enabled {
+            self.saved.extend(self.recorder.last_rotation_file().map(PathBuf::from));
+            return;
+        }
+        if track.total_points() == 0 {
+            return;
+        }
+
+        let dir = self.config.track_rotation.output_path();
+        let start = track.start_time().unwrap_or_else(chrono::Utc::now);
+        let path = unused_path(&dir, &format!("track_{}.gpx", start.format("%Y-%m-%d_%H%M%S")));
+        let mut exporter = WaypointExporter::new();
+        exporter.add_track(track);
+        let result = fs::create_dir_all(&dir)
+            .map_err(GpsError::Io)
+            .and_then(|_| exporter.export_to_file(&path, WaypointFormat::GPX));
+
+        match result {
+            Ok(()) => {
+                self.report(&format!("Saved track to {}", path.display()));
+                self.saved.push(path);
+            }
+            Err(e) => self.report(&format!("Failed to save track {}: {}", path.display(), e)),
+        }
+    }
+
+    /// Serve GET /status if enabled
+    #[cfg(feature = "status-server")]
+    fn start_status_server(&self) -> Result<Option<tokio::task::JoinHandle<()>>> {
+        let endpoint = &self.config.status_endpoint;
+        if !endpoint.enabled {
+            return Ok(None);
+        }
+        let server = StatusServer::bind(&endpoint.address, Arc::clone(&self.data))?;
+        if let Ok(addr) = server.local_addr() {
+            self.report(&format!("Status endpoint at http://{}/status", addr));
+        }
+        Ok(Some(tokio::spawn(server.run())))
+    }
+}
+
+/// Append each sentence the monitor receives to `path`, one per line
+fn attach_raw_log(monitor: &mut GpsMonitor, path: &std::path::Path, status: Option<StatusObserver>) -> Result<()> {
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir)?;
+    }
+    let file = Mutex::new(LineWriter::new(OpenOptions::new().create(true).append(true).open(path)?));
+    let failed = AtomicBool::new(false);
+    monitor.set_sentence_observer(move |line| {
+        if failed.load(Ordering::Relaxed) {
+            return;
+        }
+        if let Err(e) = writeln!(file.lock().unwrap(), "{}", line.trim_end()) {
+            if let Some(status) = &status {
+                status(&format!("Raw log stopped: {}", e));
+            }
+            failed.store(true, Ordering::Relaxed);
+        }
+    });
+    Ok(())
+}
+
+/// Connect `monitor` to the configured source and keep it connected until
+/// the monitor is stopped. Must be called within a Tokio runtime.
+pub(crate) fn spawn_connection(monitor: &GpsMonitor, config: &GpsConfig) {
+    // The connection runs on its own thread like the GUI's, since reading
+    // some sources can't move between threads
+    let (connection, source) = (monitor.clone(), GpsSource::from_config(config));
+    let runtime = tokio::runtime::Handle::current();
+    std::thread::spawn(move || runtime.block_on(keep_connected(connection, source)));
+}
+
+/// Run the connection, starting it again after a delay until the monitor is
+/// stopped. Why a connection failed shows in the data's connection status.
+async fn keep_connected(monitor: GpsMonitor, source: GpsSource) {
+    let mut attempt = 0;
+    loop {
+        // Some sources return once reading has started in the background
+        if monitor.start(source.clone()).await.is_ok() {
+            while monitor.is_running() && !matches!(monitor.get_data().connection, ConnectionStatus::Lost(_)) {
+                tokio::time::sleep(CONNECTION_CHECK_INTERVAL).await;
+            }
+        }
+        if !monitor.is_running() {
+            return;
+        }
+        attempt += 1;
+        monitor.set_reconnecting(attempt);
+        tokio::time::sleep(RECONNECT_DELAY).await;
+    }
+}
+
+/// Wait for Ctrl+C, or SIGTERM on Unix
+pub async fn wait_for_shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        match signal(SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => {}
+                    _ = sigterm.recv() => {}
+                }
+            }
+            Err(e) => {
+                eprintln!("Failed to install SIGTERM handler: {}", e);
+                let _ = tokio::signal::ctrl_c().await;
+            }
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::track_recorder::RecordingMode;
+    use tokio::io::AsyncWriteExt;
+
+    #[tokio::test]
+    async fn test_headless_records_from_stand_in_gpsd() {
+        let dir = std::env::temp_dir().join(format!("gps-monitor-headless-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+
+        // A stand-in gpsd walking north a few meters a second
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            for i in 0..4 {
+                let tpv = format!(
+                    "{{\"class\":\"TPV\",\"mode\":3,\"time\":\"2024-05-01T12:00:0{}Z\",\"lat\":{},\"lon\":-71.1,\"alt\":30.0}}\n",
+                    i,
+                    42.5 + i as f64 * 0.0001,
+                );
+                socket.write_all(tpv.as_bytes()).await.unwrap();
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+            tokio::time::sleep(Duration::from_secs(5)).await;
+        });
+
+        let mut config = GpsConfig {
+            source_type: "gpsd".to_string(),
+            gpsd_host: Some("127.0.0.1".to_string()),
+            gpsd_port: Some(port),
+            ..GpsConfig::default()
+        };
+        config.track_rotation.output_dir = dir.join("tracks").to_string_lossy().into_owned();
+        config.data_log.enabled = true;
+        config.data_log.output_dir = dir.join("logs").to_string_lossy().into_owned();
+        let options = HeadlessOptions {
+            raw_log: Some(dir.join("raw.log")),
+            checkpoint: None,
+            poll_interval: Duration::from_millis(20),
+        };
+
+        let mut logger = HeadlessLogger::new(config, options);
+        logger.recorder_mut().set_mode(RecordingMode::DistanceOnly);
+        let saved = logger.run(tokio::time::sleep(Duration::from_millis(800))).await.unwrap();
+
+        assert_eq!(saved.len(), 1);
+        let gpx = fs::read_to_string(&saved[0]).unwrap();
+        assert_eq!(gpx.matches("<trkpt").count(), 4);
+
+        let raw = fs::read_to_string(dir.join("raw.log")).unwrap();
+        assert_eq!(raw.lines().count(), 4);
+        assert!(raw.lines().all(|line| line.contains("\"class\":\"TPV\"")));
+
+        let logs: Vec<_> = fs::read_dir(dir.join("logs")).unwrap().collect();
+        assert_eq!(logs.len(), 1);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -1,4 +1,4 @@
-// src/lib.rs v6
+// src/lib.rs v10
 //! GPS Monitor Library
 //! 
 //! A cross-platform GPS monitoring library that supports multiple GPS sources
@@ -11,14 +11,26 @@ pub mod error;
 pub mod config;
 pub mod waypoint;
 pub mod map;
+pub mod data_log;
+pub mod headless;
+pub mod track_recorder;
+pub mod odometer;
+pub mod lock;
+#[cfg(feature = "obd")]
+pub mod obd;
+#[cfg(feature = "status-server")]
+pub mod status_server;
 
 // Re-export main types for convenience
-pub use gps::data::GpsData;
-pub use monitor::{GpsMonitor, GpsSource};
+pub use gps::coordinates::{CoordinateFormat, Utm};
+pub use gps::data::{AltitudeSource, DataField, FixValidity, GpsData, NorthReference, SignalQuality, SnrThresholds, TextMessage, TextSeverity};
+pub use monitor::{GpsMonitor, GpsSource, MonitorBuilder, MonitorHandle, SentenceCounts, SentenceObserver, SourcePolicy, UpdateObserver};
 pub use error::{Result, GpsError};
-pub use config::GpsConfig;
-pub use waypoint::{Waypoint, WaypointExporter, WaypointFormat, Track, TrackPoint};
-pub use map::{TileCache, CacheStats};
+pub use config::{ColorPalette, ConfigProfiles, CopyTemplate, CsvExport, CsvTimestamp, DataLog, DisplaySpeedUnit, DisplayTimeZone, DistanceUnits, GpsConfig, HorizonMask, HorizonPoint, ImportThinning, MapOptions, MarkerStyle, NoFixWarning, ObdConfig, RecentExport, RotationInterval, SerialFlowControl, SessionAutosave, SpeedZoomStep, SerialLine, SerialParity, SkyPlotGrid, Speedometer, StallReconnect, StatusEndpoint, TrackRotation, WaypointFixHold, WaypointQualityGate};
+pub use waypoint::{ElevationCorrector, ExportPreview, ImportSummary, MapMatcher, NoElevationCorrection, NoMapMatching, Route, SatelliteSnapshot, Session, Waypoint, WaypointExporter, WaypointFormat, Track, TrackPoint};
+pub use map::{TileCache, CacheStats, MapSnapshot};
+pub use data_log::DataLogger;
+pub use odometer::Odometer;
 
 #[cfg(feature = "gui")]
 pub use display::gui::GpsGuiApp;
@@ -0,0 +1,55 @@
+// src/lock.rs v1
+//! Lock access that survives a panic elsewhere. A thread that panics while
+//! holding a lock poisons it, but the data behind it stays usable (at worst
+//! one update is half applied), so a long-running monitor carries on instead
+//! of every later access panicking too.
+
+use std::sync::{PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+pub trait RecoverPoison<T> {
+    /// Read guard, taken over from a poisoned lock if need be
+    fn read_recover(&self) -> RwLockReadGuard<'_, T>;
+
+    /// Write guard, taken over from a poisoned lock if need be
+    fn write_recover(&self) -> RwLockWriteGuard<'_, T>;
+}
+
+impl<T> RecoverPoison<T> for RwLock<T> {
+    fn read_recover(&self) -> RwLockReadGuard<'_, T> {
+        self.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn write_recover(&self) -> RwLockWriteGuard<'_, T> {
+        self.write().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{gps::GpsData, monitor::GpsMonitor};
+    use std::sync::{atomic::AtomicBool, Arc};
+
+    #[test]
+    fn test_monitor_survives_poisoned_lock() {
+        let data = Arc::new(RwLock::new(GpsData::new()));
+        let monitor = GpsMonitor::new_with_shared(Arc::clone(&data), Arc::new(AtomicBool::new(true)));
+
+        // A thread panics halfway through an update
+        let poisoner = Arc::clone(&data);
+        let result = std::thread::spawn(move || {
+            let mut data = poisoner.write().unwrap();
+            data.latitude = Some(1.0);
+            panic!("simulated panic while holding the GPS data lock");
+        }).join();
+        assert!(result.is_err());
+        assert!(data.is_poisoned());
+
+        // Later sentences are still parsed and read back
+        monitor.ingest_sentence("$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47");
+        let latest = monitor.get_data();
+        assert!(latest.has_fix());
+        assert!((latest.latitude.unwrap() - 48.1173).abs() < 1e-6);
+        assert_eq!(data.read_recover().satellites, Some(8));
+    }
+}
@@ -1,22 +1,96 @@
-// src/main.rs v3
+// src/main.rs v6
 //! GPS Monitor - Cross-platform GPS monitoring tool with egui
+//!
+//! `--headless` records without opening a window (the default when built
+//! without the `gui` feature); `--raw-log <file>` also keeps every sentence.
+//! `--terminal` shows the live dashboard in the terminal instead of a window.
 
-use gps_monitor::{config::GpsConfig, *};
+use gps_monitor::{config::GpsConfig, headless::{HeadlessLogger, HeadlessOptions}, *};
+
+/// How the monitor runs instead of in a window, chosen on the command line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Headless,
+    Terminal,
+}
+
+/// The mode given on the command line, if any, and the headless options
+fn parse_args() -> Result<(Option<Mode>, HeadlessOptions)> {
+    let mut mode = None;
+    let mut options = HeadlessOptions::default();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--headless" => mode = Some(Mode::Headless),
+            "--terminal" => mode = Some(Mode::Terminal),
+            "--raw-log" => {
+                let path = args.next()
+                    .ok_or_else(|| error::GpsError::Other("--raw-log needs a file path".to_string()))?;
+                options.raw_log = Some(path.into());
+            }
+            _ => return Err(error::GpsError::Other(format!("Unknown argument: {}", arg))),
+        }
+    }
+
+    let headless = mode == Some(Mode::Headless) || (mode.is_none() && !cfg!(feature = "gui"));
+    if options.raw_log.is_some() && !headless {
+        return Err(error::GpsError::Other("--raw-log only applies with --headless".to_string()));
+    }
+    Ok((mode, options))
+}
+
+/// Connect and record until Ctrl+C or SIGTERM, with no rendering at all
+fn run_headless(config: GpsConfig, options: HeadlessOptions) -> Result<()> {
+    println!("Recording headless, press Ctrl+C to stop");
+    let runtime = tokio::runtime::Runtime::new()?;
+    let mut logger = HeadlessLogger::new(config, options);
+    logger.set_status_observer(|message| println!("{}", message));
+    let saved = runtime.block_on(logger.run(headless::wait_for_shutdown_signal()))?;
+    if saved.is_empty() {
+        println!("No track points were recorded");
+    }
+    Ok(())
+}
+
+/// Show the live dashboard in the terminal until Ctrl+C or SIGTERM
+fn run_terminal(config: GpsConfig) -> Result<()> {
+    let runtime = tokio::runtime::Runtime::new()?;
+    let mut stdout = std::io::stdout();
+    runtime.block_on(display::terminal::run_terminal(&config, &mut stdout, headless::wait_for_shutdown_signal()))?;
+    println!("\nShutting down...");
+    Ok(())
+}
 
 #[cfg(not(feature = "gui"))]
-fn main() {
-    eprintln!("Error: This application requires the 'gui' feature.");
-    eprintln!("Build with: cargo build --features gui");
-    std::process::exit(1);
+fn main() -> Result<()> {
+    let (mode, options) = parse_args()?;
+    let config = GpsConfig::load().unwrap_or_default();
+
+    println!("Starting GPS Monitor...");
+    println!("Using {} source", config.source_type);
+    match mode.unwrap_or(Mode::Headless) {
+        Mode::Terminal => run_terminal(config),
+        Mode::Headless => run_headless(config, options),
+    }
 }
 
 #[cfg(feature = "gui")]
 fn main() -> Result<()> {
+    let (mode, headless_options) = parse_args()?;
+
     // Load configuration
     let config = GpsConfig::load().unwrap_or_default();
     
     println!("Starting GPS Monitor...");
     println!("Using {} source", config.source_type);
+    match mode {
+        Some(Mode::Headless) => return run_headless(config, headless_options),
+        Some(Mode::Terminal) => return run_terminal(config),
+        None => {}
+    }
+    if let Err(e) = config.validate() {
+        eprintln!("Warning: {}", e);
+    }
     
     // Create and run the egui application
     let options = eframe::NativeOptions {
@@ -34,7 +108,10 @@ fn main() -> Result<()> {
             // Set visual style
             cc.egui_ctx.set_visuals(eframe::egui::Visuals::dark());
             
-            Ok(Box::new(display::gui::GpsGuiApp::new_from_config(config)))
+            let app = display::gui::GpsGuiApp::new_from_config(config);
+            app.install_signal_handler(cc.egui_ctx.clone());
+
+            Ok(Box::new(app))
         }),
     )
     .map_err(|e| error::GpsError::Other(format!("GUI error: {}", e)))?;
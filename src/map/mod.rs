@@ -1,6 +1,8 @@
-// src/map/mod.rs v1
+// src/map/mod.rs v5
 //! Map tile caching and rendering
 
+mod snapshot;
 mod tile_cache;
 
-pub use tile_cache::{TileCache, CacheStats, lat_lon_to_tile, tile_to_lat_lon};
+pub use snapshot::MapSnapshot;
+pub use tile_cache::{BoundingBox, TileCache, CacheStats, RegionDownload, lat_lon_to_tile, lat_to_world_y, world_y_to_lat, is_header_char, user_agent, DEFAULT_CONCURRENT_DOWNLOADS, DEFAULT_MEMORY_TILES, DEFAULT_USER_AGENT, tile_to_lat_lon, split_at_antimeridian, MAX_REGION_TILES, MAX_ZOOM};
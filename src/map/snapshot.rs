@@ -0,0 +1,257 @@
+// src/map/snapshot.rs v2
+//! Offscreen rendering of a map view to a PNG image
+
+use super::{lat_to_world_y, split_at_antimeridian, TileCache};
+use crate::config::MarkerStyle;
+use crate::error::{Result, GpsError};
+use crate::waypoint::{Track, Waypoint};
+use image::{Pixel, Rgba, RgbaImage};
+use std::path::Path;
+
+const TILE_SIZE: f64 = 256.0;
+const BACKGROUND: Rgba<u8> = Rgba([224, 224, 224, 255]);
+const OUTLINE_COLOR: Rgba<u8> = Rgba([255, 255, 255, 255]);
+
+/// A map view to render: what is visible and which overlays to draw.
+///
+/// Only tiles already in the cache are used; missing tiles are left as a
+/// plain background. Waypoint labels are not drawn.
+pub struct MapSnapshot<'a> {
+    pub zoom: u8,
+    pub center_lat: f64,
+    pub center_lon: f64,
+    pub width: u32,
+    pub height: u32,
+    pub tracks: &'a [Track],
+    pub waypoints: &'a [Waypoint],
+    pub position: Option<(f64, f64)>,
+    pub style: &'a MarkerStyle,
+}
+
+impl MapSnapshot<'_> {
+    /// Composite the cached tiles and overlays into an image
+    pub fn render(&self, tile_cache: &TileCache) -> RgbaImage {
+        let mut image = RgbaImage::from_pixel(self.width, self.height, BACKGROUND);
+        self.draw_tiles(&mut image, tile_cache);
+
+        self.draw_tracks(&mut image);
+
+        for waypoint in self.waypoints {
+            let color = waypoint.color.unwrap_or(self.style.waypoint_color);
+            let center = self.to_image(waypoint.latitude, waypoint.longitude);
+            draw_marker(&mut image, center, self.style.waypoint_size as f64, color);
+        }
+
+        if let Some((lat, lon)) = self.position {
+            let center = self.to_image(lat, lon);
+            draw_marker(&mut image, center, self.style.position_size as f64, self.style.position_color);
+        }
+
+        image
+    }
+
+    /// Render and write the view as a PNG file
+    pub fn save_png(&self, tile_cache: &TileCache, path: &Path) -> Result<()> {
+        self.render(tile_cache)
+            .save_with_format(path, image::ImageFormat::Png)
+            .map_err(|e| GpsError::Other(format!("Failed to save map image: {}", e)))
+    }
+
+    fn world_size(&self) -> f64 {
+        2_f64.powi(self.zoom as i32) * TILE_SIZE
+    }
+
+    /// Web Mercator world pixel coordinates for a lat/lon at this zoom
+    fn to_world(&self, lat: f64, lon: f64) -> (f64, f64) {
+        let world = self.world_size();
+        let x = (lon + 180.0) / 360.0 * world;
+        let y = lat_to_world_y(lat) * world;
+        (x, y)
+    }
+
+    /// World pixel of the image's top-left corner
+    fn origin(&self) -> (f64, f64) {
+        let (cx, cy) = self.to_world(self.center_lat, self.center_lon);
+        (cx - self.width as f64 / 2.0, cy - self.height as f64 / 2.0)
+    }
+
+    fn to_image(&self, lat: f64, lon: f64) -> (f64, f64) {
+        let (x, y) = self.to_world(lat, lon);
+        let (ox, oy) = self.origin();
+        (x - ox, y - oy)
+    }
+
+    /// Draw the tracks onto a transparent layer first, so overlapping passes
+    /// blend once with the map at the configured opacity
+    fn draw_tracks(&self, image: &mut RgbaImage) {
+        let [r, g, b] = self.style.track_color;
+        let mut layer = RgbaImage::new(image.width(), image.height());
+        for track in self.tracks {
+            for segment in &track.segments {
+                let runs = split_at_antimeridian(segment.points.iter().map(|p| (p.latitude, p.longitude)));
+                for run in runs {
+                    let points: Vec<(f64, f64)> = run.iter().map(|&(lat, lon)| self.to_image(lat, lon)).collect();
+                    for pair in points.windows(2) {
+                        draw_line(&mut layer, pair[0], pair[1], self.style.track_width as f64, Rgba([r, g, b, 255]));
+                    }
+                }
+            }
+        }
+
+        let alpha = (self.style.track_opacity.clamp(0.0, 1.0) * 255.0).round() as u8;
+        for (pixel, track_pixel) in image.pixels_mut().zip(layer.pixels()) {
+            if track_pixel[3] > 0 {
+                pixel.blend(&Rgba([r, g, b, alpha]));
+            }
+        }
+    }
+
+    fn draw_tiles(&self, image: &mut RgbaImage, tile_cache: &TileCache) {
+        let (ox, oy) = self.origin();
+        let tiles_per_side = 2_i64.pow(self.zoom as u32);
+
+        let first_x = (ox / TILE_SIZE).floor() as i64;
+        let first_y = (oy / TILE_SIZE).floor() as i64;
+        let last_x = ((ox + self.width as f64) / TILE_SIZE).floor() as i64;
+        let last_y = ((oy + self.height as f64) / TILE_SIZE).floor() as i64;
+
+        for ty in first_y..=last_y {
+            if ty < 0 || ty >= tiles_per_side {
+                continue;
+            }
+            for tx in first_x..=last_x {
+                // Wrap around the antimeridian
+                let wrapped_x = tx.rem_euclid(tiles_per_side) as u32;
+                let Ok(bytes) = tile_cache.get_tile(self.zoom, wrapped_x, ty as u32) else {
+                    continue;
+                };
+                let Ok(tile) = image::load_from_memory(&bytes) else {
+                    continue;
+                };
+
+                let x = (tx as f64 * TILE_SIZE - ox).round() as i64;
+                let y = (ty as f64 * TILE_SIZE - oy).round() as i64;
+                image::imageops::overlay(image, &tile.to_rgba8(), x, y);
+            }
+        }
+    }
+}
+
+/// Filled marker with a white outline, like the on-screen markers
+fn draw_marker(image: &mut RgbaImage, center: (f64, f64), radius: f64, [r, g, b]: [u8; 3]) {
+    fill_circle(image, center, radius + 2.0, OUTLINE_COLOR);
+    fill_circle(image, center, radius, Rgba([r, g, b, 255]));
+}
+
+fn fill_circle(image: &mut RgbaImage, (cx, cy): (f64, f64), radius: f64, color: Rgba<u8>) {
+    let min_x = (cx - radius).floor().max(0.0) as i64;
+    let max_x = (cx + radius).ceil().min(image.width() as f64 - 1.0) as i64;
+    let min_y = (cy - radius).floor().max(0.0) as i64;
+    let max_y = (cy + radius).ceil().min(image.height() as f64 - 1.0) as i64;
+
+    for y in min_y..=max_y {
+        for x in min_x..=max_x {
+            let dx = x as f64 + 0.5 - cx;
+            let dy = y as f64 + 0.5 - cy;
+            if dx * dx + dy * dy <= radius * radius {
+                image.put_pixel(x as u32, y as u32, color);
+            }
+        }
+    }
+}
+
+/// Thick line drawn as overlapping discs along the segment
+fn draw_line(image: &mut RgbaImage, from: (f64, f64), to: (f64, f64), width: f64, color: Rgba<u8>) {
+    let length = ((to.0 - from.0).powi(2) + (to.1 - from.1).powi(2)).sqrt();
+
+    // Skip segments entirely outside the image
+    let margin = width + length;
+    let outside = |(x, y): (f64, f64)| {
+        x < -margin || y < -margin || x > image.width() as f64 + margin || y > image.height() as f64 + margin
+    };
+    if outside(from) && outside(to) {
+        return;
+    }
+
+    let steps = (length * 2.0).ceil().max(1.0) as usize;
+    for i in 0..=steps {
+        let t = i as f64 / steps as f64;
+        let point = (from.0 + (to.0 - from.0) * t, from.1 + (to.1 - from.1) * t);
+        fill_circle(image, point, width / 2.0, color);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    #[tokio::test]
+    async fn test_snapshot_draws_overlays_without_tiles() {
+        let cache_dir = std::env::temp_dir().join(format!("gps-monitor-snapshot-{}", std::process::id()));
+        let tile_cache = TileCache::new(cache_dir.clone(), tokio::runtime::Handle::current()).unwrap();
+        let style = MarkerStyle::default();
+
+        let waypoints = vec![Waypoint {
+            name: "Center".to_string(),
+            latitude: 42.0,
+            longitude: -71.0,
+            elevation: None,
+            timestamp: Utc::now(),
+            description: None,
+            color: Some([0, 255, 0]),
+        }];
+
+        let snapshot = MapSnapshot {
+            zoom: 13,
+            center_lat: 42.0,
+            center_lon: -71.0,
+            width: 200,
+            height: 100,
+            tracks: &[],
+            waypoints: &waypoints,
+            position: None,
+            style: &style,
+        };
+        let image = snapshot.render(&tile_cache);
+        std::fs::remove_dir_all(&cache_dir).ok();
+
+        assert_eq!(image.dimensions(), (200, 100));
+        assert_eq!(*image.get_pixel(100, 50), Rgba([0, 255, 0, 255]));
+        assert_eq!(*image.get_pixel(0, 0), BACKGROUND);
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_track_width_and_opacity() {
+        let cache_dir = std::env::temp_dir().join(format!("gps-monitor-snapshot-track-{}", std::process::id()));
+        let tile_cache = TileCache::new(cache_dir.clone(), tokio::runtime::Handle::current()).unwrap();
+        let style = MarkerStyle { track_width: 6.0, track_opacity: 0.5, ..MarkerStyle::default() };
+
+        // Out and back along the same line: the overlap must not darken
+        let mut track = Track::new("Loop".to_string());
+        for lon in [-71.01, -70.99, -71.01] {
+            track.add_point(crate::waypoint::TrackPoint::new(42.0, lon, None, Utc::now()));
+        }
+        let tracks = vec![track];
+
+        let snapshot = MapSnapshot {
+            zoom: 13,
+            center_lat: 42.0,
+            center_lon: -71.0,
+            width: 200,
+            height: 100,
+            tracks: &tracks,
+            waypoints: &[],
+            position: None,
+            style: &style,
+        };
+        let image = snapshot.render(&tile_cache);
+        std::fs::remove_dir_all(&cache_dir).ok();
+
+        let mut expected = BACKGROUND;
+        expected.blend(&Rgba([255, 0, 0, 128]));
+        assert_eq!(*image.get_pixel(100, 50), expected);
+        assert_eq!(*image.get_pixel(100, 52), expected); // within the 6 px width
+        assert_eq!(*image.get_pixel(100, 56), BACKGROUND);
+    }
+}
@@ -1,17 +1,49 @@
-// src/map/tile_cache.rs v2
+// src/map/tile_cache.rs v7
 //! OpenStreetMap tile downloading and caching with resource management
 
 use crate::error::{Result, GpsError};
-use std::path::PathBuf;
-use std::sync::{Arc, Mutex};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, atomic::{AtomicBool, AtomicU64, Ordering}};
 use std::collections::{HashMap, HashSet};
+use tokio::{runtime::Handle, sync::Semaphore, task::{JoinHandle, JoinSet}};
+
+/// Highest zoom level served by the OSM tile servers
+pub const MAX_ZOOM: u8 = 18;
+
+/// Largest batch `download_region` will queue; the OSM tile usage policy
+/// discourages bulk downloading, so whole countries at street level are out
+pub const MAX_REGION_TILES: u64 = 50_000;
+
+/// Tiles kept in memory unless configured otherwise
+pub const DEFAULT_MEMORY_TILES: usize = 100;
+
+/// Tiles downloaded at once unless configured otherwise
+pub const DEFAULT_CONCURRENT_DOWNLOADS: usize = 4;
+
+/// Tiles waiting for a download slot before further requests are ignored;
+/// a full 1080p screen plus the map's margin is a little under this
+pub const MAX_QUEUED_DOWNLOADS: usize = 256;
+
+/// Web Mercator stops short of the poles
+const MAX_LATITUDE: f64 = 85.051_128_78;
+
+/// Web Mercator y of `lat` as a fraction of the world's height, from 0 at
+/// the northern edge to 1 at the southern
+pub fn lat_to_world_y(lat: f64) -> f64 {
+    let lat_rad = lat.to_radians();
+    (1.0 - (lat_rad.tan() + 1.0 / lat_rad.cos()).ln() / std::f64::consts::PI) / 2.0
+}
+
+/// Latitude at Web Mercator `y`, a fraction of the world's height
+pub fn world_y_to_lat(y: f64) -> f64 {
+    ((1.0 - 2.0 * y) * std::f64::consts::PI).sinh().atan().to_degrees()
+}
 
 /// Calculate tile coordinates from lat/lon and zoom level
 pub fn lat_lon_to_tile(lat: f64, lon: f64, zoom: u8) -> (u32, u32) {
     let n = 2_f64.powi(zoom as i32);
     let x = ((lon + 180.0) / 360.0 * n).floor() as u32;
-    let lat_rad = lat.to_radians();
-    let y = ((1.0 - (lat_rad.tan() + 1.0 / lat_rad.cos()).ln() / std::f64::consts::PI) / 2.0 * n).floor() as u32;
+    let y = (lat_to_world_y(lat) * n).floor() as u32;
     (x, y)
 }
 
@@ -19,34 +51,228 @@ pub fn lat_lon_to_tile(lat: f64, lon: f64, zoom: u8) -> (u32, u32) {
 pub fn tile_to_lat_lon(x: u32, y: u32, zoom: u8) -> (f64, f64) {
     let n = 2_f64.powi(zoom as i32);
     let lon = x as f64 / n * 360.0 - 180.0;
-    let lat_rad = ((1.0 - 2.0 * y as f64 / n) * std::f64::consts::PI).sinh().atan();
-    let lat = lat_rad.to_degrees();
+    let lat = world_y_to_lat(y as f64 / n);
     (lat, lon)
 }
 
+/// Split a line of (lat, lon) points where it crosses the antimeridian, so it
+/// is drawn as pieces ending at ±180° rather than a line across the whole map.
+/// A step of more than 180° of longitude is taken as the short way round.
+pub fn split_at_antimeridian(points: impl IntoIterator<Item = (f64, f64)>) -> Vec<Vec<(f64, f64)>> {
+    let mut runs: Vec<Vec<(f64, f64)>> = vec![Vec::new()];
+    for (lat, lon) in points {
+        let current = runs.last_mut().unwrap();
+        if let Some(&(prev_lat, prev_lon)) = current.last() {
+            if (lon - prev_lon).abs() > 180.0 {
+                // Latitude where the short way round meets the edge the previous point is nearest
+                let edge = 180.0_f64.copysign(prev_lon);
+                let unwrapped = lon + 360.0_f64.copysign(prev_lon);
+                let t = (edge - prev_lon) / (unwrapped - prev_lon);
+                let crossing_lat = prev_lat + (lat - prev_lat) * t;
+
+                current.push((crossing_lat, edge));
+                runs.push(vec![(crossing_lat, -edge)]);
+            }
+        }
+        runs.last_mut().unwrap().push((lat, lon));
+    }
+    runs.retain(|run| run.len() > 1);
+    runs
+}
+
+/// Tile key: (zoom, x, y)
+type TileKey = (u8, u32, u32);
+
+/// Geographic area in degrees. Boxes crossing the antimeridian are not supported.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingBox {
+    pub south: f64,
+    pub west: f64,
+    pub north: f64,
+    pub east: f64,
+}
+
+impl BoundingBox {
+    pub fn new(south: f64, west: f64, north: f64, east: f64) -> Result<Self> {
+        if !(-90.0..=90.0).contains(&south) || !(-90.0..=90.0).contains(&north) || south >= north {
+            return Err(GpsError::Other("South must be below north, within ±90°".to_string()));
+        }
+        if !(-180.0..=180.0).contains(&west) || !(-180.0..=180.0).contains(&east) || west >= east {
+            return Err(GpsError::Other("West must be left of east, within ±180°".to_string()));
+        }
+        Ok(Self { south, west, north, east })
+    }
+
+    /// Inclusive tile x and y ranges covering the box at `zoom`
+    fn tile_range(&self, zoom: u8) -> ((u32, u32), (u32, u32)) {
+        let last = (1u32 << zoom) - 1;
+        let (x_min, y_min) = lat_lon_to_tile(self.north.min(MAX_LATITUDE), self.west, zoom);
+        let (x_max, y_max) = lat_lon_to_tile(self.south.max(-MAX_LATITUDE), self.east, zoom);
+        ((x_min.min(last), x_max.min(last)), (y_min.min(last), y_max.min(last)))
+    }
+
+    /// Number of tiles covering the box across the zoom range
+    pub fn tile_count(&self, zoom_min: u8, zoom_max: u8) -> u64 {
+        (zoom_min..=zoom_max)
+            .map(|zoom| {
+                let ((x_min, x_max), (y_min, y_max)) = self.tile_range(zoom);
+                u64::from(x_max - x_min + 1) * u64::from(y_max - y_min + 1)
+            })
+            .sum()
+    }
+
+    /// Every tile covering the box, lowest zoom first
+    pub fn tiles(&self, zoom_min: u8, zoom_max: u8) -> impl Iterator<Item = TileKey> + '_ {
+        (zoom_min..=zoom_max).flat_map(move |zoom| {
+            let ((x_min, x_max), (y_min, y_max)) = self.tile_range(zoom);
+            (x_min..=x_max).flat_map(move |x| (y_min..=y_max).map(move |y| (zoom, x, y)))
+        })
+    }
+}
+
+/// Progress of a batch started by `TileCache::download_region`, shared with the UI
+#[derive(Debug)]
+pub struct RegionDownload {
+    total: u64,
+    completed: AtomicU64,  // Downloaded, or already on disk
+    failed: AtomicU64,
+    cancelled: AtomicBool,
+    finished: AtomicBool,
+}
+
+impl RegionDownload {
+    fn new(total: u64) -> Self {
+        Self {
+            total,
+            completed: AtomicU64::new(0),
+            failed: AtomicU64::new(0),
+            cancelled: AtomicBool::new(false),
+            finished: AtomicBool::new(false),
+        }
+    }
+
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    pub fn completed(&self) -> u64 {
+        self.completed.load(Ordering::Relaxed)
+    }
+
+    pub fn failed(&self) -> u64 {
+        self.failed.load(Ordering::Relaxed)
+    }
+
+    /// Share of the tiles dealt with so far, successfully or not
+    pub fn fraction(&self) -> f32 {
+        if self.total == 0 {
+            return 1.0;
+        }
+        (self.completed() + self.failed()) as f32 / self.total as f32
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished.load(Ordering::Relaxed)
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Stop queueing tiles; downloads already running still complete
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+}
+
+/// User-Agent sent with tile requests unless configured otherwise
+pub const DEFAULT_USER_AGENT: &str = "GPSMonitor/1.0 (Rust GPS tracking application)";
+
+/// Whether `c` may appear in an HTTP header value: visible ASCII, space or tab
+pub fn is_header_char(c: char) -> bool {
+    c == '\t' || (' '..='~').contains(&c)
+}
+
+/// User-Agent for tile requests: `name` (or the default) followed by the
+/// contact details OSM's tile usage policy asks for, when given. Characters
+/// a header can't carry are dropped, so the result is always a valid value.
+pub fn user_agent(name: Option<&str>, contact: Option<&str>) -> String {
+    let clean = |text: Option<&str>| {
+        text.map(|text| text.chars().filter(|&c| is_header_char(c)).collect::<String>().trim().to_string())
+            .filter(|text| !text.is_empty())
+    };
+    let name = clean(name).unwrap_or_else(|| DEFAULT_USER_AGENT.to_string());
+    match clean(contact) {
+        Some(contact) => format!("{} (contact: {})", name, contact),
+        None => name,
+    }
+}
+
+/// HTTP client for tile downloads
+fn build_client(user_agent: &str) -> Result<reqwest::Client> {
+    reqwest::Client::builder()
+        .user_agent(user_agent)
+        .timeout(std::time::Duration::from_secs(10))
+        .build()
+        .map_err(|e| GpsError::Other(format!("HTTP client error: {}", e)))
+}
+
 #[derive(Clone)]
 pub struct TileCache {
     cache_dir: PathBuf,
-    memory_cache: Arc<Mutex<HashMap<(u8, u32, u32), Arc<Vec<u8>>>>>,
-    downloading: Arc<Mutex<HashSet<(u8, u32, u32)>>>,
+    memory_cache: Arc<Mutex<HashMap<TileKey, Arc<Vec<u8>>>>>,
+    downloading: Arc<Mutex<HashSet<TileKey>>>,  // Queued or in-flight downloads
+    queued: Arc<Mutex<HashSet<TileKey>>>,       // Downloads still waiting for a slot
     max_memory_tiles: usize,
-    max_concurrent_downloads: usize,
+    download_permits: Arc<Semaphore>,          // Bounds concurrent downloads
+    corrupt_tiles: Arc<AtomicU64>,             // Cached tiles discarded because they didn't decode
+    client: reqwest::Client,
+    runtime: Handle,
 }
 
 impl TileCache {
-    pub fn new(cache_dir: PathBuf) -> Result<Self> {
+    /// Check that tiles can be written to `cache_dir`, creating it if needed
+    pub fn check_writable(cache_dir: &Path) -> Result<()> {
+        std::fs::create_dir_all(cache_dir)?;
+        let probe = cache_dir.join(".write_test");
+        std::fs::write(&probe, b"")?;
+        std::fs::remove_file(&probe)?;
+        Ok(())
+    }
+
+    /// Create a tile cache that downloads on the given Tokio runtime
+    pub fn new(cache_dir: PathBuf, runtime: Handle) -> Result<Self> {
+        Self::with_user_agent(cache_dir, runtime, DEFAULT_USER_AGENT)
+    }
+
+    /// Like `new`, identifying downloads with `user_agent` (see `user_agent()`)
+    pub fn with_user_agent(cache_dir: PathBuf, runtime: Handle, user_agent: &str) -> Result<Self> {
         std::fs::create_dir_all(&cache_dir)
             .map_err(|e| GpsError::Other(format!("Failed to create cache directory: {}", e)))?;
 
+        let client = build_client(user_agent)?;
+
         Ok(Self {
             cache_dir,
             memory_cache: Arc::new(Mutex::new(HashMap::new())),
             downloading: Arc::new(Mutex::new(HashSet::new())),
-            max_memory_tiles: 100,
-            max_concurrent_downloads: 4,
+            queued: Arc::new(Mutex::new(HashSet::new())),
+            max_memory_tiles: DEFAULT_MEMORY_TILES,
+            download_permits: Arc::new(Semaphore::new(DEFAULT_CONCURRENT_DOWNLOADS)),
+            corrupt_tiles: Arc::new(AtomicU64::new(0)),
+            client,
+            runtime,
         })
     }
 
+    /// Change how many tiles are kept in memory and downloaded at once, each
+    /// at least one. Call before the cache is cloned, as clones made earlier
+    /// keep the old limits.
+    pub fn set_limits(&mut self, max_memory_tiles: usize, max_concurrent_downloads: usize) {
+        self.max_memory_tiles = max_memory_tiles.max(1);
+        self.download_permits = Arc::new(Semaphore::new(max_concurrent_downloads.max(1)));
+    }
+
     /// Get tile from cache or download
     pub fn get_tile(&self, zoom: u8, x: u32, y: u32) -> Result<Arc<Vec<u8>>> {
         let key = (zoom, x, y);
@@ -60,84 +286,171 @@ impl TileCache {
         }
 
         // Check disk cache
+        self.load_from_disk(key)?
+            .ok_or_else(|| GpsError::Other("Tile not in cache".to_string()))
+    }
+
+    /// Read a tile from the disk cache into memory. `None` when it isn't on disk.
+    fn load_from_disk(&self, key: TileKey) -> Result<Option<Arc<Vec<u8>>>> {
+        let (zoom, x, y) = key;
         let path = self.get_tile_path(zoom, x, y);
-        if path.exists() {
-            let bytes = std::fs::read(&path)
-                .map_err(|e| GpsError::Other(format!("Failed to read cached tile: {}", e)))?;
-            let tile = Arc::new(bytes);
-            self.add_to_memory_cache(key, Arc::clone(&tile));
-            return Ok(tile);
+        if !path.exists() {
+            return Ok(None);
         }
+        let bytes = std::fs::read(&path)
+            .map_err(|e| GpsError::Other(format!("Failed to read cached tile: {}", e)))?;
+        let tile = Arc::new(bytes);
+        self.add_to_memory_cache(key, Arc::clone(&tile));
+        Ok(Some(tile))
+    }
 
-        // Not in cache, need to download
-        Err(GpsError::Other("Tile not in cache".to_string()))
+    /// Decoded tile from the cache. A cached tile that doesn't decode (e.g. a
+    /// download cut short) is removed from memory and disk and an error is
+    /// returned, so the caller downloads it again instead of showing it blank.
+    pub fn get_tile_image(&self, zoom: u8, x: u32, y: u32) -> Result<image::DynamicImage> {
+        let bytes = self.get_tile(zoom, x, y)?;
+        image::load_from_memory(&bytes).map_err(|e| {
+            self.discard_tile((zoom, x, y));
+            self.corrupt_tiles.fetch_add(1, Ordering::Relaxed);
+            eprintln!("Discarded corrupt map tile {}/{}/{}: {}", zoom, x, y, e);
+            GpsError::Other(format!("Corrupt tile {}/{}/{}: {}", zoom, x, y, e))
+        })
     }
 
-    /// Download tile in background (non-blocking) with concurrency limit
+    /// Drop a tile from the memory and disk caches
+    fn discard_tile(&self, key: TileKey) {
+        let (zoom, x, y) = key;
+        self.memory_cache.lock().unwrap().remove(&key);
+        let _ = std::fs::remove_file(self.get_tile_path(zoom, x, y));
+    }
+
+    /// Download tile in background (non-blocking) with concurrency limit.
+    /// Once `MAX_QUEUED_DOWNLOADS` are waiting the request is ignored; the map
+    /// asks again on a later frame if the tile is still on screen.
     pub fn download_tile_async(&self, zoom: u8, x: u32, y: u32) {
         let key = (zoom, x, y);
 
-        // Check if already downloading
-        {
-            let mut downloading = self.downloading.lock().unwrap();
-            
-            // Limit concurrent downloads
-            if downloading.len() >= self.max_concurrent_downloads {
+        let mut queued = self.queued.lock().unwrap();
+        if queued.len() >= MAX_QUEUED_DOWNLOADS {
+            return;
+        }
+        // Skip if already queued or downloading
+        if !self.downloading.lock().unwrap().insert(key) {
+            return;
+        }
+        queued.insert(key);
+        drop(queued);
+
+        let cache = self.clone();
+        self.runtime.spawn(async move {
+            // Wait for a free download slot
+            let permit = cache.download_permits.acquire().await.ok();
+
+            // Dropped while waiting, see `drop_queued_downloads`
+            if !cache.queued.lock().unwrap().remove(&key) {
+                cache.downloading.lock().unwrap().remove(&key);
                 return;
             }
-            
-            if downloading.contains(&key) {
-                return;
+
+            // Drop the error right away; GpsError is not Send
+            let downloaded = cache.download_tile(zoom, x, y).await.ok();
+            if let Some(bytes) = downloaded {
+                cache.store_tile(zoom, x, y, &bytes).await;
+                cache.add_to_memory_cache(key, Arc::new(bytes));
             }
-            
-            downloading.insert(key);
-        }
 
-        let cache_dir = self.cache_dir.clone();
-        let memory_cache = Arc::clone(&self.memory_cache);
-        let downloading = Arc::clone(&self.downloading);
+            // Respect OSM tile usage policy - add small delay before releasing the slot
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+            drop(permit);
+
+            // Remove from downloading set
+            cache.downloading.lock().unwrap().remove(&key);
+        });
+    }
+
+    /// Forget queued downloads that `keep` rejects, e.g. tiles of a zoom level
+    /// the map has left. Downloads already in flight finish.
+    pub fn drop_queued_downloads(&self, keep: impl Fn(TileKey) -> bool) {
+        self.queued.lock().unwrap().retain(|&key| keep(key));
+    }
+
+    /// Number of downloads waiting for a slot
+    pub fn queued_downloads(&self) -> usize {
+        self.queued.lock().unwrap().len()
+    }
+
+    /// Download every tile in `bbox` from `zoom_min` to `zoom_max` to disk,
+    /// skipping tiles already cached. Shares the download slots and delay with
+    /// on-screen tiles, so it runs at the same polite rate.
+    pub fn download_region(&self, bbox: BoundingBox, zoom_min: u8, zoom_max: u8) -> Result<Arc<RegionDownload>> {
+        if zoom_min > zoom_max || zoom_max > MAX_ZOOM {
+            return Err(GpsError::Other(format!("Zoom range must be within 0-{}", MAX_ZOOM)));
+        }
+        let total = bbox.tile_count(zoom_min, zoom_max);
+        if total > MAX_REGION_TILES {
+            return Err(GpsError::Other(format!(
+                "{} tiles is more than the limit of {}; choose a smaller area or zoom range",
+                total, MAX_REGION_TILES
+            )));
+        }
 
-        std::thread::spawn(move || {
-            if let Ok(bytes) = Self::download_tile(zoom, x, y) {
-                // Save to disk
-                let path = Self::tile_path(&cache_dir, zoom, x, y);
-                if let Some(parent) = path.parent() {
-                    let _ = std::fs::create_dir_all(parent);
+        let progress = Arc::new(RegionDownload::new(total));
+        let cache = self.clone();
+        let shared = Arc::clone(&progress);
+        self.runtime.spawn(async move {
+            let mut downloads = JoinSet::new();
+            for (zoom, x, y) in bbox.tiles(zoom_min, zoom_max) {
+                if shared.is_cancelled() {
+                    break;
                 }
-                let _ = std::fs::write(&path, &bytes);
-
-                // Add to memory cache
-                let tile = Arc::new(bytes);
-                let mut cache = memory_cache.lock().unwrap();
-                
-                // Limit memory cache size
-                if cache.len() >= 100 {
-                    // Remove oldest entries
-                    if let Some(first_key) = cache.keys().next().cloned() {
-                        cache.remove(&first_key);
-                    }
+                if cache.get_tile_path(zoom, x, y).exists() {
+                    shared.completed.fetch_add(1, Ordering::Relaxed);
+                    continue;
                 }
-                
-                cache.insert(key, tile);
+
+                let Ok(permit) = Arc::clone(&cache.download_permits).acquire_owned().await else {
+                    break;
+                };
+                let cache = cache.clone();
+                let shared = Arc::clone(&shared);
+                downloads.spawn(async move {
+                    match cache.download_tile(zoom, x, y).await.ok() {
+                        Some(bytes) => {
+                            cache.store_tile(zoom, x, y, &bytes).await;
+                            shared.completed.fetch_add(1, Ordering::Relaxed);
+                        }
+                        None => {
+                            shared.failed.fetch_add(1, Ordering::Relaxed);
+                        }
+                    }
+                    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+                    drop(permit);
+                });
+                while downloads.try_join_next().is_some() {}
             }
-            
-            // Remove from downloading set
-            downloading.lock().unwrap().remove(&key);
+            while downloads.join_next().await.is_some() {}
+            shared.finished.store(true, Ordering::Relaxed);
         });
+
+        Ok(progress)
+    }
+
+    /// Save a downloaded tile to the disk cache
+    async fn store_tile(&self, zoom: u8, x: u32, y: u32, bytes: &[u8]) {
+        let path = self.get_tile_path(zoom, x, y);
+        if let Some(parent) = path.parent() {
+            let _ = tokio::fs::create_dir_all(parent).await;
+        }
+        let _ = tokio::fs::write(&path, bytes).await;
     }
 
     /// Download tile from OpenStreetMap
-    fn download_tile(zoom: u8, x: u32, y: u32) -> Result<Vec<u8>> {
+    async fn download_tile(&self, zoom: u8, x: u32, y: u32) -> Result<Vec<u8>> {
         let url = format!("https://tile.openstreetmap.org/{}/{}/{}.png", zoom, x, y);
-        
-        let client = reqwest::blocking::Client::builder()
-            .user_agent("GPSMonitor/1.0 (Rust GPS tracking application)")
-            .timeout(std::time::Duration::from_secs(10))
-            .build()
-            .map_err(|e| GpsError::Other(format!("HTTP client error: {}", e)))?;
 
-        let response = client.get(&url)
+        let response = self.client.get(&url)
             .send()
+            .await
             .map_err(|e| GpsError::Other(format!("Download failed: {}", e)))?;
 
         if !response.status().is_success() {
@@ -145,12 +458,10 @@ impl TileCache {
         }
 
         let bytes = response.bytes()
+            .await
             .map_err(|e| GpsError::Other(format!("Failed to read response: {}", e)))?
             .to_vec();
 
-        // Respect OSM tile usage policy - add small delay
-        std::thread::sleep(std::time::Duration::from_millis(100));
-
         Ok(bytes)
     }
 
@@ -158,11 +469,11 @@ impl TileCache {
         Self::tile_path(&self.cache_dir, zoom, x, y)
     }
 
-    fn tile_path(cache_dir: &PathBuf, zoom: u8, x: u32, y: u32) -> PathBuf {
+    fn tile_path(cache_dir: &Path, zoom: u8, x: u32, y: u32) -> PathBuf {
         cache_dir.join(format!("{}/{}/{}.png", zoom, x, y))
     }
 
-    fn add_to_memory_cache(&self, key: (u8, u32, u32), tile: Arc<Vec<u8>>) {
+    fn add_to_memory_cache(&self, key: TileKey, tile: Arc<Vec<u8>>) {
         let mut cache = self.memory_cache.lock().unwrap();
         
         // Simple LRU-like behavior: remove oldest if at capacity
@@ -199,6 +510,21 @@ impl TileCache {
         }
     }
 
+    /// Load tiles in `bbox` at `zoom` that are already on disk into memory
+    /// on the runtime's blocking pool, without downloading anything. Stops at
+    /// the memory cache size so warming doesn't evict its own tiles. The
+    /// task returns how many were loaded.
+    pub fn warm_memory(&self, zoom: u8, bbox: &BoundingBox) -> JoinHandle<usize> {
+        let cache = self.clone();
+        let tiles: Vec<TileKey> = bbox.tiles(zoom, zoom).take(self.max_memory_tiles).collect();
+        self.runtime.spawn_blocking(move || {
+            tiles.into_iter()
+                .filter(|key| !cache.memory_cache.lock().unwrap().contains_key(key))
+                .filter(|&key| matches!(cache.load_from_disk(key), Ok(Some(_))))
+                .count()
+        })
+    }
+
     /// Clear memory cache
     pub fn clear_memory_cache(&self) {
         self.memory_cache.lock().unwrap().clear();
@@ -233,6 +559,7 @@ impl TileCache {
             memory_tiles: memory_count,
             disk_tiles: disk_count,
             disk_size_mb: disk_size as f64 / 1_048_576.0,
+            corrupt_tiles: self.corrupt_tiles.load(Ordering::Relaxed),
         }
     }
 
@@ -251,6 +578,7 @@ pub struct CacheStats {
     pub memory_tiles: usize,
     pub disk_tiles: usize,
     pub disk_size_mb: f64,
+    pub corrupt_tiles: u64,  // Discarded for re-download since the cache was created
 }
 
 #[cfg(test)]
@@ -261,7 +589,7 @@ mod tests {
     fn test_tile_coordinates() {
         // Test known coordinates
         let (x, y) = lat_lon_to_tile(42.438878, -71.119277, 12);
-        assert!(x > 0 && y > 0);
+        assert_eq!((x, y), (1238, 1513));
         
         // Test conversion back
         let (lat, lon) = tile_to_lat_lon(x, y, 12);
@@ -269,10 +597,214 @@ mod tests {
         assert!((lon - (-71.119277)).abs() < 0.1);
     }
 
+    #[test]
+    fn test_world_y() {
+        // The equator halves the world and Web Mercator's limit is its top edge
+        assert!((lat_to_world_y(0.0) - 0.5).abs() < 1e-12);
+        assert!(lat_to_world_y(MAX_LATITUDE).abs() < 1e-9);
+        assert!((lat_to_world_y(-MAX_LATITUDE) - 1.0).abs() < 1e-9);
+
+        // Tile 1513 at zoom 12 holds 42.4389°N, 0.762 of the way down it
+        assert!((lat_to_world_y(42.438878) * 4096.0 - 1513.7623).abs() < 1e-3);
+        assert!((world_y_to_lat(1513.7623 / 4096.0) - 42.438878).abs() < 1e-5);
+        assert_eq!(world_y_to_lat(0.5), 0.0);
+    }
+
     #[test]
     fn test_tile_path() {
         let cache_dir = PathBuf::from("/tmp/tiles");
         let path = TileCache::tile_path(&cache_dir, 12, 1234, 5678);
         assert_eq!(path, PathBuf::from("/tmp/tiles/12/1234/5678.png"));
     }
+
+    #[test]
+    fn test_check_writable() {
+        let dir = std::env::temp_dir().join(format!("gps-monitor-tile-check-{}", std::process::id()));
+        let cache_dir = dir.join("tiles");
+        assert!(TileCache::check_writable(&cache_dir).is_ok());
+        assert!(cache_dir.is_dir());
+        assert_eq!(std::fs::read_dir(&cache_dir).unwrap().count(), 0);
+
+        // A file where the directory should be
+        let blocked = dir.join("blocked");
+        std::fs::write(&blocked, b"").unwrap();
+        assert!(TileCache::check_writable(&blocked.join("tiles")).is_err());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_region_tile_count() {
+        assert!(BoundingBox::new(42.5, -71.0, 42.4, -70.9).is_err());
+        assert!(BoundingBox::new(42.4, -70.9, 42.5, -71.0).is_err());
+
+        // The whole world is one tile at zoom 0 and four at zoom 1
+        let world = BoundingBox::new(-90.0, -180.0, 90.0, 180.0).unwrap();
+        assert_eq!(world.tile_count(0, 0), 1);
+        assert_eq!(world.tile_count(0, 2), 1 + 4 + 16);
+
+        let town = BoundingBox::new(42.40, -71.15, 42.46, -71.08).unwrap();
+        let count = town.tile_count(10, 14);
+        assert_eq!(town.tiles(10, 14).count() as u64, count);
+        assert_eq!(town.tile_count(10, 10), 1);
+        assert!(town.tiles(14, 14).all(|(zoom, x, y)| {
+            let (lat, lon) = tile_to_lat_lon(x, y, zoom);
+            (42.40..=42.50).contains(&lat) && (-71.2..=-71.08).contains(&lon)
+        }));
+    }
+
+    #[tokio::test]
+    async fn test_warm_memory_from_disk_only() {
+        let cache_dir = std::env::temp_dir().join(format!("gps-monitor-warm-{}", std::process::id()));
+        let cache = TileCache::new(cache_dir.clone(), Handle::current()).unwrap();
+
+        let bbox = BoundingBox::new(42.40, -71.15, 42.46, -71.08).unwrap();
+        let tiles: Vec<_> = bbox.tiles(14, 14).collect();
+        assert!(tiles.len() > 2);
+
+        // Two of the area's tiles are on disk, plus one outside it
+        let (outside_x, outside_y) = lat_lon_to_tile(48.0, 11.0, 14);
+        for &(zoom, x, y) in tiles.iter().take(2).chain([&(14, outside_x, outside_y)]) {
+            let path = TileCache::tile_path(&cache_dir, zoom, x, y);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, [zoom, x as u8, y as u8]).unwrap();
+        }
+
+        assert_eq!(cache.warm_memory(14, &bbox).await.unwrap(), 2);
+        assert_eq!(cache.get_stats().memory_tiles, 2);
+        assert!(cache.memory_cache.lock().unwrap().contains_key(&tiles[0]));
+        // Already warm: nothing more to load
+        assert_eq!(cache.warm_memory(14, &bbox).await.unwrap(), 0);
+        std::fs::remove_dir_all(&cache_dir).ok();
+    }
+
+    #[tokio::test]
+    async fn test_memory_cap_is_respected() {
+        let cache_dir = std::env::temp_dir().join(format!("gps-monitor-memory-cap-{}", std::process::id()));
+        let mut cache = TileCache::new(cache_dir.clone(), Handle::current()).unwrap();
+        cache.set_limits(3, 2);
+        assert_eq!(cache.download_permits.available_permits(), 2);
+
+        for x in 0..10 {
+            cache.add_to_memory_cache((14, x, 0), Arc::new(vec![x as u8]));
+            assert!(cache.get_stats().memory_tiles <= 3);
+        }
+        assert_eq!(cache.get_stats().memory_tiles, 3);
+        assert!(cache.memory_cache.lock().unwrap().contains_key(&(14, 9, 0)));
+
+        // Clones made afterwards share the limits
+        let clone = cache.clone();
+        clone.add_to_memory_cache((14, 10, 0), Arc::new(vec![10]));
+        assert_eq!(cache.get_stats().memory_tiles, 3);
+
+        // Zero would stall every download
+        cache.set_limits(0, 0);
+        assert_eq!(cache.max_memory_tiles, 1);
+        assert_eq!(cache.download_permits.available_permits(), 1);
+        std::fs::remove_dir_all(&cache_dir).ok();
+    }
+
+    #[tokio::test]
+    async fn test_download_queue_is_bounded() {
+        let cache_dir = std::env::temp_dir().join(format!("gps-monitor-queue-{}", std::process::id()));
+        let cache = TileCache::new(cache_dir.clone(), Handle::current()).unwrap();
+        // Hold every slot so nothing leaves the queue
+        let _slots = Arc::clone(&cache.download_permits).acquire_many_owned(DEFAULT_CONCURRENT_DOWNLOADS as u32).await.unwrap();
+
+        for x in 0..10 {
+            cache.download_tile_async(15, x, 0);
+        }
+        cache.download_tile_async(15, 0, 0);
+        assert_eq!(cache.queued_downloads(), 10);
+        for x in 0..MAX_QUEUED_DOWNLOADS as u32 {
+            cache.download_tile_async(14, x, 0);
+        }
+        assert_eq!(cache.queued_downloads(), MAX_QUEUED_DOWNLOADS);
+
+        // Leaving zoom 15 frees its places
+        cache.drop_queued_downloads(|(zoom, _, _)| zoom == 14);
+        assert_eq!(cache.queued_downloads(), MAX_QUEUED_DOWNLOADS - 10);
+        std::fs::remove_dir_all(&cache_dir).ok();
+    }
+
+    #[test]
+    fn test_split_at_antimeridian() {
+        // Eastbound across the Pacific: 179°E to 179°W is 2° of travel
+        let runs = split_at_antimeridian([(10.0, 178.0), (10.0, 179.0), (12.0, -179.0), (12.0, -178.0)]);
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs[0], vec![(10.0, 178.0), (10.0, 179.0), (11.0, 180.0)]);
+        assert_eq!(runs[1], vec![(11.0, -180.0), (12.0, -179.0), (12.0, -178.0)]);
+
+        // Westbound, crossing at a quarter of the step
+        let runs = split_at_antimeridian([(0.0, -179.5), (4.0, 178.5)]);
+        assert_eq!(runs, vec![vec![(0.0, -179.5), (1.0, -180.0)], vec![(1.0, 180.0), (4.0, 178.5)]]);
+
+        // A line that stays on one side is left alone
+        let line = vec![(42.0, -71.0), (42.1, -71.2), (42.2, -71.1)];
+        assert_eq!(split_at_antimeridian(line.clone()), vec![line]);
+    }
+
+    #[tokio::test]
+    async fn test_corrupt_tile_is_discarded() {
+        let cache_dir = std::env::temp_dir().join(format!("gps-monitor-corrupt-{}", std::process::id()));
+        let cache = TileCache::new(cache_dir.clone(), Handle::current()).unwrap();
+
+        let write_tile = |x: u32, bytes: &[u8]| {
+            let path = TileCache::tile_path(&cache_dir, 14, x, 100);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, bytes).unwrap();
+            path
+        };
+
+        // A truncated PNG: the signature and nothing else
+        let corrupt = write_tile(1, b"\x89PNG\r\n\x1a\n");
+        assert!(cache.get_tile_image(14, 1, 100).is_err());
+        assert!(!corrupt.exists());
+        assert!(!cache.memory_cache.lock().unwrap().contains_key(&(14, 1, 100)));
+        assert_eq!(cache.get_stats().corrupt_tiles, 1);
+
+        let mut png = Vec::new();
+        image::RgbaImage::new(2, 2)
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+        let valid = write_tile(2, &png);
+        assert_eq!(cache.get_tile_image(14, 2, 100).unwrap().width(), 2);
+        assert!(valid.exists());
+        assert_eq!(cache.get_stats().corrupt_tiles, 1);
+
+        std::fs::remove_dir_all(&cache_dir).ok();
+    }
+
+    #[test]
+    fn test_user_agent_composition() {
+        assert_eq!(user_agent(None, None), DEFAULT_USER_AGENT);
+        assert_eq!(user_agent(Some("  "), Some("")), DEFAULT_USER_AGENT);
+        assert_eq!(user_agent(Some("FleetLogger/2.1"), None), "FleetLogger/2.1");
+        assert_eq!(
+            user_agent(None, Some("ops@example.com")),
+            format!("{} (contact: ops@example.com)", DEFAULT_USER_AGENT)
+        );
+        assert_eq!(user_agent(Some("Flötte\n"), Some("ops@exämple.com\r\n")), "Fltte (contact: ops@exmple.com)");
+        assert_eq!(user_agent(Some("ü"), None), DEFAULT_USER_AGENT);
+        assert!(build_client(&user_agent(Some("a\u{7f}b"), Some("\0"))).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_client_sends_configured_user_agent() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/0/0/0.png", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = vec![0; 4096];
+            let len = stream.read(&mut request).await.unwrap();
+            stream.write_all(b"HTTP/1.1 204 No Content\r\nContent-Length: 0\r\n\r\n").await.unwrap();
+            String::from_utf8_lossy(&request[..len]).to_lowercase()
+        });
+
+        let agent = user_agent(Some("FleetLogger/2.1"), Some("ops@example.com"));
+        build_client(&agent).unwrap().get(&url).send().await.unwrap();
+        let request = server.await.unwrap();
+        assert!(request.contains("user-agent: fleetlogger/2.1 (contact: ops@example.com)\r\n"), "{}", request);
+    }
 }
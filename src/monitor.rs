@@ -1,37 +1,274 @@
-// src/monitor.rs v2
-/// Main GPS monitor coordination
+// src/monitor.rs v10
+//! Main GPS monitor coordination
 
 use crate::{
+    config::{GpsConfig, SerialFlowControl, SerialLine, SerialParity},
     display::terminal::TerminalDisplay,
     error::{Result, GpsError},
-    gps::{data::GpsData, gpsd, nmea},
+    gps::{data::GpsData, gpsd, nmea, ConnectionStatus, RateMeter, SpeedUnit},
+    lock::RecoverPoison,
 };
+use chrono::Utc;
 use std::{
+    collections::HashMap,
+    future::Future,
+    io::Read,
+    pin::Pin,
     sync::{
         atomic::{AtomicBool, Ordering},
-        Arc, RwLock,
+        Arc, Mutex, RwLock,
     },
-    time::Duration,
+    time::{Duration, Instant},
 };
-use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, BufReader};
+use tokio::sync::watch;
 use tokio_serial::SerialPortBuilderExt;
 
 #[cfg(windows)]
 use crate::gps::windows;
 
+#[cfg(all(target_os = "linux", feature = "geoclue"))]
+use crate::gps::geoclue;
+
+/// Default time to wait for serial data before re-checking the running flag
+pub const DEFAULT_SERIAL_TIMEOUT_MS: u64 = 1000;
+
+/// Default longest serial line accepted; NMEA sentences are at most 82 bytes
+/// but proprietary ones can be longer
+pub const DEFAULT_MAX_LINE_LENGTH: usize = 1024;
+
+/// Baud rates tried by `detect_baudrate`, in order
+pub const AUTO_BAUD_RATES: [u32; 6] = [4800, 9600, 19200, 38400, 57600, 115200];
+
+/// How long `detect_baudrate` listens at each rate; receivers report at least once a second
+const BAUD_PROBE_WINDOW: Duration = Duration::from_millis(2500);
+
+/// Checksum-valid sentences needed before a baud rate is accepted
+const MIN_VALID_SENTENCES: usize = 2;
+
+/// gpsd SKY reports with many satellites can be several KB
+const GPSD_MAX_LINE_LENGTH: usize = 64 * 1024;
+
 /// GPS data source configuration
 #[derive(Debug, Clone)]
 pub enum GpsSource {
-    Serial { port: String, baudrate: u32 },
+    Serial {
+        port: String,
+        baudrate: u32,
+        read_timeout_ms: u64,
+        max_line_length: usize,
+        rmc_speed_unit: SpeedUnit,
+        line: SerialLine,
+    },
     Gpsd { host: String, port: u16 },
     #[cfg(windows)]
     Windows { accuracy: u32, interval: u64 },
+    #[cfg(all(target_os = "linux", feature = "geoclue"))]
+    GeoClue,
+    /// Several sources read at once into separate buffers, with the one
+    /// chosen by `policy` shown. Sources can't be nested.
+    Multi { sources: Vec<GpsSource>, policy: SourcePolicy },
+}
+
+impl GpsSource {
+    /// Source described by the configured source type, falling back to the
+    /// platform default (Windows Location, or gpsd on localhost)
+    pub fn from_config(config: &GpsConfig) -> Self {
+        match config.source_type.as_str() {
+            "serial" => {
+                let port = config.serial_port.clone().unwrap_or_default();
+                let baudrate = config.serial_baudrate.unwrap_or(9600);
+                let read_timeout_ms = config.serial_read_timeout_ms
+                    .map_or(DEFAULT_SERIAL_TIMEOUT_MS, u64::from);
+                let max_line_length = config.serial_max_line_length
+                    .map_or(DEFAULT_MAX_LINE_LENGTH, |n| n as usize);
+                let rmc_speed_unit = config.rmc_speed_unit;
+                let line = config.serial_line;
+                GpsSource::Serial { port, baudrate, read_timeout_ms, max_line_length, rmc_speed_unit, line }
+            }
+            "gpsd" => {
+                let host = config.gpsd_host.clone().unwrap_or_else(|| "localhost".to_string());
+                let port = config.gpsd_port.unwrap_or(2947);
+                GpsSource::Gpsd { host, port }
+            }
+            #[cfg(windows)]
+            "windows" => {
+                let accuracy = config.windows_accuracy.unwrap_or(10);
+                let interval = config.windows_interval.unwrap_or(1);
+                GpsSource::Windows { accuracy, interval }
+            }
+            #[cfg(all(target_os = "linux", feature = "geoclue"))]
+            "geoclue" => GpsSource::GeoClue,
+            _ => {
+                // Default to platform-specific source
+                #[cfg(windows)]
+                {
+                    GpsSource::Windows { accuracy: 10, interval: 1 }
+                }
+                #[cfg(not(windows))]
+                {
+                    GpsSource::Gpsd {
+                        host: "localhost".to_string(),
+                        port: 2947,
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// How `GpsSource::Multi` chooses between its sources
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SourcePolicy {
+    #[default]
+    LowestHdop,
+    MostSatellites,
+    Freshest,
+}
+
+impl SourcePolicy {
+    /// Index of the source to show. Only sources with a recent valid fix are
+    /// considered, with the freshest winning ties; when none has one, the
+    /// freshest data is shown so its connection state stays visible.
+    pub fn select(self, sources: &[&GpsData]) -> Option<usize> {
+        let freshest = |candidates: &mut dyn Iterator<Item = usize>| {
+            candidates.filter(|&i| sources[i].timestamp.is_some()).max_by_key(|&i| sources[i].timestamp)
+        };
+
+        let usable: Vec<usize> = (0..sources.len())
+            .filter(|&i| sources[i].has_valid_fix() && sources[i].is_recent())
+            .collect();
+        if usable.is_empty() {
+            return freshest(&mut (0..sources.len()));
+        }
+
+        match self {
+            SourcePolicy::LowestHdop => {
+                let hdop = |i: usize| sources[i].hdop.unwrap_or(f64::INFINITY);
+                let best = usable.iter().map(|&i| hdop(i)).fold(f64::INFINITY, f64::min);
+                freshest(&mut usable.into_iter().filter(|&i| hdop(i) <= best))
+            }
+            SourcePolicy::MostSatellites => {
+                let count = |i: usize| sources[i].used_satellite_count().unwrap_or(0);
+                let best = usable.iter().map(|&i| count(i)).max().unwrap_or(0);
+                freshest(&mut usable.into_iter().filter(|&i| count(i) == best))
+            }
+            SourcePolicy::Freshest => freshest(&mut usable.into_iter()),
+        }
+    }
+}
+
+/// Copies the selected source of a `GpsSource::Multi` into the monitor's data
+struct SourceFusion {
+    buffers: Vec<Arc<RwLock<GpsData>>>,
+    policy: SourcePolicy,
+    data: Arc<RwLock<GpsData>>,
+    observers: Vec<UpdateObserver>,
+}
+
+impl SourceFusion {
+    fn update(&self) {
+        let snapshot = {
+            let guards: Vec<_> = self.buffers.iter().map(|buffer| buffer.read_recover()).collect();
+            let sources: Vec<&GpsData> = guards.iter().map(|guard| &**guard).collect();
+            let Some(selected) = self.policy.select(&sources) else {
+                return;
+            };
+            let mut data = self.data.write_recover();
+            *data = sources[selected].clone();
+            (!self.observers.is_empty()).then(|| data.clone())
+        };
+        if let Some(snapshot) = snapshot {
+            notify(&self.observers, &snapshot);
+        }
+    }
+}
+
+/// How often a `GpsSource::Multi` re-selects without new lines, for sources
+/// such as Windows Location that update their data without notifying observers
+const FUSION_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Source name set on data fed through `GpsMonitor::ingest_sentence`
+pub const INGESTED_SOURCE: &str = "Ingested NMEA";
+
+/// Callback invoked with each raw line received from a source
+pub type SentenceObserver = Arc<dyn Fn(&str) + Send + Sync>;
+
+/// Callback invoked with a snapshot of the GPS data after each line is parsed
+pub type UpdateObserver = Arc<dyn Fn(&GpsData) + Send + Sync>;
+
+/// Optional observers notified from the read loops
+#[derive(Clone, Default)]
+struct Observers {
+    sentence: Option<SentenceObserver>,
+    update: Vec<UpdateObserver>,
+}
+
+/// Hand `snapshot` to each update observer in the order they were added
+fn notify(observers: &[UpdateObserver], snapshot: &GpsData) {
+    for observer in observers {
+        observer(snapshot);
+    }
+}
+
+/// Count of each sentence type received, e.g. to see that a receiver never
+/// sends GSA (and so never reports DOP)
+#[derive(Debug, Clone, Default)]
+pub struct SentenceCounts {
+    counts: HashMap<String, u64>,
+    rate: RateMeter,
+}
+
+impl SentenceCounts {
+    /// Count a line by its talker and type ("GPGGA"), or its class for gpsd JSON ("TPV")
+    pub fn record(&mut self, line: &str) {
+        let kind = sentence_type(line).unwrap_or("Other");
+        *self.counts.entry(kind.to_string()).or_insert(0) += 1;
+        self.rate.record(Utc::now());
+    }
+
+    pub fn count(&self, kind: &str) -> u64 {
+        self.counts.get(kind).copied().unwrap_or(0)
+    }
+
+    pub fn total(&self) -> u64 {
+        self.counts.values().sum()
+    }
+
+    /// Lines per second over the last few seconds, once that long has passed
+    pub fn rate(&self) -> Option<f64> {
+        self.rate.rate(Utc::now())
+    }
+
+    /// Types and counts, most frequent first
+    pub fn sorted(&self) -> Vec<(String, u64)> {
+        let mut counts: Vec<(String, u64)> = self.counts.iter().map(|(k, &v)| (k.clone(), v)).collect();
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        counts
+    }
+}
+
+/// Address field of an NMEA sentence, or the class of a gpsd JSON report
+fn sentence_type(line: &str) -> Option<&str> {
+    if let Some(body) = line.strip_prefix('$').or_else(|| line.strip_prefix('!')) {
+        let kind = body.split([',', '*']).next()?;
+        return (!kind.is_empty()).then_some(kind);
+    }
+    let rest = &line[line.find("\"class\"")? + 7..];
+    let value = rest.trim_start().strip_prefix(':')?.trim_start().strip_prefix('"')?;
+    value.split('"').next().filter(|class| !class.is_empty())
 }
 
 /// Main GPS monitor that coordinates data collection and display
+///
+/// Cloning the monitor shares the underlying data and running flag.
+#[derive(Clone)]
 pub struct GpsMonitor {
     data: Arc<RwLock<GpsData>>,
     running: Arc<AtomicBool>,
+    observers: Observers,
+    satellite_max_age: Option<chrono::Duration>,
+    sentence_counts: Arc<Mutex<SentenceCounts>>,
 }
 
 impl GpsMonitor {
@@ -40,9 +277,18 @@ impl GpsMonitor {
         Self {
             data: Arc::new(RwLock::new(GpsData::new())),
             running: Arc::new(AtomicBool::new(true)),
+            observers: Observers::default(),
+            satellite_max_age: None,
+            sentence_counts: Arc::new(Mutex::new(SentenceCounts::default())),
         }
     }
 
+    /// Build a monitor that runs in the background without a display and
+    /// publishes each update on a channel. See `MonitorBuilder`.
+    pub fn builder(source: GpsSource) -> MonitorBuilder {
+        MonitorBuilder::new(source)
+    }
+
     /// Create a new GPS monitor with shared data and running flag
     pub fn new_with_shared(
         data: Arc<RwLock<GpsData>>,
@@ -51,22 +297,82 @@ impl GpsMonitor {
         Self {
             data,
             running,
+            observers: Observers::default(),
+            satellite_max_age: None,
+            sentence_counts: Arc::new(Mutex::new(SentenceCounts::default())),
         }
     }
 
-    /// Clone the monitor (shares data and running flag)
-    pub fn clone(&self) -> Self {
-        Self {
-            data: Arc::clone(&self.data),
-            running: Arc::clone(&self.running),
-        }
+    /// Observe every raw sentence (or gpsd JSON line) as it is received.
+    ///
+    /// The callback runs on the Tokio task reading the source, so it must be
+    /// `Send + Sync` and should return quickly. Set it before calling `start`;
+    /// connections already running keep the observers they started with.
+    pub fn set_sentence_observer<F>(&mut self, observer: F)
+    where
+        F: Fn(&str) + Send + Sync + 'static,
+    {
+        self.observers.sentence = Some(Arc::new(observer));
+    }
+
+    /// Observe the GPS data after each sentence has been parsed. Observers
+    /// added earlier keep being notified, in the order they were added.
+    ///
+    /// The callback receives a snapshot taken after the data lock is released,
+    /// so it may safely call back into the monitor. The same threading rules as
+    /// `set_sentence_observer` apply.
+    pub fn add_update_observer<F>(&mut self, observer: F)
+    where
+        F: Fn(&GpsData) + Send + Sync + 'static,
+    {
+        self.observers.update.push(Arc::new(observer));
+    }
+
+    /// Drop satellites not reported for this long. By default (`None`) they
+    /// are kept until their constellation's next report. Set it before
+    /// calling `start`.
+    pub fn set_satellite_max_age(&mut self, max_age: Option<Duration>) {
+        self.satellite_max_age = max_age.and_then(|age| chrono::Duration::from_std(age).ok());
+    }
+
+    /// Copy of the sentence type counts since the monitor was created
+    pub fn sentence_counts(&self) -> SentenceCounts {
+        self.sentence_counts.lock().unwrap().clone()
     }
 
     /// Start monitoring GPS data from the specified source
     pub async fn start(&self, source: GpsSource) -> Result<()> {
+        // Time to first fix is measured from here
+        {
+            let mut data = self.data.write_recover();
+            data.mark_connected();
+            data.connection = ConnectionStatus::Connecting;
+        }
+
+        let result = self.connect(source).await;
+        if let Err(e) = &result {
+            self.data.write_recover().connection = ConnectionStatus::Lost(e.to_string());
+        }
+        result
+    }
+
+    fn mark_status_connected(&self) {
+        self.data.write_recover().connection = ConnectionStatus::Connected;
+    }
+
+    /// Report a reconnect attempt to the displays before calling `start` again
+    pub fn set_reconnecting(&self, attempt: u32) {
+        self.data.write_recover().connection = ConnectionStatus::Reconnecting { attempt };
+    }
+
+    async fn connect(&self, source: GpsSource) -> Result<()> {
         match source {
-            GpsSource::Serial { port, baudrate } => {
-                self.connect_serial(&port, baudrate).await?;
+            GpsSource::Serial { port, baudrate, read_timeout_ms, max_line_length, rmc_speed_unit, line } => {
+                let limits = ReadLimits {
+                    timeout: Some(Duration::from_millis(read_timeout_ms)),
+                    max_line_length,
+                };
+                self.connect_serial(&port, baudrate, limits, rmc_speed_unit, &line).await?;
             }
             GpsSource::Gpsd { host, port } => {
                 self.connect_gpsd(&host, port).await?;
@@ -75,54 +381,110 @@ impl GpsMonitor {
             GpsSource::Windows { accuracy, interval } => {
                 self.connect_windows_location(accuracy, interval).await?;
             }
+            #[cfg(all(target_os = "linux", feature = "geoclue"))]
+            GpsSource::GeoClue => {
+                self.connect_geoclue().await?;
+            }
+            GpsSource::Multi { sources, policy } => {
+                self.connect_multi(sources, policy).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Start every source on its own buffer, selecting between them after
+    /// each update. Fails only if none of the sources connects.
+    async fn connect_multi(&self, sources: Vec<GpsSource>, policy: SourcePolicy) -> Result<()> {
+        if sources.is_empty() || sources.iter().any(|s| matches!(s, GpsSource::Multi { .. })) {
+            return Err(GpsError::Other("A multi-source needs at least one source and can't be nested".to_string()));
+        }
+
+        let buffers: Vec<_> = sources.iter().map(|_| Arc::new(RwLock::new(GpsData::new()))).collect();
+        let fusion = Arc::new(SourceFusion {
+            buffers: buffers.clone(),
+            policy,
+            data: Arc::clone(&self.data),
+            observers: self.observers.update.clone(),
+        });
+        let select: UpdateObserver = {
+            let fusion = Arc::clone(&fusion);
+            Arc::new(move |_: &GpsData| fusion.update())
+        };
+
+        // The running flag is shared with later connections, e.g. after a
+        // reconnect, so the tick also stops once this connection's sources
+        // have all finished: their observers hold the only strong references.
+        let connection = Arc::downgrade(&fusion);
+        let running = Arc::clone(&self.running);
+        tokio::spawn(async move {
+            while running.load(Ordering::Relaxed) {
+                tokio::time::sleep(FUSION_INTERVAL).await;
+                let Some(fusion) = connection.upgrade() else {
+                    break;
+                };
+                fusion.update();
+            }
+        });
+        drop(fusion);
+
+        // Started together, since some sources (Windows Location) only return once stopped
+        let starts: Vec<Pin<Box<dyn Future<Output = Result<()>> + '_>>> = sources.into_iter()
+            .zip(buffers)
+            .enumerate()
+            .map(|(i, (source, buffer))| {
+                let child = GpsMonitor {
+                    data: buffer,
+                    running: Arc::clone(&self.running),
+                    observers: Observers { sentence: self.observers.sentence.clone(), update: vec![Arc::clone(&select)] },
+                    satellite_max_age: self.satellite_max_age,
+                    sentence_counts: Arc::clone(&self.sentence_counts),
+                };
+                Box::pin(async move {
+                    let result = child.start(source).await;
+                    if let Err(e) = &result {
+                        eprintln!("Source {} failed to start: {}", i + 1, e);
+                    }
+                    result
+                }) as Pin<Box<dyn Future<Output = Result<()>> + '_>>
+            })
+            .collect();
+
+        let results = futures_util::future::join_all(starts).await;
+        if results.iter().all(|result| result.is_err()) {
+            return Err(GpsError::Connection("None of the sources connected".to_string()));
         }
         Ok(())
     }
 
-    /// Start the display (terminal only for now)
-    pub async fn run_display(&self) -> Result<()> {
-        let terminal_display = TerminalDisplay::new();
-        terminal_display.run(Arc::clone(&self.data), Arc::clone(&self.running)).await
+    /// Start the display (terminal only for now), e.g. `TerminalDisplay::from_config`
+    pub async fn run_display(&self, display: &TerminalDisplay) -> Result<()> {
+        display.run(Arc::clone(&self.data), Arc::clone(&self.running)).await
     }
 
     /// Connect to a GPS device via serial port
-    async fn connect_serial(&self, port: &str, baudrate: u32) -> Result<()> {
-        println!("Connecting to GPS on {} at {} baud...", port, baudrate);
+    async fn connect_serial(&self, port: &str, baudrate: u32, limits: ReadLimits, rmc_speed_unit: SpeedUnit, line: &SerialLine) -> Result<()> {
+        println!("Connecting to GPS on {} at {} baud {}...", port, baudrate, line.framing());
 
-        let serial = tokio_serial::new(port, baudrate)
-            .timeout(Duration::from_millis(1000))
+        let timeout = limits.timeout.unwrap_or(Duration::from_millis(DEFAULT_SERIAL_TIMEOUT_MS));
+        let mut serial = serial_port_builder(port, baudrate, timeout, line)
             .open_native_async()
             .map_err(|e| GpsError::Connection(format!("Failed to open serial port {}: {}", port, e)))?;
 
-        println!("Connected successfully!");
+        if let Some(rts) = line.rts {
+            use tokio_serial::SerialPort;
+            serial.write_request_to_send(rts)
+                .map_err(|e| GpsError::Connection(format!("Failed to set RTS on {}: {}", port, e)))?;
+        }
 
-        let data = Arc::clone(&self.data);
-        let running = Arc::clone(&self.running);
+        println!("Connected successfully!");
+        self.mark_status_connected();
 
+        let monitor = self.clone();
         tokio::spawn(async move {
-            let mut reader = BufReader::new(serial);
-            let mut line = String::new();
-
-            while running.load(Ordering::Relaxed) {
-                line.clear();
-                match reader.read_line(&mut line).await {
-                    Ok(0) => break, // EOF
-                    Ok(_) => {
-                        let line = line.trim();
-                        if !line.is_empty() {
-                            let mut data_guard = data.write().unwrap();
-                            data_guard.update_timestamp();
-                            data_guard.add_raw_sentence(line);
-                            data_guard.set_source("Serial GPS");
-                            nmea::parse_nmea_sentence(&mut data_guard, line);
-                        }
-                    }
-                    Err(e) => {
-                        eprintln!("Error reading from serial port: {}", e);
-                        break;
-                    }
-                }
-            }
+            monitor.read_lines(BufReader::new(serial), "serial port", limits, |data, line| {
+                data.set_source("Serial GPS");
+                nmea::parse_nmea_sentence_with_speed_unit(data, line, rmc_speed_unit);
+            }).await;
         });
 
         Ok(())
@@ -132,41 +494,126 @@ impl GpsMonitor {
     async fn connect_gpsd(&self, host: &str, port: u16) -> Result<()> {
         println!("Connecting to gpsd at {}:{}...", host, port);
 
-        let mut reader = gpsd::connect_gpsd(host, port).await?;
+        let reader = gpsd::connect_gpsd(host, port).await?;
         println!("Connected successfully!");
+        self.mark_status_connected();
 
-        let data = Arc::clone(&self.data);
-        let running = Arc::clone(&self.running);
-
+        let monitor = self.clone();
         tokio::spawn(async move {
-            let mut line = String::new();
+            let limits = ReadLimits { timeout: None, max_line_length: GPSD_MAX_LINE_LENGTH };
+            monitor.read_lines(reader, "gpsd", limits, |data, line| {
+                data.set_source("gpsd");
+                if let Err(e) = gpsd::parse_gpsd_json(data, line) {
+                    eprintln!("Error parsing gpsd JSON: {}", e);
+                }
+            }).await;
+        });
 
-            while running.load(Ordering::Relaxed) {
-                line.clear();
-                match reader.read_line(&mut line).await {
-                    Ok(0) => break, // EOF
-                    Ok(_) => {
-                        let line = line.trim();
-                        if !line.is_empty() {
-                            let mut data_guard = data.write().unwrap();
-                            data_guard.update_timestamp();
-                            data_guard.add_raw_sentence(line);
-                            data_guard.set_source("gpsd");
-                            
-                            if let Err(e) = gpsd::parse_gpsd_json(&mut data_guard, line) {
-                                eprintln!("Error parsing gpsd JSON: {}", e);
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        eprintln!("Error reading from gpsd: {}", e);
-                        break;
-                    }
+        Ok(())
+    }
+
+    /// Read lines until EOF, a read error, or the monitor is stopped, handing
+    /// each non-empty line to `parse` and notifying any observers.
+    ///
+    /// Lines longer than `limits.max_line_length` are discarded so a device
+    /// sending garbage without newlines can't grow the buffer forever.
+    async fn read_lines<R, F>(&self, reader: R, source_name: &str, limits: ReadLimits, parse: F)
+    where
+        R: AsyncBufRead + Unpin,
+        F: Fn(&mut GpsData, &str),
+    {
+        let mut reader = LineReader::new(reader, limits.max_line_length);
+
+        while self.running.load(Ordering::Relaxed) {
+            let result = match limits.timeout {
+                // A timed-out read keeps its partial line, so just re-check the running flag
+                Some(timeout) => match tokio::time::timeout(timeout, reader.next_line()).await {
+                    Ok(result) => result,
+                    Err(_) => continue,
+                },
+                None => reader.next_line().await,
+            };
+
+            match result {
+                Ok(LineRead::Eof) => {
+                    self.data.write_recover().connection = ConnectionStatus::Lost(format!("{} closed", source_name));
+                    break;
+                }
+                Ok(LineRead::TooLong) => {
+                    eprintln!("Discarding line from {} longer than {} bytes", source_name, limits.max_line_length);
+                }
+                Ok(LineRead::Line) => {
+                    let text = reader.take_line();
+                    self.ingest_line(&text, &parse);
+                }
+                Err(e) => {
+                    eprintln!("Error reading from {}: {}", source_name, e);
+                    self.data.write_recover().connection = ConnectionStatus::Lost(e.to_string());
+                    break;
                 }
             }
+        }
+    }
+
+    /// Feed one NMEA sentence from the embedder's own transport (or a test)
+    /// through the same handling as a serial connection: the data's
+    /// timestamp, raw history, sentence counts and observers are all updated.
+    /// Blank lines are ignored; the shared data is locked only while parsing.
+    pub fn ingest_sentence(&self, line: &str) {
+        self.ingest_line(line, |data, line| {
+            data.set_source(INGESTED_SOURCE);
+            nmea::parse_nmea_sentence(data, line);
         });
+    }
 
-        Ok(())
+    /// Common handling of each received line, with `parse` for the source's format
+    ///
+    /// A bare CR also ends a line, as some receivers send no LF. NMEA lines
+    /// are cleaned of noise before the sentence (see `nmea::clean_sentence`).
+    fn ingest_line<F>(&self, text: &str, parse: F)
+    where
+        F: Fn(&mut GpsData, &str),
+    {
+        for line in text.split('\r') {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            // gpsd JSON is taken as it is
+            let sentence = if line.starts_with('{') { None } else { nmea::clean_sentence(line) };
+            self.ingest_clean_line(sentence.as_deref().unwrap_or(line), &parse);
+        }
+    }
+
+    /// Count, record and parse one trimmed line, then notify the observers
+    fn ingest_clean_line<F>(&self, line: &str, parse: F)
+    where
+        F: Fn(&mut GpsData, &str),
+    {
+        self.sentence_counts.lock().unwrap().record(line);
+
+        let snapshot = {
+            let mut data_guard = self.data.write_recover();
+            data_guard.update_timestamp();
+            data_guard.add_raw_sentence(line);
+            parse(&mut data_guard, line);
+            if let Some(jump) = data_guard.clock_jump.take() {
+                eprintln!("{}", jump);
+            }
+            if let Some(max_age) = self.satellite_max_age {
+                data_guard.prune_stale_satellites(max_age);
+            }
+            data_guard.update_time_to_first_fix();
+            data_guard.update_fix_events();
+            (!self.observers.update.is_empty()).then(|| data_guard.clone())
+        };
+
+        if let Some(ref observer) = self.observers.sentence {
+            observer(line);
+        }
+        if let Some(snapshot) = snapshot {
+            notify(&self.observers.update, &snapshot);
+        }
     }
 
     /// Connect to Windows Location Services
@@ -180,6 +627,7 @@ impl GpsMonitor {
         let geolocator = windows::create_geolocator(accuracy)?;
 
         println!("Windows Location Service initialized successfully!");
+        self.mark_status_connected();
 
         // Start monitoring
         windows::run_location_monitoring(
@@ -198,6 +646,66 @@ impl GpsMonitor {
         Err(GpsError::Other("Windows Location Service is only available on Windows".to_string()))
     }
 
+    /// Subscribe to GeoClue2 location updates over DBus
+    #[cfg(all(target_os = "linux", feature = "geoclue"))]
+    async fn connect_geoclue(&self) -> Result<()> {
+        println!("Connecting to GeoClue...");
+
+        let mut client = geoclue::GeoClueClient::start().await
+            .map_err(|e| GpsError::Connection(format!("Failed to start GeoClue client: {}", e)))?;
+
+        println!("Connected successfully!");
+        self.mark_status_connected();
+
+        let monitor = self.clone();
+        tokio::spawn(async move {
+            let mut running_check = tokio::time::interval(Duration::from_secs(1));
+            while monitor.running.load(Ordering::Relaxed) {
+                // Locations can be minutes apart, so re-check the running flag
+                // meanwhile. The read carries on across checks: dropping it
+                // part way through would lose the location it had taken.
+                let next = client.next_location();
+                tokio::pin!(next);
+                let read = loop {
+                    tokio::select! {
+                        read = &mut next => break Some(read),
+                        _ = running_check.tick() => {
+                            if !monitor.running.load(Ordering::Relaxed) {
+                                break None;
+                            }
+                        }
+                    }
+                };
+                let location = match read {
+                    Some(Ok(Some(location))) => location,
+                    Some(Ok(None)) => {
+                        monitor.data.write_recover().connection = ConnectionStatus::Lost("GeoClue closed".to_string());
+                        break;
+                    }
+                    Some(Err(e)) => {
+                        eprintln!("Error reading from GeoClue: {}", e);
+                        monitor.data.write_recover().connection = ConnectionStatus::Lost(e.to_string());
+                        break;
+                    }
+                    None => break,
+                };
+
+                let snapshot = {
+                    let mut data_guard = monitor.data.write_recover();
+                    geoclue::update_from_location(&mut data_guard, &location);
+                    data_guard.update_time_to_first_fix();
+                    (!monitor.observers.update.is_empty()).then(|| data_guard.clone())
+                };
+                if let Some(snapshot) = snapshot {
+                    notify(&monitor.observers.update, &snapshot);
+                }
+            }
+            client.stop().await;
+        });
+
+        Ok(())
+    }
+
     /// Stop the monitor
     pub fn stop(&self) {
         self.running.store(false, Ordering::Relaxed);
@@ -210,7 +718,7 @@ impl GpsMonitor {
 
     /// Get a clone of the current GPS data
     pub fn get_data(&self) -> GpsData {
-        self.data.read().unwrap().clone()
+        self.data.read_recover().clone()
     }
 }
 
@@ -220,6 +728,271 @@ impl Default for GpsMonitor {
     }
 }
 
+/// Headless monitoring for library use: connect to a source and receive
+/// `GpsData` snapshots on a `tokio::sync::watch` channel, with no terminal or
+/// GUI display.
+///
+/// ```no_run
+/// use gps_monitor::{GpsMonitor, GpsSource};
+///
+/// # async fn run() -> gps_monitor::Result<()> {
+/// let source = GpsSource::Gpsd { host: "localhost".to_string(), port: 2947 };
+/// let handle = GpsMonitor::builder(source).spawn().await?;
+///
+/// let mut updates = handle.updates();
+/// while updates.changed().await.is_ok() {
+///     let data = updates.borrow_and_update().clone();
+///     if let (Some(lat), Some(lon)) = (data.latitude, data.longitude) {
+///         println!("{:.6}, {:.6}", lat, lon);
+///     }
+/// }
+/// handle.stop();
+/// # Ok(())
+/// # }
+/// ```
+pub struct MonitorBuilder {
+    source: GpsSource,
+    satellite_max_age: Option<Duration>,
+    sentence_observer: Option<SentenceObserver>,
+}
+
+impl MonitorBuilder {
+    pub fn new(source: GpsSource) -> Self {
+        Self {
+            source,
+            satellite_max_age: None,
+            sentence_observer: None,
+        }
+    }
+
+    /// See `GpsMonitor::set_satellite_max_age`
+    pub fn satellite_max_age(mut self, max_age: Option<Duration>) -> Self {
+        self.satellite_max_age = max_age;
+        self
+    }
+
+    /// See `GpsMonitor::set_sentence_observer`
+    pub fn sentence_observer<F>(mut self, observer: F) -> Self
+    where
+        F: Fn(&str) + Send + Sync + 'static,
+    {
+        self.sentence_observer = Some(Arc::new(observer));
+        self
+    }
+
+    /// Connect to the source and start reading in the background. Returns an
+    /// error if the source can't be opened. Must be called within a Tokio runtime.
+    pub async fn spawn(self) -> Result<MonitorHandle> {
+        let mut monitor = GpsMonitor::new();
+        monitor.set_satellite_max_age(self.satellite_max_age);
+        monitor.observers.sentence = self.sentence_observer;
+
+        let (sender, updates) = watch::channel(GpsData::new());
+        monitor.add_update_observer(move |data| {
+            sender.send_replace(data.clone());
+        });
+
+        monitor.start(self.source).await?;
+        Ok(MonitorHandle { monitor, updates })
+    }
+}
+
+/// A monitor started by `MonitorBuilder::spawn`. Reading stops when `stop`
+/// is called or the handle is dropped.
+pub struct MonitorHandle {
+    monitor: GpsMonitor,
+    updates: watch::Receiver<GpsData>,
+}
+
+impl MonitorHandle {
+    /// A receiver of the data after each sentence is parsed. Receivers only
+    /// see the latest snapshot, so a slow consumer skips updates rather than
+    /// falling behind.
+    pub fn updates(&self) -> watch::Receiver<GpsData> {
+        self.updates.clone()
+    }
+
+    /// The most recent data
+    pub fn latest(&self) -> GpsData {
+        self.monitor.get_data()
+    }
+
+    pub fn sentence_counts(&self) -> SentenceCounts {
+        self.monitor.sentence_counts()
+    }
+
+    /// Stop reading. The source is closed once its next line arrives or read times out.
+    pub fn stop(&self) {
+        self.monitor.stop();
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.monitor.is_running()
+    }
+}
+
+impl Drop for MonitorHandle {
+    fn drop(&mut self) {
+        self.monitor.stop();
+    }
+}
+
+/// Limits applied by `GpsMonitor::read_lines`
+#[derive(Debug, Clone, Copy)]
+struct ReadLimits {
+    timeout: Option<Duration>,
+    max_line_length: usize,
+}
+
+/// Outcome of `LineReader::next_line`
+#[derive(Debug, PartialEq)]
+enum LineRead {
+    Line,
+    TooLong,
+    Eof,
+}
+
+/// Newline-delimited reader with a cap on line length.
+///
+/// Partial lines are kept between calls, so `next_line` can be safely
+/// cancelled (e.g. by a timeout) and called again.
+struct LineReader<R> {
+    reader: R,
+    buf: Vec<u8>,
+    discarding: bool,
+    max_len: usize,
+}
+
+impl<R: AsyncBufRead + Unpin> LineReader<R> {
+    fn new(reader: R, max_len: usize) -> Self {
+        Self {
+            reader,
+            buf: Vec::new(),
+            discarding: false,
+            max_len,
+        }
+    }
+
+    /// Read up to the next newline. On `LineRead::Line` the line is available
+    /// from `take_line`; an overlong line is dropped and reported as `TooLong`.
+    async fn next_line(&mut self) -> std::io::Result<LineRead> {
+        loop {
+            let available = self.reader.fill_buf().await?;
+            if available.is_empty() {
+                // Hand back a final unterminated line before reporting EOF
+                return Ok(if self.buf.is_empty() || self.discarding { LineRead::Eof } else { LineRead::Line });
+            }
+
+            let (used, complete) = match available.iter().position(|&b| b == b'\n') {
+                Some(i) => (i + 1, true),
+                None => (available.len(), false),
+            };
+
+            if !self.discarding {
+                if self.buf.len() + used > self.max_len {
+                    self.discarding = true;
+                    self.buf.clear();
+                } else {
+                    self.buf.extend_from_slice(&available[..used]);
+                }
+            }
+            self.reader.consume(used);
+
+            if complete {
+                let too_long = std::mem::replace(&mut self.discarding, false);
+                return Ok(if too_long { LineRead::TooLong } else { LineRead::Line });
+            }
+        }
+    }
+
+    /// Take the line read by the last `next_line`, replacing invalid UTF-8
+    fn take_line(&mut self) -> String {
+        let line = String::from_utf8_lossy(&self.buf).into_owned();
+        self.buf.clear();
+        line
+    }
+}
+
+/// Port settings for a serial connection. DTR is applied as the port opens;
+/// RTS can only be set on the open port, so `connect_serial` does that.
+pub fn serial_port_builder(port: &str, baudrate: u32, timeout: Duration, line: &SerialLine) -> tokio_serial::SerialPortBuilder {
+    let data_bits = match line.data_bits {
+        5 => tokio_serial::DataBits::Five,
+        6 => tokio_serial::DataBits::Six,
+        7 => tokio_serial::DataBits::Seven,
+        _ => tokio_serial::DataBits::Eight,
+    };
+    let parity = match line.parity {
+        SerialParity::None => tokio_serial::Parity::None,
+        SerialParity::Odd => tokio_serial::Parity::Odd,
+        SerialParity::Even => tokio_serial::Parity::Even,
+    };
+    let stop_bits = if line.stop_bits == 2 {
+        tokio_serial::StopBits::Two
+    } else {
+        tokio_serial::StopBits::One
+    };
+    let flow_control = match line.flow_control {
+        SerialFlowControl::None => tokio_serial::FlowControl::None,
+        SerialFlowControl::Software => tokio_serial::FlowControl::Software,
+        SerialFlowControl::Hardware => tokio_serial::FlowControl::Hardware,
+    };
+
+    let builder = tokio_serial::new(port, baudrate)
+        .data_bits(data_bits)
+        .parity(parity)
+        .stop_bits(stop_bits)
+        .flow_control(flow_control)
+        .timeout(timeout);
+    match line.dtr {
+        Some(dtr) => builder.dtr_on_open(dtr),
+        None => builder,
+    }
+}
+
+/// Find the baud rate a serial receiver is sending at by listening briefly
+/// at each of `AUTO_BAUD_RATES` until valid NMEA sentences arrive.
+///
+/// Blocks for up to about 15 seconds, so call it off the UI thread. The port
+/// must not be open elsewhere (e.g. by a running connection).
+pub fn detect_baudrate(port: &str) -> Result<u32> {
+    for baudrate in AUTO_BAUD_RATES {
+        let mut serial = tokio_serial::new(port, baudrate)
+            .timeout(Duration::from_millis(200))
+            .open()
+            .map_err(|e| GpsError::Connection(format!("Failed to open serial port {}: {}", port, e)))?;
+
+        let mut received = Vec::new();
+        let mut buf = [0u8; 512];
+        let deadline = Instant::now() + BAUD_PROBE_WINDOW;
+        while Instant::now() < deadline {
+            match serial.read(&mut buf) {
+                Ok(n) => received.extend_from_slice(&buf[..n]),
+                Err(e) if e.kind() == std::io::ErrorKind::TimedOut => {}
+                Err(e) => return Err(GpsError::Io(e)),
+            }
+            if count_valid_sentences(&received) >= MIN_VALID_SENTENCES {
+                return Ok(baudrate);
+            }
+        }
+    }
+
+    let tried: Vec<String> = AUTO_BAUD_RATES.iter().map(|b| b.to_string()).collect();
+    Err(GpsError::Connection(format!(
+        "No valid NMEA sentences on {} at {} baud. Check the port, the cable and that the receiver is powered",
+        port,
+        tried.join(", ")
+    )))
+}
+
+/// Sentences in `bytes` whose checksum is valid; garbage from a wrong baud rate has none
+fn count_valid_sentences(bytes: &[u8]) -> usize {
+    String::from_utf8_lossy(bytes)
+        .lines()
+        .filter(|line| nmea::has_valid_checksum(line))
+        .count()
+}
+
 /// List available serial ports
 pub async fn list_serial_ports() -> Result<()> {
     let ports = tokio_serial::available_ports()
@@ -236,3 +1009,414 @@ pub async fn list_serial_ports() -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_serial_port_builder() {
+        let timeout = Duration::from_millis(500);
+        assert_eq!(
+            serial_port_builder("/dev/ttyUSB0", 4800, timeout, &SerialLine::default()),
+            tokio_serial::new("/dev/ttyUSB0", 4800).timeout(timeout),
+        );
+
+        let line = SerialLine {
+            data_bits: 7,
+            parity: SerialParity::Even,
+            stop_bits: 2,
+            flow_control: SerialFlowControl::Hardware,
+            dtr: Some(true),
+            rts: Some(false),
+        };
+        assert_eq!(
+            serial_port_builder("/dev/ttyUSB0", 4800, timeout, &line),
+            tokio_serial::new("/dev/ttyUSB0", 4800)
+                .data_bits(tokio_serial::DataBits::Seven)
+                .parity(tokio_serial::Parity::Even)
+                .stop_bits(tokio_serial::StopBits::Two)
+                .flow_control(tokio_serial::FlowControl::Hardware)
+                .dtr_on_open(true)
+                .timeout(timeout),
+        );
+    }
+
+    #[tokio::test]
+    async fn test_observers_receive_replayed_sentences() {
+        let mut monitor = GpsMonitor::new();
+
+        let sentences = Arc::new(Mutex::new(Vec::new()));
+        let sentences_clone = Arc::clone(&sentences);
+        monitor.set_sentence_observer(move |line| {
+            sentences_clone.lock().unwrap().push(line.to_string());
+        });
+
+        let fixes = Arc::new(Mutex::new(Vec::new()));
+        let fixes_clone = Arc::clone(&fixes);
+        monitor.add_update_observer(move |data| {
+            fixes_clone.lock().unwrap().push(data.has_fix());
+        });
+
+        let replay = "$GPRMC,123519,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W*6A\r\n\
+                      \r\n\
+                      $GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47\r\n";
+        let limits = ReadLimits { timeout: None, max_line_length: DEFAULT_MAX_LINE_LENGTH };
+        monitor.read_lines(BufReader::new(replay.as_bytes()), "replay", limits, |data, line| {
+            nmea::parse_nmea_sentence(data, line);
+        }).await;
+
+        let sentences = sentences.lock().unwrap();
+        assert_eq!(sentences.len(), 2);
+        assert!(sentences[0].starts_with("$GPRMC"));
+        assert!(sentences[1].starts_with("$GPGGA"));
+        assert_eq!(*fixes.lock().unwrap(), vec![false, true]);
+        assert!(monitor.get_data().has_fix());
+        assert_eq!(monitor.sentence_counts().total(), 2);
+
+        // The replay ending is reported as a lost connection
+        assert_eq!(monitor.get_data().connection, ConnectionStatus::Lost("replay closed".to_string()));
+    }
+
+    #[test]
+    fn test_ingest_sentence() {
+        let mut monitor = GpsMonitor::new();
+        let updates = Arc::new(Mutex::new(0));
+        let updates_clone = Arc::clone(&updates);
+        monitor.add_update_observer(move |_| *updates_clone.lock().unwrap() += 1);
+        // A second observer doesn't replace the first
+        let fixes = Arc::new(Mutex::new(0));
+        let fixes_clone = Arc::clone(&fixes);
+        monitor.add_update_observer(move |data| *fixes_clone.lock().unwrap() += usize::from(data.has_fix()));
+
+        for line in [
+            "$GPGSV,1,1,02,05,45,090,40,12,30,270,35*71\r\n",
+            "",
+            "$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47",
+            "  $GPRMC,123519,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W*6A  ",
+        ] {
+            monitor.ingest_sentence(line);
+        }
+
+        let data = monitor.get_data();
+        assert!(data.has_fix());
+        assert!((data.latitude.unwrap() - 48.1173).abs() < 1e-6);
+        assert_eq!(data.altitude, Some(545.4));
+        assert_eq!(data.satellites, Some(8));
+        assert!(data.speed.is_some_and(|speed| (speed - 22.4 * 1.852).abs() < 1e-6));
+        assert_eq!(data.satellites_info.len(), 2);
+        assert_eq!(data.source.as_deref(), Some(INGESTED_SOURCE));
+        assert!(data.raw_data.starts_with("$GPRMC"));
+        assert_eq!(monitor.sentence_counts().total(), 3);
+        assert_eq!(*updates.lock().unwrap(), 3);
+        assert_eq!(*fixes.lock().unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_mixed_line_endings() {
+        let monitor = GpsMonitor::new();
+        let replay = "\0\0$GPGSV,1,1,02,05,45,090,40,12,30,270,35*71\r\n\
+                      $GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47\n\
+                      \u{fffd}$GPRMC,123519,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W*6A\r\
+                      $GPGSA,A,3,05,12,,,,,,,,,,,2.5,1.3,2.1*3F\r";
+        let limits = ReadLimits { timeout: None, max_line_length: DEFAULT_MAX_LINE_LENGTH };
+        monitor.read_lines(BufReader::new(replay.as_bytes()), "replay", limits, |data, line| {
+            nmea::parse_nmea_sentence(data, line);
+        }).await;
+
+        let counts = monitor.sentence_counts();
+        for kind in ["GPGSV", "GPGGA", "GPRMC", "GPGSA"] {
+            assert_eq!(counts.count(kind), 1, "{}", kind);
+        }
+        assert_eq!(counts.total(), 4);
+
+        let data = monitor.get_data();
+        assert_eq!(data.satellites_info.len(), 2);
+        assert!(data.speed.is_some());
+        assert_eq!(data.mode, Some(3));
+        assert!(data.raw_history.iter().all(|line| line.starts_with('$')));
+    }
+
+    #[test]
+    fn test_nmea_used_satellites() {
+        let monitor = GpsMonitor::new();
+        for line in [
+            "$GPGGA,123519,4807.038,N,01131.000,E,1,06,0.9,545.4,M,46.9,M,,*49",
+            "$GNGSA,A,3,05,12,15,24,29,,,,,,,,1.8,0.9,1.5,1*3A",
+            "$GNGSA,A,3,65,,,,,,,,,,,,1.8,0.9,1.5,2*35",
+            "$GPGSV,2,1,07,05,45,090,40,12,30,270,35,15,60,120,42,18,20,200,30*7A",
+            "$GPGSV,2,2,07,24,50,300,38,25,10,010,20,29,70,040,44*48",
+            "$GLGSV,1,1,02,65,40,100,36,71,25,220,31*67",
+        ] {
+            monitor.ingest_sentence(line);
+        }
+
+        // The GSA lists mark the satellites the GSV sentences report after them
+        let data = monitor.get_data();
+        assert_eq!(data.satellites_info.len(), 9);
+        assert_eq!(data.satellites_used(), 6);
+        assert_eq!(data.used_satellite_count(), Some(6));
+        assert!(!data.low_satellite_count(4));
+        assert!(data.low_satellite_count(7));
+    }
+
+    #[test]
+    fn test_reconnect_clears_satellites() {
+        let monitor = GpsMonitor::new();
+        monitor.ingest_sentence("$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47");
+        monitor.ingest_sentence("$GPGSV,1,1,02,05,45,090,40,12,30,270,35*71");
+        let data = monitor.get_data();
+        assert_eq!(data.satellites_info.len(), 2);
+        assert!(data.satellites_info.iter().all(|sat| sat.source.as_deref() == Some(INGESTED_SOURCE)));
+
+        // A new connection, possibly to another source, starts with an empty sky
+        monitor.data.write_recover().mark_connected();
+        let data = monitor.get_data();
+        assert!(data.satellites_info.is_empty());
+        assert_eq!((data.satellites, data.hdop, data.satellites_updated), (None, None, None));
+        assert_eq!(data.source, None);
+    }
+
+    #[test]
+    fn test_sentence_counts() {
+        let mut counts = SentenceCounts::default();
+        let lines = [
+            "$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47",
+            "$GPGSV,3,1,11,03,03,111,00,04,15,270,00,06,01,010,00,13,06,292,00*74",
+            "$GPGSV,3,2,11,14,25,170,00,16,57,208,39,18,67,296,40,19,40,246,00*74",
+            "$GPRMC,123519,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W*6A",
+            "$GPGGA,123520,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*44",
+            "$GPGSV,3,3,11,22,42,067,42,24,14,311,43,27,05,244,00,,,,*4D",
+            "{\"class\":\"TPV\",\"mode\":3}",
+            "garbage",
+        ];
+        for line in lines {
+            counts.record(line);
+        }
+
+        assert_eq!(counts.count("GPGGA"), 2);
+        assert_eq!(counts.count("GPGSA"), 0);
+        assert_eq!(counts.count("TPV"), 1);
+        assert_eq!(counts.total(), lines.len() as u64);
+        assert_eq!(counts.sorted()[..2], [("GPGSV".to_string(), 3), ("GPGGA".to_string(), 2)]);
+        assert_eq!(counts.rate(), None);
+    }
+
+    #[tokio::test]
+    async fn test_builder_streams_updates() {
+        use tokio::io::AsyncWriteExt;
+
+        // A stand-in gpsd that sends one fix
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            socket.write_all(b"{\"class\":\"TPV\",\"mode\":3,\"lat\":42.5,\"lon\":-71.1}\n").await.unwrap();
+        });
+
+        let handle = GpsMonitor::builder(GpsSource::Gpsd { host: "127.0.0.1".to_string(), port })
+            .spawn()
+            .await
+            .unwrap();
+        let mut updates = handle.updates();
+        let data = tokio::time::timeout(Duration::from_secs(5), updates.wait_for(|data| data.has_fix()))
+            .await
+            .unwrap()
+            .unwrap()
+            .clone();
+        assert_eq!((data.latitude, data.longitude), (Some(42.5), Some(-71.1)));
+        assert_eq!(handle.sentence_counts().count("TPV"), 1);
+
+        handle.stop();
+        assert!(!handle.is_running());
+    }
+
+    #[test]
+    fn test_source_policy_selection() {
+        let fix = |hdop: f64, used: u8, age_secs: i64| {
+            let mut data = GpsData::new();
+            data.latitude = Some(42.0);
+            data.longitude = Some(-71.0);
+            data.fix_quality = Some(1);
+            data.hdop = Some(hdop);
+            data.satellites = Some(used);
+            data.timestamp = Some(Utc::now() - chrono::Duration::seconds(age_secs));
+            data
+        };
+        let serial = fix(0.8, 6, 2);
+        let network = fix(2.5, 9, 0);
+        let sources = [&serial, &network];
+
+        assert_eq!(SourcePolicy::LowestHdop.select(&sources), Some(0));
+        assert_eq!(SourcePolicy::MostSatellites.select(&sources), Some(1));
+        assert_eq!(SourcePolicy::Freshest.select(&sources), Some(1));
+
+        // Ties go to the fresher source
+        let same_hdop = fix(0.8, 9, 0);
+        assert_eq!(SourcePolicy::LowestHdop.select(&[&serial, &same_hdop]), Some(1));
+
+        // A source without a valid fix, or gone quiet, is passed over
+        let mut lost = fix(0.5, 12, 0);
+        lost.fix_quality = Some(0);
+        let stale = fix(0.5, 12, 60);
+        assert_eq!(SourcePolicy::LowestHdop.select(&[&lost, &network]), Some(1));
+        assert_eq!(SourcePolicy::MostSatellites.select(&[&stale, &network]), Some(1));
+
+        // With no usable fix anywhere the freshest data is shown
+        assert_eq!(SourcePolicy::LowestHdop.select(&[&stale, &lost]), Some(1));
+        assert_eq!(SourcePolicy::LowestHdop.select(&[&GpsData::new()]), None);
+    }
+
+    #[tokio::test]
+    async fn test_multi_source_shows_selected_source() {
+        use tokio::io::AsyncWriteExt;
+
+        // Two stand-in gpsds, the second with the better HDOP
+        let mut sources = Vec::new();
+        for (lat, hdop) in [(42.5, 2.5), (43.0, 0.8)] {
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let port = listener.local_addr().unwrap().port();
+            tokio::spawn(async move {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let lines = format!(
+                    "{{\"class\":\"SKY\",\"hdop\":{}}}\n{{\"class\":\"TPV\",\"mode\":3,\"lat\":{},\"lon\":-71.1}}\n",
+                    hdop, lat
+                );
+                socket.write_all(lines.as_bytes()).await.unwrap();
+            });
+            sources.push(GpsSource::Gpsd { host: "127.0.0.1".to_string(), port });
+        }
+        // A source that fails to connect doesn't stop the others
+        let unused = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        sources.push(GpsSource::Gpsd { host: "127.0.0.1".to_string(), port: unused });
+
+        let handle = GpsMonitor::builder(GpsSource::Multi { sources, policy: SourcePolicy::LowestHdop })
+            .spawn()
+            .await
+            .unwrap();
+        let mut updates = handle.updates();
+        let data = tokio::time::timeout(Duration::from_secs(5), updates.wait_for(|data| data.latitude == Some(43.0)))
+            .await
+            .unwrap()
+            .unwrap()
+            .clone();
+        assert_eq!(data.hdop, Some(0.8));
+        assert_eq!(handle.sentence_counts().count("TPV"), 2);
+        handle.stop();
+
+        let nested = GpsSource::Multi {
+            sources: vec![GpsSource::Multi { sources: Vec::new(), policy: SourcePolicy::Freshest }],
+            policy: SourcePolicy::Freshest,
+        };
+        assert!(GpsMonitor::new().start(nested).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_multi_source_fusion_ends_with_its_sources() {
+        use tokio::io::AsyncWriteExt;
+
+        // A stand-in gpsd that sends one fix and hangs up
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            socket.write_all(b"{\"class\":\"TPV\",\"mode\":3,\"lat\":42.5,\"lon\":-71.1}\n").await.unwrap();
+        });
+        let sources = vec![GpsSource::Gpsd { host: "127.0.0.1".to_string(), port }];
+
+        let monitor = GpsMonitor::new();
+        monitor.start(GpsSource::Multi { sources, policy: SourcePolicy::Freshest }).await.unwrap();
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while monitor.get_data().latitude != Some(42.5) {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+
+        // With the source gone and the running flag still set, e.g. for the
+        // next connection, the old buffer is no longer copied in
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        monitor.data.write_recover().latitude = None;
+        tokio::time::sleep(FUSION_INTERVAL * 2).await;
+        assert!(monitor.is_running());
+        assert_eq!(monitor.get_data().latitude, None);
+    }
+
+    #[tokio::test]
+    async fn test_overlong_line_is_discarded() {
+        let mut monitor = GpsMonitor::new();
+
+        let sentences = Arc::new(Mutex::new(Vec::new()));
+        let sentences_clone = Arc::clone(&sentences);
+        monitor.set_sentence_observer(move |line| {
+            sentences_clone.lock().unwrap().push(line.to_string());
+        });
+
+        // Wrong-baud garbage with no newline for far longer than the limit,
+        // followed by a good sentence
+        let mut replay = vec![0xA5u8; 5000];
+        replay.extend_from_slice(b"\r\n$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47\r\n");
+
+        // A tiny BufReader capacity forces the line to span many reads
+        let limits = ReadLimits { timeout: None, max_line_length: 128 };
+        monitor.read_lines(BufReader::with_capacity(16, replay.as_slice()), "replay", limits, |data, line| {
+            nmea::parse_nmea_sentence(data, line);
+        }).await;
+
+        let sentences = sentences.lock().unwrap();
+        assert_eq!(sentences.len(), 1);
+        assert!(sentences[0].starts_with("$GPGGA"));
+        assert!(monitor.get_data().has_fix());
+    }
+
+    #[tokio::test]
+    async fn test_line_reader_limits() {
+        let input = b"short\n0123456789abcdef\nok\nlast";
+        let mut reader = LineReader::new(BufReader::with_capacity(4, &input[..]), 8);
+
+        assert_eq!(reader.next_line().await.unwrap(), LineRead::Line);
+        assert_eq!(reader.take_line(), "short\n");
+        assert_eq!(reader.next_line().await.unwrap(), LineRead::TooLong);
+        assert_eq!(reader.next_line().await.unwrap(), LineRead::Line);
+        assert_eq!(reader.take_line(), "ok\n");
+        assert_eq!(reader.next_line().await.unwrap(), LineRead::Line);
+        assert_eq!(reader.take_line(), "last");
+        assert_eq!(reader.next_line().await.unwrap(), LineRead::Eof);
+    }
+
+    #[test]
+    fn test_count_valid_sentences() {
+        // What a 9600 baud receiver looks like when read at 4800
+        let garbage = [0xE6u8, 0x18, 0x9E, 0x00, 0x78, 0x24, 0xF8, 0x86, 0x0A, 0x2A, 0xE0, 0x1E];
+        assert_eq!(count_valid_sentences(&garbage), 0);
+
+        let mut mixed = garbage.to_vec();
+        mixed.extend_from_slice(b"\r\n$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47\r\n");
+        mixed.extend_from_slice(b"$GPRMC,123519,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W*6A\r\n");
+        mixed.extend_from_slice(b"$GPGGA,123520,4807.0");  // Cut off mid-sentence
+        assert_eq!(count_valid_sentences(&mixed), 2);
+    }
+
+    #[tokio::test]
+    async fn test_time_to_first_fix() {
+        let monitor = GpsMonitor::new();
+        monitor.data.write_recover().mark_connected();
+
+        let replay = "$GPGGA,123519,4807.038,N,01131.000,E,0,00,,,M,,M,,*4F\r\n\
+                      $GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47\r\n";
+        let limits = ReadLimits { timeout: None, max_line_length: DEFAULT_MAX_LINE_LENGTH };
+        monitor.read_lines(BufReader::new(replay.as_bytes()), "replay", limits, |data, line| {
+            nmea::parse_nmea_sentence(data, line);
+        }).await;
+
+        let data = monitor.get_data();
+        let ttff = data.time_to_first_fix.expect("first fix should be timed");
+        assert!(ttff >= chrono::Duration::zero());
+
+        // Reconnecting starts a fresh measurement
+        monitor.data.write_recover().mark_connected();
+        assert!(monitor.get_data().time_to_first_fix.is_none());
+    }
+}
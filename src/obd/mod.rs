@@ -0,0 +1,129 @@
+// src/obd/mod.rs v2
+//! OBD-II vehicle data via an ELM327-compatible adapter (feature "obd")
+
+mod reader;
+
+pub use reader::ObdReader;
+
+use crate::waypoint::TrackPoint;
+use chrono::{DateTime, Utc};
+
+/// Latest readings older than this are not merged into track points
+const MAX_AGE_SECS: i64 = 5;
+
+/// Mode 01 PIDs polled from the vehicle
+pub const POLLED_PIDS: [u8; 5] = [PID_ENGINE_LOAD, PID_COOLANT_TEMP, PID_RPM, PID_SPEED, PID_THROTTLE];
+
+const PID_ENGINE_LOAD: u8 = 0x04;
+const PID_COOLANT_TEMP: u8 = 0x05;
+const PID_RPM: u8 = 0x0C;
+const PID_SPEED: u8 = 0x0D;
+const PID_THROTTLE: u8 = 0x11;
+
+/// Most recent value of each polled PID
+#[derive(Debug, Clone, Default)]
+pub struct ObdData {
+    pub speed: Option<f64>,         // km/h
+    pub rpm: Option<u16>,
+    pub throttle: Option<f32>,      // %
+    pub load: Option<f32>,          // %
+    pub coolant_temp: Option<i16>,  // °C
+    pub updated: Option<DateTime<Utc>>,
+    pub error: Option<String>,      // Why the adapter was lost, until someone takes it
+}
+
+impl ObdData {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether a PID response has arrived recently
+    pub fn is_recent(&self) -> bool {
+        self.updated.is_some_and(|t| (Utc::now() - t).num_seconds() < MAX_AGE_SECS)
+    }
+
+    /// Decode one adapter response line such as "41 0D 3C" and store the
+    /// value. Returns false for anything that isn't a known mode 01 reply.
+    pub fn apply_response(&mut self, line: &str) -> bool {
+        let bytes: Option<Vec<u8>> = line
+            .split_whitespace()
+            .flat_map(|token| {
+                // Adapters with spaces off ("ATS0") send "410D3C"
+                (0..token.len()).step_by(2).map(move |i| token.get(i..i + 2))
+            })
+            .map(|hex| hex.and_then(|h| u8::from_str_radix(h, 16).ok()))
+            .collect();
+
+        let Some(bytes) = bytes else {
+            return false;
+        };
+        let [0x41, pid, a, rest @ ..] = bytes.as_slice() else {
+            return false;
+        };
+        let (a, b) = (*a as f32, rest.first().copied());
+
+        match (*pid, b) {
+            (PID_ENGINE_LOAD, _) => self.load = Some(a * 100.0 / 255.0),
+            (PID_COOLANT_TEMP, _) => self.coolant_temp = Some(a as i16 - 40),
+            (PID_RPM, Some(b)) => self.rpm = Some(((a as u16) * 256 + b as u16) / 4),
+            (PID_SPEED, _) => self.speed = Some(a as f64),
+            (PID_THROTTLE, _) => self.throttle = Some(a * 100.0 / 255.0),
+            _ => return false,
+        }
+
+        self.updated = Some(Utc::now());
+        true
+    }
+
+    /// Copy recent readings into a track point's OBD fields
+    pub fn apply_to(&self, point: &mut TrackPoint) {
+        if !self.is_recent() {
+            return;
+        }
+        point.obd_speed = self.speed;
+        point.obd_rpm = self.rpm;
+        point.obd_throttle = self.throttle;
+        point.obd_load = self.load;
+        point.obd_temp = self.coolant_temp;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_pid_responses() {
+        let mut data = ObdData::new();
+        assert!(data.apply_response("41 0D 3C"));
+        assert!(data.apply_response("41 0C 1A F8"));
+        assert!(data.apply_response("410511"));
+        assert!(data.apply_response("41 04 FF"));
+        assert!(data.apply_response("41 11 80"));
+
+        assert_eq!(data.speed, Some(60.0));
+        assert_eq!(data.rpm, Some(1726));
+        assert_eq!(data.coolant_temp, Some(-23));
+        assert_eq!(data.load, Some(100.0));
+        assert!((data.throttle.unwrap() - 50.2).abs() < 0.1);
+
+        assert!(!data.apply_response("NO DATA"));
+        assert!(!data.apply_response("7F 01 12"));
+        assert!(!data.apply_response("41 0C 1A")); // RPM needs two data bytes
+    }
+
+    #[test]
+    fn test_apply_to_track_point_only_when_recent() {
+        let mut data = ObdData::new();
+        data.apply_response("41 0D 3C");
+
+        let mut point = TrackPoint::new(42.0, -71.0, None, Utc::now());
+        data.apply_to(&mut point);
+        assert_eq!(point.obd_speed, Some(60.0));
+
+        let mut stale_point = TrackPoint::new(42.0, -71.0, None, Utc::now());
+        data.updated = Some(Utc::now() - chrono::Duration::seconds(MAX_AGE_SECS + 1));
+        data.apply_to(&mut stale_point);
+        assert_eq!(stale_point.obd_speed, None);
+    }
+}
@@ -0,0 +1,152 @@
+// src/obd/reader.rs v2
+//! ELM327 serial polling loop
+
+use super::{ObdData, POLLED_PIDS};
+use crate::{error::{GpsError, Result}, lock::RecoverPoison};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, RwLock,
+    },
+    time::Duration,
+};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio_serial::SerialPortBuilderExt;
+
+/// How long to wait for the adapter's ">" prompt
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Adapter setup: reset, echo off, linefeeds off, automatic protocol
+const INIT_COMMANDS: [&str; 4] = ["ATZ", "ATE0", "ATL0", "ATSP0"];
+
+/// Wait before the first reconnect, doubled after each failure up to `MAX_RECONNECT_DELAY`
+const MIN_RECONNECT_DELAY: Duration = Duration::from_secs(2);
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(60);
+
+/// Polls an ELM327-compatible adapter and keeps the shared `ObdData` current
+pub struct ObdReader {
+    port: String,
+    baudrate: u32,
+    poll_interval: Duration,
+}
+
+impl ObdReader {
+    pub fn new(port: String, baudrate: u32, poll_interval: Duration) -> Self {
+        Self { port, baudrate, poll_interval }
+    }
+
+    /// Poll until `running` is cleared, connecting again with a growing delay
+    /// whenever the adapter can't be opened or stops answering. Each failure
+    /// is left in `data.error`.
+    pub async fn run(&self, data: Arc<RwLock<ObdData>>, running: Arc<AtomicBool>) {
+        let mut delay = MIN_RECONNECT_DELAY;
+        while running.load(Ordering::Relaxed) {
+            // GpsError isn't Send, so only its message is kept across an await
+            let result = match self.connect().await.map_err(|e| e.to_string()) {
+                Ok(mut serial) => {
+                    delay = MIN_RECONNECT_DELAY;
+                    poll(&mut serial, &data, &running, self.poll_interval).await.map_err(|e| e.to_string())
+                }
+                Err(e) => Err(e),
+            };
+            if let Err(e) = result {
+                data.write_recover().error = Some(e);
+                tokio::time::sleep(delay).await;
+                delay = (delay * 2).min(MAX_RECONNECT_DELAY);
+            }
+        }
+    }
+
+    /// Open the port and set the adapter up
+    async fn connect(&self) -> Result<tokio_serial::SerialStream> {
+        let mut serial = tokio_serial::new(&self.port, self.baudrate)
+            .open_native_async()
+            .map_err(|e| GpsError::Connection(format!("Failed to open OBD-II port {}: {}", self.port, e)))?;
+
+        for command in INIT_COMMANDS {
+            send_command(&mut serial, command).await?;
+        }
+        Ok(serial)
+    }
+}
+
+/// Query each polled PID in turn until `running` is cleared or the adapter fails
+async fn poll<S: AsyncRead + AsyncWrite + Unpin>(
+    serial: &mut S,
+    data: &RwLock<ObdData>,
+    running: &AtomicBool,
+    interval: Duration,
+) -> Result<()> {
+    while running.load(Ordering::Relaxed) {
+        for pid in POLLED_PIDS {
+            let response = send_command(serial, &format!("01{:02X}", pid)).await?;
+            let mut data = data.write_recover();
+            for line in response.lines() {
+                data.apply_response(line);
+            }
+        }
+        tokio::time::sleep(interval).await;
+    }
+    Ok(())
+}
+
+/// Send a command and collect the response up to the ">" prompt
+async fn send_command<S: AsyncRead + AsyncWrite + Unpin>(serial: &mut S, command: &str) -> Result<String> {
+    serial.write_all(format!("{}\r", command).as_bytes()).await?;
+
+    let mut response = Vec::new();
+    let mut buf = [0u8; 64];
+    loop {
+        let n = tokio::time::timeout(RESPONSE_TIMEOUT, serial.read(&mut buf))
+            .await
+            .map_err(|_| GpsError::Connection(format!("OBD-II adapter did not answer {}", command)))??;
+        if n == 0 {
+            return Err(GpsError::Connection("OBD-II adapter closed the connection".to_string()));
+        }
+        response.extend_from_slice(&buf[..n]);
+        if response.contains(&b'>') {
+            break;
+        }
+    }
+
+    Ok(String::from_utf8_lossy(&response)
+        .replace('>', "")
+        .replace('\r', "\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_send_command_reads_to_prompt() {
+        let (mut client, mut adapter) = tokio::io::duplex(64);
+
+        let adapter_task = tokio::spawn(async move {
+            let mut buf = [0u8; 16];
+            let n = adapter.read(&mut buf).await.unwrap();
+            assert_eq!(&buf[..n], b"010D\r");
+            adapter.write_all(b"41 0D 3C\r\r>").await.unwrap();
+        });
+
+        let response = send_command(&mut client, "010D").await.unwrap();
+        adapter_task.await.unwrap();
+
+        let mut data = ObdData::new();
+        assert!(response.lines().any(|line| data.apply_response(line)));
+        assert_eq!(data.speed, Some(60.0));
+    }
+
+    #[tokio::test]
+    async fn test_poll_ends_when_adapter_closes() {
+        let (mut client, adapter) = tokio::io::duplex(64);
+        drop(adapter);
+
+        let data = RwLock::new(ObdData::new());
+        let running = AtomicBool::new(true);
+        assert!(poll(&mut client, &data, &running, Duration::ZERO).await.is_err());
+
+        running.store(false, Ordering::Relaxed);
+        assert!(poll(&mut client, &data, &running, Duration::ZERO).await.is_ok());
+    }
+}
@@ -0,0 +1,275 @@
+// src/odometer.rs v3
+//! Lifetime distance accumulation from live GPS updates
+
+use crate::{
+    error::{GpsError, Result},
+    gps::{DataField, GpsData},
+    lock::RecoverPoison,
+    monitor::GpsMonitor,
+    waypoint::TrackPoint,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+};
+
+/// Ignore movement smaller than this so a stationary receiver's jitter doesn't add up
+const MIN_STEP_METERS: f64 = 5.0;
+
+/// Persist the lifetime total after this much new distance
+pub const SAVE_INTERVAL_M: f64 = 100.0;
+
+/// What goes in the odometer file. It is kept apart from the settings, so a
+/// profile switch or a settings import can't roll the total back.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct OdometerState {
+    lifetime_distance_m: f64,
+}
+
+pub struct Odometer {
+    last_point: Option<TrackPoint>,
+    last_fix: Option<DateTime<Utc>>,  // Position update time of the last fix seen
+    lifetime_m: f64,
+    unsaved_m: f64,
+}
+
+impl Odometer {
+    pub fn new() -> Self {
+        Self { last_point: None, last_fix: None, lifetime_m: 0.0, unsaved_m: 0.0 }
+    }
+
+    /// Default location in the user's local data directory
+    pub fn default_path() -> PathBuf {
+        let mut path = dirs::data_local_dir().unwrap_or_else(|| PathBuf::from("."));
+        path.push("gps-monitor");
+        path.push("odometer.json");
+        path
+    }
+
+    /// Odometer starting from the total saved at `path`, or from zero if
+    /// there is no file yet
+    pub fn load(path: &Path) -> Result<Self> {
+        let mut odometer = Self::new();
+        if path.exists() {
+            let contents = std::fs::read_to_string(path)?;
+            let state: OdometerState = serde_json::from_str(&contents)
+                .map_err(|e| GpsError::Other(format!("Invalid odometer file: {}", e)))?;
+            odometer.lifetime_m = state.lifetime_distance_m.max(0.0);
+        }
+        Ok(odometer)
+    }
+
+    /// Write the lifetime total to `path`, replacing the old file in a single rename
+    pub fn save(&mut self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let state = OdometerState { lifetime_distance_m: self.lifetime_m };
+        let temp_path = path.with_extension("json.tmp");
+        std::fs::write(&temp_path, serde_json::to_string(&state)?)?;
+        std::fs::rename(&temp_path, path)?;
+        self.unsaved_m = 0.0;
+        Ok(())
+    }
+
+    /// Count every fix `monitor` decodes into the shared `odometer`
+    pub fn attach(odometer: &Arc<RwLock<Odometer>>, monitor: &mut GpsMonitor) {
+        let odometer = Arc::clone(odometer);
+        monitor.add_update_observer(move |data| {
+            odometer.write_recover().update(data);
+        });
+    }
+
+    /// Total distance in meters across all sessions
+    pub fn lifetime_m(&self) -> f64 {
+        self.lifetime_m
+    }
+
+    /// Whether at least `SAVE_INTERVAL_M` was added since the last save
+    pub fn save_due(&self) -> bool {
+        self.unsaved_m >= SAVE_INTERVAL_M
+    }
+
+    /// Whether any distance was added since the last save
+    pub fn has_unsaved(&self) -> bool {
+        self.unsaved_m > 0.0
+    }
+
+    /// Start the lifetime total over from zero
+    pub fn reset_lifetime(&mut self) {
+        self.lifetime_m = 0.0;
+        self.unsaved_m = 0.0;
+    }
+
+    /// Feed the latest GPS data and return the distance travelled since the
+    /// last accepted point, in meters, adding it to the lifetime total.
+    /// Only a new fix counts, so each one is one step however often the data
+    /// is fed. Jumps are dropped and restart the count from the new position.
+    pub fn update(&mut self, gps_data: &GpsData) -> f64 {
+        let fix_time = gps_data.field_updated(DataField::Position);
+        if fix_time.is_none() || fix_time == self.last_fix {
+            return 0.0;
+        }
+        self.last_fix = fix_time;
+
+        let distance = self.step(gps_data);
+        self.lifetime_m += distance;
+        self.unsaved_m += distance;
+        distance
+    }
+
+    /// Distance from the last accepted point to the fix in `gps_data`
+    fn step(&mut self, gps_data: &GpsData) -> f64 {
+        if !gps_data.has_valid_fix() {
+            return 0.0;
+        }
+
+        let Some(point) = TrackPoint::from_gps_data(gps_data) else {
+            return 0.0;
+        };
+
+        let Some(last) = &self.last_point else {
+            self.last_point = Some(point);
+            return 0.0;
+        };
+
+        let distance = last.distance_to(&point);
+        if distance < MIN_STEP_METERS {
+            return 0.0;
+        }
+
+        let accepted = if last.is_plausible_step(&point) { distance } else { 0.0 };
+        self.last_point = Some(point);
+        accepted
+    }
+
+    /// Forget the last position, e.g. after a disconnect
+    pub fn reset_position(&mut self) {
+        self.last_point = None;
+    }
+}
+
+impl Default for Odometer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Path distance travelled since a waypoint was marked as the reference,
+/// kept as the lifetime odometer reading at that moment
+pub struct ReferenceDistance {
+    pub name: String,
+    start_m: f64,
+}
+
+impl ReferenceDistance {
+    pub fn new(name: String, odometer_m: f64) -> Self {
+        Self { name, start_m: odometer_m }
+    }
+
+    /// Meters travelled along the path since the reference was set
+    pub fn distance(&self, odometer_m: f64) -> f64 {
+        (odometer_m - self.start_m).max(0.0)
+    }
+
+    /// Keep the distance so far when the lifetime odometer is reset from `odometer_m` to zero
+    pub fn odometer_reset(&mut self, odometer_m: f64) {
+        self.start_m -= odometer_m;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+
+    fn gps_at(lat: f64, lon: f64, secs: i64) -> GpsData {
+        let mut data = GpsData::new();
+        data.latitude = Some(lat);
+        data.longitude = Some(lon);
+        data.fix_quality = Some(1);
+        data.timestamp = Some(Utc::now() + Duration::seconds(secs));
+        data.position_updated = data.timestamp;
+        data
+    }
+
+    #[test]
+    fn test_odometer_ignores_jitter_and_jumps() {
+        let mut odometer = Odometer::new();
+        assert_eq!(odometer.update(&gps_at(42.0, -71.0, 0)), 0.0);
+
+        // ~1 m of jitter
+        assert_eq!(odometer.update(&gps_at(42.00001, -71.0, 1)), 0.0);
+
+        // ~55 m of real movement
+        let step = odometer.update(&gps_at(42.0005, -71.0, 2));
+        assert!((step - 55.6).abs() < 1.0);
+
+        // ~11 km in a second is a jump
+        assert_eq!(odometer.update(&gps_at(42.1, -71.0, 3)), 0.0);
+
+        // Counting resumes from the new position
+        assert!(odometer.update(&gps_at(42.1005, -71.0, 4)) > 50.0);
+    }
+
+    #[test]
+    fn test_one_step_per_fix() {
+        let mut odometer = Odometer::new();
+        odometer.update(&gps_at(42.0, -71.0, 0));
+        let fix = gps_at(42.0005, -71.0, 1);
+        let step = odometer.update(&fix);
+        assert!(step > 50.0);
+
+        // The same fix seen again, e.g. after another sentence of its epoch
+        assert_eq!(odometer.update(&fix), 0.0);
+        assert_eq!(odometer.lifetime_m(), step);
+    }
+
+    #[test]
+    fn test_lifetime_total_round_trip() {
+        let dir = std::env::temp_dir().join(format!("gps-monitor-odometer-{}", std::process::id()));
+        let path = dir.join("odometer.json");
+        let _ = std::fs::remove_dir_all(&dir);
+        assert_eq!(Odometer::load(&path).unwrap().lifetime_m(), 0.0);
+
+        let mut odometer = Odometer::new();
+        for (i, lat) in [42.0, 42.0005, 42.001, 42.0015].into_iter().enumerate() {
+            odometer.update(&gps_at(lat, -71.0, i as i64));
+        }
+        assert!(odometer.save_due());
+        odometer.save(&path).unwrap();
+        assert!(!odometer.has_unsaved());
+
+        let loaded = Odometer::load(&path).unwrap();
+        assert_eq!(loaded.lifetime_m(), odometer.lifetime_m());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_distance_since_reference_follows_the_path() {
+        let mut odometer = Odometer::new();
+        let mut lifetime = 1000.0;
+        odometer.update(&gps_at(42.0, -71.0, 0));
+
+        let mut reference = ReferenceDistance::new("Bridge".to_string(), lifetime);
+        assert_eq!(reference.distance(lifetime), 0.0);
+
+        // North ~55 m, east ~41 m, then back south ~55 m: the path, not the
+        // ~41 m straight line back to the start
+        for (i, (lat, lon)) in [(42.0005, -71.0), (42.0005, -70.9995), (42.0, -70.9995)].into_iter().enumerate() {
+            lifetime += odometer.update(&gps_at(lat, lon, i as i64 + 1));
+        }
+        let travelled = reference.distance(lifetime);
+        assert!((travelled - 152.5).abs() < 1.0, "travelled {}", travelled);
+
+        // Resetting the lifetime total keeps the distance since the reference
+        reference.odometer_reset(lifetime);
+        lifetime = 0.0;
+        assert!((reference.distance(lifetime) - travelled).abs() < 1e-9);
+        lifetime += odometer.update(&gps_at(41.9995, -70.9995, 5));
+        assert!((reference.distance(lifetime) - travelled - 55.6).abs() < 1.0);
+    }
+}
@@ -0,0 +1,196 @@
+// src/status_server.rs v1
+//! Small HTTP endpoint for checking on a headless logger remotely:
+//! `GET /status` returns the latest `GpsData` and a few diagnostics as JSON.
+//! Built with the "status-server" feature.
+
+use crate::{
+    error::{GpsError, Result},
+    gps::{FixValidity, GpsData},
+    lock::RecoverPoison,
+};
+use chrono::{DateTime, Utc};
+use hyper::{
+    header::CONTENT_TYPE,
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, Server, StatusCode,
+};
+use serde::Serialize;
+use std::{
+    convert::Infallible,
+    net::{SocketAddr, TcpListener},
+    sync::{Arc, RwLock},
+};
+
+/// Health of the source at a glance, alongside the full data
+#[derive(Debug, Serialize)]
+pub struct Diagnostics {
+    pub connection: String,
+    pub has_valid_fix: bool,
+    pub fix_validity: FixValidity,
+    pub fix_age_secs: Option<i64>,
+    pub update_rate_hz: Option<f64>,
+    pub satellites_used: usize,
+    pub satellites_in_view: usize,
+    pub signal_health: u8,  // 0-100
+    pub server_time: DateTime<Utc>,
+}
+
+/// Body of `GET /status`
+#[derive(Debug, Serialize)]
+pub struct StatusReport<'a> {
+    pub data: &'a GpsData,
+    pub diagnostics: Diagnostics,
+}
+
+impl<'a> StatusReport<'a> {
+    pub fn new(data: &'a GpsData, now: DateTime<Utc>) -> Self {
+        let diagnostics = Diagnostics {
+            connection: data.connection.description(),
+            has_valid_fix: data.has_valid_fix(),
+            fix_validity: data.fix_validity(),
+            fix_age_secs: data.age_seconds(),
+            update_rate_hz: data.update_rate(),
+            satellites_used: data.satellites_used(),
+            satellites_in_view: data.satellites_info.len(),
+            signal_health: data.signal_health().0,
+            server_time: now,
+        };
+        Self { data, diagnostics }
+    }
+}
+
+/// HTTP server for the status endpoint, bound but not yet serving
+pub struct StatusServer {
+    listener: TcpListener,
+    data: Arc<RwLock<GpsData>>,
+}
+
+impl StatusServer {
+    /// Bind `address` ("ip:port") straight away, so a port in use is
+    /// reported before anything is spawned
+    pub fn bind(address: &str, data: Arc<RwLock<GpsData>>) -> Result<Self> {
+        let listener = TcpListener::bind(address.trim())
+            .map_err(|e| GpsError::Connection(format!("Failed to bind status endpoint to {}: {}", address, e)))?;
+        listener.set_nonblocking(true)?;
+        Ok(Self { listener, data })
+    }
+
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        Ok(self.listener.local_addr()?)
+    }
+
+    /// Serve requests until the task running this is dropped or aborted.
+    /// Must be awaited on a Tokio runtime; errors are printed.
+    pub async fn run(self) {
+        let data = self.data;
+        let make_service = make_service_fn(move |_| {
+            let data = Arc::clone(&data);
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request| {
+                    let response = respond(&request, &data);
+                    async move { Ok::<_, Infallible>(response) }
+                }))
+            }
+        });
+
+        let result = match Server::from_tcp(self.listener) {
+            Ok(server) => server.serve(make_service).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            eprintln!("Status endpoint stopped: {}", e);
+        }
+    }
+}
+
+fn respond(request: &Request<Body>, data: &RwLock<GpsData>) -> Response<Body> {
+    match (request.method(), request.uri().path()) {
+        (&Method::GET, "/status") => {
+            let json = {
+                let data = data.read_recover();
+                serde_json::to_string(&StatusReport::new(&data, Utc::now()))
+            };
+            match json {
+                Ok(json) => reply(StatusCode::OK, "application/json", json),
+                Err(e) => reply(StatusCode::INTERNAL_SERVER_ERROR, "text/plain", e.to_string()),
+            }
+        }
+        (_, "/status") => reply(StatusCode::METHOD_NOT_ALLOWED, "text/plain", "Use GET\n".to_string()),
+        _ => reply(StatusCode::NOT_FOUND, "text/plain", "Try GET /status\n".to_string()),
+    }
+}
+
+fn reply(status: StatusCode, content_type: &str, body: String) -> Response<Body> {
+    let mut response = Response::new(Body::from(body));
+    *response.status_mut() = status;
+    if let Ok(value) = content_type.parse() {
+        response.headers_mut().insert(CONTENT_TYPE, value);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gps::{data::SatelliteInfo, ConnectionStatus};
+    use serde_json::Value;
+
+    #[tokio::test]
+    async fn test_status_endpoint_schema() {
+        let mut data = GpsData::new();
+        data.latitude = Some(42.4389);
+        data.longitude = Some(-71.1193);
+        data.altitude = Some(31.5);
+        data.fix_quality = Some(1);
+        data.connection = ConnectionStatus::Connected;
+        data.time_to_first_fix = Some(chrono::Duration::milliseconds(12_500));
+        let mut satellite = SatelliteInfo::new(7);
+        satellite.used = true;
+        data.satellites_info.push(satellite);
+        data.update_timestamp();
+        let data = Arc::new(RwLock::new(data));
+
+        let server = StatusServer::bind("127.0.0.1:0", Arc::clone(&data)).unwrap();
+        let addr = server.local_addr().unwrap();
+        let task = tokio::spawn(server.run());
+
+        let response = reqwest::get(format!("http://{}/status", addr)).await.unwrap();
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.headers()["content-type"], "application/json");
+        let json: Value = serde_json::from_str(&response.text().await.unwrap()).unwrap();
+
+        let reported = &json["data"];
+        assert_eq!(reported["latitude"], 42.4389);
+        assert_eq!(reported["longitude"], -71.1193);
+        assert_eq!(reported["altitude"], 31.5);
+        assert_eq!(reported["speed"], Value::Null);
+        assert_eq!(reported["connection"], "connected");
+        assert_eq!(reported["time_to_first_fix"], 12.5);
+        assert!(reported["timestamp"].is_string());
+        assert_eq!(reported["satellites_info"][0]["prn"], 7);
+        assert_eq!(reported["satellites_info"][0]["constellation"], "GPS");
+        assert!(reported.get("fix_rate").is_none());
+
+        let diagnostics = &json["diagnostics"];
+        assert_eq!(diagnostics["connection"], "Connected");
+        assert_eq!(diagnostics["has_valid_fix"], true);
+        assert_eq!(diagnostics["fix_validity"], "unknown");
+        assert_eq!(diagnostics["satellites_used"], 1);
+        assert_eq!(diagnostics["satellites_in_view"], 1);
+        assert!(diagnostics["fix_age_secs"].is_i64());
+        assert!(diagnostics["signal_health"].is_u64());
+        assert!(diagnostics["server_time"].is_string());
+
+        // Updates show up on the next request
+        data.write_recover().latitude = Some(42.5);
+        let text = reqwest::get(format!("http://{}/status", addr)).await.unwrap().text().await.unwrap();
+        assert_eq!(serde_json::from_str::<Value>(&text).unwrap()["data"]["latitude"], 42.5);
+
+        let missing = reqwest::get(format!("http://{}/other", addr)).await.unwrap();
+        assert_eq!(missing.status(), 404);
+        let post = reqwest::Client::new().post(format!("http://{}/status", addr)).send().await.unwrap();
+        assert_eq!(post.status(), 405);
+
+        task.abort();
+    }
+}
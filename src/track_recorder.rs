@@ -0,0 +1,1130 @@
+// src/track_recorder.rs v10
+//! Track recording control, shared by the GUI and headless mode
+
+use crate::{
+    config::{RotationInterval, TrackRotation},
+    error::{GpsError, Result},
+    gps::GpsData,
+    waypoint::{Track, TrackPoint, TrackSegment, WaypointExporter, WaypointFormat},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+#[cfg(feature = "obd")]
+use {crate::{lock::RecoverPoison, obd::ObdData}, std::sync::{Arc, RwLock}};
+
+/// Write a checkpoint after this many new points
+const CHECKPOINT_INTERVAL: usize = 10;
+
+/// Why recording stopped without the user asking
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AutoStopReason {
+    MaxPoints,
+    Idle,
+}
+
+impl AutoStopReason {
+    pub fn description(&self) -> &str {
+        match self {
+            AutoStopReason::MaxPoints => "point limit reached",
+            AutoStopReason::Idle => "no movement recorded",
+        }
+    }
+}
+
+/// Which thresholds a new point must pass before it is recorded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RecordingMode {
+    #[default]
+    DistanceAndTime,
+    TimeOnly,      // A point every interval, even when stationary
+    DistanceOnly,  // A point whenever far enough from the last one
+}
+
+impl RecordingMode {
+    pub const ALL: [RecordingMode; 3] = [
+        RecordingMode::DistanceAndTime,
+        RecordingMode::TimeOnly,
+        RecordingMode::DistanceOnly,
+    ];
+
+    pub fn name(&self) -> &str {
+        match self {
+            RecordingMode::DistanceAndTime => "Distance + Time",
+            RecordingMode::TimeOnly => "Time only",
+            RecordingMode::DistanceOnly => "Distance only",
+        }
+    }
+
+    pub fn description(&self) -> &str {
+        match self {
+            RecordingMode::DistanceAndTime => "Points recorded only when both thresholds exceeded",
+            RecordingMode::TimeOnly => "A point every Min Time seconds, even when stationary",
+            RecordingMode::DistanceOnly => "A point every Min Distance meters, however quickly",
+        }
+    }
+
+    fn uses_time(&self) -> bool {
+        *self != RecordingMode::DistanceOnly
+    }
+
+    fn uses_distance(&self) -> bool {
+        *self != RecordingMode::TimeOnly
+    }
+}
+
+/// Minimum fix quality for a point to be recorded, checked on every update
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct FixFilter {
+    pub require_3d: bool,
+    pub max_hdop: Option<f64>,   // Points with unknown HDOP are skipped when set
+    pub skip_estimated: bool,    // Dead-reckoned positions
+}
+
+impl FixFilter {
+    pub fn accepts(&self, data: &GpsData) -> bool {
+        if self.require_3d && !data.is_3d_fix() {
+            return false;
+        }
+        if let Some(max_hdop) = self.max_hdop {
+            if data.hdop.is_none_or(|hdop| hdop > max_hdop) {
+                return false;
+            }
+        }
+        !(self.skip_estimated && data.is_estimated())
+    }
+}
+
+/// On-disk copy of an in-progress recording, so a crash doesn't lose it.
+/// The file has one JSON line per entry (a header, then each segment start
+/// and point in order), so a checkpoint only appends what is new.
+#[derive(Debug, Clone)]
+pub struct RecordingCheckpoint {
+    pub track: Track,
+    pub start_time: DateTime<Utc>,
+}
+
+/// One line of a checkpoint file
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum CheckpointLine {
+    Start {
+        name: String,
+        #[serde(default)]
+        description: Option<String>,
+        #[serde(default)]
+        color: Option<[u8; 3]>,
+        start_time: DateTime<Utc>,
+    },
+    Segment,
+    Point(TrackPoint),
+}
+
+/// How much of a track is in its checkpoint file: the number of segments
+/// started and the points of the last of them
+pub type CheckpointPosition = (usize, usize);
+
+impl RecordingCheckpoint {
+    /// Default location in the user's local data directory
+    pub fn default_path() -> PathBuf {
+        let mut path = dirs::data_local_dir().unwrap_or_else(|| PathBuf::from("."));
+        path.push("gps-monitor");
+        path.push("recording_checkpoint.jsonl");
+        path
+    }
+
+    /// Write the checkpoint, replacing any previous one in a single rename
+    pub fn save(&self, path: &Path) -> Result<()> {
+        Self::write(path, &self.track, self.start_time, None).map(|_| ())
+    }
+
+    /// Bring the checkpoint at `path` up to date with `track`. `written` is
+    /// what an earlier call returned; only the points recorded since are
+    /// appended. With `None`, a position `track` no longer has, or a file
+    /// whose last line was cut short, the file is replaced instead. Returns
+    /// the new position.
+    pub fn write(path: &Path, track: &Track, start_time: DateTime<Utc>, written: Option<CheckpointPosition>) -> Result<CheckpointPosition> {
+        let written = written
+            .filter(|&(segments, points)| match segments {
+                0 => points == 0,
+                n => track.segments.get(n - 1).is_some_and(|segment| points <= segment.len()),
+            })
+            .filter(|_| Self::ends_with_complete_line(path));
+
+        let mut lines = Vec::new();
+        if written.is_none() {
+            lines.push(CheckpointLine::Start {
+                name: track.name.clone(),
+                description: track.description.clone(),
+                color: track.color,
+                start_time,
+            });
+        }
+        let (segments, points) = written.unwrap_or((0, 0));
+        if let Some(segment) = segments.checked_sub(1).map(|last| &track.segments[last]) {
+            lines.extend(segment.points[points..].iter().cloned().map(CheckpointLine::Point));
+        }
+        for segment in &track.segments[segments..] {
+            lines.push(CheckpointLine::Segment);
+            lines.extend(segment.points.iter().cloned().map(CheckpointLine::Point));
+        }
+
+        let mut contents = String::new();
+        for line in &lines {
+            contents.push_str(&serde_json::to_string(line)?);
+            contents.push('\n');
+        }
+
+        if written.is_some() {
+            let mut file = std::fs::OpenOptions::new().append(true).open(path)?;
+            file.write_all(contents.as_bytes())?;
+        } else {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let temp_path = path.with_extension("jsonl.tmp");
+            std::fs::write(&temp_path, contents)?;
+            std::fs::rename(&temp_path, path)?;
+        }
+        Ok((track.segments.len(), track.segments.last().map_or(0, TrackSegment::len)))
+    }
+
+    /// Whether the file at `path` exists and ends with a newline, so lines
+    /// appended to it can be read back
+    fn ends_with_complete_line(path: &Path) -> bool {
+        let mut last = [0u8];
+        std::fs::File::open(path)
+            .and_then(|mut file| {
+                file.seek(SeekFrom::End(-1))?;
+                file.read_exact(&mut last)
+            })
+            .is_ok_and(|_| last[0] == b'\n')
+    }
+
+    /// Load a checkpoint if one exists
+    pub fn load(path: &Path) -> Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let invalid = |e: &dyn std::fmt::Display| GpsError::Other(format!("Invalid recording checkpoint: {}", e));
+        let contents = std::fs::read_to_string(path)?;
+        let mut lines = contents.lines();
+        let header = serde_json::from_str(lines.next().unwrap_or_default()).map_err(|e| invalid(&e))?;
+        let CheckpointLine::Start { name, description, color, start_time } = header else {
+            return Err(invalid(&"missing header"));
+        };
+
+        let mut track = Track { name, segments: Vec::new(), description, color };
+        for line in lines {
+            // Only the last line can be cut short, by a crash while appending
+            let Ok(line) = serde_json::from_str(line) else {
+                break;
+            };
+            match line {
+                CheckpointLine::Segment => track.start_new_segment(),
+                CheckpointLine::Point(point) => track.add_point(point),
+                CheckpointLine::Start { .. } => return Err(invalid(&"repeated header")),
+            }
+        }
+        if track.segments.is_empty() {
+            track.start_new_segment();
+        }
+        Ok(Some(Self { track, start_time }))
+    }
+
+    pub fn remove(path: &Path) {
+        if path.exists() {
+            if let Err(e) = std::fs::remove_file(path) {
+                eprintln!("Failed to remove recording checkpoint: {}", e);
+            }
+        }
+    }
+}
+
+pub struct TrackRecorder {
+    pub recording: bool,
+    current_track: Option<Track>,
+    track_name: String,
+    last_point_time: Option<Instant>,
+    min_distance: f64,      // Minimum distance in meters between points
+    min_time: Duration,     // Minimum time between points
+    mode: RecordingMode,
+    exclude_2d_altitude: bool,  // Drop altitude from points taken during a 2D fix
+    satellite_snapshots: bool,  // Keep satellite aggregates with each point
+    fix_filter: FixFilter,
+    filtered_points: usize,     // Fixes skipped by the filter this recording
+    last_filtered_fix: Option<DateTime<Utc>>,  // Timestamp of the last fix run through the filter
+    max_points: Option<usize>,        // Auto-stop after this many points
+    idle_timeout: Option<Duration>,   // Auto-stop after no accepted points for this long
+    idle_since: Option<Instant>,      // Recording start or the last accepted point
+    total_points: usize,
+    start_time: Option<chrono::DateTime<Utc>>,
+    checkpoint_path: Option<PathBuf>,  // No checkpoints when unset
+    checkpoint_written: Option<CheckpointPosition>,  // None to write the checkpoint afresh
+    points_since_checkpoint: usize,
+    clock_synced: bool,  // Points so far are on the GPS clock
+    rotation: Option<(RotationInterval, PathBuf)>,  // Export to dated GPX files when set
+    rotation_period: Option<DateTime<Utc>>,         // Start of the period being recorded
+    last_rotation_file: Option<PathBuf>,
+    rotated_tracks: Vec<Track>,                     // Finished periods not yet taken
+    #[cfg(feature = "obd")]
+    obd_data: Option<Arc<RwLock<ObdData>>>,  // Merged into each recorded point
+}
+
+impl TrackRecorder {
+    pub fn new() -> Self {
+        Self {
+            recording: false,
+            current_track: None,
+            track_name: String::new(),
+            last_point_time: None,
+            min_distance: 5.0,      // 5 meters default
+            min_time: Duration::from_secs(1), // 1 second default
+            mode: RecordingMode::default(),
+            exclude_2d_altitude: false,
+            satellite_snapshots: false,
+            fix_filter: FixFilter::default(),
+            filtered_points: 0,
+            last_filtered_fix: None,
+            max_points: None,
+            idle_timeout: None,
+            idle_since: None,
+            total_points: 0,
+            start_time: None,
+            checkpoint_path: None,
+            checkpoint_written: None,
+            points_since_checkpoint: 0,
+            clock_synced: false,
+            rotation: None,
+            rotation_period: None,
+            last_rotation_file: None,
+            rotated_tracks: Vec::new(),
+            #[cfg(feature = "obd")]
+            obd_data: None,
+        }
+    }
+
+    pub fn start_recording(&mut self, name: String) {
+        self.track_name = if name.is_empty() {
+            format!("Track {}", Utc::now().format("%Y-%m-%d %H:%M"))
+        } else {
+            name
+        };
+
+        self.current_track = Some(Track::new(self.track_name.clone()));
+        self.recording = true;
+        self.last_point_time = Some(Instant::now());
+        self.idle_since = Some(Instant::now());
+        self.total_points = 0;
+        self.filtered_points = 0;
+        self.last_filtered_fix = None;
+        self.start_time = Some(Utc::now());
+        self.checkpoint_written = None;
+        self.points_since_checkpoint = 0;
+        self.clock_synced = false;
+        self.rotation_period = None;
+    }
+
+    /// Continue a recording restored from a checkpoint, in a new segment.
+    /// The checkpoint is taken to have been loaded from this recorder's
+    /// checkpoint path, so later checkpoints append to that file.
+    pub fn resume_from_checkpoint(&mut self, checkpoint: RecordingCheckpoint) {
+        let mut track = checkpoint.track;
+        let written = (track.segments.len(), track.segments.last().map_or(0, TrackSegment::len));
+        if track.segments.last().is_some_and(|s| !s.is_empty()) {
+            track.start_new_segment();
+        }
+
+        self.track_name = track.name.clone();
+        self.total_points = track.total_points();
+        self.current_track = Some(track);
+        self.recording = true;
+        self.last_point_time = Some(Instant::now());
+        self.idle_since = Some(Instant::now());
+        self.start_time = Some(checkpoint.start_time);
+        self.checkpoint_written = Some(written);
+        self.points_since_checkpoint = 0;
+        self.clock_synced = true;  // Earlier points are as corrected as they will get
+        self.rotation_period = None;
+    }
+
+    pub fn stop_recording(&mut self) -> Option<Track> {
+        self.recording = false;
+        self.last_point_time = None;
+        self.idle_since = None;
+        self.checkpoint_written = None;
+        if let Some(path) = &self.checkpoint_path {
+            RecordingCheckpoint::remove(path);
+        }
+        self.current_track.take()
+    }
+
+    /// Stop recording, first writing the current period's file if rotation is on
+    pub fn finish_recording(&mut self) -> Option<Track> {
+        if self.recording {
+            self.write_rotation_file();
+        }
+        self.stop_recording()
+    }
+
+    pub fn pause_recording(&mut self) {
+        if self.recording && self.current_track.is_some() {
+            // Start a new segment when resumed
+            if let Some(ref mut track) = self.current_track {
+                track.start_new_segment();
+            }
+            self.write_checkpoint();
+        }
+        self.recording = false;
+    }
+
+    /// Shared OBD-II readings to attach to recorded points
+    #[cfg(feature = "obd")]
+    pub fn set_obd_source(&mut self, obd_data: Arc<RwLock<ObdData>>) {
+        self.obd_data = Some(obd_data);
+    }
+
+    #[cfg(feature = "obd")]
+    fn with_obd_data(&self, mut point: TrackPoint) -> TrackPoint {
+        if let Some(obd_data) = &self.obd_data {
+            obd_data.read_recover().apply_to(&mut point);
+        }
+        point
+    }
+
+    /// Where to checkpoint the in-progress track (`None` to disable)
+    pub fn set_checkpoint_path(&mut self, path: Option<PathBuf>) {
+        self.checkpoint_path = path;
+    }
+
+    /// Write the recording checkpoint now if points were added since the
+    /// last one. Only those points are appended, so this stays quick enough
+    /// to call from the UI thread however long the recording gets.
+    pub fn save_checkpoint(&mut self) {
+        if self.points_since_checkpoint > 0 {
+            self.write_checkpoint();
+        }
+    }
+
+    /// Periodically export the recording to dated GPX files (disabled unless `rotation.enabled`)
+    pub fn set_rotation(&mut self, rotation: &TrackRotation) {
+        self.rotation = rotation.enabled.then(|| (rotation.interval, rotation.output_path()));
+    }
+
+    /// Most recent file written by rotation
+    pub fn last_rotation_file(&self) -> Option<&Path> {
+        self.last_rotation_file.as_deref()
+    }
+
+    /// The tracks of periods finished by rotation since the last call, so
+    /// they aren't lost when only the current period is saved on stopping
+    pub fn take_rotated_tracks(&mut self) -> Vec<Track> {
+        std::mem::take(&mut self.rotated_tracks)
+    }
+
+    /// Start a fresh track when `time` falls in a later period than the
+    /// points recorded so far, exporting those points first. An earlier
+    /// `time`, e.g. a point stamped just before the hour, stays in the
+    /// current period.
+    fn rotate_if_due(&mut self, time: &DateTime<Utc>) {
+        let Some((interval, _)) = &self.rotation else {
+            return;
+        };
+        let period = interval.period_start(time);
+        match self.rotation_period {
+            Some(current) if current >= period => return,
+            Some(_) => {
+                self.write_rotation_file();
+                let finished = self.current_track.replace(Track::new(self.track_name.clone()));
+                self.checkpoint_written = None;
+                self.rotated_tracks.extend(finished.filter(|track| track.total_points() > 0));
+            }
+            None => {}
+        }
+        self.rotation_period = Some(period);
+    }
+
+    /// Export the current period's points, never overwriting an earlier file
+    fn write_rotation_file(&mut self) -> Option<PathBuf> {
+        let (Some((interval, dir)), Some(period), Some(track)) = (&self.rotation, self.rotation_period, &self.current_track) else {
+            return None;
+        };
+        if track.total_points() == 0 {
+            return None;
+        }
+
+        let path = unused_path(dir, &interval.file_name(&period));
+        let mut exporter = WaypointExporter::new();
+        exporter.add_track(track.clone());
+        let result = std::fs::create_dir_all(dir)
+            .map_err(GpsError::Io)
+            .and_then(|_| exporter.export_to_file(&path, WaypointFormat::GPX));
+
+        match result {
+            Ok(()) => {
+                self.last_rotation_file = Some(path.clone());
+                Some(path)
+            }
+            Err(e) => {
+                eprintln!("Failed to write rotated track {}: {}", path.display(), e);
+                None
+            }
+        }
+    }
+
+    fn write_checkpoint(&mut self) {
+        self.points_since_checkpoint = 0;
+        let (Some(path), Some(track), Some(start_time)) = (&self.checkpoint_path, &self.current_track, self.start_time) else {
+            return;
+        };
+
+        match RecordingCheckpoint::write(path, track, start_time, self.checkpoint_written) {
+            Ok(written) => self.checkpoint_written = Some(written),
+            Err(e) => {
+                eprintln!("Failed to write recording checkpoint: {}", e);
+                self.checkpoint_written = None;
+            }
+        }
+    }
+
+    /// Stop if nothing has been accepted for the idle timeout at `now`,
+    /// counting from the start so a recording that never gets a fix stops too.
+    /// Returns the finished track when it stopped.
+    pub fn check_idle(&mut self, now: Instant) -> Option<(Track, AutoStopReason)> {
+        let deadline = self.idle_deadline()?;
+        if now < deadline {
+            return None;
+        }
+        self.auto_stop(AutoStopReason::Idle)
+    }
+
+    /// When the idle timeout will stop the recording if no point is accepted
+    /// before then, so a caller can wake up for it
+    pub fn idle_deadline(&self) -> Option<Instant> {
+        if !self.recording {
+            return None;
+        }
+        self.idle_timeout.zip(self.idle_since).map(|(timeout, since)| since + timeout)
+    }
+
+    /// Record a point from the latest GPS data, received at `now`, if the
+    /// thresholds allow it.
+    ///
+    /// Returns the finished track if recording stopped automatically because
+    /// of the point cap or idle timeout.
+    pub fn update(&mut self, gps_data: &GpsData, now: Instant) -> Option<(Track, AutoStopReason)> {
+        if !self.recording || self.current_track.is_none() {
+            return None;
+        }
+
+        if let Some(stopped) = self.check_idle(now) {
+            return Some(stopped);
+        }
+
+        // Close a finished period even if no point is taken, e.g. while parked
+        if let Some(time) = gps_data.corrected_timestamp() {
+            self.rotate_if_due(&time);
+        }
+
+        // Points recorded before GPS time was known used the system clock
+        if !self.clock_synced {
+            if let (Some(offset), Some(track)) = (gps_data.clock_offset, self.current_track.as_mut()) {
+                track.shift_times(offset);
+                self.clock_synced = true;
+                self.checkpoint_written = None;  // Points already written have moved
+            }
+        }
+
+        // Check if GPS has a fix
+        if !gps_data.has_fix() {
+            return None;
+        }
+
+        if !self.fix_filter.accepts(gps_data) {
+            // The same fix is seen again on every frame until the next arrives
+            if gps_data.timestamp != self.last_filtered_fix {
+                self.filtered_points += 1;
+            }
+            self.last_filtered_fix = gps_data.timestamp;
+            return None;
+        }
+        self.last_filtered_fix = gps_data.timestamp;
+
+        // Check time threshold
+        if let Some(last_time) = self.last_point_time.filter(|_| self.mode.uses_time()) {
+            if now.duration_since(last_time) < self.min_time {
+                return None;
+            }
+        }
+
+        // Create track point from GPS data
+        if let Some(mut point) = TrackPoint::from_gps_data(gps_data) {
+            if self.exclude_2d_altitude && gps_data.is_2d_fix() {
+                point.elevation = None;
+            }
+            if !self.satellite_snapshots {
+                point.satellites_snapshot = None;
+            }
+            #[cfg(feature = "obd")]
+            let point = self.with_obd_data(point);
+
+            // Check distance threshold (if we have a previous point)
+            if let Some(ref track) = self.current_track {
+                if let Some(segment) = track.segments.last() {
+                    if let Some(last_point) = segment.points.last() {
+                        let distance = last_point.distance_to(&point);
+                        if self.mode.uses_distance() && distance < self.min_distance {
+                            return None; // Too close to last point
+                        }
+                    }
+                }
+            }
+
+            self.rotate_if_due(&point.timestamp);
+
+            // Add point to current track
+            if let Some(ref mut track) = self.current_track {
+                track.add_point(point);
+                self.total_points += 1;
+                self.last_point_time = Some(now);
+                self.idle_since = Some(now);
+                self.points_since_checkpoint += 1;
+            }
+
+            if self.max_points.is_some_and(|max| self.total_points >= max) {
+                return self.auto_stop(AutoStopReason::MaxPoints);
+            }
+
+            if self.points_since_checkpoint >= CHECKPOINT_INTERVAL {
+                self.write_checkpoint();
+            }
+        }
+
+        None
+    }
+
+    fn auto_stop(&mut self, reason: AutoStopReason) -> Option<(Track, AutoStopReason)> {
+        self.finish_recording().map(|track| (track, reason))
+    }
+
+    /// The track being recorded, including while paused
+    pub fn current_track(&self) -> Option<&Track> {
+        self.current_track.as_ref()
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recording
+    }
+
+    /// Recording, paused with a track in progress, or stopped, with the points
+    /// taken so far and the time since recording started at `now`
+    pub fn status(&self, now: DateTime<Utc>) -> RecordingStatus {
+        if self.current_track.is_none() {
+            return RecordingStatus::Stopped;
+        }
+        let points = self.total_points;
+        let elapsed = self.start_time.map_or_else(chrono::Duration::zero, |start| now - start);
+        if self.recording {
+            RecordingStatus::Recording { points, elapsed }
+        } else {
+            RecordingStatus::Paused { points, elapsed }
+        }
+    }
+
+    pub fn get_track_name(&self) -> &str {
+        &self.track_name
+    }
+
+    pub fn get_track_stats(&self) -> Option<TrackStats> {
+        let track = self.current_track.as_ref()?;
+        let start = self.start_time?;
+        let elapsed = Utc::now().signed_duration_since(start);
+
+        Some(TrackStats {
+            points: self.total_points,
+            distance_km: track.total_distance() / 1000.0,
+            start_time: start,
+            duration: elapsed,
+            avg_speed: track.average_speed(),
+        })
+    }
+
+    pub fn set_min_distance(&mut self, meters: f64) {
+        self.min_distance = meters.max(0.5); // At least 0.5m
+    }
+
+    pub fn set_mode(&mut self, mode: RecordingMode) {
+        self.mode = mode;
+    }
+
+    pub fn get_mode(&self) -> RecordingMode {
+        self.mode
+    }
+
+    /// Whether points recorded during a 2D fix keep their (held) altitude
+    pub fn set_exclude_2d_altitude(&mut self, exclude: bool) {
+        self.exclude_2d_altitude = exclude;
+    }
+
+    /// Whether points carry a snapshot of the satellite geometry and signal
+    pub fn set_satellite_snapshots(&mut self, enabled: bool) {
+        self.satellite_snapshots = enabled;
+    }
+
+    pub fn set_fix_filter(&mut self, filter: FixFilter) {
+        self.fix_filter = filter;
+    }
+
+    pub fn get_fix_filter(&self) -> FixFilter {
+        self.fix_filter
+    }
+
+    /// Fixes skipped for quality since recording started
+    pub fn get_filtered_points(&self) -> usize {
+        self.filtered_points
+    }
+
+    pub fn set_min_time(&mut self, seconds: u64) {
+        self.min_time = Duration::from_secs(seconds.max(1)); // At least 1 second
+    }
+
+    /// Set the point cap (`None` for unlimited)
+    pub fn set_max_points(&mut self, max_points: Option<usize>) {
+        self.max_points = max_points.filter(|&n| n > 0);
+    }
+
+    /// Set the idle timeout (`None` to disable)
+    pub fn set_idle_timeout(&mut self, timeout: Option<Duration>) {
+        self.idle_timeout = timeout.filter(|t| !t.is_zero());
+    }
+
+    pub fn get_max_points(&self) -> Option<usize> {
+        self.max_points
+    }
+
+    pub fn get_idle_timeout(&self) -> Option<Duration> {
+        self.idle_timeout
+    }
+
+    pub fn get_min_distance(&self) -> f64 {
+        self.min_distance
+    }
+
+    pub fn get_min_time_seconds(&self) -> u64 {
+        self.min_time.as_secs()
+    }
+}
+
+impl Default for TrackRecorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// `dir/name`, or `dir/name_2` etc. if that file already exists
+pub(crate) fn unused_path(dir: &Path, name: &str) -> PathBuf {
+    let path = dir.join(name);
+    if !path.exists() {
+        return path;
+    }
+    let stem = Path::new(name).file_stem().and_then(|s| s.to_str()).unwrap_or(name);
+    (2..)
+        .map(|n| dir.join(format!("{}_{}.gpx", stem, n)))
+        .find(|p| !p.exists())
+        .unwrap_or(path)
+}
+
+/// Whether a track is being recorded, for the recording indicator
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RecordingStatus {
+    Stopped,
+    Recording { points: usize, elapsed: chrono::Duration },
+    Paused { points: usize, elapsed: chrono::Duration },
+}
+
+pub struct TrackStats {
+    pub points: usize,
+    pub distance_km: f64,
+    pub start_time: DateTime<Utc>,
+    pub duration: chrono::Duration,
+    pub avg_speed: Option<f64>,
+}
+
+impl TrackStats {
+    pub fn format_duration(&self) -> String {
+        let total_seconds = self.duration.num_seconds();
+        let hours = total_seconds / 3600;
+        let minutes = (total_seconds % 3600) / 60;
+        let seconds = total_seconds % 60;
+
+        if hours > 0 {
+            format!("{}h {}m {}s", hours, minutes, seconds)
+        } else if minutes > 0 {
+            format!("{}m {}s", minutes, seconds)
+        } else {
+            format!("{}s", seconds)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gps_at(lat: f64, lon: f64) -> GpsData {
+        let mut data = GpsData::new();
+        data.latitude = Some(lat);
+        data.longitude = Some(lon);
+        data.update_timestamp();
+        data
+    }
+
+    #[test]
+    fn test_max_points_auto_stop() {
+        let mut recorder = TrackRecorder::new();
+        recorder.min_time = Duration::ZERO;
+        recorder.set_max_points(Some(3));
+        recorder.start_recording("Capped".to_string());
+
+        assert!(recorder.update(&gps_at(42.0, -71.0), Instant::now()).is_none());
+        assert!(recorder.update(&gps_at(42.001, -71.0), Instant::now()).is_none());
+        let (track, reason) = recorder.update(&gps_at(42.002, -71.0), Instant::now()).expect("should auto-stop");
+
+        assert_eq!(reason, AutoStopReason::MaxPoints);
+        assert_eq!(track.total_points(), 3);
+        assert!(!recorder.is_recording());
+    }
+
+    #[test]
+    fn test_recording_status() {
+        let mut recorder = TrackRecorder::new();
+        recorder.min_time = Duration::ZERO;
+        assert_eq!(recorder.status(Utc::now()), RecordingStatus::Stopped);
+
+        recorder.start_recording("Status".to_string());
+        recorder.update(&gps_at(42.0, -71.0), Instant::now());
+        recorder.update(&gps_at(42.0005, -71.0), Instant::now());
+        let later = recorder.start_time.unwrap() + chrono::Duration::seconds(90);
+        let recording = RecordingStatus::Recording { points: 2, elapsed: chrono::Duration::seconds(90) };
+        assert_eq!(recorder.status(later), recording);
+
+        recorder.pause_recording();
+        let paused = RecordingStatus::Paused { points: 2, elapsed: chrono::Duration::seconds(90) };
+        assert_eq!(recorder.status(later), paused);
+
+        recorder.stop_recording();
+        assert_eq!(recorder.status(later), RecordingStatus::Stopped);
+    }
+
+    #[test]
+    fn test_recording_modes() {
+        let stationary = [(42.0, -71.0); 3];
+        let moving = [(42.0, -71.0), (42.0005, -71.0), (42.001, -71.0)];
+        let recorded = |mode: RecordingMode, min_time: Duration, fixes: &[(f64, f64)]| {
+            let mut recorder = TrackRecorder::new();
+            recorder.min_time = min_time;
+            recorder.set_mode(mode);
+            recorder.start_recording("Mode".to_string());
+            for &(lat, lon) in fixes {
+                recorder.update(&gps_at(lat, lon), Instant::now());
+            }
+            recorder.stop_recording().map_or(0, |track| track.total_points())
+        };
+        let hour = Duration::from_secs(3600);
+
+        // (mode, stationary with no wait, moving with no wait, moving before min time)
+        for (mode, still, moved, too_soon) in [
+            (RecordingMode::DistanceAndTime, 1, 3, 0),
+            (RecordingMode::TimeOnly, 3, 3, 0),
+            (RecordingMode::DistanceOnly, 1, 3, 3),
+        ] {
+            assert_eq!(recorded(mode, Duration::ZERO, &stationary), still, "{:?} stationary", mode);
+            assert_eq!(recorded(mode, Duration::ZERO, &moving), moved, "{:?} moving", mode);
+            assert_eq!(recorded(mode, hour, &moving), too_soon, "{:?} before min time", mode);
+        }
+        assert_eq!(recorded(RecordingMode::TimeOnly, hour, &stationary), 0);
+    }
+
+    #[test]
+    fn test_early_points_shifted_to_gps_clock() {
+        let mut recorder = TrackRecorder::new();
+        recorder.min_time = Duration::ZERO;
+        recorder.start_recording("Clock".to_string());
+
+        let first = gps_at(42.0, -71.0);
+        let system_time = first.timestamp.unwrap();
+        recorder.update(&first, Instant::now());
+
+        let offset = chrono::Duration::seconds(-3600);
+        let mut synced = gps_at(42.001, -71.0);
+        synced.timestamp = Some(system_time + chrono::Duration::seconds(10));
+        synced.clock_offset = Some(offset);
+        recorder.update(&synced, Instant::now());
+
+        let track = recorder.stop_recording().unwrap();
+        let times: Vec<_> = track.segments.iter().flat_map(|s| s.points.iter()).map(|p| p.timestamp).collect();
+        assert_eq!(times, [system_time + offset, synced.corrected_timestamp().unwrap()]);
+    }
+
+    #[test]
+    fn test_2d_fix_altitude_excluded() {
+        let mut recorder = TrackRecorder::new();
+        recorder.min_time = Duration::ZERO;
+        recorder.set_exclude_2d_altitude(true);
+        recorder.start_recording("Altitude".to_string());
+
+        let at = |lat: f64, mode: u8| {
+            let mut data = gps_at(lat, -71.0);
+            data.altitude = Some(120.0);
+            data.mode = Some(mode);
+            data
+        };
+        recorder.update(&at(42.0, 2), Instant::now());
+        recorder.update(&at(42.0005, 3), Instant::now());
+
+        let track = recorder.stop_recording().unwrap();
+        let elevations: Vec<_> = track.segments.iter().flat_map(|s| s.points.iter()).map(|p| p.elevation).collect();
+        assert_eq!(elevations, [None, Some(120.0)]);
+
+        // Off by default
+        let mut recorder = TrackRecorder::new();
+        recorder.start_recording("Altitude".to_string());
+        recorder.last_point_time = None;
+        recorder.update(&at(42.0, 2), Instant::now());
+        assert_eq!(recorder.stop_recording().unwrap().segments[0].points[0].elevation, Some(120.0));
+    }
+
+    #[test]
+    fn test_fix_filter_skips_poor_fixes() {
+        let fix = |lat: f64, mode: u8, hdop: Option<f64>, quality: u8| {
+            let mut data = gps_at(lat, -71.0);
+            data.mode = Some(mode);
+            data.hdop = hdop;
+            data.fix_quality = Some(quality);
+            data
+        };
+        let sequence = [
+            fix(42.0, 3, Some(0.9), 1),
+            fix(42.0001, 2, Some(0.9), 1),  // 2D
+            fix(42.0002, 3, Some(4.5), 1),  // Poor HDOP
+            fix(42.0003, 3, None, 1),       // Unknown HDOP
+            fix(42.0004, 3, Some(1.2), 6),  // Estimated
+            fix(42.0005, 3, Some(1.5), 2),  // DGPS
+        ];
+        let recorded = |filter: FixFilter| {
+            let mut recorder = TrackRecorder::new();
+            recorder.min_time = Duration::ZERO;
+            recorder.set_fix_filter(filter);
+            recorder.start_recording("Filtered".to_string());
+            for data in &sequence {
+                // Repeated frames of the same fix are skipped once
+                recorder.update(data, Instant::now());
+                recorder.update(data, Instant::now());
+            }
+            let skipped = recorder.get_filtered_points();
+            let track = recorder.stop_recording().unwrap();
+            let lats: Vec<_> = track.segments[0].points.iter().map(|p| p.latitude).collect();
+            (lats, skipped)
+        };
+
+        assert_eq!(recorded(FixFilter::default()).0.len(), sequence.len());
+        assert_eq!(
+            recorded(FixFilter { require_3d: true, ..Default::default() }),
+            (vec![42.0, 42.0002, 42.0003, 42.0004, 42.0005], 1)
+        );
+        assert_eq!(
+            recorded(FixFilter { max_hdop: Some(2.0), skip_estimated: true, ..Default::default() }),
+            (vec![42.0, 42.0001, 42.0005], 3)
+        );
+    }
+
+    #[test]
+    fn test_idle_timeout_auto_stop() {
+        let secs = Duration::from_secs;
+        let mut recorder = TrackRecorder::new();
+        recorder.set_idle_timeout(Some(secs(5)));
+        recorder.start_recording("Idle".to_string());
+        let start = Instant::now();
+
+        assert!(recorder.update(&gps_at(42.0, -71.0), start + secs(1)).is_none());
+        assert_eq!(recorder.current_track().map(Track::total_points), Some(1));
+        assert!(recorder.idle_deadline().is_some_and(|deadline| deadline >= start + secs(6)));
+
+        // Stationary updates are rejected by the distance threshold
+        assert!(recorder.update(&gps_at(42.0, -71.0), start + secs(5)).is_none());
+        // The timer runs without updates too
+        let (track, reason) = recorder.check_idle(start + secs(7)).expect("should auto-stop");
+
+        assert_eq!(reason, AutoStopReason::Idle);
+        assert_eq!(track.total_points(), 1);
+        assert!(!recorder.is_recording());
+        assert_eq!(recorder.idle_deadline(), None);
+
+        // Never a fix: the timer runs from the start of the recording
+        let mut recorder = TrackRecorder::new();
+        recorder.set_idle_timeout(Some(secs(5)));
+        recorder.start_recording("No fix".to_string());
+        let start = Instant::now();
+        assert!(recorder.check_idle(start + secs(4)).is_none());
+        let (track, reason) = recorder.update(&GpsData::new(), start + secs(6)).expect("should auto-stop");
+        assert_eq!(reason, AutoStopReason::Idle);
+        assert_eq!(track.total_points(), 0);
+    }
+
+    #[test]
+    fn test_checkpoint_round_trip_and_resume() {
+        let dir = std::env::temp_dir().join(format!("gps-monitor-checkpoint-{}", std::process::id()));
+        let path = dir.join("recording_checkpoint.jsonl");
+
+        let mut recorder = TrackRecorder::new();
+        recorder.min_time = Duration::ZERO;
+        recorder.set_checkpoint_path(Some(path.clone()));
+        recorder.start_recording("Long drive".to_string());
+
+        for i in 0..CHECKPOINT_INTERVAL {
+            recorder.update(&gps_at(42.0 + i as f64 * 0.0005, -71.0), Instant::now());
+        }
+
+        let checkpoint = RecordingCheckpoint::load(&path).unwrap().expect("checkpoint written");
+        let recorded = recorder.current_track.as_ref().unwrap();
+        let coords = |track: &Track| -> Vec<(f64, f64, DateTime<Utc>)> {
+            track.segments.iter()
+                .flat_map(|s| s.points.iter())
+                .map(|p| (p.latitude, p.longitude, p.timestamp))
+                .collect()
+        };
+        assert_eq!(checkpoint.track.name, "Long drive");
+        assert_eq!(coords(&checkpoint.track), coords(recorded));
+
+        // A fresh recorder picks up where the checkpoint left off
+        let mut resumed = TrackRecorder::new();
+        resumed.min_time = Duration::ZERO;
+        resumed.set_checkpoint_path(Some(path.clone()));
+        resumed.resume_from_checkpoint(checkpoint);
+        assert!(resumed.is_recording());
+        assert_eq!(resumed.get_track_name(), "Long drive");
+
+        // Its checkpoints carry on appending to the same file
+        let before = std::fs::read_to_string(&path).unwrap();
+        resumed.update(&gps_at(42.01, -71.0), Instant::now());
+        resumed.save_checkpoint();
+        let after = std::fs::read_to_string(&path).unwrap();
+        assert!(after.starts_with(&before));
+        assert_eq!(after.lines().count(), before.lines().count() + 2);
+
+        let track = resumed.stop_recording().unwrap();
+        assert_eq!(track.total_points(), CHECKPOINT_INTERVAL + 1);
+        assert_eq!(track.segments.len(), 2);
+
+        // Stopping clears the checkpoint
+        assert!(RecordingCheckpoint::load(&path).unwrap().is_none());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_checkpoint_appends_new_points() {
+        let dir = std::env::temp_dir().join(format!("gps-monitor-checkpoint-append-{}", std::process::id()));
+        let path = dir.join("recording_checkpoint.jsonl");
+
+        let mut recorder = TrackRecorder::new();
+        recorder.min_time = Duration::ZERO;
+        recorder.set_checkpoint_path(Some(path.clone()));
+        recorder.start_recording("Commute".to_string());
+        let mut lat = 42.0;
+        let mut record = |recorder: &mut TrackRecorder, points: usize| {
+            for _ in 0..points {
+                recorder.update(&gps_at(lat, -71.0), Instant::now());
+                lat += 0.0005;
+            }
+        };
+
+        record(&mut recorder, CHECKPOINT_INTERVAL);
+        let first = std::fs::read_to_string(&path).unwrap();
+        recorder.pause_recording();
+        recorder.recording = true;
+        record(&mut recorder, 3);
+        recorder.save_checkpoint();
+
+        // The earlier lines are left as they were, with the segment break and
+        // the three new points after them
+        let second = std::fs::read_to_string(&path).unwrap();
+        assert!(second.starts_with(&first));
+        assert_eq!(second.lines().count(), first.lines().count() + 4);
+        let checkpoint = RecordingCheckpoint::load(&path).unwrap().unwrap();
+        assert_eq!(checkpoint.track.segments.iter().map(TrackSegment::len).collect::<Vec<_>>(), vec![CHECKPOINT_INTERVAL, 3]);
+
+        // A line cut short by a crash is dropped
+        std::fs::write(&path, format!("{}{{\"point\":{{\"lat", second)).unwrap();
+        let checkpoint = RecordingCheckpoint::load(&path).unwrap().unwrap();
+        assert_eq!(checkpoint.track.total_points(), CHECKPOINT_INTERVAL + 3);
+
+        // and resuming from it rewrites the file rather than appending after it
+        let mut resumed = TrackRecorder::new();
+        resumed.min_time = Duration::ZERO;
+        resumed.set_checkpoint_path(Some(path.clone()));
+        resumed.resume_from_checkpoint(checkpoint);
+        record(&mut resumed, 1);
+        resumed.save_checkpoint();
+        let checkpoint = RecordingCheckpoint::load(&path).unwrap().unwrap();
+        assert_eq!(checkpoint.track.segments.iter().map(TrackSegment::len).collect::<Vec<_>>(), vec![CHECKPOINT_INTERVAL, 3, 1]);
+        assert!(std::fs::read_to_string(&path).unwrap().lines().all(|line| serde_json::from_str::<serde_json::Value>(line).is_ok()));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_rotation_exports_each_period() {
+        use chrono::TimeZone;
+        let dir = std::env::temp_dir().join(format!("gps-monitor-rotation-{}", std::process::id()));
+        let at = |lat: f64, h: u32, m: u32, s: u32| {
+            let mut data = gps_at(lat, -71.0);
+            data.timestamp = Some(Utc.with_ymd_and_hms(2024, 5, 1, h, m, s).unwrap());
+            data
+        };
+
+        let mut recorder = TrackRecorder::new();
+        recorder.min_time = Duration::ZERO;
+        recorder.set_rotation(&TrackRotation {
+            enabled: true,
+            interval: RotationInterval::Hourly,
+            output_dir: dir.to_string_lossy().into_owned(),
+        });
+        recorder.start_recording("Logger".to_string());
+
+        recorder.update(&at(42.0, 12, 59, 50), Instant::now());
+        recorder.update(&at(42.0005, 12, 59, 55), Instant::now());
+        assert!(recorder.last_rotation_file().is_none());
+
+        // Crossing the hour writes the finished hour and keeps recording
+        recorder.update(&at(42.001, 13, 0, 5), Instant::now());
+        let first = dir.join("track_2024-05-01_12.gpx");
+        assert_eq!(recorder.last_rotation_file(), Some(first.as_path()));
+        assert_eq!(std::fs::read_to_string(&first).unwrap().matches("<trkpt").count(), 2);
+        assert!(recorder.is_recording());
+        assert_eq!(recorder.current_track.as_ref().unwrap().total_points(), 1);
+        let rotated = recorder.take_rotated_tracks();
+        assert_eq!(rotated.len(), 1);
+        assert_eq!(rotated[0].total_points(), 2);
+        assert!(recorder.take_rotated_tracks().is_empty());
+
+        // A period ends without a point, e.g. while parked, and a late point
+        // stamped before the hour doesn't rotate back
+        let mut parked = at(42.001, 14, 0, 5);
+        parked.latitude = None;
+        recorder.update(&parked, Instant::now());
+        assert_eq!(recorder.last_rotation_file(), Some(dir.join("track_2024-05-01_13.gpx").as_path()));
+        assert_eq!(recorder.take_rotated_tracks().len(), 1);
+        recorder.update(&at(42.002, 13, 59, 59), Instant::now());
+        assert!(recorder.take_rotated_tracks().is_empty());
+        assert_eq!(recorder.current_track.as_ref().unwrap().total_points(), 1);
+
+        // Finishing writes the partial hour; a later run in the same hour gets a new name
+        let track = recorder.finish_recording().unwrap();
+        assert_eq!(track.total_points(), 1);
+        assert!(dir.join("track_2024-05-01_14.gpx").exists());
+        assert_eq!(unused_path(&dir, "track_2024-05-01_14.gpx"), dir.join("track_2024-05-01_14_2.gpx"));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
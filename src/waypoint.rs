@@ -1,13 +1,176 @@
-// src/waypoint.rs v2
+// src/waypoint.rs v7
 //! Waypoint and track recording functionality
 
-use crate::gps::GpsData;
+use crate::config::{CsvExport, DisplayTimeZone, DistanceUnits, WaypointQualityGate};
+use crate::gps::{GpsData, DEFAULT_COORDINATE_DECIMALS};
 use crate::error::{Result, GpsError};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs::File;
 use std::io::Write;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+/// Fastest believable ground speed in m/s (~360 km/h); anything faster is a position jump
+pub const MAX_PLAUSIBLE_SPEED: f64 = 100.0;
+
+/// Steps slower than this (m/s, ~1.8 km/h) count as stopped when computing moving time
+pub const MIN_MOVING_SPEED: f64 = 0.5;
+
+/// Great-circle distance in meters between two coordinates using the Haversine formula
+fn haversine_distance(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let r = 6371000.0; // Earth radius in meters
+    let phi1 = lat1.to_radians();
+    let phi2 = lat2.to_radians();
+    let delta_lat = (lat2 - lat1).to_radians();
+    let delta_lon = (lon2 - lon1).to_radians();
+
+    let a = (delta_lat / 2.0).sin().powi(2)
+        + phi1.cos() * phi2.cos() * (delta_lon / 2.0).sin().powi(2);
+    let c = 2.0 * a.sqrt().atan2((1.0 - a).sqrt());
+
+    r * c
+}
+
+/// Distance in meters from `p` to the line from `a` to `b`, on a flat
+/// projection around `a` that is accurate enough for simplifying tracks
+fn distance_to_line(p: &TrackPoint, a: &TrackPoint, b: &TrackPoint) -> f64 {
+    let meters_per_degree = 6371000.0_f64.to_radians();
+    let x_scale = meters_per_degree * a.latitude.to_radians().cos();
+    let project = |q: &TrackPoint| ((q.longitude - a.longitude) * x_scale, (q.latitude - a.latitude) * meters_per_degree);
+    let ((px, py), (bx, by)) = (project(p), project(b));
+
+    let length_sq = bx * bx + by * by;
+    let t = if length_sq > 0.0 { ((px * bx + py * by) / length_sq).clamp(0.0, 1.0) } else { 0.0 };
+    (px - t * bx).hypot(py - t * by)
+}
+
+/// Format a duration as "1h 2m 3s", dropping leading zero units
+pub(crate) fn format_duration(duration: chrono::Duration) -> String {
+    let total_seconds = duration.num_seconds();
+    let hours = total_seconds / 3600;
+    let minutes = (total_seconds % 3600) / 60;
+    let seconds = total_seconds % 60;
+
+    if hours > 0 {
+        format!("{}h {}m {}s", hours, minutes, seconds)
+    } else if minutes > 0 {
+        format!("{}m {}s", minutes, seconds)
+    } else {
+        format!("{}s", seconds)
+    }
+}
+
+/// Initial great-circle bearing in degrees (0-360, clockwise from true north)
+fn initial_bearing(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let phi1 = lat1.to_radians();
+    let phi2 = lat2.to_radians();
+    let delta_lon = (lon2 - lon1).to_radians();
+
+    let y = delta_lon.sin() * phi2.cos();
+    let x = phi1.cos() * phi2.sin() - phi1.sin() * phi2.cos() * delta_lon.cos();
+
+    y.atan2(x).to_degrees().rem_euclid(360.0)
+}
+
+/// Position reached by travelling `distance` meters from a start point along
+/// the great circle with initial bearing `bearing` (degrees from true north)
+pub fn destination_point(lat: f64, lon: f64, bearing: f64, distance: f64) -> (f64, f64) {
+    let r = 6371000.0; // Earth radius in meters
+    let phi1 = lat.to_radians();
+    let lambda1 = lon.to_radians();
+    let theta = bearing.to_radians();
+    let delta = distance / r;
+
+    let phi2 = (phi1.sin() * delta.cos() + phi1.cos() * delta.sin() * theta.cos()).asin();
+    let lambda2 = lambda1
+        + (theta.sin() * delta.sin() * phi1.cos()).atan2(delta.cos() - phi1.sin() * phi2.sin());
+
+    (phi2.to_degrees(), (lambda2.to_degrees() + 540.0).rem_euclid(360.0) - 180.0)
+}
+
+/// Hook for replacing GPS altitude with a better elevation, e.g. from a DEM
+/// tile service or a geoid model. Implemented for closures taking (lat, lon).
+pub trait ElevationCorrector: Send + Sync {
+    /// Elevation in meters at the given position, or `None` to keep the GPS value
+    fn corrected_elevation(&self, latitude: f64, longitude: f64) -> Option<f64>;
+}
+
+impl<F> ElevationCorrector for F
+where
+    F: Fn(f64, f64) -> Option<f64> + Send + Sync,
+{
+    fn corrected_elevation(&self, latitude: f64, longitude: f64) -> Option<f64> {
+        self(latitude, longitude)
+    }
+}
+
+/// Default corrector that leaves GPS elevations untouched
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoElevationCorrection;
+
+impl ElevationCorrector for NoElevationCorrection {
+    fn corrected_elevation(&self, _latitude: f64, _longitude: f64) -> Option<f64> {
+        None
+    }
+}
+
+/// Hook for map matching ("snap to road"), e.g. through an OSRM or Valhalla
+/// match service. Implemented for closures taking a segment's points.
+pub trait MapMatcher: Send + Sync {
+    /// Corrected points for one segment, or `None` to keep it unchanged
+    fn match_points(&self, points: &[TrackPoint]) -> Option<Vec<TrackPoint>>;
+}
+
+impl<F> MapMatcher for F
+where
+    F: Fn(&[TrackPoint]) -> Option<Vec<TrackPoint>> + Send + Sync,
+{
+    fn match_points(&self, points: &[TrackPoint]) -> Option<Vec<TrackPoint>> {
+        self(points)
+    }
+}
+
+/// Default matcher that leaves tracks untouched
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoMapMatching;
+
+impl MapMatcher for NoMapMatching {
+    fn match_points(&self, _points: &[TrackPoint]) -> Option<Vec<TrackPoint>> {
+        None
+    }
+}
+
+impl WaypointQualityGate {
+    /// Reasons the current fix falls short, empty when it passes (or the gate is off)
+    pub fn unmet_requirements(&self, data: &GpsData) -> Vec<String> {
+        let mut unmet = Vec::new();
+        if !self.enabled {
+            return unmet;
+        }
+
+        match data.hdop {
+            Some(hdop) if hdop <= self.max_hdop => {}
+            Some(hdop) => unmet.push(format!("HDOP {:.1} above {:.1}", hdop, self.max_hdop)),
+            None => unmet.push("HDOP unknown".to_string()),
+        }
+
+        if self.require_3d && !data.is_3d_fix() {
+            unmet.push("no 3D fix".to_string());
+        }
+
+        // Fall back to the GGA count when there are no per-satellite reports
+        let used = match data.satellites_used() {
+            0 => data.satellites.map_or(0, usize::from),
+            n => n,
+        };
+        if used < self.min_satellites {
+            unmet.push(format!("{} satellites used, need {}", used, self.min_satellites));
+        }
+
+        unmet
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Waypoint {
@@ -17,6 +180,8 @@ pub struct Waypoint {
     pub elevation: Option<f64>,
     pub timestamp: DateTime<Utc>,
     pub description: Option<String>,
+    #[serde(default)]
+    pub color: Option<[u8; 3]>,  // RGB marker color, map default when unset
 }
 
 impl Waypoint {
@@ -27,13 +192,90 @@ impl Waypoint {
                 latitude: lat,
                 longitude: lon,
                 elevation: gps_data.altitude,
-                timestamp: gps_data.timestamp.unwrap_or_else(Utc::now),
+                timestamp: gps_data.corrected_timestamp().unwrap_or_else(Utc::now),
                 description,
+                color: None,
             })
         } else {
             None
         }
     }
+
+    /// Like `from_gps_data`, taking the elevation from `corrector` where it has one
+    pub fn from_gps_data_corrected(
+        gps_data: &GpsData,
+        name: String,
+        description: Option<String>,
+        corrector: &dyn ElevationCorrector,
+    ) -> Option<Self> {
+        let mut waypoint = Self::from_gps_data(gps_data, name, description)?;
+        waypoint.correct_elevation(corrector);
+        Some(waypoint)
+    }
+
+    /// Override the elevation if the corrector knows this position
+    pub fn correct_elevation(&mut self, corrector: &dyn ElevationCorrector) {
+        if let Some(elevation) = corrector.corrected_elevation(self.latitude, self.longitude) {
+            self.elevation = Some(elevation);
+        }
+    }
+
+    /// Great-circle distance to another waypoint in meters
+    pub fn distance_to(&self, other: &Waypoint) -> f64 {
+        haversine_distance(self.latitude, self.longitude, other.latitude, other.longitude)
+    }
+
+    /// Great-circle distance to a coordinate in meters
+    pub fn distance_from(&self, latitude: f64, longitude: f64) -> f64 {
+        haversine_distance(latitude, longitude, self.latitude, self.longitude)
+    }
+
+    /// Initial bearing to another waypoint in degrees from true north
+    pub fn bearing_to(&self, other: &Waypoint) -> f64 {
+        initial_bearing(self.latitude, self.longitude, other.latitude, other.longitude)
+    }
+
+    /// Initial bearing from a coordinate to this waypoint in degrees from true north
+    pub fn bearing_from(&self, latitude: f64, longitude: f64) -> f64 {
+        initial_bearing(latitude, longitude, self.latitude, self.longitude)
+    }
+}
+
+/// Satellite geometry and signal at the moment a track point was taken,
+/// for finding the cause of poor track segments afterwards
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SatelliteSnapshot {
+    pub used: usize,
+    pub in_view: usize,
+    pub average_snr: Option<f64>,                // dB, over the used satellites
+    pub constellations: BTreeMap<String, usize>,  // Used satellites per constellation
+}
+
+impl SatelliteSnapshot {
+    /// Aggregates of `satellites_info`, None when the source reports no satellites
+    pub fn from_gps_data(gps_data: &GpsData) -> Option<Self> {
+        if gps_data.satellites_info.is_empty() {
+            return None;
+        }
+        let mut constellations = BTreeMap::new();
+        for sat in gps_data.satellites_info.iter().filter(|sat| sat.used) {
+            *constellations.entry(sat.constellation.clone()).or_insert(0) += 1;
+        }
+        Some(Self {
+            used: gps_data.used_satellite_count().unwrap_or(0),
+            in_view: gps_data.satellites_info.len(),
+            average_snr: gps_data.average_used_snr(),
+            constellations,
+        })
+    }
+
+    /// Used count per constellation, e.g. "GLONASS:3 GPS:7"
+    pub fn constellation_summary(&self) -> String {
+        self.constellations.iter()
+            .map(|(constellation, count)| format!("{}:{}", constellation, count))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -52,16 +294,38 @@ pub struct TrackPoint {
     pub obd_throttle: Option<f32>,  // Throttle position %
     pub obd_load: Option<f32>,      // Engine load %
     pub obd_temp: Option<i16>,      // Coolant temp °C
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub satellites_snapshot: Option<SatelliteSnapshot>,
 }
 
 impl TrackPoint {
+    /// Create a track point with only position and time (e.g. from an imported file)
+    pub fn new(latitude: f64, longitude: f64, elevation: Option<f64>, timestamp: DateTime<Utc>) -> Self {
+        Self {
+            latitude,
+            longitude,
+            elevation,
+            timestamp,
+            speed: None,
+            course: None,
+            hdop: None,
+            satellites: None,
+            obd_speed: None,
+            obd_rpm: None,
+            obd_throttle: None,
+            obd_load: None,
+            obd_temp: None,
+            satellites_snapshot: None,
+        }
+    }
+
     pub fn from_gps_data(gps_data: &GpsData) -> Option<Self> {
         if let (Some(lat), Some(lon)) = (gps_data.latitude, gps_data.longitude) {
             Some(Self {
                 latitude: lat,
                 longitude: lon,
                 elevation: gps_data.altitude,
-                timestamp: gps_data.timestamp.unwrap_or_else(Utc::now),
+                timestamp: gps_data.corrected_timestamp().unwrap_or_else(Utc::now),
                 speed: gps_data.speed,
                 course: gps_data.course,
                 hdop: gps_data.hdop,
@@ -71,6 +335,7 @@ impl TrackPoint {
                 obd_throttle: None,
                 obd_load: None,
                 obd_temp: None,
+                satellites_snapshot: SatelliteSnapshot::from_gps_data(gps_data),
             })
         } else {
             None
@@ -79,17 +344,20 @@ impl TrackPoint {
 
     /// Calculate distance to another track point in meters using Haversine formula
     pub fn distance_to(&self, other: &TrackPoint) -> f64 {
-        let r = 6371000.0; // Earth radius in meters
-        let lat1 = self.latitude.to_radians();
-        let lat2 = other.latitude.to_radians();
-        let delta_lat = (other.latitude - self.latitude).to_radians();
-        let delta_lon = (other.longitude - self.longitude).to_radians();
+        haversine_distance(self.latitude, self.longitude, other.latitude, other.longitude)
+    }
 
-        let a = (delta_lat / 2.0).sin().powi(2)
-            + lat1.cos() * lat2.cos() * (delta_lon / 2.0).sin().powi(2);
-        let c = 2.0 * a.sqrt().atan2((1.0 - a).sqrt());
+    /// Whether moving from this point to `next` implies a believable speed.
+    /// Used to reject multipath/outlier jumps before they count as distance.
+    pub fn is_plausible_step(&self, next: &TrackPoint) -> bool {
+        let secs = (next.timestamp - self.timestamp).num_milliseconds() as f64 / 1000.0;
+        self.distance_to(next) <= MAX_PLAUSIBLE_SPEED * secs.max(1.0)
+    }
 
-        r * c
+    /// Average speed in m/s between this point and `next`, if time elapsed
+    fn step_speed(&self, next: &TrackPoint) -> Option<f64> {
+        let secs = (next.timestamp - self.timestamp).num_milliseconds() as f64 / 1000.0;
+        (secs > 0.0).then(|| self.distance_to(next) / secs)
     }
 }
 
@@ -131,6 +399,54 @@ impl TrackSegment {
         let end = self.points.last()?.timestamp;
         Some(end.signed_duration_since(start))
     }
+
+    /// Time spent moving faster than `MIN_MOVING_SPEED`
+    pub fn moving_time(&self) -> chrono::Duration {
+        self.points.windows(2)
+            .filter(|w| w[0].step_speed(&w[1]).is_some_and(|speed| speed >= MIN_MOVING_SPEED))
+            .map(|w| w[1].timestamp - w[0].timestamp)
+            .fold(chrono::Duration::zero(), |total, step| total + step)
+    }
+
+    /// Drop points that are within `tolerance_m` of the line through their
+    /// neighbours (Douglas-Peucker), always keeping the first and last point.
+    /// Returns the number of points removed.
+    pub fn simplify(&mut self, tolerance_m: f64) -> usize {
+        let len = self.points.len();
+        if len < 3 {
+            return 0;
+        }
+
+        let mut keep = vec![false; len];
+        keep[0] = true;
+        keep[len - 1] = true;
+        // An explicit stack, since dense imports are too deep to recurse over
+        let mut ranges = vec![(0, len - 1)];
+        while let Some((start, end)) = ranges.pop() {
+            let (a, b) = (&self.points[start], &self.points[end]);
+            let farthest = (start + 1..end)
+                .map(|i| (i, distance_to_line(&self.points[i], a, b)))
+                .max_by(|x, y| x.1.total_cmp(&y.1));
+            if let Some((i, _)) = farthest.filter(|&(_, distance)| distance > tolerance_m) {
+                keep[i] = true;
+                ranges.push((start, i));
+                ranges.push((i, end));
+            }
+        }
+
+        let mut keep = keep.into_iter();
+        self.points.retain(|_| keep.next().unwrap_or(true));
+        len - self.points.len()
+    }
+
+    /// Total climb and descent in meters between points that have elevations
+    pub fn elevation_change(&self) -> (f64, f64) {
+        let elevations: Vec<f64> = self.points.iter().filter_map(|p| p.elevation).collect();
+        elevations.windows(2).fold((0.0, 0.0), |(gain, loss), w| {
+            let delta = w[1] - w[0];
+            if delta > 0.0 { (gain + delta, loss) } else { (gain, loss - delta) }
+        })
+    }
 }
 
 impl Default for TrackSegment {
@@ -143,6 +459,10 @@ impl Default for TrackSegment {
 pub struct Track {
     pub name: String,
     pub segments: Vec<TrackSegment>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<[u8; 3]>,  // Drawn in this color on the map instead of the track style's
 }
 
 impl Track {
@@ -150,6 +470,18 @@ impl Track {
         Self {
             name,
             segments: vec![TrackSegment::new()],
+            description: None,
+            color: None,
+        }
+    }
+
+    /// Copy of the track's name, description and color with other segments
+    fn with_segments(&self, segments: Vec<TrackSegment>) -> Track {
+        Track {
+            name: self.name.clone(),
+            segments,
+            description: self.description.clone(),
+            color: self.color,
         }
     }
 
@@ -159,6 +491,13 @@ impl Track {
         }
     }
 
+    /// Move every point's timestamp by `offset`, e.g. onto the GPS clock
+    pub fn shift_times(&mut self, offset: chrono::Duration) {
+        for point in self.segments.iter_mut().flat_map(|s| s.points.iter_mut()) {
+            point.timestamp += offset;
+        }
+    }
+
     pub fn start_new_segment(&mut self) {
         self.segments.push(TrackSegment::new());
     }
@@ -167,6 +506,11 @@ impl Track {
         self.segments.iter().map(|s| s.len()).sum()
     }
 
+    /// Simplify every segment with `TrackSegment::simplify`. Returns the number of points removed.
+    pub fn simplify(&mut self, tolerance_m: f64) -> usize {
+        self.segments.iter_mut().map(|s| s.simplify(tolerance_m)).sum()
+    }
+
     pub fn total_distance(&self) -> f64 {
         self.segments.iter().map(|s| s.total_distance()).sum()
     }
@@ -180,6 +524,66 @@ impl Track {
         Some(last_point.timestamp.signed_duration_since(first_point.timestamp))
     }
 
+    /// Timestamp of the first recorded point
+    pub fn start_time(&self) -> Option<DateTime<Utc>> {
+        self.segments.iter()
+            .find_map(|s| s.points.first())
+            .map(|p| p.timestamp)
+    }
+
+    /// Timestamp of the last recorded point
+    pub fn end_time(&self) -> Option<DateTime<Utc>> {
+        self.segments.iter()
+            .rev()
+            .find_map(|s| s.points.last())
+            .map(|p| p.timestamp)
+    }
+
+    /// Position (lat, lon) at `time`, interpolated between neighbouring points.
+    /// In a gap between segments the position holds at the end of the earlier
+    /// segment. Returns `None` outside the track's time range.
+    pub fn position_at(&self, time: DateTime<Utc>) -> Option<(f64, f64)> {
+        let mut previous_end: Option<&TrackPoint> = None;
+
+        for segment in &self.segments {
+            let (first, last) = match (segment.points.first(), segment.points.last()) {
+                (Some(first), Some(last)) => (first, last),
+                _ => continue,
+            };
+
+            if time < first.timestamp {
+                // Before this segment: either in a gap or before the track starts
+                return previous_end.map(|p| (p.latitude, p.longitude));
+            }
+
+            if time <= last.timestamp {
+                if segment.points.len() == 1 {
+                    return Some((first.latitude, first.longitude));
+                }
+
+                for pair in segment.points.windows(2) {
+                    let (a, b) = (&pair[0], &pair[1]);
+                    if time >= a.timestamp && time <= b.timestamp {
+                        let span = (b.timestamp - a.timestamp).num_milliseconds() as f64;
+                        let t = if span > 0.0 {
+                            (time - a.timestamp).num_milliseconds() as f64 / span
+                        } else {
+                            0.0
+                        };
+                        return Some((
+                            a.latitude + (b.latitude - a.latitude) * t,
+                            a.longitude + (b.longitude - a.longitude) * t,
+                        ));
+                    }
+                }
+            }
+
+            previous_end = Some(last);
+        }
+
+        None
+    }
+
     pub fn average_speed(&self) -> Option<f64> {
         let distance = self.total_distance() / 1000.0; // km
         let duration = self.duration()?;
@@ -190,14 +594,151 @@ impl Track {
             None
         }
     }
+
+    /// Time spent moving, excluding stops and gaps between segments
+    pub fn moving_time(&self) -> chrono::Duration {
+        self.segments.iter()
+            .map(|s| s.moving_time())
+            .fold(chrono::Duration::zero(), |total, time| total + time)
+    }
+
+    /// Highest speed in km/h, from reported speeds or, failing that, from
+    /// the distance between consecutive points
+    pub fn max_speed(&self) -> Option<f64> {
+        let points = || self.segments.iter().flat_map(|s| s.points.iter());
+        let reported = points().filter_map(|p| p.speed).reduce(f64::max);
+
+        reported.or_else(|| {
+            self.segments.iter()
+                .flat_map(|s| s.points.windows(2))
+                .filter(|w| w[0].is_plausible_step(&w[1]))
+                .filter_map(|w| w[0].step_speed(&w[1]))
+                .map(|mps| mps * 3.6)
+                .reduce(f64::max)
+        })
+    }
+
+    /// Copy of the track with each segment passed through `matcher`
+    pub fn map_matched(&self, matcher: &dyn MapMatcher) -> Track {
+        let segments = self.segments.iter()
+            .map(|segment| TrackSegment {
+                points: matcher.match_points(&segment.points).unwrap_or_else(|| segment.points.clone()),
+            })
+            .collect();
+        self.with_segments(segments)
+    }
+
+    /// Copy of the track with latitude and longitude replaced by a centered
+    /// moving average over `window` points, to take out multipath wander.
+    /// The window shrinks toward the ends of each segment so the endpoints
+    /// stay where they were; elevation, time and the other fields are kept.
+    pub fn smooth(&self, window: usize) -> Track {
+        let half = window / 2;
+        let segments = self.segments.iter()
+            .map(|segment| {
+                let points = &segment.points;
+                let last = points.len().saturating_sub(1);
+                let smoothed = points.iter().enumerate()
+                    .map(|(i, point)| {
+                        let reach = half.min(i).min(last - i);
+                        let neighbours = &points[i - reach..=i + reach];
+                        let count = neighbours.len() as f64;
+                        let mut point = point.clone();
+                        point.latitude = neighbours.iter().map(|p| p.latitude).sum::<f64>() / count;
+                        point.longitude = neighbours.iter().map(|p| p.longitude).sum::<f64>() / count;
+                        point
+                    })
+                    .collect();
+                TrackSegment { points: smoothed }
+            })
+            .collect();
+        self.with_segments(segments)
+    }
+
+    /// Copy of the track with only the points from `start` to `end`
+    /// inclusive. Segments crossing either end are cut there and segments
+    /// entirely outside are dropped, so gaps inside the window are kept.
+    pub fn slice_by_time(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Track {
+        let segments = self.segments.iter()
+            .map(|segment| TrackSegment {
+                points: segment.points.iter()
+                    .filter(|p| p.timestamp >= start && p.timestamp <= end)
+                    .cloned()
+                    .collect(),
+            })
+            .filter(|segment| !segment.is_empty())
+            .collect();
+        self.with_segments(segments)
+    }
+
+    /// Total climb and descent in meters
+    pub fn elevation_gain_loss(&self) -> (f64, f64) {
+        self.segments.iter()
+            .map(|s| s.elevation_change())
+            .fold((0.0, 0.0), |(gain, loss), (g, l)| (gain + g, loss + l))
+    }
+
+    /// One-page Markdown summary of the track's statistics, with elevations in
+    /// `units`, coordinates to `coordinate_decimals` places and times in `time_zone`
+    pub fn summary_report(&self, units: DistanceUnits, coordinate_decimals: usize, time_zone: DisplayTimeZone) -> String {
+        let mut report = format!("# Track Summary: {}\n\n", self.name);
+        let mut line = |label: &str, value: String| {
+            report.push_str(&format!("- **{}:** {}\n", label, value));
+        };
+
+        line("Points", self.total_points().to_string());
+        line("Distance", format!("{:.2} km", self.total_distance() / 1000.0));
+        line("Duration", self.duration().map_or_else(|| "-".to_string(), format_duration));
+        line("Moving time", format_duration(self.moving_time()));
+        line("Average speed", self.average_speed().map_or_else(|| "-".to_string(), |s| format!("{:.1} km/h", s)));
+        line("Max speed", self.max_speed().map_or_else(|| "-".to_string(), |s| format!("{:.1} km/h", s)));
+
+        let (gain, loss) = self.elevation_gain_loss();
+        line("Elevation gain", units.format_altitude(gain, 0));
+        line("Elevation loss", units.format_altitude(loss, 0));
+
+        let first = self.segments.iter().find_map(|s| s.points.first());
+        let last = self.segments.iter().rev().find_map(|s| s.points.last());
+        let describe = |point: Option<&TrackPoint>| point.map_or_else(
+            || "-".to_string(),
+            |p| format!("{} at {}", GpsData::format_lat_lon(p.latitude, p.longitude, coordinate_decimals), time_zone.format_with_zone(&p.timestamp, "%Y-%m-%d %H:%M:%S")),
+        );
+        line("Start", describe(first));
+        line("End", describe(last));
+
+        report
+    }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+/// A planned, ordered list of points to navigate through, written as a GPX
+/// `<rte>`. Unlike a `Track` it says where to go, not where the receiver went.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Route {
+    pub name: String,
+    pub points: Vec<Waypoint>,  // Copies, so later edits to the waypoint list don't change the route
+}
+
+impl Route {
+    /// Route through `waypoints[i]` for each of `order`, skipping indices out of range
+    pub fn from_waypoints(name: String, waypoints: &[Waypoint], order: &[usize]) -> Self {
+        let points = order.iter().filter_map(|&i| waypoints.get(i).cloned()).collect();
+        Self { name, points }
+    }
+
+    /// Length in meters following the points in order
+    pub fn total_distance(&self) -> f64 {
+        self.points.windows(2).map(|pair| pair[0].distance_to(&pair[1])).sum()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum WaypointFormat {
     GPX,
     GeoJSON,
     KML,
     CSV,
+    JsonLines,
+    FIT,
 }
 
 impl WaypointFormat {
@@ -207,6 +748,8 @@ impl WaypointFormat {
             WaypointFormat::GeoJSON => "geojson",
             WaypointFormat::KML => "kml",
             WaypointFormat::CSV => "csv",
+            WaypointFormat::JsonLines => "jsonl",
+            WaypointFormat::FIT => "fit",
         }
     }
 
@@ -216,13 +759,38 @@ impl WaypointFormat {
             WaypointFormat::GeoJSON => "GeoJSON",
             WaypointFormat::KML => "KML (Keyhole)",
             WaypointFormat::CSV => "CSV",
+            WaypointFormat::JsonLines => "JSON Lines (track points)",
+            WaypointFormat::FIT => "FIT (fitness devices)",
         }
     }
 }
 
+/// The start of an export as it would be written, for checking the format
+/// and content before saving. Binary formats get a summary instead.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportPreview {
+    pub text: String,
+    pub total_bytes: usize,
+    pub total_lines: Option<usize>,  // None for binary formats
+    pub truncated: bool,
+}
+
+/// What `WaypointExporter::import_from_file` added
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImportSummary {
+    pub waypoints: usize,
+    pub tracks: usize,
+    pub points_before: usize,  // Track points in the file
+    pub points_after: usize,   // Track points kept after thinning
+}
+
+#[derive(Clone)]
 pub struct WaypointExporter {
     waypoints: Vec<Waypoint>,
     tracks: Vec<Track>,
+    routes: Vec<Route>,  // Only written to GPX
+    coordinate_decimals: Option<usize>,  // CSV/GeoJSON coordinate rounding, full precision when unset
+    csv_export: CsvExport,
 }
 
 impl WaypointExporter {
@@ -230,9 +798,24 @@ impl WaypointExporter {
         Self {
             waypoints: Vec::new(),
             tracks: Vec::new(),
+            routes: Vec::new(),
+            coordinate_decimals: None,
+            csv_export: CsvExport::default(),
         }
     }
 
+    /// Round coordinates in CSV and GeoJSON exports to this many places
+    /// (clamped to 3..=9), or `None` to write them at full precision.
+    /// GPX and KML are always written at full precision.
+    pub fn set_coordinate_decimals(&mut self, decimals: Option<usize>) {
+        self.coordinate_decimals = decimals.map(GpsData::coordinate_decimals);
+    }
+
+    /// Timestamp, unit and separator choices for CSV exports
+    pub fn set_csv_export(&mut self, options: CsvExport) {
+        self.csv_export = options;
+    }
+
     pub fn add_waypoint(&mut self, waypoint: Waypoint) {
         self.waypoints.push(waypoint);
     }
@@ -241,6 +824,23 @@ impl WaypointExporter {
         self.tracks.push(track);
     }
 
+    pub fn add_route(&mut self, route: Route) {
+        self.routes.push(route);
+    }
+
+    /// Remove the route at `index`, if there is one
+    pub fn remove_route(&mut self, index: usize) -> Option<Route> {
+        (index < self.routes.len()).then(|| self.routes.remove(index))
+    }
+
+    /// Enrichment step: correct the elevation of every stored waypoint,
+    /// e.g. after importing a file
+    pub fn correct_elevations(&mut self, corrector: &dyn ElevationCorrector) {
+        for waypoint in &mut self.waypoints {
+            waypoint.correct_elevation(corrector);
+        }
+    }
+
     pub fn waypoint_count(&self) -> usize {
         self.waypoints.len()
     }
@@ -249,9 +849,14 @@ impl WaypointExporter {
         self.tracks.len()
     }
 
+    pub fn route_count(&self) -> usize {
+        self.routes.len()
+    }
+
     pub fn clear(&mut self) {
         self.waypoints.clear();
         self.tracks.clear();
+        self.routes.clear();
     }
 
     pub fn clear_waypoints(&mut self) {
@@ -262,68 +867,697 @@ impl WaypointExporter {
         self.tracks.clear();
     }
 
-    pub fn export_to_file(&self, path: &Path, format: WaypointFormat) -> Result<()> {
-        if self.waypoints.is_empty() && self.tracks.is_empty() {
-            return Err(GpsError::Other("No waypoints or tracks to export".to_string()));
+    /// Replace the track at `index`, e.g. with a smoothed copy. Returns false
+    /// if there is no such track.
+    pub fn replace_track(&mut self, index: usize, track: Track) -> bool {
+        match self.tracks.get_mut(index) {
+            Some(existing) => {
+                *existing = track;
+                true
+            }
+            None => false,
         }
+    }
 
-        let content = match format {
-            WaypointFormat::GPX => self.to_gpx(),
-            WaypointFormat::GeoJSON => self.to_geojson()?,
-            WaypointFormat::KML => self.to_kml(),
-            WaypointFormat::CSV => self.to_csv(),
-        };
+    pub fn export_to_file(&self, path: &Path, format: WaypointFormat) -> Result<()> {
+        let content = self.export_bytes(format)?;
 
         let mut file = File::create(path)
-            .map_err(|e| GpsError::Io(e))?;
+            .map_err(GpsError::Io)?;
         
-        file.write_all(content.as_bytes())
-            .map_err(|e| GpsError::Io(e))?;
+        file.write_all(&content)
+            .map_err(GpsError::Io)?;
 
         Ok(())
     }
 
+    /// Export content for `format`, exactly as `export_to_file` writes it
+    pub fn export_bytes(&self, format: WaypointFormat) -> Result<Vec<u8>> {
+        let has_routes = format == WaypointFormat::GPX && !self.routes.is_empty();
+        if self.waypoints.is_empty() && self.tracks.is_empty() && !has_routes {
+            return Err(GpsError::Other("No waypoints or tracks to export".to_string()));
+        }
+
+        Ok(match format {
+            WaypointFormat::GPX => self.to_gpx().into_bytes(),
+            WaypointFormat::GeoJSON => self.to_geojson()?.into_bytes(),
+            WaypointFormat::KML => self.to_kml().into_bytes(),
+            WaypointFormat::CSV => self.to_csv(&self.csv_export).into_bytes(),
+            WaypointFormat::JsonLines if self.tracks.is_empty() => {
+                return Err(GpsError::Other("JSON Lines export only includes tracks, and there are none".to_string()));
+            }
+            WaypointFormat::JsonLines => self.to_jsonl()?.into_bytes(),
+            WaypointFormat::FIT => self.to_fit()?,
+        })
+    }
+
+    /// First `max_lines` lines of the export for `format`, or a summary of a FIT file
+    pub fn export_preview(&self, format: WaypointFormat, max_lines: usize) -> Result<ExportPreview> {
+        let content = self.export_bytes(format)?;
+        let total_bytes = content.len();
+
+        if format == WaypointFormat::FIT {
+            let points: usize = self.tracks.iter().map(Track::total_points).sum();
+            return Ok(ExportPreview {
+                text: format!(
+                    "Binary FIT activity file\n{} track(s), {} record(s), {} lap(s)\nWaypoints are not included in FIT exports",
+                    self.tracks.len(), points, self.tracks.len(),
+                ),
+                total_bytes,
+                total_lines: None,
+                truncated: false,
+            });
+        }
+
+        let text = String::from_utf8_lossy(&content);
+        let total_lines = text.lines().count();
+        Ok(ExportPreview {
+            text: text.lines().take(max_lines).collect::<Vec<_>>().join("\n"),
+            total_bytes,
+            total_lines: Some(total_lines),
+            truncated: total_lines > max_lines,
+        })
+    }
+
+    /// Export just `track` to `path`, with the same settings as a full export
+    pub fn export_track_to_file(&self, track: &Track, path: &Path, format: WaypointFormat) -> Result<()> {
+        let exporter = WaypointExporter {
+            waypoints: Vec::new(),
+            tracks: vec![track.clone()],
+            routes: Vec::new(),
+            coordinate_decimals: self.coordinate_decimals,
+            csv_export: self.csv_export.clone(),
+        };
+        exporter.export_to_file(path, format)
+    }
+
+    /// Distance (meters) and initial bearing (degrees) between every pair of
+    /// waypoints, indexed `[from][to]`. Bearings on the diagonal are `None`.
+    pub fn waypoint_matrix(&self) -> (Vec<Vec<f64>>, Vec<Vec<Option<f64>>>) {
+        let distances = self.waypoints.iter()
+            .map(|from| self.waypoints.iter().map(|to| from.distance_to(to)).collect())
+            .collect();
+        let bearings = self.waypoints.iter().enumerate()
+            .map(|(i, from)| {
+                self.waypoints.iter().enumerate()
+                    .map(|(j, to)| (i != j).then(|| from.bearing_to(to)))
+                    .collect()
+            })
+            .collect();
+
+        (distances, bearings)
+    }
+
+    /// CSV with a distance table followed by a bearing table, both with
+    /// waypoint names as row and column headers
+    pub fn to_matrix_csv(&self) -> String {
+        let (distances, bearings) = self.waypoint_matrix();
+        let header: Vec<String> = self.waypoints.iter().map(|w| Self::escape_csv(&w.name)).collect();
+
+        let mut csv = format!("Distance (m),{}\n", header.join(","));
+        for (name, row) in header.iter().zip(&distances) {
+            let cells: Vec<String> = row.iter().map(|d| format!("{:.1}", d)).collect();
+            csv.push_str(&format!("{},{}\n", name, cells.join(",")));
+        }
+
+        csv.push_str(&format!("\nBearing (deg),{}\n", header.join(",")));
+        for (name, row) in header.iter().zip(&bearings) {
+            let cells: Vec<String> = row.iter()
+                .map(|b| b.map_or(String::new(), |b| format!("{:.1}", b)))
+                .collect();
+            csv.push_str(&format!("{},{}\n", name, cells.join(",")));
+        }
+
+        csv
+    }
+
+    /// Write the waypoint distance/bearing matrix as CSV
+    pub fn export_matrix_to_file(&self, path: &Path) -> Result<()> {
+        if self.waypoints.len() < 2 {
+            return Err(GpsError::Other("Need at least two waypoints for a distance matrix".to_string()));
+        }
+
+        std::fs::write(path, self.to_matrix_csv())
+            .map_err(GpsError::Io)
+    }
+
+    /// Write a summary report for every track to a single Markdown file
+    pub fn export_summaries_to_file(&self, path: &Path, units: DistanceUnits, time_zone: DisplayTimeZone) -> Result<()> {
+        if self.tracks.is_empty() {
+            return Err(GpsError::Other("No tracks to summarize".to_string()));
+        }
+
+        let decimals = self.coordinate_decimals.unwrap_or(DEFAULT_COORDINATE_DECIMALS);
+        let reports: Vec<String> = self.tracks.iter().map(|t| t.summary_report(units, decimals, time_zone)).collect();
+        std::fs::write(path, reports.join("\n"))
+            .map_err(GpsError::Io)
+    }
+
+    /// Import waypoints and tracks from a file, appending them to the current data.
+    /// With `thin_tolerance_m`, the imported tracks are simplified to that
+    /// tolerance so dense files from other devices don't slow the map down.
+    pub fn import_from_file(&mut self, path: &Path, format: WaypointFormat, thin_tolerance_m: Option<f64>) -> Result<ImportSummary> {
+        let content = std::fs::read_to_string(path)
+            .map_err(GpsError::Io)?;
+
+        let first_track = self.tracks.len();
+        let (waypoints, tracks) = match format {
+            WaypointFormat::GPX => self.import_gpx(&content),
+            WaypointFormat::GeoJSON => self.import_geojson(&content),
+            WaypointFormat::KML => self.import_kml(&content),
+            WaypointFormat::CSV => self.import_csv(&content),
+            _ => Err(GpsError::Other(format!("Import from {} is not supported", format.display_name()))),
+        }?;
+
+        let imported = &mut self.tracks[first_track..];
+        let points_before = imported.iter().map(Track::total_points).sum();
+        if let Some(tolerance) = thin_tolerance_m {
+            for track in imported.iter_mut() {
+                track.simplify(tolerance);
+            }
+        }
+        let points_after = imported.iter().map(Track::total_points).sum();
+        Ok(ImportSummary { waypoints, tracks, points_before, points_after })
+    }
+
+    fn import_geojson(&mut self, content: &str) -> Result<(usize, usize)> {
+        let root: serde_json::Value = serde_json::from_str(content)
+            .map_err(GpsError::Json)?;
+
+        let features = match root.get("type").and_then(|t| t.as_str()) {
+            Some("FeatureCollection") => root.get("features")
+                .and_then(|f| f.as_array())
+                .cloned()
+                .unwrap_or_default(),
+            Some("Feature") => vec![root],
+            _ => return Err(GpsError::Parse("Expected a GeoJSON FeatureCollection".to_string())),
+        };
+
+        let mut waypoint_count = 0;
+        let mut track_count = 0;
+
+        for feature in &features {
+            let geometry = match feature.get("geometry") {
+                Some(g) => g,
+                None => continue,
+            };
+            let properties = feature.get("properties").cloned().unwrap_or_default();
+            let name = properties.get("name").and_then(|n| n.as_str());
+            let timestamp = properties.get("timestamp")
+                .and_then(|t| t.as_str())
+                .and_then(Self::parse_timestamp);
+
+            match geometry.get("type").and_then(|t| t.as_str()) {
+                Some("Point") => {
+                    let (lon, lat, ele) = match geometry.get("coordinates").and_then(Self::parse_position) {
+                        Some(pos) => pos,
+                        None => continue,
+                    };
+
+                    self.waypoints.push(Waypoint {
+                        name: name.map_or_else(|| format!("Waypoint {}", self.waypoints.len() + 1), str::to_string),
+                        latitude: lat,
+                        longitude: lon,
+                        elevation: properties.get("elevation").and_then(|e| e.as_f64()).or(ele),
+                        timestamp: timestamp.unwrap_or_else(Utc::now),
+                        description: properties.get("description").and_then(|d| d.as_str()).map(str::to_string),
+                        color: None,
+                    });
+                    waypoint_count += 1;
+                }
+                Some("LineString") => {
+                    let coordinates = match geometry.get("coordinates").and_then(|c| c.as_array()) {
+                        Some(c) => c,
+                        None => continue,
+                    };
+
+                    // Per-point times, if present (the "coordTimes" convention)
+                    let coord_times = properties.get("coordTimes").and_then(|t| t.as_array());
+                    let default_time = timestamp.unwrap_or_else(Utc::now);
+
+                    let mut track = Track::new(name.unwrap_or("Imported Track").to_string());
+                    for (i, coord) in coordinates.iter().enumerate() {
+                        if let Some((lon, lat, ele)) = Self::parse_position(coord) {
+                            let time = coord_times
+                                .and_then(|times| times.get(i))
+                                .and_then(|t| t.as_str())
+                                .and_then(Self::parse_timestamp)
+                                .unwrap_or(default_time);
+                            track.add_point(TrackPoint::new(lat, lon, ele, time));
+                        }
+                    }
+
+                    if track.total_points() > 0 {
+                        self.tracks.push(track);
+                        track_count += 1;
+                    }
+                }
+                _ => {
+                    // Other geometry types are not imported
+                }
+            }
+        }
+
+        Ok((waypoint_count, track_count))
+    }
+
+    /// Import a CSV export, with either separator and any of the timestamp and
+    /// unit options. Consecutive rows of a track make one track, split into
+    /// segments where the `segment` column changes. Satellite snapshot columns
+    /// are not read back.
+    fn import_csv(&mut self, content: &str) -> Result<(usize, usize)> {
+        let separator = if content.starts_with("type;") { ';' } else { ',' };
+        let mut records = Self::csv_records(content, separator).into_iter();
+        let header = records.next().unwrap_or_default();
+        let column = |name: &str| header.iter().position(|h| h.trim() == name);
+        let (Some(type_column), Some(lat_column), Some(lon_column)) = (column("type"), column("latitude"), column("longitude")) else {
+            return Err(GpsError::Parse("Expected a CSV export with type, latitude and longitude columns".to_string()));
+        };
+        let (elevation_column, feet) = match column("elevation_ft") {
+            Some(c) => (Some(c), true),
+            None => (column("elevation"), false),
+        };
+        let (speed_column, speed_units) = [
+            ("speed", DistanceUnits::Metric),
+            ("speed_mph", DistanceUnits::Imperial),
+            ("speed_kn", DistanceUnits::Nautical),
+        ]
+        .into_iter()
+        .find_map(|(name, units)| column(name).map(|c| (Some(c), units)))
+        .unwrap_or((None, DistanceUnits::Metric));
+        let (name_column, time_column, description_column) = (column("name"), column("timestamp"), column("description"));
+        let (course_column, hdop_column, satellites_column, segment_column) =
+            (column("course"), column("hdop"), column("satellites"), column("segment"));
+
+        let mut waypoint_count = 0;
+        let mut track_count = 0;
+        let mut last_track_row: Option<(String, String)> = None;  // Track name and segment
+
+        for record in records {
+            let text = |c: Option<usize>| c.and_then(|c| record.get(c)).map(|v| v.trim()).filter(|v| !v.is_empty());
+            let number = |c: Option<usize>| -> Option<f64> {
+                let value = text(c)?;
+                if separator == ';' { value.replace(',', ".").parse().ok() } else { value.parse().ok() }
+            };
+            let (Some(lat), Some(lon)) = (number(Some(lat_column)), number(Some(lon_column))) else {
+                continue;
+            };
+            let elevation = number(elevation_column)
+                .map(|e| if feet { e / DistanceUnits::Imperial.altitude_from_meters(1.0) } else { e });
+            let timestamp = text(time_column).and_then(Self::parse_csv_timestamp).unwrap_or_else(Utc::now);
+
+            match text(Some(type_column)) {
+                Some("waypoint") => {
+                    self.waypoints.push(Waypoint {
+                        name: text(name_column).map_or_else(|| format!("Waypoint {}", self.waypoints.len() + 1), str::to_string),
+                        latitude: lat,
+                        longitude: lon,
+                        elevation,
+                        timestamp,
+                        description: text(description_column).map(str::to_string),
+                        color: None,
+                    });
+                    waypoint_count += 1;
+                    last_track_row = None;
+                }
+                Some("track") => {
+                    let name = text(name_column).unwrap_or("Imported Track").to_string();
+                    let segment = text(segment_column).unwrap_or_default().to_string();
+                    match (&last_track_row, self.tracks.last_mut()) {
+                        (Some((last_name, last_segment)), Some(track)) if *last_name == name => {
+                            if *last_segment != segment {
+                                track.start_new_segment();
+                            }
+                        }
+                        _ => {
+                            self.tracks.push(Track::new(name.clone()));
+                            track_count += 1;
+                        }
+                    }
+
+                    let mut point = TrackPoint::new(lat, lon, elevation, timestamp);
+                    point.speed = number(speed_column).map(|s| s / speed_units.speed_from_kmh(1.0));
+                    point.course = number(course_column);
+                    point.hdop = number(hdop_column);
+                    point.satellites = text(satellites_column).and_then(|s| s.parse().ok());
+                    if let Some(track) = self.tracks.last_mut() {
+                        track.add_point(point);
+                    }
+                    last_track_row = Some((name, segment));
+                }
+                _ => {}
+            }
+        }
+
+        Ok((waypoint_count, track_count))
+    }
+
+    /// Split CSV into rows of fields, honouring quoted fields with doubled
+    /// quotes, separators and line breaks inside
+    fn csv_records(content: &str, separator: char) -> Vec<Vec<String>> {
+        let mut records = Vec::new();
+        let (mut record, mut field, mut quoted) = (Vec::new(), String::new(), false);
+        let mut chars = content.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '"' if quoted && chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                '"' => quoted = !quoted,
+                '\r' if !quoted => {}
+                '\n' if !quoted => {
+                    record.push(std::mem::take(&mut field));
+                    records.push(std::mem::take(&mut record));
+                }
+                c if c == separator && !quoted => record.push(std::mem::take(&mut field)),
+                c => field.push(c),
+            }
+        }
+        if !field.is_empty() || !record.is_empty() {
+            record.push(field);
+            records.push(record);
+        }
+        records
+    }
+
+    /// A CSV timestamp in any `CsvTimestamp` format
+    fn parse_csv_timestamp(s: &str) -> Option<DateTime<Utc>> {
+        if let Some(time) = Self::parse_timestamp(s) {
+            return Some(time);
+        }
+        if let Ok(epoch) = s.parse::<i64>() {
+            return DateTime::from_timestamp(epoch, 0);
+        }
+        chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S")
+            .ok()
+            .and_then(|local| local.and_local_timezone(chrono::Local).earliest())
+            .map(|time| time.with_timezone(&Utc))
+    }
+
+    /// Parse a GeoJSON position `[lon, lat]` or `[lon, lat, elevation]`
+    fn parse_position(value: &serde_json::Value) -> Option<(f64, f64, Option<f64>)> {
+        let coords = value.as_array()?;
+        let lon = coords.first()?.as_f64()?;
+        let lat = coords.get(1)?.as_f64()?;
+        let ele = coords.get(2).and_then(|e| e.as_f64());
+        Some((lon, lat, ele))
+    }
+
+    fn parse_timestamp(s: &str) -> Option<DateTime<Utc>> {
+        DateTime::parse_from_rfc3339(s).ok().map(|t| t.with_timezone(&Utc))
+    }
+
+    /// Import GPX `<wpt>` waypoints and `<trk>` tracks, with their names,
+    /// descriptions, elevations and times. Routes and extensions are skipped.
+    fn import_gpx(&mut self, content: &str) -> Result<(usize, usize)> {
+        if !content.contains("<gpx") {
+            return Err(GpsError::Parse("Expected a GPX document".to_string()));
+        }
+        // Position from the lat/lon attributes, elevation and time (now when missing) from the body
+        let point = |attributes: &str, body: &str| -> Option<TrackPoint> {
+            let lat = Self::xml_attribute(attributes, "lat")?.parse().ok()?;
+            let lon = Self::xml_attribute(attributes, "lon")?.parse().ok()?;
+            let ele = Self::xml_text(body, "ele").and_then(|e| e.parse().ok());
+            let time = Self::xml_text(body, "time").and_then(|t| Self::parse_timestamp(&t));
+            Some(TrackPoint::new(lat, lon, ele, time.unwrap_or_else(Utc::now)))
+        };
+
+        let mut waypoint_count = 0;
+        for (attributes, body) in Self::xml_elements_with_attributes(content, "wpt") {
+            let body = body.unwrap_or("");
+            let Some(position) = point(attributes, body) else {
+                continue;
+            };
+            self.waypoints.push(Waypoint {
+                name: Self::xml_text(body, "name").unwrap_or_else(|| format!("Waypoint {}", self.waypoints.len() + 1)),
+                latitude: position.latitude,
+                longitude: position.longitude,
+                elevation: position.elevation,
+                timestamp: position.timestamp,
+                description: Self::xml_text(body, "desc"),
+                color: None,
+            });
+            waypoint_count += 1;
+        }
+
+        let mut track_count = 0;
+        for trk in Self::xml_elements(content, "trk") {
+            // The track's own name and description come before its segments
+            let header = &trk[..trk.find("<trkseg").unwrap_or(trk.len())];
+            let mut track = Track::new(Self::xml_text(header, "name").unwrap_or_else(|| "Imported Track".to_string()));
+            track.description = Self::xml_text(header, "desc");
+            track.segments.clear();
+
+            for trkseg in Self::xml_elements(trk, "trkseg") {
+                let mut segment = TrackSegment::new();
+                for (attributes, body) in Self::xml_elements_with_attributes(trkseg, "trkpt") {
+                    if let Some(position) = point(attributes, body.unwrap_or("")) {
+                        segment.add_point(position);
+                    }
+                }
+                if !segment.is_empty() {
+                    track.segments.push(segment);
+                }
+            }
+
+            if !track.segments.is_empty() {
+                self.tracks.push(track);
+                track_count += 1;
+            }
+        }
+
+        Ok((waypoint_count, track_count))
+    }
+
+    /// Import KML Placemarks: `<Point>` becomes a waypoint, while each
+    /// `<LineString>` or `<gx:Track>` becomes a segment of a track.
+    fn import_kml(&mut self, content: &str) -> Result<(usize, usize)> {
+        if !content.contains("<kml") {
+            return Err(GpsError::Parse("Expected a KML document".to_string()));
+        }
+
+        let mut waypoint_count = 0;
+        let mut track_count = 0;
+
+        for placemark in Self::xml_elements(content, "Placemark") {
+            let name = Self::xml_text(placemark, "name");
+            let description = Self::xml_text(placemark, "description");
+            let timestamp = Self::xml_element(placemark, "TimeStamp")
+                .and_then(|t| Self::xml_text(t, "when"))
+                .and_then(|t| Self::parse_timestamp(&t));
+
+            let mut track = Track::new(name.clone().unwrap_or_else(|| "Imported Track".to_string()));
+            track.description = description.clone();
+            track.segments.clear();
+
+            for line in Self::xml_elements(placemark, "LineString") {
+                let default_time = timestamp.unwrap_or_else(Utc::now);
+                let mut segment = TrackSegment::new();
+                for (lon, lat, ele) in Self::xml_text(line, "coordinates").iter().flat_map(|c| Self::parse_kml_coordinates(c)) {
+                    segment.add_point(TrackPoint::new(lat, lon, ele, default_time));
+                }
+                if !segment.is_empty() {
+                    track.segments.push(segment);
+                }
+            }
+
+            for gx_track in Self::xml_elements(placemark, "gx:Track") {
+                let times = Self::xml_elements(gx_track, "when");
+                let mut segment = TrackSegment::new();
+                for (i, coord) in Self::xml_elements(gx_track, "gx:coord").into_iter().enumerate() {
+                    // gx:coord separates lon lat alt with spaces instead of commas
+                    let mut parts = coord.split_whitespace().map(str::parse::<f64>);
+                    let (Some(Ok(lon)), Some(Ok(lat))) = (parts.next(), parts.next()) else {
+                        continue;
+                    };
+                    let ele = parts.next().and_then(|e| e.ok());
+                    let time = times.get(i)
+                        .and_then(|t| Self::parse_timestamp(t.trim()))
+                        .or(timestamp)
+                        .unwrap_or_else(Utc::now);
+                    segment.add_point(TrackPoint::new(lat, lon, ele, time));
+                }
+                if !segment.is_empty() {
+                    track.segments.push(segment);
+                }
+            }
+
+            if !track.segments.is_empty() {
+                self.tracks.push(track);
+                track_count += 1;
+                continue;
+            }
+
+            let position = Self::xml_element(placemark, "Point")
+                .and_then(|point| Self::xml_text(point, "coordinates"))
+                .and_then(|c| Self::parse_kml_coordinates(&c).into_iter().next());
+            if let Some((lon, lat, ele)) = position {
+                self.waypoints.push(Waypoint {
+                    name: name.unwrap_or_else(|| format!("Waypoint {}", self.waypoints.len() + 1)),
+                    latitude: lat,
+                    longitude: lon,
+                    elevation: ele,
+                    timestamp: timestamp.unwrap_or_else(Utc::now),
+                    description,
+                    color: None,
+                });
+                waypoint_count += 1;
+            }
+        }
+
+        Ok((waypoint_count, track_count))
+    }
+
+    /// Parse a KML coordinate list: whitespace-separated `lon,lat[,alt]` tuples
+    fn parse_kml_coordinates(text: &str) -> Vec<(f64, f64, Option<f64>)> {
+        text.split_whitespace()
+            .filter_map(|tuple| {
+                let mut parts = tuple.split(',').map(str::parse::<f64>);
+                let lon = parts.next()?.ok()?;
+                let lat = parts.next()?.ok()?;
+                let ele = parts.next().and_then(|e| e.ok());
+                Some((lon, lat, ele))
+            })
+            .collect()
+    }
+
+    /// Inner content of every `<tag>` element in `content`. Good enough for
+    /// KML and GPX, where the elements we read don't nest inside themselves.
+    fn xml_elements<'a>(content: &'a str, tag: &str) -> Vec<&'a str> {
+        Self::xml_elements_with_attributes(content, tag).into_iter()
+            .filter_map(|(_, body)| body)
+            .collect()
+    }
+
+    /// Attributes from the opening tag and inner content (None when
+    /// self-closing) of every `<tag>` element in `content`
+    fn xml_elements_with_attributes<'a>(content: &'a str, tag: &str) -> Vec<(&'a str, Option<&'a str>)> {
+        let open = format!("<{}", tag);
+        let close = format!("</{}>", tag);
+        let mut elements = Vec::new();
+        let mut rest = content;
+
+        while let Some(start) = rest.find(&open) {
+            let after = &rest[start + open.len()..];
+            // Make sure we matched the whole tag name, not a prefix of a longer one
+            if !after.starts_with(['>', ' ', '\t', '\n', '\r', '/']) {
+                rest = after;
+                continue;
+            }
+            let Some(tag_end) = after.find('>') else { break };
+            if let Some(attributes) = after[..tag_end].strip_suffix('/') {
+                elements.push((attributes, None));
+                rest = &after[tag_end + 1..];
+                continue;
+            }
+            let body = &after[tag_end + 1..];
+            let Some(end) = body.find(&close) else { break };
+            elements.push((&after[..tag_end], Some(&body[..end])));
+            rest = &body[end + close.len()..];
+        }
+
+        elements
+    }
+
+    /// Unescaped value of the `name` attribute in an opening tag's attributes
+    fn xml_attribute(attributes: &str, name: &str) -> Option<String> {
+        let mut rest = attributes;
+        while let Some(start) = rest.find(name) {
+            let before = &rest[..start];
+            let after = rest[start + name.len()..].trim_start();
+            rest = &rest[start + name.len()..];
+            if !before.is_empty() && !before.ends_with(char::is_whitespace) {
+                continue;
+            }
+            let Some(value) = after.strip_prefix('=').map(str::trim_start) else { continue };
+            let quote = value.chars().next().filter(|&c| c == '"' || c == '\'')?;
+            let value = &value[1..];
+            return value.find(quote).map(|end| Self::unescape_xml(&value[..end]));
+        }
+        None
+    }
+
+    fn xml_element<'a>(content: &'a str, tag: &str) -> Option<&'a str> {
+        Self::xml_elements(content, tag).into_iter().next()
+    }
+
+    /// Trimmed, unescaped text of the first `<tag>` element
+    fn xml_text(content: &str, tag: &str) -> Option<String> {
+        let text = Self::xml_element(content, tag)?.trim();
+        let text = text.strip_prefix("<![CDATA[")
+            .and_then(|t| t.strip_suffix("]]>"))
+            .map(str::to_string)
+            .unwrap_or_else(|| Self::unescape_xml(text));
+        Some(text)
+    }
+
+    fn unescape_xml(s: &str) -> String {
+        s.replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&quot;", "\"")
+            .replace("&apos;", "'")
+            .replace("&amp;", "&")
+    }
+
+    /// A `<wpt>` or `<rtept>` element, which share the same content
+    fn push_gpx_point(gpx: &mut String, tag: &str, waypoint: &Waypoint, indent: &str) {
+        gpx.push_str(&format!(
+            "{indent}<{tag} lat=\"{}\" lon=\"{}\">\n{indent}  <name>{}</name>\n",
+            waypoint.latitude,
+            waypoint.longitude,
+            Self::escape_xml(&waypoint.name)
+        ));
+
+        if let Some(ele) = waypoint.elevation {
+            gpx.push_str(&format!("{indent}  <ele>{}</ele>\n", ele));
+        }
+
+        gpx.push_str(&format!(
+            "{indent}  <time>{}</time>\n",
+            waypoint.timestamp.to_rfc3339()
+        ));
+
+        if let Some(ref desc) = waypoint.description {
+            gpx.push_str(&format!(
+                "{indent}  <desc>{}</desc>\n",
+                Self::escape_xml(desc)
+            ));
+        }
+
+        gpx.push_str(&format!("{indent}</{tag}>\n"));
+    }
+
     fn to_gpx(&self) -> String {
         let mut gpx = String::from(r#"<?xml version="1.0" encoding="UTF-8"?>
 <gpx version="1.1" creator="GPS Monitor" 
      xmlns="http://www.topografix.com/GPX/1/1"
-     xmlns:obd="http://gpsmonitor.com/obd/1.0">
+     xmlns:obd="http://gpsmonitor.com/obd/1.0"
+     xmlns:sat="http://gpsmonitor.com/satellites/1.0">
 "#);
 
         // Add waypoints
-        for waypoint in &self.waypoints {
-            gpx.push_str(&format!(
-                r#"  <wpt lat="{}" lon="{}">
-    <name>{}</name>
-"#,
-                waypoint.latitude,
-                waypoint.longitude,
-                Self::escape_xml(&waypoint.name)
-            ));
-
-            if let Some(ele) = waypoint.elevation {
-                gpx.push_str(&format!("    <ele>{}</ele>\n", ele));
-            }
-
-            gpx.push_str(&format!(
-                "    <time>{}</time>\n",
-                waypoint.timestamp.to_rfc3339()
-            ));
+        for waypoint in &self.waypoints {
+            Self::push_gpx_point(&mut gpx, "wpt", waypoint, "  ");
+        }
 
-            if let Some(ref desc) = waypoint.description {
-                gpx.push_str(&format!(
-                    "    <desc>{}</desc>\n",
-                    Self::escape_xml(desc)
-                ));
+        // Add routes, which the schema puts between waypoints and tracks
+        for route in &self.routes {
+            gpx.push_str("  <rte>\n");
+            gpx.push_str(&format!("    <name>{}</name>\n", Self::escape_xml(&route.name)));
+            for point in &route.points {
+                Self::push_gpx_point(&mut gpx, "rtept", point, "    ");
             }
-
-            gpx.push_str("  </wpt>\n");
+            gpx.push_str("  </rte>\n");
         }
 
         // Add tracks
         for track in &self.tracks {
             gpx.push_str("  <trk>\n");
             gpx.push_str(&format!("    <name>{}</name>\n", Self::escape_xml(&track.name)));
+            if let Some(ref desc) = track.description {
+                gpx.push_str(&format!("    <desc>{}</desc>\n", Self::escape_xml(desc)));
+            }
 
             for segment in &track.segments {
                 if segment.is_empty() {
@@ -350,7 +1584,8 @@ impl WaypointExporter {
                     // Add GPS quality data
                     if point.speed.is_some() || point.course.is_some() || 
                        point.hdop.is_some() || point.satellites.is_some() ||
-                       point.obd_speed.is_some() || point.obd_rpm.is_some() {
+                       point.obd_speed.is_some() || point.obd_rpm.is_some() ||
+                       point.satellites_snapshot.is_some() {
                         gpx.push_str("        <extensions>\n");
 
                         if let Some(speed) = point.speed {
@@ -398,6 +1633,22 @@ impl WaypointExporter {
                             gpx.push_str("          </obd:vehicle_data>\n");
                         }
 
+                        if let Some(snapshot) = &point.satellites_snapshot {
+                            let average_snr = snapshot.average_snr
+                                .map_or(String::new(), |snr| format!(" average_snr=\"{:.1}\"", snr));
+                            gpx.push_str(&format!(
+                                "          <sat:snapshot used=\"{}\" in_view=\"{}\"{}>\n",
+                                snapshot.used, snapshot.in_view, average_snr
+                            ));
+                            for (constellation, count) in &snapshot.constellations {
+                                gpx.push_str(&format!(
+                                    "            <sat:constellation name=\"{}\" used=\"{}\"/>\n",
+                                    Self::escape_xml(constellation), count
+                                ));
+                            }
+                            gpx.push_str("          </sat:snapshot>\n");
+                        }
+
                         gpx.push_str("        </extensions>\n");
                     }
 
@@ -437,34 +1688,43 @@ impl WaypointExporter {
                 "type": "Feature",
                 "geometry": {
                     "type": "Point",
-                    "coordinates": [wp.longitude, wp.latitude, wp.elevation.unwrap_or(0.0)]
+                    "coordinates": self.geojson_position(wp.longitude, wp.latitude, wp.elevation)
                 },
                 "properties": properties
             }));
         }
 
-        // Add tracks as LineString features
+        // Add tracks as LineString features, one per segment; a segment after a
+        // pause notes how long the recording was paused
         for track in &self.tracks {
-            for segment in &track.segments {
-                if segment.is_empty() {
+            let mut previous_end: Option<DateTime<Utc>> = None;
+            for (index, segment) in track.segments.iter().enumerate() {
+                let (Some(first), Some(last)) = (segment.points.first(), segment.points.last()) else {
                     continue;
-                }
+                };
 
                 let coordinates: Vec<serde_json::Value> = segment.points.iter().map(|pt| {
-                    serde_json::json!([pt.longitude, pt.latitude, pt.elevation.unwrap_or(0.0)])
+                    self.geojson_position(pt.longitude, pt.latitude, pt.elevation)
                 }).collect();
 
+                let mut properties = serde_json::json!({
+                    "name": track.name,
+                    "type": "track",
+                    "points": segment.len(),
+                    "segment": index + 1
+                });
+                if let Some(end) = previous_end {
+                    properties["pause_before_s"] = serde_json::json!((first.timestamp - end).num_seconds());
+                }
+                previous_end = Some(last.timestamp);
+
                 features.push(serde_json::json!({
                     "type": "Feature",
                     "geometry": {
                         "type": "LineString",
                         "coordinates": coordinates
                     },
-                    "properties": {
-                        "name": track.name,
-                        "type": "track",
-                        "points": segment.len()
-                    }
+                    "properties": properties
                 }));
             }
         }
@@ -475,7 +1735,7 @@ impl WaypointExporter {
         });
 
         serde_json::to_string_pretty(&feature_collection)
-            .map_err(|e| GpsError::Json(e))
+            .map_err(GpsError::Json)
     }
 
     fn to_kml(&self) -> String {
@@ -507,10 +1767,8 @@ impl WaypointExporter {
 
             kml.push_str("      <Point>\n");
             kml.push_str(&format!(
-                "        <coordinates>{},{},{}</coordinates>\n",
-                waypoint.longitude,
-                waypoint.latitude,
-                waypoint.elevation.unwrap_or(0.0)
+                "        <coordinates>{}</coordinates>\n",
+                Self::kml_coordinate(waypoint.longitude, waypoint.latitude, waypoint.elevation)
             ));
             kml.push_str("      </Point>\n");
             kml.push_str("    </Placemark>\n");
@@ -520,9 +1778,14 @@ impl WaypointExporter {
         for track in &self.tracks {
             kml.push_str("    <Placemark>\n");
             kml.push_str(&format!("      <name>{}</name>\n", Self::escape_xml(&track.name)));
+            if let Some(ref desc) = track.description {
+                kml.push_str(&format!("      <description>{}</description>\n", Self::escape_xml(desc)));
+            }
+            // KML colors are alpha, blue, green, red
+            let [r, g, b] = track.color.unwrap_or([255, 0, 0]);
             kml.push_str("      <Style>\n");
             kml.push_str("        <LineStyle>\n");
-            kml.push_str("          <color>ff0000ff</color>\n");
+            kml.push_str(&format!("          <color>ff{:02x}{:02x}{:02x}</color>\n", b, g, r));
             kml.push_str("          <width>4</width>\n");
             kml.push_str("        </LineStyle>\n");
             kml.push_str("      </Style>\n");
@@ -537,10 +1800,8 @@ impl WaypointExporter {
 
                 for point in &segment.points {
                     kml.push_str(&format!(
-                        "          {},{},{}\n",
-                        point.longitude,
-                        point.latitude,
-                        point.elevation.unwrap_or(0.0)
+                        "          {}\n",
+                        Self::kml_coordinate(point.longitude, point.latitude, point.elevation)
                     ));
                 }
 
@@ -555,38 +1816,107 @@ impl WaypointExporter {
         kml
     }
 
-    fn to_csv(&self) -> String {
-        let mut csv = String::from("type,name,latitude,longitude,elevation,timestamp,description,speed,course,hdop,satellites\n");
+    fn to_csv(&self, options: &CsvExport) -> String {
+        let separator = if options.decimal_comma { ";" } else { "," };
+        let number = |value: String| if options.decimal_comma { value.replace('.', ",") } else { value };
+        let text = |value: &str| Self::escape_csv_field(value, separator);
+        // Converted values are rounded; metric ones are written as recorded
+        let elevation = |meters: Option<f64>| match (meters, options.units) {
+            (Some(m), DistanceUnits::Imperial) => number(format!("{:.2}", options.units.altitude_from_meters(m))),
+            (Some(m), _) => number(m.to_string()),
+            (None, _) => String::new(),
+        };
+        let speed = |kmh: Option<f64>| match (kmh, options.units) {
+            (Some(s), DistanceUnits::Metric) => number(s.to_string()),
+            (Some(s), units) => number(format!("{:.2}", units.speed_from_kmh(s))),
+            (None, _) => String::new(),
+        };
+
+        let elevation_column = match options.units {
+            DistanceUnits::Imperial => "elevation_ft",
+            _ => "elevation",
+        };
+        let speed_column = match options.units {
+            DistanceUnits::Metric => "speed",
+            DistanceUnits::Imperial => "speed_mph",
+            DistanceUnits::Nautical => "speed_kn",
+        };
+        let mut header = vec![
+            "type", "name", "latitude", "longitude", elevation_column, "timestamp",
+            "description", speed_column, "course", "hdop", "satellites",
+        ];
+        // Satellite snapshot columns only when recorded, so other files keep their layout
+        let snapshots = self.tracks.iter()
+            .flat_map(|t| t.segments.iter().flat_map(|s| s.points.iter()))
+            .any(|point| point.satellites_snapshot.is_some());
+        if options.segment_column {
+            header.push("segment");
+        }
+        if snapshots {
+            header.extend(["satellites_used", "satellites_in_view", "average_snr", "constellations"]);
+        }
+        let snapshot_cells = |snapshot: Option<&SatelliteSnapshot>| -> Vec<String> {
+            match snapshot {
+                _ if !snapshots => Vec::new(),
+                None => vec![String::new(); 4],
+                Some(snapshot) => vec![
+                    snapshot.used.to_string(),
+                    snapshot.in_view.to_string(),
+                    snapshot.average_snr.map_or(String::new(), |snr| number(format!("{:.1}", snr))),
+                    text(&snapshot.constellation_summary()),
+                ],
+            }
+        };
+        let mut csv = header.join(separator);
+        csv.push('\n');
 
         // Add waypoints
         for waypoint in &self.waypoints {
-            csv.push_str(&format!(
-                "waypoint,{},{},{},{},{},{},,,,,\n",
-                Self::escape_csv(&waypoint.name),
-                waypoint.latitude,
-                waypoint.longitude,
-                waypoint.elevation.map_or(String::new(), |e| e.to_string()),
-                waypoint.timestamp.to_rfc3339(),
-                waypoint.description.as_ref().map_or(String::new(), |d| Self::escape_csv(d))
-            ));
+            let mut row = vec![
+                "waypoint".to_string(),
+                text(&waypoint.name),
+                number(self.csv_coordinate(waypoint.latitude)),
+                number(self.csv_coordinate(waypoint.longitude)),
+                elevation(waypoint.elevation),
+                options.timestamp.format(&waypoint.timestamp),
+                waypoint.description.as_deref().map_or(String::new(), text),
+                String::new(),
+                String::new(),
+                String::new(),
+                String::new(),
+            ];
+            if options.segment_column {
+                row.push(String::new());
+            }
+            row.extend(snapshot_cells(None));
+            csv.push_str(&row.join(separator));
+            csv.push('\n');
         }
 
         // Add track points
         for track in &self.tracks {
-            for segment in &track.segments {
+            for (index, segment) in track.segments.iter().enumerate() {
                 for point in &segment.points {
-                    csv.push_str(&format!(
-                        "track,{},{},{},{},{},,,{},{},{},{}\n",
-                        Self::escape_csv(&track.name),
-                        point.latitude,
-                        point.longitude,
-                        point.elevation.map_or(String::new(), |e| e.to_string()),
-                        point.timestamp.to_rfc3339(),
-                        point.speed.map_or(String::new(), |s| s.to_string()),
-                        point.course.map_or(String::new(), |c| c.to_string()),
-                        point.hdop.map_or(String::new(), |h| h.to_string()),
-                        point.satellites.map_or(String::new(), |s| s.to_string())
-                    ));
+                    let mut row = vec![
+                        "track".to_string(),
+                        text(&track.name),
+                        number(self.csv_coordinate(point.latitude)),
+                        number(self.csv_coordinate(point.longitude)),
+                        elevation(point.elevation),
+                        options.timestamp.format(&point.timestamp),
+                        String::new(),
+                        speed(point.speed),
+                        point.course.map_or(String::new(), |c| number(c.to_string())),
+                        point.hdop.map_or(String::new(), |h| number(h.to_string())),
+                        point.satellites.map_or(String::new(), |s| s.to_string()),
+                    ];
+                    // Numbered from 1; a new number is where the recording was paused
+                    if options.segment_column {
+                        row.push((index + 1).to_string());
+                    }
+                    row.extend(snapshot_cells(point.satellites_snapshot.as_ref()));
+                    csv.push_str(&row.join(separator));
+                    csv.push('\n');
                 }
             }
         }
@@ -594,6 +1924,184 @@ impl WaypointExporter {
         csv
     }
 
+    /// One JSON object per track point, tagged with its track name and
+    /// track/segment indices so lines can be streamed and appended independently
+    fn to_jsonl(&self) -> Result<String> {
+        let mut jsonl = String::new();
+
+        for (track_index, track) in self.tracks.iter().enumerate() {
+            for (segment_index, segment) in track.segments.iter().enumerate() {
+                for point in &segment.points {
+                    let mut line = serde_json::to_value(point)?;
+                    if let Some(fields) = line.as_object_mut() {
+                        fields.insert("track".to_string(), serde_json::json!(track.name));
+                        fields.insert("track_index".to_string(), serde_json::json!(track_index));
+                        fields.insert("segment".to_string(), serde_json::json!(segment_index));
+                    }
+                    jsonl.push_str(&serde_json::to_string(&line)?);
+                    jsonl.push('\n');
+                }
+            }
+        }
+
+        Ok(jsonl)
+    }
+
+    /// FIT activity file: one record message per track point, a lap per
+    /// track and a session covering them all. Waypoints are not included.
+    pub fn to_fit(&self) -> Result<Vec<u8>> {
+        let points: Vec<&TrackPoint> = self.tracks.iter()
+            .flat_map(|t| t.segments.iter().flat_map(|s| s.points.iter()))
+            .collect();
+        let (Some(first), Some(last)) = (points.first(), points.last()) else {
+            return Err(GpsError::Other("FIT export only includes track points, and there are none".to_string()));
+        };
+        let start = fit_time(&first.timestamp);
+        let end = fit_time(&last.timestamp);
+
+        let mut fit = FitWriter::new();
+
+        fit.define(0, FIT_FILE_ID, &[
+            (0, 1, FIT_ENUM),      // type
+            (1, 2, FIT_UINT16),    // manufacturer
+            (2, 2, FIT_UINT16),    // product
+            (3, 4, FIT_UINT32Z),   // serial number
+            (4, 4, FIT_UINT32),    // time created
+        ]);
+        fit.message(0).u8(FIT_FILE_ACTIVITY).u16(FIT_MANUFACTURER_DEVELOPMENT).u16(0).u32(1).u32(start);
+
+        fit.define(1, FIT_RECORD, &[
+            (253, 4, FIT_UINT32),  // timestamp
+            (0, 4, FIT_SINT32),    // latitude, semicircles
+            (1, 4, FIT_SINT32),    // longitude, semicircles
+            (2, 2, FIT_UINT16),    // altitude, (m + 500) * 5
+            (6, 2, FIT_UINT16),    // speed, mm/s
+            (5, 4, FIT_UINT32),    // distance, cm
+        ]);
+        fit.define(2, FIT_LAP, &[
+            (253, 4, FIT_UINT32),  // timestamp
+            (2, 4, FIT_UINT32),    // start time
+            (7, 4, FIT_UINT32),    // total elapsed time, ms
+            (8, 4, FIT_UINT32),    // total timer time, ms
+            (9, 4, FIT_UINT32),    // total distance, cm
+            (0, 1, FIT_ENUM),      // event
+            (1, 1, FIT_ENUM),      // event type
+        ]);
+
+        let mut distance = 0.0;
+        let mut laps = 0u16;
+        for track in &self.tracks {
+            let (Some(lap_start), Some(lap_end)) = (track.start_time(), track.end_time()) else {
+                continue;
+            };
+            let lap_distance_start = distance;
+            for segment in &track.segments {
+                for (i, point) in segment.points.iter().enumerate() {
+                    if i > 0 {
+                        distance += segment.points[i - 1].distance_to(point);
+                    }
+                    let altitude = point.elevation
+                        .map_or(u16::MAX, |alt| ((alt + 500.0) * 5.0).round().clamp(0.0, 65534.0) as u16);
+                    let speed = point.speed
+                        .map_or(u16::MAX, |kmh| (kmh / 3.6 * 1000.0).round().clamp(0.0, 65534.0) as u16);
+                    fit.message(1)
+                        .u32(fit_time(&point.timestamp))
+                        .i32(fit_semicircles(point.latitude))
+                        .i32(fit_semicircles(point.longitude))
+                        .u16(altitude)
+                        .u16(speed)
+                        .u32(fit_centimeters(distance));
+                }
+            }
+
+            let elapsed = fit_milliseconds(lap_end - lap_start);
+            fit.message(2)
+                .u32(fit_time(&lap_end))
+                .u32(fit_time(&lap_start))
+                .u32(elapsed)
+                .u32(elapsed)
+                .u32(fit_centimeters(distance - lap_distance_start))
+                .u8(FIT_EVENT_LAP)
+                .u8(FIT_EVENT_TYPE_STOP);
+            laps += 1;
+        }
+
+        let elapsed = fit_milliseconds(last.timestamp - first.timestamp);
+        fit.define(3, FIT_SESSION, &[
+            (253, 4, FIT_UINT32),  // timestamp
+            (2, 4, FIT_UINT32),    // start time
+            (7, 4, FIT_UINT32),    // total elapsed time, ms
+            (8, 4, FIT_UINT32),    // total timer time, ms
+            (9, 4, FIT_UINT32),    // total distance, cm
+            (5, 1, FIT_ENUM),      // sport
+            (25, 2, FIT_UINT16),   // first lap index
+            (26, 2, FIT_UINT16),   // number of laps
+            (0, 1, FIT_ENUM),      // event
+            (1, 1, FIT_ENUM),      // event type
+        ]);
+        fit.message(3)
+            .u32(end)
+            .u32(start)
+            .u32(elapsed)
+            .u32(elapsed)
+            .u32(fit_centimeters(distance))
+            .u8(FIT_SPORT_GENERIC)
+            .u16(0)
+            .u16(laps)
+            .u8(FIT_EVENT_SESSION)
+            .u8(FIT_EVENT_TYPE_STOP);
+
+        fit.define(4, FIT_ACTIVITY, &[
+            (253, 4, FIT_UINT32),  // timestamp
+            (0, 4, FIT_UINT32),    // total timer time, ms
+            (1, 2, FIT_UINT16),    // number of sessions
+            (2, 1, FIT_ENUM),      // type
+            (3, 1, FIT_ENUM),      // event
+            (4, 1, FIT_ENUM),      // event type
+        ]);
+        fit.message(4)
+            .u32(end)
+            .u32(elapsed)
+            .u16(1)
+            .u8(FIT_ACTIVITY_MANUAL)
+            .u8(FIT_EVENT_ACTIVITY)
+            .u8(FIT_EVENT_TYPE_STOP);
+
+        Ok(fit.finish())
+    }
+
+    /// GeoJSON position, leaving out the elevation when it is unknown so it
+    /// doesn't read back as sea level
+    fn geojson_position(&self, lon: f64, lat: f64, elevation: Option<f64>) -> serde_json::Value {
+        let (lon, lat) = match self.coordinate_decimals {
+            Some(decimals) => {
+                let scale = 10f64.powi(decimals as i32);
+                ((lon * scale).round() / scale, (lat * scale).round() / scale)
+            }
+            None => (lon, lat),
+        };
+        match elevation {
+            Some(ele) => serde_json::json!([lon, lat, ele]),
+            None => serde_json::json!([lon, lat]),
+        }
+    }
+
+    /// KML "lon,lat[,alt]" tuple; like `geojson_position`, unknown elevation is omitted
+    fn kml_coordinate(lon: f64, lat: f64, elevation: Option<f64>) -> String {
+        match elevation {
+            Some(ele) => format!("{},{},{}", lon, lat, ele),
+            None => format!("{},{}", lon, lat),
+        }
+    }
+
+    /// CSV coordinate text, rounded when `coordinate_decimals` is set
+    fn csv_coordinate(&self, value: f64) -> String {
+        match self.coordinate_decimals {
+            Some(decimals) => format!("{:.*}", decimals, value),
+            None => value.to_string(),
+        }
+    }
+
     fn escape_xml(s: &str) -> String {
         s.replace('&', "&amp;")
             .replace('<', "&lt;")
@@ -603,7 +2111,12 @@ impl WaypointExporter {
     }
 
     fn escape_csv(s: &str) -> String {
-        if s.contains(',') || s.contains('"') || s.contains('\n') {
+        Self::escape_csv_field(s, ",")
+    }
+
+    /// Quote a field containing the separator, a quote or a line break
+    fn escape_csv_field(s: &str, separator: &str) -> String {
+        if s.contains(separator) || s.contains('"') || s.contains('\n') {
             format!("\"{}\"", s.replace('"', "\"\""))
         } else {
             s.to_string()
@@ -617,6 +2130,70 @@ impl WaypointExporter {
     pub fn get_tracks(&self) -> &[Track] {
         &self.tracks
     }
+
+    pub fn get_routes(&self) -> &[Route] {
+        &self.routes
+    }
+
+    /// Copy of the saved waypoints, tracks and routes
+    pub fn session(&self) -> Session {
+        Session {
+            waypoints: self.waypoints.clone(),
+            tracks: self.tracks.clone(),
+            routes: self.routes.clone(),
+        }
+    }
+
+    /// Replace the current waypoints, tracks and routes with those saved in
+    /// `session`, which holds them all
+    pub fn restore_session(&mut self, session: Session) {
+        self.waypoints = session.waypoints;
+        self.tracks = session.tracks;
+        self.routes = session.routes;
+    }
+}
+
+/// Saved waypoints, tracks and routes, written periodically so a crash
+/// doesn't lose them and read back on the next start
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Session {
+    pub waypoints: Vec<Waypoint>,
+    pub tracks: Vec<Track>,
+    #[serde(default)]
+    pub routes: Vec<Route>,
+}
+
+impl Session {
+    /// Where the GUI autosaves the session: `session.json` beside the other
+    /// gps-monitor data in the user's local data directory
+    pub fn default_path() -> PathBuf {
+        let mut path = dirs::data_local_dir().unwrap_or_else(|| PathBuf::from("."));
+        path.push("gps-monitor");
+        path.push("session.json");
+        path
+    }
+
+    /// Write the session, replacing any previous one in a single rename
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let temp_path = path.with_extension("json.tmp");
+        std::fs::write(&temp_path, serde_json::to_string(self)?)?;
+        std::fs::rename(&temp_path, path)?;
+        Ok(())
+    }
+
+    /// Load a saved session if one exists
+    pub fn load(path: &Path) -> Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let contents = std::fs::read_to_string(path)?;
+        serde_json::from_str(&contents)
+            .map(Some)
+            .map_err(|e| GpsError::Other(format!("Invalid session file: {}", e)))
+    }
 }
 
 impl Default for WaypointExporter {
@@ -625,6 +2202,132 @@ impl Default for WaypointExporter {
     }
 }
 
+// FIT global message numbers
+const FIT_FILE_ID: u16 = 0;
+const FIT_SESSION: u16 = 18;
+const FIT_LAP: u16 = 19;
+const FIT_RECORD: u16 = 20;
+const FIT_ACTIVITY: u16 = 34;
+
+// FIT base types
+const FIT_ENUM: u8 = 0x00;
+const FIT_UINT16: u8 = 0x84;
+const FIT_SINT32: u8 = 0x85;
+const FIT_UINT32: u8 = 0x86;
+const FIT_UINT32Z: u8 = 0x8C;
+
+// FIT profile enum values
+const FIT_FILE_ACTIVITY: u8 = 4;
+const FIT_MANUFACTURER_DEVELOPMENT: u16 = 255;
+const FIT_SPORT_GENERIC: u8 = 0;
+const FIT_ACTIVITY_MANUAL: u8 = 0;
+const FIT_EVENT_SESSION: u8 = 8;
+const FIT_EVENT_LAP: u8 = 9;
+const FIT_EVENT_ACTIVITY: u8 = 26;
+const FIT_EVENT_TYPE_STOP: u8 = 1;
+
+/// Protocol 1.0, profile 21.00
+const FIT_PROTOCOL_VERSION: u8 = 0x10;
+const FIT_PROFILE_VERSION: u16 = 2100;
+
+/// FIT timestamps count seconds from 1989-12-31T00:00:00Z
+const FIT_EPOCH_OFFSET: i64 = 631_065_600;
+
+fn fit_time(time: &DateTime<Utc>) -> u32 {
+    (time.timestamp() - FIT_EPOCH_OFFSET).clamp(0, u32::MAX as i64) as u32
+}
+
+/// Degrees to FIT semicircles (2^31 per 180 degrees)
+fn fit_semicircles(degrees: f64) -> i32 {
+    (degrees * (2f64.powi(31) / 180.0)).round() as i32
+}
+
+fn fit_centimeters(meters: f64) -> u32 {
+    (meters * 100.0).round() as u32
+}
+
+fn fit_milliseconds(duration: chrono::Duration) -> u32 {
+    duration.num_milliseconds().clamp(0, u32::MAX as i64) as u32
+}
+
+/// CRC-16 used for the FIT header and file trailer
+fn fit_crc(bytes: &[u8]) -> u16 {
+    const TABLE: [u16; 16] = [
+        0x0000, 0xCC01, 0xD801, 0x1400, 0xF001, 0x3C00, 0x2800, 0xE401,
+        0xA001, 0x6C00, 0x7800, 0xB401, 0x5000, 0x9C01, 0x8801, 0x4400,
+    ];
+    bytes.iter().fold(0u16, |mut crc, &byte| {
+        for nibble in [byte & 0x0F, byte >> 4] {
+            let tmp = TABLE[(crc & 0x0F) as usize];
+            crc = ((crc >> 4) & 0x0FFF) ^ tmp ^ TABLE[nibble as usize];
+        }
+        crc
+    })
+}
+
+/// Little-endian FIT record stream; `finish` wraps it in the header and CRC
+struct FitWriter {
+    data: Vec<u8>,
+}
+
+impl FitWriter {
+    fn new() -> Self {
+        Self { data: Vec::new() }
+    }
+
+    /// Definition message binding `local` to a global message with
+    /// `(field number, size, base type)` fields
+    fn define(&mut self, local: u8, global: u16, fields: &[(u8, u8, u8)]) {
+        self.data.extend_from_slice(&[0x40 | local, 0, 0]);
+        self.data.extend_from_slice(&global.to_le_bytes());
+        self.data.push(fields.len() as u8);
+        for &(number, size, base_type) in fields {
+            self.data.extend_from_slice(&[number, size, base_type]);
+        }
+    }
+
+    /// Start a data message; field values follow in definition order
+    fn message(&mut self, local: u8) -> &mut Self {
+        self.data.push(local);
+        self
+    }
+
+    fn u8(&mut self, value: u8) -> &mut Self {
+        self.data.push(value);
+        self
+    }
+
+    fn u16(&mut self, value: u16) -> &mut Self {
+        self.data.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    fn u32(&mut self, value: u32) -> &mut Self {
+        self.data.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    fn i32(&mut self, value: i32) -> &mut Self {
+        self.data.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    fn finish(self) -> Vec<u8> {
+        let mut file = Vec::with_capacity(14 + self.data.len() + 2);
+        file.push(14);
+        file.push(FIT_PROTOCOL_VERSION);
+        file.extend_from_slice(&FIT_PROFILE_VERSION.to_le_bytes());
+        file.extend_from_slice(&(self.data.len() as u32).to_le_bytes());
+        file.extend_from_slice(b".FIT");
+        let header_crc = fit_crc(&file);
+        file.extend_from_slice(&header_crc.to_le_bytes());
+        file.extend_from_slice(&self.data);
+        let file_crc = fit_crc(&file);
+        file.extend_from_slice(&file_crc.to_le_bytes());
+        file
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -645,6 +2348,7 @@ mod tests {
             obd_throttle: None,
             obd_load: None,
             obd_temp: None,
+            satellites_snapshot: None,
         };
 
         let p2 = TrackPoint {
@@ -661,12 +2365,24 @@ mod tests {
             obd_throttle: None,
             obd_load: None,
             obd_temp: None,
+            satellites_snapshot: None,
         };
 
         let distance = p1.distance_to(&p2);
         assert!(distance > 1100.0 && distance < 1120.0); // ~1.11 km
     }
 
+    #[test]
+    fn test_destination_point() {
+        let (lat, lon) = destination_point(42.0, -71.0, 90.0, 1000.0);
+        assert!((haversine_distance(42.0, -71.0, lat, lon) - 1000.0).abs() < 0.01);
+        assert!((initial_bearing(42.0, -71.0, lat, lon) - 90.0).abs() < 0.01);
+
+        // Wraps across the antimeridian
+        let (_, lon) = destination_point(0.0, 179.999, 90.0, 1000.0);
+        assert!(lon < -179.0);
+    }
+
     #[test]
     fn test_track_statistics() {
         let mut track = Track::new("Test Track".to_string());
@@ -685,6 +2401,7 @@ mod tests {
             obd_throttle: None,
             obd_load: None,
             obd_temp: None,
+            satellites_snapshot: None,
         };
 
         let p2 = TrackPoint {
@@ -701,6 +2418,7 @@ mod tests {
             obd_throttle: None,
             obd_load: None,
             obd_temp: None,
+            satellites_snapshot: None,
         };
 
         track.add_point(p1);
@@ -710,6 +2428,917 @@ mod tests {
         assert!(track.total_distance() > 1100.0);
         assert!(track.duration().is_some());
     }
-}
 
+    #[test]
+    fn test_distance_across_antimeridian() {
+        let time = Utc::now();
+        let mut track = Track::new("Pacific".to_string());
+        track.add_point(TrackPoint::new(0.0, 179.9, None, time));
+        track.add_point(TrackPoint::new(0.0, -179.9, None, time));
+
+        // 0.2° of longitude at the equator the short way, not 359.8° the long way
+        let distance = track.total_distance();
+        assert!((distance - 22_239.0).abs() < 10.0, "{}", distance);
+    }
+
+    #[test]
+    fn test_track_position_at() {
+        let t0 = DateTime::parse_from_rfc3339("2024-05-01T12:00:00Z").unwrap().with_timezone(&Utc);
+        let secs = |s: i64| t0 + chrono::Duration::seconds(s);
+
+        let mut track = Track::new("Playback".to_string());
+        track.add_point(TrackPoint::new(42.0, -71.0, None, secs(0)));
+        track.add_point(TrackPoint::new(42.1, -71.2, None, secs(10)));
+        track.start_new_segment();
+        track.add_point(TrackPoint::new(43.0, -72.0, None, secs(30)));
+        track.add_point(TrackPoint::new(43.0, -73.0, None, secs(40)));
+        track.start_new_segment(); // empty trailing segment, as left by pause
+
+        assert_eq!(track.start_time(), Some(secs(0)));
+        assert_eq!(track.end_time(), Some(secs(40)));
+
+        let (lat, lon) = track.position_at(secs(5)).unwrap();
+        assert!((lat - 42.05).abs() < 1e-9);
+        assert!((lon - -71.1).abs() < 1e-9);
+
+        // Holds at the end of the first segment during the gap
+        assert_eq!(track.position_at(secs(20)), Some((42.1, -71.2)));
+
+        let (_, lon) = track.position_at(secs(35)).unwrap();
+        assert!((lon - -72.5).abs() < 1e-9);
+
+        assert_eq!(track.position_at(secs(40)), Some((43.0, -73.0)));
+        assert_eq!(track.position_at(secs(-1)), None);
+        assert_eq!(track.position_at(secs(41)), None);
+    }
+
+    #[test]
+    fn test_track_summary_report() {
+        let t0 = DateTime::parse_from_rfc3339("2024-05-01T12:00:00Z").unwrap().with_timezone(&Utc);
+        let secs = |s: i64| t0 + chrono::Duration::seconds(s);
+
+        let mut track = Track::new("Drive".to_string());
+        track.add_point(TrackPoint::new(42.0, -71.0, Some(100.0), secs(0)));
+        track.add_point(TrackPoint::new(42.01, -71.0, Some(110.0), secs(60)));
+        track.add_point(TrackPoint::new(42.01, -71.0, Some(110.0), secs(120))); // stopped
+        track.add_point(TrackPoint::new(42.02, -71.0, Some(95.0), secs(180)));
+
+        assert_eq!(track.moving_time(), chrono::Duration::seconds(120));
+        assert_eq!(track.elevation_gain_loss(), (10.0, 15.0));
+
+        let report = track.summary_report(DistanceUnits::Metric, DEFAULT_COORDINATE_DECIMALS, DisplayTimeZone::Utc);
+        assert!(report.starts_with("# Track Summary: Drive"));
+        assert!(report.contains("- **Points:** 4\n"));
+        assert!(report.contains("- **Distance:** 2.22 km\n"));
+        assert!(report.contains("- **Duration:** 3m 0s\n"));
+        assert!(report.contains("- **Moving time:** 2m 0s\n"));
+        assert!(report.contains("- **Average speed:** 44.5 km/h\n"));
+        assert!(report.contains("- **Max speed:** 66.7 km/h\n"));
+        assert!(report.contains("- **Elevation gain:** 10 m\n"));
+        assert!(report.contains("- **Elevation loss:** 15 m\n"));
+        assert!(report.contains("- **Start:** 42.000000, -71.000000 at 2024-05-01 12:00:00 UTC\n"));
+        assert!(report.contains("- **End:** 42.020000, -71.000000 at 2024-05-01 12:03:00 UTC\n"));
+
+        let report = track.summary_report(DistanceUnits::Imperial, 4, DisplayTimeZone::Fixed(-300));
+        assert!(report.contains("- **Start:** 42.0000, -71.0000 at 2024-05-01 07:00:00 UTC-05:00\n"));
+        assert!(report.contains("- **Elevation gain:** 33 ft\n"));
+        assert!(report.contains("- **Elevation loss:** 49 ft\n"));
+    }
+
+    #[test]
+    fn test_altitude_units_are_display_only() {
+        assert_eq!(DistanceUnits::Metric.format_altitude(1234.56, 1), "1234.6 m");
+        assert_eq!(DistanceUnits::Nautical.format_altitude(100.0, 0), "100 m");
+        assert_eq!(DistanceUnits::Imperial.format_altitude(100.0, 0), "328 ft");
+
+        let timestamp = DateTime::parse_from_rfc3339("2024-05-01T12:00:00Z").unwrap().with_timezone(&Utc);
+        let mut exporter = WaypointExporter::new();
+        exporter.add_waypoint(Waypoint {
+            name: "Summit".to_string(),
+            latitude: 42.0,
+            longitude: -71.0,
+            elevation: Some(100.0),
+            timestamp,
+            description: None,
+            color: None,
+        });
+
+        // Standard formats stay in meters whatever the display unit
+        assert!(exporter.to_gpx().contains("<ele>100</ele>"));
+        assert!(exporter.to_kml().contains("-71,42,100"));
+
+        // CSV is read by people, so it follows the chosen unit and says so in the header
+        let csv = exporter.to_csv(&CsvExport { units: DistanceUnits::Imperial, ..Default::default() });
+        let mut lines = csv.lines();
+        assert!(lines.next().unwrap().contains(",elevation_ft,"));
+        assert!(lines.next().unwrap().contains(",328.08,"));
+    }
+
+    #[test]
+    fn test_geojson_round_trip() {
+        let timestamp = DateTime::parse_from_rfc3339("2024-05-01T12:00:00Z").unwrap().with_timezone(&Utc);
+
+        let mut exporter = WaypointExporter::new();
+        exporter.add_waypoint(Waypoint {
+            name: "Home".to_string(),
+            latitude: 42.5,
+            longitude: -71.25,
+            elevation: Some(30.0),
+            timestamp,
+            description: Some("Start".to_string()),
+            color: None,
+        });
+
+        let mut track = Track::new("Drive".to_string());
+        track.add_point(TrackPoint::new(42.0, -71.0, Some(100.0), timestamp));
+        track.add_point(TrackPoint::new(42.01, -71.0, None, timestamp));
+        exporter.add_track(track);
+
+        let geojson = exporter.to_geojson().unwrap();
+
+        let mut importer = WaypointExporter::new();
+        assert_eq!(importer.import_geojson(&geojson).unwrap(), (1, 1));
+
+        let wp = &importer.get_waypoints()[0];
+        assert_eq!(wp.name, "Home");
+        assert_eq!(wp.latitude, 42.5);
+        assert_eq!(wp.longitude, -71.25);
+        assert_eq!(wp.elevation, Some(30.0));
+        assert_eq!(wp.timestamp, timestamp);
+        assert_eq!(wp.description.as_deref(), Some("Start"));
+
+        let track = &importer.get_tracks()[0];
+        assert_eq!(track.name, "Drive");
+        assert_eq!(track.segments.len(), 1);
+        assert_eq!(track.total_points(), 2);
+        assert_eq!(track.segments[0].points[0].elevation, Some(100.0));
+        assert_eq!(track.segments[0].points[1].latitude, 42.01);
+    }
+
+    #[test]
+    fn test_geojson_import_2d_coordinates() {
+        let geojson = r#"{"type":"FeatureCollection","features":[
+            {"type":"Feature","geometry":{"type":"Point","coordinates":[11.5,48.1]},"properties":{}},
+            {"type":"Feature","geometry":{"type":"LineString","coordinates":[[11.5,48.1],[11.6,48.2]]},"properties":{"name":"Walk"}}
+        ]}"#;
+
+        let mut importer = WaypointExporter::new();
+        assert_eq!(importer.import_geojson(geojson).unwrap(), (1, 1));
+        assert_eq!(importer.get_waypoints()[0].name, "Waypoint 1");
+        assert_eq!(importer.get_waypoints()[0].elevation, None);
+        assert_eq!(importer.get_tracks()[0].segments[0].points[1].longitude, 11.6);
+    }
+
+    #[test]
+    fn test_geojson_import_rejects_non_collection() {
+        let mut importer = WaypointExporter::new();
+        assert!(importer.import_geojson(r#"{"type":"Point","coordinates":[0,0]}"#).is_err());
+    }
+
+    #[test]
+    fn test_plausible_step() {
+        let start = Utc::now();
+        let p1 = TrackPoint::new(42.0, -71.0, None, start);
+        // ~55 m in a second is plausible, ~1.1 km is not unless a minute has passed
+        let near = TrackPoint::new(42.0005, -71.0, None, start + chrono::Duration::seconds(1));
+        let far = TrackPoint::new(42.01, -71.0, None, start + chrono::Duration::seconds(1));
+        let far_later = TrackPoint::new(42.01, -71.0, None, start + chrono::Duration::seconds(60));
+
+        assert!(p1.is_plausible_step(&near));
+        assert!(!p1.is_plausible_step(&far));
+        assert!(p1.is_plausible_step(&far_later));
+    }
+
+    fn waypoint_at(name: &str, lat: f64, lon: f64) -> Waypoint {
+        Waypoint {
+            name: name.to_string(),
+            latitude: lat,
+            longitude: lon,
+            elevation: None,
+            timestamp: Utc::now(),
+            description: None,
+            color: None,
+        }
+    }
+
+    #[test]
+    fn test_waypoint_matrix() {
+        let mut exporter = WaypointExporter::new();
+        exporter.add_waypoint(waypoint_at("Origin", 0.0, 0.0));
+        exporter.add_waypoint(waypoint_at("North", 1.0, 0.0));
+        exporter.add_waypoint(waypoint_at("East", 0.0, 1.0));
+
+        let (distances, bearings) = exporter.waypoint_matrix();
+
+        for (i, row) in distances.iter().enumerate() {
+            assert_eq!(row[i], 0.0);
+            assert!(bearings[i][i].is_none());
+            for (j, distance) in row.iter().enumerate() {
+                assert!((distance - distances[j][i]).abs() < 1e-6);
+            }
+        }
+
+        // One degree of latitude/longitude at the equator is ~111.2 km
+        assert!((distances[0][1] - 111_195.0).abs() < 10.0);
+        assert!((bearings[0][1].unwrap() - 0.0).abs() < 1e-6);
+        assert!((bearings[0][2].unwrap() - 90.0).abs() < 1e-6);
+        assert!((bearings[1][0].unwrap() - 180.0).abs() < 1e-6);
+        assert!((bearings[2][0].unwrap() - 270.0).abs() < 1e-6);
+
+        let csv = exporter.to_matrix_csv();
+        assert!(csv.starts_with("Distance (m),Origin,North,East\n"));
+        assert!(csv.contains("\nBearing (deg),Origin,North,East\n"));
+    }
+
+    #[test]
+    fn test_kml_round_trip() {
+        let mut exporter = WaypointExporter::new();
+        let mut waypoint = waypoint_at("Tom & Jerry's", 42.5, -71.25);
+        waypoint.elevation = Some(12.5);
+        waypoint.description = Some("<home>".to_string());
+        exporter.add_waypoint(waypoint.clone());
+
+        let mut track = Track::new("Commute".to_string());
+        let start = Utc::now();
+        track.add_point(TrackPoint::new(42.0, -71.0, Some(10.0), start));
+        track.add_point(TrackPoint::new(42.001, -71.001, Some(11.0), start));
+        track.start_new_segment();
+        track.add_point(TrackPoint::new(42.002, -71.002, Some(12.0), start));
+        exporter.add_track(track);
+
+        let kml = exporter.to_kml();
+        let mut imported = WaypointExporter::new();
+        assert_eq!(imported.import_kml(&kml).unwrap(), (1, 1));
+
+        let wp = &imported.get_waypoints()[0];
+        assert_eq!(wp.name, "Tom & Jerry's");
+        assert_eq!(wp.description.as_deref(), Some("<home>"));
+        assert_eq!((wp.latitude, wp.longitude, wp.elevation), (42.5, -71.25, Some(12.5)));
+        assert_eq!(wp.timestamp.timestamp(), waypoint.timestamp.timestamp());
+
+        let track = &imported.get_tracks()[0];
+        assert_eq!(track.name, "Commute");
+        assert_eq!(track.segments.len(), 2);
+        assert_eq!(track.total_points(), 3);
+        let p = &track.segments[0].points[1];
+        assert_eq!((p.latitude, p.longitude, p.elevation), (42.001, -71.001, Some(11.0)));
+    }
+
+    #[test]
+    fn test_import_thinning() {
+        // A dense drive east with centimeter jitter, then a turn north
+        let start = DateTime::parse_from_rfc3339("2024-05-01T12:00:00Z").unwrap().with_timezone(&Utc);
+        let mut track = Track::new("Dense".to_string());
+        for i in 0..1000 {
+            let jitter = if i % 2 == 0 { 0.000001 } else { -0.000001 };
+            track.add_point(TrackPoint::new(42.0 + jitter, -71.0 + i as f64 * 0.00001, None, start + chrono::Duration::seconds(i)));
+        }
+        for i in 1..=500 {
+            track.add_point(TrackPoint::new(42.0 + i as f64 * 0.00001, -71.0 + 999.0 * 0.00001, None, start + chrono::Duration::seconds(999 + i)));
+        }
+        let mut exporter = WaypointExporter::new();
+        exporter.add_track(track);
+        let path = std::env::temp_dir().join(format!("gps-monitor-thinning-{}.gpx", std::process::id()));
+        exporter.export_to_file(&path, WaypointFormat::GPX).unwrap();
+
+        let mut full = WaypointExporter::new();
+        let summary = full.import_from_file(&path, WaypointFormat::GPX, None).unwrap();
+        assert_eq!((summary.points_before, summary.points_after), (1500, 1500));
+
+        let mut thinned = WaypointExporter::new();
+        let summary = thinned.import_from_file(&path, WaypointFormat::GPX, Some(5.0)).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!((summary.tracks, summary.points_before, summary.points_after), (1, 1500, 3));
+
+        let (original, kept) = (&full.get_tracks()[0].segments[0].points, &thinned.get_tracks()[0].segments[0].points);
+        assert_eq!(kept.first().map(|p| p.timestamp), original.first().map(|p| p.timestamp));
+        assert_eq!(kept.last().map(|p| p.timestamp), original.last().map(|p| p.timestamp));
+        assert_eq!(kept[1].timestamp, original[999].timestamp);
+    }
+
+    #[test]
+    fn test_gpx_round_trip() {
+        let mut exporter = WaypointExporter::new();
+        let mut waypoint = waypoint_at("Trailhead", 44.27, -71.3);
+        waypoint.elevation = Some(613.0);
+        waypoint.description = Some("Parking & start".to_string());
+        exporter.add_waypoint(waypoint);
+
+        let mut track = Track::new("Loop <north>".to_string());
+        track.description = Some("Clockwise, \"wet\" in spring".to_string());
+        track.color = Some([255, 128, 0]);
+        let start = DateTime::parse_from_rfc3339("2024-05-01T12:00:00Z").unwrap().with_timezone(&Utc);
+        track.add_point(TrackPoint::new(44.27, -71.3, Some(613.0), start));
+        track.add_point(TrackPoint::new(44.28, -71.31, None, start + chrono::Duration::seconds(30)));
+        track.start_new_segment();
+        track.add_point(TrackPoint::new(44.29, -71.32, Some(700.5), start + chrono::Duration::seconds(90)));
+        exporter.add_track(track);
+
+        let gpx = exporter.to_gpx();
+        assert!(gpx.contains("    <desc>Clockwise, &quot;wet&quot; in spring</desc>\n"));
+
+        let mut imported = WaypointExporter::new();
+        assert_eq!(imported.import_gpx(&gpx).unwrap(), (1, 1));
+
+        let wp = &imported.get_waypoints()[0];
+        assert_eq!(wp.name, "Trailhead");
+        assert_eq!(wp.description.as_deref(), Some("Parking & start"));
+        assert_eq!(wp.elevation, Some(613.0));
+
+        let track = &imported.get_tracks()[0];
+        assert_eq!(track.name, "Loop <north>");
+        assert_eq!(track.description.as_deref(), Some("Clockwise, \"wet\" in spring"));
+        assert_eq!(track.segments.len(), 2);
+        let p = &track.segments[0].points[1];
+        assert_eq!((p.latitude, p.longitude, p.elevation), (44.28, -71.31, None));
+        assert_eq!(p.timestamp, start + chrono::Duration::seconds(30));
+        assert_eq!(track.segments[1].points[0].elevation, Some(700.5));
+
+        // Copies such as smoothing keep the metadata
+        let original = &exporter.get_tracks()[0];
+        assert_eq!(original.smooth(3).color, Some([255, 128, 0]));
+        assert!(exporter.to_kml().contains("<color>ff0080ff</color>"));
+
+        assert!(imported.import_gpx("<kml></kml>").is_err());
+    }
+
+    #[test]
+    fn test_session_round_trip() {
+        let mut exporter = WaypointExporter::new();
+        exporter.add_waypoint(waypoint_at("Home", 42.5, -71.1));
+        exporter.add_waypoint(waypoint_at("Work", 42.36, -71.06));
+        let mut track = Track::new("Commute".to_string());
+        let start = DateTime::parse_from_rfc3339("2024-05-01T08:00:00Z").unwrap().with_timezone(&Utc);
+        track.add_point(TrackPoint::new(42.5, -71.1, Some(30.0), start));
+        track.add_point(TrackPoint::new(42.36, -71.06, None, start + chrono::Duration::minutes(25)));
+        exporter.add_track(track);
+        exporter.add_route(Route::from_waypoints("Back".to_string(), exporter.get_waypoints(), &[1, 0]));
+
+        let path = std::env::temp_dir().join(format!("gps-monitor-session-{}", std::process::id())).join("session.json");
+        assert!(Session::load(&path).unwrap().is_none());
+        exporter.session().save(&path).unwrap();
+
+        // Restoring replaces what is there rather than adding to it
+        let mut restored = WaypointExporter::new();
+        restored.add_waypoint(waypoint_at("Stale", 0.0, 0.0));
+        for _ in 0..2 {
+            restored.restore_session(Session::load(&path).unwrap().expect("session written"));
+        }
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+
+        assert_eq!(restored.waypoint_count(), 2);
+        assert_eq!(restored.get_waypoints()[1].name, "Work");
+        assert_eq!(restored.get_tracks()[0].total_points(), 2);
+        assert_eq!(restored.get_tracks()[0].segments[0].points[1].timestamp, start + chrono::Duration::minutes(25));
+        assert_eq!(restored.get_routes()[0].points[0].name, "Work");
+    }
+
+    #[test]
+    fn test_below_sea_level_elevations_export() {
+        let mut exporter = WaypointExporter::new();
+        let mut waypoint = waypoint_at("Badwater", 36.23, -116.77);
+        waypoint.elevation = Some(-85.9);
+        exporter.add_waypoint(waypoint);
+
+        let mut track = Track::new("Shore".to_string());
+        let start = Utc::now();
+        track.add_point(TrackPoint::new(31.5, 35.5, Some(-430.5), start));
+        track.add_point(TrackPoint::new(31.501, 35.5, Some(0.0), start));
+        track.add_point(TrackPoint::new(31.502, 35.5, None, start));
+        exporter.add_track(track);
+
+        assert_eq!(exporter.tracks[0].elevation_gain_loss(), (430.5, 0.0));
+        assert!(exporter.to_gpx().contains("<ele>-85.9</ele>"));
+
+        let elevations = |importer: &WaypointExporter| {
+            let wp = importer.get_waypoints()[0].elevation;
+            let points: Vec<_> = importer.get_tracks()[0].segments[0].points.iter().map(|p| p.elevation).collect();
+            (wp, points)
+        };
+        let expected = (Some(-85.9), vec![Some(-430.5), Some(0.0), None]);
+
+        let mut from_kml = WaypointExporter::new();
+        from_kml.import_kml(&exporter.to_kml()).unwrap();
+        assert_eq!(elevations(&from_kml), expected);
+
+        let mut from_geojson = WaypointExporter::new();
+        from_geojson.import_geojson(&exporter.to_geojson().unwrap()).unwrap();
+        assert_eq!(elevations(&from_geojson), expected);
+    }
+
+    #[test]
+    fn test_export_coordinate_decimals() {
+        let mut exporter = WaypointExporter::new();
+        exporter.add_waypoint(waypoint_at("Survey mark", 42.438878123, -71.119277456));
+
+        // Full precision by default
+        assert!(exporter.to_csv(&CsvExport::default()).contains(",42.438878123,-71.119277456,"));
+
+        exporter.set_coordinate_decimals(Some(4));
+        assert!(exporter.to_csv(&CsvExport::default()).contains(",42.4389,-71.1193,"));
+        let geojson: serde_json::Value = serde_json::from_str(&exporter.to_geojson().unwrap()).unwrap();
+        assert_eq!(geojson["features"][0]["geometry"]["coordinates"], serde_json::json!([-71.1193, 42.4389]));
 
+        // GPX keeps full precision
+        assert!(exporter.to_gpx().contains("lat=\"42.438878123\""));
+    }
+
+    #[test]
+    fn test_gpx_route_points_in_order() {
+        let mut exporter = WaypointExporter::new();
+        exporter.add_waypoint(waypoint_at("Marina", 42.0, -71.0));
+        exporter.add_waypoint(waypoint_at("Buoy & Bell", 42.1, -70.9));
+        exporter.add_waypoint(waypoint_at("Harbor", 42.2, -70.8));
+
+        let route = Route::from_waypoints("Out & back".to_string(), exporter.get_waypoints(), &[2, 0, 1, 9]);
+        assert_eq!(route.points.len(), 3);
+        assert!(route.total_distance() > 0.0);
+        exporter.add_route(route);
+
+        let gpx = exporter.to_gpx();
+        let rte_start = gpx.find("<rte>").unwrap();
+        let rte_end = gpx.find("</rte>").unwrap();
+        // Schema order: waypoints, then routes, then tracks
+        assert!(gpx.rfind("</wpt>").unwrap() < rte_start);
+
+        let rte = &gpx[rte_start..rte_end];
+        assert!(rte.contains("<name>Out &amp; back</name>"));
+        let names: Vec<&str> = rte
+            .split("<rtept ")
+            .skip(1)
+            .map(|point| point.split("<name>").nth(1).unwrap().split("</name>").next().unwrap())
+            .collect();
+        assert_eq!(names, ["Harbor", "Marina", "Buoy &amp; Bell"]);
+        assert!(rte.contains(r#"<rtept lat="42.2" lon="-70.8">"#));
+
+        // Routes alone are enough for a GPX export, but not for other formats
+        exporter.clear_waypoints();
+        assert!(exporter.export_bytes(WaypointFormat::GPX).is_ok());
+        assert!(exporter.export_bytes(WaypointFormat::CSV).is_err());
+    }
+
+    #[test]
+    fn test_export_preview() {
+        let mut exporter = WaypointExporter::new();
+        assert!(exporter.export_preview(WaypointFormat::GPX, 5).is_err());
+
+        for i in 0..10 {
+            exporter.add_waypoint(waypoint_at(&format!("WP{}", i), 42.0 + i as f64 * 0.01, -71.0));
+        }
+        let gpx = exporter.to_gpx();
+        let preview = exporter.export_preview(WaypointFormat::GPX, 5).unwrap();
+        assert_eq!(preview.text, gpx.lines().take(5).collect::<Vec<_>>().join("\n"));
+        assert_eq!(preview.total_bytes, gpx.len());
+        assert_eq!(preview.total_lines, Some(gpx.lines().count()));
+        assert!(preview.truncated);
+
+        let csv = exporter.export_preview(WaypointFormat::CSV, 100).unwrap();
+        assert_eq!(csv.total_lines, Some(11));
+        assert!(!csv.truncated);
+
+        // FIT has no tracks to write yet, then gets a summary rather than bytes
+        assert!(exporter.export_preview(WaypointFormat::FIT, 5).is_err());
+        let t0 = DateTime::parse_from_rfc3339("2024-05-01T12:00:00Z").unwrap().with_timezone(&Utc);
+        let mut track = Track::new("Ride".to_string());
+        track.add_point(TrackPoint::new(42.0, -71.0, None, t0));
+        track.add_point(TrackPoint::new(42.001, -71.001, None, t0 + chrono::Duration::seconds(10)));
+        exporter.add_track(track);
+        let fit = exporter.export_preview(WaypointFormat::FIT, 5).unwrap();
+        assert_eq!(fit.total_bytes, exporter.to_fit().unwrap().len());
+        assert_eq!(fit.total_lines, None);
+        assert!(fit.text.contains("1 track(s), 2 record(s)"));
+    }
+
+    #[test]
+    fn test_elevation_corrector_hook() {
+        let mut gps_data = GpsData::new();
+        gps_data.latitude = Some(42.5);
+        gps_data.longitude = Some(-71.25);
+        gps_data.altitude = Some(55.0);
+
+        // Stub DEM that only covers the northern hemisphere
+        let dem = |lat: f64, _lon: f64| (lat > 0.0).then_some(38.2);
+
+        let wp = Waypoint::from_gps_data_corrected(&gps_data, "Summit".to_string(), None, &dem).unwrap();
+        assert_eq!(wp.elevation, Some(38.2));
+
+        let wp = Waypoint::from_gps_data_corrected(&gps_data, "Raw".to_string(), None, &NoElevationCorrection).unwrap();
+        assert_eq!(wp.elevation, Some(55.0));
+
+        let mut exporter = WaypointExporter::new();
+        exporter.add_waypoint(waypoint_at("North", 10.0, 0.0));
+        exporter.add_waypoint(waypoint_at("South", -10.0, 0.0));
+        exporter.correct_elevations(&dem);
+        let elevations: Vec<_> = exporter.get_waypoints().iter().map(|wp| wp.elevation).collect();
+        assert_eq!(elevations, vec![Some(38.2), None]);
+    }
+
+    #[test]
+    fn test_kml_gx_track_import() {
+        let kml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<kml xmlns="http://www.opengis.net/kml/2.2" xmlns:gx="http://www.google.com/kml/ext/2.2">
+  <Document>
+    <Placemark>
+      <name>Drive</name>
+      <gx:Track>
+        <when>2024-05-01T12:00:00Z</when>
+        <when>2024-05-01T12:00:10Z</when>
+        <gx:coord>-71.0 42.0 10</gx:coord>
+        <gx:coord>-71.001 42.001 11</gx:coord>
+      </gx:Track>
+    </Placemark>
+  </Document>
+</kml>"#;
+
+        let mut exporter = WaypointExporter::new();
+        assert_eq!(exporter.import_kml(kml).unwrap(), (0, 1));
+
+        let track = &exporter.get_tracks()[0];
+        assert_eq!(track.name, "Drive");
+        assert_eq!(track.total_points(), 2);
+        assert_eq!(track.duration(), Some(chrono::Duration::seconds(10)));
+        assert_eq!(track.segments[0].points[1].latitude, 42.001);
+    }
+
+    #[test]
+    fn test_jsonl_one_object_per_line() {
+        let t0 = DateTime::parse_from_rfc3339("2024-05-01T12:00:00Z").unwrap().with_timezone(&Utc);
+        let mut track = Track::new("Commute".to_string());
+        let mut point = TrackPoint::new(42.0, -71.0, Some(10.0), t0);
+        point.speed = Some(36.0);
+        point.obd_rpm = Some(2100);
+        track.add_point(point);
+        track.start_new_segment();
+        track.add_point(TrackPoint::new(42.001, -71.001, None, t0 + chrono::Duration::seconds(5)));
+
+        let mut exporter = WaypointExporter::new();
+        exporter.add_waypoint(waypoint_at("Not exported", 0.0, 0.0));
+        exporter.add_track(track);
+
+        let jsonl = exporter.to_jsonl().unwrap();
+        let lines: Vec<serde_json::Value> = jsonl.lines()
+            .map(|line| serde_json::from_str(line).expect("each line is valid JSON"))
+            .collect();
+
+        assert_eq!(lines.len(), 2);
+        assert!(lines.iter().all(|l| l.is_object()));
+        assert_eq!(lines[0]["track"], "Commute");
+        assert_eq!(lines[0]["segment"], 0);
+        assert_eq!(lines[0]["latitude"], 42.0);
+        assert_eq!(lines[0]["speed"], 36.0);
+        assert_eq!(lines[0]["obd_rpm"], 2100);
+        assert_eq!(lines[1]["segment"], 1);
+        assert!(lines[1]["elevation"].is_null());
+
+        let point: TrackPoint = serde_json::from_value(lines[1].clone()).unwrap();
+        assert_eq!(point.timestamp, t0 + chrono::Duration::seconds(5));
+    }
+
+    #[test]
+    fn test_fit_header_and_crc() {
+        assert_eq!(fit_crc(b"123456789"), 0xBB3D);
+
+        let t0 = DateTime::parse_from_rfc3339("2024-05-01T12:00:00Z").unwrap().with_timezone(&Utc);
+        let mut track = Track::new("Ride".to_string());
+        let mut point = TrackPoint::new(42.0, -71.0, Some(10.0), t0);
+        point.speed = Some(36.0);
+        track.add_point(point);
+        track.add_point(TrackPoint::new(42.001, -71.001, None, t0 + chrono::Duration::seconds(10)));
+
+        let mut exporter = WaypointExporter::new();
+        assert!(exporter.to_fit().is_err());
+        exporter.add_track(track);
+        let fit = exporter.to_fit().unwrap();
+
+        assert_eq!(fit[0], 14);
+        assert_eq!(&fit[8..12], b".FIT");
+        let data_size = u32::from_le_bytes(fit[4..8].try_into().unwrap()) as usize;
+        assert_eq!(fit.len(), 14 + data_size + 2);
+        assert_eq!(u16::from_le_bytes([fit[12], fit[13]]), fit_crc(&fit[..12]));
+        // A CRC run over data followed by its own CRC comes out as zero
+        assert_eq!(fit_crc(&fit), 0);
+
+        // Walk the records, checking every data message matches its definition
+        let mut definitions: std::collections::HashMap<u8, (u16, usize)> = std::collections::HashMap::new();
+        let mut messages = Vec::new();
+        let mut pos = 14;
+        while pos < 14 + data_size {
+            let header = fit[pos];
+            let local = header & 0x0F;
+            if header & 0x40 != 0 {
+                let global = u16::from_le_bytes([fit[pos + 3], fit[pos + 4]]);
+                let count = fit[pos + 5] as usize;
+                let fields = &fit[pos + 6..pos + 6 + count * 3];
+                let size = fields.chunks(3).map(|f| f[1] as usize).sum();
+                definitions.insert(local, (global, size));
+                pos += 6 + count * 3;
+            } else {
+                let (global, size) = definitions[&local];
+                messages.push((global, &fit[pos + 1..pos + 1 + size]));
+                pos += 1 + size;
+            }
+        }
+        assert_eq!(pos, 14 + data_size);
+
+        let globals: Vec<u16> = messages.iter().map(|(g, _)| *g).collect();
+        assert_eq!(globals, vec![FIT_FILE_ID, FIT_RECORD, FIT_RECORD, FIT_LAP, FIT_SESSION, FIT_ACTIVITY]);
+
+        let record = messages[1].1;
+        assert_eq!(u32::from_le_bytes(record[0..4].try_into().unwrap()), fit_time(&t0));
+        assert_eq!(i32::from_le_bytes(record[4..8].try_into().unwrap()), fit_semicircles(42.0));
+        assert_eq!(u16::from_le_bytes([record[12], record[13]]), 2550);   // (10 m + 500) * 5
+        assert_eq!(u16::from_le_bytes([record[14], record[15]]), 10_000); // 36 km/h = 10 m/s
+        let second = messages[2].1;
+        assert_eq!(u16::from_le_bytes([second[12], second[13]]), u16::MAX);
+
+        let lap = messages[3].1;
+        assert_eq!(u32::from_le_bytes(lap[8..12].try_into().unwrap()), 10_000);
+    }
+
+    #[test]
+    fn test_smoothing_reduces_jitter() {
+        let t0 = DateTime::parse_from_rfc3339("2024-05-01T12:00:00Z").unwrap().with_timezone(&Utc);
+        let mut track = Track::new("Noisy".to_string());
+        // Heading east with the position zig-zagging ~5 m either side of the road
+        for i in 0..50 {
+            let noise = if i % 2 == 0 { 0.000045 } else { -0.000045 };
+            let mut point = TrackPoint::new(42.0 + noise, -71.0 + i as f64 * 0.0001, Some(10.0 + i as f64), t0 + chrono::Duration::seconds(i));
+            point.speed = Some(30.0);
+            track.add_point(point);
+        }
+
+        // Sum of direction changes between consecutive steps
+        let jitter = |track: &Track| -> f64 {
+            track.segments[0].points.windows(3)
+                .map(|w| {
+                    let second_lat = w[2].latitude - 2.0 * w[1].latitude + w[0].latitude;
+                    let second_lon = w[2].longitude - 2.0 * w[1].longitude + w[0].longitude;
+                    second_lat.hypot(second_lon)
+                })
+                .sum()
+        };
+
+        let smoothed = track.smooth(5);
+        assert_eq!(smoothed.total_points(), track.total_points());
+        assert!(jitter(&smoothed) < jitter(&track) / 3.0);
+        assert!(smoothed.total_distance() < track.total_distance());
+
+        // Ends stay put and the other fields are untouched
+        let (original, result) = (&track.segments[0].points, &smoothed.segments[0].points);
+        assert_eq!(result[0].latitude, original[0].latitude);
+        assert_eq!(result[49].longitude, original[49].longitude);
+        assert_eq!(result[20].elevation, original[20].elevation);
+        assert_eq!(result[20].timestamp, original[20].timestamp);
+        assert_eq!(result[20].speed, Some(30.0));
+
+        // A window of one changes nothing
+        assert_eq!(jitter(&track.smooth(1)), jitter(&track));
+    }
+
+    #[test]
+    fn test_slice_by_time() {
+        let t0 = DateTime::parse_from_rfc3339("2024-05-01T12:00:00Z").unwrap().with_timezone(&Utc);
+        let at = |secs: i64| t0 + chrono::Duration::seconds(secs);
+        let mut track = Track::new("Drive".to_string());
+        for i in 0..10 {
+            track.add_point(TrackPoint::new(42.0, -71.0 + i as f64 * 0.001, None, at(i * 10)));
+        }
+        track.start_new_segment();
+        for i in 0..10 {
+            track.add_point(TrackPoint::new(42.1, -71.0 + i as f64 * 0.001, None, at(200 + i * 10)));
+        }
+        track.start_new_segment();
+        track.add_point(TrackPoint::new(42.2, -71.0, None, at(500)));
+
+        // Window from the middle of the first segment into the second
+        let slice = track.slice_by_time(at(45), at(230));
+        assert_eq!(slice.name, "Drive");
+        assert_eq!(slice.segments.len(), 2);
+        assert_eq!(slice.segments[0].len(), 5);
+        assert_eq!(slice.segments[1].len(), 4);
+        assert_eq!(slice.start_time(), Some(at(50)));
+        assert_eq!(slice.end_time(), Some(at(230)));
+        assert!(slice.segments.iter()
+            .flat_map(|s| &s.points)
+            .all(|p| p.timestamp >= at(45) && p.timestamp <= at(230)));
+
+        // Boundaries are inclusive, and the original is untouched
+        assert_eq!(track.slice_by_time(at(0), at(500)).total_points(), track.total_points());
+        assert_eq!(track.slice_by_time(at(90), at(90)).total_points(), 1);
+        assert_eq!(track.total_points(), 21);
+
+        // Nothing in the window
+        assert!(track.slice_by_time(at(300), at(400)).segments.is_empty());
+    }
+
+    #[test]
+    fn test_map_matching_hook() {
+        let t0 = DateTime::parse_from_rfc3339("2024-05-01T12:00:00Z").unwrap().with_timezone(&Utc);
+        let mut track = Track::new("Drive".to_string());
+        track.add_point(TrackPoint::new(42.00003, -71.0, None, t0));
+        track.add_point(TrackPoint::new(41.99998, -70.999, None, t0 + chrono::Duration::seconds(5)));
+
+        assert_eq!(track.map_matched(&NoMapMatching).segments[0].points[0].latitude, 42.00003);
+
+        // Snap everything onto the 42nd parallel
+        let snap = |points: &[TrackPoint]| -> Option<Vec<TrackPoint>> {
+            Some(points.iter().map(|p| TrackPoint { latitude: 42.0, ..p.clone() }).collect())
+        };
+        let matched = track.map_matched(&snap);
+        assert!(matched.segments[0].points.iter().all(|p| p.latitude == 42.0));
+        assert_eq!(matched.segments[0].points[1].longitude, -70.999);
+    }
+
+    #[test]
+    fn test_csv_segments_round_trip() {
+        let t0 = DateTime::parse_from_rfc3339("2024-05-01T12:00:00Z").unwrap().with_timezone(&Utc);
+        let at = |secs: i64| t0 + chrono::Duration::seconds(secs);
+        let mut track = Track::new("Errands, Saturday".to_string());
+        track.add_point(TrackPoint::new(42.5, -71.25, Some(100.0), at(0)));
+        track.add_point(TrackPoint::new(42.501, -71.25, Some(101.5), at(10)));
+        track.start_new_segment();
+        let mut point = TrackPoint::new(42.51, -71.24, None, at(600));
+        point.speed = Some(40.0);
+        point.satellites = Some(9);
+        track.add_point(point);
+        track.start_new_segment();
+        track.add_point(TrackPoint::new(42.52, -71.23, Some(98.0), at(900)));
+
+        let mut exporter = WaypointExporter::new();
+        exporter.add_waypoint(Waypoint {
+            name: "Shop".to_string(),
+            latitude: 42.51,
+            longitude: -71.24,
+            elevation: None,
+            timestamp: at(600),
+            description: Some("Line one\nline \"two\"".to_string()),
+            color: None,
+        });
+        exporter.add_track(track.clone());
+        exporter.add_track(Track { segments: vec![track.segments[0].clone()], ..Track::new("Walk".to_string()) });
+
+        for options in [
+            CsvExport { segment_column: true, ..CsvExport::default() },
+            CsvExport {
+                timestamp: crate::config::CsvTimestamp::Epoch,
+                units: DistanceUnits::Imperial,
+                decimal_comma: true,
+                segment_column: true,
+            },
+        ] {
+            let csv = exporter.to_csv(&options);
+            assert!(csv.lines().next().unwrap().ends_with("satellites;segment") == options.decimal_comma);
+
+            let mut imported = WaypointExporter::new();
+            assert_eq!(imported.import_csv(&csv).unwrap(), (1, 2));
+            assert_eq!(imported.waypoints[0].description, exporter.waypoints[0].description);
+
+            let round_trip = &imported.tracks[0];
+            assert_eq!(round_trip.name, "Errands, Saturday");
+            let lengths: Vec<_> = round_trip.segments.iter().map(TrackSegment::len).collect();
+            assert_eq!(lengths, [2, 1, 1]);
+            let point = &round_trip.segments[1].points[0];
+            assert_eq!(point.timestamp, at(600));
+            assert!((point.speed.unwrap() - 40.0).abs() < 0.01);
+            assert_eq!(point.satellites, Some(9));
+            assert!((round_trip.segments[0].points[1].elevation.unwrap() - 101.5).abs() < 0.01);
+            assert_eq!(imported.tracks[1].segments.len(), 1);
+        }
+
+        // Without the column a track comes back as one segment
+        let mut imported = WaypointExporter::new();
+        imported.import_csv(&exporter.to_csv(&CsvExport::default())).unwrap();
+        assert_eq!(imported.tracks[0].segments.len(), 1);
+        assert_eq!(imported.tracks[0].total_points(), 4);
+
+        // GeoJSON notes the pause before each later segment
+        let geojson: serde_json::Value = serde_json::from_str(&exporter.to_geojson().unwrap()).unwrap();
+        let pauses: Vec<_> = geojson["features"].as_array().unwrap().iter()
+            .filter(|f| f["properties"]["name"] == "Errands, Saturday")
+            .map(|f| (f["properties"]["segment"].as_u64(), f["properties"]["pause_before_s"].as_i64()))
+            .collect();
+        assert_eq!(pauses, [(Some(1), None), (Some(2), Some(590)), (Some(3), Some(300))]);
+    }
+
+    #[test]
+    fn test_csv_epoch_timestamps_and_imperial_units() {
+        let t0 = DateTime::parse_from_rfc3339("2024-05-01T12:00:00Z").unwrap().with_timezone(&Utc);
+        let mut track = Track::new("Loop; east".to_string());
+        let mut point = TrackPoint::new(42.5, -71.25, Some(100.0), t0);
+        point.speed = Some(160.9344);
+        point.hdop = Some(0.9);
+        track.add_point(point);
+
+        let mut exporter = WaypointExporter::new();
+        exporter.add_track(track);
+
+        let options = CsvExport {
+            timestamp: crate::config::CsvTimestamp::Epoch,
+            units: DistanceUnits::Imperial,
+            decimal_comma: false,
+            segment_column: false,
+        };
+        let csv = exporter.to_csv(&options);
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next(),
+            Some("type,name,latitude,longitude,elevation_ft,timestamp,description,speed_mph,course,hdop,satellites")
+        );
+        assert_eq!(lines.next(), Some("track,Loop; east,42.5,-71.25,328.08,1714564800,,100.00,,0.9,"));
+
+        // Decimal commas switch the field separator and quote by it
+        let csv = exporter.to_csv(&CsvExport { decimal_comma: true, ..options });
+        assert_eq!(csv.lines().nth(1), Some("track;\"Loop; east\";42,5;-71,25;328,08;1714564800;;100,00;;0,9;"));
+
+        // The default format is unchanged
+        let csv = exporter.to_csv(&CsvExport::default());
+        assert!(csv.starts_with("type,name,latitude,longitude,elevation,timestamp,description,speed,"));
+        assert!(csv.contains(",100,2024-05-01T12:00:00+00:00,,160.9344,"));
+    }
+
+    #[test]
+    fn test_satellite_snapshot_export() {
+        use crate::gps::data::SatelliteInfo;
+
+        let mut data = GpsData::new();
+        data.latitude = Some(42.5);
+        data.longitude = Some(-71.25);
+        data.satellites_info = vec![
+            SatelliteInfo { constellation: "GPS".to_string(), snr: Some(40.0), used: true, ..SatelliteInfo::new(5) },
+            SatelliteInfo { constellation: "GPS".to_string(), snr: Some(30.0), used: true, ..SatelliteInfo::new(12) },
+            SatelliteInfo { constellation: "GLONASS".to_string(), snr: Some(35.0), used: true, ..SatelliteInfo::new(70) },
+            SatelliteInfo { constellation: "GLONASS".to_string(), snr: Some(15.0), used: false, ..SatelliteInfo::new(71) },
+        ];
+
+        let point = TrackPoint::from_gps_data(&data).unwrap();
+        let snapshot = point.satellites_snapshot.clone().unwrap();
+        assert_eq!(snapshot.used, 3);
+        assert_eq!(snapshot.in_view, 4);
+        assert_eq!(snapshot.average_snr, Some(35.0));
+        assert_eq!(snapshot.constellation_summary(), "GLONASS:1 GPS:2");
+        assert_eq!(SatelliteSnapshot::from_gps_data(&GpsData::new()), None);
+
+        let mut track = Track::new("RF".to_string());
+        track.add_point(point);
+        track.add_point(TrackPoint::new(42.6, -71.25, None, Utc::now()));
+        let mut exporter = WaypointExporter::new();
+        exporter.add_track(track);
+
+        let gpx = exporter.to_gpx();
+        assert!(gpx.contains("<sat:snapshot used=\"3\" in_view=\"4\" average_snr=\"35.0\">"));
+        assert!(gpx.contains("<sat:constellation name=\"GLONASS\" used=\"1\"/>"));
+
+        let csv = exporter.to_csv(&CsvExport::default());
+        let mut lines = csv.lines();
+        assert!(lines.next().unwrap().ends_with(",satellites,satellites_used,satellites_in_view,average_snr,constellations"));
+        assert!(lines.next().unwrap().ends_with(",3,4,35.0,GLONASS:1 GPS:2"));
+        assert!(lines.next().unwrap().ends_with(",,,,,"));
+
+        // Older point files without the field still load
+        let json = serde_json::to_string(&TrackPoint::new(1.0, 2.0, None, Utc::now())).unwrap();
+        assert!(!json.contains("satellites_snapshot"));
+        let loaded: TrackPoint = serde_json::from_str(&json).unwrap();
+        assert!(loaded.satellites_snapshot.is_none());
+
+        // Without used flags, e.g. a receiver that sends no GSA, the GGA count is recorded
+        data.satellites_info.iter_mut().for_each(|sat| sat.used = false);
+        data.satellites = Some(7);
+        let snapshot = SatelliteSnapshot::from_gps_data(&data).unwrap();
+        assert_eq!((snapshot.used, snapshot.in_view, snapshot.average_snr), (7, 4, None));
+    }
+
+    #[test]
+    fn test_waypoint_quality_gate() {
+        let mut gate = WaypointQualityGate { enabled: true, ..Default::default() };
+
+        let mut data = GpsData::new();
+        data.latitude = Some(42.0);
+        data.longitude = Some(-71.0);
+        assert_eq!(gate.unmet_requirements(&data).len(), 3);
+
+        // NMEA-style fix: no mode, 3D inferred from altitude, GGA satellite count
+        data.fix_quality = Some(1);
+        data.altitude = Some(20.0);
+        data.hdop = Some(0.9);
+        data.satellites = Some(8);
+        assert!(gate.unmet_requirements(&data).is_empty());
+
+        // gpsd-style 2D fix with poor geometry
+        data.mode = Some(2);
+        data.hdop = Some(4.5);
+        assert_eq!(gate.unmet_requirements(&data), vec!["HDOP 4.5 above 2.0", "no 3D fix"]);
+
+        // Per-satellite reports take precedence over the GGA count
+        data.mode = Some(3);
+        data.hdop = Some(1.2);
+        data.satellites_info = (1..=3).map(|prn| {
+            let mut sat = crate::gps::data::SatelliteInfo::new(prn);
+            sat.used = true;
+            sat
+        }).collect();
+        assert_eq!(gate.unmet_requirements(&data), vec!["3 satellites used, need 5"]);
+
+        gate.enabled = false;
+        assert!(gate.unmet_requirements(&data).is_empty());
+    }
+}